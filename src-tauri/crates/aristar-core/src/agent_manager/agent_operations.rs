@@ -4,9 +4,16 @@ use chrono::Utc;
 
 use crate::worktrees::operations as worktree_ops;
 
+use super::context_files;
+use super::leaderboard::collect_agent_outcomes;
+use super::scratchpad;
+use super::snapshot;
 use super::store::TaskManagerState;
-use super::task_operations::{get_task_folder_path, slugify, slugify_model_id};
-use super::types::{AgentStatus, Task, TaskAgent};
+use super::task_operations::{
+    agent_worktree_path, get_task_impl, initial_agent_status, push_timeline_entry, relay_hand_off,
+    relay_successor,
+};
+use super::types::{AgentStatus, Task, TaskAgent, TimelineEntry, TimelineEntryKind};
 
 /// Add a new agent to an existing task.
 pub fn add_agent_to_task_impl(
@@ -27,9 +34,7 @@ pub fn add_agent_to_task_impl(
         let now = Utc::now().timestamp_millis();
         let agent_num = task.agents.len() + 1;
         let agent_id = format!("agent-{}", agent_num);
-        let worktree_name = format!("{}-{}", slugify(&task.name), slugify_model_id(&model_id));
-        let task_folder = get_task_folder_path(&task_id);
-        let worktree_path = task_folder.join(&worktree_name);
+        let worktree_path = agent_worktree_path(&task_id, &task.name, &model_id, agent_num, &[])?;
         let worktree_path_str = worktree_path.to_string_lossy().to_string();
 
         // Determine source for worktree
@@ -39,12 +44,20 @@ pub fn add_agent_to_task_impl(
         };
 
         // Create the worktree
-        let created_path = worktree_ops::create_worktree_at_path(
+        let created_path = worktree_ops::create_sparse_worktree_at_path(
             &task.source_repo_path,
             &worktree_path_str,
             source_ref.as_deref(),
+            &task.sparse_paths,
         )?;
+        if let Some(ref commit) = task.snapshot_commit {
+            snapshot::apply_snapshot(&created_path, commit)?;
+        }
+        context_files::sync_task_context(task, &created_path)?;
+        scratchpad::link_task_scratchpad(&task.id, &created_path)?;
 
+        // A relay grows by one more leg
+        let status = initial_agent_status(task.mode, task.agents.len());
         task.agents.push(TaskAgent {
             id: agent_id,
             model_id,
@@ -52,9 +65,14 @@ pub fn add_agent_to_task_impl(
             agent_type,
             worktree_path: created_path,
             session_id: None,
-            status: AgentStatus::Idle,
+            status,
             accepted: false,
             created_at: now,
+            preferred_port: None,
+            review_comments: Vec::new(),
+            last_started_at: None,
+            forked_from: None,
+            quota_exceeded: None,
         });
         task.updated_at = now;
 
@@ -66,6 +84,93 @@ pub fn add_agent_to_task_impl(
     Ok(task)
 }
 
+/// Add an agent that continues another agent's work with a different model.
+///
+/// The source worktree's uncommitted changes are checkpointed first; the new
+/// worktree starts at the source agent's `HEAD` with the checkpoint applied as
+/// uncommitted changes. The source agent is left untouched.
+pub fn fork_agent_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    source_agent_id: String,
+    model_id: String,
+    provider_id: String,
+    agent_type: Option<String>,
+) -> Result<Task, String> {
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let source = task
+            .agents
+            .iter()
+            .find(|a| a.id == source_agent_id)
+            .ok_or_else(|| format!("Agent not found: {}", source_agent_id))?;
+        if !std::path::Path::new(&source.worktree_path).exists() {
+            return Err(format!(
+                "Worktree of {} not found: {}",
+                source_agent_id, source.worktree_path
+            ));
+        }
+        let source_path = source.worktree_path.clone();
+        let agent_type = agent_type.or_else(|| source.agent_type.clone());
+
+        let now = Utc::now().timestamp_millis();
+        let agent_num = task.agents.len() + 1;
+        let agent_id = format!("agent-{}", agent_num);
+        let worktree_path = agent_worktree_path(&task_id, &task.name, &model_id, agent_num, &[])?;
+        let worktree_path_str = worktree_path.to_string_lossy().to_string();
+
+        let head = worktree_ops::run_git_command(&["rev-parse", "--verify", "HEAD"], &source_path)
+            .map_err(|e| format!("Failed to read HEAD of {}: {}", source_agent_id, e.trim()))?;
+        let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+        let checkpoint = snapshot::create_agent_checkpoint(&source_path, &task_id, &agent_id)?;
+
+        let created_path = worktree_ops::create_sparse_worktree_at_path(
+            &task.source_repo_path,
+            &worktree_path_str,
+            Some(&head),
+            &task.sparse_paths,
+        )?;
+        if let Some(ref commit) = checkpoint {
+            snapshot::apply_snapshot(&created_path, commit)?;
+        }
+        context_files::sync_task_context(task, &created_path)?;
+        scratchpad::link_task_scratchpad(&task.id, &created_path)?;
+
+        task.agents.push(TaskAgent {
+            id: agent_id.clone(),
+            model_id,
+            provider_id,
+            agent_type,
+            worktree_path: created_path,
+            session_id: None,
+            status: AgentStatus::Idle,
+            accepted: false,
+            created_at: now,
+            preferred_port: None,
+            review_comments: Vec::new(),
+            last_started_at: None,
+            forked_from: Some(source_agent_id.clone()),
+            quota_exceeded: None,
+        });
+        task.updated_at = now;
+
+        task.clone()
+    };
+
+    state.save()?;
+    println!(
+        "[task_manager] Forked agent {} of task {} into a new agent",
+        source_agent_id, task_id
+    );
+    Ok(task)
+}
+
 /// Remove an agent from a task.
 pub fn remove_agent_from_task_impl(
     state: &TaskManagerState,
@@ -165,12 +270,16 @@ pub fn update_agent_status_impl(
     Ok(())
 }
 
-/// Mark an agent as accepted (winner).
+/// Mark an agent as accepted (winner). In a relay task this also hands the
+/// agent's work to the next waiting agent. Every agent's outcome is recorded
+/// for the model leaderboard.
 pub fn accept_agent_impl(
     state: &TaskManagerState,
     task_id: String,
     agent_id: String,
 ) -> Result<(), String> {
+    // Diffs are measured before the store is locked
+    let outcomes = collect_agent_outcomes(&get_task_impl(state, &task_id)?, &agent_id);
     {
         let mut store = state.store.lock().map_err(|e| e.to_string())?;
         let task = store
@@ -179,6 +288,11 @@ pub fn accept_agent_impl(
             .find(|t| t.id == task_id)
             .ok_or_else(|| format!("Task not found: {}", task_id))?;
 
+        // In a relay, accepting the agent holding it hands its work on
+        if let Some(next) = relay_successor(task, &agent_id)? {
+            relay_hand_off(task, next - 1, next)?;
+        }
+
         // Unaccept all agents first
         for agent in &mut task.agents {
             agent.accepted = false;
@@ -192,6 +306,7 @@ pub fn accept_agent_impl(
             .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
 
         agent.accepted = true;
+        task.agent_outcomes = outcomes;
         task.updated_at = Utc::now().timestamp_millis();
     }
 
@@ -239,7 +354,7 @@ pub fn recreate_agent_worktree_impl(
     task_id: String,
     agent_id: String,
 ) -> Result<String, String> {
-    let (source_repo_path, source_ref, worktree_path) = {
+    let (task, source_ref, worktree_path) = {
         let store = state.store.lock().map_err(|e| e.to_string())?;
         let task = store
             .tasks
@@ -263,19 +378,21 @@ pub fn recreate_agent_worktree_impl(
             _ => task.source_branch.clone(),
         };
 
-        (
-            task.source_repo_path.clone(),
-            source_ref,
-            agent.worktree_path.clone(),
-        )
+        (task.clone(), source_ref, agent.worktree_path.clone())
     };
 
     // Create the worktree
-    let created_path = worktree_ops::create_worktree_at_path(
-        &source_repo_path,
+    let created_path = worktree_ops::create_sparse_worktree_at_path(
+        &task.source_repo_path,
         &worktree_path,
         source_ref.as_deref(),
+        &task.sparse_paths,
     )?;
+    if let Some(ref commit) = task.snapshot_commit {
+        snapshot::apply_snapshot(&created_path, commit)?;
+    }
+    context_files::sync_task_context(&task, &created_path)?;
+    scratchpad::link_task_scratchpad(&task.id, &created_path)?;
 
     println!(
         "[task_manager] Recreated worktree for agent {} in task {}",
@@ -329,3 +446,107 @@ pub fn cleanup_unaccepted_agents_impl(
     );
     Ok(())
 }
+
+/// Record the port an agent's OpenCode server is running on.
+///
+/// The first assigned port becomes the agent's preferred port and is never
+/// overwritten. Returns the previous preferred port when the server had to
+/// fall back to a different one.
+pub fn record_agent_port_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+    port: u16,
+) -> Result<Option<u16>, String> {
+    let (changed_from, needs_save) = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let agent = task
+            .agents
+            .iter_mut()
+            .find(|a| a.id == agent_id)
+            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+        match agent.preferred_port {
+            Some(preferred) if preferred != port => (Some(preferred), false),
+            Some(_) => (None, false),
+            None => {
+                agent.preferred_port = Some(port);
+                (None, true)
+            }
+        }
+    };
+
+    if needs_save {
+        state.save()?;
+    }
+    Ok(changed_from)
+}
+
+/// Record that an agent's OpenCode server was started, opening a new
+/// window for `audit_agent_writes`.
+pub fn record_agent_started_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let agent = task
+            .agents
+            .iter_mut()
+            .find(|a| a.id == agent_id)
+            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+        agent.last_started_at = Some(Utc::now().timestamp_millis());
+    }
+
+    state.save()
+}
+
+/// Record in the task timeline that an agent's server started with a sandbox
+/// the OS does not enforce (the task allowed it).
+pub fn record_sandbox_unenforced_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let now = Utc::now().timestamp_millis();
+        push_timeline_entry(
+            task,
+            TimelineEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                timestamp: now,
+                agent_id: Some(agent_id.to_string()),
+                kind: TimelineEntryKind::SandboxUnenforced,
+                message: format!(
+                    "{} started without an enforced sandbox: file writes and network are not restricted",
+                    agent_id
+                ),
+                details: vec!["Only the environment is restricted (no sandbox-exec)".to_string()],
+            },
+        );
+        task.updated_at = now;
+    }
+
+    state.save()
+}
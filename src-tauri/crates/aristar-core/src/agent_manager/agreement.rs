@@ -0,0 +1,231 @@
+//! Agreement between the agents of a task.
+//!
+//! Compares every agent's diff against the task source file by file. A file is
+//! `identical` when every agent left it with the same content, `similar` when
+//! every agent changed it and the changed lines overlap enough, and
+//! `divergent` otherwise. Shows which parts of a change all models agree on
+//! before one is accepted.
+//!
+//! Patch similarity is the Dice coefficient of the changed lines (`+`/`-`
+//! lines of a `-U0` diff, whitespace-trimmed). Untracked files count as fully
+//! added.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+use crate::worktrees::diff::{diff_base, diff_output, untracked_files};
+
+use super::change_summary::get_task_base_ref;
+use super::store::TaskManagerState;
+use super::task_operations::get_task_impl;
+use super::types::{AgentAgreementReport, FileAgreement, FileAgreementEntry};
+
+/// Lowest patch similarity (percent) for files to count as `similar`.
+pub const SIMILAR_THRESHOLD: u8 = 60;
+
+/// One agent's change to a file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilePatch {
+    /// Changed lines, prefixed with `+` or `-`
+    pub lines: Vec<String>,
+    /// Content in the worktree, `None` when deleted
+    pub content: Option<Vec<u8>>,
+}
+
+// ============ Comparison ============
+
+/// Split a `git diff -U0` patch into the changed lines of each file.
+pub fn parse_patch_lines(patch: &str) -> BTreeMap<String, Vec<String>> {
+    let mut files: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header
+                .rsplit_once(" b/")
+                .map(|(_, p)| p.to_string())
+                .unwrap_or_default();
+            files.entry(path.clone()).or_default();
+            current = Some(path);
+            continue;
+        }
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        let Some(path) = current.as_ref() else {
+            continue;
+        };
+        let sign = match line.chars().next() {
+            Some(c @ ('+' | '-')) => c,
+            _ => continue,
+        };
+        files
+            .entry(path.clone())
+            .or_default()
+            .push(format!("{}{}", sign, line[1..].trim()));
+    }
+    files
+}
+
+/// Similarity of two patches in percent: twice the shared changed lines over
+/// all changed lines. Two empty patches (e.g. binary files) are 100% alike.
+pub fn patch_similarity(a: &[String], b: &[String]) -> u8 {
+    if a.is_empty() && b.is_empty() {
+        return 100;
+    }
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for line in a {
+        *counts.entry(line.as_str()).or_default() += 1;
+    }
+    let mut shared = 0;
+    for line in b {
+        if let Some(count) = counts.get_mut(line.as_str()) {
+            if *count > 0 {
+                *count -= 1;
+                shared += 1;
+            }
+        }
+    }
+    ((2 * shared * 100) / (a.len() + b.len())) as u8
+}
+
+/// Compare the changes of several agents to one file. `patches` holds the
+/// change of every agent that touched it; `agent_count` is the number of
+/// agents compared.
+pub fn classify_file(
+    path: &str,
+    patches: &[(String, FilePatch)],
+    agent_count: usize,
+) -> FileAgreementEntry {
+    let changed_by: Vec<String> = patches.iter().map(|(id, _)| id.clone()).collect();
+    let everyone = agent_count > 1 && patches.len() == agent_count;
+
+    let mut similarity = if everyone { 100 } else { 0 };
+    for (i, (_, a)) in patches.iter().enumerate() {
+        for (_, b) in &patches[i + 1..] {
+            similarity = similarity.min(patch_similarity(&a.lines, &b.lines));
+        }
+    }
+
+    let same_content = patches.windows(2).all(|w| w[0].1.content == w[1].1.content);
+    let agreement = if everyone && same_content {
+        similarity = 100;
+        FileAgreement::Identical
+    } else if similarity >= SIMILAR_THRESHOLD {
+        FileAgreement::Similar
+    } else {
+        FileAgreement::Divergent
+    };
+
+    FileAgreementEntry {
+        path: path.to_string(),
+        agreement,
+        changed_by,
+        similarity,
+    }
+}
+
+/// Build the report from each agent's changed files, divergent files first.
+pub fn build_agreement_report(
+    agents: Vec<(String, BTreeMap<String, FilePatch>)>,
+) -> AgentAgreementReport {
+    let agent_ids: Vec<String> = agents.iter().map(|(id, _)| id.clone()).collect();
+    let paths: BTreeSet<String> = agents
+        .iter()
+        .flat_map(|(_, files)| files.keys().cloned())
+        .collect();
+
+    let mut files: Vec<FileAgreementEntry> = paths
+        .iter()
+        .map(|path| {
+            let patches: Vec<(String, FilePatch)> = agents
+                .iter()
+                .filter_map(|(id, files)| files.get(path).map(|p| (id.clone(), p.clone())))
+                .collect();
+            classify_file(path, &patches, agent_ids.len())
+        })
+        .collect();
+    let rank = |a: FileAgreement| match a {
+        FileAgreement::Divergent => 0,
+        FileAgreement::Similar => 1,
+        FileAgreement::Identical => 2,
+    };
+    files.sort_by(|a, b| {
+        rank(a.agreement)
+            .cmp(&rank(b.agreement))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let count = |kind: FileAgreement| files.iter().filter(|f| f.agreement == kind).count();
+    AgentAgreementReport {
+        identical_count: count(FileAgreement::Identical),
+        similar_count: count(FileAgreement::Similar),
+        divergent_count: count(FileAgreement::Divergent),
+        agent_ids,
+        files,
+    }
+}
+
+// ============ Git ============
+
+/// Changed files of a worktree against the merge base with `base_ref`,
+/// including uncommitted and untracked changes.
+pub fn collect_file_patches(
+    worktree_path: &str,
+    base_ref: &str,
+) -> Result<BTreeMap<String, FilePatch>, String> {
+    let base = diff_base(worktree_path, base_ref)?;
+    let patch = diff_output(worktree_path, &base, true, &["-U0"], &[])?;
+    let mut lines = parse_patch_lines(&patch);
+    for path in untracked_files(worktree_path, &[])? {
+        let added = fs::read_to_string(Path::new(worktree_path).join(&path))
+            .map(|content| content.lines().map(|l| format!("+{}", l.trim())).collect())
+            .unwrap_or_default();
+        lines.insert(path, added);
+    }
+
+    Ok(lines
+        .into_iter()
+        .map(|(path, lines)| {
+            let content = fs::read(Path::new(worktree_path).join(&path)).ok();
+            (path, FilePatch { lines, content })
+        })
+        .collect())
+}
+
+/// Compare the diffs of agents given as (agent ID, worktree path).
+pub fn compute_agreement(
+    agents: &[(String, String)],
+    base_ref: &str,
+) -> Result<AgentAgreementReport, String> {
+    let mut patches = Vec::new();
+    for (id, worktree_path) in agents {
+        patches.push((id.clone(), collect_file_patches(worktree_path, base_ref)?));
+    }
+    Ok(build_agreement_report(patches))
+}
+
+/// Compare the diffs of a task's agents against the task source. Needs at
+/// least two agents with a worktree. The git work runs on a blocking thread.
+pub async fn compute_agent_agreement_async(
+    state: &TaskManagerState,
+    task_id: String,
+    default_base: Option<String>,
+) -> Result<AgentAgreementReport, String> {
+    let task = get_task_impl(state, &task_id)?;
+    let base_ref = get_task_base_ref(&task).or_else(|e| default_base.ok_or(e))?;
+    let agents: Vec<(String, String)> = task
+        .agents
+        .iter()
+        .filter(|a| Path::new(&a.worktree_path).exists())
+        .map(|a| (a.id.clone(), a.worktree_path.clone()))
+        .collect();
+    if agents.len() < 2 {
+        return Err("At least two agents with a worktree are needed to compare".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || compute_agreement(&agents, &base_ref))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
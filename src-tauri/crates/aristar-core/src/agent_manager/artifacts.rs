@@ -0,0 +1,175 @@
+//! Refs the app created for a task, and their cleanup.
+//!
+//! Tasks started from uncommitted changes keep a snapshot ref, and forked
+//! agents a checkpoint ref each (see `snapshot`). They pile up once tasks are
+//! done. `list_task_artifacts` lists them with whether accepted work started
+//! from them (an accepted agent, or an agent it was forked from) and whether
+//! their commit is already on the task's source branch.
+//! `cleanup_task_artifacts` deletes them once no agent is running; with
+//! `keep_accepted`, refs of accepted work that isn't on the source branch are
+//! kept. A ref is only deleted while it still points at the listed commit.
+
+use std::collections::HashSet;
+
+use crate::worktrees::operations::{is_ancestor, run_git_command};
+
+use super::snapshot::{snapshot_ref_name, CHECKPOINT_REF_PREFIX};
+use super::store::TaskManagerState;
+use super::task_operations::get_task_impl;
+use super::types::{
+    AgentStatus, Task, TaskArtifact, TaskArtifactCleanup, TaskArtifactKind, TaskStatus,
+};
+
+/// Ids of the accepted agents and the agents they were forked from.
+pub fn accepted_lineage(task: &Task) -> HashSet<String> {
+    let mut lineage = HashSet::new();
+    for agent in task.agents.iter().filter(|a| a.accepted) {
+        let mut current = Some(agent);
+        while let Some(agent) = current {
+            if !lineage.insert(agent.id.clone()) {
+                break;
+            }
+            current = agent
+                .forked_from
+                .as_ref()
+                .and_then(|id| task.agents.iter().find(|a| &a.id == id));
+        }
+    }
+    lineage
+}
+
+/// Whether `commit` is on the task's source branch.
+fn is_published(task: &Task, commit: &str) -> bool {
+    let Some(branch) = task.source_branch.as_deref() else {
+        return false;
+    };
+    is_ancestor(
+        &task.source_repo_path,
+        commit,
+        &format!("refs/heads/{}", branch),
+    )
+    .unwrap_or(false)
+}
+
+/// The refs the app created for a task.
+pub fn list_task_artifacts(task: &Task) -> Result<Vec<TaskArtifact>, String> {
+    let snapshot_ref = snapshot_ref_name(&task.id);
+    let checkpoint_prefix = format!("{}{}/", CHECKPOINT_REF_PREFIX, task.id);
+    let output = run_git_command(
+        &[
+            "for-each-ref",
+            "--format=%(refname) %(objectname)",
+            &snapshot_ref,
+            &checkpoint_prefix,
+        ],
+        &task.source_repo_path,
+    )?;
+
+    let lineage = accepted_lineage(task);
+    let mut artifacts = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((ref_name, commit)) = line.split_once(' ') else {
+            continue;
+        };
+        let (kind, agent_id, accepted) = if ref_name == snapshot_ref {
+            (TaskArtifactKind::Snapshot, None, !lineage.is_empty())
+        } else if let Some(agent_id) = ref_name.strip_prefix(&checkpoint_prefix) {
+            (
+                TaskArtifactKind::Checkpoint,
+                Some(agent_id.to_string()),
+                lineage.contains(agent_id),
+            )
+        } else {
+            continue;
+        };
+        artifacts.push(TaskArtifact {
+            ref_name: ref_name.to_string(),
+            kind,
+            agent_id,
+            commit: commit.to_string(),
+            accepted,
+            published: is_published(task, commit),
+        });
+    }
+    Ok(artifacts)
+}
+
+/// Delete the refs the app created for a task, keeping those of accepted
+/// work not yet on the source branch with `keep_accepted`. Fails while the
+/// task or one of its agents is running.
+pub fn cleanup_task_artifacts(
+    task: &Task,
+    keep_accepted: bool,
+) -> Result<TaskArtifactCleanup, String> {
+    if task.status == TaskStatus::Running
+        || task.agents.iter().any(|a| a.status == AgentStatus::Running)
+    {
+        return Err(format!(
+            "Task {} is still running; clean up its refs when it's done",
+            task.id
+        ));
+    }
+
+    let mut cleanup = TaskArtifactCleanup {
+        deleted: Vec::new(),
+        kept: Vec::new(),
+    };
+    for artifact in list_task_artifacts(task)? {
+        if keep_accepted && artifact.accepted && !artifact.published {
+            cleanup.kept.push(artifact);
+            continue;
+        }
+        // Only delete the ref if nothing moved it since it was listed
+        match run_git_command(
+            &["update-ref", "-d", &artifact.ref_name, &artifact.commit],
+            &task.source_repo_path,
+        ) {
+            Ok(_) => cleanup.deleted.push(artifact),
+            Err(e) => {
+                eprintln!("[artifacts] Keeping {}: {}", artifact.ref_name, e.trim());
+                cleanup.kept.push(artifact);
+            }
+        }
+    }
+    println!(
+        "[artifacts] Task {}: deleted {} ref(s), kept {}",
+        task.id,
+        cleanup.deleted.len(),
+        cleanup.kept.len()
+    );
+    Ok(cleanup)
+}
+
+/// List a task's refs by task id.
+pub fn list_task_artifacts_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+) -> Result<Vec<TaskArtifact>, String> {
+    list_task_artifacts(&get_task_impl(state, task_id)?)
+}
+
+/// Clean up a task's refs by task id. The task forgets a deleted snapshot, so
+/// agents added later start without its changes.
+pub fn cleanup_task_artifacts_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    keep_accepted: bool,
+) -> Result<TaskArtifactCleanup, String> {
+    let task = get_task_impl(state, task_id)?;
+    let cleanup = cleanup_task_artifacts(&task, keep_accepted)?;
+
+    if cleanup
+        .deleted
+        .iter()
+        .any(|a| a.kind == TaskArtifactKind::Snapshot)
+    {
+        {
+            let mut store = state.store.lock().map_err(|e| e.to_string())?;
+            if let Some(task) = store.tasks.iter_mut().find(|t| t.id == task_id) {
+                task.snapshot_commit = None;
+            }
+        }
+        state.save()?;
+    }
+    Ok(cleanup)
+}
@@ -0,0 +1,279 @@
+//! Automatic accept of a clear winner after a pipeline run.
+//!
+//! A task's `AutoAcceptPolicy` is checked when its pipeline run finishes. If it
+//! picks an agent, the accept is scheduled `delay_secs` later and announced with
+//! `auto-accept-scheduled`; until then it can be cancelled with the
+//! `cancel_auto_accept` command or by emitting `auto-accept-cancel` with
+//! `{ "taskId": ... }`. A background loop fires due accepts (also ones left
+//! pending when the app was closed), optionally cleans up the other agents and
+//! emits `auto-accepted`. Every step is recorded in the task timeline.
+
+use chrono::Utc;
+use serde::Deserialize;
+use std::time::Duration;
+
+use super::agent_operations::{accept_agent_impl, cleanup_unaccepted_agents_impl};
+use super::pipeline::top_score;
+use super::store::TaskManagerState;
+use super::task_operations::push_timeline_entry;
+use super::types::{
+    AgentStatus, AutoAcceptEvent, AutoAcceptPolicy, PendingAutoAccept, Task, TimelineEntry,
+    TimelineEntryKind,
+};
+
+pub use crate::core::events::{
+    AUTO_ACCEPTED_EVENT, AUTO_ACCEPT_CANCEL_EVENT, AUTO_ACCEPT_SCHEDULED_EVENT,
+};
+
+/// How often pending accepts are checked.
+pub const AUTO_ACCEPT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest allowed delay (one day).
+const MAX_DELAY_SECS: u64 = 24 * 60 * 60;
+
+/// Payload of `auto-accept-cancel`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelAutoAcceptPayload {
+    pub task_id: String,
+}
+
+/// Check a policy before it is attached to a task.
+pub fn validate_auto_accept_policy(policy: &AutoAcceptPolicy) -> Result<(), String> {
+    if !policy.sole_passer && policy.min_score.is_none() {
+        return Err("Auto-accept needs a rule: sole passer or a minimum score".to_string());
+    }
+    if policy.min_score.is_some_and(|s| s > 100) {
+        return Err("Minimum score must be 0-100".to_string());
+    }
+    if policy.delay_secs > MAX_DELAY_SECS {
+        return Err("Delay cannot be longer than a day".to_string());
+    }
+    Ok(())
+}
+
+/// Agent the policy picks from the task's latest pipeline run, with the
+/// reason. Nothing is picked once an agent has been accepted.
+pub fn auto_accept_candidate(policy: &AutoAcceptPolicy, task: &Task) -> Option<(String, String)> {
+    let run = task.pipeline_run.as_ref()?;
+    if task.agents.iter().any(|a| a.accepted) {
+        return None;
+    }
+
+    if policy.sole_passer {
+        let passers: Vec<&str> = task
+            .agents
+            .iter()
+            .filter(|a| a.status == AgentStatus::Completed)
+            .filter(|a| {
+                let checks: Vec<_> = run
+                    .verifications
+                    .iter()
+                    .filter(|v| v.agent_id == a.id)
+                    .collect();
+                !checks.is_empty() && checks.iter().all(|v| v.passed)
+            })
+            .map(|a| a.id.as_str())
+            .collect();
+        if let [only] = passers.as_slice() {
+            return Some((
+                only.to_string(),
+                "only agent to pass verification".to_string(),
+            ));
+        }
+    }
+
+    if let Some(min_score) = policy.min_score {
+        let top = top_score(&run.scores)?;
+        let tied = run.scores.iter().filter(|s| s.score == top.score).count() > 1;
+        if top.score >= min_score && !tied {
+            return Some((
+                top.agent_id.clone(),
+                format!("top score {} (minimum {})", top.score, min_score),
+            ));
+        }
+    }
+    None
+}
+
+fn timeline_entry(agent_id: &str, message: String, now: i64) -> TimelineEntry {
+    TimelineEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: now,
+        agent_id: Some(agent_id.to_string()),
+        kind: TimelineEntryKind::AutoAccept,
+        message,
+        details: Vec::new(),
+    }
+}
+
+/// Attach an auto-accept policy to a task, or remove it with `None`.
+/// Removing the policy also cancels a pending accept.
+pub fn set_task_auto_accept_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    policy: Option<AutoAcceptPolicy>,
+) -> Result<Task, String> {
+    if let Some(policy) = &policy {
+        validate_auto_accept_policy(policy)?;
+    }
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        if policy.is_none() {
+            task.pending_auto_accept = None;
+        }
+        task.auto_accept = policy;
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
+
+/// Schedule an accept if the task's policy picks an agent. Returns the
+/// scheduled accept.
+pub fn schedule_auto_accept_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    now: i64,
+) -> Result<Option<PendingAutoAccept>, String> {
+    let pending = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let Some(policy) = task.auto_accept.clone() else {
+            return Ok(None);
+        };
+        let Some((agent_id, reason)) = auto_accept_candidate(&policy, task) else {
+            return Ok(None);
+        };
+
+        let pending = PendingAutoAccept {
+            agent_id,
+            reason,
+            due_at: now + policy.delay_secs as i64 * 1000,
+        };
+        let message = format!(
+            "Auto-accept of {} scheduled in {} s: {}",
+            pending.agent_id, policy.delay_secs, pending.reason
+        );
+        push_timeline_entry(task, timeline_entry(&pending.agent_id, message, now));
+        task.pending_auto_accept = Some(pending.clone());
+        task.updated_at = now;
+        pending
+    };
+
+    state.save()?;
+    Ok(Some(pending))
+}
+
+/// Cancel a task's pending accept. Returns whether one was pending.
+pub fn cancel_auto_accept_impl(state: &TaskManagerState, task_id: &str) -> Result<bool, String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let Some(pending) = task.pending_auto_accept.take() else {
+            return Ok(false);
+        };
+        let now = Utc::now().timestamp_millis();
+        let message = format!("Auto-accept of {} cancelled", pending.agent_id);
+        push_timeline_entry(task, timeline_entry(&pending.agent_id, message, now));
+        task.updated_at = now;
+    }
+
+    state.save()?;
+    println!("[auto_accept] Cancelled auto-accept in task {}", task_id);
+    Ok(true)
+}
+
+/// Carry out every pending accept that is due.
+pub fn fire_due_auto_accepts(
+    state: &TaskManagerState,
+    now: i64,
+) -> Result<Vec<AutoAcceptEvent>, String> {
+    // Take the due accepts under the lock so a cancel can't race them
+    let due: Vec<(String, PendingAutoAccept, bool)> = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        store
+            .tasks
+            .iter_mut()
+            .filter(|t| {
+                t.pending_auto_accept
+                    .as_ref()
+                    .is_some_and(|p| p.due_at <= now)
+            })
+            .filter_map(|t| {
+                let cleanup = t.auto_accept.as_ref().is_some_and(|p| p.cleanup);
+                t.pending_auto_accept
+                    .take()
+                    .map(|pending| (t.id.clone(), pending, cleanup))
+            })
+            .collect()
+    };
+    if due.is_empty() {
+        return Ok(Vec::new());
+    }
+    state.save()?;
+
+    let mut events = Vec::new();
+    for (task_id, pending, cleanup) in due {
+        let result =
+            accept_agent_impl(state, task_id.clone(), pending.agent_id.clone()).and_then(|_| {
+                if cleanup {
+                    cleanup_unaccepted_agents_impl(state, task_id.clone())
+                } else {
+                    Ok(())
+                }
+            });
+
+        let message = match &result {
+            Ok(()) if cleanup => format!(
+                "Auto-accepted {} and removed the other agents",
+                pending.agent_id
+            ),
+            Ok(()) => format!("Auto-accepted {}", pending.agent_id),
+            Err(e) => format!("Auto-accept of {} failed: {}", pending.agent_id, e),
+        };
+        {
+            let mut store = state.store.lock().map_err(|e| e.to_string())?;
+            if let Some(task) = store.tasks.iter_mut().find(|t| t.id == task_id) {
+                push_timeline_entry(task, timeline_entry(&pending.agent_id, message, now));
+            }
+        }
+        state.save()?;
+
+        match result {
+            Ok(()) => {
+                println!(
+                    "[auto_accept] Accepted {} in task {}",
+                    pending.agent_id, task_id
+                );
+                events.push(AutoAcceptEvent {
+                    task_id,
+                    agent_id: pending.agent_id,
+                    reason: pending.reason,
+                    due_at: pending.due_at,
+                });
+            }
+            Err(e) => eprintln!("[auto_accept] Task {}: {}", task_id, e),
+        }
+    }
+    Ok(events)
+}
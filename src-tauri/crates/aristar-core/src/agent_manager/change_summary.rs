@@ -0,0 +1,345 @@
+//! Change summaries for agent diffs.
+//!
+//! Produces a human-readable summary of what an agent changed (files by area,
+//! added public APIs, notable deletions) using plain text heuristics over
+//! `git diff` output. No model is involved, so summaries are instant and
+//! deterministic.
+
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::worktrees::diff::{changed_files, diff_base, diff_output, ChangedFileStatus};
+
+use super::store::TaskManagerState;
+use super::task_operations::get_task_impl;
+use super::types::{AreaChanges, ChangeSummary, FileChange, Task};
+
+/// Folders that group code rather than describe an area; their child is used instead.
+const CONTAINER_DIRS: [&str; 6] = ["src", "lib", "packages", "crates", "apps", "src-tauri"];
+
+/// Files removing at least this many lines (and mostly removing) are called out.
+const LARGE_REMOVAL_LINES: u32 = 100;
+
+/// Maximum files listed per area in the markdown rendering.
+const MAX_FILES_PER_AREA: usize = 10;
+
+/// Declaration prefixes that introduce a public API, with the kind they declare.
+const API_PREFIXES: [(&str, &str); 19] = [
+    ("pub async fn ", "fn"),
+    ("pub fn ", "fn"),
+    ("pub struct ", "struct"),
+    ("pub enum ", "enum"),
+    ("pub trait ", "trait"),
+    ("pub type ", "type"),
+    ("pub const ", "const"),
+    ("pub mod ", "mod"),
+    ("export default function ", "function"),
+    ("export async function ", "function"),
+    ("export function ", "function"),
+    ("export const ", "const"),
+    ("export class ", "class"),
+    ("export interface ", "interface"),
+    ("export type ", "type"),
+    ("export enum ", "enum"),
+    ("async def ", "def"),
+    ("def ", "def"),
+    ("class ", "class"),
+];
+
+// ============ Parsing ============
+
+/// Area of a path: its top-level folder, or the first two levels for
+/// container folders like `src/` (e.g. `src/components`). Top-level files are `(root)`.
+pub fn area_for_path(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.len() {
+        0 | 1 => "(root)".to_string(),
+        2 => parts[0].to_string(),
+        _ if CONTAINER_DIRS.contains(&parts[0]) => format!("{}/{}", parts[0], parts[1]),
+        _ => parts[0].to_string(),
+    }
+}
+
+/// Extract a public API declaration (`"fn parse"`, `"interface Props"`) from a source line.
+///
+/// Python `def`/`class` only count at the top level (no indentation).
+pub fn extract_api_declaration(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    let indented = trimmed.len() != line.len();
+
+    for (prefix, kind) in API_PREFIXES {
+        let is_python = matches!(prefix, "def " | "async def " | "class ");
+        if is_python && indented {
+            continue;
+        }
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                .collect();
+            if name.is_empty() {
+                return None;
+            }
+            return Some(format!("{} {}", kind, name));
+        }
+    }
+    None
+}
+
+/// Collect (added, removed) public APIs from a `git diff -U0` patch.
+/// APIs present on both sides (e.g. a changed signature) are left out.
+pub fn collect_api_changes(patch: &str) -> (Vec<String>, Vec<String>) {
+    let mut added: Vec<String> = Vec::new();
+    let mut removed: Vec<String> = Vec::new();
+    let mut current_file = String::new();
+
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            current_file = header
+                .rsplit_once(" b/")
+                .map(|(_, p)| p.to_string())
+                .unwrap_or_default();
+            continue;
+        }
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+
+        let (list, content) = if let Some(content) = line.strip_prefix('+') {
+            (&mut added, content)
+        } else if let Some(content) = line.strip_prefix('-') {
+            (&mut removed, content)
+        } else {
+            continue;
+        };
+
+        if let Some(api) = extract_api_declaration(content) {
+            let entry = format!("{}: {}", current_file, api);
+            if !list.contains(&entry) {
+                list.push(entry);
+            }
+        }
+    }
+
+    let only_added: Vec<String> = added
+        .iter()
+        .filter(|a| !removed.contains(a))
+        .cloned()
+        .collect();
+    let only_removed: Vec<String> = removed.into_iter().filter(|r| !added.contains(r)).collect();
+    (only_added, only_removed)
+}
+
+// ============ Summary ============
+
+/// Build a summary from the changed files and the `-U0` patch of tracked files.
+/// `extra_added_apis` carries APIs found in untracked files.
+pub fn build_change_summary(
+    base_commit: &str,
+    files: Vec<FileChange>,
+    patch: &str,
+    extra_added_apis: Vec<String>,
+) -> ChangeSummary {
+    let (mut added_apis, removed_apis) = collect_api_changes(patch);
+    added_apis.extend(extra_added_apis);
+
+    let mut notable_deletions: Vec<String> = Vec::new();
+    for file in &files {
+        if file.status == "deleted" {
+            notable_deletions.push(format!("Deleted {} ({} lines)", file.path, file.deletions));
+        } else if file.deletions >= LARGE_REMOVAL_LINES && file.deletions > file.insertions * 2 {
+            notable_deletions.push(format!(
+                "Removed {} lines from {}",
+                file.deletions, file.path
+            ));
+        }
+    }
+
+    let mut by_area: BTreeMap<String, Vec<FileChange>> = BTreeMap::new();
+    for file in &files {
+        by_area
+            .entry(area_for_path(&file.path))
+            .or_default()
+            .push(file.clone());
+    }
+    let mut areas: Vec<AreaChanges> = by_area
+        .into_iter()
+        .map(|(area, files)| AreaChanges {
+            insertions: files.iter().map(|f| f.insertions).sum(),
+            deletions: files.iter().map(|f| f.deletions).sum(),
+            area,
+            files,
+        })
+        .collect();
+    areas.sort_by_key(|a| std::cmp::Reverse(a.insertions + a.deletions));
+
+    let mut summary = ChangeSummary {
+        base_commit: base_commit.to_string(),
+        files_changed: files.len(),
+        insertions: files.iter().map(|f| f.insertions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        areas,
+        added_apis,
+        removed_apis,
+        notable_deletions,
+        markdown: String::new(),
+    };
+    summary.markdown = render_summary_markdown(&summary);
+    summary
+}
+
+/// Render a summary as Markdown.
+pub fn render_summary_markdown(summary: &ChangeSummary) -> String {
+    let mut out = format!(
+        "## Summary of changes\n\n{} file{} changed, +{} / -{}\n",
+        summary.files_changed,
+        if summary.files_changed == 1 { "" } else { "s" },
+        summary.insertions,
+        summary.deletions
+    );
+
+    if !summary.areas.is_empty() {
+        out.push_str("\n### Files by area\n\n");
+        for area in &summary.areas {
+            out.push_str(&format!(
+                "- **{}** ({} file{}, +{} / -{})\n",
+                area.area,
+                area.files.len(),
+                if area.files.len() == 1 { "" } else { "s" },
+                area.insertions,
+                area.deletions
+            ));
+            for file in area.files.iter().take(MAX_FILES_PER_AREA) {
+                let counts = if file.binary {
+                    "binary".to_string()
+                } else {
+                    format!("+{} / -{}", file.insertions, file.deletions)
+                };
+                out.push_str(&format!(
+                    "  - `{}` ({}, {})\n",
+                    file.path, file.status, counts
+                ));
+            }
+            if area.files.len() > MAX_FILES_PER_AREA {
+                out.push_str(&format!(
+                    "  - ... and {} more\n",
+                    area.files.len() - MAX_FILES_PER_AREA
+                ));
+            }
+        }
+    }
+
+    let mut section = |title: &str, items: &[String]| {
+        if items.is_empty() {
+            return;
+        }
+        out.push_str(&format!("\n### {}\n\n", title));
+        for item in items {
+            out.push_str(&format!("- {}\n", item));
+        }
+    };
+    section("Added APIs", &summary.added_apis);
+    section("Removed APIs", &summary.removed_apis);
+    section("Notable deletions", &summary.notable_deletions);
+
+    out
+}
+
+// ============ Git ============
+
+/// Compute the change summary of a worktree against a base ref.
+/// Includes uncommitted and untracked changes.
+pub fn compute_change_summary(
+    worktree_path: &str,
+    base_ref: &str,
+) -> Result<ChangeSummary, String> {
+    let base = diff_base(worktree_path, base_ref)?;
+    let patch = diff_output(worktree_path, &base, true, &["-U0"], &[])?;
+
+    let mut untracked_apis: Vec<String> = Vec::new();
+    let mut files: Vec<FileChange> = Vec::new();
+    for file in changed_files(worktree_path, &base, true)? {
+        if file.status == ChangedFileStatus::Untracked && !file.binary {
+            let content = std::fs::read_to_string(Path::new(worktree_path).join(&file.path))
+                .unwrap_or_default();
+            for line in content.lines() {
+                if let Some(api) = extract_api_declaration(line) {
+                    untracked_apis.push(format!("{}: {}", file.path, api));
+                }
+            }
+        }
+        let status = match file.status {
+            ChangedFileStatus::Added | ChangedFileStatus::Untracked => "added",
+            ChangedFileStatus::Deleted => "deleted",
+            ChangedFileStatus::Modified | ChangedFileStatus::TypeChanged => "modified",
+        };
+        files.push(FileChange {
+            path: file.path,
+            status: status.to_string(),
+            insertions: file.insertions,
+            deletions: file.deletions,
+            binary: file.binary,
+        });
+    }
+
+    Ok(build_change_summary(&base, files, &patch, untracked_apis))
+}
+
+/// Get the ref an agent's work is compared against.
+pub fn get_task_base_ref(task: &Task) -> Result<String, String> {
+    match task.source_type.as_str() {
+        "commit" => task.source_commit.clone(),
+        _ => task.source_branch.clone(),
+    }
+    .ok_or_else(|| format!("Task {} has no source ref", task.id))
+}
+
+/// Store Markdown as a task's result summary.
+pub fn set_task_result_summary_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    summary: Option<String>,
+) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+        task.result_summary = summary;
+        task.updated_at = Utc::now().timestamp_millis();
+    }
+    state.save()
+}
+
+/// Generate a change summary for an agent's worktree against the task source.
+/// When `save` is set, the Markdown is stored as the task's result summary.
+/// The git work runs on a blocking thread to avoid freezing the UI.
+pub async fn generate_change_summary_async(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_id: String,
+    save: bool,
+    default_base: Option<String>,
+) -> Result<ChangeSummary, String> {
+    let task = get_task_impl(state, &task_id)?;
+    let worktree_path = task
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id)
+        .map(|a| a.worktree_path.clone())
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+    let base_ref = get_task_base_ref(&task).or_else(|e| default_base.ok_or(e))?;
+
+    let summary =
+        tokio::task::spawn_blocking(move || compute_change_summary(&worktree_path, &base_ref))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+
+    if save {
+        set_task_result_summary_impl(state, &task_id, Some(summary.markdown.clone()))?;
+    }
+    Ok(summary)
+}
@@ -0,0 +1,501 @@
+//! Side-by-side HTML comparison of agent diffs.
+//!
+//! `export_agent_comparison` writes one standalone HTML file (inline styles,
+//! no scripts or external assets) with a section per changed file and, in it,
+//! a column per selected agent showing that agent's diff against the task
+//! source, with line numbers and syntax highlighting. It can be shared with
+//! someone who doesn't run the app.
+//!
+//! Highlighting uses syntect's bundled syntaxes and `InspiredGitHub` theme,
+//! written as inline styles; files without a known syntax are shown plain.
+//! Diff text passes through `core::redaction` before it is written, like logs
+//! and transcripts.
+
+use chrono::{TimeZone, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::html::{styled_line_to_highlighted_html, IncludeBackground};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+use crate::core::redaction::redact;
+use crate::worktrees::diff::{diff_base, diff_output, untracked_files};
+
+use super::change_summary::get_task_base_ref;
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_impl, slugify};
+use super::types::{AgentComparisonExport, Task};
+
+/// Diff lines shown per file and agent; the rest is cut with a note.
+pub const MAX_LINES_PER_FILE: usize = 2000;
+
+/// Kind of a line in a rendered diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// `@@ ... @@` hunk header
+    Hunk,
+    Added,
+    Removed,
+    Context,
+    /// Not a line of the file, e.g. "Binary files differ"
+    Note,
+}
+
+/// A line of a file's diff, with its numbers in the old and new file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+    pub text: String,
+}
+
+impl DiffLine {
+    fn note(text: &str) -> Self {
+        Self {
+            kind: DiffLineKind::Note,
+            old_line: None,
+            new_line: None,
+            text: text.to_string(),
+        }
+    }
+}
+
+/// One agent's column in the comparison.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentDiff {
+    pub agent_id: String,
+    /// Shown in the column header, e.g. the model
+    pub label: String,
+    pub files: BTreeMap<String, Vec<DiffLine>>,
+}
+
+// ============ Diff Parsing ============
+
+/// Start lines of the old and new side from a `@@ -a,b +c,d @@` header.
+fn parse_hunk_header(line: &str) -> Option<(u32, u32)> {
+    let mut parts = line.split_whitespace().skip(1);
+    let start = |part: Option<&str>, sign: char| -> Option<u32> {
+        part?.strip_prefix(sign)?.split(',').next()?.parse().ok()
+    };
+    Some((start(parts.next(), '-')?, start(parts.next(), '+')?))
+}
+
+/// Split a unified `git diff` into the lines of each file, numbered from the
+/// hunk headers. File headers (`diff --git`, `index`, `---`/`+++`) are dropped.
+pub fn parse_unified_diff(patch: &str) -> BTreeMap<String, Vec<DiffLine>> {
+    let mut files: BTreeMap<String, Vec<DiffLine>> = BTreeMap::new();
+    let mut current: Option<String> = None;
+    let (mut old, mut new) = (0u32, 0u32);
+    let mut in_hunk = false;
+
+    for line in patch.lines() {
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let path = header
+                .rsplit_once(" b/")
+                .map(|(_, p)| p.to_string())
+                .unwrap_or_default();
+            files.entry(path.clone()).or_default();
+            current = Some(path);
+            in_hunk = false;
+            continue;
+        }
+        let Some(lines) = current.as_ref().and_then(|p| files.get_mut(p)) else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            if let Some((o, n)) = parse_hunk_header(line) {
+                (old, new) = (o, n);
+                in_hunk = true;
+                lines.push(DiffLine {
+                    kind: DiffLineKind::Hunk,
+                    old_line: None,
+                    new_line: None,
+                    text: line.to_string(),
+                });
+            }
+            continue;
+        }
+        if !in_hunk {
+            if line.starts_with("Binary files") {
+                lines.push(DiffLine::note("Binary file changed"));
+            }
+            continue;
+        }
+
+        let (kind, old_line, new_line) = match line.chars().next() {
+            Some('+') => {
+                new += 1;
+                (DiffLineKind::Added, None, Some(new - 1))
+            }
+            Some('-') => {
+                old += 1;
+                (DiffLineKind::Removed, Some(old - 1), None)
+            }
+            Some(' ') | None => {
+                old += 1;
+                new += 1;
+                (DiffLineKind::Context, Some(old - 1), Some(new - 1))
+            }
+            // "\ No newline at end of file"
+            _ => {
+                lines.push(DiffLine::note(line.trim_start_matches("\\ ")));
+                continue;
+            }
+        };
+        lines.push(DiffLine {
+            kind,
+            old_line,
+            new_line,
+            text: line.get(1..).unwrap_or("").to_string(),
+        });
+    }
+    files
+}
+
+/// Lines of an untracked file, shown as fully added.
+fn untracked_file_lines(path: &Path) -> Vec<DiffLine> {
+    match fs::read(path) {
+        Ok(bytes) if bytes.contains(&0) => vec![DiffLine::note("Binary file added")],
+        Ok(bytes) => String::from_utf8_lossy(&bytes)
+            .lines()
+            .enumerate()
+            .map(|(i, text)| DiffLine {
+                kind: DiffLineKind::Added,
+                old_line: None,
+                new_line: Some(i as u32 + 1),
+                text: text.to_string(),
+            })
+            .collect(),
+        Err(e) => vec![DiffLine::note(&format!("Unreadable: {}", e))],
+    }
+}
+
+/// An agent's diff against the merge base with `base_ref`, including
+/// uncommitted and untracked changes.
+pub fn collect_agent_diff(
+    worktree_path: &str,
+    base_ref: &str,
+) -> Result<BTreeMap<String, Vec<DiffLine>>, String> {
+    let base = diff_base(worktree_path, base_ref)?;
+    let patch = diff_output(worktree_path, &base, true, &[], &[])?;
+    let mut files = parse_unified_diff(&patch);
+    for path in untracked_files(worktree_path, &[])? {
+        let lines = untracked_file_lines(&Path::new(worktree_path).join(&path));
+        files.insert(path, lines);
+    }
+    Ok(files)
+}
+
+// ============ Highlighting ============
+
+/// Bundled syntect theme the diffs are highlighted with, light like the page.
+pub const HIGHLIGHT_THEME: &str = "InspiredGitHub";
+
+static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+
+fn syntaxes() -> &'static SyntaxSet {
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_nonewlines)
+}
+
+/// The syntax for a file, by extension or by name (e.g. `Makefile`).
+/// TypeScript, which syntect doesn't bundle, is highlighted as JavaScript.
+pub fn syntax_for_path(path: &str) -> Option<&'static SyntaxReference> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or(name);
+    let extension = match extension {
+        "ts" | "tsx" | "mts" | "cts" => "js",
+        extension => extension,
+    };
+    syntaxes().find_syntax_by_extension(extension)
+}
+
+/// Escape text for HTML element content and attribute values.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn highlight(highlighter: &mut HighlightLines, code: &str) -> Option<String> {
+    let regions = highlighter.highlight_line(code, syntaxes()).ok()?;
+    styled_line_to_highlighted_html(&regions, IncludeBackground::No).ok()
+}
+
+/// The lines of a file's diff as HTML, highlighted for the file's syntax or
+/// only escaped. The old side (context and removed lines) and the new side
+/// (context and added lines) are parsed separately, so strings and comments
+/// spanning lines are followed on each; every hunk starts afresh.
+pub fn highlight_diff_lines(path: &str, lines: &[DiffLine]) -> Vec<String> {
+    let Some(syntax) = syntax_for_path(path) else {
+        return lines.iter().map(|line| escape_html(&line.text)).collect();
+    };
+    let theme = &THEMES.get_or_init(ThemeSet::load_defaults).themes[HIGHLIGHT_THEME];
+    let mut old = HighlightLines::new(syntax, theme);
+    let mut new = HighlightLines::new(syntax, theme);
+
+    lines
+        .iter()
+        .map(|line| {
+            let html = match line.kind {
+                DiffLineKind::Hunk => {
+                    old = HighlightLines::new(syntax, theme);
+                    new = HighlightLines::new(syntax, theme);
+                    None
+                }
+                DiffLineKind::Note => None,
+                DiffLineKind::Added => highlight(&mut new, &line.text),
+                DiffLineKind::Removed => highlight(&mut old, &line.text),
+                DiffLineKind::Context => {
+                    highlight(&mut old, &line.text);
+                    highlight(&mut new, &line.text)
+                }
+            };
+            html.unwrap_or_else(|| escape_html(&line.text))
+        })
+        .collect()
+}
+
+// ============ Rendering ============
+
+const STYLE: &str = "\
+body{margin:0;font:14px/1.4 -apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;color:#1f2328;background:#fff}\
+header{padding:16px 24px;border-bottom:1px solid #d0d7de}\
+h1{margin:0 0 4px;font-size:20px}\
+header p{margin:0;color:#59636e}\
+nav{padding:8px 24px;border-bottom:1px solid #d0d7de;columns:3}\
+nav a{display:block;color:#0969da;text-decoration:none;font:12px ui-monospace,Menlo,monospace}\
+section{padding:16px 24px}\
+h2{margin:0 0 8px;font:600 14px ui-monospace,Menlo,monospace}\
+.cols{display:grid;gap:12px}\
+.col{min-width:0;border:1px solid #d0d7de;border-radius:6px;overflow:auto}\
+h3{margin:0;padding:6px 10px;font-size:13px;background:#f6f8fa;border-bottom:1px solid #d0d7de}\
+.none{padding:8px 10px;color:#59636e;font-style:italic}\
+table{border-collapse:collapse;width:100%;font:12px/1.5 ui-monospace,Menlo,monospace}\
+td{padding:0 8px;white-space:pre;vertical-align:top}\
+td.ln{width:1%;color:#8c959f;text-align:right;user-select:none}\
+tr.add{background:#e6ffec}tr.del{background:#ffebe9}\
+tr.hunk td,tr.note td{color:#59636e;background:#ddf4ff}";
+
+fn render_rows(path: &str, lines: &[DiffLine]) -> String {
+    let shown = &lines[..lines.len().min(MAX_LINES_PER_FILE)];
+    let mut rows = String::from("<table>");
+    for (line, code) in shown.iter().zip(highlight_diff_lines(path, shown)) {
+        let number = |n: Option<u32>| n.map(|n| n.to_string()).unwrap_or_default();
+        let (class, sign) = match line.kind {
+            DiffLineKind::Hunk => ("hunk", ""),
+            DiffLineKind::Note => ("note", ""),
+            DiffLineKind::Added => ("add", "+"),
+            DiffLineKind::Removed => ("del", "-"),
+            DiffLineKind::Context => ("ctx", " "),
+        };
+        rows.push_str(&format!(
+            "<tr class=\"{}\"><td class=\"ln\">{}</td><td class=\"ln\">{}</td><td>{}{}</td></tr>",
+            class,
+            number(line.old_line),
+            number(line.new_line),
+            sign,
+            code
+        ));
+    }
+    if lines.len() > MAX_LINES_PER_FILE {
+        rows.push_str(&format!(
+            "<tr class=\"note\"><td class=\"ln\"></td><td class=\"ln\"></td><td>{} more lines not shown</td></tr>",
+            lines.len() - MAX_LINES_PER_FILE
+        ));
+    }
+    rows.push_str("</table>");
+    rows
+}
+
+/// The comparison as a standalone HTML document. `exported_at` is in
+/// milliseconds since epoch.
+pub fn render_comparison_html(
+    title: &str,
+    base_ref: &str,
+    agents: &[AgentDiff],
+    exported_at: i64,
+) -> String {
+    let files: BTreeSet<&String> = agents.iter().flat_map(|a| a.files.keys()).collect();
+    let exported = Utc
+        .timestamp_millis_opt(exported_at)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <header><h1>{title}</h1><p>{count} agent(s), {files} file(s) changed, compared against \
+         <code>{base}</code>. Exported {exported}.</p></header>\n",
+        title = escape_html(title),
+        count = agents.len(),
+        files = files.len(),
+        base = escape_html(base_ref),
+        exported = exported,
+    );
+
+    html.push_str("<nav>");
+    for (i, path) in files.iter().enumerate() {
+        html.push_str(&format!(
+            "<a href=\"#file-{}\">{}</a>",
+            i,
+            escape_html(path)
+        ));
+    }
+    html.push_str("</nav>\n");
+
+    for (i, path) in files.iter().enumerate() {
+        html.push_str(&format!(
+            "<section id=\"file-{}\"><h2>{}</h2><div class=\"cols\" style=\"grid-template-columns:repeat({},minmax(0,1fr))\">",
+            i,
+            escape_html(path),
+            agents.len().max(1)
+        ));
+        for agent in agents {
+            html.push_str(&format!(
+                "<div class=\"col\"><h3>{} &middot; {}</h3>",
+                escape_html(&agent.agent_id),
+                escape_html(&agent.label)
+            ));
+            match agent.files.get(*path) {
+                Some(lines) if !lines.is_empty() => html.push_str(&render_rows(path, lines)),
+                Some(_) => html.push_str("<div class=\"none\">No line changes</div>"),
+                None => html.push_str("<div class=\"none\">Not changed</div>"),
+            }
+            html.push_str("</div>");
+        }
+        html.push_str("</div></section>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// ============ Export ============
+
+/// Where to write: `dest` itself, or a file named after the task inside it
+/// when `dest` is a folder.
+fn export_path(dest: &Path, task: &Task) -> Result<PathBuf, String> {
+    if dest.is_dir() {
+        let slug = slugify(&task.name);
+        let name = if slug.is_empty() {
+            task.id.clone()
+        } else {
+            slug
+        };
+        return Ok(dest.join(format!("{}-comparison.html", name)));
+    }
+    match dest.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+            Err(format!("Folder does not exist: {}", parent.display()))
+        }
+        _ => Ok(dest.to_path_buf()),
+    }
+}
+
+/// Write the comparison of `agent_ids` (all agents with a worktree when
+/// empty) to `dest`, a file or a folder. Diffs are taken against `base_ref`.
+pub fn export_agent_comparison(
+    task: &Task,
+    agent_ids: &[String],
+    base_ref: &str,
+    dest: &Path,
+) -> Result<AgentComparisonExport, String> {
+    let selected: Vec<_> = if agent_ids.is_empty() {
+        task.agents
+            .iter()
+            .filter(|a| Path::new(&a.worktree_path).exists())
+            .collect()
+    } else {
+        agent_ids
+            .iter()
+            .map(|id| {
+                task.agents
+                    .iter()
+                    .find(|a| &a.id == id)
+                    .ok_or_else(|| format!("Agent not found: {}", id))
+            })
+            .collect::<Result<_, _>>()?
+    };
+    if selected.is_empty() {
+        return Err("No agents with a worktree to compare".to_string());
+    }
+
+    let mut agents = Vec::with_capacity(selected.len());
+    for agent in selected {
+        let mut files = collect_agent_diff(&agent.worktree_path, base_ref)
+            .map_err(|e| format!("{}: {}", agent.id, e))?;
+        for lines in files.values_mut() {
+            for line in lines.iter_mut() {
+                line.text = redact(&line.text);
+            }
+        }
+        agents.push(AgentDiff {
+            agent_id: agent.id.clone(),
+            label: format!("{}/{}", agent.provider_id, agent.model_id),
+            files,
+        });
+    }
+
+    let html = render_comparison_html(
+        &format!("{}: agent comparison", task.name),
+        base_ref,
+        &agents,
+        Utc::now().timestamp_millis(),
+    );
+    let path = export_path(dest, task)?;
+    fs::write(&path, &html).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let file_count = agents
+        .iter()
+        .flat_map(|a| a.files.keys())
+        .collect::<BTreeSet<_>>()
+        .len();
+    println!(
+        "[comparison] Exported {} agent(s) of task {} to {}",
+        agents.len(),
+        task.id,
+        path.display()
+    );
+    Ok(AgentComparisonExport {
+        path: path.to_string_lossy().to_string(),
+        agent_ids: agents.into_iter().map(|a| a.agent_id).collect(),
+        file_count,
+        size_bytes: html.len() as u64,
+    })
+}
+
+/// Export a task's agent comparison (async version). Tasks without a source
+/// ref are compared against `default_base`.
+pub async fn export_agent_comparison_async(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_ids: Vec<String>,
+    dest: String,
+    default_base: Option<String>,
+) -> Result<AgentComparisonExport, String> {
+    let task = get_task_impl(state, &task_id)?;
+    let base_ref = get_task_base_ref(&task).or_else(|e| default_base.ok_or(e))?;
+
+    tokio::task::spawn_blocking(move || {
+        export_agent_comparison(&task, &agent_ids, &base_ref, Path::new(&dest))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
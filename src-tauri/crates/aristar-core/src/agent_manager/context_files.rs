@@ -0,0 +1,189 @@
+//! Context files shared by every agent of a task.
+//!
+//! Attached files are copied into the task folder
+//! (`~/.aristar-worktrees/tasks/{task-id}/context/`), which is the source of truth,
+//! and from there into `.aristar/context/` in each agent worktree. `.aristar/` is
+//! added to the repository's `info/exclude` so the copies never show up in diffs.
+
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::worktrees::operations::ensure_aristar_excluded;
+
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_folder_path, get_task_impl};
+use super::types::{ContextFile, Task};
+
+/// Folder inside each agent worktree holding the context files.
+pub const WORKTREE_CONTEXT_DIR: &str = ".aristar/context";
+
+/// Maximum size of a single context file.
+pub const MAX_CONTEXT_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Folder holding a task's context files: ~/.aristar-worktrees/tasks/{task-id}/context/
+pub fn get_task_context_dir(task_id: &str) -> PathBuf {
+    get_task_folder_path(task_id).join("context")
+}
+
+/// Copy `source` into `store_dir`, replacing any file with the same name.
+pub fn stage_context_file(
+    source: &Path,
+    store_dir: &Path,
+    now: i64,
+) -> Result<ContextFile, String> {
+    let metadata = fs::metadata(source)
+        .map_err(|e| format!("Cannot read context file {}: {}", source.display(), e))?;
+    if !metadata.is_file() {
+        return Err(format!("Context file is not a file: {}", source.display()));
+    }
+    if metadata.len() > MAX_CONTEXT_FILE_SIZE {
+        return Err(format!(
+            "Context file is larger than {} MB: {}",
+            MAX_CONTEXT_FILE_SIZE / (1024 * 1024),
+            source.display()
+        ));
+    }
+
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid context file path: {}", source.display()))?;
+
+    fs::create_dir_all(store_dir).map_err(|e| format!("Failed to create context folder: {}", e))?;
+    fs::copy(source, store_dir.join(&name))
+        .map_err(|e| format!("Failed to copy context file {}: {}", name, e))?;
+
+    Ok(ContextFile {
+        name,
+        source_path: source.to_string_lossy().to_string(),
+        size: metadata.len(),
+        added_at: now,
+    })
+}
+
+/// Copy the given context files from `store_dir` into a worktree's `.aristar/context/`.
+pub fn sync_context_files(
+    store_dir: &Path,
+    worktree_path: &Path,
+    files: &[ContextFile],
+) -> Result<(), String> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    ensure_aristar_excluded(worktree_path)?;
+    let target_dir = worktree_path.join(WORKTREE_CONTEXT_DIR);
+    fs::create_dir_all(&target_dir)
+        .map_err(|e| format!("Failed to create {}: {}", target_dir.display(), e))?;
+
+    for file in files {
+        fs::copy(store_dir.join(&file.name), target_dir.join(&file.name))
+            .map_err(|e| format!("Failed to copy context file {}: {}", file.name, e))?;
+    }
+    Ok(())
+}
+
+/// Copy a task's context files into an agent worktree.
+pub fn sync_task_context(task: &Task, worktree_path: &str) -> Result<(), String> {
+    sync_context_files(
+        &get_task_context_dir(&task.id),
+        Path::new(worktree_path),
+        &task.context_files,
+    )
+}
+
+/// Attach files to a task and copy them into every existing agent worktree.
+/// Re-attaching a file with the same name replaces it.
+pub fn add_task_context_files_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    paths: Vec<String>,
+) -> Result<Task, String> {
+    if paths.is_empty() {
+        return Err("No context files given".to_string());
+    }
+
+    let task = get_task_impl(state, task_id)?;
+    let store_dir = get_task_context_dir(task_id);
+    let now = Utc::now().timestamp_millis();
+
+    let mut added = Vec::new();
+    for path in &paths {
+        added.push(stage_context_file(Path::new(path), &store_dir, now)?);
+    }
+
+    for agent in &task.agents {
+        if Path::new(&agent.worktree_path).exists() {
+            sync_context_files(&store_dir, Path::new(&agent.worktree_path), &added)?;
+        }
+    }
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        for file in added {
+            task.context_files.retain(|f| f.name != file.name);
+            task.context_files.push(file);
+        }
+        task.updated_at = now;
+        task.clone()
+    };
+
+    state.save()?;
+    println!(
+        "[task_manager] Attached {} context file(s) to task {}",
+        paths.len(),
+        task_id
+    );
+    Ok(task)
+}
+
+/// Detach a context file from a task and remove its copies.
+pub fn remove_task_context_file_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    name: &str,
+) -> Result<Task, String> {
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let before = task.context_files.len();
+        task.context_files.retain(|f| f.name != name);
+        if task.context_files.len() == before {
+            return Err(format!("Context file not found: {}", name));
+        }
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+
+    let _ = fs::remove_file(get_task_context_dir(task_id).join(name));
+    for agent in &task.agents {
+        let _ = fs::remove_file(
+            Path::new(&agent.worktree_path)
+                .join(WORKTREE_CONTEXT_DIR)
+                .join(name),
+        );
+    }
+    Ok(task)
+}
+
+/// List a task's context files.
+pub fn get_task_context_files_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+) -> Result<Vec<ContextFile>, String> {
+    Ok(get_task_impl(state, task_id)?.context_files)
+}
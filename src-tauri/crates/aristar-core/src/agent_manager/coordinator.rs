@@ -0,0 +1,156 @@
+//! Commands spanning the task store and the OpenCode manager.
+//!
+//! Lock hierarchy, outermost first:
+//!
+//! 1. `AppState::store`, then `AppState::index`
+//! 2. `TaskManagerState::store`
+//! 3. `OpenCodeManager::instances`
+//!
+//! A lock may only be taken while holding locks above it, never below. Code
+//! that needs more than one state does not nest them at all: it copies what it
+//! needs out of one state, releases the lock, then takes the next. Events and
+//! notifications are emitted with no lock held, since listeners may call back
+//! into any state. The functions here follow that pattern for agent OpenCode
+//! servers so the Tauri commands only emit what they return.
+
+use std::path::PathBuf;
+
+use super::agent_operations::{
+    record_agent_port_impl, record_agent_started_impl, record_sandbox_unenforced_impl,
+};
+use super::opencode::OpenCodeManager;
+use super::opencode_install::resolve_pinned_binary;
+use super::sandbox::sandbox_exec_available;
+use super::store::TaskManagerState;
+use super::types::SandboxConfig;
+
+/// What starting an agent's OpenCode server needs from the task store.
+#[derive(Debug, Clone)]
+pub struct AgentServerTarget {
+    pub worktree_path: PathBuf,
+    pub opencode_version: Option<String>,
+    pub preferred_port: Option<u16>,
+    pub sandbox: SandboxConfig,
+}
+
+/// Result of `start_agent_server`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentServerStarted {
+    pub port: u16,
+    /// The agent's preferred port, when the server had to use another
+    pub previous_port: Option<u16>,
+}
+
+/// Copy an agent's server settings out of the task store.
+pub fn agent_server_target(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<AgentServerTarget, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    let task = store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    let agent = task
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    Ok(AgentServerTarget {
+        worktree_path: PathBuf::from(&agent.worktree_path),
+        opencode_version: task.opencode_version.clone(),
+        preferred_port: agent.preferred_port,
+        sandbox: task.sandbox.clone(),
+    })
+}
+
+/// Worktree paths of every agent of a task.
+pub fn task_worktree_paths(
+    state: &TaskManagerState,
+    task_id: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    let task = store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+    Ok(task
+        .agents
+        .iter()
+        .map(|a| PathBuf::from(&a.worktree_path))
+        .collect())
+}
+
+/// Start an agent's OpenCode server with the task's pinned version and
+/// sandbox, on its preferred port when free, and record the start and port.
+/// A start with an unenforced sandbox is recorded in the task timeline.
+pub fn start_agent_server(
+    task_state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<AgentServerStarted, String> {
+    let target = agent_server_target(task_state, task_id, agent_id)?;
+
+    let binary = resolve_pinned_binary(target.opencode_version.as_deref())?;
+    let already_running = opencode.is_running(&target.worktree_path);
+    let port = opencode.start_with_options(
+        target.worktree_path,
+        binary,
+        target.preferred_port,
+        Some(&target.sandbox),
+    )?;
+    if !already_running {
+        record_agent_started_impl(task_state, task_id, agent_id)?;
+        if target.sandbox.enabled && !sandbox_exec_available() {
+            record_sandbox_unenforced_impl(task_state, task_id, agent_id)?;
+        }
+    }
+
+    let previous_port = record_agent_port_impl(task_state, task_id, agent_id, port)?;
+    Ok(AgentServerStarted {
+        port,
+        previous_port,
+    })
+}
+
+/// Stop an agent's OpenCode server.
+pub fn stop_agent_server(
+    task_state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<(), String> {
+    let target = agent_server_target(task_state, task_id, agent_id)?;
+    opencode.stop(&target.worktree_path)
+}
+
+/// Port of an agent's OpenCode server, if running.
+pub fn agent_server_port(
+    task_state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<Option<u16>, String> {
+    let target = agent_server_target(task_state, task_id, agent_id)?;
+    opencode.get_port(&target.worktree_path)
+}
+
+/// Stop the OpenCode servers of every agent of a task. Failures to stop one
+/// server don't keep the others running.
+pub fn stop_task_servers(
+    task_state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    task_id: &str,
+) -> Result<(), String> {
+    for path in task_worktree_paths(task_state, task_id)? {
+        if let Err(e) = opencode.stop(&path) {
+            eprintln!("[opencode] Failed to stop {}: {}", path.display(), e);
+        }
+    }
+    Ok(())
+}
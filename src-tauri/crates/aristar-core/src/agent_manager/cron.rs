@@ -0,0 +1,190 @@
+//! Cron expressions for task schedules.
+//!
+//! Supports the standard five fields (`minute hour day-of-month month day-of-week`)
+//! with `*`, lists (`1,15`), ranges (`1-5`), steps (`*/10`, `8-18/2`), month and
+//! weekday names (`jan`, `mon`) and the aliases `@hourly`, `@daily`, `@midnight`,
+//! `@weekly`, `@monthly`, `@yearly` and `@annually`. Times are local time.
+//!
+//! As in Vixie cron, when both day fields are restricted a day matches if either does.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead to look for the next run before giving up (e.g. `0 0 30 2 *`).
+const MAX_YEARS_AHEAD: i32 = 5;
+
+/// A parsed cron expression. Each field is a bitmask of allowed values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day-of-month field is not `*`
+    dom_restricted: bool,
+    /// Day-of-week field is not `*`
+    dow_restricted: bool,
+}
+
+fn expand_alias(expr: &str) -> Option<&'static str> {
+    match expr {
+        "@hourly" => Some("0 * * * *"),
+        "@daily" | "@midnight" => Some("0 0 * * *"),
+        "@weekly" => Some("0 0 * * 0"),
+        "@monthly" => Some("0 0 1 * *"),
+        "@yearly" | "@annually" => Some("0 0 1 1 *"),
+        _ => None,
+    }
+}
+
+fn parse_value(value: &str, names: &[&str], offset: u32) -> Option<u32> {
+    if let Ok(n) = value.parse() {
+        return Some(n);
+    }
+    let lower = value.to_lowercase();
+    names
+        .iter()
+        .position(|n| *n == lower)
+        .map(|i| i as u32 + offset)
+}
+
+/// Parse one field into a bitmask of values in `min..=max`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let invalid = || format!("Invalid cron field '{}'", field);
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (
+                parse_value(a, names, min).ok_or_else(invalid)?,
+                parse_value(b, names, min).ok_or_else(invalid)?,
+            )
+        } else {
+            let value = parse_value(range, names, min).ok_or_else(invalid)?;
+            // "5/15" means every 15 starting at 5
+            (value, if step.is_some() { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(format!(
+                "Cron field '{}' out of range {}-{}",
+                field, min, max
+            ));
+        }
+
+        let mut value = start;
+        while value <= end {
+            mask |= 1 << value;
+            value += step.unwrap_or(1);
+        }
+    }
+
+    Ok(mask)
+}
+
+impl CronExpr {
+    /// Parse a cron expression.
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expr = expand_alias(expr).unwrap_or(expr);
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Cron expression must have 5 fields (minute hour day month weekday): '{}'",
+                expr
+            ));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        // 7 is an alias for Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59, &[])?,
+            hours: parse_field(fields[1], 0, 23, &[])?,
+            days: parse_field(fields[2], 1, 31, &[])?,
+            months: parse_field(fields[3], 1, 12, &MONTH_NAMES)?,
+            weekdays,
+            dom_restricted: !fields[2].starts_with('*'),
+            dow_restricted: !fields[4].starts_with('*'),
+        })
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days & (1 << date.day()) != 0;
+        let dow = self.weekdays & (1 << date.weekday().num_days_from_sunday()) != 0;
+        if self.dom_restricted && self.dow_restricted {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+
+    /// First matching minute strictly after `after`.
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after.year() + MAX_YEARS_AHEAD;
+
+        while t.year() <= limit {
+            if self.months & (1 << t.month()) == 0 {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = (t.date() + Duration::days(1)).and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if self.minutes & (1 << t.minute()) == 0 {
+                t += Duration::minutes(1);
+                continue;
+            }
+            return Some(t);
+        }
+        None
+    }
+}
+
+/// Next run of `expr` in local time strictly after `after_ms`, as milliseconds
+/// since epoch. Local times skipped by DST changes are skipped.
+pub fn next_run_after(expr: &CronExpr, after_ms: i64) -> Option<i64> {
+    let mut after = Local.timestamp_millis_opt(after_ms).single()?.naive_local();
+    loop {
+        let next = expr.next_after(after)?;
+        if let Some(local) = Local.from_local_datetime(&next).earliest() {
+            if local.timestamp_millis() > after_ms {
+                return Some(local.timestamp_millis());
+            }
+        }
+        after = next;
+    }
+}
@@ -0,0 +1,166 @@
+//! Which models win: acceptance history across tasks.
+//!
+//! Accepting an agent records an `AgentOutcome` for every agent of the task:
+//! whether it won, its pipeline score and the size of its diff against the task
+//! source. The outcomes stay on the task after `cleanup_unaccepted_agents`
+//! removes the losers, and accepting another agent later records them again.
+//! Tasks decided before outcomes were recorded fall back to their remaining
+//! agents, without diff sizes.
+//!
+//! `get_model_leaderboard` groups the outcomes of all tasks (or of one
+//! repository's) by provider and model.
+
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::change_summary::{compute_change_summary, get_task_base_ref};
+use super::store::TaskManagerState;
+use super::task_operations::same_repo_path;
+use super::types::{AgentDiffStats, AgentOutcome, ModelLeaderboardEntry, Task};
+
+/// Diff size of an agent worktree against `base_ref`, including uncommitted
+/// and untracked changes. `None` when the worktree is gone or git fails.
+pub fn agent_diff_stats(worktree_path: &str, base_ref: &str) -> Option<AgentDiffStats> {
+    if !Path::new(worktree_path).exists() {
+        return None;
+    }
+    match compute_change_summary(worktree_path, base_ref) {
+        Ok(summary) => Some(AgentDiffStats {
+            files_changed: summary.files_changed,
+            insertions: summary.insertions,
+            deletions: summary.deletions,
+        }),
+        Err(e) => {
+            eprintln!("[leaderboard] {}: {}", worktree_path, e);
+            None
+        }
+    }
+}
+
+/// Outcomes of the task's agents with `accepted_id` as the winner. Diffs are
+/// measured against the task source; tasks without a source ref get none.
+pub fn collect_agent_outcomes(task: &Task, accepted_id: &str) -> Vec<AgentOutcome> {
+    let base_ref = get_task_base_ref(task).ok();
+    let recorded_at = Utc::now().timestamp_millis();
+    task.agents
+        .iter()
+        .map(|agent| AgentOutcome {
+            agent_id: agent.id.clone(),
+            provider_id: agent.provider_id.clone(),
+            model_id: agent.model_id.clone(),
+            accepted: agent.id == accepted_id,
+            score: agent_score(task, &agent.id),
+            diff: base_ref
+                .as_deref()
+                .and_then(|base| agent_diff_stats(&agent.worktree_path, base)),
+            recorded_at,
+        })
+        .collect()
+}
+
+fn agent_score(task: &Task, agent_id: &str) -> Option<u32> {
+    task.pipeline_run
+        .as_ref()?
+        .scores
+        .iter()
+        .find(|s| s.agent_id == agent_id)
+        .map(|s| s.score)
+}
+
+/// Recorded outcomes of a task, or for a task decided before they were
+/// recorded, outcomes from its remaining agents. Empty while undecided.
+pub fn task_outcomes(task: &Task) -> Vec<AgentOutcome> {
+    if !task.agent_outcomes.is_empty() {
+        return task.agent_outcomes.clone();
+    }
+    if !task.agents.iter().any(|a| a.accepted) {
+        return Vec::new();
+    }
+    task.agents
+        .iter()
+        .map(|agent| AgentOutcome {
+            agent_id: agent.id.clone(),
+            provider_id: agent.provider_id.clone(),
+            model_id: agent.model_id.clone(),
+            accepted: agent.accepted,
+            score: agent_score(task, &agent.id),
+            diff: None,
+            recorded_at: task.updated_at,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Tally {
+    runs: usize,
+    accepted: usize,
+    scores: Vec<u32>,
+    diff_lines: Vec<u32>,
+}
+
+fn average<T: Copy + Into<f64>>(values: &[T]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().map(|&v| v.into()).sum::<f64>() / values.len() as f64)
+}
+
+/// Per-model acceptance across `tasks`, only those of `repo_path` when given.
+/// Sorted by acceptance rate, then by number of wins.
+pub fn build_model_leaderboard(
+    tasks: &[Task],
+    repo_path: Option<&str>,
+) -> Vec<ModelLeaderboardEntry> {
+    let mut tallies: BTreeMap<(String, String), Tally> = BTreeMap::new();
+    for task in tasks {
+        if let Some(repo) = repo_path {
+            if !same_repo_path(&task.source_repo_path, repo) {
+                continue;
+            }
+        }
+        for outcome in task_outcomes(task) {
+            let tally = tallies
+                .entry((outcome.provider_id, outcome.model_id))
+                .or_default();
+            tally.runs += 1;
+            if outcome.accepted {
+                tally.accepted += 1;
+            }
+            tally.scores.extend(outcome.score);
+            if let Some(diff) = outcome.diff {
+                tally
+                    .diff_lines
+                    .push(diff.insertions.saturating_add(diff.deletions));
+            }
+        }
+    }
+
+    let mut entries: Vec<ModelLeaderboardEntry> = tallies
+        .into_iter()
+        .map(|((provider_id, model_id), tally)| ModelLeaderboardEntry {
+            provider_id,
+            model_id,
+            runs: tally.runs,
+            accepted: tally.accepted,
+            acceptance_rate: tally.accepted as f64 / tally.runs as f64,
+            average_score: average(&tally.scores),
+            average_diff_lines: average(&tally.diff_lines),
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.acceptance_rate
+            .total_cmp(&a.acceptance_rate)
+            .then(b.accepted.cmp(&a.accepted))
+    });
+    entries
+}
+
+/// Model leaderboard over the stored tasks.
+pub fn get_model_leaderboard_impl(
+    state: &TaskManagerState,
+    repo_path: Option<&str>,
+) -> Result<Vec<ModelLeaderboardEntry>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    Ok(build_model_leaderboard(&store.tasks, repo_path))
+}
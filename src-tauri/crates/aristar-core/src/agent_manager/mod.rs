@@ -0,0 +1,60 @@
+//! Agent Manager module - AI agent orchestration.
+//!
+//! This module handles task and agent management including:
+//! - Task CRUD operations
+//! - Agent management (add, remove, update status)
+//! - OpenCode process management
+//! - Lock hierarchy and commands spanning the task store and OpenCode servers
+//! - Managed OpenCode binary installation
+//! - Review comments on agent diffs
+//! - Change summaries of agent diffs
+//! - Per-file agreement between agent diffs
+//! - Side-by-side HTML comparisons of agent diffs
+//! - Model leaderboard from accepted agents across tasks
+//! - Sandboxing for agent OpenCode servers
+//! - Post-run audits of writes outside agent worktrees
+//! - Tasks started from uncommitted changes
+//! - Context files shared by every agent of a task
+//! - A shared scratchpad for agents working together
+//! - Recorded OpenCode session transcripts of agents
+//! - Scheduled (recurring) tasks
+//! - Battery-aware throttling of OpenCode servers and schedules
+//! - Saved task presets and bulk task creation across repositories
+//! - Post-completion pipelines (verify, score, auto-accept, publish)
+//! - Auto-accept policies with a cancellable delay
+//! - Disk quotas pausing agents whose worktrees grow too large
+//! - Worktree creation for agents
+//! - Cleanup of refs created for finished tasks
+//! - Agent status files for shell prompts
+
+pub mod agent_operations;
+pub mod agreement;
+pub mod artifacts;
+pub mod auto_accept;
+pub mod change_summary;
+pub mod comparison;
+pub mod context_files;
+pub mod coordinator;
+pub mod cron;
+pub mod leaderboard;
+pub mod opencode;
+pub mod opencode_install;
+pub mod pipeline;
+pub mod power;
+pub mod presets;
+pub mod prompt_status;
+pub mod quota;
+pub mod review;
+pub mod sandbox;
+pub mod scheduler;
+pub mod scratchpad;
+pub mod snapshot;
+pub mod store;
+pub mod task_operations;
+pub mod transcript;
+pub mod types;
+pub mod write_audit;
+
+// Re-export commonly used types
+pub use opencode::OpenCodeManager;
+pub use store::TaskManagerState;
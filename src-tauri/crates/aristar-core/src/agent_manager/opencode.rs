@@ -3,8 +3,7 @@
 //! Manages OpenCode server instances for agent worktrees.
 
 use dirs::home_dir;
-use portpicker::pick_unused_port;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
@@ -14,6 +13,15 @@ use std::process::{Child, Command, Stdio};
 use std::sync::Mutex;
 
 use crate::core::get_aristar_worktrees_base;
+use crate::core::ports;
+
+use super::opencode_install::find_latest_managed_binary;
+use super::power;
+use super::sandbox::{
+    build_sandbox_launch, check_sandbox_enforceable, sandbox_exec_available,
+    sandbox_writable_paths, AllowlistProxy,
+};
+use super::types::SandboxConfig;
 
 // ============ PID File Management ============
 
@@ -122,6 +130,11 @@ pub(crate) fn cleanup_tracked_pids() -> u32 {
 }
 
 fn find_opencode_binary() -> Option<PathBuf> {
+    // Prefer binaries installed by the app (~/.aristar-worktrees/bin/)
+    if let Some(managed) = find_latest_managed_binary() {
+        return Some(managed);
+    }
+
     let standard_path = home_dir()?.join(".opencode").join("bin").join("opencode");
 
     if standard_path.exists() {
@@ -155,7 +168,7 @@ fn is_executable(path: &PathBuf) -> bool {
 
 fn get_opencode_command() -> Result<PathBuf, String> {
     find_opencode_binary()
-        .ok_or_else(|| "OpenCode binary not found. Expected at ~/.opencode/bin/opencode or in PATH. Please install OpenCode from https://opencode.ai".to_string())
+        .ok_or_else(|| "OpenCode binary not found. Install it from the app, or place it at ~/.opencode/bin/opencode or in PATH (https://opencode.ai)".to_string())
 }
 
 /// Represents a running OpenCode server instance.
@@ -164,9 +177,15 @@ pub struct OpenCodeInstance {
     pub port: u16,
     #[allow(dead_code)]
     pub working_dir: PathBuf,
+    /// Allowlist proxy of a sandboxed server; stopped when the instance is dropped
+    #[allow(dead_code)]
+    pub proxy: Option<AllowlistProxy>,
 }
 
 /// Manages multiple OpenCode server instances.
+///
+/// `instances` is the innermost lock (see `coordinator`): nothing else is
+/// locked while it is held.
 #[derive(Default)]
 pub struct OpenCodeManager {
     instances: Mutex<HashMap<PathBuf, OpenCodeInstance>>,
@@ -174,8 +193,11 @@ pub struct OpenCodeManager {
 
 impl OpenCodeManager {
     pub fn new() -> Self {
-        // Clean up any orphaned processes from previous crashes
-        Self::cleanup_orphaned_processes();
+        // Clean up any orphaned processes from previous crashes.
+        // Skipped in read-only mode: the PID file may belong to another machine's data dir.
+        if !crate::core::is_read_only() {
+            Self::cleanup_orphaned_processes();
+        }
 
         Self {
             instances: Mutex::new(HashMap::new()),
@@ -184,6 +206,23 @@ impl OpenCodeManager {
 
     /// Start an OpenCode server for a worktree.
     pub fn start(&self, worktree_path: PathBuf) -> Result<u16, String> {
+        self.start_with_options(worktree_path, None, None, None)
+    }
+
+    /// Start an OpenCode server for a worktree.
+    ///
+    /// `binary` overrides the default binary lookup. `preferred_port` is used when
+    /// it is free; otherwise the worktree's reserved port, or a new one, is taken
+    /// from the port registry (see `core::ports`). An enabled `sandbox` launches
+    /// the server sandboxed (see `sandbox.rs`). Fails when battery throttling
+    /// allows no more servers (see `power.rs`).
+    pub fn start_with_options(
+        &self,
+        worktree_path: PathBuf,
+        binary: Option<PathBuf>,
+        preferred_port: Option<u16>,
+        sandbox: Option<&SandboxConfig>,
+    ) -> Result<u16, String> {
         let mut instances = self.instances.lock().map_err(|e| e.to_string())?;
 
         if let Some(instance) = instances.get(&worktree_path) {
@@ -193,8 +232,20 @@ impl OpenCodeManager {
             );
             return Ok(instance.port);
         }
+        power::ensure_can_start_instance(instances.len())?;
+        if let Some(config) = sandbox.filter(|s| s.enabled) {
+            check_sandbox_enforceable(config, sandbox_exec_available())?;
+        }
 
-        let port = pick_unused_port().ok_or("No available port for OpenCode server")?;
+        let port = ports::reserve_port(
+            ports::OPENCODE_SERVICE,
+            &worktree_path.to_string_lossy(),
+            preferred_port,
+        )
+        .map_err(|e| format!("No available port for OpenCode server: {}", e))?;
+        if let Some(p) = preferred_port.filter(|&p| p != port) {
+            println!("[opencode] Preferred port {} is taken, using {}", p, port);
+        }
 
         println!(
             "[opencode] Starting server on port {} for worktree: {}",
@@ -202,20 +253,63 @@ impl OpenCodeManager {
             worktree_path.display()
         );
 
-        let opencode_path = get_opencode_command()?;
+        let opencode_path = match binary {
+            Some(path) => path,
+            None => get_opencode_command()?,
+        };
         println!(
             "[opencode] Using OpenCode binary: {}",
             opencode_path.display()
         );
 
-        let child = Command::new(&opencode_path)
-            .args([
-                "serve",
-                "--port",
-                &port.to_string(),
-                "--hostname",
-                "127.0.0.1",
-            ])
+        let server_args: Vec<String> = vec![
+            "serve".to_string(),
+            "--port".to_string(),
+            port.to_string(),
+            "--hostname".to_string(),
+            "127.0.0.1".to_string(),
+        ];
+
+        let mut proxy = None;
+        let mut command = match sandbox.filter(|s| s.enabled) {
+            Some(config) => {
+                let sandbox_temp = get_aristar_worktrees_base()
+                    .join("sandbox")
+                    .join(worktree_path.file_name().unwrap_or_default());
+                fs::create_dir_all(&sandbox_temp)
+                    .map_err(|e| format!("Failed to create sandbox temp directory: {}", e))?;
+
+                let writable =
+                    sandbox_writable_paths(&worktree_path, config, home_dir().as_deref(), &sandbox_temp);
+                let allowlist = AllowlistProxy::start(config.allowed_hosts.clone())?;
+                let launch = build_sandbox_launch(
+                    &opencode_path,
+                    &server_args,
+                    &writable,
+                    &sandbox_temp,
+                    allowlist.port,
+                    sandbox_exec_available(),
+                );
+                if !launch.enforced {
+                    println!("[opencode] sandbox-exec unavailable; the task allows an unenforced sandbox, using a restricted environment only");
+                }
+                proxy = Some(allowlist);
+
+                let mut command = Command::new(&launch.program);
+                command.args(&launch.args).envs(launch.env);
+                for var in &launch.env_remove {
+                    command.env_remove(var);
+                }
+                command
+            }
+            None => {
+                let mut command = Command::new(&opencode_path);
+                command.args(&server_args);
+                command
+            }
+        };
+
+        let child = command
             .current_dir(&worktree_path)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -238,6 +332,7 @@ impl OpenCodeManager {
                 process: child,
                 port,
                 working_dir: worktree_path,
+                proxy,
             },
         );
 
@@ -396,6 +491,19 @@ impl OpenCodeManager {
         Ok(instances.get(worktree_path).map(|i| i.port))
     }
 
+    /// Number of running OpenCode servers.
+    pub fn running_count(&self) -> usize {
+        self.instances.lock().map(|i| i.len()).unwrap_or(0)
+    }
+
+    /// Worktrees with a running OpenCode server.
+    pub fn running_paths(&self) -> HashSet<PathBuf> {
+        self.instances
+            .lock()
+            .map(|i| i.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
     /// Check if an OpenCode server is running for a worktree.
     pub fn is_running(&self, worktree_path: &PathBuf) -> bool {
         if let Ok(instances) = self.instances.lock() {
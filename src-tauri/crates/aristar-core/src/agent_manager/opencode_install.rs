@@ -0,0 +1,343 @@
+//! Managed OpenCode binaries.
+//!
+//! Downloads official OpenCode releases into `~/.aristar-worktrees/bin/` so the app
+//! does not depend on a user-installed binary. Each version lives in its own folder:
+//! `~/.aristar-worktrees/bin/opencode-{version}/opencode`.
+
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::get_aristar_worktrees_base;
+
+/// GitHub repository that publishes OpenCode releases.
+const OPENCODE_RELEASES_REPO: &str = "sst/opencode";
+
+/// Folder prefix for each installed version.
+const VERSION_DIR_PREFIX: &str = "opencode-";
+
+// ============ Paths ============
+
+/// Get the directory holding managed binaries: ~/.aristar-worktrees/bin/
+pub fn get_managed_bin_dir() -> PathBuf {
+    get_aristar_worktrees_base().join("bin")
+}
+
+/// Get the path of a managed OpenCode binary for a version (may not exist).
+pub fn get_managed_opencode_path(version: &str) -> PathBuf {
+    get_managed_bin_dir()
+        .join(format!("{}{}", VERSION_DIR_PREFIX, version))
+        .join("opencode")
+}
+
+// ============ Versions ============
+
+/// Normalize a version string: trims whitespace and a leading "v".
+pub fn normalize_version(version: &str) -> String {
+    let trimmed = version.trim();
+    trimmed.strip_prefix('v').unwrap_or(trimmed).to_string()
+}
+
+/// Validate a version string before using it in URLs and paths.
+pub fn validate_version(version: &str) -> Result<(), String> {
+    if version.is_empty() {
+        return Err("OpenCode version cannot be empty".to_string());
+    }
+    if !version
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        || version.starts_with('.')
+        || version.contains("..")
+    {
+        return Err(format!("Invalid OpenCode version: {}", version));
+    }
+    Ok(())
+}
+
+/// Compare two versions by their numeric components (e.g. "0.10.2" > "0.9.7").
+/// Non-numeric components compare as strings.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> { v.split(['.', '-']).map(|s| s.to_string()).collect() };
+    let (pa, pb) = (split(a), split(b));
+
+    for (x, y) in pa.iter().zip(pb.iter()) {
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(nx), Ok(ny)) => nx.cmp(&ny),
+            _ => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+
+    pa.len().cmp(&pb.len())
+}
+
+/// List installed managed OpenCode versions, newest first.
+pub fn list_installed_versions() -> Vec<String> {
+    list_installed_versions_in(&get_managed_bin_dir())
+}
+
+/// List installed versions in a specific bin directory, newest first.
+pub fn list_installed_versions_in(bin_dir: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(bin_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut versions: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().join("opencode").is_file())
+        .filter_map(|e| {
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix(VERSION_DIR_PREFIX))
+                .map(|v| v.to_string())
+        })
+        .collect();
+
+    versions.sort_by(|a, b| compare_versions(b, a));
+    versions
+}
+
+/// Get the newest installed managed binary, if any.
+pub fn find_latest_managed_binary() -> Option<PathBuf> {
+    list_installed_versions()
+        .first()
+        .map(|v| get_managed_opencode_path(v))
+}
+
+/// Resolve the binary to run for an optional pinned version.
+///
+/// Returns `Ok(None)` when no version is pinned (the default lookup applies),
+/// and an error when the pinned version is not installed.
+pub fn resolve_pinned_binary(version: Option<&str>) -> Result<Option<PathBuf>, String> {
+    let version = match version {
+        Some(v) if !v.trim().is_empty() => normalize_version(v),
+        _ => return Ok(None),
+    };
+    validate_version(&version)?;
+
+    let path = get_managed_opencode_path(&version);
+    if !path.is_file() {
+        return Err(format!(
+            "OpenCode {} is pinned for this task but not installed. Install it first.",
+            version
+        ));
+    }
+    Ok(Some(path))
+}
+
+// ============ Download & Install ============
+
+/// Get the release asset name for the current platform.
+pub fn get_platform_asset_name() -> Result<String, String> {
+    platform_asset_name(std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Get the release asset name for an OS/architecture pair.
+pub fn platform_asset_name(os: &str, arch: &str) -> Result<String, String> {
+    let os_name = match os {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        other => return Err(format!("Unsupported platform for OpenCode: {}", other)),
+    };
+    let arch_name = match arch {
+        "aarch64" => "arm64",
+        "x86_64" => "x64",
+        other => return Err(format!("Unsupported architecture for OpenCode: {}", other)),
+    };
+    Ok(format!("opencode-{}-{}.zip", os_name, arch_name))
+}
+
+/// Get the download URL for a release asset.
+pub fn get_release_asset_url(version: &str, asset: &str) -> String {
+    format!(
+        "https://github.com/{}/releases/download/v{}/{}",
+        OPENCODE_RELEASES_REPO, version, asset
+    )
+}
+
+/// Compute the SHA-256 of a file as lowercase hex.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Verify a file against an expected SHA-256 checksum.
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<(), String> {
+    let actual = sha256_file(path)?;
+    // Checksum files are "<hash>  <filename>"; only the hash matters
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            path.display(),
+            expected,
+            actual
+        ));
+    }
+    Ok(())
+}
+
+/// Run curl and return its stdout.
+fn curl(args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--retry", "2"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Resolve the latest released OpenCode version from GitHub.
+pub fn resolve_latest_version() -> Result<String, String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        OPENCODE_RELEASES_REPO
+    );
+    let body = curl(&["-H", "Accept: application/vnd.github+json", &url])
+        .map_err(|e| format!("Failed to query latest OpenCode release: {}", e))?;
+    let json: serde_json::Value = serde_json::from_slice(&body)
+        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+
+    json.get("tag_name")
+        .and_then(|t| t.as_str())
+        .map(normalize_version)
+        .ok_or_else(|| "Release info has no tag_name".to_string())
+}
+
+/// Download and install an OpenCode release into the managed bin directory.
+///
+/// `version` defaults to the latest release. The download is verified against
+/// `expected_sha256` when provided, otherwise against the `.sha256` file
+/// published next to the release asset. Returns the installed binary path.
+pub fn install_opencode(
+    version: Option<&str>,
+    expected_sha256: Option<&str>,
+) -> Result<PathBuf, String> {
+    let version = match version {
+        Some(v) if !v.trim().is_empty() => normalize_version(v),
+        _ => resolve_latest_version()?,
+    };
+    validate_version(&version)?;
+
+    let binary_path = get_managed_opencode_path(&version);
+    if binary_path.is_file() {
+        println!("[opencode] Version {} already installed", version);
+        return Ok(binary_path);
+    }
+
+    let asset = get_platform_asset_name()?;
+    let url = get_release_asset_url(&version, &asset);
+    let bin_dir = get_managed_bin_dir();
+    fs::create_dir_all(&bin_dir).map_err(|e| format!("Failed to create bin directory: {}", e))?;
+
+    let download_path = bin_dir.join(format!(".download-{}-{}", version, asset));
+    let download_str = download_path.to_string_lossy().to_string();
+
+    println!("[opencode] Downloading {}", url);
+    curl(&["-o", &download_str, &url])
+        .map_err(|e| format!("Failed to download OpenCode {}: {}", version, e))?;
+
+    let result = (|| {
+        let checksum = match expected_sha256 {
+            Some(sum) => sum.to_string(),
+            None => {
+                let sum = curl(&[&format!("{}.sha256", url)]).map_err(|_| {
+                    format!(
+                        "No checksum published for OpenCode {} ({}); pass sha256 explicitly",
+                        version, asset
+                    )
+                })?;
+                String::from_utf8_lossy(&sum).to_string()
+            }
+        };
+        verify_checksum(&download_path, &checksum)?;
+
+        let version_dir = binary_path
+            .parent()
+            .ok_or("Invalid managed binary path")?
+            .to_path_buf();
+        fs::create_dir_all(&version_dir)
+            .map_err(|e| format!("Failed to create version directory: {}", e))?;
+
+        let output = Command::new("unzip")
+            .args(["-o", "-q", &download_str, "-d"])
+            .arg(&version_dir)
+            .output()
+            .map_err(|e| format!("Failed to run unzip: {}", e))?;
+        if !output.status.success() {
+            let _ = fs::remove_dir_all(&version_dir);
+            return Err(format!(
+                "Failed to extract OpenCode: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        if !binary_path.is_file() {
+            let _ = fs::remove_dir_all(&version_dir);
+            return Err(format!(
+                "Archive {} did not contain an opencode binary",
+                asset
+            ));
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&binary_path, fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to make OpenCode executable: {}", e))?;
+        }
+
+        Ok(())
+    })();
+
+    let _ = fs::remove_file(&download_path);
+    result?;
+
+    println!(
+        "[opencode] Installed OpenCode {} at {}",
+        version,
+        binary_path.display()
+    );
+    Ok(binary_path)
+}
+
+/// Install OpenCode (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn install_opencode_async(
+    version: Option<String>,
+    expected_sha256: Option<String>,
+) -> Result<PathBuf, String> {
+    tokio::task::spawn_blocking(move || {
+        install_opencode(version.as_deref(), expected_sha256.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
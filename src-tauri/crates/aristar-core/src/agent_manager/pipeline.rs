@@ -0,0 +1,525 @@
+//! Post-completion pipelines.
+//!
+//! A task can declare a named pipeline: ordered steps that run once every
+//! agent has finished (see `pipeline_ready`), or on demand. Each step reuses an
+//! existing subsystem:
+//!
+//! - `verify` runs a shell command in each completed agent's worktree
+//! - `score` rates agents from the verification results and review comments
+//! - `auto-accept` accepts the top scorer via `accept_agent_impl`
+//! - `publish` publishes the accepted agent with the merge module
+//!
+//! Steps run in order; the first failing step stops the run and the remaining
+//! steps are skipped. Progress is stored in `Task.pipeline_run` and every
+//! finished step is recorded in the task timeline. A finished run is then
+//! checked against the task's auto-accept policy (see `auto_accept.rs`).
+
+use chrono::Utc;
+use std::process::Command;
+
+use crate::merge::operations::{publish_worktree, resolve_merge_strategy};
+use crate::worktrees::store::{AppState, RepositoryKey};
+
+use super::agent_operations::accept_agent_impl;
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_impl, push_timeline_entry};
+use super::types::{
+    AgentScore, AgentStatus, AgentVerification, Pipeline, PipelineRun, PipelineStatus,
+    PipelineStep, PipelineStepKind, PipelineStepRun, Task, TimelineEntry, TimelineEntryKind,
+};
+
+/// Number of output lines kept per verification.
+const MAX_OUTPUT_LINES: usize = 20;
+
+/// Points deducted from a score per unresolved review comment.
+const OPEN_COMMENT_PENALTY: u32 = 10;
+
+/// Check a pipeline before it is attached to a task.
+pub fn validate_pipeline(pipeline: &Pipeline) -> Result<(), String> {
+    if pipeline.name.trim().is_empty() {
+        return Err("Pipeline name cannot be empty".to_string());
+    }
+    if pipeline.steps.is_empty() {
+        return Err("Pipeline needs at least one step".to_string());
+    }
+
+    let mut scored = false;
+    for (i, step) in pipeline.steps.iter().enumerate() {
+        match step.kind {
+            PipelineStepKind::Verify => {
+                if step.command.as_deref().is_none_or(|c| c.trim().is_empty()) {
+                    return Err(format!("Step {}: verify needs a command", i + 1));
+                }
+            }
+            PipelineStepKind::Score => scored = true,
+            PipelineStepKind::AutoAccept => {
+                if !scored {
+                    return Err(format!(
+                        "Step {}: auto-accept needs a score step before it",
+                        i + 1
+                    ));
+                }
+                if step.threshold.is_some_and(|t| t > 100) {
+                    return Err(format!("Step {}: threshold must be 0-100", i + 1));
+                }
+            }
+            PipelineStepKind::Publish => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether a task's pipeline should start on its own: it has one that has not
+/// run yet, and all agents have finished with at least one completed.
+pub fn pipeline_ready(task: &Task) -> bool {
+    task.pipeline.is_some()
+        && task.pipeline_run.is_none()
+        && task
+            .agents
+            .iter()
+            .all(|a| matches!(a.status, AgentStatus::Completed | AgentStatus::Failed))
+        && task
+            .agents
+            .iter()
+            .any(|a| a.status == AgentStatus::Completed)
+}
+
+/// A new run with every step pending.
+pub fn new_pipeline_run(pipeline: &Pipeline, now: i64) -> PipelineRun {
+    PipelineRun {
+        pipeline: pipeline.name.clone(),
+        status: PipelineStatus::Running,
+        started_at: now,
+        finished_at: None,
+        steps: pipeline
+            .steps
+            .iter()
+            .map(|step| PipelineStepRun {
+                kind: step.kind,
+                status: PipelineStatus::Pending,
+                started_at: None,
+                finished_at: None,
+                message: None,
+            })
+            .collect(),
+        verifications: Vec::new(),
+        scores: Vec::new(),
+    }
+}
+
+/// Run a verification command in an agent's worktree.
+pub fn run_verification(agent_id: &str, worktree_path: &str, command: &str) -> AgentVerification {
+    let (passed, exit_code, output) = match Command::new("bash")
+        .args(["-c", command])
+        .current_dir(worktree_path)
+        .output()
+    {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            (output.status.success(), output.status.code(), text)
+        }
+        Err(e) => (false, None, format!("Failed to run command: {}", e)),
+    };
+
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join("\n");
+
+    AgentVerification {
+        agent_id: agent_id.to_string(),
+        command: command.to_string(),
+        passed,
+        exit_code,
+        output: tail,
+    }
+}
+
+/// Score each completed agent: the share of passed checks (100 when there
+/// were none), minus 10 points per unresolved review comment.
+pub fn score_agents(task: &Task, verifications: &[AgentVerification]) -> Vec<AgentScore> {
+    task.agents
+        .iter()
+        .filter(|a| a.status == AgentStatus::Completed)
+        .map(|agent| {
+            let checks: Vec<&AgentVerification> = verifications
+                .iter()
+                .filter(|v| v.agent_id == agent.id)
+                .collect();
+            let checks_passed = checks.iter().filter(|v| v.passed).count();
+            let open_comments = agent.review_comments.iter().filter(|c| !c.resolved).count();
+
+            let base = if checks.is_empty() {
+                100
+            } else {
+                (checks_passed * 100 / checks.len()) as u32
+            };
+            AgentScore {
+                agent_id: agent.id.clone(),
+                score: base.saturating_sub(open_comments as u32 * OPEN_COMMENT_PENALTY),
+                checks_passed,
+                checks_total: checks.len(),
+                open_comments,
+            }
+        })
+        .collect()
+}
+
+/// Highest-scoring agent; ties go to the earlier agent.
+pub fn top_score(scores: &[AgentScore]) -> Option<&AgentScore> {
+    scores
+        .iter()
+        .fold(None, |best: Option<&AgentScore>, s| match best {
+            Some(b) if b.score >= s.score => Some(b),
+            _ => Some(s),
+        })
+}
+
+fn step_label(kind: PipelineStepKind) -> &'static str {
+    match kind {
+        PipelineStepKind::Verify => "verify",
+        PipelineStepKind::Score => "score",
+        PipelineStepKind::AutoAccept => "auto-accept",
+        PipelineStepKind::Publish => "publish",
+    }
+}
+
+/// Timeline entry describing a finished step.
+pub fn timeline_entry_for_step(
+    pipeline: &str,
+    step: &PipelineStepRun,
+    agent_id: Option<String>,
+    details: Vec<String>,
+    now: i64,
+) -> TimelineEntry {
+    let kind = if step.status == PipelineStatus::Failed {
+        TimelineEntryKind::PipelineStepFailed
+    } else {
+        TimelineEntryKind::PipelineStep
+    };
+    let status = match step.status {
+        PipelineStatus::Passed => "passed",
+        PipelineStatus::Failed => "failed",
+        PipelineStatus::Skipped => "skipped",
+        PipelineStatus::Pending | PipelineStatus::Running => "running",
+    };
+    let mut message = format!(
+        "Pipeline '{}': {} {}",
+        pipeline,
+        step_label(step.kind),
+        status
+    );
+    if let Some(text) = &step.message {
+        message.push_str(&format!(" ({})", text));
+    }
+
+    TimelineEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: now,
+        agent_id,
+        kind,
+        message,
+        details,
+    }
+}
+
+/// Store the run on the task, optionally with a timeline entry, and save.
+fn record_run(
+    state: &TaskManagerState,
+    task_id: &str,
+    run: &PipelineRun,
+    entry: Option<TimelineEntry>,
+) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        task.pipeline_run = Some(run.clone());
+        if let Some(entry) = entry {
+            push_timeline_entry(task, entry);
+        }
+        task.updated_at = Utc::now().timestamp_millis();
+    }
+    state.save()
+}
+
+/// Outcome of a step: status, message, agent and timeline details.
+struct StepOutcome {
+    status: PipelineStatus,
+    message: String,
+    agent_id: Option<String>,
+    details: Vec<String>,
+}
+
+impl StepOutcome {
+    fn new(status: PipelineStatus, message: String) -> Self {
+        Self {
+            status,
+            message,
+            agent_id: None,
+            details: Vec::new(),
+        }
+    }
+}
+
+fn run_step(
+    state: &TaskManagerState,
+    app_state: &AppState,
+    task_id: &str,
+    step: &PipelineStep,
+    run: &mut PipelineRun,
+) -> Result<StepOutcome, String> {
+    let task = get_task_impl(state, task_id)?;
+
+    match step.kind {
+        PipelineStepKind::Verify => {
+            let command = step.command.as_deref().unwrap_or_default();
+            let agents: Vec<_> = task
+                .agents
+                .iter()
+                .filter(|a| a.status == AgentStatus::Completed)
+                .collect();
+            if agents.is_empty() {
+                return Err("no completed agents".to_string());
+            }
+
+            let results: Vec<AgentVerification> = agents
+                .iter()
+                .map(|a| run_verification(&a.id, &a.worktree_path, command))
+                .collect();
+            let passed = results.iter().filter(|r| r.passed).count();
+            let details = results
+                .iter()
+                .map(|r| {
+                    format!(
+                        "{}: {}",
+                        r.agent_id,
+                        match (r.passed, r.exit_code) {
+                            (true, _) => "passed".to_string(),
+                            (false, Some(code)) => format!("failed (exit {})", code),
+                            (false, None) => "failed".to_string(),
+                        }
+                    )
+                })
+                .collect();
+            let total = results.len();
+            run.verifications.extend(results);
+
+            Ok(StepOutcome {
+                details,
+                ..StepOutcome::new(
+                    if passed > 0 {
+                        PipelineStatus::Passed
+                    } else {
+                        PipelineStatus::Failed
+                    },
+                    format!("{} of {} agents passed `{}`", passed, total, command),
+                )
+            })
+        }
+        PipelineStepKind::Score => {
+            run.scores = score_agents(&task, &run.verifications);
+            let top = top_score(&run.scores).ok_or("no completed agents to score")?;
+            Ok(StepOutcome {
+                details: run
+                    .scores
+                    .iter()
+                    .map(|s| format!("{}: {}", s.agent_id, s.score))
+                    .collect(),
+                ..StepOutcome::new(
+                    PipelineStatus::Passed,
+                    format!("top score {} ({})", top.score, top.agent_id),
+                )
+            })
+        }
+        PipelineStepKind::AutoAccept => {
+            if let Some(accepted) = task.agents.iter().find(|a| a.accepted) {
+                return Ok(StepOutcome::new(
+                    PipelineStatus::Skipped,
+                    format!("{} is already accepted", accepted.id),
+                ));
+            }
+
+            let threshold = step.threshold.unwrap_or(100);
+            let top = top_score(&run.scores).ok_or("no scores; add a score step first")?;
+            if top.score < threshold {
+                return Ok(StepOutcome::new(
+                    PipelineStatus::Failed,
+                    format!(
+                        "top score {} ({}) is below {}",
+                        top.score, top.agent_id, threshold
+                    ),
+                ));
+            }
+
+            accept_agent_impl(state, task_id.to_string(), top.agent_id.clone())?;
+            Ok(StepOutcome {
+                agent_id: Some(top.agent_id.clone()),
+                ..StepOutcome::new(
+                    PipelineStatus::Passed,
+                    format!("accepted {} with score {}", top.agent_id, top.score),
+                )
+            })
+        }
+        PipelineStepKind::Publish => {
+            let agent = task
+                .agents
+                .iter()
+                .find(|a| a.accepted)
+                .ok_or("no accepted agent to publish")?;
+            let target_branch = task
+                .source_branch
+                .clone()
+                .ok_or("task was created from a commit and has no branch to publish to")?;
+
+            let repo_default = app_state
+                .get_repository(RepositoryKey::Path(&task.source_repo_path))?
+                .and_then(|r| r.default_merge_strategy);
+            let strategy = resolve_merge_strategy(step.strategy, repo_default);
+            let source_label = format!("{} ({})", agent.id, agent.model_id);
+
+            let result = publish_worktree(
+                &agent.worktree_path,
+                &task.source_repo_path,
+                &target_branch,
+                strategy,
+                &task.name,
+                Some(&source_label),
+                None,
+            )?;
+            Ok(StepOutcome {
+                agent_id: Some(agent.id.clone()),
+                ..StepOutcome::new(
+                    PipelineStatus::Passed,
+                    format!(
+                        "published {} commit(s) from {} to {}",
+                        result.commits_published, agent.id, target_branch
+                    ),
+                )
+            })
+        }
+    }
+}
+
+/// Run a task's pipeline from the first step. A finished previous run is
+/// replaced when `restart` is set; otherwise the pipeline only runs once.
+pub fn run_task_pipeline_impl(
+    state: &TaskManagerState,
+    app_state: &AppState,
+    task_id: &str,
+    restart: bool,
+) -> Result<PipelineRun, String> {
+    let (pipeline, mut run) = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let pipeline = task.pipeline.clone().ok_or("Task has no pipeline")?;
+        if task
+            .pipeline_run
+            .as_ref()
+            .is_some_and(|r| r.status == PipelineStatus::Running)
+        {
+            return Err("Pipeline is already running".to_string());
+        }
+        if !restart && task.pipeline_run.is_some() {
+            return Err("Pipeline has already run".to_string());
+        }
+
+        // Claimed under the lock so concurrent triggers run it only once
+        let run = new_pipeline_run(&pipeline, Utc::now().timestamp_millis());
+        task.pipeline_run = Some(run.clone());
+        (pipeline, run)
+    };
+    state.save()?;
+    println!(
+        "[pipeline] Running '{}' for task {}",
+        pipeline.name, task_id
+    );
+
+    let mut failed = false;
+    for (i, step) in pipeline.steps.iter().enumerate() {
+        if failed {
+            run.steps[i].status = PipelineStatus::Skipped;
+            continue;
+        }
+
+        run.steps[i].status = PipelineStatus::Running;
+        run.steps[i].started_at = Some(Utc::now().timestamp_millis());
+        record_run(state, task_id, &run, None)?;
+
+        let outcome = run_step(state, app_state, task_id, step, &mut run)
+            .unwrap_or_else(|e| StepOutcome::new(PipelineStatus::Failed, e));
+        failed = outcome.status == PipelineStatus::Failed;
+
+        let now = Utc::now().timestamp_millis();
+        let step_run = &mut run.steps[i];
+        step_run.status = outcome.status;
+        step_run.finished_at = Some(now);
+        step_run.message = Some(outcome.message);
+        let entry = timeline_entry_for_step(
+            &pipeline.name,
+            step_run,
+            outcome.agent_id,
+            outcome.details,
+            now,
+        );
+        record_run(state, task_id, &run, Some(entry))?;
+    }
+
+    run.status = if failed {
+        PipelineStatus::Failed
+    } else {
+        PipelineStatus::Passed
+    };
+    run.finished_at = Some(Utc::now().timestamp_millis());
+    record_run(state, task_id, &run, None)?;
+
+    println!(
+        "[pipeline] '{}' for task {} {}",
+        pipeline.name,
+        task_id,
+        if failed { "failed" } else { "passed" }
+    );
+    Ok(run)
+}
+
+/// Attach a pipeline to a task, or remove it with `None`.
+pub fn set_task_pipeline_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    pipeline: Option<Pipeline>,
+) -> Result<Task, String> {
+    if let Some(pipeline) = &pipeline {
+        validate_pipeline(pipeline)?;
+    }
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        if task
+            .pipeline_run
+            .as_ref()
+            .is_some_and(|r| r.status == PipelineStatus::Running)
+        {
+            return Err("Cannot change the pipeline while it is running".to_string());
+        }
+        task.pipeline = pipeline;
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
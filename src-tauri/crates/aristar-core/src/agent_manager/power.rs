@@ -0,0 +1,157 @@
+//! Battery-aware throttling of agents.
+//!
+//! The scheduler loop checks the power state on every tick. On battery below
+//! `settings.battery_throttle_percent` (default 20%), the app is throttled: at
+//! most `settings.battery_max_opencode_instances` (default 1) OpenCode servers
+//! may run, further starts are refused, and due schedules wait. Servers that are
+//! already running keep running. Back on AC power (or above the threshold) the
+//! limit is lifted and the waiting schedules run on the next tick. Every change
+//! emits `power-throttled`.
+//!
+//! The power state comes from `pmset -g batt` on macOS and
+//! `/sys/class/power_supply` on Linux; elsewhere the app counts as on AC power.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub use crate::core::events::POWER_THROTTLED_EVENT;
+
+/// Battery percentage below which agents are throttled, unless configured.
+pub const DEFAULT_BATTERY_THROTTLE_PERCENT: u8 = 20;
+
+/// OpenCode servers allowed while throttled, unless configured.
+pub const DEFAULT_BATTERY_MAX_INSTANCES: usize = 1;
+
+/// Power source and battery level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerState {
+    pub on_battery: bool,
+    /// `None` on machines without a battery
+    pub battery_percent: Option<u8>,
+}
+
+impl PowerState {
+    /// Plugged in, or no battery found.
+    pub const AC: PowerState = PowerState {
+        on_battery: false,
+        battery_percent: None,
+    };
+}
+
+/// Payload of `power-throttled`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerThrottledEvent {
+    /// Whether throttling started (true) or ended (false)
+    pub throttled: bool,
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    /// OpenCode servers allowed while throttled
+    pub max_instances: Option<usize>,
+}
+
+/// Whether agents should be throttled. A `threshold_percent` of 0 disables
+/// throttling.
+pub fn should_throttle(state: PowerState, threshold_percent: u8) -> bool {
+    state.on_battery
+        && threshold_percent > 0
+        && state
+            .battery_percent
+            .is_some_and(|percent| percent < threshold_percent)
+}
+
+// ============ Throttle State ============
+
+/// OpenCode servers allowed while throttled; 0 when not throttled.
+static THROTTLE_LIMIT: AtomicUsize = AtomicUsize::new(0);
+
+/// Record whether agents are throttled. Returns true when this changed.
+pub fn set_throttle_limit(limit: Option<usize>) -> bool {
+    // At least one server stays allowed, so 0 can mean "not throttled"
+    let value = limit.map(|l| l.max(1)).unwrap_or(0);
+    THROTTLE_LIMIT.swap(value, Ordering::SeqCst) != value
+}
+
+/// OpenCode servers allowed right now, or `None` when not throttled.
+pub fn throttle_limit() -> Option<usize> {
+    match THROTTLE_LIMIT.load(Ordering::SeqCst) {
+        0 => None,
+        limit => Some(limit),
+    }
+}
+
+/// Refuse to start another OpenCode server while throttled and `running`
+/// servers already reach the limit.
+pub fn ensure_can_start_instance(running: usize) -> Result<(), String> {
+    match throttle_limit() {
+        Some(limit) if running >= limit => Err(format!(
+            "On low battery at most {} OpenCode server(s) run at once; plug in or stop an agent",
+            limit
+        )),
+        _ => Ok(()),
+    }
+}
+
+// ============ Power State Detection ============
+
+/// Parse `pmset -g batt` output.
+///
+/// ```text
+/// Now drawing from 'Battery Power'
+///  -InternalBattery-0 (id=4653155) 18%; discharging; 1:02 remaining present: true
+/// ```
+pub fn parse_pmset_batt(output: &str) -> PowerState {
+    let on_battery = output
+        .lines()
+        .next()
+        .is_some_and(|line| line.contains("'Battery Power'"));
+    let battery_percent = output
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|word| word.strip_suffix('%')?.parse().ok());
+
+    PowerState {
+        on_battery,
+        battery_percent,
+    }
+}
+
+/// Read the first battery under a `/sys/class/power_supply`-style folder.
+pub fn read_power_supply(dir: &Path) -> PowerState {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return PowerState::AC;
+    };
+    let read = |path: &Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut supplies: Vec<_> = entries.flatten().map(|e| e.path()).collect();
+    supplies.sort();
+    supplies
+        .iter()
+        .find(|path| read(path, "type") == "Battery")
+        .map(|battery| PowerState {
+            on_battery: read(battery, "status") == "Discharging",
+            battery_percent: read(battery, "capacity").parse().ok(),
+        })
+        .unwrap_or(PowerState::AC)
+}
+
+/// Current power state of this machine.
+pub fn get_power_state() -> PowerState {
+    if cfg!(target_os = "macos") {
+        return Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|o| parse_pmset_batt(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or(PowerState::AC);
+    }
+    if cfg!(target_os = "linux") {
+        return read_power_supply(Path::new("/sys/class/power_supply"));
+    }
+    PowerState::AC
+}
@@ -0,0 +1,175 @@
+//! Saved task presets and bulk task creation.
+//!
+//! Presets are stored with the tasks in `tasks.json`. `create_task_batch`
+//! instantiates one preset in several repositories at once, one task per
+//! repository, and reports success or failure per repository instead of
+//! stopping at the first error.
+
+use chrono::Utc;
+
+use crate::core::paths_equal;
+use crate::worktrees::operations::{get_repository_name, run_git_command};
+use crate::worktrees::store::AppState;
+
+use super::scheduler::validate_preset;
+use super::store::TaskManagerState;
+use super::task_operations::create_task_impl;
+use super::types::{BatchTaskResult, BatchTaskSummary, SavedTaskPreset, TaskMode, TaskPreset};
+
+/// Build a saved preset.
+pub fn new_task_preset(preset: TaskPreset, now: i64) -> Result<SavedTaskPreset, String> {
+    validate_preset(&preset)?;
+    Ok(SavedTaskPreset {
+        id: uuid::Uuid::new_v4().to_string(),
+        preset,
+        created_at: now,
+    })
+}
+
+/// Name of a batch task, e.g. "Bump CI image (billing-service)".
+pub fn batch_task_name(preset_name: &str, repo_path: &str) -> String {
+    format!("{} ({})", preset_name, get_repository_name(repo_path))
+}
+
+/// Trimmed, non-empty repository paths with duplicates removed, in order.
+pub fn unique_repo_paths(repos: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for repo in repos {
+        let repo = repo.trim().to_string();
+        if !repo.is_empty() && !unique.iter().any(|r| paths_equal(r, &repo)) {
+            unique.push(repo);
+        }
+    }
+    unique
+}
+
+/// Save a preset.
+pub fn save_task_preset_impl(
+    state: &TaskManagerState,
+    preset: TaskPreset,
+) -> Result<SavedTaskPreset, String> {
+    let saved = new_task_preset(preset, Utc::now().timestamp_millis())?;
+
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        store.presets.push(saved.clone());
+    }
+    state.save()?;
+    Ok(saved)
+}
+
+/// List all saved presets.
+pub fn list_task_presets_impl(state: &TaskManagerState) -> Result<Vec<SavedTaskPreset>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    Ok(store.presets.clone())
+}
+
+/// Delete a saved preset. Tasks created from it are kept.
+pub fn delete_task_preset_impl(state: &TaskManagerState, preset_id: &str) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let before = store.presets.len();
+        store.presets.retain(|p| p.id != preset_id);
+        if store.presets.len() == before {
+            return Err(format!("Preset not found: {}", preset_id));
+        }
+    }
+    state.save()
+}
+
+/// Source branch of a batch task in `repo_path`: the preset's branch if the
+/// repository has it, otherwise the repository's default base branch (so a
+/// preset made on `main` also works in repositories that use `master`).
+fn batch_source_branch(
+    app_state: &AppState,
+    preset: &TaskPreset,
+    repo_path: &str,
+) -> Result<Option<String>, String> {
+    if preset.source_type == "commit" {
+        return Ok(preset.source_branch.clone());
+    }
+    match &preset.source_branch {
+        Some(branch)
+            if run_git_command(&["rev-parse", "--verify", "--quiet", branch], repo_path)
+                .is_ok() =>
+        {
+            Ok(Some(branch.clone()))
+        }
+        _ => app_state.resolve_base_branch(repo_path),
+    }
+}
+
+/// Create one task from a saved preset in each repository.
+pub fn create_task_batch_impl(
+    state: &TaskManagerState,
+    app_state: &AppState,
+    preset_id: &str,
+    repos: Vec<String>,
+) -> Result<BatchTaskSummary, String> {
+    let preset = {
+        let store = state.store.lock().map_err(|e| e.to_string())?;
+        store
+            .presets
+            .iter()
+            .find(|p| p.id == preset_id)
+            .map(|p| p.preset.clone())
+            .ok_or_else(|| format!("Preset not found: {}", preset_id))?
+    };
+
+    let repos = unique_repo_paths(repos);
+    if repos.is_empty() {
+        return Err("At least one repository must be selected".to_string());
+    }
+
+    let mut results = Vec::new();
+    for repo_path in repos {
+        let result = batch_source_branch(app_state, &preset, &repo_path).and_then(|branch| {
+            create_task_impl(
+                state,
+                batch_task_name(&preset.name, &repo_path),
+                preset.source_type.clone(),
+                branch,
+                preset.source_commit.clone(),
+                repo_path.clone(),
+                preset.agent_type.clone(),
+                preset.models.clone(),
+                preset.opencode_version.clone(),
+                false,
+                preset.sparse_paths.clone(),
+                TaskMode::Parallel,
+            )
+        });
+
+        results.push(match result {
+            Ok(task) => BatchTaskResult {
+                repo_path,
+                task_id: Some(task.id),
+                error: None,
+            },
+            Err(e) => {
+                eprintln!("[presets] Batch task in {} failed: {}", repo_path, e);
+                BatchTaskResult {
+                    repo_path,
+                    task_id: None,
+                    error: Some(e),
+                }
+            }
+        });
+    }
+
+    let succeeded = results.iter().filter(|r| r.task_id.is_some()).count();
+    println!(
+        "[presets] Created {} of {} tasks from preset '{}'",
+        succeeded,
+        results.len(),
+        preset.name
+    );
+
+    Ok(BatchTaskSummary {
+        preset_id: preset_id.to_string(),
+        prompt: preset.prompt,
+        failed: results.len() - succeeded,
+        succeeded,
+        results,
+    })
+}
@@ -0,0 +1,134 @@
+//! Agent status for shell prompts.
+//!
+//! Every agent worktree gets a `.aristar/status` file naming its task, agent,
+//! model and status as `key=value` lines, rewritten whenever the task store is
+//! saved. Shell prompts (starship, powerlevel10k) can read it directly, or run
+//! `aristar-worktrees status --porcelain`, which prints the file of the agent
+//! worktree containing the current directory (exit code 1 outside one).
+//! Without `--porcelain` it prints a single line for humans.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::worktrees::operations::ensure_aristar_excluded;
+
+use super::types::{Task, TaskAgent, TaskStoreData};
+
+/// Status file inside each agent worktree.
+pub const WORKTREE_STATUS_FILE: &str = ".aristar/status";
+
+/// Replace line breaks, which would end a value early.
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Contents of an agent's status file.
+pub fn format_status_file(task: &Task, agent: &TaskAgent) -> String {
+    let status = serde_json::to_value(&agent.status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let fields = [
+        ("task_id", task.id.as_str()),
+        ("task", task.name.as_str()),
+        ("agent", agent.id.as_str()),
+        ("model", agent.model_id.as_str()),
+        ("provider", agent.provider_id.as_str()),
+        ("status", status.as_str()),
+        ("accepted", if agent.accepted { "true" } else { "false" }),
+    ];
+    fields
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, single_line(value)))
+        .collect()
+}
+
+/// `(key, value)` pairs of a status file, in order.
+pub fn parse_status_file(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Write an agent's status file, unless it's unchanged or the worktree is gone.
+pub fn write_agent_status(task: &Task, agent: &TaskAgent) -> Result<(), String> {
+    let worktree = Path::new(&agent.worktree_path);
+    if !worktree.exists() {
+        return Ok(());
+    }
+    let path = worktree.join(WORKTREE_STATUS_FILE);
+    let contents = format_status_file(task, agent);
+    match fs::read_to_string(&path) {
+        Ok(existing) if existing == contents => return Ok(()),
+        Ok(_) => {}
+        Err(_) => {
+            ensure_aristar_excluded(worktree)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+        }
+    }
+    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Refresh the status files of every agent. Failures are logged.
+pub fn sync_status_files(data: &TaskStoreData) {
+    for task in &data.tasks {
+        for agent in &task.agents {
+            if let Err(e) = write_agent_status(task, agent) {
+                eprintln!("[prompt-status] {}", e);
+            }
+        }
+    }
+}
+
+/// The status file of the agent worktree containing `dir`, if any.
+pub fn find_status_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(WORKTREE_STATUS_FILE))
+        .find(|p| p.is_file())
+}
+
+/// Run `status [--porcelain]` when `args` ask for it, returning the exit code.
+/// `None` means the app should start normally.
+pub fn run_status_command(args: &[String]) -> Option<i32> {
+    if args.get(1).map(String::as_str) != Some("status") {
+        return None;
+    }
+    let porcelain = args.iter().skip(2).any(|a| a == "--porcelain");
+
+    let contents = std::env::current_dir()
+        .ok()
+        .and_then(|dir| find_status_file(&dir))
+        .and_then(|path| fs::read_to_string(path).ok());
+    let Some(contents) = contents else {
+        if !porcelain {
+            eprintln!("Not inside an agent worktree");
+        }
+        return Some(1);
+    };
+
+    if porcelain {
+        print!("{}", contents);
+    } else {
+        let fields = parse_status_file(&contents);
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or("")
+        };
+        println!(
+            "{} / {} ({}): {}",
+            get("task"),
+            get("agent"),
+            get("model"),
+            get("status")
+        );
+    }
+    Some(0)
+}
@@ -0,0 +1,224 @@
+//! Disk quotas for agent worktrees.
+//!
+//! A task's `disk_quota_mb` caps how large each agent worktree may grow. A
+//! background loop measures the worktree of every agent whose OpenCode server
+//! is running, every `QUOTA_CHECK_INTERVAL`, with the same `du` helper the
+//! global status uses. An agent over the quota has its server stopped and is
+//! set to `Paused`; `quota_exceeded` on the agent flags its worktree for review,
+//! the task timeline records it and `agent-quota-exceeded` is emitted. The flag
+//! stays until `clear_agent_quota_flag`; an agent started again while still
+//! over the quota is paused again on the next check.
+
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::workspace::status::get_directory_size_bytes;
+
+use super::opencode::OpenCodeManager;
+use super::store::TaskManagerState;
+use super::task_operations::push_timeline_entry;
+use super::types::{
+    AgentQuotaExceededEvent, AgentStatus, DiskQuotaExceeded, Task, TimelineEntry, TimelineEntryKind,
+};
+
+pub use crate::core::events::AGENT_QUOTA_EVENT;
+
+/// How often running agents are measured.
+pub const QUOTA_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Largest quota accepted (1 TB).
+pub const MAX_DISK_QUOTA_MB: u64 = 1024 * 1024;
+
+/// An agent worktree to measure against its task's quota.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaCandidate {
+    pub task_id: String,
+    pub agent_id: String,
+    pub worktree_path: String,
+    pub limit_bytes: u64,
+}
+
+/// Check a quota before it is attached to a task.
+pub fn validate_disk_quota(quota_mb: u64) -> Result<(), String> {
+    if quota_mb == 0 || quota_mb > MAX_DISK_QUOTA_MB {
+        return Err(format!(
+            "Disk quota must be between 1 and {} MB",
+            MAX_DISK_QUOTA_MB
+        ));
+    }
+    Ok(())
+}
+
+/// Set a task's disk quota, or remove it with `None`.
+pub fn set_task_disk_quota_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    quota_mb: Option<u64>,
+) -> Result<Task, String> {
+    if let Some(quota_mb) = quota_mb {
+        validate_disk_quota(quota_mb)?;
+    }
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        task.disk_quota_mb = quota_mb;
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
+
+/// Agents of tasks with a quota whose server `is_running`, with their limits.
+pub fn quota_candidates(tasks: &[Task], is_running: impl Fn(&Path) -> bool) -> Vec<QuotaCandidate> {
+    tasks
+        .iter()
+        .filter_map(|task| task.disk_quota_mb.map(|mb| (task, mb * 1024 * 1024)))
+        .flat_map(|(task, limit_bytes)| {
+            task.agents
+                .iter()
+                .filter(|agent| is_running(Path::new(&agent.worktree_path)))
+                .map(move |agent| QuotaCandidate {
+                    task_id: task.id.clone(),
+                    agent_id: agent.id.clone(),
+                    worktree_path: agent.worktree_path.clone(),
+                    limit_bytes,
+                })
+        })
+        .collect()
+}
+
+/// Pause an agent of `task` for outgrowing its quota: set it to `Paused`, flag
+/// it for review and record it in the timeline. Returns false when the agent
+/// does not exist.
+pub fn flag_quota_exceeded(task: &mut Task, agent_id: &str, exceeded: DiskQuotaExceeded) -> bool {
+    let Some(agent) = task.agents.iter_mut().find(|a| a.id == agent_id) else {
+        return false;
+    };
+    agent.status = AgentStatus::Paused;
+    agent.quota_exceeded = Some(exceeded.clone());
+
+    let now = exceeded.detected_at;
+    push_timeline_entry(
+        task,
+        TimelineEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: now,
+            agent_id: Some(agent_id.to_string()),
+            kind: TimelineEntryKind::DiskQuotaExceeded,
+            message: format!(
+                "{} paused: worktree is {} MB, over the {} MB quota",
+                agent_id,
+                exceeded.size_bytes / (1024 * 1024),
+                exceeded.limit_bytes / (1024 * 1024)
+            ),
+            details: Vec::new(),
+        },
+    );
+    task.updated_at = now;
+    true
+}
+
+/// Measure every running agent of a task with a quota and pause those over it.
+/// Returns the agents that were paused.
+pub fn enforce_disk_quotas(
+    state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    now: i64,
+) -> Result<Vec<AgentQuotaExceededEvent>, String> {
+    // Copied out first: the task store lock is never held with the OpenCode one
+    let running = opencode.running_paths();
+    let candidates = {
+        let store = state.store.lock().map_err(|e| e.to_string())?;
+        quota_candidates(&store.tasks, |path| running.contains(path))
+    };
+
+    // Measure without holding the lock; `du` can take a while
+    let mut events = Vec::new();
+    for candidate in candidates {
+        let Some(size_bytes) = get_directory_size_bytes(Path::new(&candidate.worktree_path)) else {
+            continue;
+        };
+        if size_bytes <= candidate.limit_bytes {
+            continue;
+        }
+
+        let path = PathBuf::from(&candidate.worktree_path);
+        if let Err(e) = opencode.stop(&path) {
+            eprintln!(
+                "[quota] Failed to stop OpenCode for {}: {}",
+                candidate.agent_id, e
+            );
+        }
+
+        let exceeded = DiskQuotaExceeded {
+            size_bytes,
+            limit_bytes: candidate.limit_bytes,
+            detected_at: now,
+        };
+        let flagged = {
+            let mut store = state.store.lock().map_err(|e| e.to_string())?;
+            store
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == candidate.task_id)
+                .is_some_and(|task| {
+                    flag_quota_exceeded(task, &candidate.agent_id, exceeded.clone())
+                })
+        };
+        if !flagged {
+            continue;
+        }
+        state.save()?;
+
+        println!(
+            "[quota] Paused {} in task {}: {} bytes over the {} byte quota",
+            candidate.agent_id, candidate.task_id, size_bytes, candidate.limit_bytes
+        );
+        events.push(AgentQuotaExceededEvent {
+            task_id: candidate.task_id,
+            agent_id: candidate.agent_id,
+            worktree_path: candidate.worktree_path,
+            exceeded,
+        });
+    }
+    Ok(events)
+}
+
+/// Clear an agent's quota flag once its worktree was reviewed. Returns whether
+/// it was flagged.
+pub fn clear_agent_quota_flag_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+) -> Result<bool, String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+        let agent = task
+            .agents
+            .iter_mut()
+            .find(|a| a.id == agent_id)
+            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+        if agent.quota_exceeded.take().is_none() {
+            return Ok(false);
+        }
+        task.updated_at = Utc::now().timestamp_millis();
+    }
+
+    state.save()?;
+    Ok(true)
+}
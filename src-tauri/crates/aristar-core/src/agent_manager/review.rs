@@ -0,0 +1,217 @@
+//! Review comments on agent diffs.
+//!
+//! Comments are stored on the `TaskAgent` they belong to, so they are persisted
+//! with the task in `tasks.json` and deleted together with it.
+
+use chrono::Utc;
+use std::path::{Component, Path};
+
+use crate::core::{humanize_duration, humanize_relative, Locale};
+
+use super::store::TaskManagerState;
+use super::types::{AgentStatus, AgentSummary, ReviewComment, Task, TaskAgent};
+
+/// Validate a review comment's file path: must be relative and stay inside the worktree.
+pub fn validate_comment_file(file: &str) -> Result<(), String> {
+    let path = Path::new(file);
+    if file.trim().is_empty() {
+        return Err("Comment file cannot be empty".to_string());
+    }
+    if path.is_absolute()
+        || path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!(
+            "Comment file must be relative to the worktree: {}",
+            file
+        ));
+    }
+    Ok(())
+}
+
+/// Add a comment to an agent. Returns the new comment.
+pub fn add_comment(
+    agent: &mut TaskAgent,
+    file: &str,
+    line: u32,
+    body: &str,
+    now: i64,
+) -> Result<ReviewComment, String> {
+    validate_comment_file(file)?;
+    if line == 0 {
+        return Err("Comment line numbers start at 1".to_string());
+    }
+    let body = body.trim();
+    if body.is_empty() {
+        return Err("Comment body cannot be empty".to_string());
+    }
+
+    let comment = ReviewComment {
+        id: uuid::Uuid::new_v4().to_string(),
+        file: file.to_string(),
+        line,
+        body: body.to_string(),
+        resolved: false,
+        created_at: now,
+        resolved_at: None,
+    };
+    agent.review_comments.push(comment.clone());
+    Ok(comment)
+}
+
+/// Mark a comment as resolved. Resolving twice keeps the first timestamp.
+pub fn resolve_agent_comment(
+    agent: &mut TaskAgent,
+    comment_id: &str,
+    now: i64,
+) -> Result<ReviewComment, String> {
+    let comment = agent
+        .review_comments
+        .iter_mut()
+        .find(|c| c.id == comment_id)
+        .ok_or_else(|| format!("Comment not found: {}", comment_id))?;
+
+    if !comment.resolved {
+        comment.resolved = true;
+        comment.resolved_at = Some(now);
+    }
+    Ok(comment.clone())
+}
+
+/// Build per-agent summaries for a task, humanizing times relative to `now`.
+pub fn summarize_agents(task: &Task, now: i64, locale: Locale) -> Vec<AgentSummary> {
+    task.agents
+        .iter()
+        .map(|agent| AgentSummary {
+            agent_id: agent.id.clone(),
+            model_id: agent.model_id.clone(),
+            provider_id: agent.provider_id.clone(),
+            status: agent.status.clone(),
+            accepted: agent.accepted,
+            open_comments: agent.review_comments.iter().filter(|c| !c.resolved).count(),
+            total_comments: agent.review_comments.len(),
+            created_relative: humanize_relative(agent.created_at, now, locale),
+            last_started_relative: agent
+                .last_started_at
+                .map(|t| humanize_relative(t, now, locale)),
+            running_for: agent
+                .last_started_at
+                .filter(|_| agent.status == AgentStatus::Running)
+                .map(|t| humanize_duration(now - t, locale)),
+        })
+        .collect()
+}
+
+/// Run a closure on an agent of a task, updating the task timestamp.
+fn with_agent_mut<F, R>(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+    f: F,
+) -> Result<R, String>
+where
+    F: FnOnce(&mut TaskAgent, i64) -> Result<R, String>,
+{
+    let mut store = state.store.lock().map_err(|e| e.to_string())?;
+    let task = store
+        .tasks
+        .iter_mut()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    let agent = task
+        .agents
+        .iter_mut()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    let now = Utc::now().timestamp_millis();
+    let result = f(agent, now)?;
+    task.updated_at = now;
+    Ok(result)
+}
+
+/// Add a review comment to an agent's diff.
+pub fn add_review_comment_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_id: String,
+    file: String,
+    line: u32,
+    body: String,
+) -> Result<ReviewComment, String> {
+    let comment = with_agent_mut(state, &task_id, &agent_id, |agent, now| {
+        add_comment(agent, &file, line, &body, now)
+    })?;
+
+    state.save()?;
+    println!(
+        "[task_manager] Added review comment on {}:{} for agent {} in task {}",
+        file, line, agent_id, task_id
+    );
+    Ok(comment)
+}
+
+/// List an agent's review comments, optionally including resolved ones.
+pub fn list_review_comments_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_id: String,
+    include_resolved: bool,
+) -> Result<Vec<ReviewComment>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    let task = store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    let agent = task
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    Ok(agent
+        .review_comments
+        .iter()
+        .filter(|c| include_resolved || !c.resolved)
+        .cloned()
+        .collect())
+}
+
+/// Resolve a review comment.
+pub fn resolve_comment_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_id: String,
+    comment_id: String,
+) -> Result<ReviewComment, String> {
+    let comment = with_agent_mut(state, &task_id, &agent_id, |agent, now| {
+        resolve_agent_comment(agent, &comment_id, now)
+    })?;
+
+    state.save()?;
+    Ok(comment)
+}
+
+/// Get per-agent summaries (status, open review comments) for a task.
+pub fn get_agent_summaries_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    locale: Locale,
+) -> Result<Vec<AgentSummary>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    let task = store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+    Ok(summarize_agents(
+        task,
+        Utc::now().timestamp_millis(),
+        locale,
+    ))
+}
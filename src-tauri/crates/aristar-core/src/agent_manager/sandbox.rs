@@ -0,0 +1,430 @@
+//! Sandboxing for agent OpenCode servers.
+//!
+//! When a task enables its sandbox, agent servers are launched with:
+//! - **macOS**: `sandbox-exec` and a generated profile that denies file writes
+//!   outside the worktree (plus git metadata, temp and OpenCode data dirs) and
+//!   denies all network traffic except localhost.
+//! - **Other platforms**: nothing enforces the sandbox, so servers refuse to
+//!   start unless the task sets `allow_unenforced`. They then get a restricted
+//!   environment only (private `TMPDIR`, proxy variables, credentials
+//!   scrubbed) and the task timeline records it.
+//!
+//! Sandbox profiles can only filter network traffic by `localhost` vs `*`, so
+//! approved hosts are reached through a small allowlist proxy on localhost that
+//! the server is pointed at with `HTTP(S)_PROXY`.
+
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::worktrees::operations::get_repo_context;
+
+use super::types::SandboxConfig;
+
+/// Path of the macOS sandbox launcher.
+pub const SANDBOX_EXEC: &str = "/usr/bin/sandbox-exec";
+
+/// OpenCode state directories (relative to `$HOME`) the server must be able to write.
+pub const OPENCODE_DATA_DIRS: [&str; 4] = [
+    ".local/share/opencode",
+    ".local/state/opencode",
+    ".config/opencode",
+    ".cache/opencode",
+];
+
+/// Environment variables removed from sandboxed servers.
+const SCRUBBED_ENV: [&str; 8] = [
+    "SSH_AUTH_SOCK",
+    "GITHUB_TOKEN",
+    "GH_TOKEN",
+    "GITLAB_TOKEN",
+    "NPM_TOKEN",
+    "AWS_ACCESS_KEY_ID",
+    "AWS_SECRET_ACCESS_KEY",
+    "AWS_SESSION_TOKEN",
+];
+
+/// Maximum size of a proxied request head.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// Timeout for connecting to an approved host.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ============ Configuration ============
+
+/// Validate a task's sandbox configuration.
+pub fn validate_sandbox_config(config: &SandboxConfig) -> Result<(), String> {
+    for host in &config.allowed_hosts {
+        let bare = host.trim_start_matches("*.");
+        if bare.is_empty()
+            || !bare
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        {
+            return Err(format!(
+                "Invalid allowed host '{}': use a host name like api.example.com or *.example.com",
+                host
+            ));
+        }
+    }
+    for path in &config.writable_paths {
+        if !Path::new(path).is_absolute() {
+            return Err(format!("Writable path must be absolute: {}", path));
+        }
+    }
+    Ok(())
+}
+
+/// Whether a host matches the allowlist. `*.example.com` matches subdomains only.
+pub fn host_allowed(host: &str, allowed_hosts: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_lowercase();
+    allowed_hosts.iter().any(|pattern| {
+        let pattern = pattern.to_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host.ends_with(&format!(".{}", domain)),
+            None => host == pattern,
+        }
+    })
+}
+
+/// Git common directory of a worktree (where commits made in it are written).
+pub fn git_common_dir(worktree_path: &Path) -> Option<PathBuf> {
+    get_repo_context(&worktree_path.to_string_lossy())
+        .ok()
+        .map(|context| context.common_dir)
+}
+
+/// Paths a sandboxed server may write to. Existing paths are canonicalized,
+/// since sandbox profiles match real paths (e.g. `/private/var` on macOS).
+pub fn sandbox_writable_paths(
+    worktree_path: &Path,
+    config: &SandboxConfig,
+    home: Option<&Path>,
+    temp_dir: &Path,
+) -> Vec<PathBuf> {
+    let mut paths = vec![worktree_path.to_path_buf(), temp_dir.to_path_buf()];
+    paths.extend(git_common_dir(worktree_path));
+    if let Some(home) = home {
+        paths.extend(OPENCODE_DATA_DIRS.iter().map(|d| home.join(d)));
+    }
+    paths.extend(config.writable_paths.iter().map(PathBuf::from));
+
+    let mut resolved: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let path = path.canonicalize().unwrap_or(path);
+        if !resolved.contains(&path) {
+            resolved.push(path);
+        }
+    }
+    resolved
+}
+
+// ============ Launch ============
+
+/// Quote a string for a sandbox profile.
+pub fn sbpl_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Build a `sandbox-exec` profile allowing writes only below `writable` and
+/// network traffic only to/from localhost.
+pub fn build_sandbox_profile(writable: &[PathBuf]) -> String {
+    let mut profile = String::from("(version 1)\n(allow default)\n\n(deny file-write*)\n");
+    profile.push_str("(allow file-write*\n    (regex #\"^/dev/\")");
+    for path in writable {
+        profile.push_str(&format!(
+            "\n    (subpath {})",
+            sbpl_string(&path.to_string_lossy())
+        ));
+    }
+    profile.push_str(")\n\n");
+    profile.push_str(
+        "(deny network*)\n\
+         (allow network-bind (local ip \"localhost:*\"))\n\
+         (allow network-inbound (local ip \"localhost:*\"))\n\
+         (allow network-outbound (remote ip \"localhost:*\"))\n\
+         (allow network-outbound (remote unix-socket))\n",
+    );
+    profile
+}
+
+/// How to launch a sandboxed server.
+#[derive(Debug, Clone)]
+pub struct SandboxLaunch {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub env_remove: Vec<String>,
+    /// Whether file and network restrictions are enforced by the OS
+    pub enforced: bool,
+}
+
+/// Build the launch command for a sandboxed server.
+///
+/// `use_sandbox_exec` selects the macOS profile; otherwise only the environment
+/// is restricted. `proxy_port` is the server's allowlist proxy.
+pub fn build_sandbox_launch(
+    binary: &Path,
+    server_args: &[String],
+    writable: &[PathBuf],
+    sandbox_temp: &Path,
+    proxy_port: u16,
+    use_sandbox_exec: bool,
+) -> SandboxLaunch {
+    let mut env = vec![(
+        "TMPDIR".to_string(),
+        sandbox_temp.to_string_lossy().to_string(),
+    )];
+    let proxy = format!("http://127.0.0.1:{}", proxy_port);
+    for var in [
+        "HTTP_PROXY",
+        "HTTPS_PROXY",
+        "ALL_PROXY",
+        "http_proxy",
+        "https_proxy",
+    ] {
+        env.push((var.to_string(), proxy.clone()));
+    }
+    env.push(("NO_PROXY".to_string(), "localhost,127.0.0.1".to_string()));
+    env.push(("no_proxy".to_string(), "localhost,127.0.0.1".to_string()));
+
+    let env_remove = SCRUBBED_ENV.iter().map(|v| v.to_string()).collect();
+
+    if use_sandbox_exec {
+        let mut args = vec![
+            "-p".to_string(),
+            build_sandbox_profile(writable),
+            binary.to_string_lossy().to_string(),
+        ];
+        args.extend_from_slice(server_args);
+        SandboxLaunch {
+            program: PathBuf::from(SANDBOX_EXEC),
+            args,
+            env,
+            env_remove,
+            enforced: true,
+        }
+    } else {
+        SandboxLaunch {
+            program: binary.to_path_buf(),
+            args: server_args.to_vec(),
+            env,
+            env_remove,
+            enforced: false,
+        }
+    }
+}
+
+/// Whether `sandbox-exec` is available on this machine.
+pub fn sandbox_exec_available() -> bool {
+    cfg!(target_os = "macos") && Path::new(SANDBOX_EXEC).exists()
+}
+
+/// Refuse a sandbox the OS can't enforce (`enforceable` is false without
+/// `sandbox-exec`) unless the task allows an unenforced one.
+pub fn check_sandbox_enforceable(config: &SandboxConfig, enforceable: bool) -> Result<(), String> {
+    if enforceable || config.allow_unenforced {
+        return Ok(());
+    }
+    Err(
+        "The agent sandbox can't be enforced on this platform (it needs sandbox-exec); \
+         allow an unenforced sandbox on the task to start with a restricted environment only"
+            .to_string(),
+    )
+}
+
+// ============ Allowlist Proxy ============
+
+/// Target of a proxied request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyTarget {
+    pub host: String,
+    pub port: u16,
+    /// `CONNECT` tunnel (HTTPS) rather than a plain HTTP request
+    pub tunnel: bool,
+}
+
+/// Parse the request line of a proxy request (`CONNECT host:443 HTTP/1.1` or
+/// `GET http://host/path HTTP/1.1`).
+pub fn parse_proxy_target(request_head: &str) -> Option<ProxyTarget> {
+    let line = request_head.lines().next()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+
+    let split_host_port = |authority: &str, default_port: u16| -> Option<(String, u16)> {
+        if let Some(rest) = authority.strip_prefix('[') {
+            let (host, port) = rest.split_once(']')?;
+            let port = port
+                .strip_prefix(':')
+                .map_or(Some(default_port), |p| p.parse().ok())?;
+            return Some((host.to_string(), port));
+        }
+        match authority.rsplit_once(':') {
+            Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+            None => Some((authority.to_string(), default_port)),
+        }
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = split_host_port(target, 443)?;
+        return Some(ProxyTarget {
+            host,
+            port,
+            tunnel: true,
+        });
+    }
+
+    let rest = target.strip_prefix("http://")?;
+    let authority = rest.split('/').next()?;
+    let authority = authority.rsplit('@').next()?;
+    let (host, port) = split_host_port(authority, 80)?;
+    Some(ProxyTarget {
+        host,
+        port,
+        tunnel: false,
+    })
+}
+
+/// HTTP proxy on localhost that only connects to approved hosts.
+/// Stops accepting connections when dropped.
+pub struct AllowlistProxy {
+    pub port: u16,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl AllowlistProxy {
+    /// Start the proxy on a free localhost port.
+    pub fn start(allowed_hosts: Vec<String>) -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| format!("Failed to start sandbox proxy: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start sandbox proxy: {}", e))?
+            .port();
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| format!("Failed to start sandbox proxy: {}", e))?;
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let stop = shutdown.clone();
+        let allowed = Arc::new(allowed_hosts);
+
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let allowed = allowed.clone();
+                        std::thread::spawn(move || {
+                            let _ = stream.set_nonblocking(false);
+                            handle_proxy_connection(stream, &allowed);
+                        });
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(Duration::from_millis(100));
+                    }
+                    Err(e) => {
+                        eprintln!("[sandbox] Proxy accept failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        println!("[sandbox] Allowlist proxy listening on 127.0.0.1:{}", port);
+        Ok(Self { port, shutdown })
+    }
+}
+
+impl Drop for AllowlistProxy {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Serve one proxy connection.
+fn handle_proxy_connection(mut client: TcpStream, allowed_hosts: &[String]) {
+    let mut head: Vec<u8> = Vec::new();
+    let mut buf = [0u8; 4096];
+    let header_end = loop {
+        match client.read(&mut buf) {
+            Ok(0) | Err(_) => return,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+        if let Some(pos) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if head.len() > MAX_REQUEST_HEAD {
+            let _ = client.write_all(b"HTTP/1.1 431 Request Header Fields Too Large\r\n\r\n");
+            return;
+        }
+    };
+
+    let target = match parse_proxy_target(&String::from_utf8_lossy(&head[..header_end])) {
+        Some(t) => t,
+        None => {
+            let _ = client.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+            return;
+        }
+    };
+
+    if !host_allowed(&target.host, allowed_hosts) {
+        println!(
+            "[sandbox] Blocked connection to {}:{}",
+            target.host, target.port
+        );
+        let _ = client.write_all(b"HTTP/1.1 403 Forbidden\r\n\r\nHost not allowed by sandbox\n");
+        return;
+    }
+
+    let upstream = (target.host.as_str(), target.port)
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| {
+            addrs.find_map(|a| TcpStream::connect_timeout(&a, CONNECT_TIMEOUT).ok())
+        });
+    let mut upstream = match upstream {
+        Some(s) => s,
+        None => {
+            let _ = client.write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n");
+            return;
+        }
+    };
+
+    let forwarded = if target.tunnel {
+        if client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .is_err()
+        {
+            return;
+        }
+        upstream.write_all(&head[header_end..])
+    } else {
+        upstream.write_all(&head)
+    };
+    if forwarded.is_err() {
+        return;
+    }
+
+    pipe(client, upstream);
+}
+
+/// Copy bytes in both directions until either side closes.
+fn pipe(client: TcpStream, upstream: TcpStream) {
+    let (mut client_read, mut upstream_write) = match (client.try_clone(), upstream.try_clone()) {
+        (Ok(c), Ok(u)) => (c, u),
+        _ => return,
+    };
+    let to_upstream = std::thread::spawn(move || {
+        let _ = std::io::copy(&mut client_read, &mut upstream_write);
+        let _ = upstream_write.shutdown(Shutdown::Write);
+    });
+
+    let (mut upstream_read, mut client_write) = (upstream, client);
+    let _ = std::io::copy(&mut upstream_read, &mut client_write);
+    let _ = client_write.shutdown(Shutdown::Write);
+    let _ = to_upstream.join();
+}
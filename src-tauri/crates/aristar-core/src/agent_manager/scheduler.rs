@@ -0,0 +1,260 @@
+//! Scheduled (recurring) tasks.
+//!
+//! Schedules are stored with the tasks in `tasks.json`. A background loop checks
+//! them every `SCHEDULER_INTERVAL`; each due schedule creates a task from its
+//! preset and emits `schedule-triggered` so the frontend can start the agents
+//! with the preset's prompt. Runs missed while the app was closed fire once on
+//! the next check. Nothing runs in read-only mode.
+//!
+//! Each check also updates battery throttling (see `power.rs`); while
+//! throttled, due schedules wait until the machine is back on AC power.
+
+use chrono::{Local, TimeZone, Utc};
+use std::time::Duration;
+
+use crate::worktrees::store::AppState;
+
+use super::cron::{next_run_after, CronExpr};
+use super::power::{
+    get_power_state, set_throttle_limit, should_throttle, PowerThrottledEvent,
+    DEFAULT_BATTERY_MAX_INSTANCES, DEFAULT_BATTERY_THROTTLE_PERCENT,
+};
+use super::store::TaskManagerState;
+use super::task_operations::create_task_impl;
+use super::types::{Schedule, ScheduleTriggeredEvent, TaskMode, TaskPreset};
+
+pub use crate::core::events::SCHEDULE_EVENT;
+
+/// How often due schedules are checked.
+pub const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Validate a schedule's task preset.
+pub fn validate_preset(preset: &TaskPreset) -> Result<(), String> {
+    if preset.name.trim().is_empty() {
+        return Err("Task name cannot be empty".to_string());
+    }
+    if preset.source_repo_path.trim().is_empty() {
+        return Err("Source repository path cannot be empty".to_string());
+    }
+    if preset.models.is_empty() {
+        return Err("At least one model must be selected".to_string());
+    }
+    Ok(())
+}
+
+/// Build a new schedule, computing its first run after `now`.
+pub fn new_schedule(
+    name: String,
+    cron: String,
+    preset: TaskPreset,
+    now: i64,
+) -> Result<Schedule, String> {
+    if name.trim().is_empty() {
+        return Err("Schedule name cannot be empty".to_string());
+    }
+    validate_preset(&preset)?;
+    let expr = CronExpr::parse(&cron)?;
+    let next_run_at = next_run_after(&expr, now)
+        .ok_or_else(|| format!("Cron expression never runs: {}", cron))?;
+
+    Ok(Schedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        cron: cron.trim().to_string(),
+        preset,
+        paused: false,
+        created_at: now,
+        next_run_at: Some(next_run_at),
+        last_run_at: None,
+        last_task_id: None,
+        last_error: None,
+    })
+}
+
+/// IDs of schedules due at `now`.
+pub fn due_schedule_ids(schedules: &[Schedule], now: i64) -> Vec<String> {
+    schedules
+        .iter()
+        .filter(|s| !s.paused && s.next_run_at.is_some_and(|t| t <= now))
+        .map(|s| s.id.clone())
+        .collect()
+}
+
+/// Name of the task created by a run, e.g. "Update deps (2025-01-31 02:00)".
+pub fn run_task_name(preset_name: &str, now: i64) -> String {
+    match Local.timestamp_millis_opt(now).single() {
+        Some(time) => format!("{} ({})", preset_name, time.format("%Y-%m-%d %H:%M")),
+        None => preset_name.to_string(),
+    }
+}
+
+/// Create a schedule.
+pub fn create_schedule_impl(
+    state: &TaskManagerState,
+    name: String,
+    cron: String,
+    preset: TaskPreset,
+) -> Result<Schedule, String> {
+    let schedule = new_schedule(name, cron, preset, Utc::now().timestamp_millis())?;
+
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        store.schedules.push(schedule.clone());
+    }
+    state.save()?;
+
+    println!(
+        "[scheduler] Created schedule '{}' ({})",
+        schedule.name, schedule.cron
+    );
+    Ok(schedule)
+}
+
+/// List all schedules.
+pub fn list_schedules_impl(state: &TaskManagerState) -> Result<Vec<Schedule>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    Ok(store.schedules.clone())
+}
+
+/// Pause or resume a schedule. Resuming computes the next run from now, so
+/// runs missed while paused are skipped.
+pub fn set_schedule_paused_impl(
+    state: &TaskManagerState,
+    schedule_id: &str,
+    paused: bool,
+) -> Result<Schedule, String> {
+    let schedule = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let schedule = store
+            .schedules
+            .iter_mut()
+            .find(|s| s.id == schedule_id)
+            .ok_or_else(|| format!("Schedule not found: {}", schedule_id))?;
+
+        if !paused && schedule.paused {
+            let expr = CronExpr::parse(&schedule.cron)?;
+            schedule.next_run_at = next_run_after(&expr, Utc::now().timestamp_millis());
+        }
+        schedule.paused = paused;
+        schedule.clone()
+    };
+
+    state.save()?;
+    Ok(schedule)
+}
+
+/// Delete a schedule. Tasks it created are kept.
+pub fn delete_schedule_impl(state: &TaskManagerState, schedule_id: &str) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let before = store.schedules.len();
+        store.schedules.retain(|s| s.id != schedule_id);
+        if store.schedules.len() == before {
+            return Err(format!("Schedule not found: {}", schedule_id));
+        }
+    }
+    state.save()
+}
+
+/// Create tasks for all due schedules and advance them to their next run.
+pub fn run_due_schedules(
+    state: &TaskManagerState,
+    now: i64,
+) -> Result<Vec<ScheduleTriggeredEvent>, String> {
+    let due: Vec<Schedule> = {
+        let store = state.store.lock().map_err(|e| e.to_string())?;
+        let ids = due_schedule_ids(&store.schedules, now);
+        store
+            .schedules
+            .iter()
+            .filter(|s| ids.contains(&s.id))
+            .cloned()
+            .collect()
+    };
+
+    let mut events = Vec::new();
+    for schedule in due {
+        let preset = schedule.preset.clone();
+        let result = create_task_impl(
+            state,
+            run_task_name(&preset.name, now),
+            preset.source_type,
+            preset.source_branch,
+            preset.source_commit,
+            preset.source_repo_path,
+            preset.agent_type,
+            preset.models,
+            preset.opencode_version,
+            false,
+            preset.sparse_paths,
+            TaskMode::Parallel,
+        );
+        let next_run_at = CronExpr::parse(&schedule.cron)
+            .ok()
+            .and_then(|expr| next_run_after(&expr, now));
+
+        let (task_id, error) = match result {
+            Ok(task) => (Some(task.id), None),
+            Err(e) => {
+                eprintln!("[scheduler] Schedule '{}' failed: {}", schedule.name, e);
+                (None, Some(e))
+            }
+        };
+
+        {
+            let mut store = state.store.lock().map_err(|e| e.to_string())?;
+            if let Some(stored) = store.schedules.iter_mut().find(|s| s.id == schedule.id) {
+                stored.last_run_at = Some(now);
+                stored.next_run_at = next_run_at;
+                stored.last_task_id = task_id.clone();
+                stored.last_error = error.clone();
+            }
+        }
+        state.save()?;
+
+        events.push(ScheduleTriggeredEvent {
+            schedule_id: schedule.id,
+            task_id,
+            prompt: schedule.preset.prompt,
+            error,
+        });
+    }
+
+    Ok(events)
+}
+
+/// Throttle or unthrottle agents for the current power state. Returns whether
+/// agents are throttled, and the `power-throttled` event when that changed.
+pub fn update_power_throttle(state: &AppState) -> (bool, Option<PowerThrottledEvent>) {
+    let (threshold, max_instances) = state
+        .store
+        .read()
+        .map(|store| {
+            (
+                store.settings.battery_throttle_percent,
+                store.settings.battery_max_opencode_instances,
+            )
+        })
+        .unwrap_or_default();
+    let threshold = threshold.unwrap_or(DEFAULT_BATTERY_THROTTLE_PERCENT);
+    let max_instances = max_instances.unwrap_or(DEFAULT_BATTERY_MAX_INSTANCES);
+
+    let power = get_power_state();
+    let throttled = should_throttle(power, threshold);
+    let limit = throttled.then_some(max_instances);
+    if !set_throttle_limit(limit) {
+        return (throttled, None);
+    }
+    println!(
+        "[scheduler] Power throttling {} (battery {:?}%)",
+        if throttled { "started" } else { "ended" },
+        power.battery_percent
+    );
+    let event = PowerThrottledEvent {
+        throttled,
+        on_battery: power.on_battery,
+        battery_percent: power.battery_percent,
+        max_instances: limit,
+    };
+    (throttled, Some(event))
+}
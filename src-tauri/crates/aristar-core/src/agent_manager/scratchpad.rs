@@ -0,0 +1,178 @@
+//! Shared scratchpad for the agents of a task.
+//!
+//! Each task has one Markdown file in its task folder
+//! (`~/.aristar-worktrees/tasks/{task-id}/scratchpad.md`), linked into every
+//! agent worktree as `.aristar/scratchpad.md`. Agents working together rather
+//! than competing can leave notes there for each other; the app reads and
+//! appends through `read_task_scratchpad` and `append_task_scratchpad`.
+//!
+//! The link is a symlink where the platform allows it, otherwise a copy that is
+//! refreshed on every append (writes an agent makes to a copy stay local).
+//! `.aristar/` is in the repository's `info/exclude`, so the file never shows up
+//! in diffs.
+
+use chrono::{TimeZone, Utc};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::core::mark_store_changed;
+use crate::worktrees::operations::ensure_aristar_excluded;
+
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_folder_path, get_task_impl};
+
+/// Scratchpad path inside each agent worktree.
+pub const WORKTREE_SCRATCHPAD: &str = ".aristar/scratchpad.md";
+
+/// Serializes appends, so concurrent notes never interleave.
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// A task's scratchpad: ~/.aristar-worktrees/tasks/{task-id}/scratchpad.md
+pub fn get_task_scratchpad_path(task_id: &str) -> PathBuf {
+    get_task_folder_path(task_id).join("scratchpad.md")
+}
+
+/// Contents of the scratchpad at `path`; empty when it doesn't exist yet.
+pub fn read_scratchpad(path: &Path) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("Failed to read scratchpad: {}", e)),
+    }
+}
+
+/// Append a note to the scratchpad at `path` under a heading naming the
+/// author and time (epoch millis `now`). Returns the new contents.
+pub fn append_scratchpad(
+    path: &Path,
+    author: Option<&str>,
+    text: &str,
+    now: i64,
+) -> Result<String, String> {
+    let text = text.trim_end();
+    if text.trim().is_empty() {
+        return Err("Scratchpad note cannot be empty".to_string());
+    }
+
+    let time = Utc
+        .timestamp_millis_opt(now)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_default();
+    let author = author.map(str::trim).filter(|a| !a.is_empty());
+    let heading = match author {
+        Some(author) => format!("### {} ({})", author, time),
+        None => format!("### {}", time),
+    };
+
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let existing = read_scratchpad(path)?;
+    let separator = match existing.as_str() {
+        "" => "",
+        s if s.ends_with("\n\n") => "",
+        s if s.ends_with('\n') => "\n",
+        _ => "\n\n",
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create task folder: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open scratchpad: {}", e))?;
+    write!(file, "{}{}\n\n{}\n", separator, heading, text)
+        .map_err(|e| format!("Failed to write scratchpad: {}", e))?;
+
+    read_scratchpad(path)
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+/// Link `scratchpad` into a worktree's `.aristar/`, creating it empty if
+/// needed. Falls back to copying when symlinks can't be created.
+pub fn link_scratchpad(scratchpad: &Path, worktree_path: &Path) -> Result<(), String> {
+    if !scratchpad.exists() {
+        if let Some(parent) = scratchpad.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create task folder: {}", e))?;
+        }
+        fs::write(scratchpad, "").map_err(|e| format!("Failed to create scratchpad: {}", e))?;
+    }
+
+    ensure_aristar_excluded(worktree_path)?;
+    let link = worktree_path.join(WORKTREE_SCRATCHPAD);
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    if let Ok(metadata) = fs::symlink_metadata(&link) {
+        if metadata.file_type().is_symlink()
+            && fs::read_link(&link).ok().as_deref() == Some(scratchpad)
+        {
+            return Ok(());
+        }
+        fs::remove_file(&link)
+            .map_err(|e| format!("Failed to replace {}: {}", link.display(), e))?;
+    }
+
+    if let Err(e) = create_symlink(scratchpad, &link) {
+        println!(
+            "[scratchpad] Symlink failed ({}), copying into {}",
+            e,
+            worktree_path.display()
+        );
+        fs::copy(scratchpad, &link)
+            .map_err(|e| format!("Failed to copy scratchpad into worktree: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Link a task's scratchpad into an agent worktree.
+pub fn link_task_scratchpad(task_id: &str, worktree_path: &str) -> Result<(), String> {
+    link_scratchpad(&get_task_scratchpad_path(task_id), Path::new(worktree_path))
+}
+
+/// Read a task's scratchpad.
+pub fn read_task_scratchpad_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+) -> Result<String, String> {
+    get_task_impl(state, task_id)?;
+    read_scratchpad(&get_task_scratchpad_path(task_id))
+}
+
+/// Append a note to a task's scratchpad and refresh its links in the agent
+/// worktrees. Returns the new contents.
+pub fn append_task_scratchpad_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    text: &str,
+    author: Option<&str>,
+) -> Result<String, String> {
+    let task = get_task_impl(state, task_id)?;
+    let path = get_task_scratchpad_path(task_id);
+    let contents = append_scratchpad(&path, author, text, Utc::now().timestamp_millis())?;
+    mark_store_changed();
+
+    for agent in &task.agents {
+        if Path::new(&agent.worktree_path).exists() {
+            if let Err(e) = link_scratchpad(&path, Path::new(&agent.worktree_path)) {
+                eprintln!("[scratchpad] {}", e);
+            }
+        }
+    }
+    Ok(contents)
+}
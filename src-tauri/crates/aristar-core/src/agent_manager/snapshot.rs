@@ -0,0 +1,171 @@
+//! Snapshots of uncommitted changes for tasks started from a dirty working tree.
+//!
+//! The snapshot is a commit whose parent is the repository's `HEAD` and whose tree
+//! is the working tree (tracked changes plus untracked, non-ignored files). It is
+//! built with a temporary index so the user's index and working tree are untouched,
+//! and kept alive by `refs/aristar/snapshots/{task-id}` until the task is deleted.
+//! Each agent worktree gets the snapshot's changes applied as uncommitted changes.
+//!
+//! Forking an agent uses the same mechanism on the source agent's worktree: the
+//! checkpoint is kept by `refs/aristar/checkpoints/{task-id}/{agent-id}` (the
+//! new agent's id) until the task is deleted.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use uuid::Uuid;
+
+use crate::worktrees::operations::run_git_command;
+
+/// Ref namespace holding task snapshots.
+pub const SNAPSHOT_REF_PREFIX: &str = "refs/aristar/snapshots/";
+
+/// Ref namespace holding checkpoints of forked agents.
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/aristar/checkpoints/";
+
+/// Ref keeping a task's snapshot reachable.
+pub fn snapshot_ref_name(task_id: &str) -> String {
+    format!("{}{}", SNAPSHOT_REF_PREFIX, task_id)
+}
+
+/// Ref keeping the checkpoint an agent was forked from reachable.
+pub fn checkpoint_ref_name(task_id: &str, agent_id: &str) -> String {
+    format!("{}{}/{}", CHECKPOINT_REF_PREFIX, task_id, agent_id)
+}
+
+/// Run git with a separate index file.
+fn run_git_with_index(args: &[&str], cwd: &str, index: &Path) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .env("GIT_INDEX_FILE", index)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Snapshot the uncommitted changes of the working tree at `repo_path`.
+///
+/// Returns `None` when there is nothing to snapshot.
+pub fn create_uncommitted_snapshot(
+    repo_path: &str,
+    task_id: &str,
+) -> Result<Option<String>, String> {
+    snapshot_working_tree(
+        repo_path,
+        &snapshot_ref_name(task_id),
+        &format!("Uncommitted changes for task {}", task_id),
+    )
+}
+
+/// Commit the working tree at `path` on top of its `HEAD` without touching its
+/// index, and keep the commit alive with `ref_name`.
+fn snapshot_working_tree(
+    path: &str,
+    ref_name: &str,
+    message: &str,
+) -> Result<Option<String>, String> {
+    let status = run_git_command(&["status", "--porcelain"], path)?;
+    if String::from_utf8_lossy(&status.stdout).trim().is_empty() {
+        return Ok(None);
+    }
+
+    let head = run_git_command(&["rev-parse", "--verify", "HEAD"], path).map_err(|_| {
+        "Cannot snapshot uncommitted changes: repository has no commits".to_string()
+    })?;
+    let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+
+    // Start from a copy of the real index so unchanged files are not re-hashed
+    let index_path = run_git_command(&["rev-parse", "--git-path", "index"], path)?;
+    let index_path = PathBuf::from(String::from_utf8_lossy(&index_path.stdout).trim());
+    let index_path = if index_path.is_absolute() {
+        index_path
+    } else {
+        PathBuf::from(path).join(index_path)
+    };
+    let temp_index =
+        std::env::temp_dir().join(format!("aristar-snapshot-{}.index", Uuid::new_v4()));
+    if index_path.exists() {
+        std::fs::copy(&index_path, &temp_index)
+            .map_err(|e| format!("Failed to copy git index: {}", e))?;
+    }
+
+    let result = (|| {
+        if !index_path.exists() {
+            run_git_with_index(&["read-tree", &head], path, &temp_index)?;
+        }
+        run_git_with_index(&["add", "-A"], path, &temp_index)?;
+        let tree = run_git_with_index(&["write-tree"], path, &temp_index)?;
+        run_git_with_index(
+            &["commit-tree", &tree, "-p", &head, "-m", message],
+            path,
+            &temp_index,
+        )
+    })();
+    let _ = std::fs::remove_file(&temp_index);
+    let commit = result.map_err(|e| format!("Failed to snapshot uncommitted changes: {}", e))?;
+
+    run_git_command(&["update-ref", ref_name, &commit], path)?;
+
+    println!(
+        "[snapshot] Snapshotted uncommitted changes of {} as {}",
+        path, commit
+    );
+    Ok(Some(commit))
+}
+
+/// Apply a snapshot's changes to a worktree as uncommitted changes.
+/// On conflict the worktree is reset and an error is returned.
+pub fn apply_snapshot(worktree_path: &str, commit: &str) -> Result<(), String> {
+    if let Err(e) = run_git_command(&["cherry-pick", "--no-commit", commit], worktree_path) {
+        let _ = run_git_command(&["cherry-pick", "--abort"], worktree_path);
+        let _ = run_git_command(&["reset", "--hard", "-q"], worktree_path);
+        return Err(format!(
+            "Failed to apply uncommitted changes to {}: {}",
+            worktree_path,
+            e.trim()
+        ));
+    }
+
+    // Leave the changes unstaged, as they were in the source working tree
+    run_git_command(&["reset", "-q"], worktree_path)?;
+    Ok(())
+}
+
+/// Delete a task's snapshot ref. Missing refs are ignored.
+pub fn delete_snapshot_ref(repo_path: &str, task_id: &str) {
+    let _ = run_git_command(
+        &["update-ref", "-d", &snapshot_ref_name(task_id)],
+        repo_path,
+    );
+}
+
+/// Checkpoint the uncommitted changes of an agent's worktree before forking it
+/// into `forked_agent_id`. Returns `None` when the worktree is clean.
+pub fn create_agent_checkpoint(
+    worktree_path: &str,
+    task_id: &str,
+    forked_agent_id: &str,
+) -> Result<Option<String>, String> {
+    snapshot_working_tree(
+        worktree_path,
+        &checkpoint_ref_name(task_id, forked_agent_id),
+        &format!("Checkpoint for {} in task {}", forked_agent_id, task_id),
+    )
+}
+
+/// Delete all checkpoint refs of a task.
+pub fn delete_checkpoint_refs(repo_path: &str, task_id: &str) {
+    let prefix = format!("{}{}/", CHECKPOINT_REF_PREFIX, task_id);
+    let Ok(output) = run_git_command(&["for-each-ref", "--format=%(refname)", &prefix], repo_path)
+    else {
+        return;
+    };
+    for ref_name in String::from_utf8_lossy(&output.stdout).lines() {
+        let _ = run_git_command(&["update-ref", "-d", ref_name], repo_path);
+    }
+}
@@ -0,0 +1,104 @@
+//! Task manager store state.
+//!
+//! `tasks.json` is read on first use rather than before the window opens: the
+//! app loads it on a background thread at startup and emits `tasks-loaded`,
+//! and anything reaching the store earlier waits for that load instead of
+//! starting its own.
+
+use std::sync::{LockResult, Mutex, MutexGuard, OnceLock};
+use std::time::Instant;
+
+use crate::core::mark_store_changed;
+
+use super::prompt_status::sync_status_files;
+use super::task_operations::{load_tasks, save_tasks};
+use super::types::{TaskStoreData, TasksLoadedEvent};
+
+pub use crate::core::events::TASKS_LOADED_EVENT;
+
+/// Task data, read from `tasks.json` the first time it is locked.
+pub struct TaskStore {
+    data: Mutex<TaskStoreData>,
+    loaded: OnceLock<TasksLoadedEvent>,
+}
+
+impl TaskStore {
+    /// A store that reads `tasks.json` on first use.
+    pub fn lazy() -> Self {
+        Self {
+            data: Mutex::new(TaskStoreData::default()),
+            loaded: OnceLock::new(),
+        }
+    }
+
+    /// A store holding `data`, never read from disk.
+    pub fn with_data(data: TaskStoreData) -> Self {
+        let loaded = OnceLock::new();
+        let _ = loaded.set(TasksLoadedEvent {
+            task_count: data.tasks.len(),
+            duration_ms: 0,
+        });
+        Self {
+            data: Mutex::new(data),
+            loaded,
+        }
+    }
+
+    /// Lock the task data, loading it first if needed.
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, TaskStoreData>> {
+        self.ensure_loaded();
+        self.data.lock()
+    }
+
+    /// Load the task data unless already loaded. Concurrent callers wait for
+    /// the one load.
+    pub fn ensure_loaded(&self) -> &TasksLoadedEvent {
+        self.loaded.get_or_init(|| {
+            let started = Instant::now();
+            let data = load_tasks();
+            let task_count = data.tasks.len();
+            *self.data.lock().unwrap_or_else(|e| e.into_inner()) = data;
+            TasksLoadedEvent {
+                task_count,
+                duration_ms: started.elapsed().as_millis() as u64,
+            }
+        })
+    }
+
+    /// The load, once it happened.
+    pub fn loaded(&self) -> Option<&TasksLoadedEvent> {
+        self.loaded.get()
+    }
+}
+
+impl Default for TaskStore {
+    fn default() -> Self {
+        Self::with_data(TaskStoreData::default())
+    }
+}
+
+/// Task Manager state - holds in-memory task data.
+///
+/// Lock order: after the worktree `AppState`, before `OpenCodeManager` (see
+/// `coordinator`).
+#[derive(Default)]
+pub struct TaskManagerState {
+    pub store: TaskStore,
+}
+
+impl TaskManagerState {
+    /// State whose tasks are loaded on first use (see `TaskStore`).
+    pub fn new() -> Self {
+        Self {
+            store: TaskStore::lazy(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let store = self.store.lock().map_err(|e| e.to_string())?;
+        save_tasks(&store)?;
+        mark_store_changed();
+        sync_status_files(&store);
+        Ok(())
+    }
+}
@@ -0,0 +1,817 @@
+//! Task CRUD operations, repointing tasks at a moved repository and relay
+//! mode hand-offs.
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::core::journal::{self, JournalOperation};
+use crate::core::{get_aristar_worktrees_base, TaskFolderCollision};
+use crate::worktrees::list_cache::invalidate_worktree_list_cache;
+use crate::worktrees::operations as worktree_ops;
+use crate::worktrees::repo_queue::run_queued;
+use crate::worktrees::sparse;
+
+use super::opencode_install::{normalize_version, validate_version};
+use super::sandbox::validate_sandbox_config;
+use super::scratchpad;
+use super::snapshot;
+use super::store::TaskManagerState;
+use super::types::{
+    AgentStatus, ModelSelection, RepointTasksReport, SandboxConfig, Task, TaskAgent, TaskMode,
+    TaskStatus, TaskStoreData, TimelineEntry, TimelineEntryKind, WorktreeRepairFailure,
+};
+
+/// Maximum number of entries kept in a task timeline.
+pub const MAX_TIMELINE_ENTRIES: usize = 500;
+
+// ============ Path Utilities ============
+
+/// Get the base path for task storage: ~/.aristar-worktrees/tasks/
+pub fn get_tasks_base_path() -> PathBuf {
+    get_aristar_worktrees_base().join("tasks")
+}
+
+/// Get the path to tasks.json: ~/.aristar-worktrees/tasks.json
+pub fn get_tasks_store_path() -> PathBuf {
+    get_aristar_worktrees_base().join("tasks.json")
+}
+
+/// Get the folder path for a specific task: ~/.aristar-worktrees/tasks/{task-id}/
+pub fn get_task_folder_path(task_id: &str) -> PathBuf {
+    get_tasks_base_path().join(task_id)
+}
+
+// ============ ID and Name Utilities ============
+
+/// Generate an 8-character task ID from the task name and current time.
+pub fn generate_task_id(name: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    Utc::now().timestamp_millis().hash(&mut hasher);
+    format!("{:08x}", hasher.finish() & 0xFFFFFFFF)
+}
+
+/// Slugify a string for use in folder names.
+/// e.g., "Refactor Authentication" -> "refactor-authentication"
+pub fn slugify(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Slugify model ID for use in folder names.
+/// e.g., "claude-sonnet-4" stays as "claude-sonnet-4"
+pub fn slugify_model_id(model_id: &str) -> String {
+    model_id
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// ============ Agent Folder Names ============
+
+/// Longest task name slug in agent folder names when not configured.
+pub const DEFAULT_TASK_SLUG_MAX_LEN: usize = 40;
+/// Shortest configurable slug length: a few characters and the hash suffix.
+pub const MIN_TASK_SLUG_MAX_LEN: usize = 12;
+/// Longest agent worktree path when not configured. Leaves room for the
+/// files inside under the 260 characters some Windows toolchains allow.
+pub const DEFAULT_MAX_WORKTREE_PATH_LEN: usize = 200;
+
+struct TaskFolderSettings {
+    slug_max_len: usize,
+    max_path_len: usize,
+    collision: TaskFolderCollision,
+}
+
+static FOLDER_SETTINGS: RwLock<TaskFolderSettings> = RwLock::new(TaskFolderSettings {
+    slug_max_len: DEFAULT_TASK_SLUG_MAX_LEN,
+    max_path_len: DEFAULT_MAX_WORKTREE_PATH_LEN,
+    collision: TaskFolderCollision::Number,
+});
+
+/// Set how agent folders are named; `None` keeps the defaults.
+pub fn set_task_folder_settings(
+    slug_max_len: Option<usize>,
+    max_path_len: Option<usize>,
+    collision: Option<TaskFolderCollision>,
+) {
+    *FOLDER_SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = TaskFolderSettings {
+        slug_max_len: slug_max_len
+            .unwrap_or(DEFAULT_TASK_SLUG_MAX_LEN)
+            .max(MIN_TASK_SLUG_MAX_LEN),
+        max_path_len: max_path_len.unwrap_or(DEFAULT_MAX_WORKTREE_PATH_LEN),
+        collision: collision.unwrap_or(TaskFolderCollision::Number),
+    };
+}
+
+/// Cut a slug to `max_len` characters, ending it in the first 8 hex digits of
+/// the full slug's SHA-256 so that long names sharing a prefix stay distinct.
+/// e.g., "a-very-long-task-name" (max 16) -> "a-very-1f3a9c2e"
+pub fn truncate_slug(slug: &str, max_len: usize) -> String {
+    if slug.chars().count() <= max_len {
+        return slug.to_string();
+    }
+    let hash = hex::encode(&Sha256::digest(slug.as_bytes())[..4]);
+    let prefix: String = slug.chars().take(max_len.saturating_sub(9)).collect();
+    let prefix = prefix.trim_end_matches('-');
+    if prefix.is_empty() {
+        hash
+    } else {
+        format!("{}-{}", prefix, hash)
+    }
+}
+
+/// Folder name of an agent worktree: the task name slug (at most
+/// `slug_max_len` characters) and the model.
+pub fn agent_folder_name(task_name: &str, model_id: &str, slug_max_len: usize) -> String {
+    format!(
+        "{}-{}",
+        truncate_slug(&slugify(task_name), slug_max_len),
+        slugify_model_id(model_id)
+    )
+}
+
+/// Fail when `path` is longer than `max_len` bytes.
+pub fn validate_worktree_path_len(path: &Path, max_len: usize) -> Result<(), String> {
+    let len = path.to_string_lossy().len();
+    if len > max_len {
+        return Err(format!(
+            "Worktree path is {} characters, over the limit of {}: {}. Use a shorter task name or lower task_slug_max_length",
+            len,
+            max_len,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Worktree path for agent `agent_num` in `task_folder`. A name already on
+/// disk or in `taken` gets the agent number appended, or fails, per
+/// `collision`. The path length is checked against `max_path_len`.
+pub fn resolve_agent_worktree_path(
+    task_folder: &Path,
+    folder_name: &str,
+    agent_num: usize,
+    taken: &[PathBuf],
+    collision: TaskFolderCollision,
+    max_path_len: usize,
+) -> Result<PathBuf, String> {
+    let is_taken = |path: &PathBuf| path.exists() || taken.contains(path);
+
+    let mut path = task_folder.join(folder_name);
+    if is_taken(&path) {
+        path = match collision {
+            TaskFolderCollision::Number => {
+                task_folder.join(format!("{}-{}", folder_name, agent_num))
+            }
+            TaskFolderCollision::Error => {
+                return Err(format!("Agent folder already exists: {}", path.display()))
+            }
+        };
+        if is_taken(&path) {
+            return Err(format!("Agent folder already exists: {}", path.display()));
+        }
+    }
+    validate_worktree_path_len(&path, max_path_len)?;
+    Ok(path)
+}
+
+/// Worktree path for a new agent of a task, using the folder settings.
+pub fn agent_worktree_path(
+    task_id: &str,
+    task_name: &str,
+    model_id: &str,
+    agent_num: usize,
+    taken: &[PathBuf],
+) -> Result<PathBuf, String> {
+    let settings = FOLDER_SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    resolve_agent_worktree_path(
+        &get_task_folder_path(task_id),
+        &agent_folder_name(task_name, model_id, settings.slug_max_len),
+        agent_num,
+        taken,
+        settings.collision,
+        settings.max_path_len,
+    )
+}
+
+// ============ Persistence ============
+
+/// Load tasks from tasks.json.
+pub fn load_tasks() -> TaskStoreData {
+    let store_path = get_tasks_store_path();
+
+    if !store_path.exists() {
+        println!("[task_manager] No tasks file found, using defaults");
+        return TaskStoreData::default();
+    }
+
+    match std::fs::read_to_string(&store_path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(data) => {
+                println!("[task_manager] Loaded tasks from store");
+                data
+            }
+            Err(e) => {
+                eprintln!("[task_manager] Failed to parse tasks file: {}", e);
+                TaskStoreData::default()
+            }
+        },
+        Err(e) => {
+            eprintln!("[task_manager] Failed to read tasks file: {}", e);
+            TaskStoreData::default()
+        }
+    }
+}
+
+/// Save tasks to tasks.json.
+pub fn save_tasks(data: &TaskStoreData) -> Result<(), String> {
+    let store_path = get_tasks_store_path();
+
+    if let Some(parent) = store_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create tasks directory: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("Failed to serialize tasks: {}", e))?;
+
+    std::fs::write(&store_path, json).map_err(|e| format!("Failed to write tasks file: {}", e))?;
+
+    println!("[task_manager] Saved {} tasks to store", data.tasks.len());
+    Ok(())
+}
+
+// ============ Task CRUD Operations ============
+
+/// Create a new task with agents.
+#[allow(clippy::too_many_arguments)]
+pub fn create_task_impl(
+    state: &TaskManagerState,
+    name: String,
+    source_type: String,
+    source_branch: Option<String>,
+    source_commit: Option<String>,
+    source_repo_path: String,
+    agent_type: String,
+    models: Vec<ModelSelection>,
+    opencode_version: Option<String>,
+    include_uncommitted: bool,
+    sparse_paths: Vec<String>,
+    mode: TaskMode,
+) -> Result<Task, String> {
+    // Validation
+    if name.trim().is_empty() {
+        return Err("Task name cannot be empty".to_string());
+    }
+
+    let opencode_version = opencode_version
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| normalize_version(&v));
+    if let Some(ref v) = opencode_version {
+        validate_version(v)?;
+    }
+
+    if models.is_empty() {
+        return Err("At least one model must be selected".to_string());
+    }
+    let sparse_paths = sparse::normalize_sparse_paths(&sparse_paths)?;
+
+    let task_id = generate_task_id(&name);
+    let task_folder = get_task_folder_path(&task_id);
+    let now = Utc::now().timestamp_millis();
+
+    // Resolve every worktree path before touching the repository
+    let mut worktree_paths: Vec<PathBuf> = Vec::new();
+    for (idx, model) in models.iter().enumerate() {
+        let path = agent_worktree_path(&task_id, &name, &model.model_id, idx + 1, &worktree_paths)?;
+        worktree_paths.push(path);
+    }
+
+    // Rolled back at startup if the app dies before the task is saved
+    let _intent = journal::record_intent(JournalOperation::CreateTask {
+        task_id: task_id.clone(),
+        source_repo_path: source_repo_path.clone(),
+        task_folder: task_folder.to_string_lossy().to_string(),
+        worktree_paths: worktree_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+    })?;
+
+    // Create task folder
+    std::fs::create_dir_all(&task_folder)
+        .map_err(|e| format!("Failed to create task folder: {}", e))?;
+
+    let snapshot_commit = if include_uncommitted {
+        snapshot::create_uncommitted_snapshot(&source_repo_path, &task_id)?
+    } else {
+        None
+    };
+
+    // Determine the source reference for worktree creation
+    let source_ref = match source_type.as_str() {
+        "commit" => source_commit.clone(),
+        _ => source_branch.clone(),
+    };
+
+    // Create agents with worktrees
+    let mut agents = Vec::new();
+    for (idx, (model, worktree_path)) in models.iter().zip(&worktree_paths).enumerate() {
+        let agent_id = format!("agent-{}", idx + 1);
+        let worktree_path_str = worktree_path.to_string_lossy().to_string();
+
+        // Create the worktree at the specified path
+        let created_path = worktree_ops::create_sparse_worktree_at_path(
+            &source_repo_path,
+            &worktree_path_str,
+            source_ref.as_deref(),
+            &sparse_paths,
+        )?;
+        if let Some(ref commit) = snapshot_commit {
+            snapshot::apply_snapshot(&created_path, commit)?;
+        }
+        scratchpad::link_task_scratchpad(&task_id, &created_path)?;
+
+        agents.push(TaskAgent {
+            id: agent_id,
+            model_id: model.model_id.clone(),
+            provider_id: model.provider_id.clone(),
+            agent_type: None,
+            worktree_path: created_path,
+            session_id: None,
+            status: initial_agent_status(mode, idx),
+            accepted: false,
+            created_at: now,
+            preferred_port: None,
+            review_comments: Vec::new(),
+            last_started_at: None,
+            forked_from: None,
+            quota_exceeded: None,
+        });
+    }
+
+    let task = Task {
+        id: task_id,
+        name,
+        source_type,
+        source_branch,
+        source_commit,
+        source_repo_path,
+        agent_type,
+        mode,
+        status: TaskStatus::Idle,
+        created_at: now,
+        updated_at: now,
+        agents,
+        opencode_version,
+        result_summary: None,
+        sandbox: SandboxConfig::default(),
+        timeline: Vec::new(),
+        snapshot_commit,
+        context_files: Vec::new(),
+        sparse_paths,
+        pipeline: None,
+        pipeline_run: None,
+        auto_accept: None,
+        pending_auto_accept: None,
+        disk_quota_mb: None,
+        agent_outcomes: Vec::new(),
+    };
+
+    // Save to store
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        store.tasks.push(task.clone());
+    }
+    state.save()?;
+
+    println!("[task_manager] Created task: {}", task.id);
+    Ok(task)
+}
+
+/// Get all tasks.
+pub fn get_tasks_impl(state: &TaskManagerState) -> Result<Vec<Task>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    Ok(store.tasks.clone())
+}
+
+/// Get all tasks without their timelines, which `get_task_timeline_impl`
+/// returns on demand.
+pub fn get_task_list_impl(state: &TaskManagerState) -> Result<Vec<Task>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    Ok(store
+        .tasks
+        .iter()
+        .map(|task| Task {
+            timeline: Vec::new(),
+            ..task.clone()
+        })
+        .collect())
+}
+
+/// Get a task's timeline.
+pub fn get_task_timeline_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+) -> Result<Vec<TimelineEntry>, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .map(|t| t.timeline.clone())
+        .ok_or_else(|| format!("Task not found: {}", task_id))
+}
+
+/// Get a single task by ID.
+pub fn get_task_impl(state: &TaskManagerState, task_id: &str) -> Result<Task, String> {
+    let store = state.store.lock().map_err(|e| e.to_string())?;
+    store
+        .tasks
+        .iter()
+        .find(|t| t.id == task_id)
+        .cloned()
+        .ok_or_else(|| format!("Task not found: {}", task_id))
+}
+
+/// Update a task's properties.
+pub fn update_task_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    name: Option<String>,
+    status: Option<TaskStatus>,
+) -> Result<Task, String> {
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        if let Some(n) = name {
+            task.name = n;
+        }
+        if let Some(s) = status {
+            task.status = s;
+        }
+        task.updated_at = Utc::now().timestamp_millis();
+
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
+
+/// Delete a task and optionally its worktrees.
+pub fn delete_task_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    delete_worktrees: bool,
+) -> Result<(), String> {
+    let task = get_task_impl(state, &task_id)?;
+
+    // Delete worktrees if requested
+    if delete_worktrees {
+        for agent in &task.agents {
+            if std::path::Path::new(&agent.worktree_path).exists() {
+                // Try to remove the worktree using git, ignore errors
+                let _ = worktree_ops::remove_worktree(&agent.worktree_path, true, true);
+            }
+        }
+
+        // Also remove the task folder
+        let task_folder = get_task_folder_path(&task_id);
+        if task_folder.exists() {
+            let _ = std::fs::remove_dir_all(&task_folder);
+        }
+    }
+
+    if task.snapshot_commit.is_some() {
+        snapshot::delete_snapshot_ref(&task.source_repo_path, &task_id);
+    }
+    snapshot::delete_checkpoint_refs(&task.source_repo_path, &task_id);
+
+    // Remove from store
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        store.tasks.retain(|t| t.id != task_id);
+    }
+    state.save()?;
+
+    println!("[task_manager] Deleted task: {}", task_id);
+    Ok(())
+}
+
+/// Whether two paths name the same repository. Paths that no longer exist
+/// (a repository that moved away) are compared without trailing separators.
+pub fn same_repo_path(a: &str, b: &str) -> bool {
+    let resolve = |p: &str| {
+        Path::new(p)
+            .canonicalize()
+            .unwrap_or_else(|_| Path::new(p.trim_end_matches(['/', '\\'])).to_path_buf())
+    };
+    resolve(a) == resolve(b)
+}
+
+/// Point every task whose source repository is `old_repo_path` at
+/// `new_repo_path` after the repository was renamed or moved. Returns the
+/// resolved new path, the tasks changed and their agent worktrees on disk.
+fn repoint_task_records(
+    state: &TaskManagerState,
+    old_repo_path: &str,
+    new_repo_path: &str,
+) -> Result<(String, Vec<String>, Vec<String>), String> {
+    let new_repo = Path::new(new_repo_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", new_repo_path, e))?
+        .to_string_lossy()
+        .to_string();
+    if !worktree_ops::is_git_repository(&new_repo) {
+        return Err(format!("Not a git repository: {}", new_repo));
+    }
+    if same_repo_path(old_repo_path, &new_repo) {
+        return Err("The repository is already at this path".to_string());
+    }
+
+    let (task_ids, worktrees) = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let now = Utc::now().timestamp_millis();
+        let mut task_ids = Vec::new();
+        let mut worktrees = Vec::new();
+        for task in store
+            .tasks
+            .iter_mut()
+            .filter(|t| same_repo_path(&t.source_repo_path, old_repo_path))
+        {
+            task.source_repo_path = new_repo.clone();
+            task.updated_at = now;
+            task_ids.push(task.id.clone());
+            worktrees.extend(
+                task.agents
+                    .iter()
+                    .filter(|a| Path::new(&a.worktree_path).exists())
+                    .map(|a| a.worktree_path.clone()),
+            );
+        }
+        (task_ids, worktrees)
+    };
+    if task_ids.is_empty() {
+        return Err(format!("No tasks use {}", old_repo_path));
+    }
+    state.save()?;
+    Ok((new_repo, task_ids, worktrees))
+}
+
+/// Run `git worktree repair` from `repo_path` on each of `worktrees`, whose
+/// `.git` files may still point into the repository's old location. A failed
+/// repair is reported and doesn't stop the others.
+pub fn repair_agent_worktrees(
+    repo_path: &str,
+    worktrees: Vec<String>,
+) -> (Vec<String>, Vec<WorktreeRepairFailure>) {
+    let mut repaired = Vec::new();
+    let mut failed = Vec::new();
+    for path in worktrees {
+        let result = run_queued(repo_path, "repair worktree", || {
+            worktree_ops::run_git_command(&["worktree", "repair", &path], repo_path)
+        });
+        match result {
+            Ok(_) => repaired.push(path),
+            Err(e) => failed.push(WorktreeRepairFailure {
+                path,
+                error: e.trim().to_string(),
+            }),
+        }
+    }
+    invalidate_worktree_list_cache(repo_path);
+    (repaired, failed)
+}
+
+fn repoint_report(
+    old_repo_path: &str,
+    new_repo_path: String,
+    task_ids: Vec<String>,
+    (repaired, failed): (Vec<String>, Vec<WorktreeRepairFailure>),
+) -> RepointTasksReport {
+    println!(
+        "[task_manager] Repointed {} task(s) from {} to {}, repaired {} worktree(s)",
+        task_ids.len(),
+        old_repo_path,
+        new_repo_path,
+        repaired.len()
+    );
+    RepointTasksReport {
+        new_repo_path,
+        task_ids,
+        repaired,
+        failed,
+    }
+}
+
+/// Point the tasks of a renamed or moved repository at its new path and
+/// repair their agent worktrees. Agents without a worktree on disk are
+/// skipped.
+pub fn repoint_tasks_impl(
+    state: &TaskManagerState,
+    old_repo_path: &str,
+    new_repo_path: &str,
+) -> Result<RepointTasksReport, String> {
+    let (new_repo, task_ids, worktrees) =
+        repoint_task_records(state, old_repo_path, new_repo_path)?;
+    let outcome = repair_agent_worktrees(&new_repo, worktrees);
+    Ok(repoint_report(old_repo_path, new_repo, task_ids, outcome))
+}
+
+/// Repoint tasks at a moved repository (async version).
+/// The repairs run on a blocking thread to avoid freezing the UI.
+pub async fn repoint_tasks_async(
+    state: &TaskManagerState,
+    old_repo_path: String,
+    new_repo_path: String,
+) -> Result<RepointTasksReport, String> {
+    let (new_repo, task_ids, worktrees) =
+        repoint_task_records(state, &old_repo_path, &new_repo_path)?;
+    let repo = new_repo.clone();
+    let outcome = tokio::task::spawn_blocking(move || repair_agent_worktrees(&repo, worktrees))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    Ok(repoint_report(&old_repo_path, new_repo, task_ids, outcome))
+}
+
+/// Pin (or unpin with `None`) the OpenCode version used by a task's agents.
+pub fn set_task_opencode_version_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    version: Option<String>,
+) -> Result<Task, String> {
+    let version = version
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| normalize_version(&v));
+    if let Some(ref v) = version {
+        validate_version(v)?;
+    }
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        task.opencode_version = version;
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
+
+/// Set the sandbox options used when starting a task's agent servers.
+/// Running servers keep their current sandbox until restarted.
+pub fn set_task_sandbox_impl(
+    state: &TaskManagerState,
+    task_id: String,
+    sandbox: SandboxConfig,
+) -> Result<Task, String> {
+    validate_sandbox_config(&sandbox)?;
+
+    let task = {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        task.sandbox = sandbox;
+        task.updated_at = Utc::now().timestamp_millis();
+        task.clone()
+    };
+
+    state.save()?;
+    Ok(task)
+}
+
+/// Append an entry to a task's timeline, dropping the oldest entries beyond
+/// `MAX_TIMELINE_ENTRIES`.
+pub fn push_timeline_entry(task: &mut Task, entry: TimelineEntry) {
+    task.timeline.push(entry);
+    if task.timeline.len() > MAX_TIMELINE_ENTRIES {
+        let excess = task.timeline.len() - MAX_TIMELINE_ENTRIES;
+        task.timeline.drain(..excess);
+    }
+}
+
+// ============ Relay Mode ============
+
+/// Status of the agent at `position` when a task is created: in a relay only the
+/// first agent can start, the others wait for their hand-off.
+pub fn initial_agent_status(mode: TaskMode, position: usize) -> AgentStatus {
+    match mode {
+        TaskMode::Relay if position > 0 => AgentStatus::Waiting,
+        _ => AgentStatus::Idle,
+    }
+}
+
+/// Position of the agent that receives `agent_id`'s work when it is accepted in
+/// a relay task: the first waiting agent. `None` for parallel tasks and for the
+/// last agent of a relay. Fails when `agent_id` is not the agent holding the
+/// relay (the one right before the first waiting agent).
+pub fn relay_successor(task: &Task, agent_id: &str) -> Result<Option<usize>, String> {
+    if task.mode != TaskMode::Relay {
+        return Ok(None);
+    }
+    let position = task
+        .agents
+        .iter()
+        .position(|a| a.id == agent_id)
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    let Some(next) = task
+        .agents
+        .iter()
+        .position(|a| a.status == AgentStatus::Waiting)
+    else {
+        return Ok(None);
+    };
+    if position + 1 != next {
+        return Err(format!(
+            "{} can't be accepted yet: {} holds the relay",
+            agent_id,
+            next.checked_sub(1)
+                .map(|i| task.agents[i].id.as_str())
+                .unwrap_or("no agent")
+        ));
+    }
+    Ok(Some(next))
+}
+
+/// Hand the work of the agent at `from` to the waiting agent at `to`: the
+/// receiving worktree is reset to the giver's `HEAD` and the giver's uncommitted
+/// changes are applied on top (checkpointed under
+/// `refs/aristar/checkpoints/{task-id}/{to-agent}`).
+pub fn relay_hand_off(task: &mut Task, from: usize, to: usize) -> Result<(), String> {
+    let giver = task.agents[from].clone();
+    let receiver_id = task.agents[to].id.clone();
+    let receiver_path = task.agents[to].worktree_path.clone();
+
+    let head =
+        worktree_ops::run_git_command(&["rev-parse", "--verify", "HEAD"], &giver.worktree_path)
+            .map_err(|e| format!("Failed to read HEAD of {}: {}", giver.id, e.trim()))?;
+    let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+    let checkpoint =
+        snapshot::create_agent_checkpoint(&giver.worktree_path, &task.id, &receiver_id)?;
+
+    worktree_ops::run_git_command(&["reset", "--hard", "-q", &head], &receiver_path)
+        .map_err(|e| format!("Failed to reset {}: {}", receiver_id, e.trim()))?;
+    worktree_ops::run_git_command(&["clean", "-fdq"], &receiver_path)
+        .map_err(|e| format!("Failed to clean {}: {}", receiver_id, e.trim()))?;
+    if let Some(ref commit) = checkpoint {
+        snapshot::apply_snapshot(&receiver_path, commit)?;
+    }
+
+    let now = Utc::now().timestamp_millis();
+    task.agents[to].status = AgentStatus::Idle;
+    push_timeline_entry(
+        task,
+        TimelineEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: now,
+            agent_id: Some(receiver_id.clone()),
+            kind: TimelineEntryKind::RelayHandOff,
+            message: format!("{} handed off to {}", giver.id, receiver_id),
+            details: vec![head],
+        },
+    );
+    println!(
+        "[task_manager] Relay of task {}: {} handed off to {}",
+        task.id, giver.id, receiver_id
+    );
+    Ok(())
+}
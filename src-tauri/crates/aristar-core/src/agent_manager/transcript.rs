@@ -0,0 +1,223 @@
+//! Session transcripts of agents.
+//!
+//! With `recordTranscripts` on in the app settings, the frontend forwards the
+//! OpenCode events it receives over SSE for each agent to
+//! `append_agent_transcript`. Finished pieces of the session (completed
+//! messages, text and reasoning parts, completed or failed tool calls, session
+//! errors) are appended to
+//! `~/.aristar-worktrees/tasks/{task-id}/transcripts/{agent-id}.jsonl`, one
+//! `TranscriptEntry` per line. Streaming updates are dropped and every message
+//! or part is recorded once. `get_agent_transcript` reads entries back for the
+//! review UI and task reports. Secrets are redacted before writing (see
+//! `core::redaction`).
+
+use chrono::Utc;
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::core::redaction::redact_json;
+
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_folder_path, get_task_impl};
+use super::types::{AgentTranscript, TranscriptEntry, TranscriptRange};
+
+/// IDs already recorded, per transcript file (loaded on first append).
+static RECORDED: OnceLock<Mutex<HashMap<PathBuf, HashSet<String>>>> = OnceLock::new();
+
+/// An agent's transcript: ~/.aristar-worktrees/tasks/{task-id}/transcripts/{agent-id}.jsonl
+pub fn get_transcript_path(task_id: &str, agent_id: &str) -> PathBuf {
+    get_task_folder_path(task_id)
+        .join("transcripts")
+        .join(format!("{}.jsonl", agent_id))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn has_field(value: &Value, path: &[&str]) -> bool {
+    path.iter()
+        .try_fold(value, |v, key| v.get(key))
+        .is_some_and(|v| !v.is_null())
+}
+
+// ============ Events ============
+
+/// The transcript entry for an OpenCode event, if it carries a finished piece
+/// of the session. Streaming updates (text deltas, running tools) and
+/// unrelated events give `None`.
+pub fn transcript_entry(event: &Value, now: i64) -> Option<TranscriptEntry> {
+    let event_type = event.get("type").and_then(Value::as_str)?;
+    let props = event.get("properties")?;
+
+    match event_type {
+        "message.updated" => {
+            let info = props.get("info")?;
+            let is_user = info.get("role").and_then(Value::as_str) == Some("user");
+            if !is_user && !has_field(info, &["time", "completed"]) {
+                return None;
+            }
+            let id = str_field(info, "id")?;
+            Some(TranscriptEntry {
+                message_id: Some(id.clone()),
+                id,
+                kind: "message".to_string(),
+                session_id: str_field(info, "sessionID"),
+                timestamp: now,
+                data: info.clone(),
+            })
+        }
+        "message.part.updated" => {
+            let part = props.get("part")?;
+            let kind = part.get("type").and_then(Value::as_str)?;
+            let finished = match kind {
+                // User parts carry no time; streamed parts get `time.end` when done
+                "text" | "reasoning" => {
+                    props.get("delta").is_none()
+                        && (part.get("time").is_none() || has_field(part, &["time", "end"]))
+                }
+                "tool" => matches!(
+                    part.pointer("/state/status").and_then(Value::as_str),
+                    Some("completed" | "error")
+                ),
+                _ => false,
+            };
+            if !finished {
+                return None;
+            }
+            Some(TranscriptEntry {
+                id: str_field(part, "id")?,
+                kind: kind.to_string(),
+                session_id: str_field(part, "sessionID"),
+                message_id: str_field(part, "messageID"),
+                timestamp: now,
+                data: part.clone(),
+            })
+        }
+        "session.error" => Some(TranscriptEntry {
+            id: format!("error-{}", now),
+            kind: "error".to_string(),
+            session_id: str_field(props, "sessionID"),
+            message_id: None,
+            timestamp: now,
+            data: props.get("error").cloned().unwrap_or(Value::Null),
+        }),
+        _ => None,
+    }
+}
+
+// ============ Storage ============
+
+/// Read the transcript at `path`; empty when it doesn't exist yet. Lines that
+/// fail to parse (e.g. cut off by a crash) are skipped.
+pub fn read_transcript(path: &Path) -> Result<Vec<TranscriptEntry>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read transcript: {}", e)),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Append the finished pieces among `events` to the transcript at `path`,
+/// skipping IDs already recorded. Returns the number of entries written.
+pub fn append_transcript(path: &Path, events: &[Value], now: i64) -> Result<usize, String> {
+    let recorded = RECORDED.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut recorded = recorded.lock().unwrap_or_else(|e| e.into_inner());
+    let ids = match recorded.entry(path.to_path_buf()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            entry.insert(read_transcript(path)?.into_iter().map(|e| e.id).collect())
+        }
+    };
+
+    let mut lines = String::new();
+    let mut count = 0;
+    for mut entry in events.iter().filter_map(|e| transcript_entry(e, now)) {
+        if !ids.insert(entry.id.clone()) {
+            continue;
+        }
+        redact_json(&mut entry.data);
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize transcript entry: {}", e))?;
+        lines.push_str(&line);
+        lines.push('\n');
+        count += 1;
+    }
+    if count == 0 {
+        return Ok(0);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create transcript folder: {}", e))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open transcript: {}", e))?;
+    file.write_all(lines.as_bytes())
+        .map_err(|e| format!("Failed to write transcript: {}", e))?;
+    Ok(count)
+}
+
+/// Entries of `range` (all when `None`), clamped to the transcript's length.
+pub fn slice_transcript(
+    entries: Vec<TranscriptEntry>,
+    range: Option<TranscriptRange>,
+) -> Vec<TranscriptEntry> {
+    let Some(range) = range else {
+        return entries;
+    };
+    let end = range.end.unwrap_or(entries.len()).min(entries.len());
+    let start = range.start.min(end);
+    entries.into_iter().skip(start).take(end - start).collect()
+}
+
+fn ensure_agent(state: &TaskManagerState, task_id: &str, agent_id: &str) -> Result<(), String> {
+    let task = get_task_impl(state, task_id)?;
+    if !task.agents.iter().any(|a| a.id == agent_id) {
+        return Err(format!("Agent not found: {}", agent_id));
+    }
+    Ok(())
+}
+
+/// Record OpenCode events of an agent. Returns the number of entries written.
+pub fn append_agent_transcript_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+    events: &[Value],
+) -> Result<usize, String> {
+    ensure_agent(state, task_id, agent_id)?;
+    append_transcript(
+        &get_transcript_path(task_id, agent_id),
+        events,
+        Utc::now().timestamp_millis(),
+    )
+}
+
+/// Read an agent's transcript, or the entries of `range`.
+pub fn get_agent_transcript_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+    range: Option<TranscriptRange>,
+) -> Result<AgentTranscript, String> {
+    ensure_agent(state, task_id, agent_id)?;
+    let entries = read_transcript(&get_transcript_path(task_id, agent_id))?;
+    Ok(AgentTranscript {
+        agent_id: agent_id.to_string(),
+        total: entries.len(),
+        entries: slice_transcript(entries, range),
+    })
+}
@@ -0,0 +1,837 @@
+//! Task and Agent types for the Agent Manager feature.
+//!
+//! These types represent tasks (goals/prompts) and the AI agents working on them.
+//! Each task can have multiple agents, each with its own worktree.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::worktrees::types::MergeStrategy;
+
+pub use crate::core::events::{AGENT_PORT_EVENT, AGENT_STATUS_EVENT};
+
+/// Status of a task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskStatus {
+    #[default]
+    Idle,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+}
+
+/// How the agents of a task work.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskMode {
+    /// Every agent starts from the source ref and works at the same time
+    #[default]
+    Parallel,
+    /// Agents run one after another, each starting from the previous agent's
+    /// accepted work
+    Relay,
+}
+
+/// Status of an agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentStatus {
+    #[default]
+    Idle,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    /// Relay agent waiting for the previous agent to hand off
+    Waiting,
+}
+
+/// A file/line comment left while reviewing an agent's diff.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    /// Unique comment ID (UUID)
+    pub id: String,
+    /// File path relative to the agent's worktree
+    pub file: String,
+    /// 1-based line number in the new version of the file
+    pub line: u32,
+    /// Comment text
+    pub body: String,
+    /// Whether the comment has been resolved
+    pub resolved: bool,
+    /// Timestamp when comment was created (milliseconds since epoch)
+    pub created_at: i64,
+    /// Timestamp when comment was resolved (milliseconds since epoch)
+    pub resolved_at: Option<i64>,
+}
+
+/// Represents one AI model/agent working on a task.
+/// Each agent has its own worktree and OpenCode session.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAgent {
+    /// Unique ID within task (e.g., "agent-1")
+    pub id: String,
+    /// Model ID (e.g., "claude-sonnet-4")
+    pub model_id: String,
+    /// Provider ID (e.g., "anthropic")
+    pub provider_id: String,
+    /// Override task's default agent type
+    pub agent_type: Option<String>,
+    /// Full path to agent's worktree
+    pub worktree_path: String,
+    /// OpenCode session ID
+    pub session_id: Option<String>,
+    /// Current status
+    pub status: AgentStatus,
+    /// Whether this agent's output was accepted as the winner
+    pub accepted: bool,
+    /// Timestamp when agent was created (milliseconds since epoch)
+    pub created_at: i64,
+    /// Port first assigned to this agent's OpenCode server, reused on restart when free
+    #[serde(default)]
+    pub preferred_port: Option<u16>,
+    /// Review comments left on this agent's diff
+    #[serde(default)]
+    pub review_comments: Vec<ReviewComment>,
+    /// Timestamp when the agent's OpenCode server was last started (milliseconds since epoch)
+    #[serde(default)]
+    pub last_started_at: Option<i64>,
+    /// Agent this one was forked from, continuing its work
+    #[serde(default)]
+    pub forked_from: Option<String>,
+    /// Set when the agent was paused for outgrowing the task's disk quota;
+    /// its worktree needs a review before it runs again
+    #[serde(default)]
+    pub quota_exceeded: Option<DiskQuotaExceeded>,
+}
+
+/// An agent worktree found over its task's disk quota.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskQuotaExceeded {
+    /// Size of the worktree when measured, in bytes
+    pub size_bytes: u64,
+    /// The quota at the time, in bytes
+    pub limit_bytes: u64,
+    /// Timestamp of the measurement (milliseconds since epoch)
+    pub detected_at: i64,
+}
+
+/// Kind of a task timeline entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimelineEntryKind {
+    /// Write audit found no writes outside the agent's worktree
+    WriteAudit,
+    /// Write audit found writes outside the agent's worktree
+    WriteViolation,
+    /// A pipeline step passed or was skipped
+    PipelineStep,
+    /// A pipeline step failed, stopping the pipeline
+    PipelineStepFailed,
+    /// An automatic accept was scheduled, carried out or cancelled
+    AutoAccept,
+    /// A relay agent's accepted work was handed to the next agent
+    RelayHandOff,
+    /// An agent was paused for outgrowing the disk quota
+    DiskQuotaExceeded,
+    /// An agent server started with a sandbox the OS does not enforce
+    SandboxUnenforced,
+}
+
+/// An entry in a task's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEntry {
+    /// Unique entry ID (UUID)
+    pub id: String,
+    /// Timestamp of the entry (milliseconds since epoch)
+    pub timestamp: i64,
+    /// Agent the entry refers to, if any
+    pub agent_id: Option<String>,
+    pub kind: TimelineEntryKind,
+    /// Short description
+    pub message: String,
+    /// Additional lines (e.g. offending paths)
+    #[serde(default)]
+    pub details: Vec<String>,
+}
+
+/// A file modified outside an agent's worktree during its run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteViolation {
+    pub path: String,
+    /// Modification time (milliseconds since epoch)
+    pub modified_at: i64,
+}
+
+/// Result of `audit_agent_writes`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteAuditReport {
+    pub agent_id: String,
+    /// Start of the audited window (milliseconds since epoch)
+    pub since: i64,
+    /// Directories that were scanned
+    pub scanned_roots: Vec<String>,
+    pub violations: Vec<WriteViolation>,
+    /// Whether the violation list was cut off
+    pub truncated: bool,
+}
+
+/// Kind of a ref the app created for a task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskArtifactKind {
+    /// Uncommitted changes the task started from (`refs/aristar/snapshots/`)
+    Snapshot,
+    /// Uncommitted changes an agent was forked from (`refs/aristar/checkpoints/`)
+    Checkpoint,
+}
+
+/// A ref the app created for a task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskArtifact {
+    pub ref_name: String,
+    pub kind: TaskArtifactKind,
+    /// Agent a checkpoint was forked into
+    pub agent_id: Option<String>,
+    pub commit: String,
+    /// Accepted work started from it (kept by `keep_accepted`)
+    pub accepted: bool,
+    /// Its commit is already on the task's source branch
+    pub published: bool,
+}
+
+/// Result of `cleanup_task_artifacts`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskArtifactCleanup {
+    pub deleted: Vec<TaskArtifact>,
+    pub kept: Vec<TaskArtifact>,
+}
+
+/// A reference file (design doc, spec) attached to a task.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextFile {
+    /// File name inside `.aristar/context/`
+    pub name: String,
+    /// Path the file was attached from
+    pub source_path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// Timestamp when the file was attached (milliseconds since epoch)
+    pub added_at: i64,
+}
+
+/// Sandbox options for a task's agent servers.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxConfig {
+    /// Launch agent servers sandboxed
+    pub enabled: bool,
+    /// Hosts reachable through the sandbox proxy (e.g. "api.anthropic.com", "*.github.com")
+    #[serde(default)]
+    pub allowed_hosts: Vec<String>,
+    /// Extra absolute paths agents may write to, besides their worktree
+    #[serde(default)]
+    pub writable_paths: Vec<String>,
+    /// Where the OS can't enforce the sandbox (no `sandbox-exec`), start agent
+    /// servers with a restricted environment only instead of refusing
+    #[serde(default)]
+    pub allow_unenforced: bool,
+}
+
+/// Kind of a pipeline step.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PipelineStepKind {
+    /// Run a shell command in each completed agent's worktree
+    Verify,
+    /// Score completed agents from verification results and review comments
+    Score,
+    /// Accept the top-scoring agent if its score reaches a threshold
+    AutoAccept,
+    /// Publish the accepted agent onto the task's source branch
+    Publish,
+}
+
+/// A pipeline step. Only the fields of the step's kind are used.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStep {
+    pub kind: PipelineStepKind,
+    /// Command for `verify`, run with `bash -c` (e.g. "cargo test")
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Minimum score (0-100) for `auto-accept`; defaults to 100
+    #[serde(default)]
+    pub threshold: Option<u32>,
+    /// Strategy for `publish`; defaults like `publish_agent`
+    #[serde(default)]
+    pub strategy: Option<MergeStrategy>,
+}
+
+/// Named, ordered steps run once a task's agents have finished.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pipeline {
+    pub name: String,
+    pub steps: Vec<PipelineStep>,
+}
+
+/// Status of a pipeline step or of a whole pipeline run.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PipelineStatus {
+    #[default]
+    Pending,
+    Running,
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Progress of one pipeline step.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineStepRun {
+    pub kind: PipelineStepKind,
+    pub status: PipelineStatus,
+    /// Timestamp when the step started (milliseconds since epoch)
+    pub started_at: Option<i64>,
+    /// Timestamp when the step finished (milliseconds since epoch)
+    pub finished_at: Option<i64>,
+    /// Outcome, e.g. "2 of 3 agents passed"
+    pub message: Option<String>,
+}
+
+/// Result of running a `verify` command in one agent's worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentVerification {
+    pub agent_id: String,
+    pub command: String,
+    pub passed: bool,
+    /// Exit code (`None` when killed by a signal or not started)
+    pub exit_code: Option<i32>,
+    /// Last lines of the combined output
+    pub output: String,
+}
+
+/// Score of a completed agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentScore {
+    pub agent_id: String,
+    /// 0-100
+    pub score: u32,
+    pub checks_passed: usize,
+    pub checks_total: usize,
+    /// Unresolved review comments, each costing 10 points
+    pub open_comments: usize,
+}
+
+/// State of a task's latest pipeline run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PipelineRun {
+    /// Name of the pipeline that ran
+    pub pipeline: String,
+    /// `running`, `passed` or `failed`
+    pub status: PipelineStatus,
+    /// Timestamp when the run started (milliseconds since epoch)
+    pub started_at: i64,
+    /// Timestamp when the run finished (milliseconds since epoch)
+    pub finished_at: Option<i64>,
+    /// One entry per pipeline step, in order
+    pub steps: Vec<PipelineStepRun>,
+    pub verifications: Vec<AgentVerification>,
+    pub scores: Vec<AgentScore>,
+}
+
+/// When to accept an agent automatically after a pipeline run. At least one
+/// rule must be set; the sole-passer rule is checked first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAcceptPolicy {
+    /// Accept the only completed agent that passed every verification
+    #[serde(default)]
+    pub sole_passer: bool,
+    /// Accept the top scorer if its score is at least this (0-100) and no
+    /// other agent has the same score
+    #[serde(default)]
+    pub min_score: Option<u32>,
+    /// Seconds to wait before accepting, during which it can be cancelled
+    #[serde(default)]
+    pub delay_secs: u64,
+    /// Remove the other agents and their worktrees after accepting
+    #[serde(default)]
+    pub cleanup: bool,
+}
+
+/// An automatic accept waiting for its delay to pass.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingAutoAccept {
+    pub agent_id: String,
+    /// Why the agent was picked, e.g. "only agent to pass verification"
+    pub reason: String,
+    /// When the accept fires (milliseconds since epoch)
+    pub due_at: i64,
+}
+
+/// Payload of the `auto-accept-scheduled` and `auto-accepted` events.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoAcceptEvent {
+    pub task_id: String,
+    pub agent_id: String,
+    pub reason: String,
+    pub due_at: i64,
+}
+
+/// A task represents a goal/prompt with multiple agents working on it.
+/// Each task has its own folder with agent worktrees inside.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Unique 8-char hash (e.g., "a1b2c3d4")
+    pub id: String,
+    /// User-friendly name
+    pub name: String,
+    /// Source type: "branch" or "commit"
+    pub source_type: String,
+    /// Source branch name (when source_type is "branch")
+    pub source_branch: Option<String>,
+    /// Source commit hash (when source_type is "commit")
+    pub source_commit: Option<String>,
+    /// Original repository path
+    pub source_repo_path: String,
+    /// Default agent type for all agents (e.g., "build")
+    pub agent_type: String,
+    /// Whether agents work in parallel or as a relay
+    #[serde(default)]
+    pub mode: TaskMode,
+    /// Current task status
+    pub status: TaskStatus,
+    /// Timestamp when task was created (milliseconds since epoch)
+    pub created_at: i64,
+    /// Timestamp when task was last updated (milliseconds since epoch)
+    pub updated_at: i64,
+    /// List of agents working on this task
+    pub agents: Vec<TaskAgent>,
+    /// Pinned OpenCode version (managed binary); None uses the newest available
+    #[serde(default)]
+    pub opencode_version: Option<String>,
+    /// Human-readable summary of the result (e.g. from `generate_change_summary`)
+    #[serde(default)]
+    pub result_summary: Option<String>,
+    /// Sandbox options applied when starting agent servers
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    /// Timeline of notable task events (e.g. write audits), oldest first
+    #[serde(default)]
+    pub timeline: Vec<TimelineEntry>,
+    /// Snapshot commit of the source repo's uncommitted changes, applied to every
+    /// agent worktree (kept reachable by `refs/aristar/snapshots/{task-id}`)
+    #[serde(default)]
+    pub snapshot_commit: Option<String>,
+    /// Reference files copied into every agent worktree's `.aristar/context/`
+    #[serde(default)]
+    pub context_files: Vec<ContextFile>,
+    /// Folders agent worktrees are restricted to (sparse checkout); empty
+    /// checks out the whole repository
+    #[serde(default)]
+    pub sparse_paths: Vec<String>,
+    /// Steps run automatically once all agents have finished
+    #[serde(default)]
+    pub pipeline: Option<Pipeline>,
+    /// Latest pipeline run
+    #[serde(default)]
+    pub pipeline_run: Option<PipelineRun>,
+    /// Automatic accept after pipeline runs
+    #[serde(default)]
+    pub auto_accept: Option<AutoAcceptPolicy>,
+    /// Automatic accept waiting for its delay to pass
+    #[serde(default)]
+    pub pending_auto_accept: Option<PendingAutoAccept>,
+    /// Largest size each agent worktree may grow to, in megabytes; `None`
+    /// leaves agents unwatched
+    #[serde(default)]
+    pub disk_quota_mb: Option<u64>,
+    /// How each agent fared, recorded when a winner was accepted so it
+    /// outlives the cleanup of the other agents
+    #[serde(default)]
+    pub agent_outcomes: Vec<AgentOutcome>,
+}
+
+/// Model selection for creating agents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSelection {
+    pub provider_id: String,
+    pub model_id: String,
+}
+
+/// One changed file in an agent's diff.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub path: String,
+    /// "added", "modified" or "deleted"
+    pub status: String,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub binary: bool,
+}
+
+/// Changed files grouped by area (top-level folder).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AreaChanges {
+    pub area: String,
+    pub files: Vec<FileChange>,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+/// Heuristic summary of an agent's diff against its base.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSummary {
+    /// Commit the diff was computed against
+    pub base_commit: String,
+    pub files_changed: usize,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub areas: Vec<AreaChanges>,
+    /// Public functions/types added (e.g. "src/lib.rs: fn parse")
+    pub added_apis: Vec<String>,
+    /// Public functions/types removed
+    pub removed_apis: Vec<String>,
+    /// Deleted files and large removals
+    pub notable_deletions: Vec<String>,
+    /// Markdown rendering, suitable for PR descriptions
+    pub markdown: String,
+}
+
+/// How closely the agents' changes to a file agree.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileAgreement {
+    /// Every agent left the file with the same content
+    Identical,
+    /// Every agent changed the file and the patches are alike
+    Similar,
+    /// Only some agents changed the file, or their patches differ
+    Divergent,
+}
+
+/// Agreement between the agents on one changed file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAgreementEntry {
+    pub path: String,
+    pub agreement: FileAgreement,
+    /// Agents whose diff touches the file
+    pub changed_by: Vec<String>,
+    /// Lowest patch similarity between two agents, in percent
+    pub similarity: u8,
+}
+
+/// Per-file agreement of a task's agents, from `compute_agent_agreement`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAgreementReport {
+    /// Agents compared (those with a worktree)
+    pub agent_ids: Vec<String>,
+    /// Files changed by any agent, divergent first
+    pub files: Vec<FileAgreementEntry>,
+    pub identical_count: usize,
+    pub similar_count: usize,
+    pub divergent_count: usize,
+}
+
+/// HTML comparison written by `export_agent_comparison`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentComparisonExport {
+    /// File written
+    pub path: String,
+    /// Agents in the comparison, one column each
+    pub agent_ids: Vec<String>,
+    /// Files changed by any of them
+    pub file_count: usize,
+    pub size_bytes: u64,
+}
+
+/// Size of an agent's diff against the task source.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentDiffStats {
+    pub files_changed: usize,
+    pub insertions: u32,
+    pub deletions: u32,
+}
+
+/// One agent of a task whose winner was accepted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentOutcome {
+    pub agent_id: String,
+    pub provider_id: String,
+    pub model_id: String,
+    pub accepted: bool,
+    /// Score from the task's pipeline run, when it scored the agent
+    pub score: Option<u32>,
+    /// `None` when the worktree was gone or the diff could not be computed
+    pub diff: Option<AgentDiffStats>,
+    /// Timestamp of the accept (milliseconds since epoch)
+    pub recorded_at: i64,
+}
+
+/// How often a model's agents won, from `get_model_leaderboard`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelLeaderboardEntry {
+    pub provider_id: String,
+    pub model_id: String,
+    /// Agents of the model in tasks with an accepted winner
+    pub runs: usize,
+    pub accepted: usize,
+    /// `accepted / runs`, from 0 to 1
+    pub acceptance_rate: f64,
+    /// Average pipeline score of the runs that were scored
+    pub average_score: Option<f64>,
+    /// Average changed lines (insertions plus deletions) of the runs with a
+    /// recorded diff
+    pub average_diff_lines: Option<f64>,
+}
+
+/// One finished piece of an agent's OpenCode session, a line of its transcript.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptEntry {
+    /// Message or part ID from OpenCode
+    pub id: String,
+    /// "message", "text", "reasoning", "tool" or "error"
+    pub kind: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub message_id: Option<String>,
+    /// Timestamp when recorded (milliseconds since epoch)
+    pub timestamp: i64,
+    /// Message info, part or error as sent by OpenCode
+    pub data: serde_json::Value,
+}
+
+/// Entries `start..end` of a transcript; `end: None` reads to the end.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRange {
+    pub start: usize,
+    #[serde(default)]
+    pub end: Option<usize>,
+}
+
+/// Part of an agent's transcript, from `get_agent_transcript`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTranscript {
+    pub agent_id: String,
+    /// Number of entries in the whole transcript
+    pub total: usize,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+/// Per-agent overview of a task, used for comparing agents at a glance.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentSummary {
+    pub agent_id: String,
+    pub model_id: String,
+    pub provider_id: String,
+    pub status: AgentStatus,
+    pub accepted: bool,
+    /// Number of unresolved review comments
+    pub open_comments: usize,
+    /// Total number of review comments
+    pub total_comments: usize,
+    /// When the agent was created, e.g. "2 days ago"
+    pub created_relative: String,
+    /// When the agent's server was last started, e.g. "5 minutes ago"
+    pub last_started_relative: Option<String>,
+    /// Time since the last start while the agent is running, e.g. "1 h 5 min"
+    pub running_for: Option<String>,
+}
+
+/// Payload of the `agent-port-changed` event, emitted when an agent's
+/// OpenCode server could not get its preferred port back.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentPortChangedEvent {
+    pub task_id: String,
+    pub agent_id: String,
+    pub previous_port: u16,
+    pub port: u16,
+}
+
+/// Payload of the `agent-status-changed` event.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentStatusChangedEvent {
+    pub task_id: String,
+    pub agent_id: String,
+    pub status: AgentStatus,
+}
+
+/// Payload of the `tasks-loaded` event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TasksLoadedEvent {
+    pub task_count: usize,
+    /// Time spent reading and parsing `tasks.json`
+    pub duration_ms: u64,
+}
+
+/// Payload of the `agent-quota-exceeded` event.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentQuotaExceededEvent {
+    pub task_id: String,
+    pub agent_id: String,
+    pub worktree_path: String,
+    pub exceeded: DiskQuotaExceeded,
+}
+
+/// Task settings a schedule creates each run from (mirrors `create_task`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskPreset {
+    /// Task name; each run appends its date
+    pub name: String,
+    pub source_type: String,
+    pub source_branch: Option<String>,
+    pub source_commit: Option<String>,
+    pub source_repo_path: String,
+    pub agent_type: String,
+    pub models: Vec<ModelSelection>,
+    #[serde(default)]
+    pub opencode_version: Option<String>,
+    /// Prompt the frontend sends to the agents of each created task
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Folders the agent worktrees are restricted to; empty for a full checkout
+    #[serde(default)]
+    pub sparse_paths: Vec<String>,
+}
+
+/// A task preset saved for reuse, e.g. across repositories with
+/// `create_task_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedTaskPreset {
+    /// Unique preset ID (UUID)
+    pub id: String,
+    pub preset: TaskPreset,
+    /// Timestamp when preset was saved (milliseconds since epoch)
+    pub created_at: i64,
+}
+
+/// Outcome of creating a batch task in one repository.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTaskResult {
+    pub repo_path: String,
+    /// Created task, if creation succeeded
+    pub task_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// An agent worktree `repoint_tasks` could not repair.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeRepairFailure {
+    pub path: String,
+    pub error: String,
+}
+
+/// Result of `repoint_tasks`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepointTasksReport {
+    /// Resolved new repository path stored in the tasks
+    pub new_repo_path: String,
+    /// Tasks now pointing at the new path
+    pub task_ids: Vec<String>,
+    /// Agent worktrees repaired
+    pub repaired: Vec<String>,
+    pub failed: Vec<WorktreeRepairFailure>,
+}
+
+/// Summary of `create_task_batch`, one result per repository.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTaskSummary {
+    pub preset_id: String,
+    /// Prompt the frontend sends to the agents of each created task
+    pub prompt: Option<String>,
+    pub results: Vec<BatchTaskResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+/// A recurring task run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    /// Unique schedule ID (UUID)
+    pub id: String,
+    pub name: String,
+    /// Cron expression in local time (see `cron.rs`)
+    pub cron: String,
+    pub preset: TaskPreset,
+    pub paused: bool,
+    /// Timestamp when schedule was created (milliseconds since epoch)
+    pub created_at: i64,
+    /// Next due run (milliseconds since epoch)
+    pub next_run_at: Option<i64>,
+    /// Last run (milliseconds since epoch)
+    pub last_run_at: Option<i64>,
+    /// Task created by the last run
+    pub last_task_id: Option<String>,
+    /// Error of the last run, if it failed
+    pub last_error: Option<String>,
+}
+
+/// Payload of the `schedule-triggered` event.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleTriggeredEvent {
+    pub schedule_id: String,
+    /// Created task, if the run succeeded
+    pub task_id: Option<String>,
+    pub prompt: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Persistent storage for tasks.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct TaskStoreData {
+    pub tasks: Vec<Task>,
+    /// Recurring task schedules
+    #[serde(default)]
+    pub schedules: Vec<Schedule>,
+    /// Saved task presets
+    #[serde(default)]
+    pub presets: Vec<SavedTaskPreset>,
+}
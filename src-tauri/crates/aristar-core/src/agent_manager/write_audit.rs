@@ -0,0 +1,290 @@
+//! Post-run audit of agent filesystem writes.
+//!
+//! Without a sandbox nothing stops an agent from writing outside its worktree.
+//! The audit scans the source repository and `$HOME` for files modified since
+//! the agent's server was last started, skipping the agent's own worktree and
+//! paths the app and OpenCode write to. Findings are recorded in the task timeline.
+//!
+//! This is a heuristic: writes by other processes during the window are
+//! reported too, and deletions are not detected.
+
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::core::{get_aristar_worktrees_base, is_read_only};
+
+use super::sandbox::{git_common_dir, OPENCODE_DATA_DIRS};
+use super::store::TaskManagerState;
+use super::task_operations::{get_task_impl, push_timeline_entry};
+use super::types::{TimelineEntry, TimelineEntryKind, WriteAuditReport, WriteViolation};
+
+/// Scan depth for the source repository (effectively unlimited).
+pub const SOURCE_REPO_SCAN_DEPTH: usize = 64;
+
+/// Scan depth below `$HOME`.
+pub const HOME_SCAN_DEPTH: usize = 4;
+
+/// Maximum number of violations reported per audit.
+pub const MAX_VIOLATIONS: usize = 200;
+
+/// Maximum number of paths listed in a timeline entry.
+const MAX_TIMELINE_PATHS: usize = 20;
+
+/// Cache and package directories skipped wherever they appear.
+const IGNORED_DIR_NAMES: [&str; 8] = [
+    "node_modules",
+    "Library",
+    ".Trash",
+    ".cache",
+    ".npm",
+    ".cargo",
+    ".rustup",
+    ".pnpm-store",
+];
+
+/// A directory to scan and how deep to descend.
+#[derive(Debug, Clone)]
+pub struct AuditRoot {
+    pub path: PathBuf,
+    pub max_depth: usize,
+}
+
+/// Default roots and exclusions for an agent's audit.
+pub fn default_audit_scope(
+    worktree_path: &Path,
+    source_repo_path: &Path,
+    home: Option<&Path>,
+) -> (Vec<AuditRoot>, Vec<PathBuf>) {
+    let mut roots = vec![AuditRoot {
+        path: source_repo_path.to_path_buf(),
+        max_depth: SOURCE_REPO_SCAN_DEPTH,
+    }];
+    let mut excluded = vec![worktree_path.to_path_buf(), get_aristar_worktrees_base()];
+    // Commits made in the worktree are written to the repository's git dir
+    excluded.extend(git_common_dir(worktree_path));
+
+    if let Some(home) = home {
+        roots.push(AuditRoot {
+            path: home.to_path_buf(),
+            max_depth: HOME_SCAN_DEPTH,
+        });
+        excluded.extend(OPENCODE_DATA_DIRS.iter().map(|d| home.join(d)));
+    }
+
+    (roots, excluded)
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    let duration = modified.duration_since(UNIX_EPOCH).ok()?;
+    Some(duration.as_millis() as i64)
+}
+
+/// Collect files below `root` modified at or after `since`.
+/// Symlinks are not followed. Returns `false` once `limit` is reached.
+pub fn scan_writes_since(
+    root: &AuditRoot,
+    since: i64,
+    excluded: &[PathBuf],
+    limit: usize,
+    out: &mut Vec<WriteViolation>,
+) -> bool {
+    let mut stack = vec![(root.path.clone(), 0usize)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if excluded.iter().any(|ex| path.starts_with(ex)) {
+                continue;
+            }
+            let file_type = match entry.file_type() {
+                Ok(t) => t,
+                Err(_) => continue,
+            };
+
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                let ignored = IGNORED_DIR_NAMES.iter().any(|n| name.to_str() == Some(*n));
+                if !ignored && depth + 1 < root.max_depth {
+                    stack.push((path, depth + 1));
+                }
+            } else if file_type.is_file() {
+                let modified_at = match entry.metadata().ok().and_then(|m| modified_millis(&m)) {
+                    Some(ms) => ms,
+                    None => continue,
+                };
+                if modified_at >= since {
+                    if out.len() >= limit {
+                        return false;
+                    }
+                    out.push(WriteViolation {
+                        path: path.to_string_lossy().to_string(),
+                        modified_at,
+                    });
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// Scan `roots` for writes since `since`. Paths are canonicalized first and
+/// each root is excluded from later ones so overlapping roots report once.
+pub fn audit_writes(
+    agent_id: &str,
+    roots: &[AuditRoot],
+    excluded: &[PathBuf],
+    since: i64,
+) -> WriteAuditReport {
+    let mut excluded: Vec<PathBuf> = excluded
+        .iter()
+        .map(|p| p.canonicalize().unwrap_or_else(|_| p.clone()))
+        .collect();
+    let mut violations = Vec::new();
+    let mut scanned_roots = Vec::new();
+    let mut truncated = false;
+
+    for root in roots {
+        let root = AuditRoot {
+            path: root
+                .path
+                .canonicalize()
+                .unwrap_or_else(|_| root.path.clone()),
+            max_depth: root.max_depth,
+        };
+        if !root.path.is_dir() || excluded.iter().any(|ex| root.path.starts_with(ex)) {
+            continue;
+        }
+
+        scanned_roots.push(root.path.to_string_lossy().to_string());
+        if !scan_writes_since(&root, since, &excluded, MAX_VIOLATIONS, &mut violations) {
+            truncated = true;
+            break;
+        }
+        excluded.push(root.path);
+    }
+
+    violations.sort_by(|a, b| a.path.cmp(&b.path));
+
+    WriteAuditReport {
+        agent_id: agent_id.to_string(),
+        since,
+        scanned_roots,
+        violations,
+        truncated,
+    }
+}
+
+/// Timeline entry describing an audit result.
+pub fn timeline_entry_for_audit(report: &WriteAuditReport, now: i64) -> TimelineEntry {
+    let (kind, message) = if report.violations.is_empty() {
+        (
+            TimelineEntryKind::WriteAudit,
+            format!("No writes outside {}'s worktree", report.agent_id),
+        )
+    } else {
+        (
+            TimelineEntryKind::WriteViolation,
+            format!(
+                "{} wrote {}{} file(s) outside its worktree",
+                report.agent_id,
+                report.violations.len(),
+                if report.truncated { "+" } else { "" }
+            ),
+        )
+    };
+
+    let mut details: Vec<String> = report
+        .violations
+        .iter()
+        .take(MAX_TIMELINE_PATHS)
+        .map(|v| v.path.clone())
+        .collect();
+    if report.violations.len() > MAX_TIMELINE_PATHS {
+        details.push(format!(
+            "... and {} more",
+            report.violations.len() - MAX_TIMELINE_PATHS
+        ));
+    }
+
+    TimelineEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: now,
+        agent_id: Some(report.agent_id.clone()),
+        kind,
+        message,
+        details,
+    }
+}
+
+/// Append an audit result to the task timeline.
+pub fn record_write_audit_impl(
+    state: &TaskManagerState,
+    task_id: &str,
+    report: &WriteAuditReport,
+) -> Result<(), String> {
+    {
+        let mut store = state.store.lock().map_err(|e| e.to_string())?;
+        let task = store
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == task_id)
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+
+        let now = Utc::now().timestamp_millis();
+        push_timeline_entry(task, timeline_entry_for_audit(report, now));
+        task.updated_at = now;
+    }
+
+    state.save()
+}
+
+/// Audit an agent's writes since its server was last started (or since the
+/// agent was created) and record the result in the task timeline.
+/// In read-only mode the report is returned without being recorded.
+pub async fn audit_agent_writes_async(
+    state: &TaskManagerState,
+    task_id: String,
+    agent_id: String,
+) -> Result<WriteAuditReport, String> {
+    let task = get_task_impl(state, &task_id)?;
+    let agent = task
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id)
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    let since = agent.last_started_at.unwrap_or(agent.created_at);
+    let worktree_path = PathBuf::from(&agent.worktree_path);
+    let source_repo_path = PathBuf::from(&task.source_repo_path);
+    let audited_agent = agent_id.clone();
+
+    let report = tokio::task::spawn_blocking(move || {
+        let home = dirs::home_dir();
+        let (roots, excluded) =
+            default_audit_scope(&worktree_path, &source_repo_path, home.as_deref());
+        audit_writes(&audited_agent, &roots, &excluded, since)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    println!(
+        "[write_audit] {} in task {}: {} violation(s)",
+        agent_id,
+        task_id,
+        report.violations.len()
+    );
+
+    if !is_read_only() {
+        record_write_audit_impl(state, &task_id, &report)?;
+    }
+    Ok(report)
+}
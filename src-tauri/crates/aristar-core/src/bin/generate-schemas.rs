@@ -0,0 +1,27 @@
+//! Write the JSON schema and TypeScript definitions of the IPC types.
+//!
+//! Usage: `cargo run -p aristar-core --bin generate-schemas [output-dir]`.
+//! The output folder defaults to `src/store/generated/` of the frontend.
+
+use std::path::PathBuf;
+
+fn main() {
+    let dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../../src/store/generated")
+        });
+
+    match aristar_core::core::schema::write_generated_files(&dir) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! IPC schema versioning between the frontend and the Rust core.
+//!
+//! `API_VERSION` is bumped whenever a command result or event payload changes
+//! shape in a way an older frontend would misread (a renamed or removed field,
+//! a changed type). Adding an optional field does not need a bump.
+//!
+//! On startup the frontend calls `check_api_compatibility` with the version it
+//! was built against and shows the error instead of running with missing
+//! fields. Frontends between `MIN_FRONTEND_API_VERSION` and `API_VERSION` are
+//! accepted; that is where compatibility shims for older payloads belong.
+//! Every event payload is wrapped in `Versioned`, adding `schemaVersion`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Current IPC schema version of the backend.
+///
+/// 2: worktree, repository, settings, status and sync types serialized as camelCase.
+pub const API_VERSION: u32 = 2;
+
+/// Oldest frontend schema version the backend still serves. Version 1
+/// frontends read snake_case fields, so there is nothing to shim.
+pub const MIN_FRONTEND_API_VERSION: u32 = 2;
+
+/// Result of `get_api_version`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiVersionInfo {
+    pub api_version: u32,
+    pub min_frontend_api_version: u32,
+    /// App version from Cargo.toml
+    pub app_version: String,
+}
+
+impl ApiVersionInfo {
+    pub fn current() -> Self {
+        Self {
+            api_version: API_VERSION,
+            min_frontend_api_version: MIN_FRONTEND_API_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// An event payload tagged with the schema version it was written for.
+/// The payload's own fields stay at the top level.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Versioned<T> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(payload: T) -> Self {
+        Self {
+            schema_version: API_VERSION,
+            payload,
+        }
+    }
+}
+
+/// Check whether a frontend built against `frontend_version` can talk to this
+/// backend, naming the side that needs rebuilding when it can't.
+pub fn check_compatibility(frontend_version: u32) -> Result<ApiVersionInfo, String> {
+    if frontend_version > API_VERSION {
+        return Err(format!(
+            "Frontend expects API v{} but the backend provides v{}; rebuild the Rust core",
+            frontend_version, API_VERSION
+        ));
+    }
+    if frontend_version < MIN_FRONTEND_API_VERSION {
+        return Err(format!(
+            "Frontend API v{} is no longer supported (backend v{} needs at least v{}); rebuild the frontend",
+            frontend_version, API_VERSION, MIN_FRONTEND_API_VERSION
+        ));
+    }
+    Ok(ApiVersionInfo::current())
+}
+
+/// Check the frontend's version when it starts, logging when shims are needed.
+pub fn register_frontend(frontend_version: u32) -> Result<ApiVersionInfo, String> {
+    let info = check_compatibility(frontend_version)?;
+    if frontend_version < API_VERSION {
+        println!(
+            "[api] Frontend uses API v{}, backend v{}; serving compatibility shims",
+            frontend_version, API_VERSION
+        );
+    }
+    Ok(info)
+}
@@ -0,0 +1,113 @@
+//! Names of the events exchanged with the frontend.
+//!
+//! Every event name is declared here once and listed in `APP_EVENTS`, so
+//! consumers of "all events" (the event socket) can't fall behind when an event
+//! is added. The modules emitting an event re-export its constant.
+
+/// Event emitted when an agent's OpenCode server moved to another port.
+pub const AGENT_PORT_EVENT: &str = "agent-port-changed";
+
+/// Event emitted when an agent's status changes (e.g. to completed).
+pub const AGENT_STATUS_EVENT: &str = "agent-status-changed";
+
+/// Event emitted when an agent was paused for outgrowing its quota.
+pub const AGENT_QUOTA_EVENT: &str = "agent-quota-exceeded";
+
+/// Event emitted when an automatic accept is scheduled.
+pub const AUTO_ACCEPT_SCHEDULED_EVENT: &str = "auto-accept-scheduled";
+
+/// Event emitted when an automatic accept was carried out.
+pub const AUTO_ACCEPTED_EVENT: &str = "auto-accepted";
+
+/// Event the frontend emits to cancel a pending automatic accept.
+pub const AUTO_ACCEPT_CANCEL_EVENT: &str = "auto-accept-cancel";
+
+/// Event emitted after a schedule ran.
+pub const SCHEDULE_EVENT: &str = "schedule-triggered";
+
+/// Event emitted once the task store has been read from disk.
+pub const TASKS_LOADED_EVENT: &str = "tasks-loaded";
+
+/// Event emitted when throttling starts or ends.
+pub const POWER_THROTTLED_EVENT: &str = "power-throttled";
+
+/// Name of the event emitted when a repository becomes (un)available.
+pub const AVAILABILITY_EVENT: &str = "repository-availability-changed";
+
+/// Event emitted when a repository's remote default branch changed.
+pub const DEFAULT_BRANCH_EVENT: &str = "default-branch-changed";
+
+/// Event emitted when a repository's branch cleanup suggestions changed.
+pub const BRANCH_CLEANUP_EVENT: &str = "branch-cleanup-suggested";
+
+/// Event emitted when an expired lock was lifted.
+pub const WORKTREE_LOCK_EXPIRED_EVENT: &str = "worktree-lock-expired";
+
+/// Event emitted after a background cleanup found stale worktrees.
+pub const STALE_WORKTREES_EVENT: &str = "stale-worktrees";
+
+/// Event emitted while LFS objects are downloaded into a new worktree.
+pub const LFS_PULL_PROGRESS_EVENT: &str = "lfs-pull-progress";
+
+/// Event emitted when a worktree operation has to wait for another one.
+pub const REPO_OPERATION_QUEUED_EVENT: &str = "repo-operation-queued";
+
+/// Event emitted when a remote operation failed on an unknown SSH host.
+pub const SSH_HOST_KEY_UNKNOWN_EVENT: &str = "ssh-host-key-unknown";
+
+/// Name of the event carrying `GlobalStatus` to the frontend.
+pub const GLOBAL_STATUS_EVENT: &str = "global-status";
+
+/// An event name and who gets to see it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppEvent {
+    pub name: &'static str,
+    /// Emitted by the frontend for the backend rather than the other way round
+    pub from_frontend: bool,
+    /// Sent to event socket clients too (see `workspace::event_socket`)
+    pub forwarded: bool,
+}
+
+const fn emitted(name: &'static str, forwarded: bool) -> AppEvent {
+    AppEvent {
+        name,
+        from_frontend: false,
+        forwarded,
+    }
+}
+
+/// Every event exchanged with the frontend.
+pub const APP_EVENTS: &[AppEvent] = &[
+    emitted(AGENT_STATUS_EVENT, true),
+    emitted(AGENT_PORT_EVENT, true),
+    emitted(AGENT_QUOTA_EVENT, true),
+    emitted(AUTO_ACCEPT_SCHEDULED_EVENT, true),
+    emitted(AUTO_ACCEPTED_EVENT, true),
+    emitted(SCHEDULE_EVENT, true),
+    emitted(TASKS_LOADED_EVENT, true),
+    emitted(POWER_THROTTLED_EVENT, true),
+    emitted(AVAILABILITY_EVENT, true),
+    emitted(DEFAULT_BRANCH_EVENT, true),
+    emitted(BRANCH_CLEANUP_EVENT, true),
+    emitted(WORKTREE_LOCK_EXPIRED_EVENT, true),
+    emitted(STALE_WORKTREES_EVENT, true),
+    emitted(GLOBAL_STATUS_EVENT, true),
+    // Progress of a single operation, only meaningful to the window showing it
+    emitted(LFS_PULL_PROGRESS_EVENT, false),
+    emitted(REPO_OPERATION_QUEUED_EVENT, false),
+    // Answered through the app's host key prompt
+    emitted(SSH_HOST_KEY_UNKNOWN_EVENT, false),
+    AppEvent {
+        name: AUTO_ACCEPT_CANCEL_EVENT,
+        from_frontend: true,
+        forwarded: false,
+    },
+];
+
+/// Names of the events forwarded to event socket clients.
+pub fn forwarded_events() -> impl Iterator<Item = &'static str> {
+    APP_EVENTS
+        .iter()
+        .filter(|event| event.forwarded)
+        .map(|event| event.name)
+}
@@ -0,0 +1,186 @@
+//! Humanized dates and durations for reports.
+//!
+//! Reports and exports carry strings like "2 days ago" or "1 h 5 min" produced
+//! here, so they read the same wherever they are rendered. The locale comes from
+//! `settings.locale`, falling back to `LANG` and then English.
+
+/// Languages humanized strings are available in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+/// A relative time unit, smallest to largest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+const MINUTE_MS: i64 = 60 * 1000;
+const HOUR_MS: i64 = 60 * MINUTE_MS;
+const DAY_MS: i64 = 24 * HOUR_MS;
+const MONTH_MS: i64 = 30 * DAY_MS;
+const YEAR_MS: i64 = 365 * DAY_MS;
+
+impl Locale {
+    /// Parse a locale tag such as `de`, `de-DE` or `de_DE.UTF-8`.
+    pub fn parse(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase();
+        match language.as_str() {
+            "en" => Some(Self::En),
+            "de" => Some(Self::De),
+            "fr" => Some(Self::Fr),
+            "es" => Some(Self::Es),
+            _ => None,
+        }
+    }
+
+    fn unit_name(self, unit: Unit, count: i64) -> &'static str {
+        let plural = count != 1;
+        match (self, unit, plural) {
+            (Self::En, Unit::Minute, false) => "minute",
+            (Self::En, Unit::Minute, true) => "minutes",
+            (Self::En, Unit::Hour, false) => "hour",
+            (Self::En, Unit::Hour, true) => "hours",
+            (Self::En, Unit::Day, false) => "day",
+            (Self::En, Unit::Day, true) => "days",
+            (Self::En, Unit::Month, false) => "month",
+            (Self::En, Unit::Month, true) => "months",
+            (Self::En, Unit::Year, false) => "year",
+            (Self::En, Unit::Year, true) => "years",
+            // German uses the dative after "vor"/"in"
+            (Self::De, Unit::Minute, false) => "Minute",
+            (Self::De, Unit::Minute, true) => "Minuten",
+            (Self::De, Unit::Hour, false) => "Stunde",
+            (Self::De, Unit::Hour, true) => "Stunden",
+            (Self::De, Unit::Day, false) => "Tag",
+            (Self::De, Unit::Day, true) => "Tagen",
+            (Self::De, Unit::Month, false) => "Monat",
+            (Self::De, Unit::Month, true) => "Monaten",
+            (Self::De, Unit::Year, false) => "Jahr",
+            (Self::De, Unit::Year, true) => "Jahren",
+            (Self::Fr, Unit::Minute, false) => "minute",
+            (Self::Fr, Unit::Minute, true) => "minutes",
+            (Self::Fr, Unit::Hour, false) => "heure",
+            (Self::Fr, Unit::Hour, true) => "heures",
+            (Self::Fr, Unit::Day, false) => "jour",
+            (Self::Fr, Unit::Day, true) => "jours",
+            (Self::Fr, Unit::Month, _) => "mois",
+            (Self::Fr, Unit::Year, false) => "an",
+            (Self::Fr, Unit::Year, true) => "ans",
+            (Self::Es, Unit::Minute, false) => "minuto",
+            (Self::Es, Unit::Minute, true) => "minutos",
+            (Self::Es, Unit::Hour, false) => "hora",
+            (Self::Es, Unit::Hour, true) => "horas",
+            (Self::Es, Unit::Day, false) => "día",
+            (Self::Es, Unit::Day, true) => "días",
+            (Self::Es, Unit::Month, false) => "mes",
+            (Self::Es, Unit::Month, true) => "meses",
+            (Self::Es, Unit::Year, false) => "año",
+            (Self::Es, Unit::Year, true) => "años",
+        }
+    }
+
+    fn just_now(self) -> &'static str {
+        match self {
+            Self::En => "just now",
+            Self::De => "gerade eben",
+            Self::Fr => "à l'instant",
+            Self::Es => "justo ahora",
+        }
+    }
+
+    fn relative(self, amount: &str, past: bool) -> String {
+        match (self, past) {
+            (Self::En, true) => format!("{} ago", amount),
+            (Self::En, false) => format!("in {}", amount),
+            (Self::De, true) => format!("vor {}", amount),
+            (Self::De, false) => format!("in {}", amount),
+            (Self::Fr, true) => format!("il y a {}", amount),
+            (Self::Fr, false) => format!("dans {}", amount),
+            (Self::Es, true) => format!("hace {}", amount),
+            (Self::Es, false) => format!("en {}", amount),
+        }
+    }
+}
+
+/// Locale for humanized strings: the setting if it names a supported language,
+/// otherwise `LANG`, otherwise English.
+pub fn resolve_locale(setting: Option<&str>) -> Locale {
+    setting
+        .and_then(Locale::parse)
+        .or_else(|| {
+            std::env::var("LANG")
+                .ok()
+                .as_deref()
+                .and_then(Locale::parse)
+        })
+        .unwrap_or_default()
+}
+
+fn largest_unit(ms: i64) -> (i64, Unit) {
+    if ms >= YEAR_MS {
+        (ms / YEAR_MS, Unit::Year)
+    } else if ms >= MONTH_MS {
+        (ms / MONTH_MS, Unit::Month)
+    } else if ms >= DAY_MS {
+        (ms / DAY_MS, Unit::Day)
+    } else if ms >= HOUR_MS {
+        (ms / HOUR_MS, Unit::Hour)
+    } else {
+        (ms / MINUTE_MS, Unit::Minute)
+    }
+}
+
+/// Describe `timestamp` relative to `now` (both milliseconds since epoch),
+/// e.g. "2 days ago" or "in 3 hours". Under a minute is "just now".
+pub fn humanize_relative(timestamp: i64, now: i64, locale: Locale) -> String {
+    let delta = now - timestamp;
+    if delta.abs() < MINUTE_MS {
+        return locale.just_now().to_string();
+    }
+
+    let (count, unit) = largest_unit(delta.abs());
+    let amount = format!("{} {}", count, locale.unit_name(unit, count));
+    locale.relative(&amount, delta > 0)
+}
+
+/// Describe a duration in milliseconds with its two largest units, e.g.
+/// "1 h 5 min", "3 d 2 h" or "45 s". Unit abbreviations are the same in all
+/// supported languages except for days.
+pub fn humanize_duration(ms: i64, locale: Locale) -> String {
+    let ms = ms.max(0);
+    let day = match locale {
+        Locale::En => "d",
+        Locale::De => "T",
+        Locale::Fr => "j",
+        Locale::Es => "d",
+    };
+
+    let days = ms / DAY_MS;
+    let hours = ms % DAY_MS / HOUR_MS;
+    let minutes = ms % HOUR_MS / MINUTE_MS;
+    let seconds = ms % MINUTE_MS / 1000;
+
+    if days > 0 {
+        format!("{} {} {} h", days, day, hours)
+    } else if hours > 0 {
+        format!("{} h {} min", hours, minutes)
+    } else if minutes > 0 {
+        format!("{} min {} s", minutes, seconds)
+    } else {
+        format!("{} s", seconds)
+    }
+}
@@ -0,0 +1,121 @@
+//! Write-ahead intent journal for multi-step operations.
+//!
+//! Creating a task or removing a worktree takes several steps (worktrees,
+//! refs, folders, the store). Before the first step an intent is written to
+//! `~/.aristar-worktrees/journal/{id}.json`; the `IntentGuard` returned removes
+//! it when the operation returns, successfully or not. An intent still there at
+//! startup means the app died mid-operation, and `workspace::recovery` rolls it
+//! back or forward.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::get_aristar_worktrees_base;
+
+/// An operation recorded before it starts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum JournalOperation {
+    /// Creating a task: its folder, agent worktrees and snapshot ref
+    #[serde(rename_all = "camelCase")]
+    CreateTask {
+        task_id: String,
+        source_repo_path: String,
+        task_folder: String,
+        worktree_paths: Vec<String>,
+    },
+    /// Removing a worktree from git and from the repository store
+    #[serde(rename_all = "camelCase")]
+    RemoveWorktree {
+        repo_path: String,
+        worktree_path: String,
+    },
+}
+
+/// A journal entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalIntent {
+    pub id: String,
+    pub operation: JournalOperation,
+    /// Milliseconds since epoch
+    pub started_at: i64,
+}
+
+/// Removes its intent from the journal when dropped.
+#[derive(Debug)]
+pub struct IntentGuard {
+    path: PathBuf,
+}
+
+impl Drop for IntentGuard {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            eprintln!("[journal] Failed to remove {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Journal folder: ~/.aristar-worktrees/journal/
+pub fn get_journal_dir() -> PathBuf {
+    get_aristar_worktrees_base().join("journal")
+}
+
+/// Record `operation` in the journal at `dir` before it starts.
+pub fn record_intent_in(dir: &Path, operation: JournalOperation) -> Result<IntentGuard, String> {
+    let intent = JournalIntent {
+        id: uuid::Uuid::new_v4().to_string(),
+        operation,
+        started_at: Utc::now().timestamp_millis(),
+    };
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create journal folder: {}", e))?;
+    let json = serde_json::to_string_pretty(&intent)
+        .map_err(|e| format!("Failed to serialize intent: {}", e))?;
+
+    // Write then rename, so a crash never leaves a half-written intent
+    let path = dir.join(format!("{}.json", intent.id));
+    let tmp = dir.join(format!("{}.json.tmp", intent.id));
+    fs::write(&tmp, json).map_err(|e| format!("Failed to write intent: {}", e))?;
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to write intent: {}", e))?;
+    Ok(IntentGuard { path })
+}
+
+/// Record `operation` in the journal before it starts.
+pub fn record_intent(operation: JournalOperation) -> Result<IntentGuard, String> {
+    record_intent_in(&get_journal_dir(), operation)
+}
+
+/// Intents left in the journal at `dir`, oldest first. Unreadable entries are
+/// logged and skipped.
+pub fn pending_intents_in(dir: &Path) -> Vec<JournalIntent> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut intents: Vec<JournalIntent> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+            parsed
+                .inspect_err(|e| eprintln!("[journal] Skipping {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect();
+    intents.sort_by_key(|i| i.started_at);
+    intents
+}
+
+/// Drop an intent from the journal at `dir` once it has been recovered.
+pub fn discard_intent_in(dir: &Path, id: &str) -> Result<(), String> {
+    match fs::remove_file(dir.join(format!("{}.json", id))) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove intent {}: {}", id, e)),
+    }
+}
@@ -0,0 +1,39 @@
+//! Core module - Shared infrastructure for the application.
+//!
+//! This module contains:
+//! - IPC schema versioning between frontend and backend
+//! - Persistence utilities (store load/save)
+//! - Names of the events exchanged with the frontend
+//! - Path normalization for store lookups
+//! - Humanized dates and durations for reports
+//! - Write-ahead journal of multi-step operations
+//! - Desktop notifications gated by macOS Focus
+//! - Read-only observer mode
+//! - Port reservations shared by OpenCode, dev servers and the event socket
+//! - Redaction of secrets in logs and transcripts
+//! - JSON schema and TypeScript definitions of the IPC types
+//! - Plugins declaring custom actions in TOML manifests
+//! - Shared types (AppSettings)
+//! - System operations (clipboard, finder)
+
+pub mod api_version;
+pub mod events;
+pub mod humanize;
+pub mod journal;
+pub mod notifications;
+pub mod paths;
+pub mod persistence;
+pub mod plugins;
+pub mod ports;
+pub mod read_only;
+pub mod redaction;
+pub mod schema;
+pub mod system;
+pub mod types;
+
+pub use humanize::*;
+pub use paths::*;
+pub use persistence::*;
+pub use read_only::*;
+pub use system::*;
+pub use types::*;
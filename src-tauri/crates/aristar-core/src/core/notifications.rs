@@ -0,0 +1,223 @@
+//! Desktop notifications that respect macOS Focus / Do Not Disturb.
+//!
+//! Before a notification is shown, the Focus state is checked. While a Focus
+//! mode is on, normal notifications (e.g. an agent finished) are held back and
+//! delivered once it ends; urgent ones (e.g. an agent failed) are shown anyway,
+//! and macOS still decides whether they break through. Held-back notifications
+//! are listed by `get_pending_notifications`.
+//!
+//! The Focus state is read from `~/Library/DoNotDisturb/DB/Assertions.json`
+//! (macOS 12+), falling back to the `doNotDisturb` default of older versions.
+//! Reading it may need Full Disk Access; when it can't be read, Focus counts as
+//! off. Notifications are shown with `osascript`.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Interval between checks whether held-back notifications can be delivered.
+pub const PENDING_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Most notifications held back; the oldest are dropped beyond this.
+pub const MAX_PENDING_NOTIFICATIONS: usize = 50;
+
+/// Above this many held-back notifications, one summary is shown instead.
+const SUMMARY_THRESHOLD: usize = 3;
+
+/// Whether a notification may be shown during Focus.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationUrgency {
+    /// Held back while Focus is on
+    Normal,
+    /// Shown even while Focus is on
+    Urgent,
+}
+
+/// A desktop notification.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DesktopNotification {
+    pub title: String,
+    pub body: String,
+    pub urgency: NotificationUrgency,
+    /// Epoch millis
+    pub created_at: i64,
+}
+
+impl DesktopNotification {
+    pub fn new(title: &str, body: &str, urgency: NotificationUrgency) -> Self {
+        Self {
+            title: title.to_string(),
+            body: body.to_string(),
+            urgency,
+            created_at: Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// Notifications held back while Focus is on.
+#[derive(Default)]
+pub struct NotificationQueue {
+    pending: Mutex<Vec<DesktopNotification>>,
+}
+
+impl NotificationQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return `notification` if it should be shown now, or hold it back.
+    pub fn submit(
+        &self,
+        notification: DesktopNotification,
+        focus_active: bool,
+    ) -> Option<DesktopNotification> {
+        if !focus_active || notification.urgency == NotificationUrgency::Urgent {
+            return Some(notification);
+        }
+
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.push(notification);
+        if pending.len() > MAX_PENDING_NOTIFICATIONS {
+            let excess = pending.len() - MAX_PENDING_NOTIFICATIONS;
+            pending.drain(..excess);
+        }
+        None
+    }
+
+    /// Held-back notifications, oldest first.
+    pub fn pending(&self) -> Vec<DesktopNotification> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Take the held-back notifications to show once Focus is off: all of them,
+    /// or a single summary when there are many.
+    pub fn take_deliverable(&self, focus_active: bool) -> Vec<DesktopNotification> {
+        if focus_active {
+            return Vec::new();
+        }
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        if pending.len() <= SUMMARY_THRESHOLD {
+            return pending;
+        }
+
+        let titles: Vec<&str> = pending.iter().map(|n| n.title.as_str()).collect();
+        vec![DesktopNotification::new(
+            &format!("{} updates while Focus was on", pending.len()),
+            &titles.join(", "),
+            NotificationUrgency::Normal,
+        )]
+    }
+}
+
+static QUEUE: OnceLock<NotificationQueue> = OnceLock::new();
+
+fn queue() -> &'static NotificationQueue {
+    QUEUE.get_or_init(NotificationQueue::new)
+}
+
+/// Whether `Assertions.json` holds an active Focus assertion.
+pub fn parse_focus_assertions(json: &str) -> bool {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(json) else {
+        return false;
+    };
+    value["data"].as_array().is_some_and(|data| {
+        data.iter().any(|entry| {
+            entry["storeAssertionRecords"]
+                .as_array()
+                .is_some_and(|records| !records.is_empty())
+        })
+    })
+}
+
+fn focus_assertions_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json"))
+}
+
+/// Whether a Focus / Do Not Disturb mode is on. Always false outside macOS.
+pub fn is_focus_active() -> bool {
+    if !cfg!(target_os = "macos") {
+        return false;
+    }
+
+    if let Some(json) = focus_assertions_path().and_then(|p| std::fs::read_to_string(p).ok()) {
+        return parse_focus_assertions(&json);
+    }
+
+    // macOS 11 and earlier
+    Command::new("defaults")
+        .args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+        .unwrap_or(false)
+}
+
+/// Quote a string for AppleScript.
+pub fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Show a notification now, regardless of Focus.
+pub fn show_notification(notification: &DesktopNotification) -> Result<(), String> {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(&notification.body),
+        applescript_string(&notification.title)
+    );
+    let output = Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+/// Show a notification, or hold it back while Focus is on unless urgent.
+pub fn notify(notification: DesktopNotification) {
+    match queue().submit(notification, is_focus_active()) {
+        Some(notification) => {
+            if let Err(e) = show_notification(&notification) {
+                eprintln!("[notifications] Failed to show notification: {}", e);
+            }
+        }
+        None => println!("[notifications] Focus is on, notification held back"),
+    }
+}
+
+/// Notifications held back while Focus is on.
+pub fn get_pending_notifications() -> Vec<DesktopNotification> {
+    queue().pending()
+}
+
+/// Spawn a background thread that delivers held-back notifications once
+/// Focus is off.
+pub fn spawn_pending_notification_loop() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(PENDING_CHECK_INTERVAL);
+
+        if queue().pending().is_empty() {
+            continue;
+        }
+        for notification in queue().take_deliverable(is_focus_active()) {
+            if let Err(e) = show_notification(&notification) {
+                eprintln!("[notifications] Failed to show notification: {}", e);
+            }
+        }
+    });
+}
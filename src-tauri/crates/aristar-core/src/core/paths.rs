@@ -0,0 +1,56 @@
+//! Path normalization for store lookups.
+//!
+//! The same directory can reach the store under different spellings: trailing
+//! slashes, symlinks (`/tmp` vs `/private/tmp`) or different casing on
+//! case-insensitive filesystems (macOS, Windows). Paths are normalized once
+//! when they enter the store, so lookups only need the `path_key` of the path
+//! asked for and compare it with `stored_path_key`, which doesn't touch the
+//! filesystem while the store lock is held.
+
+use std::path::Path;
+
+/// Whether the platform's default filesystem is case-insensitive.
+pub const CASE_INSENSITIVE_FS: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// Normalize a path: canonicalize when it exists, otherwise strip trailing separators.
+pub fn normalize_path(path: &str) -> String {
+    if let Ok(canonical) = Path::new(path).canonicalize() {
+        return canonical.to_string_lossy().to_string();
+    }
+
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.is_empty() && path.starts_with('/') {
+        "/".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Comparison key for a path, folded to lowercase on case-insensitive platforms.
+pub fn path_key(path: &str) -> String {
+    path_key_with(path, CASE_INSENSITIVE_FS)
+}
+
+/// Comparison key for a path with explicit case sensitivity.
+pub fn path_key_with(path: &str, case_insensitive: bool) -> String {
+    fold_case(&normalize_path(path), case_insensitive)
+}
+
+/// Comparison key for a path already normalized with `normalize_path`, as
+/// store paths are. Equal to `path_key` of any spelling of the same path.
+pub fn stored_path_key(path: &str) -> String {
+    fold_case(path, CASE_INSENSITIVE_FS)
+}
+
+fn fold_case(path: &str, case_insensitive: bool) -> String {
+    if case_insensitive {
+        path.to_lowercase()
+    } else {
+        path.to_string()
+    }
+}
+
+/// Whether two paths refer to the same location for store purposes.
+pub fn paths_equal(a: &str, b: &str) -> bool {
+    a == b || path_key(a) == path_key(b)
+}
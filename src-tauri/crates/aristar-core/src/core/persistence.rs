@@ -1,9 +1,59 @@
 //! Persistence utilities for loading and saving store data.
 
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+/// Launch flag that points the app at an alternate data directory.
+pub const DATA_DIR_FLAG: &str = "--data-dir";
+
+/// Environment variable that points the app at an alternate data directory.
+pub const DATA_DIR_ENV: &str = "ARISTAR_DATA_DIR";
+
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Bumped on every store save, so data derived from the stores (e.g. the
+/// search index) knows when to catch up.
+static STORE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Note that a store (or a file derived data reads, like a scratchpad) changed.
+pub fn mark_store_changed() {
+    STORE_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Number of store changes since startup.
+pub fn store_generation() -> u64 {
+    STORE_GENERATION.load(Ordering::Relaxed)
+}
+
+/// Override the data directory for the running process.
+/// Only the first call takes effect; it must happen before any store is loaded.
+pub fn set_data_dir_override(path: PathBuf) {
+    println!("[persistence] Using data directory override: {:?}", path);
+    let _ = DATA_DIR_OVERRIDE.set(path);
+}
+
+/// Resolve a data directory override from launch args (`--data-dir <path>`)
+/// or the `ARISTAR_DATA_DIR` environment variable.
+pub fn data_dir_from_args(args: &[String]) -> Option<PathBuf> {
+    args.iter()
+        .position(|a| a == DATA_DIR_FLAG)
+        .and_then(|idx| args.get(idx + 1))
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var(DATA_DIR_ENV)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .map(PathBuf::from)
+        })
+}
 
 /// Get the base directory for all aristar worktrees (~/.aristar-worktrees)
 pub fn get_aristar_worktrees_base() -> PathBuf {
+    if let Some(path) = DATA_DIR_OVERRIDE.get() {
+        return path.clone();
+    }
+
     dirs::home_dir()
         .expect("Could not find home directory")
         .join(".aristar-worktrees")
@@ -0,0 +1,307 @@
+//! Plugins: custom actions declared in TOML manifests.
+//!
+//! Every `*.toml` file in `~/.aristar-worktrees/plugins/` is a plugin; its id
+//! is the file name without the extension. A manifest declares actions, each
+//! run on a repository, a worktree or a task:
+//!
+//! ```toml
+//! name = "Fork"
+//! description = "Open worktrees in Fork"
+//!
+//! [[actions]]
+//! id = "open"
+//! name = "Open in Fork"
+//! context = "worktree"
+//! command = ["/usr/bin/open", "-a", "Fork", "{{worktree_path}}"]
+//! ```
+//!
+//! `command` is the program and its arguments; no shell is involved. The
+//! program must pass `validate_custom_command` (an absolute path in a known
+//! location, no shell metacharacters). Arguments may use the startup script
+//! variables (`{{branch}}`, `{{worktree_path}}`, `{{repo_name}}`,
+//! `{{task_id}}`, `{{port}}`) and are otherwise held to the same character
+//! rules; values are rendered with `render_script`, which rejects values
+//! containing shell metacharacters. Manifests are read again on every
+//! `list_plugins`, and checked again before an action runs.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::worktrees::external_apps::{validate_custom_command, FORBIDDEN_COMMAND_CHARS};
+use crate::worktrees::templates::{render_script, ScriptTemplateContext};
+
+use super::get_aristar_worktrees_base;
+
+/// Most actions in one plugin.
+pub const MAX_PLUGIN_ACTIONS: usize = 50;
+
+/// What a plugin action runs on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginContext {
+    /// A repository; `{{worktree_path}}` is its main worktree
+    Repo,
+    Worktree,
+    /// A task; `{{worktree_path}}` is the task folder
+    Task,
+}
+
+/// A custom action declared by a plugin.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginAction {
+    /// Unique within the plugin (lowercase letters, digits and `-`)
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub context: PluginContext,
+    /// Program and arguments, with template variables in the arguments
+    pub command: Vec<String>,
+}
+
+/// A plugin loaded from a manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Plugin {
+    /// File name of the manifest without `.toml`
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub actions: Vec<PluginAction>,
+    /// Path of the manifest
+    pub path: String,
+}
+
+/// A manifest that could not be loaded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginLoadError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Result of `list_plugins`: the valid plugins and why the others were skipped.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginList {
+    pub plugins: Vec<Plugin>,
+    pub errors: Vec<PluginLoadError>,
+}
+
+/// Outcome of a plugin action that ran.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginActionResult {
+    pub success: bool,
+    /// `None` when the process was killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// A manifest as written; `id` and `path` come from the file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    actions: Vec<PluginAction>,
+}
+
+/// Directory plugin manifests are read from.
+pub fn get_plugins_dir() -> PathBuf {
+    get_aristar_worktrees_base().join("plugins")
+}
+
+fn is_plugin_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Check an action's command: the program with `validate_custom_command`, the
+/// arguments for unknown template variables and shell metacharacters outside
+/// the placeholders.
+pub fn validate_action_command(command: &[String]) -> Result<(), String> {
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| "Command is empty".to_string())?;
+    validate_custom_command(program)?;
+
+    for arg in args {
+        let rendered = render_script(arg, &ScriptTemplateContext::default())?;
+        if rendered
+            .chars()
+            .any(|c| FORBIDDEN_COMMAND_CHARS.contains(&c))
+        {
+            return Err(format!("Argument contains forbidden characters: {}", arg));
+        }
+    }
+    Ok(())
+}
+
+/// Parse and validate the manifest of plugin `id`.
+pub fn parse_manifest(id: &str, path: &Path, text: &str) -> Result<Plugin, String> {
+    if !is_plugin_id(id) {
+        return Err(format!(
+            "Invalid plugin id {:?}: use lowercase letters, digits and '-' in the file name",
+            id
+        ));
+    }
+    let manifest: Manifest = toml::from_str(text).map_err(|e| e.to_string())?;
+    if manifest.name.trim().is_empty() {
+        return Err("Plugin name is empty".to_string());
+    }
+    if manifest.actions.len() > MAX_PLUGIN_ACTIONS {
+        return Err(format!(
+            "At most {} actions are allowed",
+            MAX_PLUGIN_ACTIONS
+        ));
+    }
+
+    for (i, action) in manifest.actions.iter().enumerate() {
+        if !is_plugin_id(&action.id) {
+            return Err(format!("Invalid action id {:?}", action.id));
+        }
+        if manifest.actions[..i].iter().any(|a| a.id == action.id) {
+            return Err(format!("Duplicate action id {:?}", action.id));
+        }
+        if action.name.trim().is_empty() {
+            return Err(format!("Action {:?} has no name", action.id));
+        }
+        validate_action_command(&action.command)
+            .map_err(|e| format!("Action {:?}: {}", action.id, e))?;
+    }
+
+    Ok(Plugin {
+        id: id.to_string(),
+        name: manifest.name.trim().to_string(),
+        description: manifest.description,
+        version: manifest.version,
+        actions: manifest.actions,
+        path: path.to_string_lossy().to_string(),
+    })
+}
+
+/// Load every `*.toml` manifest in `dir`, sorted by id. A missing directory
+/// has no plugins.
+pub fn load_plugins_from(dir: &Path) -> PluginList {
+    let mut list = PluginList::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return list;
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "toml"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let id = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_manifest(&id, &path, &text));
+        match loaded {
+            Ok(plugin) => list.plugins.push(plugin),
+            Err(message) => list.errors.push(PluginLoadError {
+                path: path.to_string_lossy().to_string(),
+                message,
+            }),
+        }
+    }
+    list
+}
+
+/// Load the plugins in `get_plugins_dir()`.
+pub fn list_plugins() -> PluginList {
+    load_plugins_from(&get_plugins_dir())
+}
+
+/// The action `action_id` of plugin `plugin_id` in `dir`, loaded (and so
+/// validated) afresh.
+pub fn find_plugin_action_in(
+    dir: &Path,
+    plugin_id: &str,
+    action_id: &str,
+) -> Result<PluginAction, String> {
+    if !is_plugin_id(plugin_id) {
+        return Err(format!("Invalid plugin id: {}", plugin_id));
+    }
+    let path = dir.join(format!("{}.toml", plugin_id));
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read plugin {}: {}", plugin_id, e))?;
+    parse_manifest(plugin_id, &path, &text)?
+        .actions
+        .into_iter()
+        .find(|a| a.id == action_id)
+        .ok_or_else(|| format!("Plugin {} has no action {}", plugin_id, action_id))
+}
+
+/// The command of `action` with its arguments rendered for `context`.
+pub fn render_action_command(
+    action: &PluginAction,
+    context: &ScriptTemplateContext,
+) -> Result<Vec<String>, String> {
+    validate_action_command(&action.command)?;
+    let (program, args) = action
+        .command
+        .split_first()
+        .ok_or_else(|| "Command is empty".to_string())?;
+
+    let mut command = vec![program.clone()];
+    for arg in args {
+        command.push(render_script(arg, context)?);
+    }
+    Ok(command)
+}
+
+/// Run `action` for `context` in `cwd` and wait for it to finish.
+pub fn run_plugin_action(
+    action: &PluginAction,
+    context: &ScriptTemplateContext,
+    cwd: &Path,
+) -> Result<PluginActionResult, String> {
+    let command = render_action_command(action, context)?;
+    println!("[plugins] Running {} in {}", action.id, cwd.display());
+
+    let output = Command::new(&command[0])
+        .args(&command[1..])
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", command[0], e))?;
+
+    Ok(PluginActionResult {
+        success: output.status.success(),
+        exit_code: output.status.code(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    })
+}
+
+/// Run a plugin action (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn run_plugin_action_async(
+    action: PluginAction,
+    context: ScriptTemplateContext,
+    cwd: PathBuf,
+) -> Result<PluginActionResult, String> {
+    tokio::task::spawn_blocking(move || run_plugin_action(&action, &context, &cwd))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
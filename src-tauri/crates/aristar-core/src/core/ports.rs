@@ -0,0 +1,290 @@
+//! Port reservations shared by every feature that listens on a port.
+//!
+//! OpenCode servers, dev servers started by startup scripts (`{{port}}`) and
+//! the event socket each reserve their port here, keyed by service and owner
+//! (a worktree path, or "app"). Reservations are persisted in
+//! `~/.aristar-worktrees/ports.json`, so an owner gets the same port back
+//! across restarts while it is free, and two features never hand out the same
+//! port. New ports come from `settings.port_range_start`..=`port_range_end`
+//! (default 41000-41999, read at startup). A worktree's reservations are
+//! released when it is removed.
+
+use chrono::Utc;
+use portpicker::is_free;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+use super::{get_aristar_worktrees_base, load_json_store, save_json_store};
+
+/// Service name of OpenCode servers (owner: worktree path).
+pub const OPENCODE_SERVICE: &str = "opencode";
+/// Service name of dev servers started by startup scripts (owner: worktree path).
+pub const DEV_SERVER_SERVICE: &str = "dev-server";
+/// Service name of the event socket (owner: `APP_OWNER`).
+pub const EVENT_SOCKET_SERVICE: &str = "event-socket";
+/// Owner of app-wide reservations.
+pub const APP_OWNER: &str = "app";
+
+/// Range new ports are picked from when the settings don't set one.
+pub const DEFAULT_PORT_RANGE: PortRange = PortRange {
+    start: 41000,
+    end: 41999,
+};
+
+/// Inclusive range of ports handed out.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// The range from the settings; the default where a bound is missing or
+    /// the range is invalid.
+    pub fn from_settings(start: Option<u16>, end: Option<u16>) -> Self {
+        let range = PortRange {
+            start: start.unwrap_or(DEFAULT_PORT_RANGE.start),
+            end: end.unwrap_or(DEFAULT_PORT_RANGE.end),
+        };
+        if range.start == 0 || range.start > range.end {
+            eprintln!(
+                "[ports] Invalid port range {}-{}, using {}-{}",
+                range.start, range.end, DEFAULT_PORT_RANGE.start, DEFAULT_PORT_RANGE.end
+            );
+            return DEFAULT_PORT_RANGE;
+        }
+        range
+    }
+
+    pub fn contains(&self, port: u16) -> bool {
+        (self.start..=self.end).contains(&port)
+    }
+}
+
+/// A port held by a service for an owner.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortReservation {
+    pub service: String,
+    pub owner: String,
+    pub port: u16,
+    pub reserved_at: i64,
+}
+
+/// Why reservations need attention.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PortConflictKind {
+    /// Several reservations hold the same port (e.g. an edited `ports.json`)
+    Duplicate,
+    /// Outside the configured range, e.g. after the range changed
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortConflict {
+    pub port: u16,
+    pub kind: PortConflictKind,
+    pub reservations: Vec<PortReservation>,
+}
+
+/// Result of `get_port_reservations`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortReport {
+    pub range: PortRange,
+    pub reservations: Vec<PortReservation>,
+    pub conflicts: Vec<PortConflict>,
+}
+
+/// The persisted reservations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortRegistry {
+    pub reservations: Vec<PortReservation>,
+}
+
+impl PortRegistry {
+    pub fn find(&self, service: &str, owner: &str) -> Option<&PortReservation> {
+        self.reservations
+            .iter()
+            .find(|r| r.service == service && r.owner == owner)
+    }
+
+    /// The reservation holding `port` for anyone but `service`/`owner`.
+    fn held_by_other(&self, port: u16, service: &str, owner: &str) -> Option<&PortReservation> {
+        self.reservations
+            .iter()
+            .find(|r| r.port == port && !(r.service == service && r.owner == owner))
+    }
+
+    fn record(&mut self, service: &str, owner: &str, port: u16, now: i64) {
+        self.reservations
+            .retain(|r| !(r.service == service && r.owner == owner));
+        self.reservations.push(PortReservation {
+            service: service.to_string(),
+            owner: owner.to_string(),
+            port,
+            reserved_at: now,
+        });
+    }
+
+    /// Reserve a port for `service`/`owner`: its current reservation, else
+    /// `preferred`, else the first port of `range`, whichever is neither held
+    /// by another reservation nor in use (`is_free`).
+    pub fn reserve(
+        &mut self,
+        service: &str,
+        owner: &str,
+        preferred: Option<u16>,
+        range: PortRange,
+        is_free: impl Fn(u16) -> bool,
+        now: i64,
+    ) -> Result<u16, String> {
+        let current = self.find(service, owner).map(|r| r.port);
+        let port = current
+            .into_iter()
+            .chain(preferred)
+            .chain(range.start..=range.end)
+            .find(|&p| p != 0 && self.held_by_other(p, service, owner).is_none() && is_free(p))
+            .ok_or_else(|| {
+                format!(
+                    "No free port in {}-{} for {}",
+                    range.start, range.end, service
+                )
+            })?;
+
+        if current != Some(port) {
+            self.record(service, owner, port, now);
+        }
+        Ok(port)
+    }
+
+    /// Reserve exactly `port` for `service`/`owner` (a configured port).
+    /// Fails when another reservation holds it.
+    pub fn claim(&mut self, service: &str, owner: &str, port: u16, now: i64) -> Result<(), String> {
+        if let Some(other) = self.held_by_other(port, service, owner) {
+            return Err(format!(
+                "Port {} is reserved for {} ({})",
+                port, other.service, other.owner
+            ));
+        }
+        if self.find(service, owner).map(|r| r.port) != Some(port) {
+            self.record(service, owner, port, now);
+        }
+        Ok(())
+    }
+
+    /// Release the reservations of `owner`, only those of `service` when given.
+    /// Returns the number released.
+    pub fn release(&mut self, owner: &str, service: Option<&str>) -> usize {
+        let before = self.reservations.len();
+        self.reservations
+            .retain(|r| r.owner != owner || service.is_some_and(|s| r.service != s));
+        before - self.reservations.len()
+    }
+
+    /// Ports held by several reservations, and reservations outside `range`.
+    pub fn conflicts(&self, range: PortRange) -> Vec<PortConflict> {
+        let mut conflicts: Vec<PortConflict> = Vec::new();
+        for reservation in &self.reservations {
+            let holders: Vec<PortReservation> = self
+                .reservations
+                .iter()
+                .filter(|r| r.port == reservation.port)
+                .cloned()
+                .collect();
+            let reported = conflicts
+                .iter()
+                .any(|c| c.port == reservation.port && c.kind == PortConflictKind::Duplicate);
+            if holders.len() > 1 && !reported {
+                conflicts.push(PortConflict {
+                    port: reservation.port,
+                    kind: PortConflictKind::Duplicate,
+                    reservations: holders,
+                });
+            }
+        }
+        for reservation in self.reservations.iter().filter(|r| !range.contains(r.port)) {
+            conflicts.push(PortConflict {
+                port: reservation.port,
+                kind: PortConflictKind::OutOfRange,
+                reservations: vec![reservation.clone()],
+            });
+        }
+        conflicts
+    }
+}
+
+// ============ Registry file ============
+
+static RANGE: RwLock<Option<PortRange>> = RwLock::new(None);
+static REGISTRY: Mutex<Option<PortRegistry>> = Mutex::new(None);
+
+/// Reservations file: ~/.aristar-worktrees/ports.json
+pub fn get_ports_path() -> PathBuf {
+    get_aristar_worktrees_base().join("ports.json")
+}
+
+/// Set the range new ports are picked from (from the settings at startup).
+pub fn set_port_range(range: PortRange) {
+    *RANGE.write().unwrap_or_else(|e| e.into_inner()) = Some(range);
+}
+
+/// The range new ports are picked from.
+pub fn port_range() -> PortRange {
+    RANGE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .unwrap_or(DEFAULT_PORT_RANGE)
+}
+
+/// Run `f` on the registry (loaded on first use), saving it when the
+/// reservations changed.
+fn with_registry<R>(f: impl FnOnce(&mut PortRegistry) -> R) -> Result<R, String> {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let registry = registry.get_or_insert_with(|| load_json_store(&get_ports_path()));
+    let before = registry.clone();
+    let result = f(registry);
+    if *registry != before {
+        save_json_store(&get_ports_path(), registry)?;
+    }
+    Ok(result)
+}
+
+/// Reserve a port for `service`/`owner`; see `PortRegistry::reserve`.
+pub fn reserve_port(service: &str, owner: &str, preferred: Option<u16>) -> Result<u16, String> {
+    let range = port_range();
+    let now = Utc::now().timestamp_millis();
+    with_registry(|registry| registry.reserve(service, owner, preferred, range, is_free, now))?
+}
+
+/// Reserve exactly `port` for `service`/`owner`; see `PortRegistry::claim`.
+pub fn claim_port(service: &str, owner: &str, port: u16) -> Result<(), String> {
+    let now = Utc::now().timestamp_millis();
+    with_registry(|registry| registry.claim(service, owner, port, now))?
+}
+
+/// Release the reservations of `owner` (only `service`'s when given).
+pub fn release_ports(owner: &str, service: Option<&str>) -> Result<usize, String> {
+    with_registry(|registry| registry.release(owner, service))
+}
+
+/// The port reserved for `service`/`owner`, if any.
+pub fn reserved_port(service: &str, owner: &str) -> Result<Option<u16>, String> {
+    with_registry(|registry| registry.find(service, owner).map(|r| r.port))
+}
+
+/// All reservations with the configured range and any conflicts.
+pub fn get_port_report() -> Result<PortReport, String> {
+    let range = port_range();
+    with_registry(|registry| PortReport {
+        range,
+        reservations: registry.reservations.clone(),
+        conflicts: registry.conflicts(range),
+    })
+}
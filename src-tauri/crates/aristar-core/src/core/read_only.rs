@@ -0,0 +1,69 @@
+//! Read-only observer mode.
+//!
+//! When enabled, every mutating command is rejected with a clear error before it
+//! touches git, the stores, or any OpenCode process. Useful for pairing/demo
+//! sessions or for inspecting someone else's data directory without risk.
+//!
+//! The mode can be enabled by:
+//! - the `--read-only` launch flag
+//! - the `ARISTAR_READ_ONLY=1` environment variable
+//! - the `read_only` field in the persisted `AppSettings`
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Launch flag that enables read-only mode.
+pub const READ_ONLY_FLAG: &str = "--read-only";
+
+/// Environment variable that enables read-only mode.
+pub const READ_ONLY_ENV: &str = "ARISTAR_READ_ONLY";
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable read-only mode for the running process.
+pub fn set_read_only(enabled: bool) {
+    READ_ONLY.store(enabled, Ordering::SeqCst);
+    if enabled {
+        println!("[read_only] Read-only observer mode enabled");
+    }
+}
+
+/// Whether the app is running in read-only mode.
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::SeqCst)
+}
+
+/// Check whether read-only mode was requested via launch args or environment.
+pub fn read_only_requested(args: &[String]) -> bool {
+    if args.iter().any(|a| a == READ_ONLY_FLAG) {
+        return true;
+    }
+
+    std::env::var(READ_ONLY_ENV)
+        .map(|v| is_truthy(&v))
+        .unwrap_or(false)
+}
+
+/// Interpret an environment variable value as a boolean flag.
+pub fn is_truthy(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Build the error returned when a mutating action is attempted in read-only mode.
+pub fn read_only_error(action: &str) -> String {
+    format!(
+        "Cannot {}: the app is running in read-only observer mode",
+        action
+    )
+}
+
+/// Reject a mutating action when read-only mode is enabled.
+/// Call this at the top of every command that changes state on disk.
+pub fn ensure_writable(action: &str) -> Result<(), String> {
+    if is_read_only() {
+        return Err(read_only_error(action));
+    }
+    Ok(())
+}
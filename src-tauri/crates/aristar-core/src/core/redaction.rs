@@ -0,0 +1,306 @@
+//! Redaction of secrets and personal data in logs and transcripts.
+//!
+//! Everything written to the log files (`append_to_log_file`) and to agent
+//! transcripts passes through `redact` first. Built-in rules replace:
+//! - API keys and tokens with a well-known prefix (`sk-`, `ghp_`, `AKIA`, ...)
+//! - values following a secret-looking key (`api_key=...`, `"password": "..."`,
+//!   `Authorization: Bearer ...`)
+//! - email addresses
+//!
+//! `settings.redaction_patterns` adds patterns of its own, typically internal
+//! hostnames: `*` matches any run of characters, matching is case-insensitive
+//! and applies to whole words (e.g. `*.corp.example.com`, `build-agent-*`).
+//! `settings.redact_logs: false` turns redaction off. Both are read at startup;
+//! `test_redaction` shows what the active rules do to a sample.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::RwLock;
+
+/// Prefixes of API keys and tokens, redacted when followed by enough characters.
+pub const KEY_PREFIXES: &[&str] = &[
+    "sk-",
+    "sk_live_",
+    "sk_test_",
+    "rk_live_",
+    "ghp_",
+    "gho_",
+    "ghu_",
+    "ghs_",
+    "ghr_",
+    "github_pat_",
+    "glpat-",
+    "xoxb-",
+    "xoxp-",
+    "xapp-",
+    "AKIA",
+    "ASIA",
+    "AIza",
+    "hf_",
+    "npm_",
+];
+
+/// Key names whose value is redacted (matched as a suffix, case-insensitive).
+pub const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "api-key",
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "credentials",
+    "private_key",
+    "access_key",
+    "authorization",
+];
+
+/// Characters needed after a key prefix for a word to count as a key.
+const MIN_KEY_BODY: usize = 16;
+
+/// Active redaction rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedactionRules {
+    pub enabled: bool,
+    /// Extra patterns from `settings.redaction_patterns`
+    pub patterns: Vec<String>,
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// One replaced piece of text.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionMatch {
+    /// "api-key", "secret", "email" or the matching pattern
+    pub rule: String,
+    pub text: String,
+}
+
+/// Result of `test_redaction`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionPreview {
+    pub enabled: bool,
+    pub redacted: String,
+    pub matches: Vec<RedactionMatch>,
+}
+
+static RULES: RwLock<Option<RedactionRules>> = RwLock::new(None);
+
+/// Replace the active redaction rules (from the settings at startup).
+pub fn set_redaction_rules(rules: RedactionRules) {
+    let mut active = RULES.write().unwrap_or_else(|e| e.into_inner());
+    *active = Some(rules);
+}
+
+/// The active redaction rules.
+pub fn redaction_rules() -> RedactionRules {
+    RULES
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+// ============ Matching ============
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '@' | '+' | '%')
+}
+
+/// Case-insensitive match of a whole word against a pattern where `*`
+/// matches any run of characters.
+pub fn pattern_matches(pattern: &str, word: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let word: Vec<char> = word.to_lowercase().chars().collect();
+
+    // Iterative wildcard matching with backtracking to the last `*`
+    let (mut p, mut w) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while w < word.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, w));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == word[w] {
+            p += 1;
+            w += 1;
+        } else if let Some((star_p, star_w)) = star {
+            p = star_p + 1;
+            w = star_w + 1;
+            star = Some((star_p, star_w + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn is_email(word: &str) -> bool {
+    let Some((local, domain)) = word.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain
+            .rsplit_once('.')
+            .is_some_and(|(host, tld)| !host.is_empty() && tld.len() >= 2)
+        && domain
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '.' || c == '-')
+}
+
+fn is_api_key(word: &str) -> bool {
+    KEY_PREFIXES.iter().any(|prefix| {
+        word.strip_prefix(prefix)
+            .is_some_and(|body| body.len() >= MIN_KEY_BODY)
+    })
+}
+
+fn is_secret_key(word: &str) -> bool {
+    let word = word.to_lowercase();
+    SECRET_KEYS.iter().any(|key| word.ends_with(key))
+}
+
+/// The rule redacting a word, if any.
+fn word_rule(word: &str, rules: &RedactionRules) -> Option<String> {
+    if is_api_key(word) {
+        return Some("api-key".to_string());
+    }
+    if is_email(word) {
+        return Some("email".to_string());
+    }
+    rules
+        .patterns
+        .iter()
+        .find(|pattern| !pattern.trim().is_empty() && pattern_matches(pattern.trim(), word))
+        .cloned()
+}
+
+/// Redact `text` with `rules`, returning the new text and what was replaced.
+pub fn redact_with(text: &str, rules: &RedactionRules) -> (String, Vec<RedactionMatch>) {
+    if !rules.enabled {
+        return (text.to_string(), Vec::new());
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut matches = Vec::new();
+    // Shortest word redacted as a secret: set after a secret key and its
+    // `:`/`=`, or (for longer words) after a bare `Bearer`
+    let mut expect_secret: Option<usize> = None;
+    let mut after_key = false;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if !is_word_char(c) {
+            if after_key && (c == ':' || c == '=') {
+                expect_secret = Some(1);
+            } else if !matches!(c, ' ' | '\t' | '"' | '\'') {
+                after_key = false;
+                expect_secret = None;
+            }
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let end = rest.find(|c| !is_word_char(c)).unwrap_or(rest.len());
+        // Sentence punctuation isn't part of the word
+        let word = rest[..end].trim_end_matches(['.', '-']);
+        let tail = &rest[word.len()..end];
+        rest = &rest[end..];
+        if word.is_empty() {
+            output.push_str(tail);
+            continue;
+        }
+
+        let is_scheme = word.eq_ignore_ascii_case("bearer") || word.eq_ignore_ascii_case("basic");
+        let rule = match expect_secret.take() {
+            // `Authorization: Bearer <token>`
+            Some(min) if is_scheme => {
+                expect_secret = Some(min);
+                None
+            }
+            Some(min) if word.chars().count() >= min => Some("secret".to_string()),
+            _ if word == "Bearer" => {
+                expect_secret = Some(MIN_KEY_BODY);
+                None
+            }
+            _ => word_rule(word, rules),
+        };
+        after_key = rule.is_none() && is_secret_key(word);
+
+        match rule {
+            Some(rule) => {
+                output.push_str(&format!("[REDACTED:{}]", label(&rule)));
+                matches.push(RedactionMatch {
+                    rule,
+                    text: word.to_string(),
+                });
+            }
+            None => output.push_str(word),
+        }
+        output.push_str(tail);
+    }
+    (output, matches)
+}
+
+fn label(rule: &str) -> &str {
+    match rule {
+        "api-key" | "secret" | "email" => rule,
+        _ => "pattern",
+    }
+}
+
+/// Redact `text` with the active rules.
+pub fn redact(text: &str) -> String {
+    redact_with(text, &redaction_rules()).0
+}
+
+/// Redact every string in a JSON value with the active rules.
+pub fn redact_json(value: &mut Value) {
+    let rules = redaction_rules();
+    if rules.enabled {
+        redact_json_with(value, &rules);
+    }
+}
+
+/// Redact every string in a JSON value with `rules`; values of secret keys are
+/// replaced whole.
+pub fn redact_json_with(value: &mut Value, rules: &RedactionRules) {
+    match value {
+        Value::String(s) => *s = redact_with(s, rules).0,
+        Value::Array(items) => items.iter_mut().for_each(|v| redact_json_with(v, rules)),
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                match v {
+                    // `{"apiKey": "..."}` has no `:` inside the string value
+                    Value::String(s) if is_secret_key(key) && !s.is_empty() => {
+                        *s = "[REDACTED:secret]".to_string();
+                    }
+                    _ => redact_json_with(v, rules),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Run the active rules on a sample.
+pub fn test_redaction(sample: &str) -> RedactionPreview {
+    let rules = redaction_rules();
+    let (redacted, matches) = redact_with(sample, &rules);
+    RedactionPreview {
+        enabled: rules.enabled,
+        redacted,
+        matches,
+    }
+}
@@ -0,0 +1,403 @@
+//! JSON schema and TypeScript definitions of the IPC types.
+//!
+//! Every serialized type derives `JsonSchema`. `frontend_schema` collects the
+//! command results and event payloads the webview receives (and everything
+//! they contain) into one draft-07 schema, and `typescript_definitions` turns
+//! its definitions into TypeScript. Both are committed under
+//! `src/store/generated/` and regenerated with
+//! `cargo run -p aristar-core --bin generate-schemas`. A test fails when the
+//! committed files differ from the Rust types, so a new field can't reach the
+//! frontend unnoticed.
+//!
+//! Definitions and properties are sorted by name, so the output does not
+//! depend on which features of `schemars` the build enabled.
+
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::agent_manager::power::PowerThrottledEvent;
+use crate::agent_manager::types::{
+    AgentAgreementReport, AgentComparisonExport, AgentPortChangedEvent, AgentQuotaExceededEvent,
+    AgentStatusChangedEvent, AgentSummary, AgentTranscript, AutoAcceptEvent, BatchTaskSummary,
+    ChangeSummary, ModelLeaderboardEntry, PipelineRun, RepointTasksReport, SavedTaskPreset,
+    Schedule, ScheduleTriggeredEvent, Task, TaskArtifactCleanup, TaskPreset, TasksLoadedEvent,
+    TranscriptRange, WriteAuditReport,
+};
+use crate::core::api_version::ApiVersionInfo;
+use crate::core::notifications::DesktopNotification;
+use crate::core::plugins::{PluginActionResult, PluginList};
+use crate::core::ports::PortReport;
+use crate::core::redaction::RedactionPreview;
+use crate::core::types::AppSettings;
+use crate::merge::types::{BaseMergeResult, PublishResult};
+use crate::workspace::event_socket::EventSocketInfo;
+use crate::workspace::snapshots::{WorkspaceSnapshot, WorkspaceSnapshotInfo};
+use crate::workspace::types::{GlobalStatus, RecoveryReport, SearchResult};
+use crate::worktrees::archive::WorktreeArchive;
+use crate::worktrees::availability::RepositoryAvailabilityEvent;
+use crate::worktrees::branch_cleanup::{BranchCleanupSuggestion, BranchDeletionReport};
+use crate::worktrees::config::{RepoConfigSummary, WorktreeConfigEntry};
+use crate::worktrees::diff::{WorktreeChanges, WorktreeDiff};
+use crate::worktrees::files::FileTree;
+use crate::worktrees::known_hosts::UnknownSshHost;
+use crate::worktrees::labels::LabelUsage;
+use crate::worktrees::lfs::LfsPullProgress;
+use crate::worktrees::lock_expiry::WorktreeLockExpiredEvent;
+use crate::worktrees::processes::WorktreeProcess;
+use crate::worktrees::repo_queue::{RepoOperationQueued, RepoQueueStatus};
+use crate::worktrees::shared_deps::SharedDir;
+use crate::worktrees::stale::StaleCleanupReport;
+use crate::worktrees::sync::SyncResult;
+use crate::worktrees::types::{
+    BranchInfo, BranchPage, BranchSort, CommitInfo, DeepScanResult, DefaultBranchChange,
+    RebaseResult, RenameWorktreeError, Repository, WorktreeInfo, WorktreeLockFile, WorktreeStatus,
+};
+
+/// File name of the JSON schema in the generated folder.
+pub const SCHEMA_FILE: &str = "schema.json";
+
+/// File name of the TypeScript definitions in the generated folder.
+pub const TYPESCRIPT_FILE: &str = "types.ts";
+
+/// Command that rewrites the generated files.
+pub const GENERATE_COMMAND: &str = "cargo run -p aristar-core --bin generate-schemas";
+
+/// Schema of every type the webview receives, with all types in `definitions`.
+pub fn frontend_schema() -> Value {
+    let mut generator = SchemaSettings::draft07().into_generator();
+
+    // Worktrees
+    generator.subschema_for::<Repository>();
+    generator.subschema_for::<WorktreeInfo>();
+    generator.subschema_for::<WorktreeStatus>();
+    generator.subschema_for::<WorktreeChanges>();
+    generator.subschema_for::<WorktreeDiff>();
+    generator.subschema_for::<BranchInfo>();
+    generator.subschema_for::<BranchPage>();
+    generator.subschema_for::<BranchSort>();
+    generator.subschema_for::<CommitInfo>();
+    generator.subschema_for::<RebaseResult>();
+    generator.subschema_for::<DefaultBranchChange>();
+    generator.subschema_for::<WorktreeArchive>();
+    generator.subschema_for::<RepositoryAvailabilityEvent>();
+    generator.subschema_for::<RepoConfigSummary>();
+    generator.subschema_for::<WorktreeConfigEntry>();
+    generator.subschema_for::<WorktreeLockFile>();
+    generator.subschema_for::<SharedDir>();
+    generator.subschema_for::<FileTree>();
+    generator.subschema_for::<LfsPullProgress>();
+    generator.subschema_for::<WorktreeProcess>();
+    generator.subschema_for::<RepoOperationQueued>();
+    generator.subschema_for::<RepoQueueStatus>();
+    generator.subschema_for::<SyncResult>();
+    generator.subschema_for::<DeepScanResult>();
+    generator.subschema_for::<RenameWorktreeError>();
+    generator.subschema_for::<LabelUsage>();
+    generator.subschema_for::<StaleCleanupReport>();
+    generator.subschema_for::<WorktreeLockExpiredEvent>();
+    generator.subschema_for::<BranchCleanupSuggestion>();
+    generator.subschema_for::<BranchDeletionReport>();
+    generator.subschema_for::<UnknownSshHost>();
+
+    // Tasks and agents
+    generator.subschema_for::<Task>();
+    generator.subschema_for::<TaskPreset>();
+    generator.subschema_for::<SavedTaskPreset>();
+    generator.subschema_for::<BatchTaskSummary>();
+    generator.subschema_for::<Schedule>();
+    generator.subschema_for::<ScheduleTriggeredEvent>();
+    generator.subschema_for::<PipelineRun>();
+    generator.subschema_for::<ChangeSummary>();
+    generator.subschema_for::<AgentAgreementReport>();
+    generator.subschema_for::<AgentComparisonExport>();
+    generator.subschema_for::<ModelLeaderboardEntry>();
+    generator.subschema_for::<RepointTasksReport>();
+    generator.subschema_for::<AgentTranscript>();
+    generator.subschema_for::<TranscriptRange>();
+    generator.subschema_for::<AgentSummary>();
+    generator.subschema_for::<AgentPortChangedEvent>();
+    generator.subschema_for::<AgentStatusChangedEvent>();
+    generator.subschema_for::<AgentQuotaExceededEvent>();
+    generator.subschema_for::<TasksLoadedEvent>();
+    generator.subschema_for::<AutoAcceptEvent>();
+    generator.subschema_for::<PowerThrottledEvent>();
+    generator.subschema_for::<WriteAuditReport>();
+    generator.subschema_for::<TaskArtifactCleanup>();
+
+    // Merging
+    generator.subschema_for::<PublishResult>();
+    generator.subschema_for::<BaseMergeResult>();
+
+    // Workspace
+    generator.subschema_for::<GlobalStatus>();
+    generator.subschema_for::<RecoveryReport>();
+    generator.subschema_for::<SearchResult>();
+    generator.subschema_for::<WorkspaceSnapshot>();
+    generator.subschema_for::<WorkspaceSnapshotInfo>();
+    generator.subschema_for::<EventSocketInfo>();
+
+    // Core
+    generator.subschema_for::<ApiVersionInfo>();
+    generator.subschema_for::<AppSettings>();
+    generator.subschema_for::<PortReport>();
+    generator.subschema_for::<DesktopNotification>();
+    generator.subschema_for::<RedactionPreview>();
+    generator.subschema_for::<PluginList>();
+    generator.subschema_for::<PluginActionResult>();
+
+    let definitions =
+        serde_json::to_value(generator.definitions()).expect("Schemas serialize to JSON");
+    sorted(json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "Aristar Worktrees IPC types",
+        "definitions": definitions,
+    }))
+}
+
+/// Recursively rebuild objects with their keys in sorted order.
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().map(|(k, v)| (k, sorted(v))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sorted).collect()),
+        other => other,
+    }
+}
+
+/// TypeScript definitions for the `definitions` of `schema`: an interface per
+/// struct and a type alias per enum, with the doc comments carried over.
+pub fn typescript_definitions(schema: &Value) -> String {
+    let mut out = format!(
+        "// Generated from the Rust types by `{}`. Do not edit.\n",
+        GENERATE_COMMAND
+    );
+    let Some(definitions) = schema.get("definitions").and_then(Value::as_object) else {
+        return out;
+    };
+
+    for (name, definition) in definitions {
+        out.push('\n');
+        push_doc(&mut out, definition, "");
+        match definition.as_object().filter(|d| is_interface(d)) {
+            Some(object) => {
+                out.push_str(&format!("export interface {} {{\n", name));
+                push_properties(&mut out, object, "  ");
+                out.push_str("}\n");
+            }
+            None => out.push_str(&format!(
+                "export type {} = {};\n",
+                name,
+                ts_type(definition)
+            )),
+        }
+    }
+    out
+}
+
+/// A plain struct: an object with properties and nothing to combine.
+fn is_interface(schema: &Map<String, Value>) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        && schema.contains_key("properties")
+        && !["oneOf", "anyOf", "allOf"]
+            .iter()
+            .any(|key| schema.contains_key(*key))
+}
+
+fn push_doc(out: &mut String, schema: &Value, indent: &str) {
+    let Some(description) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let description = description.replace("*/", "*\\/");
+    if description.contains('\n') {
+        out.push_str(&format!("{}/**\n", indent));
+        for line in description.lines() {
+            out.push_str(format!("{} * {}", indent, line).trim_end());
+            out.push('\n');
+        }
+        out.push_str(&format!("{} */\n", indent));
+    } else {
+        out.push_str(&format!("{}/** {} */\n", indent, description));
+    }
+}
+
+fn push_properties(out: &mut String, schema: &Map<String, Value>, indent: &str) {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return;
+    };
+    for (name, property) in properties {
+        push_doc(out, property, indent);
+        out.push_str(&format!(
+            "{}{}{}: {};\n",
+            indent,
+            property_name(name),
+            if is_required(schema, name) { "" } else { "?" },
+            ts_type(property)
+        ));
+    }
+}
+
+fn is_required(schema: &Map<String, Value>, name: &str) -> bool {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .is_some_and(|required| required.iter().any(|r| r.as_str() == Some(name)))
+}
+
+/// Quote names that are not plain identifiers, e.g. kebab-case enum variants.
+fn property_name(name: &str) -> String {
+    let is_identifier = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        Value::String(name.to_string()).to_string()
+    }
+}
+
+/// TypeScript type expression for a schema.
+fn ts_type(schema: &Value) -> String {
+    let object = match schema {
+        Value::Object(object) => object,
+        Value::Bool(false) => return "never".to_string(),
+        _ => return "unknown".to_string(),
+    };
+
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(values) = object.get("enum").and_then(Value::as_array) {
+        return union(values.iter().map(Value::to_string).collect());
+    }
+    if let Some(value) = object.get("const") {
+        return value.to_string();
+    }
+
+    let mut parts = Vec::new();
+    if let Some(types) = object.get("type") {
+        let types: Vec<&str> = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        parts.push(union(
+            types
+                .into_iter()
+                .map(|instance_type| instance_ts_type(object, instance_type))
+                .collect(),
+        ));
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = object.get(key).and_then(Value::as_array) {
+            parts.push(union(variants.iter().map(ts_type).collect()));
+        }
+    }
+    if let Some(all) = object.get("allOf").and_then(Value::as_array) {
+        let all: Vec<String> = all.iter().map(ts_type).map(|t| grouped(&t)).collect();
+        parts.push(all.join(" & "));
+    }
+
+    match parts.len() {
+        0 => "unknown".to_string(),
+        1 => parts.remove(0),
+        _ => parts
+            .iter()
+            .map(|p| grouped(p))
+            .collect::<Vec<_>>()
+            .join(" & "),
+    }
+}
+
+fn instance_ts_type(schema: &Map<String, Value>, instance_type: &str) -> String {
+    match instance_type {
+        "string" => "string".to_string(),
+        "integer" | "number" => "number".to_string(),
+        "boolean" => "boolean".to_string(),
+        "null" => "null".to_string(),
+        "array" => match schema.get("items") {
+            Some(Value::Array(items)) => {
+                let items: Vec<String> = items.iter().map(ts_type).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Some(items) => format!("{}[]", grouped(&ts_type(items))),
+            None => "unknown[]".to_string(),
+        },
+        "object" => match schema.get("properties").and_then(Value::as_object) {
+            Some(properties) if !properties.is_empty() => {
+                let fields: Vec<String> = properties
+                    .iter()
+                    .map(|(name, property)| {
+                        format!(
+                            "{}{}: {}",
+                            property_name(name),
+                            if is_required(schema, name) { "" } else { "?" },
+                            ts_type(property)
+                        )
+                    })
+                    .collect();
+                format!("{{ {} }}", fields.join("; "))
+            }
+            _ => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => "Record<string, never>".to_string(),
+                Some(value @ Value::Object(_)) => format!("Record<string, {}>", ts_type(value)),
+                _ => "Record<string, unknown>".to_string(),
+            },
+        },
+        _ => "unknown".to_string(),
+    }
+}
+
+/// Join alternatives with `|`, dropping duplicates.
+fn union(mut alternatives: Vec<String>) -> String {
+    let mut seen = Vec::new();
+    alternatives.retain(|a| {
+        let new = !seen.contains(a);
+        seen.push(a.clone());
+        new
+    });
+    alternatives.join(" | ")
+}
+
+/// Parenthesize unions and intersections inside `[]` and `&`.
+fn grouped(ts: &str) -> String {
+    if ts.contains(" | ") || ts.contains(" & ") {
+        format!("({})", ts)
+    } else {
+        ts.to_string()
+    }
+}
+
+/// Contents of the generated files, by file name.
+pub fn generated_files() -> Vec<(&'static str, String)> {
+    let schema = frontend_schema();
+    let schema_json =
+        serde_json::to_string_pretty(&schema).expect("Schemas serialize to JSON") + "\n";
+    vec![
+        (SCHEMA_FILE, schema_json),
+        (TYPESCRIPT_FILE, typescript_definitions(&schema)),
+    ]
+}
+
+/// Write the generated files into `dir`, creating it if needed.
+pub fn write_generated_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    generated_files()
+        .into_iter()
+        .map(|(name, contents)| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            Ok(path)
+        })
+        .collect()
+}
@@ -4,6 +4,8 @@ use chrono::Local;
 use std::io::Write;
 use std::path::PathBuf;
 
+use super::redaction::redact;
+
 /// Reveal a path in Finder (macOS).
 pub fn reveal_in_finder(path: &str) -> Result<(), String> {
     let output = std::process::Command::new("open")
@@ -53,9 +55,11 @@ pub fn get_log_file_path() -> PathBuf {
     logs_dir.join(format!("aristar-{}.log", date))
 }
 
-/// Append content to the log file with rotation support.
+/// Append content to the log file with rotation support. Secrets are
+/// redacted before anything is written.
 pub fn append_to_log_file(path: &str, content: &str) -> Result<(), String> {
     let path_buf = PathBuf::from(path);
+    let content = redact(content);
 
     if let Some(parent) = path_buf.parent() {
         if let Err(e) = std::fs::create_dir_all(parent) {
@@ -0,0 +1,176 @@
+//! Shared types used across modules.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Application settings stored in the persistent store.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    #[serde(alias = "theme_name")]
+    pub theme_name: String,
+    #[serde(alias = "color_scheme")]
+    pub color_scheme: String,
+    #[serde(alias = "auto_refresh")]
+    pub auto_refresh: bool,
+    /// Start in read-only observer mode (all mutating commands rejected)
+    #[serde(default, alias = "read_only")]
+    pub read_only: bool,
+    /// Template for suggested worktree names (e.g. "{user}/{hint}")
+    #[serde(default, alias = "branch_name_template")]
+    pub branch_name_template: Option<String>,
+    /// Template for names generated when `create_worktree` gets none (e.g.
+    /// "{branch}-{date}"); `None` uses "{branch}"
+    #[serde(default, alias = "worktree_name_template")]
+    pub worktree_name_template: Option<String>,
+    /// Ticket IDs found in branch names for `{ticket}`, `*` matching letters
+    /// and `#` digits; `None` uses "*-#" (e.g. "PROJ-123")
+    #[serde(default, alias = "ticket_pattern")]
+    pub ticket_pattern: Option<String>,
+    /// Maintain readable symlinks under ~/.aristar-worktrees/by-name/
+    #[serde(default, alias = "worktree_aliases")]
+    pub worktree_aliases: bool,
+    /// Language of humanized report fields (e.g. "de"); `None` uses `LANG`
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Port of the local event WebSocket; `None` keeps it disabled
+    #[serde(default, alias = "event_socket_port")]
+    pub event_socket_port: Option<u16>,
+    /// Battery percentage below which agents are throttled; `None` uses 20,
+    /// 0 disables throttling
+    #[serde(default, alias = "battery_throttle_percent")]
+    pub battery_throttle_percent: Option<u8>,
+    /// OpenCode servers allowed while throttled; `None` uses 1
+    #[serde(default, alias = "battery_max_opencode_instances")]
+    pub battery_max_opencode_instances: Option<usize>,
+    /// Run `git lfs pull` in new worktrees of LFS repositories; `None` means on
+    #[serde(default, alias = "lfs_auto_pull")]
+    pub lfs_auto_pull: Option<bool>,
+    /// Copy a relative `core.hooksPath` folder into new worktrees; `None` means on
+    #[serde(default, alias = "copy_hooks")]
+    pub copy_hooks: Option<bool>,
+    /// Redact secrets and emails in logs and transcripts; `None` means on
+    #[serde(default, alias = "redact_logs")]
+    pub redact_logs: Option<bool>,
+    /// Extra redaction patterns, `*` matching any characters (e.g. "*.corp.example.com")
+    #[serde(default, alias = "redaction_patterns")]
+    pub redaction_patterns: Vec<String>,
+    /// First port handed out by the port registry; `None` uses 41000
+    #[serde(default, alias = "port_range_start")]
+    pub port_range_start: Option<u16>,
+    /// Last port handed out by the port registry; `None` uses 41999
+    #[serde(default, alias = "port_range_end")]
+    pub port_range_end: Option<u16>,
+    /// Token for the GitHub API (branch protection); `None` uses `GITHUB_TOKEN`
+    /// or `GH_TOKEN`
+    #[serde(default, alias = "github_token")]
+    pub github_token: Option<String>,
+    /// Untracked files copied into new worktrees, as git globs (e.g. ".env*",
+    /// "config/local.*")
+    #[serde(default, alias = "copy_untracked_files")]
+    pub copy_untracked_files: Vec<String>,
+    /// Share dependency folders of the main worktree into new worktrees;
+    /// `None` means off
+    #[serde(default, alias = "share_dependencies")]
+    pub share_dependencies: Option<SharedDepsMode>,
+    /// Folders shared; empty uses `node_modules`, `target` and `.venv`
+    #[serde(default, alias = "shared_dependency_dirs")]
+    pub shared_dependency_dirs: Vec<String>,
+    /// Longest task name slug in agent folder names; `None` uses 40. Longer
+    /// slugs are cut and end in a hash of the full slug
+    #[serde(default, alias = "task_slug_max_length")]
+    pub task_slug_max_length: Option<usize>,
+    /// What to do when an agent folder name is taken; `None` appends the
+    /// agent number
+    #[serde(default, alias = "task_folder_collision")]
+    pub task_folder_collision: Option<TaskFolderCollision>,
+    /// Longest agent worktree path accepted, in bytes; `None` uses 200
+    #[serde(default, alias = "max_worktree_path_length")]
+    pub max_worktree_path_length: Option<usize>,
+    /// Which worktrees count as stale, and whether a background run cleans
+    /// them up (see `worktrees::stale`)
+    #[serde(default, alias = "stale_cleanup")]
+    pub stale_cleanup: StaleCleanupPolicy,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            theme_name: "aristar".to_string(),
+            color_scheme: "system".to_string(),
+            auto_refresh: true,
+            read_only: false,
+            branch_name_template: None,
+            worktree_name_template: None,
+            ticket_pattern: None,
+            worktree_aliases: false,
+            locale: None,
+            event_socket_port: None,
+            battery_throttle_percent: None,
+            battery_max_opencode_instances: None,
+            lfs_auto_pull: None,
+            copy_hooks: None,
+            redact_logs: None,
+            redaction_patterns: Vec::new(),
+            port_range_start: None,
+            port_range_end: None,
+            github_token: None,
+            copy_untracked_files: Vec::new(),
+            share_dependencies: None,
+            shared_dependency_dirs: Vec::new(),
+            task_slug_max_length: None,
+            task_folder_collision: None,
+            max_worktree_path_length: None,
+            stale_cleanup: StaleCleanupPolicy::default(),
+        }
+    }
+}
+
+/// How dependency folders of the main worktree are shared into others.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SharedDepsMode {
+    /// One symlink to the main worktree's folder
+    Symlink,
+    /// A folder tree whose files are hard links
+    Hardlink,
+}
+
+/// What happens when an agent worktree folder name is already taken.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskFolderCollision {
+    /// Append the agent number, e.g. "fix-login-gpt-4-2"
+    Number,
+    /// Refuse to create the agent
+    Error,
+}
+
+/// When a worktree is stale and what `cleanup_stale_worktrees` may do with it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StaleCleanupPolicy {
+    /// Days without activity (`last_active`) after which a worktree is stale
+    pub max_idle_days: u32,
+    /// Keep worktrees with uncommitted changes
+    pub only_clean: bool,
+    /// Keep locked worktrees
+    pub only_unlocked: bool,
+    /// Run the cleanup in the background once a day
+    pub scheduled: bool,
+    /// Let the background run remove stale worktrees; otherwise it only
+    /// reports them
+    pub remove_in_background: bool,
+}
+
+impl Default for StaleCleanupPolicy {
+    fn default() -> Self {
+        Self {
+            max_idle_days: 30,
+            only_clean: true,
+            only_unlocked: true,
+            scheduled: false,
+            remove_in_background: false,
+        }
+    }
+}
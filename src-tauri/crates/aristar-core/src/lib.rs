@@ -0,0 +1,23 @@
+//! Domain logic of Aristar Worktrees: git worktree operations, the task and
+//! agent manager, OpenCode process management, merging and workspace-wide
+//! aggregates. No Tauri dependency, so the app, its CLI commands, tests and
+//! editor plugins can all build on it.
+//!
+//! Background work reports to the caller through return values or listener
+//! functions (e.g. `worktrees::lfs::set_progress_listener`); the Tauri shell
+//! turns those into app events.
+
+pub mod agent_manager;
+pub mod core;
+pub mod merge;
+pub mod workspace;
+pub mod worktrees;
+
+#[cfg(test)]
+mod tests;
+
+// Re-export commonly used types and functions
+pub use agent_manager::{OpenCodeManager, TaskManagerState};
+pub use workspace::GlobalStatusCache;
+pub use worktrees::init_store;
+pub use worktrees::store::AppState;
@@ -0,0 +1,9 @@
+//! Merge module - Publishing worktree commits onto a target branch.
+//!
+//! This module contains:
+//! - Merge strategies (squash, rebase, merge commit)
+//! - Publishing an agent's work onto the task's source branch
+//! - Per-repository default strategy
+
+pub mod operations;
+pub mod types;
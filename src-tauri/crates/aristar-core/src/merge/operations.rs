@@ -0,0 +1,437 @@
+//! Git operations for publishing worktree commits.
+//!
+//! All history rewriting happens inside the (detached) source worktree, so the
+//! target branch only ever moves forward: via `git merge --ff-only` in the
+//! worktree that has it checked out, or `git update-ref` with the expected old
+//! value when it is not checked out anywhere.
+//!
+//! Merging a branch worktree into its base branch never touches the worktree:
+//! the merge is computed with `git merge-tree --write-tree` (git 2.38+), which
+//! reports conflicts without a checkout.
+
+use std::process::Command;
+
+use crate::worktrees::operations::{get_current_branch, is_ancestor, run_git_command};
+use crate::worktrees::types::MergeStrategy;
+
+use super::types::{BaseMergeResult, PublishResult};
+
+/// Pick the strategy to use: explicit request, then repository default, then `MergeCommit`.
+pub fn resolve_merge_strategy(
+    requested: Option<MergeStrategy>,
+    repo_default: Option<MergeStrategy>,
+) -> MergeStrategy {
+    requested.or(repo_default).unwrap_or_default()
+}
+
+/// Build the commit message for a squashed publish.
+pub fn build_squash_message(
+    title: &str,
+    source_label: Option<&str>,
+    subjects: &[String],
+) -> String {
+    let mut message = title.trim().to_string();
+    if message.is_empty() {
+        message = "Squashed changes".to_string();
+    }
+
+    message.push_str(&format!(
+        "\n\nSquashed {} commit{}",
+        subjects.len(),
+        if subjects.len() == 1 { "" } else { "s" }
+    ));
+    if let Some(label) = source_label {
+        message.push_str(&format!(" from {}", label));
+    }
+    message.push(':');
+
+    // git log lists newest first; the message reads oldest first
+    for subject in subjects.iter().rev() {
+        message.push_str(&format!("\n- {}", subject));
+    }
+    message
+}
+
+/// Resolve a revision to a full commit hash.
+fn rev_parse(cwd: &str, rev: &str) -> Result<String, String> {
+    let output = run_git_command(&["rev-parse", "--verify", rev], cwd)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Subjects of the commits in `base..head`, newest first.
+fn commit_subjects(cwd: &str, base: &str, head: &str) -> Result<Vec<String>, String> {
+    let range = format!("{}..{}", base, head);
+    let output = run_git_command(&["log", "--format=%s", &range], cwd)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Find the worktree that has `branch` checked out, if any.
+pub fn find_branch_worktree(repo_path: &str, branch: &str) -> Result<Option<String>, String> {
+    let output = run_git_command(&["worktree", "list", "--porcelain"], repo_path)?;
+    let wanted = format!("branch refs/heads/{}", branch);
+
+    let mut current_path: Option<&str> = None;
+    let text = String::from_utf8_lossy(&output.stdout);
+    for line in text.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current_path = Some(path);
+        } else if line == wanted {
+            return Ok(current_path.map(|p| p.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether a worktree has no uncommitted changes.
+pub fn is_worktree_clean(worktree_path: &str) -> Result<bool, String> {
+    let output = run_git_command(&["status", "--porcelain"], worktree_path)?;
+    Ok(output.stdout.is_empty())
+}
+
+/// Put the worktree back on `head` after a failed merge/rebase attempt.
+fn restore_head(worktree_path: &str, head: &str) {
+    let _ = run_git_command(&["reset", "--hard", head], worktree_path);
+}
+
+/// Move the target branch to `new_commit`, which must descend from `old_commit`.
+fn advance_branch(
+    repo_path: &str,
+    branch: &str,
+    old_commit: &str,
+    new_commit: &str,
+) -> Result<(), String> {
+    match find_branch_worktree(repo_path, branch)? {
+        Some(checkout) => {
+            run_git_command(&["merge", "--ff-only", new_commit], &checkout).map_err(|e| {
+                format!(
+                    "Failed to fast-forward {} in {}: {}",
+                    branch,
+                    checkout,
+                    e.trim()
+                )
+            })?;
+        }
+        None => {
+            let ref_name = format!("refs/heads/{}", branch);
+            run_git_command(
+                &["update-ref", &ref_name, new_commit, old_commit],
+                repo_path,
+            )
+            .map_err(|e| format!("Failed to update {}: {}", branch, e.trim()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Publish the commits of a worktree onto `target_branch` of its repository.
+///
+/// The worktree must be clean. On conflicts the worktree is restored to its
+/// previous HEAD and the target branch is left untouched. On success the
+/// worktree is left detached at the published commit.
+///
+/// `title` and `source_label` (e.g. the agent and model) seed generated commit
+/// messages; `message` overrides them.
+pub fn publish_worktree(
+    worktree_path: &str,
+    repo_path: &str,
+    target_branch: &str,
+    strategy: MergeStrategy,
+    title: &str,
+    source_label: Option<&str>,
+    message: Option<&str>,
+) -> Result<PublishResult, String> {
+    let target_ref = format!("refs/heads/{}", target_branch);
+    let old_tip = rev_parse(repo_path, &target_ref)
+        .map_err(|_| format!("Target branch not found: {}", target_branch))?;
+
+    if !is_worktree_clean(worktree_path)? {
+        return Err(
+            "Worktree has uncommitted changes; commit or discard them before publishing"
+                .to_string(),
+        );
+    }
+
+    let head = rev_parse(worktree_path, "HEAD")?;
+    let subjects = commit_subjects(worktree_path, &old_tip, &head)?;
+    if subjects.is_empty() {
+        return Err(format!(
+            "Nothing to publish: no commits ahead of {}",
+            target_branch
+        ));
+    }
+
+    let up_to_date = is_ancestor(worktree_path, &old_tip, &head)?;
+
+    let new_commit = match strategy {
+        MergeStrategy::Squash => {
+            let message = message
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| build_squash_message(title, source_label, &subjects));
+
+            if up_to_date {
+                // Target has not moved: the squashed commit is the worktree's tree on top of it
+                let tree = format!("{}^{{tree}}", head);
+                let output = run_git_command(
+                    &["commit-tree", &tree, "-p", &old_tip, "-m", &message],
+                    worktree_path,
+                )?;
+                let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                run_git_command(&["checkout", "--detach", &commit], worktree_path)?;
+                commit
+            } else {
+                run_git_command(&["checkout", "--detach", &old_tip], worktree_path)?;
+                if let Err(e) = run_git_command(&["merge", "--squash", &head], worktree_path) {
+                    restore_head(worktree_path, &head);
+                    return Err(format!(
+                        "Squash conflicts with {}: {}",
+                        target_branch,
+                        e.trim()
+                    ));
+                }
+                run_git_command(&["commit", "--no-verify", "-m", &message], worktree_path)
+                    .inspect_err(|_| restore_head(worktree_path, &head))?;
+                rev_parse(worktree_path, "HEAD")?
+            }
+        }
+        MergeStrategy::Rebase => {
+            if !up_to_date {
+                if let Err(e) = run_git_command(&["rebase", &old_tip], worktree_path) {
+                    let _ = run_git_command(&["rebase", "--abort"], worktree_path);
+                    restore_head(worktree_path, &head);
+                    return Err(format!(
+                        "Rebase onto {} has conflicts: {}",
+                        target_branch,
+                        e.trim()
+                    ));
+                }
+            }
+            rev_parse(worktree_path, "HEAD")?
+        }
+        MergeStrategy::FastForward => {
+            if !up_to_date {
+                return Err(format!(
+                    "Cannot fast-forward {}: it has commits that are not in the worktree",
+                    target_branch
+                ));
+            }
+            head.clone()
+        }
+        MergeStrategy::MergeCommit => {
+            let message = message
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| format!("Merge '{}' into {}", title.trim(), target_branch));
+
+            run_git_command(&["checkout", "--detach", &old_tip], worktree_path)?;
+            if let Err(e) = run_git_command(
+                &["merge", "--no-ff", "--no-verify", "-m", &message, &head],
+                worktree_path,
+            ) {
+                let _ = run_git_command(&["merge", "--abort"], worktree_path);
+                restore_head(worktree_path, &head);
+                return Err(format!(
+                    "Merge into {} has conflicts: {}",
+                    target_branch,
+                    e.trim()
+                ));
+            }
+            rev_parse(worktree_path, "HEAD")?
+        }
+    };
+
+    advance_branch(repo_path, target_branch, &old_tip, &new_commit)?;
+
+    println!(
+        "[merge] Published {} commit(s) from {} onto {} ({:?})",
+        subjects.len(),
+        worktree_path,
+        target_branch,
+        strategy
+    );
+
+    Ok(PublishResult {
+        strategy,
+        target_branch: target_branch.to_string(),
+        previous_commit: old_tip,
+        commit: new_commit,
+        commits_published: subjects.len(),
+    })
+}
+
+/// Parse `git merge-tree --write-tree --name-only` output into the merged tree,
+/// the conflicted paths and git's informational messages.
+pub fn parse_merge_tree_output(output: &str) -> (String, Vec<String>, Vec<String>) {
+    let mut lines = output.lines();
+    let tree = lines.next().unwrap_or_default().trim().to_string();
+
+    let mut conflicts: Vec<String> = Vec::new();
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        if !conflicts.iter().any(|c| c == line) {
+            conflicts.push(line.to_string());
+        }
+    }
+    let messages = lines
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.to_string())
+        .collect();
+    (tree, conflicts, messages)
+}
+
+/// Merge `head` into `base` without a checkout. Exit code 1 means conflicts,
+/// which are returned rather than treated as an error.
+fn merge_tree(
+    repo_path: &str,
+    base: &str,
+    head: &str,
+) -> Result<(String, Vec<String>, Vec<String>), String> {
+    let output = Command::new("git")
+        .args(["merge-tree", "--write-tree", "--name-only", base, head])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(parse_merge_tree_output(&String::from_utf8_lossy(
+            &output.stdout,
+        ))),
+        _ => Err(format!(
+            "git merge-tree failed (git 2.38 or newer is required): {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Merge the committed work of a branch worktree into `base_branch` of its
+/// repository, leaving the worktree and its branch as they are.
+///
+/// The worktree must be clean. Conflicts are reported in the result and leave
+/// everything untouched. On success the base branch moves forward like with
+/// `publish_worktree`, updating the checkout that has it (usually the main
+/// repository). `Rebase` is not supported because it rewrites the worktree.
+pub fn merge_worktree_into_base(
+    worktree_path: &str,
+    repo_path: &str,
+    base_branch: &str,
+    strategy: MergeStrategy,
+    message: Option<&str>,
+) -> Result<BaseMergeResult, String> {
+    if strategy == MergeStrategy::Rebase {
+        return Err(
+            "Rebase is not available when merging into the base branch; use fast-forward, merge commit or squash"
+                .to_string(),
+        );
+    }
+
+    let base_ref = format!("refs/heads/{}", base_branch);
+    let old_tip = rev_parse(repo_path, &base_ref)
+        .map_err(|_| format!("Base branch not found: {}", base_branch))?;
+
+    let head = rev_parse(worktree_path, "HEAD")?;
+    let source = match get_current_branch(worktree_path) {
+        Ok(branch) if branch == base_branch => {
+            return Err(format!("Worktree is on {} itself", base_branch));
+        }
+        Ok(branch) => branch,
+        Err(_) => head.chars().take(7).collect(),
+    };
+
+    if !is_worktree_clean(worktree_path)? {
+        return Err(
+            "Worktree has uncommitted changes; commit or discard them before merging".to_string(),
+        );
+    }
+
+    let subjects = commit_subjects(worktree_path, &old_tip, &head)?;
+    if subjects.is_empty() {
+        return Err(format!(
+            "Nothing to merge: {} has no commits that are not in {}",
+            source, base_branch
+        ));
+    }
+
+    let mut result = BaseMergeResult {
+        strategy,
+        target_branch: base_branch.to_string(),
+        source: source.clone(),
+        merged: false,
+        previous_commit: old_tip.clone(),
+        commit: None,
+        commits_merged: subjects.len(),
+        conflicts: Vec::new(),
+        messages: Vec::new(),
+    };
+
+    let new_commit = if strategy == MergeStrategy::FastForward {
+        if !is_ancestor(repo_path, &old_tip, &head)? {
+            return Err(format!(
+                "Cannot fast-forward {}: it has commits that are not in {}",
+                base_branch, source
+            ));
+        }
+        head
+    } else {
+        let (tree, conflicts, messages) = merge_tree(repo_path, &old_tip, &head)?;
+        result.messages = messages;
+        if !conflicts.is_empty() {
+            result.conflicts = conflicts;
+            return Ok(result);
+        }
+
+        let mut args = vec!["commit-tree", tree.as_str(), "-p", old_tip.as_str()];
+        let message = match strategy {
+            MergeStrategy::Squash => message
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| build_squash_message(&source, None, &subjects)),
+            _ => {
+                args.extend(["-p", head.as_str()]);
+                message
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| format!("Merge branch '{}' into {}", source, base_branch))
+            }
+        };
+        args.extend(["-m", message.as_str()]);
+        let output = run_git_command(&args, repo_path)?;
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    advance_branch(repo_path, base_branch, &old_tip, &new_commit)?;
+
+    println!(
+        "[merge] Merged {} commit(s) from {} into {} ({:?})",
+        subjects.len(),
+        source,
+        base_branch,
+        strategy
+    );
+
+    result.merged = true;
+    result.commit = Some(new_commit);
+    Ok(result)
+}
+
+/// Merge a worktree into its base branch (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn merge_worktree_into_base_async(
+    worktree_path: String,
+    repo_path: String,
+    base_branch: String,
+    strategy: MergeStrategy,
+    message: Option<String>,
+) -> Result<BaseMergeResult, String> {
+    tokio::task::spawn_blocking(move || {
+        merge_worktree_into_base(
+            &worktree_path,
+            &repo_path,
+            &base_branch,
+            strategy,
+            message.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
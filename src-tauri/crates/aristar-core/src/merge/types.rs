@@ -0,0 +1,47 @@
+//! Merge-related types.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::worktrees::types::MergeStrategy;
+
+/// Result of publishing a worktree onto a target branch.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishResult {
+    /// Strategy that was applied
+    pub strategy: MergeStrategy,
+    /// Branch that received the commits
+    pub target_branch: String,
+    /// Target branch tip before publishing
+    pub previous_commit: String,
+    /// Target branch tip after publishing
+    pub commit: String,
+    /// Number of worktree commits that were published
+    pub commits_published: usize,
+}
+
+/// Result of merging a worktree into its base branch. When the merge has
+/// conflicts nothing is changed, `merged` is false and `conflicts` lists them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseMergeResult {
+    /// Strategy that was applied
+    pub strategy: MergeStrategy,
+    /// Branch that received the commits
+    pub target_branch: String,
+    /// Worktree branch (or short commit when detached) that was merged
+    pub source: String,
+    /// Whether the target branch was updated
+    pub merged: bool,
+    /// Target branch tip before merging
+    pub previous_commit: String,
+    /// Target branch tip after merging (`None` when not merged)
+    pub commit: Option<String>,
+    /// Number of worktree commits not yet on the target
+    pub commits_merged: usize,
+    /// Paths with conflicts
+    pub conflicts: Vec<String>,
+    /// Git's messages about the merge (e.g. "CONFLICT (content): ...")
+    pub messages: Vec<String>,
+}
@@ -0,0 +1,92 @@
+//! Tests for agreement between agent diffs.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::agreement::{
+    classify_file, compute_agreement, parse_patch_lines, patch_similarity, FilePatch,
+};
+use crate::agent_manager::types::FileAgreement;
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+
+fn patch(lines: &[&str], content: &str) -> FilePatch {
+    FilePatch {
+        lines: lines.iter().map(|l| l.to_string()).collect(),
+        content: Some(content.as_bytes().to_vec()),
+    }
+}
+
+#[test]
+fn test_parse_patch_lines_groups_by_file() {
+    let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                @@ -1 +1 @@\n-old\n+  new  \n\
+                diff --git a/gone.txt b/gone.txt\n--- a/gone.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-bye\n";
+    let files = parse_patch_lines(diff);
+
+    assert_eq!(files["src/a.rs"], vec!["-old", "+new"]);
+    assert_eq!(files["gone.txt"], vec!["-bye"]);
+}
+
+#[test]
+fn test_patch_similarity() {
+    let a: Vec<String> = vec!["+a".into(), "+b".into(), "-c".into(), "+d".into()];
+    let b: Vec<String> = vec!["+a".into(), "+b".into(), "-c".into(), "+e".into()];
+
+    assert_eq!(patch_similarity(&a, &a), 100);
+    assert_eq!(patch_similarity(&a, &b), 75);
+    assert_eq!(patch_similarity(&a, &[]), 0);
+    assert_eq!(patch_similarity(&[], &[]), 100);
+}
+
+#[test]
+fn test_classify_file() {
+    let same = classify_file(
+        "a.rs",
+        &[
+            ("agent-1".into(), patch(&["+x"], "x")),
+            ("agent-2".into(), patch(&["+x"], "x")),
+        ],
+        2,
+    );
+    assert_eq!(same.agreement, FileAgreement::Identical);
+    assert_eq!(same.similarity, 100);
+
+    let close = classify_file(
+        "a.rs",
+        &[
+            ("agent-1".into(), patch(&["+a", "+b", "-c", "+d"], "abd")),
+            ("agent-2".into(), patch(&["+a", "+b", "-c", "+e"], "abe")),
+        ],
+        2,
+    );
+    assert_eq!(close.agreement, FileAgreement::Similar);
+    assert_eq!(close.similarity, 75);
+
+    let partial = classify_file("a.rs", &[("agent-1".into(), patch(&["+x"], "x"))], 2);
+    assert_eq!(partial.agreement, FileAgreement::Divergent);
+    assert_eq!(partial.changed_by, vec!["agent-1".to_string()]);
+}
+
+#[test]
+fn test_compute_agreement_across_worktrees() {
+    let repo = TestRepo::new();
+    let parent = TempDir::new().unwrap();
+    let mut agents = Vec::new();
+    for (i, extra) in ["one", "two"].iter().enumerate() {
+        let path = parent.path().join(extra).to_string_lossy().to_string();
+        run_git_command(&["worktree", "add", "--detach", &path], &repo.path_str()).unwrap();
+        fs::write(format!("{}/test.txt", path), "agreed change").unwrap();
+        fs::write(format!("{}/{}.txt", path, extra), "only here").unwrap();
+        agents.push((format!("agent-{}", i + 1), path));
+    }
+
+    let report = compute_agreement(&agents, "HEAD").unwrap();
+
+    assert_eq!(report.agent_ids, vec!["agent-1", "agent-2"]);
+    assert_eq!(report.identical_count, 1);
+    assert_eq!(report.divergent_count, 2);
+    assert_eq!(report.files.last().unwrap().path, "test.txt");
+    assert_eq!(report.files[0].agreement, FileAgreement::Divergent);
+}
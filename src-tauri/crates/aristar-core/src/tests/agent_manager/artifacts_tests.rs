@@ -0,0 +1,120 @@
+//! Tests for listing and cleaning up refs created for tasks.
+
+use std::fs;
+
+use crate::agent_manager::artifacts::{
+    accepted_lineage, cleanup_task_artifacts, list_task_artifacts,
+};
+use crate::agent_manager::snapshot::{
+    checkpoint_ref_name, create_uncommitted_snapshot, snapshot_ref_name,
+};
+use crate::agent_manager::types::{AgentStatus, Task, TaskAgent, TaskArtifactKind, TaskStatus};
+use crate::tests::helpers::{test_agent, test_task, TestRepo};
+use crate::worktrees::operations::run_git_command;
+
+fn create_agent(id: &str, accepted: bool, forked_from: Option<&str>) -> TaskAgent {
+    TaskAgent {
+        accepted,
+        forked_from: forked_from.map(str::to_string),
+        ..test_agent(id)
+    }
+}
+
+fn create_task(repo: &TestRepo, agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: "Cleanup".to_string(),
+        source_branch: Some(repo.current_branch()),
+        source_repo_path: repo.path_str(),
+        status: TaskStatus::Completed,
+        ..test_task(agents)
+    }
+}
+
+fn ref_exists(repo: &TestRepo, ref_name: &str) -> bool {
+    run_git_command(&["rev-parse", "--verify", "-q", ref_name], &repo.path_str()).is_ok()
+}
+
+#[test]
+fn test_accepted_lineage_follows_forks() {
+    let repo = TestRepo::new();
+    let task = create_task(
+        &repo,
+        vec![
+            create_agent("agent-1", false, None),
+            create_agent("agent-2", true, Some("agent-1")),
+            create_agent("agent-3", false, Some("agent-1")),
+        ],
+    );
+    let lineage = accepted_lineage(&task);
+    assert_eq!(lineage.len(), 2);
+    assert!(lineage.contains("agent-1") && lineage.contains("agent-2"));
+    assert!(accepted_lineage(&create_task(&repo, vec![])).is_empty());
+}
+
+#[test]
+fn test_list_and_cleanup_task_artifacts() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join("test.txt"), "uncommitted").unwrap();
+    let snapshot = create_uncommitted_snapshot(&repo.path_str(), "a1b2c3d4")
+        .unwrap()
+        .unwrap();
+    let head = String::from_utf8_lossy(
+        &run_git_command(&["rev-parse", "HEAD"], &repo.path_str())
+            .unwrap()
+            .stdout,
+    )
+    .trim()
+    .to_string();
+    let update_ref = |name: &str, commit: &str| {
+        run_git_command(&["update-ref", name, commit], &repo.path_str()).unwrap();
+    };
+    // The accepted agent's checkpoint, and a rejected one already on the branch
+    update_ref(&checkpoint_ref_name("a1b2c3d4", "agent-2"), &snapshot);
+    update_ref(&checkpoint_ref_name("a1b2c3d4", "agent-3"), &head);
+    // Another task's ref with the same prefix
+    update_ref(&snapshot_ref_name("a1b2c3d4ff"), &snapshot);
+
+    let mut task = create_task(
+        &repo,
+        vec![
+            create_agent("agent-1", false, None),
+            create_agent("agent-2", true, Some("agent-1")),
+            create_agent("agent-3", false, Some("agent-1")),
+        ],
+    );
+    let artifacts = list_task_artifacts(&task).unwrap();
+    assert_eq!(artifacts.len(), 3);
+    let snapshot_artifact = artifacts
+        .iter()
+        .find(|a| a.kind == TaskArtifactKind::Snapshot)
+        .unwrap();
+    assert_eq!(snapshot_artifact.commit, snapshot);
+    assert!(snapshot_artifact.accepted && !snapshot_artifact.published);
+    let rejected = artifacts
+        .iter()
+        .find(|a| a.agent_id.as_deref() == Some("agent-3"))
+        .unwrap();
+    assert!(!rejected.accepted && rejected.published);
+
+    // Nothing is deleted while an agent runs
+    task.agents[2].status = AgentStatus::Running;
+    assert!(cleanup_task_artifacts(&task, true).is_err());
+    task.agents[2].status = AgentStatus::Completed;
+
+    let cleanup = cleanup_task_artifacts(&task, true).unwrap();
+    assert_eq!(cleanup.deleted.len(), 1);
+    assert_eq!(cleanup.kept.len(), 2);
+    assert!(!ref_exists(
+        &repo,
+        &checkpoint_ref_name("a1b2c3d4", "agent-3")
+    ));
+    assert!(ref_exists(
+        &repo,
+        &checkpoint_ref_name("a1b2c3d4", "agent-2")
+    ));
+
+    let cleanup = cleanup_task_artifacts(&task, false).unwrap();
+    assert_eq!(cleanup.deleted.len(), 2);
+    assert!(list_task_artifacts(&task).unwrap().is_empty());
+    assert!(ref_exists(&repo, &snapshot_ref_name("a1b2c3d4ff")));
+}
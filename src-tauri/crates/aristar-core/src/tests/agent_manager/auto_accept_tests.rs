@@ -0,0 +1,138 @@
+//! Tests for auto-accept policies.
+
+use crate::agent_manager::auto_accept::{auto_accept_candidate, validate_auto_accept_policy};
+use crate::agent_manager::types::{
+    AgentScore, AgentStatus, AgentVerification, AutoAcceptPolicy, PipelineRun, PipelineStatus,
+    Task, TaskAgent, TaskStatus,
+};
+use crate::tests::helpers::{test_agent, test_task};
+
+fn create_agent(id: &str, status: AgentStatus) -> TaskAgent {
+    TaskAgent {
+        status,
+        ..test_agent(id)
+    }
+}
+
+fn verification(agent_id: &str, passed: bool) -> AgentVerification {
+    AgentVerification {
+        agent_id: agent_id.to_string(),
+        command: "cargo test".to_string(),
+        passed,
+        exit_code: Some(if passed { 0 } else { 1 }),
+        output: String::new(),
+    }
+}
+
+fn score(agent_id: &str, score: u32) -> AgentScore {
+    AgentScore {
+        agent_id: agent_id.to_string(),
+        score,
+        checks_passed: 0,
+        checks_total: 0,
+        open_comments: 0,
+    }
+}
+
+fn create_task(verifications: Vec<AgentVerification>, scores: Vec<AgentScore>) -> Task {
+    Task {
+        name: "Auto".to_string(),
+        status: TaskStatus::Completed,
+        pipeline_run: Some(PipelineRun {
+            pipeline: "ci".to_string(),
+            status: PipelineStatus::Passed,
+            started_at: 0,
+            finished_at: Some(1),
+            steps: Vec::new(),
+            verifications,
+            scores,
+        }),
+        ..test_task(vec![
+            create_agent("agent-1", AgentStatus::Completed),
+            create_agent("agent-2", AgentStatus::Completed),
+            create_agent("agent-3", AgentStatus::Failed),
+        ])
+    }
+}
+
+fn sole_passer() -> AutoAcceptPolicy {
+    AutoAcceptPolicy {
+        sole_passer: true,
+        ..Default::default()
+    }
+}
+
+fn min_score(min: u32) -> AutoAcceptPolicy {
+    AutoAcceptPolicy {
+        min_score: Some(min),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_validate_auto_accept_policy() {
+    assert!(validate_auto_accept_policy(&sole_passer()).is_ok());
+    assert!(validate_auto_accept_policy(&min_score(80)).is_ok());
+    assert!(validate_auto_accept_policy(&AutoAcceptPolicy::default())
+        .unwrap_err()
+        .contains("needs a rule"));
+    assert!(validate_auto_accept_policy(&min_score(101)).is_err());
+    assert!(validate_auto_accept_policy(&AutoAcceptPolicy {
+        delay_secs: 2 * 24 * 60 * 60,
+        ..sole_passer()
+    })
+    .is_err());
+}
+
+#[test]
+fn test_sole_passer_candidate() {
+    let task = create_task(
+        vec![
+            verification("agent-1", true),
+            verification("agent-1", true),
+            verification("agent-2", true),
+            verification("agent-2", false),
+            // Failed agents never count
+            verification("agent-3", true),
+        ],
+        Vec::new(),
+    );
+    assert_eq!(
+        auto_accept_candidate(&sole_passer(), &task),
+        Some((
+            "agent-1".to_string(),
+            "only agent to pass verification".to_string()
+        ))
+    );
+
+    // Two passers: no clear winner
+    let task = create_task(
+        vec![verification("agent-1", true), verification("agent-2", true)],
+        Vec::new(),
+    );
+    assert_eq!(auto_accept_candidate(&sole_passer(), &task), None);
+
+    // Nothing once an agent is accepted
+    let mut task = create_task(vec![verification("agent-1", true)], Vec::new());
+    task.agents[1].accepted = true;
+    assert_eq!(auto_accept_candidate(&sole_passer(), &task), None);
+}
+
+#[test]
+fn test_min_score_candidate() {
+    let task = create_task(Vec::new(), vec![score("agent-1", 70), score("agent-2", 90)]);
+    assert_eq!(
+        auto_accept_candidate(&min_score(80), &task).map(|(id, _)| id),
+        Some("agent-2".to_string())
+    );
+    assert_eq!(auto_accept_candidate(&min_score(95), &task), None);
+
+    // Ties are left to the user
+    let task = create_task(Vec::new(), vec![score("agent-1", 90), score("agent-2", 90)]);
+    assert_eq!(auto_accept_candidate(&min_score(80), &task), None);
+
+    // Without a pipeline run there is nothing to go on
+    let mut task = create_task(Vec::new(), vec![score("agent-1", 100)]);
+    task.pipeline_run = None;
+    assert_eq!(auto_accept_candidate(&min_score(0), &task), None);
+}
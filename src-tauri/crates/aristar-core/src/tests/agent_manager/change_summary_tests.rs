@@ -0,0 +1,107 @@
+//! Tests for heuristic change summaries of agent diffs.
+
+use crate::agent_manager::change_summary::{
+    area_for_path, build_change_summary, collect_api_changes, compute_change_summary,
+    extract_api_declaration,
+};
+use crate::agent_manager::types::FileChange;
+use crate::tests::helpers::TestRepo;
+
+fn file(path: &str, status: &str, insertions: u32, deletions: u32) -> FileChange {
+    FileChange {
+        path: path.to_string(),
+        status: status.to_string(),
+        insertions,
+        deletions,
+        binary: false,
+    }
+}
+
+// ============================================================================
+// Parsing tests
+// ============================================================================
+
+#[test]
+fn test_area_for_path() {
+    assert_eq!(area_for_path("README.md"), "(root)");
+    assert_eq!(area_for_path("docs/guide.md"), "docs");
+    assert_eq!(area_for_path("src/components/button.tsx"), "src/components");
+    assert_eq!(area_for_path("src/main.rs"), "src");
+    assert_eq!(area_for_path("scripts/ci/build.sh"), "scripts");
+}
+
+#[test]
+fn test_extract_api_declaration() {
+    assert_eq!(
+        extract_api_declaration("pub fn parse(input: &str)"),
+        Some("fn parse".to_string())
+    );
+    assert_eq!(
+        extract_api_declaration("export interface ButtonProps {"),
+        Some("interface ButtonProps".to_string())
+    );
+    assert_eq!(
+        extract_api_declaration("def handler(event):"),
+        Some("def handler".to_string())
+    );
+    // Indented Python methods and private Rust items are not public APIs
+    assert_eq!(extract_api_declaration("    def helper(self):"), None);
+    assert_eq!(extract_api_declaration("fn private()"), None);
+}
+
+#[test]
+fn test_collect_api_changes_ignores_changed_signatures() {
+    let patch = "diff --git a/src/lib.rs b/src/lib.rs\n\
+                 --- a/src/lib.rs\n\
+                 +++ b/src/lib.rs\n\
+                 -pub fn keep(a: u8)\n\
+                 +pub fn keep(a: u16)\n\
+                 +pub struct Added;\n\
+                 -pub enum Gone {}\n";
+    let (added, removed) = collect_api_changes(patch);
+    assert_eq!(added, vec!["src/lib.rs: struct Added"]);
+    assert_eq!(removed, vec!["src/lib.rs: enum Gone"]);
+}
+
+// ============================================================================
+// Summary tests
+// ============================================================================
+
+#[test]
+fn test_build_change_summary_notable_deletions() {
+    let files = vec![
+        file("src/old.rs", "deleted", 0, 40),
+        file("src/big.rs", "modified", 10, 150),
+        file("README.md", "modified", 5, 1),
+    ];
+    let summary = build_change_summary("abc123", files, "", vec![]);
+
+    assert_eq!(summary.files_changed, 3);
+    assert_eq!(summary.deletions, 191);
+    assert_eq!(summary.notable_deletions.len(), 2);
+    assert!(summary.notable_deletions[0].contains("Deleted src/old.rs"));
+    assert!(summary.markdown.contains("### Notable deletions"));
+    assert!(summary.markdown.contains("3 files changed, +15 / -191"));
+}
+
+#[test]
+fn test_compute_change_summary_includes_commits_and_untracked_files() {
+    let repo = TestRepo::new();
+    repo.create_branch("base");
+    repo.commit("Committed change");
+    std::fs::write(repo.path().join("api.rs"), "pub fn exported() {}\n").unwrap();
+
+    let summary = compute_change_summary(&repo.path_str(), "base").unwrap();
+
+    // One committed file plus one untracked file
+    assert_eq!(summary.files_changed, 2);
+    let paths: Vec<&str> = summary
+        .areas
+        .iter()
+        .flat_map(|a| a.files.iter().map(|f| f.path.as_str()))
+        .collect();
+    assert!(paths.contains(&"api.rs"));
+    assert!(summary
+        .added_apis
+        .contains(&"api.rs: fn exported".to_string()));
+}
@@ -0,0 +1,159 @@
+//! Tests for the side-by-side HTML comparison of agent diffs.
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::comparison::{
+    escape_html, export_agent_comparison, highlight_diff_lines, parse_unified_diff,
+    render_comparison_html, syntax_for_path, AgentDiff, DiffLineKind,
+};
+use crate::agent_manager::types::{Task, TaskAgent, TaskStatus};
+use crate::tests::helpers::{test_agent, test_task, TestRepo};
+use crate::worktrees::operations::run_git_command;
+
+fn create_agent(id: &str, worktree_path: String) -> TaskAgent {
+    TaskAgent {
+        worktree_path,
+        ..test_agent(id)
+    }
+}
+
+fn create_task(repo: &TestRepo, agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: "Fix <login>".to_string(),
+        source_branch: Some(repo.current_branch()),
+        source_repo_path: repo.path_str(),
+        status: TaskStatus::Completed,
+        ..test_task(agents)
+    }
+}
+
+#[test]
+fn test_parse_unified_diff_numbers_lines() {
+    let diff = "diff --git a/src/a.rs b/src/a.rs\nindex 1..2 100644\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+                @@ -3,3 +3,3 @@ fn main() {\n keep\n-old\n+new\n keep\n\\ No newline at end of file\n\
+                diff --git a/logo.png b/logo.png\nBinary files a/logo.png and b/logo.png differ\n";
+    let files = parse_unified_diff(diff);
+
+    let lines = &files["src/a.rs"];
+    assert_eq!(lines[0].kind, DiffLineKind::Hunk);
+    assert_eq!((lines[1].old_line, lines[1].new_line), (Some(3), Some(3)));
+    assert_eq!(
+        (lines[2].kind, lines[2].old_line),
+        (DiffLineKind::Removed, Some(4))
+    );
+    assert_eq!(
+        (lines[3].kind, lines[3].new_line),
+        (DiffLineKind::Added, Some(4))
+    );
+    assert_eq!(lines[3].text, "new");
+    assert_eq!((lines[4].old_line, lines[4].new_line), (Some(5), Some(5)));
+    assert_eq!(lines[5].kind, DiffLineKind::Note);
+    assert_eq!(files["logo.png"][0].text, "Binary file changed");
+}
+
+#[test]
+fn test_syntax_for_path() {
+    assert_eq!(syntax_for_path("src/main.rs").unwrap().name, "Rust");
+    assert_eq!(syntax_for_path("web/app.tsx").unwrap().name, "JavaScript");
+    assert_eq!(syntax_for_path("build/Makefile").unwrap().name, "Makefile");
+    assert!(syntax_for_path("data/notes.unknown").is_none());
+}
+
+#[test]
+fn test_highlight_diff_lines() {
+    let files = parse_unified_diff(
+        "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,3 @@\n\
+         -let a = \"x<y\"; /* old\n\
+         +let a = 1; /* new\n\
+         +still comment */\n\
+          fn main() {}\n",
+    );
+    let lines = &files["a.rs"];
+    let html = highlight_diff_lines("a.rs", lines);
+    assert_eq!(html.len(), lines.len());
+    assert_eq!(html[0], escape_html(&lines[0].text));
+    assert!(html[1].contains("<span style=") && html[1].contains("&quot;x&lt;y&quot;"));
+
+    // A comment opened on the new side continues on the next added line, while
+    // the comment left open on the old side doesn't leak into the context line
+    let style_of = |html: &str, text: &str| {
+        let end = html.find(text).unwrap();
+        html[..end]
+            .rsplit("<span style=\"")
+            .next()
+            .unwrap()
+            .to_string()
+    };
+    let comment = style_of(&html[2], "/* new");
+    assert_eq!(style_of(&html[3], "still comment"), comment);
+    assert!(!html[4].contains(&comment), "{}", html[4]);
+
+    let plain = highlight_diff_lines("notes.unknown", lines);
+    assert_eq!(plain[1], "let a = &quot;x&lt;y&quot;; /* old");
+}
+
+#[test]
+fn test_render_comparison_html() {
+    let diff = parse_unified_diff(
+        "diff --git a/a.py b/a.py\n--- a/a.py\n+++ b/a.py\n@@ -1 +1 @@\n-x = 1\n+x = 2\n",
+    );
+    let agents = vec![
+        AgentDiff {
+            agent_id: "agent-1".to_string(),
+            label: "anthropic/<model>".to_string(),
+            files: diff,
+        },
+        AgentDiff {
+            agent_id: "agent-2".to_string(),
+            label: "openai/gpt".to_string(),
+            files: BTreeMap::new(),
+        },
+    ];
+    let html = render_comparison_html("Task <1>", "main", &agents, 0);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>Task &lt;1&gt;</title>"));
+    assert!(html.contains("anthropic/&lt;model&gt;"));
+    assert!(html.contains("repeat(2,minmax(0,1fr))"));
+    assert!(html.contains("<tr class=\"add\">"));
+    assert!(html.contains("Not changed"));
+    assert!(html.contains("1970-01-01 00:00 UTC"));
+    assert!(!html.contains("<script"));
+}
+
+#[test]
+fn test_export_agent_comparison() {
+    let repo = TestRepo::new();
+    let parent = TempDir::new().unwrap();
+    let mut agents = Vec::new();
+    for (i, name) in ["one", "two"].iter().enumerate() {
+        let path = parent.path().join(name).to_string_lossy().to_string();
+        run_git_command(&["worktree", "add", "--detach", &path], &repo.path_str()).unwrap();
+        fs::write(format!("{}/{}.rs", path, name), "fn main() {}\n").unwrap();
+        agents.push(create_agent(&format!("agent-{}", i + 1), path));
+    }
+    let task = create_task(&repo, agents);
+    let base = repo.current_branch();
+
+    let dest = parent.path().join("out");
+    fs::create_dir(&dest).unwrap();
+    let export = export_agent_comparison(&task, &["agent-2".to_string()], &base, &dest).unwrap();
+    assert!(export.path.ends_with("out/fix-login-comparison.html"));
+    assert_eq!(export.agent_ids, vec!["agent-2"]);
+    assert_eq!(export.file_count, 1);
+    let html = fs::read_to_string(&export.path).unwrap();
+    assert!(html.contains("two.rs") && !html.contains("one.rs"));
+
+    // All agents when none are selected
+    let file = parent.path().join("all.html");
+    let export = export_agent_comparison(&task, &[], &base, &file).unwrap();
+    assert_eq!(export.agent_ids.len(), 2);
+    assert_eq!(export.file_count, 2);
+
+    assert!(export_agent_comparison(&task, &["agent-9".to_string()], &base, &file).is_err());
+    let missing = parent.path().join("missing/out.html");
+    assert!(export_agent_comparison(&task, &[], &base, &missing).is_err());
+}
@@ -0,0 +1,68 @@
+//! Tests for task context files.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::context_files::{
+    stage_context_file, sync_context_files, WORKTREE_CONTEXT_DIR,
+};
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::{ensure_aristar_excluded, run_git_command};
+
+#[test]
+fn test_stage_context_file_copies_into_store() {
+    let source_dir = TempDir::new().unwrap();
+    let store = TempDir::new().unwrap();
+    let source = source_dir.path().join("design.md");
+    fs::write(&source, "# Design").unwrap();
+
+    let file = stage_context_file(&source, store.path(), 42).unwrap();
+
+    assert_eq!(file.name, "design.md");
+    assert_eq!(file.size, 8);
+    assert_eq!(file.added_at, 42);
+    assert_eq!(
+        fs::read_to_string(store.path().join("design.md")).unwrap(),
+        "# Design"
+    );
+}
+
+#[test]
+fn test_stage_context_file_rejects_directories_and_missing_files() {
+    let dir = TempDir::new().unwrap();
+    let store = TempDir::new().unwrap();
+
+    assert!(stage_context_file(dir.path(), store.path(), 0).is_err());
+    assert!(stage_context_file(&dir.path().join("missing.md"), store.path(), 0).is_err());
+}
+
+#[test]
+fn test_sync_context_files_is_git_excluded() {
+    let repo = TestRepo::new();
+    let store = TempDir::new().unwrap();
+    let source = store.path().join("spec.txt");
+    fs::write(&source, "spec").unwrap();
+    let file = stage_context_file(&source, store.path(), 0).unwrap();
+
+    sync_context_files(store.path(), repo.path(), &[file]).unwrap();
+
+    assert!(repo
+        .path()
+        .join(WORKTREE_CONTEXT_DIR)
+        .join("spec.txt")
+        .exists());
+    let status = run_git_command(&["status", "--porcelain"], &repo.path_str()).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
+
+#[test]
+fn test_ensure_aristar_excluded_is_idempotent() {
+    let repo = TestRepo::new();
+
+    ensure_aristar_excluded(repo.path()).unwrap();
+    ensure_aristar_excluded(repo.path()).unwrap();
+
+    let exclude = fs::read_to_string(repo.path().join(".git/info/exclude")).unwrap();
+    assert_eq!(exclude.matches("/.aristar/").count(), 1);
+}
@@ -0,0 +1,114 @@
+//! Tests for commands spanning the task store and the OpenCode manager.
+
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use crate::agent_manager::coordinator::{
+    agent_server_port, agent_server_target, stop_agent_server, stop_task_servers,
+    task_worktree_paths,
+};
+use crate::agent_manager::opencode::OpenCodeManager;
+use crate::agent_manager::store::{TaskManagerState, TaskStore};
+use crate::agent_manager::types::{AgentStatus, Task, TaskAgent, TaskStatus, TaskStoreData};
+use crate::tests::helpers::{test_agent, test_task};
+
+fn create_agent(id: &str) -> TaskAgent {
+    TaskAgent {
+        model_id: "sonnet".to_string(),
+        worktree_path: format!("/tmp/coordinator/{}", id),
+        status: AgentStatus::Running,
+        preferred_port: Some(41000),
+        ..test_agent(id)
+    }
+}
+
+fn create_state() -> TaskManagerState {
+    let task = Task {
+        id: "t1".to_string(),
+        name: "Coordinator".to_string(),
+        status: TaskStatus::Running,
+        opencode_version: Some("0.5.0".to_string()),
+        ..test_task(vec![create_agent("agent-1"), create_agent("agent-2")])
+    };
+    TaskManagerState {
+        store: TaskStore::with_data(TaskStoreData {
+            tasks: vec![task],
+            ..Default::default()
+        }),
+    }
+}
+
+#[test]
+fn test_agent_server_target_copies_task_settings() {
+    let state = create_state();
+    let target = agent_server_target(&state, "t1", "agent-2").unwrap();
+    assert_eq!(
+        target.worktree_path,
+        PathBuf::from("/tmp/coordinator/agent-2")
+    );
+    assert_eq!(target.opencode_version.as_deref(), Some("0.5.0"));
+    assert_eq!(target.preferred_port, Some(41000));
+
+    assert!(agent_server_target(&state, "missing", "agent-1")
+        .unwrap_err()
+        .contains("Task not found"));
+    assert!(agent_server_target(&state, "t1", "missing")
+        .unwrap_err()
+        .contains("Agent not found"));
+    assert_eq!(task_worktree_paths(&state, "t1").unwrap().len(), 2);
+}
+
+#[test]
+fn test_cross_state_commands_do_not_deadlock() {
+    let state = Arc::new(create_state());
+    let opencode = Arc::new(OpenCodeManager::default());
+    let (done, finished) = mpsc::channel();
+    let mut threads = 0;
+
+    // Commands reading the task store, then the OpenCode manager
+    for _ in 0..4 {
+        let (state, opencode, done) = (state.clone(), opencode.clone(), done.clone());
+        std::thread::spawn(move || {
+            for _ in 0..50 {
+                assert_eq!(
+                    agent_server_port(&state, &opencode, "t1", "agent-1").unwrap(),
+                    None
+                );
+                stop_agent_server(&state, &opencode, "t1", "agent-2").unwrap();
+                stop_task_servers(&state, &opencode, "t1").unwrap();
+            }
+            done.send(()).unwrap();
+        });
+        threads += 1;
+    }
+    // Writers nesting in hierarchy order: task store, then OpenCode manager
+    for _ in 0..2 {
+        let (state, opencode, done) = (state.clone(), opencode.clone(), done.clone());
+        std::thread::spawn(move || {
+            for i in 0..200u16 {
+                let mut store = state.store.lock().unwrap();
+                store.tasks[0].agents[0].preferred_port = Some(41000 + i);
+                assert_eq!(opencode.running_count(), 0);
+            }
+            done.send(()).unwrap();
+        });
+        threads += 1;
+    }
+    {
+        let (opencode, done) = (opencode.clone(), done.clone());
+        std::thread::spawn(move || {
+            for _ in 0..200 {
+                assert!(opencode.running_paths().is_empty());
+            }
+            done.send(()).unwrap();
+        });
+        threads += 1;
+    }
+
+    for _ in 0..threads {
+        finished
+            .recv_timeout(Duration::from_secs(30))
+            .expect("cross-state commands deadlocked or a thread panicked");
+    }
+}
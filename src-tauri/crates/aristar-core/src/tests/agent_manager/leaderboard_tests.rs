@@ -0,0 +1,148 @@
+//! Tests for the model leaderboard.
+
+use crate::agent_manager::leaderboard::{
+    build_model_leaderboard, collect_agent_outcomes, task_outcomes,
+};
+use crate::agent_manager::types::{
+    AgentDiffStats, AgentOutcome, AgentScore, PipelineRun, PipelineStatus, Task, TaskAgent,
+    TaskStatus,
+};
+use crate::tests::helpers::{test_agent, test_task, TestRepo};
+
+fn create_agent(id: &str, model_id: &str, worktree_path: &str) -> TaskAgent {
+    TaskAgent {
+        model_id: model_id.to_string(),
+        worktree_path: worktree_path.to_string(),
+        ..test_agent(id)
+    }
+}
+
+fn create_task(repo_path: &str, agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: "Leaderboard".to_string(),
+        source_branch: Some("base".to_string()),
+        source_repo_path: repo_path.to_string(),
+        status: TaskStatus::Completed,
+        ..test_task(agents)
+    }
+}
+
+fn outcome(model_id: &str, accepted: bool, score: Option<u32>, lines: Option<u32>) -> AgentOutcome {
+    AgentOutcome {
+        agent_id: format!("agent-{}", model_id),
+        provider_id: "anthropic".to_string(),
+        model_id: model_id.to_string(),
+        accepted,
+        score,
+        diff: lines.map(|l| AgentDiffStats {
+            files_changed: 1,
+            insertions: l,
+            deletions: 0,
+        }),
+        recorded_at: 0,
+    }
+}
+
+#[test]
+fn test_build_model_leaderboard() {
+    let mut first = create_task("/repos/one", Vec::new());
+    first.agent_outcomes = vec![
+        outcome("sonnet", true, Some(90), Some(10)),
+        outcome("haiku", false, Some(50), Some(30)),
+    ];
+    let mut second = create_task("/repos/one", Vec::new());
+    second.agent_outcomes = vec![
+        outcome("sonnet", false, None, Some(20)),
+        outcome("haiku", false, Some(70), None),
+    ];
+    let mut other_repo = create_task("/repos/two", Vec::new());
+    other_repo.agent_outcomes = vec![outcome("haiku", true, None, None)];
+    let mut other_repo_again = create_task("/repos/two", Vec::new());
+    other_repo_again.agent_outcomes = vec![outcome("haiku", true, None, None)];
+    // Undecided tasks don't count
+    let undecided = create_task("/repos/one", vec![create_agent("a", "sonnet", "/tmp/a")]);
+
+    let tasks = vec![first, second, other_repo, other_repo_again, undecided];
+    let board = build_model_leaderboard(&tasks, Some("/repos/one/"));
+    assert_eq!(board.len(), 2);
+    assert_eq!(board[0].model_id, "sonnet");
+    assert_eq!((board[0].runs, board[0].accepted), (2, 1));
+    assert_eq!(board[0].acceptance_rate, 0.5);
+    assert_eq!(board[0].average_score, Some(90.0));
+    assert_eq!(board[0].average_diff_lines, Some(15.0));
+    assert_eq!(board[1].model_id, "haiku");
+    assert_eq!(board[1].acceptance_rate, 0.0);
+    assert_eq!(board[1].average_score, Some(60.0));
+    assert_eq!(board[1].average_diff_lines, Some(30.0));
+
+    // Across repositories both win half their runs; haiku has more wins
+    let board = build_model_leaderboard(&tasks, None);
+    assert_eq!(board[0].model_id, "haiku");
+    assert_eq!((board[0].runs, board[0].accepted), (4, 2));
+    assert_eq!(board[1].acceptance_rate, board[0].acceptance_rate);
+}
+
+#[test]
+fn test_task_outcomes_falls_back_to_remaining_agents() {
+    let mut winner = create_agent("agent-1", "sonnet", "/tmp/agent-1");
+    winner.accepted = true;
+    let mut task = create_task(
+        "/repos/one",
+        vec![winner, create_agent("agent-2", "haiku", "/tmp/agent-2")],
+    );
+    task.updated_at = 42;
+    task.pipeline_run = Some(PipelineRun {
+        pipeline: "default".to_string(),
+        status: PipelineStatus::Passed,
+        started_at: 0,
+        finished_at: None,
+        steps: Vec::new(),
+        verifications: Vec::new(),
+        scores: vec![AgentScore {
+            agent_id: "agent-1".to_string(),
+            score: 80,
+            checks_passed: 1,
+            checks_total: 1,
+            open_comments: 0,
+        }],
+    });
+
+    let outcomes = task_outcomes(&task);
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[0].accepted && !outcomes[1].accepted);
+    assert_eq!(outcomes[0].score, Some(80));
+    assert_eq!(outcomes[1].score, None);
+    assert!(outcomes
+        .iter()
+        .all(|o| o.diff.is_none() && o.recorded_at == 42));
+
+    task.agents[0].accepted = false;
+    assert!(task_outcomes(&task).is_empty());
+}
+
+#[test]
+fn test_collect_agent_outcomes_measures_diffs() {
+    let repo = TestRepo::new();
+    repo.create_branch("base");
+    std::fs::write(repo.path().join("new.txt"), "one\ntwo\n").unwrap();
+
+    let task = create_task(
+        &repo.path_str(),
+        vec![
+            create_agent("agent-1", "sonnet", &repo.path_str()),
+            create_agent("agent-2", "haiku", "/nonexistent/agent-2"),
+        ],
+    );
+    let outcomes = collect_agent_outcomes(&task, "agent-1");
+    assert!(outcomes[0].accepted && !outcomes[1].accepted);
+    assert_eq!(
+        outcomes[0].diff,
+        Some(AgentDiffStats {
+            files_changed: 1,
+            insertions: 2,
+            deletions: 0,
+        })
+    );
+    // Worktree already removed
+    assert_eq!(outcomes[1].diff, None);
+}
@@ -0,0 +1,27 @@
+//! Agent manager tests.
+
+mod agreement_tests;
+mod artifacts_tests;
+mod auto_accept_tests;
+mod change_summary_tests;
+mod comparison_tests;
+mod context_files_tests;
+mod coordinator_tests;
+mod leaderboard_tests;
+mod opencode_install_tests;
+mod opencode_tests;
+mod pipeline_tests;
+mod power_tests;
+mod presets_tests;
+mod prompt_status_tests;
+mod quota_tests;
+mod relay_tests;
+mod review_tests;
+mod sandbox_tests;
+mod scheduler_tests;
+mod scratchpad_tests;
+mod snapshot_tests;
+mod store_tests;
+mod task_tests;
+mod transcript_tests;
+mod write_audit_tests;
@@ -0,0 +1,114 @@
+//! Tests for managed OpenCode binary helpers.
+
+use std::cmp::Ordering;
+use std::fs;
+use tempfile::TempDir;
+
+use crate::agent_manager::opencode_install::*;
+
+// ============================================================================
+// Version helpers
+// ============================================================================
+
+#[test]
+fn test_normalize_version_strips_v_prefix() {
+    assert_eq!(normalize_version(" v0.3.12 "), "0.3.12");
+    assert_eq!(normalize_version("0.3.12"), "0.3.12");
+}
+
+#[test]
+fn test_validate_version_rejects_path_traversal() {
+    assert!(validate_version("0.3.12").is_ok());
+    assert!(validate_version("1.0.0-beta.1").is_ok());
+    assert!(validate_version("../../etc").is_err());
+    assert!(validate_version("0.3/../x").is_err());
+    assert!(validate_version("").is_err());
+}
+
+#[test]
+fn test_compare_versions_numeric() {
+    assert_eq!(compare_versions("0.10.2", "0.9.7"), Ordering::Greater);
+    assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+}
+
+#[test]
+fn test_list_installed_versions_in_sorted_newest_first() {
+    let temp = TempDir::new().unwrap();
+    for v in ["0.9.7", "0.10.2", "0.3.0"] {
+        let dir = temp.path().join(format!("opencode-{}", v));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("opencode"), "bin").unwrap();
+    }
+    // Folder without a binary is ignored
+    fs::create_dir_all(temp.path().join("opencode-1.0.0")).unwrap();
+
+    let versions = list_installed_versions_in(temp.path());
+    assert_eq!(versions, vec!["0.10.2", "0.9.7", "0.3.0"]);
+}
+
+#[test]
+fn test_list_installed_versions_in_missing_dir() {
+    let versions = list_installed_versions_in(std::path::Path::new("/nonexistent/bin"));
+    assert!(versions.is_empty());
+}
+
+// ============================================================================
+// Download helpers
+// ============================================================================
+
+#[test]
+fn test_platform_asset_name() {
+    assert_eq!(
+        platform_asset_name("macos", "aarch64").unwrap(),
+        "opencode-darwin-arm64.zip"
+    );
+    assert_eq!(
+        platform_asset_name("linux", "x86_64").unwrap(),
+        "opencode-linux-x64.zip"
+    );
+    assert!(platform_asset_name("freebsd", "x86_64").is_err());
+}
+
+#[test]
+fn test_verify_checksum_matches() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("asset.zip");
+    fs::write(&file, "hello").unwrap();
+
+    // sha256("hello")
+    let sum = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    assert!(verify_checksum(&file, sum).is_ok());
+    assert!(verify_checksum(&file, &format!("{}  asset.zip\n", sum.to_uppercase())).is_ok());
+}
+
+#[test]
+fn test_verify_checksum_mismatch() {
+    let temp = TempDir::new().unwrap();
+    let file = temp.path().join("asset.zip");
+    fs::write(&file, "tampered").unwrap();
+
+    let result = verify_checksum(&file, "deadbeef");
+    assert!(result.unwrap_err().contains("Checksum mismatch"));
+}
+
+// ============================================================================
+// Version pinning
+// ============================================================================
+
+#[test]
+fn test_resolve_pinned_binary_unpinned() {
+    assert_eq!(resolve_pinned_binary(None).unwrap(), None);
+    assert_eq!(resolve_pinned_binary(Some("  ")).unwrap(), None);
+}
+
+#[test]
+fn test_resolve_pinned_binary_not_installed() {
+    let err = resolve_pinned_binary(Some("999.0.0-missing")).unwrap_err();
+    assert!(err.contains("not installed"));
+}
+
+#[test]
+fn test_resolve_pinned_binary_invalid_version() {
+    assert!(resolve_pinned_binary(Some("../evil")).is_err());
+}
@@ -0,0 +1,196 @@
+//! Tests for post-completion pipelines.
+
+use tempfile::TempDir;
+
+use crate::agent_manager::pipeline::{
+    new_pipeline_run, pipeline_ready, run_verification, score_agents, timeline_entry_for_step,
+    top_score, validate_pipeline,
+};
+use crate::agent_manager::types::{
+    AgentStatus, Pipeline, PipelineStatus, PipelineStep, PipelineStepKind, ReviewComment, Task,
+    TaskAgent, TaskStatus, TimelineEntryKind,
+};
+use crate::tests::helpers::{test_agent, test_task};
+
+fn create_agent(id: &str, status: AgentStatus) -> TaskAgent {
+    TaskAgent {
+        status,
+        ..test_agent(id)
+    }
+}
+
+fn create_task(agents: Vec<TaskAgent>, pipeline: Option<Pipeline>) -> Task {
+    Task {
+        name: "Pipeline".to_string(),
+        status: TaskStatus::Running,
+        pipeline,
+        ..test_task(agents)
+    }
+}
+
+fn step(kind: PipelineStepKind) -> PipelineStep {
+    PipelineStep {
+        kind,
+        command: None,
+        threshold: None,
+        strategy: None,
+    }
+}
+
+fn verify(command: &str) -> PipelineStep {
+    PipelineStep {
+        command: Some(command.to_string()),
+        ..step(PipelineStepKind::Verify)
+    }
+}
+
+fn pipeline(steps: Vec<PipelineStep>) -> Pipeline {
+    Pipeline {
+        name: "ci".to_string(),
+        steps,
+    }
+}
+
+#[test]
+fn test_validate_pipeline() {
+    let full = pipeline(vec![
+        verify("cargo test"),
+        step(PipelineStepKind::Score),
+        PipelineStep {
+            threshold: Some(80),
+            ..step(PipelineStepKind::AutoAccept)
+        },
+        step(PipelineStepKind::Publish),
+    ]);
+    assert!(validate_pipeline(&full).is_ok());
+
+    assert!(validate_pipeline(&pipeline(vec![])).is_err());
+    assert!(validate_pipeline(&Pipeline {
+        name: " ".to_string(),
+        ..full.clone()
+    })
+    .is_err());
+    assert!(
+        validate_pipeline(&pipeline(vec![step(PipelineStepKind::Verify)]))
+            .unwrap_err()
+            .contains("needs a command")
+    );
+    assert!(
+        validate_pipeline(&pipeline(vec![step(PipelineStepKind::AutoAccept)]))
+            .unwrap_err()
+            .contains("score step before it")
+    );
+    assert!(validate_pipeline(&pipeline(vec![
+        step(PipelineStepKind::Score),
+        PipelineStep {
+            threshold: Some(101),
+            ..step(PipelineStepKind::AutoAccept)
+        },
+    ]))
+    .is_err());
+}
+
+#[test]
+fn test_pipeline_ready_once_agents_finish() {
+    let ci = Some(pipeline(vec![verify("true")]));
+    let mut task = create_task(
+        vec![
+            create_agent("agent-1", AgentStatus::Completed),
+            create_agent("agent-2", AgentStatus::Running),
+        ],
+        ci.clone(),
+    );
+    assert!(!pipeline_ready(&task));
+
+    task.agents[1].status = AgentStatus::Failed;
+    assert!(pipeline_ready(&task));
+
+    // Only runs once on its own
+    task.pipeline_run = Some(new_pipeline_run(task.pipeline.as_ref().unwrap(), 0));
+    assert!(!pipeline_ready(&task));
+
+    // Nothing to do when every agent failed, or without a pipeline
+    let failed = create_task(vec![create_agent("agent-1", AgentStatus::Failed)], ci);
+    assert!(!pipeline_ready(&failed));
+    let none = create_task(vec![create_agent("agent-1", AgentStatus::Completed)], None);
+    assert!(!pipeline_ready(&none));
+}
+
+#[test]
+fn test_run_verification_and_scores() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().to_string_lossy().to_string();
+    std::fs::write(dir.path().join("marker"), "ok").unwrap();
+
+    let passed = run_verification("agent-1", &path, "test -f marker && echo fine");
+    assert!(passed.passed);
+    assert_eq!(passed.exit_code, Some(0));
+    assert_eq!(passed.output, "fine");
+
+    let failed = run_verification("agent-2", &path, "seq 1 30; exit 3");
+    assert!(!failed.passed);
+    assert_eq!(failed.exit_code, Some(3));
+    assert!(failed.output.starts_with("11\n"));
+    assert!(failed.output.ends_with("30"));
+
+    let mut agent_3 = create_agent("agent-3", AgentStatus::Completed);
+    agent_3.review_comments.push(ReviewComment {
+        id: "c1".to_string(),
+        file: "src/lib.rs".to_string(),
+        line: 1,
+        body: "Fix this".to_string(),
+        resolved: false,
+        created_at: 0,
+        resolved_at: None,
+    });
+    let task = create_task(
+        vec![
+            create_agent("agent-1", AgentStatus::Completed),
+            create_agent("agent-2", AgentStatus::Completed),
+            agent_3,
+            create_agent("agent-4", AgentStatus::Failed),
+        ],
+        None,
+    );
+
+    let scores = score_agents(&task, &[passed, failed]);
+    let summary: Vec<(&str, u32)> = scores
+        .iter()
+        .map(|s| (s.agent_id.as_str(), s.score))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![("agent-1", 100), ("agent-2", 0), ("agent-3", 90)]
+    );
+    assert_eq!(top_score(&scores).unwrap().agent_id, "agent-1");
+    assert!(top_score(&[]).is_none());
+}
+
+#[test]
+fn test_timeline_entry_for_step() {
+    let mut run = new_pipeline_run(
+        &pipeline(vec![verify("true"), step(PipelineStepKind::Score)]),
+        0,
+    );
+    assert_eq!(run.status, PipelineStatus::Running);
+    assert!(run
+        .steps
+        .iter()
+        .all(|s| s.status == PipelineStatus::Pending));
+
+    run.steps[0].status = PipelineStatus::Failed;
+    run.steps[0].message = Some("0 of 2 agents passed `true`".to_string());
+    let entry = timeline_entry_for_step("ci", &run.steps[0], None, vec![], 5);
+    assert_eq!(entry.kind, TimelineEntryKind::PipelineStepFailed);
+    assert_eq!(
+        entry.message,
+        "Pipeline 'ci': verify failed (0 of 2 agents passed `true`)"
+    );
+    assert_eq!(entry.timestamp, 5);
+
+    run.steps[1].status = PipelineStatus::Passed;
+    let entry =
+        timeline_entry_for_step("ci", &run.steps[1], Some("agent-1".to_string()), vec![], 6);
+    assert_eq!(entry.kind, TimelineEntryKind::PipelineStep);
+    assert_eq!(entry.message, "Pipeline 'ci': score passed");
+}
@@ -0,0 +1,96 @@
+//! Tests for battery-aware throttling.
+
+use crate::agent_manager::power::{
+    ensure_can_start_instance, parse_pmset_batt, read_power_supply, set_throttle_limit,
+    should_throttle, throttle_limit, PowerState,
+};
+
+#[test]
+fn test_parse_pmset_batt() {
+    let battery = parse_pmset_batt(
+        "Now drawing from 'Battery Power'\n \
+         -InternalBattery-0 (id=4653155)\t18%; discharging; 1:02 remaining present: true\n",
+    );
+    assert_eq!(
+        battery,
+        PowerState {
+            on_battery: true,
+            battery_percent: Some(18),
+        }
+    );
+
+    let ac = parse_pmset_batt(
+        "Now drawing from 'AC Power'\n \
+         -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n",
+    );
+    assert!(!ac.on_battery);
+    assert_eq!(ac.battery_percent, Some(100));
+
+    // Desktop Macs have no battery line
+    assert_eq!(
+        parse_pmset_batt("Now drawing from 'AC Power'\n"),
+        PowerState::AC
+    );
+}
+
+#[test]
+fn test_read_power_supply() {
+    let dir = tempfile::TempDir::new().unwrap();
+    assert_eq!(read_power_supply(dir.path()), PowerState::AC);
+
+    let ac = dir.path().join("AC");
+    std::fs::create_dir(&ac).unwrap();
+    std::fs::write(ac.join("type"), "Mains\n").unwrap();
+    let battery = dir.path().join("BAT0");
+    std::fs::create_dir(&battery).unwrap();
+    std::fs::write(battery.join("type"), "Battery\n").unwrap();
+    std::fs::write(battery.join("status"), "Discharging\n").unwrap();
+    std::fs::write(battery.join("capacity"), "42\n").unwrap();
+
+    assert_eq!(
+        read_power_supply(dir.path()),
+        PowerState {
+            on_battery: true,
+            battery_percent: Some(42),
+        }
+    );
+    assert!(read_power_supply(&dir.path().join("missing")) == PowerState::AC);
+}
+
+#[test]
+fn test_should_throttle() {
+    let low = PowerState {
+        on_battery: true,
+        battery_percent: Some(15),
+    };
+    assert!(should_throttle(low, 20));
+    assert!(!should_throttle(low, 15));
+    assert!(!should_throttle(low, 0));
+    assert!(!should_throttle(
+        PowerState {
+            on_battery: false,
+            ..low
+        },
+        20
+    ));
+    assert!(!should_throttle(PowerState::AC, 20));
+}
+
+#[test]
+fn test_throttle_limit() {
+    assert!(set_throttle_limit(Some(2)));
+    assert!(!set_throttle_limit(Some(2)));
+    assert_eq!(throttle_limit(), Some(2));
+    assert!(ensure_can_start_instance(1).is_ok());
+    assert!(ensure_can_start_instance(2)
+        .unwrap_err()
+        .contains("at most 2"));
+
+    // A limit of 0 still allows one server
+    set_throttle_limit(Some(0));
+    assert_eq!(throttle_limit(), Some(1));
+
+    assert!(set_throttle_limit(None));
+    assert_eq!(throttle_limit(), None);
+    assert!(ensure_can_start_instance(10).is_ok());
+}
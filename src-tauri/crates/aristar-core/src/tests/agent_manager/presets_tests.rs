@@ -0,0 +1,56 @@
+//! Tests for saved task presets and batch task helpers.
+
+use crate::agent_manager::presets::{batch_task_name, new_task_preset, unique_repo_paths};
+use crate::agent_manager::types::{ModelSelection, TaskPreset};
+
+fn preset() -> TaskPreset {
+    TaskPreset {
+        name: "Bump CI image".to_string(),
+        source_type: "branch".to_string(),
+        source_branch: None,
+        source_commit: None,
+        source_repo_path: "/tmp/services/billing".to_string(),
+        agent_type: "build".to_string(),
+        models: vec![ModelSelection {
+            provider_id: "anthropic".to_string(),
+            model_id: "claude-sonnet-4".to_string(),
+        }],
+        opencode_version: None,
+        prompt: Some("Bump the CI image to the latest tag".to_string()),
+        sparse_paths: Vec::new(),
+    }
+}
+
+#[test]
+fn test_new_task_preset_validates() {
+    let saved = new_task_preset(preset(), 42).unwrap();
+    assert!(!saved.id.is_empty());
+    assert_eq!(saved.created_at, 42);
+    assert_eq!(saved.preset, preset());
+
+    let no_models = TaskPreset {
+        models: vec![],
+        ..preset()
+    };
+    assert!(new_task_preset(no_models, 0).is_err());
+}
+
+#[test]
+fn test_batch_task_name_uses_repo_name() {
+    assert_eq!(
+        batch_task_name("Bump CI image", "/tmp/services/billing"),
+        "Bump CI image (billing)"
+    );
+}
+
+#[test]
+fn test_unique_repo_paths() {
+    let repos = unique_repo_paths(vec![
+        "/tmp/a".to_string(),
+        " ".to_string(),
+        "/tmp/b".to_string(),
+        "/tmp/a/".to_string(),
+        " /tmp/b".to_string(),
+    ]);
+    assert_eq!(repos, vec!["/tmp/a".to_string(), "/tmp/b".to_string()]);
+}
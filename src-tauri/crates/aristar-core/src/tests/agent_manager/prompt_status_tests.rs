@@ -0,0 +1,75 @@
+//! Tests for the agent status files read by shell prompts.
+
+use std::fs;
+
+use crate::agent_manager::prompt_status::{
+    find_status_file, format_status_file, parse_status_file, write_agent_status,
+    WORKTREE_STATUS_FILE,
+};
+use crate::agent_manager::types::{AgentStatus, Task, TaskAgent, TaskStatus};
+use crate::tests::helpers::{test_agent, test_task, TestRepo};
+use crate::worktrees::operations::run_git_command;
+
+fn create_agent(worktree_path: String) -> TaskAgent {
+    TaskAgent {
+        model_id: "gpt-4o".to_string(),
+        provider_id: "openai".to_string(),
+        worktree_path,
+        status: AgentStatus::Running,
+        ..test_agent("agent-2")
+    }
+}
+
+fn create_task(name: &str, agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: name.to_string(),
+        status: TaskStatus::Running,
+        ..test_task(agents)
+    }
+}
+
+#[test]
+fn test_format_status_file_is_one_field_per_line() {
+    let agent = create_agent("/tmp/wt".to_string());
+    let task = create_task("Fix login\nbug", vec![agent.clone()]);
+
+    let contents = format_status_file(&task, &agent);
+    assert_eq!(
+        contents,
+        "task_id=a1b2c3d4\ntask=Fix login bug\nagent=agent-2\nmodel=gpt-4o\n\
+         provider=openai\nstatus=running\naccepted=false\n"
+    );
+    let fields = parse_status_file(&contents);
+    assert_eq!(fields.len(), 7);
+    assert_eq!(fields[1], ("task".to_string(), "Fix login bug".to_string()));
+}
+
+#[test]
+fn test_write_agent_status_found_from_subfolders() {
+    let repo = TestRepo::new();
+    let mut agent = create_agent(repo.path_str());
+    let task = create_task("Fix login", vec![agent.clone()]);
+
+    write_agent_status(&task, &agent).unwrap();
+    let path = repo.path().join(WORKTREE_STATUS_FILE);
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("status=running\n"));
+
+    agent.status = AgentStatus::Completed;
+    write_agent_status(&task, &agent).unwrap();
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("status=completed\n"));
+
+    let nested = repo.path().join("src/deep");
+    fs::create_dir_all(&nested).unwrap();
+    assert_eq!(
+        find_status_file(&nested).unwrap().canonicalize().unwrap(),
+        path.canonicalize().unwrap()
+    );
+
+    // The file never shows up as a change
+    let status = run_git_command(&["status", "--porcelain"], &repo.path_str()).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
@@ -0,0 +1,72 @@
+//! Tests for agent disk quotas.
+
+use std::path::Path;
+
+use crate::agent_manager::quota::{
+    flag_quota_exceeded, quota_candidates, validate_disk_quota, MAX_DISK_QUOTA_MB,
+};
+use crate::agent_manager::types::{
+    AgentStatus, DiskQuotaExceeded, Task, TaskAgent, TaskStatus, TimelineEntryKind,
+};
+use crate::tests::helpers::{test_agent, test_task};
+
+fn create_agent(id: &str) -> TaskAgent {
+    TaskAgent {
+        worktree_path: format!("/tmp/task/{}", id),
+        status: AgentStatus::Running,
+        ..test_agent(id)
+    }
+}
+
+fn create_task(id: &str, disk_quota_mb: Option<u64>) -> Task {
+    Task {
+        id: id.to_string(),
+        name: "Quota".to_string(),
+        status: TaskStatus::Running,
+        disk_quota_mb,
+        ..test_task(vec![create_agent("agent-1"), create_agent("agent-2")])
+    }
+}
+
+#[test]
+fn test_validate_disk_quota() {
+    assert!(validate_disk_quota(1).is_ok());
+    assert!(validate_disk_quota(MAX_DISK_QUOTA_MB).is_ok());
+    assert!(validate_disk_quota(0).is_err());
+    assert!(validate_disk_quota(MAX_DISK_QUOTA_MB + 1).is_err());
+}
+
+#[test]
+fn test_quota_candidates_only_running_agents_with_quota() {
+    let tasks = vec![create_task("a1", Some(500)), create_task("b2", None)];
+    let running = |path: &Path| path.ends_with("agent-2");
+
+    let candidates = quota_candidates(&tasks, running);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].task_id, "a1");
+    assert_eq!(candidates[0].agent_id, "agent-2");
+    assert_eq!(candidates[0].limit_bytes, 500 * 1024 * 1024);
+}
+
+#[test]
+fn test_flag_quota_exceeded_pauses_and_records() {
+    let mut task = create_task("a1", Some(100));
+    let exceeded = DiskQuotaExceeded {
+        size_bytes: 150 * 1024 * 1024,
+        limit_bytes: 100 * 1024 * 1024,
+        detected_at: 42,
+    };
+
+    assert!(flag_quota_exceeded(&mut task, "agent-1", exceeded.clone()));
+    assert_eq!(task.agents[0].status, AgentStatus::Paused);
+    assert_eq!(task.agents[0].quota_exceeded, Some(exceeded.clone()));
+    assert_eq!(task.agents[1].status, AgentStatus::Running);
+
+    let entry = task.timeline.last().unwrap();
+    assert_eq!(entry.kind, TimelineEntryKind::DiskQuotaExceeded);
+    assert_eq!(entry.agent_id.as_deref(), Some("agent-1"));
+    assert!(entry.message.contains("150 MB"), "{}", entry.message);
+    assert_eq!(task.updated_at, 42);
+
+    assert!(!flag_quota_exceeded(&mut task, "missing", exceeded));
+}
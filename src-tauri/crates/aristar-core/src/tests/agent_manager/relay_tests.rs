@@ -0,0 +1,110 @@
+//! Tests for relay (sequential) task mode.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::task_operations::{
+    initial_agent_status, relay_hand_off, relay_successor,
+};
+use crate::agent_manager::types::{
+    AgentStatus, Task, TaskAgent, TaskMode, TaskStatus, TimelineEntryKind,
+};
+use crate::tests::helpers::{test_agent, test_task, TestRepo};
+use crate::worktrees::operations::run_git_command;
+
+fn create_agent(id: &str, worktree_path: &str, status: AgentStatus) -> TaskAgent {
+    TaskAgent {
+        worktree_path: worktree_path.to_string(),
+        status,
+        ..test_agent(id)
+    }
+}
+
+fn create_task(mode: TaskMode, agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: "Relay".to_string(),
+        mode,
+        status: TaskStatus::Running,
+        ..test_task(agents)
+    }
+}
+
+#[test]
+fn test_initial_agent_status() {
+    assert_eq!(
+        initial_agent_status(TaskMode::Parallel, 2),
+        AgentStatus::Idle
+    );
+    assert_eq!(initial_agent_status(TaskMode::Relay, 0), AgentStatus::Idle);
+    assert_eq!(
+        initial_agent_status(TaskMode::Relay, 1),
+        AgentStatus::Waiting
+    );
+}
+
+#[test]
+fn test_relay_successor_enforces_order() {
+    let agents = vec![
+        create_agent("agent-1", "/tmp/1", AgentStatus::Completed),
+        create_agent("agent-2", "/tmp/2", AgentStatus::Waiting),
+        create_agent("agent-3", "/tmp/3", AgentStatus::Waiting),
+    ];
+    let relay = create_task(TaskMode::Relay, agents.clone());
+
+    assert_eq!(relay_successor(&relay, "agent-1").unwrap(), Some(1));
+    assert!(relay_successor(&relay, "agent-2").is_err());
+    assert!(relay_successor(&relay, "agent-3").is_err());
+    assert!(relay_successor(&relay, "agent-9").is_err());
+
+    let parallel = create_task(TaskMode::Parallel, agents);
+    assert_eq!(relay_successor(&parallel, "agent-2").unwrap(), None);
+
+    let finished = create_task(
+        TaskMode::Relay,
+        vec![
+            create_agent("agent-1", "/tmp/1", AgentStatus::Completed),
+            create_agent("agent-2", "/tmp/2", AgentStatus::Completed),
+        ],
+    );
+    assert_eq!(relay_successor(&finished, "agent-2").unwrap(), None);
+}
+
+#[test]
+fn test_relay_hand_off_carries_commits_and_uncommitted_changes() {
+    let repo = TestRepo::new();
+    let parent = TempDir::new().unwrap();
+    let mut paths = Vec::new();
+    for name in ["first", "second"] {
+        let path = parent.path().join(name).to_string_lossy().to_string();
+        run_git_command(&["worktree", "add", "--detach", &path], &repo.path_str()).unwrap();
+        paths.push(path);
+    }
+
+    fs::write(format!("{}/done.txt", paths[0]), "leg one").unwrap();
+    run_git_command(&["add", "done.txt"], &paths[0]).unwrap();
+    run_git_command(&["commit", "-m", "Leg one"], &paths[0]).unwrap();
+    fs::write(format!("{}/test.txt", paths[0]), "work in progress").unwrap();
+
+    let mut task = create_task(
+        TaskMode::Relay,
+        vec![
+            create_agent("agent-1", &paths[0], AgentStatus::Completed),
+            create_agent("agent-2", &paths[1], AgentStatus::Waiting),
+        ],
+    );
+    relay_hand_off(&mut task, 0, 1).unwrap();
+
+    let head = |path: &str| {
+        run_git_command(&["rev-parse", "HEAD"], path)
+            .unwrap()
+            .stdout
+    };
+    assert_eq!(head(&paths[0]), head(&paths[1]));
+    assert_eq!(
+        fs::read_to_string(format!("{}/test.txt", paths[1])).unwrap(),
+        "work in progress"
+    );
+    assert_eq!(task.agents[1].status, AgentStatus::Idle);
+    assert_eq!(task.timeline[0].kind, TimelineEntryKind::RelayHandOff);
+}
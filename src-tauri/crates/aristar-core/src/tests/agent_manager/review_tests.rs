@@ -0,0 +1,109 @@
+//! Tests for review comments on agent diffs.
+
+use crate::agent_manager::review::{
+    add_comment, resolve_agent_comment, summarize_agents, validate_comment_file,
+};
+use crate::agent_manager::types::{AgentStatus, Task, TaskAgent, TaskStatus};
+use crate::core::Locale;
+use crate::tests::helpers::{test_agent, test_task};
+
+const HOUR: i64 = 60 * 60 * 1000;
+const DAY: i64 = 24 * HOUR;
+
+fn create_task(agents: Vec<TaskAgent>) -> Task {
+    Task {
+        name: "Review".to_string(),
+        status: TaskStatus::Completed,
+        ..test_task(agents)
+    }
+}
+
+// ============================================================================
+// Validation tests
+// ============================================================================
+
+#[test]
+fn test_validate_comment_file_accepts_relative_paths() {
+    assert!(validate_comment_file("src/main.rs").is_ok());
+    assert!(validate_comment_file("./README.md").is_ok());
+}
+
+#[test]
+fn test_validate_comment_file_rejects_escaping_paths() {
+    assert!(validate_comment_file("").is_err());
+    assert!(validate_comment_file("/etc/passwd").is_err());
+    assert!(validate_comment_file("../outside.rs").is_err());
+    assert!(validate_comment_file("src/../../outside.rs").is_err());
+}
+
+#[test]
+fn test_add_comment_rejects_empty_body_and_line_zero() {
+    let mut agent = test_agent("agent-1");
+    assert!(add_comment(&mut agent, "src/lib.rs", 0, "hi", 0).is_err());
+    assert!(add_comment(&mut agent, "src/lib.rs", 1, "   ", 0).is_err());
+    assert!(agent.review_comments.is_empty());
+}
+
+// ============================================================================
+// Comment lifecycle tests
+// ============================================================================
+
+#[test]
+fn test_add_and_resolve_comment() {
+    let mut agent = test_agent("agent-1");
+    let comment = add_comment(&mut agent, "src/lib.rs", 12, " Needs a test ", 100).unwrap();
+    assert_eq!(comment.body, "Needs a test");
+    assert!(!comment.resolved);
+
+    let resolved = resolve_agent_comment(&mut agent, &comment.id, 200).unwrap();
+    assert!(resolved.resolved);
+    assert_eq!(resolved.resolved_at, Some(200));
+
+    // Resolving again keeps the original timestamp
+    let again = resolve_agent_comment(&mut agent, &comment.id, 300).unwrap();
+    assert_eq!(again.resolved_at, Some(200));
+}
+
+#[test]
+fn test_resolve_unknown_comment_fails() {
+    let mut agent = test_agent("agent-1");
+    let err = resolve_agent_comment(&mut agent, "missing", 0).unwrap_err();
+    assert!(err.contains("Comment not found"));
+}
+
+#[test]
+fn test_summarize_agents_counts_open_comments() {
+    let mut first = test_agent("agent-1");
+    let c1 = add_comment(&mut first, "a.rs", 1, "one", 0).unwrap();
+    add_comment(&mut first, "b.rs", 2, "two", 0).unwrap();
+    resolve_agent_comment(&mut first, &c1.id, 1).unwrap();
+    let second = test_agent("agent-2");
+
+    let summaries = summarize_agents(&create_task(vec![first, second]), 0, Locale::En);
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(summaries[0].open_comments, 1);
+    assert_eq!(summaries[0].total_comments, 2);
+    assert_eq!(summaries[1].open_comments, 0);
+
+    let json = serde_json::to_value(&summaries[0]).unwrap();
+    assert_eq!(json["openComments"], 1);
+}
+
+#[test]
+fn test_summarize_agents_humanizes_times() {
+    let mut agent = test_agent("agent-1");
+    agent.status = AgentStatus::Running;
+    agent.last_started_at = Some(HOUR);
+    let now = 2 * DAY;
+
+    let summaries = summarize_agents(&create_task(vec![agent]), now, Locale::En);
+    assert_eq!(summaries[0].created_relative, "2 days ago");
+    assert_eq!(
+        summaries[0].last_started_relative.as_deref(),
+        Some("1 day ago")
+    );
+    assert_eq!(summaries[0].running_for.as_deref(), Some("1 d 23 h"));
+
+    let json = serde_json::to_value(&summaries[0]).unwrap();
+    assert_eq!(json["createdRelative"], "2 days ago");
+}
@@ -0,0 +1,175 @@
+//! Tests for agent server sandboxing.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+
+use crate::agent_manager::sandbox::{
+    build_sandbox_launch, build_sandbox_profile, check_sandbox_enforceable, host_allowed,
+    parse_proxy_target, sandbox_writable_paths, validate_sandbox_config, AllowlistProxy,
+    ProxyTarget, SANDBOX_EXEC,
+};
+use crate::agent_manager::types::SandboxConfig;
+
+fn hosts(list: &[&str]) -> Vec<String> {
+    list.iter().map(|h| h.to_string()).collect()
+}
+
+#[test]
+fn test_host_allowed_exact_and_wildcard() {
+    let allowed = hosts(&["api.anthropic.com", "*.github.com"]);
+
+    assert!(host_allowed("api.anthropic.com", &allowed));
+    assert!(host_allowed("API.Anthropic.com.", &allowed));
+    assert!(host_allowed("api.github.com", &allowed));
+    assert!(!host_allowed("github.com", &allowed));
+    assert!(!host_allowed("evilgithub.com", &allowed));
+    assert!(!host_allowed("example.com", &allowed));
+    assert!(!host_allowed("example.com", &[]));
+}
+
+#[test]
+fn test_validate_sandbox_config() {
+    let valid = SandboxConfig {
+        enabled: true,
+        allowed_hosts: hosts(&["api.openai.com", "*.example-cdn.net"]),
+        writable_paths: vec!["/tmp/shared".to_string()],
+        allow_unenforced: false,
+    };
+    assert!(validate_sandbox_config(&valid).is_ok());
+
+    let bad_host = SandboxConfig {
+        allowed_hosts: hosts(&["https://api.openai.com"]),
+        ..valid.clone()
+    };
+    assert!(validate_sandbox_config(&bad_host).is_err());
+
+    let relative_path = SandboxConfig {
+        writable_paths: vec!["shared".to_string()],
+        ..valid
+    };
+    assert!(validate_sandbox_config(&relative_path).is_err());
+}
+
+#[test]
+fn test_unenforced_sandbox_needs_opt_in() {
+    let config = SandboxConfig {
+        enabled: true,
+        ..Default::default()
+    };
+    assert!(check_sandbox_enforceable(&config, true).is_ok());
+    let err = check_sandbox_enforceable(&config, false).unwrap_err();
+    assert!(err.contains("can't be enforced"), "{}", err);
+
+    let opted_in = SandboxConfig {
+        allow_unenforced: true,
+        ..config
+    };
+    assert!(check_sandbox_enforceable(&opted_in, false).is_ok());
+}
+
+#[test]
+fn test_parse_proxy_target() {
+    assert_eq!(
+        parse_proxy_target("CONNECT api.anthropic.com:443 HTTP/1.1\r\nHost: x\r\n\r\n"),
+        Some(ProxyTarget {
+            host: "api.anthropic.com".to_string(),
+            port: 443,
+            tunnel: true,
+        })
+    );
+    assert_eq!(
+        parse_proxy_target("GET http://example.com/path HTTP/1.1\r\n\r\n"),
+        Some(ProxyTarget {
+            host: "example.com".to_string(),
+            port: 80,
+            tunnel: false,
+        })
+    );
+    assert_eq!(
+        parse_proxy_target("GET http://[::1]:8080/ HTTP/1.1\r\n\r\n").map(|t| (t.host, t.port)),
+        Some(("::1".to_string(), 8080))
+    );
+    assert_eq!(parse_proxy_target("GET /relative HTTP/1.1\r\n\r\n"), None);
+    assert_eq!(parse_proxy_target(""), None);
+}
+
+#[test]
+fn test_sandbox_profile_limits_writes_and_network() {
+    let profile = build_sandbox_profile(&[
+        PathBuf::from("/work/tree"),
+        PathBuf::from("/tmp/with \"quote\""),
+    ]);
+
+    assert!(profile.contains("(deny file-write*)"));
+    assert!(profile.contains("(subpath \"/work/tree\")"));
+    assert!(profile.contains("(subpath \"/tmp/with \\\"quote\\\"\")"));
+    assert!(profile.contains("(deny network*)"));
+    assert!(profile.contains("(allow network-outbound (remote ip \"localhost:*\"))"));
+}
+
+#[test]
+fn test_sandbox_launch_with_and_without_sandbox_exec() {
+    let binary = Path::new("/opt/opencode");
+    let args = hosts(&["serve", "--port", "4096"]);
+    let writable = vec![PathBuf::from("/work/tree")];
+    let temp = Path::new("/tmp/sandbox");
+
+    let launch = build_sandbox_launch(binary, &args, &writable, temp, 9000, true);
+    assert!(launch.enforced);
+    assert_eq!(launch.program, PathBuf::from(SANDBOX_EXEC));
+    assert_eq!(launch.args[0], "-p");
+    assert_eq!(launch.args[2], "/opt/opencode");
+    assert_eq!(&launch.args[3..], args.as_slice());
+    assert!(launch.env.contains(&(
+        "HTTPS_PROXY".to_string(),
+        "http://127.0.0.1:9000".to_string()
+    )));
+    assert!(launch
+        .env
+        .contains(&("TMPDIR".to_string(), "/tmp/sandbox".to_string())));
+    assert!(launch.env_remove.contains(&"GITHUB_TOKEN".to_string()));
+
+    let launch = build_sandbox_launch(binary, &args, &writable, temp, 9000, false);
+    assert!(!launch.enforced);
+    assert_eq!(launch.program, binary);
+    assert_eq!(launch.args, args);
+}
+
+#[test]
+fn test_sandbox_writable_paths_include_worktree_and_extras() {
+    let worktree = tempfile::TempDir::new().unwrap();
+    let temp = tempfile::TempDir::new().unwrap();
+    let config = SandboxConfig {
+        enabled: true,
+        allowed_hosts: Vec::new(),
+        writable_paths: vec!["/nonexistent/extra".to_string()],
+        allow_unenforced: false,
+    };
+
+    let paths = sandbox_writable_paths(
+        worktree.path(),
+        &config,
+        Some(Path::new("/home/user")),
+        temp.path(),
+    );
+
+    assert!(paths.contains(&worktree.path().canonicalize().unwrap()));
+    assert!(paths.contains(&temp.path().canonicalize().unwrap()));
+    assert!(paths.contains(&PathBuf::from("/home/user/.local/share/opencode")));
+    assert!(paths.contains(&PathBuf::from("/nonexistent/extra")));
+}
+
+#[test]
+fn test_allowlist_proxy_blocks_unapproved_hosts() {
+    let proxy = AllowlistProxy::start(hosts(&["api.anthropic.com"])).unwrap();
+
+    let mut stream = TcpStream::connect(("127.0.0.1", proxy.port)).unwrap();
+    stream
+        .write_all(b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n")
+        .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 403"));
+}
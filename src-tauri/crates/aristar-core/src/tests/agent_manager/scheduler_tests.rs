@@ -0,0 +1,163 @@
+//! Tests for cron expressions and scheduled tasks.
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::agent_manager::cron::{next_run_after, CronExpr};
+use crate::agent_manager::scheduler::{due_schedule_ids, new_schedule, run_task_name};
+use crate::agent_manager::types::{ModelSelection, Schedule, TaskPreset};
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d)
+        .unwrap()
+        .and_hms_opt(h, min, 0)
+        .unwrap()
+}
+
+fn next(expr: &str, after: NaiveDateTime) -> Option<NaiveDateTime> {
+    CronExpr::parse(expr).unwrap().next_after(after)
+}
+
+fn preset() -> TaskPreset {
+    TaskPreset {
+        name: "Update deps".to_string(),
+        source_type: "branch".to_string(),
+        source_branch: Some("main".to_string()),
+        source_commit: None,
+        source_repo_path: "/tmp/repo".to_string(),
+        agent_type: "build".to_string(),
+        models: vec![ModelSelection {
+            provider_id: "anthropic".to_string(),
+            model_id: "claude-sonnet-4".to_string(),
+        }],
+        opencode_version: None,
+        prompt: Some("Update dependencies and fix the build".to_string()),
+        sparse_paths: Vec::new(),
+    }
+}
+
+#[test]
+fn test_cron_parse_rejects_invalid_expressions() {
+    for expr in [
+        "* * * *",
+        "60 * * * *",
+        "* 24 * * *",
+        "* * 0 * *",
+        "*/0 * * * *",
+        "5-1 * * * *",
+        "* * * foo *",
+        "@often",
+    ] {
+        assert!(CronExpr::parse(expr).is_err(), "{} should be invalid", expr);
+    }
+}
+
+#[test]
+fn test_cron_next_after_steps_and_rollover() {
+    // 2025-01-31 is a Friday
+    assert_eq!(
+        next("*/15 * * * *", at(2025, 1, 31, 10, 7)),
+        Some(at(2025, 1, 31, 10, 15))
+    );
+    assert_eq!(
+        next("0 2 * * *", at(2025, 1, 31, 3, 0)),
+        Some(at(2025, 2, 1, 2, 0))
+    );
+    assert_eq!(
+        next("@yearly", at(2025, 1, 31, 3, 0)),
+        Some(at(2026, 1, 1, 0, 0))
+    );
+    assert_eq!(
+        next("30 8-18/5 * * *", at(2025, 1, 31, 9, 0)),
+        Some(at(2025, 1, 31, 13, 30))
+    );
+}
+
+#[test]
+fn test_cron_next_after_weekdays() {
+    // Friday 10:00 -> Monday 09:00
+    assert_eq!(
+        next("0 9 * * mon-fri", at(2025, 1, 31, 10, 0)),
+        Some(at(2025, 2, 3, 9, 0))
+    );
+    // 7 means Sunday
+    assert_eq!(
+        next("0 0 * * 7", at(2025, 1, 31, 10, 0)),
+        Some(at(2025, 2, 2, 0, 0))
+    );
+    // Both day fields restricted: either matches (Friday 7th before the 13th)
+    assert_eq!(
+        next("0 0 13 * fri", at(2025, 2, 1, 0, 0)),
+        Some(at(2025, 2, 7, 0, 0))
+    );
+}
+
+#[test]
+fn test_cron_impossible_date_never_runs() {
+    assert_eq!(next("0 0 30 feb *", at(2025, 1, 1, 0, 0)), None);
+}
+
+#[test]
+fn test_next_run_after_is_in_the_future() {
+    let expr = CronExpr::parse("* * * * *").unwrap();
+    let now = chrono::Utc::now().timestamp_millis();
+    let next = next_run_after(&expr, now).unwrap();
+
+    assert!(next > now);
+    assert!(next <= now + 60_000);
+}
+
+#[test]
+fn test_new_schedule_validates_and_sets_next_run() {
+    let now = chrono::Utc::now().timestamp_millis();
+    let schedule = new_schedule(
+        "Nightly".to_string(),
+        "0 2 * * *".to_string(),
+        preset(),
+        now,
+    )
+    .unwrap();
+    assert!(!schedule.paused);
+    assert!(schedule.next_run_at.unwrap() > now);
+
+    assert!(new_schedule("Nightly".to_string(), "bad".to_string(), preset(), now).is_err());
+    assert!(new_schedule(
+        "Nightly".to_string(),
+        "0 2 * * *".to_string(),
+        TaskPreset {
+            models: Vec::new(),
+            ..preset()
+        },
+        now
+    )
+    .is_err());
+}
+
+#[test]
+fn test_due_schedule_ids() {
+    let base = new_schedule("A".to_string(), "@daily".to_string(), preset(), 0).unwrap();
+    let due = Schedule {
+        id: "due".to_string(),
+        next_run_at: Some(100),
+        ..base.clone()
+    };
+    let paused = Schedule {
+        id: "paused".to_string(),
+        next_run_at: Some(100),
+        paused: true,
+        ..base.clone()
+    };
+    let later = Schedule {
+        id: "later".to_string(),
+        next_run_at: Some(300),
+        ..base
+    };
+
+    assert_eq!(due_schedule_ids(&[due, paused, later], 200), vec!["due"]);
+}
+
+#[test]
+fn test_run_task_name_includes_date() {
+    let name = run_task_name("Update deps", chrono::Utc::now().timestamp_millis());
+    assert!(name.starts_with("Update deps ("));
+    assert!(name.ends_with(')'));
+}
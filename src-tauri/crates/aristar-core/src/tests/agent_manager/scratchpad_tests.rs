@@ -0,0 +1,44 @@
+//! Tests for the shared task scratchpad.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::scratchpad::{
+    append_scratchpad, link_scratchpad, read_scratchpad, WORKTREE_SCRATCHPAD,
+};
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+
+#[test]
+fn test_append_scratchpad_adds_headed_notes() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("scratchpad.md");
+    assert_eq!(read_scratchpad(&path).unwrap(), "");
+
+    append_scratchpad(&path, Some("agent-1"), "Taking the parser", 0).unwrap();
+    let contents = append_scratchpad(&path, None, "Tests are flaky\n", 60_000).unwrap();
+
+    assert_eq!(
+        contents,
+        "### agent-1 (1970-01-01 00:00:00 UTC)\n\nTaking the parser\n\n\
+         ### 1970-01-01 00:01:00 UTC\n\nTests are flaky\n"
+    );
+    assert!(append_scratchpad(&path, None, "  \n", 0).is_err());
+}
+
+#[test]
+fn test_link_scratchpad_shares_the_file_and_is_git_excluded() {
+    let repo = TestRepo::new();
+    let task_dir = TempDir::new().unwrap();
+    let path = task_dir.path().join("scratchpad.md");
+
+    link_scratchpad(&path, repo.path()).unwrap();
+    link_scratchpad(&path, repo.path()).unwrap();
+    append_scratchpad(&path, None, "hello", 0).unwrap();
+
+    let linked = fs::read_to_string(repo.path().join(WORKTREE_SCRATCHPAD)).unwrap();
+    assert!(linked.contains("hello"));
+    let status = run_git_command(&["status", "--porcelain"], &repo.path_str()).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
@@ -0,0 +1,182 @@
+//! Tests for snapshots of uncommitted changes.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::snapshot::{
+    apply_snapshot, checkpoint_ref_name, create_agent_checkpoint, create_uncommitted_snapshot,
+    delete_checkpoint_refs, delete_snapshot_ref, snapshot_ref_name,
+};
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+
+fn git_stdout(args: &[&str], cwd: &str) -> String {
+    let output = run_git_command(args, cwd).unwrap();
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// Add a detached worktree of `rev` to a new temp dir.
+fn add_worktree(repo: &TestRepo, rev: &str) -> (TempDir, String) {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("agent").to_string_lossy().to_string();
+    git_stdout(
+        &["worktree", "add", "--detach", &path, rev],
+        &repo.path_str(),
+    );
+    (dir, path)
+}
+
+/// Repo with a modified tracked file, a staged new file and an untracked file.
+fn dirty_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join("test.txt"), "changed content").unwrap();
+    fs::write(repo.path().join("staged.txt"), "staged").unwrap();
+    git_stdout(&["add", "staged.txt"], &repo.path_str());
+    fs::write(repo.path().join("untracked.txt"), "untracked").unwrap();
+    repo
+}
+
+#[test]
+fn test_snapshot_of_clean_repo_is_none() {
+    let repo = TestRepo::new();
+    assert_eq!(
+        create_uncommitted_snapshot(&repo.path_str(), "clean").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_snapshot_leaves_source_untouched() {
+    let repo = dirty_repo();
+    let status_before = git_stdout(&["status", "--porcelain"], &repo.path_str());
+
+    let commit = create_uncommitted_snapshot(&repo.path_str(), "a1b2c3d4")
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        git_stdout(&["status", "--porcelain"], &repo.path_str()),
+        status_before
+    );
+    assert_eq!(
+        git_stdout(
+            &["rev-parse", &snapshot_ref_name("a1b2c3d4")],
+            &repo.path_str()
+        ),
+        commit
+    );
+    let files = git_stdout(
+        &["diff", "--name-only", &format!("{}^", commit), &commit],
+        &repo.path_str(),
+    );
+    assert_eq!(files, "staged.txt\ntest.txt\nuntracked.txt");
+}
+
+#[test]
+fn test_apply_snapshot_to_worktree() {
+    let repo = dirty_repo();
+    let commit = create_uncommitted_snapshot(&repo.path_str(), "apply")
+        .unwrap()
+        .unwrap();
+    let (_dir, worktree) = add_worktree(&repo, "HEAD");
+
+    apply_snapshot(&worktree, &commit).unwrap();
+
+    let worktree_path = std::path::Path::new(&worktree);
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("test.txt")).unwrap(),
+        "changed content"
+    );
+    assert!(worktree_path.join("untracked.txt").exists());
+    // Changes are left unstaged
+    assert_eq!(
+        git_stdout(&["diff", "--cached", "--name-only"], &worktree),
+        ""
+    );
+    assert_eq!(
+        git_stdout(&["rev-parse", "HEAD"], &worktree),
+        git_stdout(&["rev-parse", "HEAD"], &repo.path_str())
+    );
+}
+
+#[test]
+fn test_apply_snapshot_conflict_resets_worktree() {
+    let repo = TestRepo::new();
+    repo.create_branch("other");
+    fs::write(repo.path().join("test.txt"), "local edit").unwrap();
+    let commit = create_uncommitted_snapshot(&repo.path_str(), "conflict")
+        .unwrap()
+        .unwrap();
+
+    // "other" changes the same line
+    let (_dir, worktree) = add_worktree(&repo, "other");
+    fs::write(
+        std::path::Path::new(&worktree).join("test.txt"),
+        "other edit",
+    )
+    .unwrap();
+    git_stdout(&["commit", "-am", "Other edit"], &worktree);
+
+    assert!(apply_snapshot(&worktree, &commit).is_err());
+    assert_eq!(git_stdout(&["status", "--porcelain"], &worktree), "");
+}
+
+#[test]
+fn test_delete_snapshot_ref() {
+    let repo = dirty_repo();
+    create_uncommitted_snapshot(&repo.path_str(), "gone").unwrap();
+
+    delete_snapshot_ref(&repo.path_str(), "gone");
+    delete_snapshot_ref(&repo.path_str(), "gone");
+
+    assert!(run_git_command(
+        &["rev-parse", "--verify", &snapshot_ref_name("gone")],
+        &repo.path_str()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_fork_checkpoint_carries_commits_and_changes() {
+    let repo = TestRepo::new();
+    let (_source_dir, source) = add_worktree(&repo, "HEAD");
+
+    // Half-finished attempt: one commit plus uncommitted work
+    fs::write(format!("{}/done.txt", source), "done").unwrap();
+    git_stdout(&["add", "done.txt"], &source);
+    git_stdout(&["commit", "-q", "-m", "Step one"], &source);
+    fs::write(format!("{}/test.txt", source), "in progress").unwrap();
+    fs::write(format!("{}/new.txt", source), "new").unwrap();
+    let source_status = git_stdout(&["status", "--porcelain"], &source);
+
+    let head = git_stdout(&["rev-parse", "HEAD"], &source);
+    let checkpoint = create_agent_checkpoint(&source, "fork-task", "agent-2")
+        .unwrap()
+        .unwrap();
+    let (_fork_dir, fork) = add_worktree(&repo, &head);
+    apply_snapshot(&fork, &checkpoint).unwrap();
+
+    assert_eq!(git_stdout(&["rev-parse", "HEAD"], &fork), head);
+    assert_eq!(
+        fs::read_to_string(format!("{}/test.txt", fork)).unwrap(),
+        "in progress"
+    );
+    assert!(std::path::Path::new(&format!("{}/new.txt", fork)).exists());
+    // The source agent is untouched
+    assert_eq!(
+        git_stdout(&["status", "--porcelain"], &source),
+        source_status
+    );
+
+    delete_checkpoint_refs(&repo.path_str(), "fork-task");
+    assert!(run_git_command(
+        &[
+            "rev-parse",
+            "--verify",
+            &checkpoint_ref_name("fork-task", "agent-2")
+        ],
+        &repo.path_str()
+    )
+    .is_err());
+}
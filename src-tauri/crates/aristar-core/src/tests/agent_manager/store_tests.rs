@@ -0,0 +1,61 @@
+//! Tests for the task store and on-demand timelines.
+
+use crate::agent_manager::store::{TaskManagerState, TaskStore};
+use crate::agent_manager::task_operations::{get_task_list_impl, get_task_timeline_impl};
+use crate::agent_manager::types::{Task, TaskStoreData, TimelineEntry, TimelineEntryKind};
+use crate::tests::helpers::test_task;
+
+fn create_task(id: &str, timeline: Vec<TimelineEntry>) -> Task {
+    Task {
+        id: id.to_string(),
+        name: "Store".to_string(),
+        timeline,
+        ..test_task(Vec::new())
+    }
+}
+
+fn entry(message: &str) -> TimelineEntry {
+    TimelineEntry {
+        id: message.to_string(),
+        timestamp: 0,
+        agent_id: None,
+        kind: TimelineEntryKind::WriteAudit,
+        message: message.to_string(),
+        details: Vec::new(),
+    }
+}
+
+#[test]
+fn test_task_store_with_data_is_loaded() {
+    let store = TaskStore::with_data(TaskStoreData {
+        tasks: vec![create_task("t1", Vec::new())],
+        ..Default::default()
+    });
+    let loaded = store.loaded().expect("loaded");
+    assert_eq!(loaded.task_count, 1);
+    assert_eq!(store.ensure_loaded().task_count, 1);
+    assert_eq!(store.lock().unwrap().tasks[0].id, "t1");
+
+    // Lazy stores report nothing until first used
+    assert!(TaskStore::lazy().loaded().is_none());
+}
+
+#[test]
+fn test_task_list_leaves_out_timelines() {
+    let state = TaskManagerState::default();
+    state
+        .store
+        .lock()
+        .unwrap()
+        .tasks
+        .push(create_task("t1", vec![entry("first"), entry("second")]));
+
+    let tasks = get_task_list_impl(&state).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert!(tasks[0].timeline.is_empty());
+
+    let timeline = get_task_timeline_impl(&state, "t1").unwrap();
+    let messages: Vec<&str> = timeline.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(messages, ["first", "second"]);
+    assert!(get_task_timeline_impl(&state, "missing").is_err());
+}
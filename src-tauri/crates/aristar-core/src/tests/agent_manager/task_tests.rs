@@ -0,0 +1,245 @@
+//! Task operation tests.
+
+use std::path::{Path, PathBuf};
+
+use crate::agent_manager::task_operations::{
+    agent_folder_name, generate_task_id, repair_agent_worktrees, resolve_agent_worktree_path,
+    same_repo_path, slugify, slugify_model_id, truncate_slug, validate_worktree_path_len,
+};
+use crate::agent_manager::types::{AgentPortChangedEvent, TaskAgent};
+use crate::core::TaskFolderCollision;
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+
+// ============================================================================
+// ID generation tests
+// ============================================================================
+
+#[test]
+fn test_generate_task_id_is_8_chars() {
+    let id = generate_task_id("Test Task");
+    assert_eq!(id.len(), 8);
+}
+
+#[test]
+fn test_generate_task_id_is_hex() {
+    let id = generate_task_id("Test Task");
+    assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+}
+
+#[test]
+fn test_generate_task_id_different_for_same_name() {
+    // IDs should be different even for same name (because of timestamp)
+    let id1 = generate_task_id("Same Name");
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    let id2 = generate_task_id("Same Name");
+    assert_ne!(id1, id2);
+}
+
+// ============================================================================
+// Slugify tests
+// ============================================================================
+
+#[test]
+fn test_slugify_basic() {
+    assert_eq!(slugify("Hello World"), "hello-world");
+}
+
+#[test]
+fn test_slugify_with_special_chars() {
+    assert_eq!(
+        slugify("Refactor Authentication!"),
+        "refactor-authentication"
+    );
+}
+
+#[test]
+fn test_slugify_multiple_spaces() {
+    assert_eq!(slugify("Hello   World"), "hello-world");
+}
+
+#[test]
+fn test_slugify_numbers() {
+    assert_eq!(slugify("Version 2.0 Release"), "version-2-0-release");
+}
+
+#[test]
+fn test_slugify_model_id_basic() {
+    assert_eq!(slugify_model_id("claude-sonnet-4"), "claude-sonnet-4");
+}
+
+#[test]
+fn test_slugify_model_id_with_dots() {
+    assert_eq!(slugify_model_id("gpt-4.0-turbo"), "gpt-4-0-turbo");
+}
+
+#[test]
+fn test_slugify_model_id_preserves_hyphens() {
+    assert_eq!(
+        slugify_model_id("claude-3-5-sonnet-20241022"),
+        "claude-3-5-sonnet-20241022"
+    );
+}
+
+// ============================================================================
+// Agent folder name tests
+// ============================================================================
+
+#[test]
+fn test_truncate_slug_at_boundary() {
+    let exact = "a".repeat(20);
+    assert_eq!(truncate_slug(&exact, 20), exact);
+
+    let over = format!("{}b", exact);
+    let cut = truncate_slug(&over, 20);
+    assert_eq!(cut.chars().count(), 20);
+    assert!(cut.starts_with("aaaaaaaaaaa-"));
+    assert!(cut[12..].chars().all(|c| c.is_ascii_hexdigit()));
+
+    // Names sharing the kept prefix still get distinct folders
+    assert_ne!(truncate_slug(&format!("{}c", exact), 20), cut);
+    // A cut ending in a hyphen doesn't leave two in a row
+    let hyphenated = truncate_slug("refactor-the-authentication-layer", 18);
+    assert!(hyphenated.starts_with("refactor-"));
+    assert!(!hyphenated.contains("--"));
+    assert_eq!(
+        agent_folder_name("Refactor the authentication layer", "gpt-4", 18),
+        format!("{}-gpt-4", hyphenated)
+    );
+}
+
+#[test]
+fn test_validate_worktree_path_len_at_boundary() {
+    let path = PathBuf::from(format!("/{}", "x".repeat(99)));
+    assert!(validate_worktree_path_len(&path, 100).is_ok());
+    let err = validate_worktree_path_len(&path, 99).unwrap_err();
+    assert!(err.contains("100 characters"));
+}
+
+#[test]
+fn test_resolve_agent_worktree_path_collisions() {
+    let folder = Path::new("/tasks/a1b2c3d4");
+    let first = folder.join("fix-login-gpt-4");
+    let path = resolve_agent_worktree_path(
+        folder,
+        "fix-login-gpt-4",
+        1,
+        &[],
+        TaskFolderCollision::Number,
+        200,
+    )
+    .unwrap();
+    assert_eq!(path, first);
+
+    let taken = vec![first.clone()];
+    let path = resolve_agent_worktree_path(
+        folder,
+        "fix-login-gpt-4",
+        2,
+        &taken,
+        TaskFolderCollision::Number,
+        200,
+    )
+    .unwrap();
+    assert_eq!(path, folder.join("fix-login-gpt-4-2"));
+
+    assert!(resolve_agent_worktree_path(
+        folder,
+        "fix-login-gpt-4",
+        2,
+        &taken,
+        TaskFolderCollision::Error,
+        200,
+    )
+    .is_err());
+
+    // The length is checked on the final, suffixed path
+    let limit = first.to_string_lossy().len() + 1;
+    assert!(resolve_agent_worktree_path(
+        folder,
+        "fix-login-gpt-4",
+        2,
+        &taken,
+        TaskFolderCollision::Number,
+        limit,
+    )
+    .is_err());
+}
+
+// ============================================================================
+// Serialization tests
+// ============================================================================
+
+#[test]
+fn test_task_agent_without_preferred_port_deserializes() {
+    // tasks.json written before preferred ports existed must still load
+    let json = r#"{
+        "id": "agent-1",
+        "modelId": "claude-sonnet-4",
+        "providerId": "anthropic",
+        "agentType": null,
+        "worktreePath": "/tmp/wt",
+        "sessionId": null,
+        "status": "idle",
+        "accepted": false,
+        "createdAt": 0
+    }"#;
+    let agent: TaskAgent = serde_json::from_str(json).unwrap();
+    assert_eq!(agent.preferred_port, None);
+    assert!(agent.review_comments.is_empty());
+}
+
+#[test]
+fn test_agent_port_changed_event_is_camel_case() {
+    let event = AgentPortChangedEvent {
+        task_id: "a1b2c3d4".to_string(),
+        agent_id: "agent-1".to_string(),
+        previous_port: 4096,
+        port: 4097,
+    };
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["previousPort"], 4096);
+    assert_eq!(json["taskId"], "a1b2c3d4");
+}
+
+// ============================================================================
+// Repository move tests
+// ============================================================================
+
+#[test]
+fn test_same_repo_path() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    assert!(same_repo_path(&path, &format!("{}/", path)));
+    assert!(same_repo_path("/gone/repo/", "/gone/repo"));
+    assert!(!same_repo_path("/gone/repo", "/gone/other"));
+}
+
+#[test]
+fn test_repair_agent_worktrees_after_repository_move() {
+    let repo = TestRepo::new();
+    let worktrees = tempfile::TempDir::new().unwrap();
+    let agent = worktrees.path().join("agent-1");
+    let agent_str = agent.to_string_lossy().to_string();
+    run_git_command(
+        &["worktree", "add", "--detach", &agent_str],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    let moved_parent = tempfile::TempDir::new().unwrap();
+    let moved = moved_parent.path().join("moved-repo");
+    std::fs::rename(repo.path(), &moved).unwrap();
+    // The agent worktree still points into the old location
+    assert!(run_git_command(&["status"], &agent_str).is_err());
+
+    let moved_str = moved.canonicalize().unwrap().to_string_lossy().to_string();
+    let (repaired, failed) = repair_agent_worktrees(
+        &moved_str,
+        vec![agent_str.clone(), "/nonexistent/agent-2".to_string()],
+    );
+    assert_eq!(repaired, vec![agent_str.clone()]);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].path, "/nonexistent/agent-2");
+    assert!(run_git_command(&["status"], &agent_str).is_ok());
+}
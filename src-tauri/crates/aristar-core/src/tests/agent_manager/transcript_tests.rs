@@ -0,0 +1,108 @@
+//! Tests for recorded agent transcripts.
+
+use serde_json::json;
+use tempfile::TempDir;
+
+use crate::agent_manager::transcript::{
+    append_transcript, read_transcript, slice_transcript, transcript_entry,
+};
+use crate::agent_manager::types::TranscriptRange;
+
+#[test]
+fn test_transcript_entry_keeps_finished_pieces() {
+    let streaming = json!({
+        "type": "message.part.updated",
+        "properties": {
+            "part": { "id": "prt_1", "type": "text", "text": "Hel", "time": { "start": 1 } },
+            "delta": "Hel"
+        }
+    });
+    assert!(transcript_entry(&streaming, 0).is_none());
+
+    let text = json!({
+        "type": "message.part.updated",
+        "properties": {
+            "part": {
+                "id": "prt_1", "messageID": "msg_1", "sessionID": "ses_1",
+                "type": "text", "text": "Hello", "time": { "start": 1, "end": 2 }
+            }
+        }
+    });
+    let entry = transcript_entry(&text, 5).unwrap();
+    assert_eq!(entry.id, "prt_1");
+    assert_eq!(entry.kind, "text");
+    assert_eq!(entry.message_id.as_deref(), Some("msg_1"));
+    assert_eq!(entry.session_id.as_deref(), Some("ses_1"));
+    assert_eq!(entry.timestamp, 5);
+
+    let running_tool = json!({
+        "type": "message.part.updated",
+        "properties": { "part": { "id": "prt_2", "type": "tool", "state": { "status": "running" } } }
+    });
+    assert!(transcript_entry(&running_tool, 0).is_none());
+    let failed_tool = json!({
+        "type": "message.part.updated",
+        "properties": { "part": { "id": "prt_2", "type": "tool", "state": { "status": "error" } } }
+    });
+    assert_eq!(transcript_entry(&failed_tool, 0).unwrap().kind, "tool");
+
+    let assistant = |completed: serde_json::Value| {
+        json!({
+            "type": "message.updated",
+            "properties": { "info": { "id": "msg_1", "role": "assistant", "time": { "created": 1, "completed": completed } } }
+        })
+    };
+    assert!(transcript_entry(&assistant(json!(null)), 0).is_none());
+    assert_eq!(
+        transcript_entry(&assistant(json!(3)), 0).unwrap().kind,
+        "message"
+    );
+
+    let user = json!({
+        "type": "message.updated",
+        "properties": { "info": { "id": "msg_0", "role": "user", "time": { "created": 1 } } }
+    });
+    assert!(transcript_entry(&user, 0).is_some());
+    assert!(
+        transcript_entry(&json!({ "type": "server.connected", "properties": {} }), 0).is_none()
+    );
+}
+
+#[test]
+fn test_append_transcript_records_once_and_slices() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("transcripts").join("agent-1.jsonl");
+    let part = |id: &str| {
+        json!({
+            "type": "message.part.updated",
+            "properties": { "part": { "id": id, "type": "text", "text": id } }
+        })
+    };
+
+    assert_eq!(
+        append_transcript(&path, &[part("a"), part("b"), part("a")], 1).unwrap(),
+        2
+    );
+    assert_eq!(
+        append_transcript(&path, &[part("b"), part("c")], 2).unwrap(),
+        1
+    );
+    std::fs::write(
+        &path,
+        std::fs::read_to_string(&path).unwrap() + "{\"cut off",
+    )
+    .unwrap();
+
+    let entries = read_transcript(&path).unwrap();
+    let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+
+    let range = |start, end| Some(TranscriptRange { start, end });
+    assert_eq!(slice_transcript(entries.clone(), range(1, None)).len(), 2);
+    assert_eq!(
+        slice_transcript(entries.clone(), range(0, Some(1)))[0].id,
+        "a"
+    );
+    assert!(slice_transcript(entries.clone(), range(5, Some(9))).is_empty());
+    assert_eq!(slice_transcript(entries, None).len(), 3);
+}
@@ -0,0 +1,167 @@
+//! Tests for post-run write audits.
+
+use std::fs;
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::task_operations::{push_timeline_entry, MAX_TIMELINE_ENTRIES};
+use crate::agent_manager::types::{
+    Task, TimelineEntry, TimelineEntryKind, WriteAuditReport, WriteViolation,
+};
+use crate::agent_manager::write_audit::{
+    audit_writes, default_audit_scope, scan_writes_since, timeline_entry_for_audit, AuditRoot,
+};
+use crate::tests::helpers::test_task;
+
+fn root(path: PathBuf, max_depth: usize) -> AuditRoot {
+    AuditRoot { path, max_depth }
+}
+
+fn report(paths: &[&str], truncated: bool) -> WriteAuditReport {
+    WriteAuditReport {
+        agent_id: "agent-1".to_string(),
+        since: 0,
+        scanned_roots: Vec::new(),
+        violations: paths
+            .iter()
+            .map(|p| WriteViolation {
+                path: p.to_string(),
+                modified_at: 1,
+            })
+            .collect(),
+        truncated,
+    }
+}
+
+#[test]
+fn test_scan_writes_since_skips_excluded_and_ignored() {
+    let dir = TempDir::new().unwrap();
+    let base = dir.path().canonicalize().unwrap();
+    fs::create_dir_all(base.join("worktree")).unwrap();
+    fs::create_dir_all(base.join("node_modules/pkg")).unwrap();
+    fs::create_dir_all(base.join("a/b/c")).unwrap();
+    fs::write(base.join("outside.txt"), "x").unwrap();
+    fs::write(base.join("worktree/inside.txt"), "x").unwrap();
+    fs::write(base.join("node_modules/pkg/index.js"), "x").unwrap();
+    fs::write(base.join("a/b/c/deep.txt"), "x").unwrap();
+
+    let mut found = Vec::new();
+    let complete = scan_writes_since(
+        &root(base.clone(), 2),
+        0,
+        &[base.join("worktree")],
+        100,
+        &mut found,
+    );
+
+    assert!(complete);
+    let paths: Vec<&str> = found.iter().map(|v| v.path.as_str()).collect();
+    assert_eq!(paths, vec![base.join("outside.txt").to_str().unwrap()]);
+}
+
+#[test]
+fn test_scan_writes_since_respects_window_and_limit() {
+    let dir = TempDir::new().unwrap();
+    for i in 0..3 {
+        fs::write(dir.path().join(format!("f{}.txt", i)), "x").unwrap();
+    }
+
+    let mut found = Vec::new();
+    let future = chrono::Utc::now().timestamp_millis() + 60_000;
+    assert!(scan_writes_since(
+        &root(dir.path().to_path_buf(), 4),
+        future,
+        &[],
+        100,
+        &mut found
+    ));
+    assert!(found.is_empty());
+
+    assert!(!scan_writes_since(
+        &root(dir.path().to_path_buf(), 4),
+        0,
+        &[],
+        2,
+        &mut found
+    ));
+    assert_eq!(found.len(), 2);
+}
+
+#[test]
+fn test_audit_writes_reports_overlapping_roots_once() {
+    let dir = TempDir::new().unwrap();
+    let repo = dir.path().join("repo");
+    let worktree = dir.path().join("worktree");
+    fs::create_dir_all(&repo).unwrap();
+    fs::create_dir_all(&worktree).unwrap();
+    fs::write(repo.join("leak.txt"), "x").unwrap();
+    fs::write(worktree.join("ok.txt"), "x").unwrap();
+
+    let report = audit_writes(
+        "agent-1",
+        &[root(repo.clone(), 64), root(dir.path().to_path_buf(), 4)],
+        &[worktree],
+        0,
+    );
+
+    assert_eq!(report.scanned_roots.len(), 2);
+    assert_eq!(report.violations.len(), 1);
+    assert!(report.violations[0].path.ends_with("leak.txt"));
+    assert!(!report.truncated);
+}
+
+#[test]
+fn test_default_audit_scope() {
+    let (roots, excluded) = default_audit_scope(
+        &PathBuf::from("/nonexistent/worktree"),
+        &PathBuf::from("/nonexistent/repo"),
+        Some(&PathBuf::from("/home/user")),
+    );
+
+    assert_eq!(roots[0].path, PathBuf::from("/nonexistent/repo"));
+    assert_eq!(roots[1].path, PathBuf::from("/home/user"));
+    assert!(excluded.contains(&PathBuf::from("/nonexistent/worktree")));
+    assert!(excluded.contains(&PathBuf::from("/home/user/.local/share/opencode")));
+}
+
+#[test]
+fn test_timeline_entry_for_audit() {
+    let clean = timeline_entry_for_audit(&report(&[], false), 10);
+    assert_eq!(clean.kind, TimelineEntryKind::WriteAudit);
+    assert_eq!(clean.agent_id.as_deref(), Some("agent-1"));
+    assert!(clean.details.is_empty());
+
+    let paths: Vec<String> = (0..25).map(|i| format!("/home/user/f{}", i)).collect();
+    let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let entry = timeline_entry_for_audit(&report(&refs, true), 10);
+    assert_eq!(entry.kind, TimelineEntryKind::WriteViolation);
+    assert!(entry.message.contains("25+"));
+    assert_eq!(entry.details.len(), 21);
+    assert_eq!(entry.details[20], "... and 5 more");
+}
+
+#[test]
+fn test_push_timeline_entry_caps_length() {
+    let mut task = Task {
+        name: "Audit".to_string(),
+        ..test_task(Vec::new())
+    };
+
+    for i in 0..(MAX_TIMELINE_ENTRIES + 3) {
+        push_timeline_entry(
+            &mut task,
+            TimelineEntry {
+                id: i.to_string(),
+                timestamp: i as i64,
+                agent_id: None,
+                kind: TimelineEntryKind::WriteAudit,
+                message: String::new(),
+                details: Vec::new(),
+            },
+        );
+    }
+
+    assert_eq!(task.timeline.len(), MAX_TIMELINE_ENTRIES);
+    assert_eq!(task.timeline[0].id, "3");
+}
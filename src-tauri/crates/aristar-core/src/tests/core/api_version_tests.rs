@@ -0,0 +1,29 @@
+//! Tests for IPC schema versioning.
+
+use crate::core::api_version::*;
+use crate::workspace::types::GlobalStatus;
+
+#[test]
+fn test_check_compatibility_accepts_current_version() {
+    let info = check_compatibility(API_VERSION).unwrap();
+    assert_eq!(info.api_version, API_VERSION);
+    assert_eq!(info.min_frontend_api_version, MIN_FRONTEND_API_VERSION);
+    assert!(!info.app_version.is_empty());
+}
+
+#[test]
+fn test_check_compatibility_names_side_to_rebuild() {
+    let newer = check_compatibility(API_VERSION + 1).unwrap_err();
+    assert!(newer.contains("rebuild the Rust core"), "{}", newer);
+
+    let older = check_compatibility(0).unwrap_err();
+    assert!(older.contains("rebuild the frontend"), "{}", older);
+}
+
+#[test]
+fn test_versioned_payload_keeps_fields_at_top_level() {
+    let value = serde_json::to_value(Versioned::new(GlobalStatus::default())).unwrap();
+    assert_eq!(value["schemaVersion"], API_VERSION);
+    assert_eq!(value["serversRunning"], 0);
+    assert!(value.get("payload").is_none());
+}
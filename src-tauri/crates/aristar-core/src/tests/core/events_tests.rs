@@ -0,0 +1,63 @@
+//! Tests for the event name registry.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::core::events::*;
+
+#[test]
+fn test_app_events_are_unique() {
+    let names: HashSet<&str> = APP_EVENTS.iter().map(|e| e.name).collect();
+    assert_eq!(names.len(), APP_EVENTS.len());
+}
+
+#[test]
+fn test_forwarded_events() {
+    let forwarded: Vec<&str> = forwarded_events().collect();
+    for event in [
+        AGENT_STATUS_EVENT,
+        AGENT_QUOTA_EVENT,
+        TASKS_LOADED_EVENT,
+        WORKTREE_LOCK_EXPIRED_EVENT,
+        STALE_WORKTREES_EVENT,
+        POWER_THROTTLED_EVENT,
+        GLOBAL_STATUS_EVENT,
+    ] {
+        assert!(forwarded.contains(&event), "{} not forwarded", event);
+    }
+    assert!(!forwarded.contains(&AUTO_ACCEPT_CANCEL_EVENT));
+    assert!(!forwarded.contains(&LFS_PULL_PROGRESS_EVENT));
+}
+
+#[test]
+fn test_event_names_only_declared_in_registry() {
+    fn visit(dir: &Path, found: &mut Vec<String>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                visit(&path, found);
+            } else if path.extension().is_some_and(|e| e == "rs")
+                && !path.ends_with("core/events.rs")
+            {
+                let source = std::fs::read_to_string(&path).unwrap();
+                if source
+                    .lines()
+                    .any(|l| l.starts_with("pub const ") && l.contains("_EVENT: &str"))
+                {
+                    found.push(path.display().to_string());
+                }
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    visit(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut found,
+    );
+    assert!(
+        found.is_empty(),
+        "event names declared outside core/events.rs: {:?}",
+        found
+    );
+}
@@ -0,0 +1,65 @@
+//! Tests for humanized report fields.
+
+use crate::core::{humanize_duration, humanize_relative, resolve_locale, Locale};
+
+const MINUTE: i64 = 60 * 1000;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+
+#[test]
+fn test_locale_parse() {
+    assert_eq!(Locale::parse("de"), Some(Locale::De));
+    assert_eq!(Locale::parse("fr-CA"), Some(Locale::Fr));
+    assert_eq!(Locale::parse("es_ES.UTF-8"), Some(Locale::Es));
+    assert_eq!(Locale::parse("EN"), Some(Locale::En));
+    assert_eq!(Locale::parse("ja"), None);
+    assert_eq!(resolve_locale(Some("de-AT")), Locale::De);
+}
+
+#[test]
+fn test_humanize_relative() {
+    let now = 1_000 * DAY;
+    assert_eq!(humanize_relative(now - 30_000, now, Locale::En), "just now");
+    assert_eq!(
+        humanize_relative(now - MINUTE, now, Locale::En),
+        "1 minute ago"
+    );
+    assert_eq!(
+        humanize_relative(now - 2 * DAY - HOUR, now, Locale::En),
+        "2 days ago"
+    );
+    assert_eq!(
+        humanize_relative(now + 3 * HOUR, now, Locale::En),
+        "in 3 hours"
+    );
+    assert_eq!(
+        humanize_relative(now - 400 * DAY, now, Locale::En),
+        "1 year ago"
+    );
+}
+
+#[test]
+fn test_humanize_relative_locales() {
+    let now = 1_000 * DAY;
+    assert_eq!(
+        humanize_relative(now - 2 * DAY, now, Locale::De),
+        "vor 2 Tagen"
+    );
+    assert_eq!(
+        humanize_relative(now - 2 * DAY, now, Locale::Fr),
+        "il y a 2 jours"
+    );
+    assert_eq!(humanize_relative(now + HOUR, now, Locale::Es), "en 1 hora");
+}
+
+#[test]
+fn test_humanize_duration() {
+    assert_eq!(humanize_duration(45_000, Locale::En), "45 s");
+    assert_eq!(
+        humanize_duration(HOUR + 5 * MINUTE, Locale::En),
+        "1 h 5 min"
+    );
+    assert_eq!(humanize_duration(3 * DAY + 2 * HOUR, Locale::En), "3 d 2 h");
+    assert_eq!(humanize_duration(3 * DAY, Locale::De), "3 T 0 h");
+    assert_eq!(humanize_duration(-5, Locale::En), "0 s");
+}
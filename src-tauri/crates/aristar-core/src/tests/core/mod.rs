@@ -0,0 +1,12 @@
+//! Core module tests.
+
+mod api_version_tests;
+mod events_tests;
+mod humanize_tests;
+mod notifications_tests;
+mod paths_tests;
+mod plugins_tests;
+mod ports_tests;
+mod read_only_tests;
+mod redaction_tests;
+mod schema_tests;
@@ -0,0 +1,71 @@
+//! Tests for Focus-gated desktop notifications.
+
+use crate::core::notifications::{
+    applescript_string, parse_focus_assertions, DesktopNotification, NotificationQueue,
+    NotificationUrgency, MAX_PENDING_NOTIFICATIONS,
+};
+
+fn notification(title: &str, urgency: NotificationUrgency) -> DesktopNotification {
+    DesktopNotification::new(title, "body", urgency)
+}
+
+#[test]
+fn test_parse_focus_assertions() {
+    let active = r#"{"data":[{"storeAssertionRecords":[{"assertionDetails":{"assertionDetailsModeIdentifier":"com.apple.donotdisturb.mode.default"}}]}]}"#;
+    assert!(parse_focus_assertions(active));
+    assert!(!parse_focus_assertions(
+        r#"{"data":[{"storeAssertionRecords":[]}]}"#
+    ));
+    assert!(!parse_focus_assertions(r#"{"data":[{}]}"#));
+    assert!(!parse_focus_assertions("not json"));
+}
+
+#[test]
+fn test_queue_holds_back_normal_notifications_during_focus() {
+    let queue = NotificationQueue::new();
+
+    assert!(queue
+        .submit(notification("a", NotificationUrgency::Normal), false)
+        .is_some());
+    assert!(queue
+        .submit(notification("b", NotificationUrgency::Urgent), true)
+        .is_some());
+    assert!(queue
+        .submit(notification("c", NotificationUrgency::Normal), true)
+        .is_none());
+    assert_eq!(queue.pending().len(), 1);
+
+    // Nothing is delivered while Focus stays on
+    assert!(queue.take_deliverable(true).is_empty());
+    let delivered = queue.take_deliverable(false);
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].title, "c");
+    assert!(queue.pending().is_empty());
+}
+
+#[test]
+fn test_queue_summarizes_and_caps_pending() {
+    let queue = NotificationQueue::new();
+    for i in 0..MAX_PENDING_NOTIFICATIONS + 5 {
+        queue.submit(
+            notification(&format!("n{}", i), NotificationUrgency::Normal),
+            true,
+        );
+    }
+    let pending = queue.pending();
+    assert_eq!(pending.len(), MAX_PENDING_NOTIFICATIONS);
+    assert_eq!(pending[0].title, "n5");
+
+    let delivered = queue.take_deliverable(false);
+    assert_eq!(delivered.len(), 1);
+    assert!(delivered[0].title.starts_with("50 updates"));
+}
+
+#[test]
+fn test_applescript_string() {
+    assert_eq!(applescript_string("plain"), "\"plain\"");
+    assert_eq!(
+        applescript_string(r#"say "hi" \ bye"#),
+        r#""say \"hi\" \\ bye""#
+    );
+}
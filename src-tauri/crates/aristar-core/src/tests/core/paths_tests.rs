@@ -0,0 +1,41 @@
+//! Tests for store path normalization.
+
+use crate::core::{normalize_path, path_key_with, paths_equal};
+use crate::tests::helpers::TestRepo;
+
+#[test]
+fn test_normalize_path_strips_trailing_slash_for_missing_path() {
+    assert_eq!(normalize_path("/no/such/dir/"), "/no/such/dir");
+    assert_eq!(normalize_path("/no/such/dir//"), "/no/such/dir");
+}
+
+#[test]
+fn test_normalize_path_keeps_root() {
+    assert_eq!(normalize_path("/"), "/");
+}
+
+#[test]
+fn test_normalize_path_canonicalizes_existing_path() {
+    let repo = TestRepo::new();
+    let canonical = repo.path().canonicalize().unwrap();
+    let dotted = format!("{}/./", repo.path().display());
+    assert_eq!(normalize_path(&dotted), canonical.to_string_lossy());
+}
+
+#[test]
+fn test_path_key_case_folding() {
+    assert_eq!(
+        path_key_with("/Users/Dev/Repo", true),
+        path_key_with("/users/dev/repo/", true)
+    );
+    assert_ne!(
+        path_key_with("/Users/Dev/Repo", false),
+        path_key_with("/users/dev/repo", false)
+    );
+}
+
+#[test]
+fn test_paths_equal_ignores_trailing_slash() {
+    assert!(paths_equal("/no/such/repo", "/no/such/repo/"));
+    assert!(!paths_equal("/no/such/repo", "/no/such/other"));
+}
@@ -0,0 +1,122 @@
+//! Tests for plugin manifests and actions.
+
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::core::plugins::{
+    find_plugin_action_in, load_plugins_from, parse_manifest, render_action_command,
+    run_plugin_action, PluginContext,
+};
+use crate::worktrees::templates::ScriptTemplateContext;
+
+const MANIFEST: &str = r#"
+name = "Tools"
+version = "1.0.0"
+
+[[actions]]
+id = "show-branch"
+name = "Show branch"
+context = "worktree"
+command = ["/usr/bin/printf", "%s:%s", "{{ branch }}", "{{repo_name}}"]
+
+[[actions]]
+id = "where"
+name = "Where am I"
+context = "task"
+command = ["/usr/bin/pwd"]
+"#;
+
+fn manifest_with_command(command: &str) -> String {
+    format!(
+        "name = \"Bad\"\n[[actions]]\nid = \"run\"\nname = \"Run\"\ncontext = \"repo\"\ncommand = {}\n",
+        command
+    )
+}
+
+#[test]
+fn test_parse_manifest() {
+    let plugin = parse_manifest("tools", Path::new("/p/tools.toml"), MANIFEST).unwrap();
+    assert_eq!(plugin.name, "Tools");
+    assert_eq!(plugin.version.as_deref(), Some("1.0.0"));
+    assert_eq!(plugin.actions.len(), 2);
+    assert_eq!(plugin.actions[0].context, PluginContext::Worktree);
+
+    // Programs outside the allowed locations, shell metacharacters and
+    // unknown variables are refused
+    for command in [
+        r#"["/bin/sh", "-c", "rm -rf /"]"#,
+        r#"["sh"]"#,
+        r#"["/usr/bin/printf", "a; rm -rf /"]"#,
+        r#"["/usr/bin/printf", "$HOME"]"#,
+        r#"["/usr/bin/printf", "{{secret}}"]"#,
+        r#"[]"#,
+    ] {
+        let manifest = manifest_with_command(command);
+        assert!(
+            parse_manifest("bad", Path::new("/p/bad.toml"), &manifest).is_err(),
+            "{}",
+            command
+        );
+    }
+    assert!(parse_manifest("Bad Name", Path::new("/p/x.toml"), MANIFEST).is_err());
+    assert!(parse_manifest(
+        "tools",
+        Path::new("/p/tools.toml"),
+        "name = \"x\"\nunknown = 1"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_load_plugins_collects_errors() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("tools.toml"), MANIFEST).unwrap();
+    std::fs::write(
+        dir.path().join("broken.toml"),
+        manifest_with_command(r#"["/bin/sh"]"#),
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "not a manifest").unwrap();
+
+    let list = load_plugins_from(dir.path());
+    assert_eq!(list.plugins.len(), 1);
+    assert_eq!(list.plugins[0].id, "tools");
+    assert_eq!(list.errors.len(), 1);
+    assert!(list.errors[0].path.ends_with("broken.toml"));
+
+    assert!(find_plugin_action_in(dir.path(), "tools", "where").is_ok());
+    assert!(find_plugin_action_in(dir.path(), "tools", "missing").is_err());
+    assert!(find_plugin_action_in(dir.path(), "../tools", "where").is_err());
+
+    assert!(load_plugins_from(&dir.path().join("missing"))
+        .plugins
+        .is_empty());
+}
+
+#[test]
+fn test_render_and_run_plugin_action() {
+    let plugin = parse_manifest("tools", Path::new("/p/tools.toml"), MANIFEST).unwrap();
+    let action = &plugin.actions[0];
+    let context = ScriptTemplateContext {
+        branch: Some("feature/login".to_string()),
+        repo_name: Some("web app".to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        render_action_command(action, &context).unwrap(),
+        vec!["/usr/bin/printf", "%s:%s", "feature/login", "web app"]
+    );
+
+    let dir = TempDir::new().unwrap();
+    let result = run_plugin_action(action, &context, dir.path()).unwrap();
+    assert!(result.success, "{:?}", result);
+    assert_eq!(result.stdout, "feature/login:web app");
+
+    // Values with shell metacharacters are rejected, not passed on
+    let unsafe_context = ScriptTemplateContext {
+        branch: Some("x;reboot".to_string()),
+        ..Default::default()
+    };
+    assert!(render_action_command(action, &unsafe_context).is_err());
+}
@@ -0,0 +1,84 @@
+//! Tests for the port reservation registry.
+
+use crate::core::ports::{PortConflictKind, PortRange, PortRegistry, DEFAULT_PORT_RANGE};
+
+const RANGE: PortRange = PortRange {
+    start: 5000,
+    end: 5003,
+};
+
+#[test]
+fn test_reserve_keeps_ports_apart_and_stable() {
+    let mut registry = PortRegistry::default();
+    let all_free = |_| true;
+
+    let a = registry
+        .reserve("opencode", "/wt/a", None, RANGE, all_free, 1)
+        .unwrap();
+    let b = registry
+        .reserve("opencode", "/wt/b", None, RANGE, all_free, 1)
+        .unwrap();
+    let dev = registry
+        .reserve("dev-server", "/wt/a", Some(a), RANGE, all_free, 1)
+        .unwrap();
+    assert_eq!((a, b, dev), (5000, 5001, 5002));
+
+    // Same owner gets its port back, even when asking for another one
+    assert_eq!(
+        registry
+            .reserve("opencode", "/wt/a", Some(5003), RANGE, all_free, 2)
+            .unwrap(),
+        5000
+    );
+    // Ports in use by other processes are skipped
+    assert_eq!(
+        registry
+            .reserve("opencode", "/wt/a", None, RANGE, |p| p != 5000, 3)
+            .unwrap(),
+        5003
+    );
+    assert_eq!(registry.find("opencode", "/wt/a").unwrap().port, 5003);
+    // 5000 is still in use, the rest of the range is reserved
+    assert!(registry
+        .reserve("opencode", "/wt/c", None, RANGE, |p| p != 5000, 4)
+        .is_err());
+
+    assert!(registry.claim("event-socket", "app", 5001, 5).is_err());
+    registry.claim("event-socket", "app", 7391, 5).unwrap();
+
+    assert_eq!(registry.release("/wt/a", Some("dev-server")), 1);
+    assert_eq!(registry.release("/wt/a", None), 1);
+    assert!(registry.find("opencode", "/wt/a").is_none());
+    assert_eq!(registry.reservations.len(), 2);
+}
+
+#[test]
+fn test_conflicts_and_range_settings() {
+    let mut registry = PortRegistry::default();
+    registry.claim("opencode", "/wt/a", 5000, 1).unwrap();
+    registry.claim("event-socket", "app", 9000, 1).unwrap();
+    // As from an edited ports.json
+    let mut duplicate = registry.reservations[0].clone();
+    duplicate.owner = "/wt/b".to_string();
+    registry.reservations.push(duplicate);
+
+    let conflicts = registry.conflicts(RANGE);
+    assert_eq!(conflicts.len(), 2);
+    assert_eq!(conflicts[0].kind, PortConflictKind::Duplicate);
+    assert_eq!(conflicts[0].reservations.len(), 2);
+    assert_eq!(conflicts[1].kind, PortConflictKind::OutOfRange);
+    assert_eq!(conflicts[1].port, 9000);
+
+    assert_eq!(PortRange::from_settings(None, None), DEFAULT_PORT_RANGE);
+    assert_eq!(
+        PortRange::from_settings(Some(6000), Some(6100)),
+        PortRange {
+            start: 6000,
+            end: 6100
+        }
+    );
+    assert_eq!(
+        PortRange::from_settings(Some(7000), Some(6000)),
+        DEFAULT_PORT_RANGE
+    );
+}
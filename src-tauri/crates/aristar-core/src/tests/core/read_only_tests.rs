@@ -0,0 +1,51 @@
+//! Tests for read-only observer mode and launch flag parsing.
+
+use std::path::PathBuf;
+
+use crate::core::{data_dir_from_args, is_truthy, read_only_error, read_only_requested};
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+// ============================================================================
+// read_only_requested tests
+// ============================================================================
+
+#[test]
+fn test_read_only_requested_with_flag() {
+    assert!(read_only_requested(&args(&["aristar", "--read-only"])));
+}
+
+#[test]
+fn test_read_only_error_mentions_action() {
+    let err = read_only_error("create a worktree");
+    assert!(err.contains("create a worktree"));
+    assert!(err.contains("read-only"));
+}
+
+#[test]
+fn test_is_truthy_values() {
+    assert!(is_truthy("1"));
+    assert!(is_truthy("TRUE"));
+    assert!(is_truthy(" yes "));
+    assert!(!is_truthy("0"));
+    assert!(!is_truthy(""));
+}
+
+// ============================================================================
+// data_dir_from_args tests
+// ============================================================================
+
+#[test]
+fn test_data_dir_from_args_flag() {
+    let dir = data_dir_from_args(&args(&["aristar", "--data-dir", "/tmp/bundle"]));
+    assert_eq!(dir, Some(PathBuf::from("/tmp/bundle")));
+}
+
+#[test]
+fn test_data_dir_from_args_flag_without_value() {
+    // A dangling flag must not be treated as a path
+    let dir = data_dir_from_args(&args(&["aristar", "--data-dir"]));
+    assert_ne!(dir, Some(PathBuf::from("--data-dir")));
+}
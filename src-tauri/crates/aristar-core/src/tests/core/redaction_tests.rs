@@ -0,0 +1,89 @@
+//! Tests for redaction of logs and transcripts.
+
+use serde_json::json;
+
+use crate::core::redaction::{pattern_matches, redact_json_with, redact_with, RedactionRules};
+
+fn rules(patterns: &[&str]) -> RedactionRules {
+    RedactionRules {
+        enabled: true,
+        patterns: patterns.iter().map(|p| p.to_string()).collect(),
+    }
+}
+
+#[test]
+fn test_redact_builtin_rules() {
+    let (text, matches) = redact_with(
+        "key sk-abcdefghijklmnop1234 for ada@example.com. api_key=hunter2 \
+         Authorization: Bearer abc.def token usage sk-short",
+        &rules(&[]),
+    );
+
+    assert_eq!(
+        text,
+        "key [REDACTED:api-key] for [REDACTED:email]. api_key=[REDACTED:secret] \
+         Authorization: Bearer [REDACTED:secret] token usage sk-short"
+    );
+    let kinds: Vec<&str> = matches.iter().map(|m| m.rule.as_str()).collect();
+    assert_eq!(kinds, ["api-key", "email", "secret", "secret"]);
+    assert_eq!(matches[2].text, "hunter2");
+
+    // Prose mentioning the words stays untouched
+    let prose = "Basic usage: the password is set, a Bearer token";
+    assert_eq!(redact_with(prose, &rules(&[])).0, prose);
+
+    let disabled = RedactionRules {
+        enabled: false,
+        patterns: Vec::new(),
+    };
+    assert_eq!(
+        redact_with("ada@example.com", &disabled).0,
+        "ada@example.com"
+    );
+}
+
+#[test]
+fn test_redaction_patterns() {
+    assert!(pattern_matches(
+        "*.corp.example.com",
+        "DB1.Corp.Example.com"
+    ));
+    assert!(pattern_matches("build-agent-*", "build-agent-7"));
+    assert!(pattern_matches("a*b*c", "aXbYbZc"));
+    assert!(!pattern_matches(
+        "*.corp.example.com",
+        "corp.example.com.evil"
+    ));
+    assert!(!pattern_matches("build-agent-*", "my-build-agent-7"));
+
+    let (text, matches) = redact_with(
+        "connect to db1.corp.example.com:5432 from build-agent-7",
+        &rules(&["*.corp.example.com", "build-agent-*"]),
+    );
+    assert_eq!(
+        text,
+        "connect to [REDACTED:pattern]:5432 from [REDACTED:pattern]"
+    );
+    assert_eq!(matches[0].rule, "*.corp.example.com");
+}
+
+#[test]
+fn test_redact_json_values() {
+    let mut value = json!({
+        "apiKey": "plain-looking-value",
+        "text": "mail ada@example.com",
+        "nested": [{ "output": "token=abc" }],
+        "tokens": { "input": 12 }
+    });
+    redact_json_with(&mut value, &rules(&[]));
+
+    assert_eq!(
+        value,
+        json!({
+            "apiKey": "[REDACTED:secret]",
+            "text": "mail [REDACTED:email]",
+            "nested": [{ "output": "token=[REDACTED:secret]" }],
+            "tokens": { "input": 12 }
+        })
+    );
+}
@@ -0,0 +1,75 @@
+//! Tests for the generated IPC schema and TypeScript definitions.
+
+use serde_json::json;
+
+use crate::core::schema::*;
+
+#[test]
+fn test_generated_files_are_up_to_date() {
+    let committed = [
+        (
+            SCHEMA_FILE,
+            include_str!("../../../../../../src/store/generated/schema.json"),
+        ),
+        (
+            TYPESCRIPT_FILE,
+            include_str!("../../../../../../src/store/generated/types.ts"),
+        ),
+    ];
+    for ((name, generated), (_, committed)) in generated_files().iter().zip(committed) {
+        assert!(
+            generated == committed,
+            "src/store/generated/{} is out of date, run `{}`",
+            name,
+            GENERATE_COMMAND
+        );
+    }
+}
+
+#[test]
+fn test_frontend_schema_covers_nested_types() {
+    let schema = frontend_schema();
+    let definitions = schema["definitions"].as_object().unwrap();
+
+    let worktree = &definitions["WorktreeInfo"];
+    assert!(worktree["properties"].get("headState").is_some());
+    // Reached only through `Task`
+    assert!(definitions.contains_key("TaskAgent"));
+    assert!(definitions.contains_key("AgentStatus"));
+}
+
+#[test]
+fn test_typescript_definitions_render_structs_and_enums() {
+    let schema = json!({
+        "definitions": {
+            "Kind": { "type": "string", "enum": ["up-to-date", "failed"] },
+            "Thing": {
+                "description": "A thing.",
+                "type": "object",
+                "required": ["id", "tags"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "kind": { "anyOf": [{ "$ref": "#/definitions/Kind" }, { "type": "null" }] },
+                    "sizes": { "type": "object", "additionalProperties": { "type": "integer" } },
+                    "tags": { "type": "array", "items": { "type": ["string", "null"] } }
+                }
+            }
+        }
+    });
+    let ts = typescript_definitions(&schema);
+
+    assert!(
+        ts.contains("export type Kind = \"up-to-date\" | \"failed\";"),
+        "{}",
+        ts
+    );
+    assert!(
+        ts.contains("/** A thing. */\nexport interface Thing {"),
+        "{}",
+        ts
+    );
+    assert!(ts.contains("  id: string;\n"), "{}", ts);
+    assert!(ts.contains("  kind?: Kind | null;\n"), "{}", ts);
+    assert!(ts.contains("  sizes?: Record<string, number>;\n"), "{}", ts);
+    assert!(ts.contains("  tags: (string | null)[];\n"), "{}", ts);
+}
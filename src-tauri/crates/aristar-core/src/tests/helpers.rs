@@ -0,0 +1,215 @@
+//! Shared test utilities.
+
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+use crate::agent_manager::types::{AgentStatus, Task, TaskAgent, TaskMode, TaskStatus};
+use crate::worktrees::types::{Repository, WorktreeInfo};
+
+/// A test fixture that creates a temporary git repository with some initial setup.
+/// The repository is automatically cleaned up when the fixture is dropped.
+pub struct TestRepo {
+    pub temp_dir: TempDir,
+}
+
+impl TestRepo {
+    /// Creates a new temporary git repository with:
+    /// - An initial commit
+    /// - A file called "test.txt"
+    pub fn new() -> Self {
+        let temp_dir = TempDir::new().expect("Failed to create temp directory");
+        let repo_path = temp_dir.path();
+
+        // Initialize git repo
+        run_git(&["init"], repo_path);
+
+        // Configure git user for commits
+        run_git(&["config", "user.email", "test@example.com"], repo_path);
+        run_git(&["config", "user.name", "Test User"], repo_path);
+
+        // Create initial file and commit
+        std::fs::write(repo_path.join("test.txt"), "initial content").unwrap();
+        run_git(&["add", "."], repo_path);
+        run_git(&["commit", "-m", "Initial commit"], repo_path);
+
+        TestRepo { temp_dir }
+    }
+
+    /// Creates a new temporary git repository with multiple branches.
+    pub fn with_branches(branch_names: &[&str]) -> Self {
+        let repo = Self::new();
+        let repo_path = repo.path();
+
+        for branch_name in branch_names {
+            run_git(&["branch", branch_name], repo_path);
+        }
+
+        repo
+    }
+
+    /// Returns the path to the repository root.
+    pub fn path(&self) -> &Path {
+        self.temp_dir.path()
+    }
+
+    /// Returns the path as a string.
+    pub fn path_str(&self) -> String {
+        self.temp_dir.path().to_string_lossy().to_string()
+    }
+
+    /// Creates a new commit with a given message.
+    #[allow(dead_code)]
+    pub fn commit(&self, message: &str) {
+        let file_path = self
+            .path()
+            .join(format!("{}.txt", message.replace(' ', "_")));
+        std::fs::write(&file_path, message).unwrap();
+        run_git(&["add", "."], self.path());
+        run_git(&["commit", "-m", message], self.path());
+    }
+
+    /// Creates a new branch.
+    pub fn create_branch(&self, name: &str) {
+        run_git(&["branch", name], self.path());
+    }
+
+    /// Checks out a branch.
+    pub fn checkout(&self, branch: &str) {
+        run_git(&["checkout", branch], self.path());
+    }
+
+    /// Gets the current branch name.
+    #[allow(dead_code)]
+    pub fn current_branch(&self) -> String {
+        let output = Command::new("git")
+            .args(["symbolic-ref", "--short", "HEAD"])
+            .current_dir(self.path())
+            .output()
+            .expect("Failed to get current branch");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+}
+
+/// Helper function to run git commands.
+fn run_git(args: &[&str], cwd: &Path) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("Failed to execute git command");
+
+    if !output.status.success() {
+        panic!(
+            "Git command failed: git {}\nstderr: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+/// Creates a non-git directory for testing error cases.
+pub fn create_non_git_dir() -> TempDir {
+    TempDir::new().expect("Failed to create temp directory")
+}
+
+/// A completed agent with its worktree at `/tmp/{id}`. Tests set the fields
+/// they care about with struct update syntax (`TaskAgent { status, ..test_agent("agent-1") }`).
+pub fn test_agent(id: &str) -> TaskAgent {
+    TaskAgent {
+        id: id.to_string(),
+        model_id: "claude-sonnet-4".to_string(),
+        provider_id: "anthropic".to_string(),
+        agent_type: None,
+        worktree_path: format!("/tmp/{}", id),
+        session_id: None,
+        status: AgentStatus::Completed,
+        accepted: false,
+        created_at: 0,
+        preferred_port: None,
+        review_comments: Vec::new(),
+        last_started_at: None,
+        forked_from: None,
+        quota_exceeded: None,
+    }
+}
+
+/// An idle parallel task `a1b2c3d4` on `main` of `/tmp/repo`, with `agents`.
+pub fn test_task(agents: Vec<TaskAgent>) -> Task {
+    Task {
+        id: "a1b2c3d4".to_string(),
+        name: "Task".to_string(),
+        source_type: "branch".to_string(),
+        source_branch: Some("main".to_string()),
+        source_commit: None,
+        source_repo_path: "/tmp/repo".to_string(),
+        agent_type: "build".to_string(),
+        mode: TaskMode::Parallel,
+        status: TaskStatus::Idle,
+        created_at: 0,
+        updated_at: 0,
+        agents,
+        opencode_version: None,
+        result_summary: None,
+        sandbox: Default::default(),
+        timeline: Vec::new(),
+        snapshot_commit: None,
+        context_files: Vec::new(),
+        sparse_paths: Vec::new(),
+        pipeline: None,
+        pipeline_run: None,
+        auto_accept: None,
+        pending_auto_accept: None,
+        disk_quota_mb: None,
+        agent_outcomes: Vec::new(),
+    }
+}
+
+/// A worktree at `path` on `branch`, named after the last path segment (also
+/// its id). Tests set the other fields with struct update syntax.
+pub fn test_worktree(path: &str, branch: &str) -> WorktreeInfo {
+    WorktreeInfo {
+        id: path.to_string(),
+        name: path.rsplit('/').next().unwrap_or(path).to_string(),
+        path: path.to_string(),
+        branch: Some(branch.to_string()),
+        commit: None,
+        head_state: None,
+        is_detached: false,
+        is_main: false,
+        is_locked: false,
+        lock_reason: None,
+        lock_expires_at: None,
+        startup_script: None,
+        script_executed: false,
+        created_at: 0,
+        hidden: false,
+        dev_url: None,
+        description: None,
+        labels: Vec::new(),
+        last_active: None,
+    }
+}
+
+/// A repository at `path` with `worktrees`, its id and name the last path segment.
+pub fn test_repository(path: &str, worktrees: Vec<WorktreeInfo>) -> Repository {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    Repository {
+        id: name.to_string(),
+        path: path.to_string(),
+        name: name.to_string(),
+        display_name: None,
+        worktrees,
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: None,
+        remote_default_branch: None,
+        protected_branches: Vec::new(),
+        remote_protected_branches: Vec::new(),
+        remote_protection_checked_at: None,
+        scan_settings: Default::default(),
+        labels: Vec::new(),
+    }
+}
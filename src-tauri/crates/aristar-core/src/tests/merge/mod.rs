@@ -0,0 +1,3 @@
+//! Merge module tests.
+
+mod operations_tests;
@@ -0,0 +1,412 @@
+//! Tests for publishing worktree commits with each merge strategy, and for
+//! merging branch worktrees into their base branch.
+
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+use crate::merge::operations::{
+    build_squash_message, find_branch_worktree, merge_worktree_into_base, parse_merge_tree_output,
+    publish_worktree, resolve_merge_strategy,
+};
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::types::MergeStrategy;
+
+fn git(args: &[&str], cwd: &Path) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .expect("Failed to execute git command");
+    assert!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+fn commit_file(cwd: &Path, file: &str, content: &str, message: &str) {
+    std::fs::write(cwd.join(file), content).unwrap();
+    git(&["add", "."], cwd);
+    git(&["commit", "-m", message], cwd);
+}
+
+/// A repository plus a detached worktree (like an agent's) with two commits.
+struct PublishFixture {
+    repo: TestRepo,
+    _worktree_dir: TempDir,
+    worktree: String,
+    branch: String,
+}
+
+fn create_fixture() -> PublishFixture {
+    let repo = TestRepo::new();
+    let branch = repo.current_branch();
+    let worktree_dir = TempDir::new().unwrap();
+    let worktree_path = worktree_dir.path().join("agent");
+    let worktree = worktree_path.to_string_lossy().to_string();
+
+    git(
+        &["worktree", "add", "--detach", &worktree, &branch],
+        repo.path(),
+    );
+    commit_file(&worktree_path, "a.txt", "a", "Add a");
+    commit_file(&worktree_path, "b.txt", "b", "Add b");
+
+    PublishFixture {
+        repo,
+        _worktree_dir: worktree_dir,
+        worktree,
+        branch,
+    }
+}
+
+fn commit_count(cwd: &Path, rev: &str) -> usize {
+    git(&["rev-list", "--count", rev], cwd).parse().unwrap()
+}
+
+// ============================================================================
+// Helper tests
+// ============================================================================
+
+#[test]
+fn test_resolve_merge_strategy_precedence() {
+    assert_eq!(
+        resolve_merge_strategy(Some(MergeStrategy::Rebase), Some(MergeStrategy::Squash)),
+        MergeStrategy::Rebase
+    );
+    assert_eq!(
+        resolve_merge_strategy(None, Some(MergeStrategy::Squash)),
+        MergeStrategy::Squash
+    );
+    assert_eq!(
+        resolve_merge_strategy(None, None),
+        MergeStrategy::MergeCommit
+    );
+}
+
+#[test]
+fn test_build_squash_message_lists_oldest_first() {
+    let subjects = vec!["Second".to_string(), "First".to_string()];
+    let message = build_squash_message("Fix login", Some("agent-1 (gpt-4o)"), &subjects);
+    assert!(message.starts_with("Fix login\n\nSquashed 2 commits from agent-1 (gpt-4o):"));
+    assert!(message.find("- First").unwrap() < message.find("- Second").unwrap());
+}
+
+#[test]
+fn test_merge_strategy_serializes_kebab_case() {
+    let json = serde_json::to_string(&MergeStrategy::MergeCommit).unwrap();
+    assert_eq!(json, "\"merge-commit\"");
+}
+
+#[test]
+fn test_find_branch_worktree() {
+    let fixture = create_fixture();
+    let repo_path = fixture.repo.path_str();
+    let checkout = find_branch_worktree(&repo_path, &fixture.branch).unwrap();
+    assert!(checkout.is_some());
+    assert!(find_branch_worktree(&repo_path, "missing")
+        .unwrap()
+        .is_none());
+}
+
+// ============================================================================
+// Publish tests
+// ============================================================================
+
+#[test]
+fn test_publish_squash_creates_single_commit() {
+    let fixture = create_fixture();
+    let repo_path = fixture.repo.path();
+    let before = commit_count(repo_path, &fixture.branch);
+
+    let result = publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Squash,
+        "Add files",
+        Some("agent-1"),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(result.commits_published, 2);
+    assert_eq!(commit_count(repo_path, &fixture.branch), before + 1);
+    assert_eq!(
+        git(&["rev-parse", &fixture.branch], repo_path),
+        result.commit
+    );
+    assert!(repo_path.join("b.txt").exists());
+    let subject = git(&["log", "-1", "--format=%s", &fixture.branch], repo_path);
+    assert_eq!(subject, "Add files");
+}
+
+#[test]
+fn test_publish_rebase_after_target_moved() {
+    let fixture = create_fixture();
+    let repo_path = fixture.repo.path();
+    commit_file(repo_path, "main.txt", "main", "Main change");
+    let before = commit_count(repo_path, &fixture.branch);
+
+    publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Rebase,
+        "Add files",
+        None,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(commit_count(repo_path, &fixture.branch), before + 2);
+    let merges = git(
+        &["rev-list", "--merges", "--count", &fixture.branch],
+        repo_path,
+    );
+    assert_eq!(merges, "0");
+}
+
+#[test]
+fn test_publish_merge_commit_to_unchecked_out_branch() {
+    let fixture = create_fixture();
+    let repo_path = fixture.repo.path();
+    fixture.repo.create_branch("target");
+
+    let result = publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        "target",
+        MergeStrategy::MergeCommit,
+        "Add files",
+        None,
+        Some("Merge agent work"),
+    )
+    .unwrap();
+
+    assert_eq!(git(&["rev-parse", "target"], repo_path), result.commit);
+    let subject = git(&["log", "-1", "--format=%s", "target"], repo_path);
+    assert_eq!(subject, "Merge agent work");
+    let merges = git(&["rev-list", "--merges", "--count", "target"], repo_path);
+    assert_eq!(merges, "1");
+}
+
+#[test]
+fn test_publish_conflict_leaves_target_untouched() {
+    let fixture = create_fixture();
+    let repo_path = fixture.repo.path();
+    commit_file(repo_path, "a.txt", "conflicting", "Conflicting change");
+    let target_before = git(&["rev-parse", &fixture.branch], repo_path);
+    let head_before = git(&["rev-parse", "HEAD"], Path::new(&fixture.worktree));
+
+    let result = publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Squash,
+        "Add files",
+        None,
+        None,
+    );
+
+    assert!(result.is_err());
+    assert_eq!(
+        git(&["rev-parse", &fixture.branch], repo_path),
+        target_before
+    );
+    assert_eq!(
+        git(&["rev-parse", "HEAD"], Path::new(&fixture.worktree)),
+        head_before
+    );
+}
+
+#[test]
+fn test_publish_rejects_dirty_worktree_and_empty_range() {
+    let fixture = create_fixture();
+    std::fs::write(Path::new(&fixture.worktree).join("dirty.txt"), "x").unwrap();
+    let dirty = publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Squash,
+        "t",
+        None,
+        None,
+    );
+    assert!(dirty.unwrap_err().contains("uncommitted changes"));
+
+    std::fs::remove_file(Path::new(&fixture.worktree).join("dirty.txt")).unwrap();
+    publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Rebase,
+        "t",
+        None,
+        None,
+    )
+    .unwrap();
+    let empty = publish_worktree(
+        &fixture.worktree,
+        &fixture.repo.path_str(),
+        &fixture.branch,
+        MergeStrategy::Rebase,
+        "t",
+        None,
+        None,
+    );
+    assert!(empty.unwrap_err().contains("Nothing to publish"));
+}
+
+// ============================================================================
+// Merging into the base branch
+// ============================================================================
+
+/// Add a worktree on a new `feature` branch with one commit.
+fn create_branch_worktree(repo: &TestRepo, dir: &TempDir) -> String {
+    let worktree_path = dir.path().join("feature");
+    let worktree = worktree_path.to_string_lossy().to_string();
+    git(
+        &["worktree", "add", "-b", "feature", &worktree],
+        repo.path(),
+    );
+    commit_file(&worktree_path, "feature.txt", "feature", "Add feature");
+    worktree
+}
+
+#[test]
+fn test_parse_merge_tree_output() {
+    let output = "abc123\na.txt\nb.txt\na.txt\n\nAuto-merging a.txt\nCONFLICT (content): Merge conflict in a.txt\n";
+    let (tree, conflicts, messages) = parse_merge_tree_output(output);
+    assert_eq!(tree, "abc123");
+    assert_eq!(conflicts, vec!["a.txt", "b.txt"]);
+    assert_eq!(messages.len(), 2);
+
+    let (tree, conflicts, messages) = parse_merge_tree_output("abc123\n");
+    assert_eq!(tree, "abc123");
+    assert!(conflicts.is_empty() && messages.is_empty());
+}
+
+#[test]
+fn test_merge_into_base_fast_forward_and_merge_commit() {
+    let repo = TestRepo::new();
+    let base = repo.current_branch();
+    let dir = TempDir::new().unwrap();
+    let worktree = create_branch_worktree(&repo, &dir);
+
+    let result = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::FastForward,
+        None,
+    )
+    .unwrap();
+    assert!(result.merged);
+    assert_eq!(result.source, "feature");
+    assert_eq!(result.commits_merged, 1);
+    assert_eq!(
+        result.commit.as_deref(),
+        Some(git(&["rev-parse", "feature"], repo.path()).as_str())
+    );
+    // The base checkout was fast-forwarded too
+    assert!(repo.path().join("feature.txt").exists());
+
+    // Both sides move on: fast-forward is refused, a merge commit works
+    commit_file(repo.path(), "main.txt", "main", "Main change");
+    commit_file(Path::new(&worktree), "more.txt", "more", "More feature");
+    let err = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::FastForward,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.contains("Cannot fast-forward"), "{}", err);
+
+    let result = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::MergeCommit,
+        None,
+    )
+    .unwrap();
+    assert!(result.merged);
+    assert_eq!(
+        git(&["log", "-1", "--format=%s", &base], repo.path()),
+        format!("Merge branch 'feature' into {}", base)
+    );
+    assert_eq!(
+        git(&["rev-list", "--merges", "--count", &base], repo.path()),
+        "1"
+    );
+    assert!(repo.path().join("more.txt").exists());
+    // The worktree branch is not touched
+    assert_eq!(
+        git(&["branch", "--show-current"], Path::new(&worktree)),
+        "feature"
+    );
+}
+
+#[test]
+fn test_merge_into_base_squash_and_conflicts() {
+    let repo = TestRepo::new();
+    let base = repo.current_branch();
+    let dir = TempDir::new().unwrap();
+    let worktree = create_branch_worktree(&repo, &dir);
+    commit_file(Path::new(&worktree), "shared.txt", "feature", "Edit shared");
+    commit_file(repo.path(), "shared.txt", "base", "Conflicting edit");
+    let base_before = git(&["rev-parse", &base], repo.path());
+
+    let result = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::Squash,
+        None,
+    )
+    .unwrap();
+    assert!(!result.merged);
+    assert!(result.commit.is_none());
+    assert_eq!(result.conflicts, vec!["shared.txt"]);
+    assert!(result.messages.iter().any(|m| m.contains("CONFLICT")));
+    assert_eq!(git(&["rev-parse", &base], repo.path()), base_before);
+
+    // Resolve on the feature branch, then squash
+    git(
+        &["merge", "--no-edit", "-X", "ours", &base],
+        Path::new(&worktree),
+    );
+    let result = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::Squash,
+        Some("Ship feature"),
+    )
+    .unwrap();
+    assert!(result.merged);
+    assert_eq!(
+        commit_count(repo.path(), &format!("{}..{}", base_before, base)),
+        1
+    );
+    assert_eq!(
+        git(&["log", "-1", "--format=%s", &base], repo.path()),
+        "Ship feature"
+    );
+
+    let err = merge_worktree_into_base(
+        &worktree,
+        &repo.path_str(),
+        &base,
+        MergeStrategy::Rebase,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.contains("Rebase is not available"));
+}
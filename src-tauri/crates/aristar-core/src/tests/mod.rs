@@ -1,5 +1,8 @@
 //! Centralized tests for the application.
 
 pub mod agent_manager;
+pub mod core;
 pub mod helpers;
+pub mod merge;
+pub mod workspace;
 pub mod worktrees;
@@ -0,0 +1,160 @@
+//! Tests for the local event WebSocket.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::workspace::event_socket::*;
+
+/// A masked client frame, as browsers send them.
+fn client_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let mut frame = vec![0x80 | opcode, 0x80 | payload.len() as u8];
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    frame
+}
+
+fn connect(port: u16, target: &str) -> (TcpStream, String) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        target
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+        response.push(byte[0]);
+    }
+    (stream, String::from_utf8_lossy(&response).to_string())
+}
+
+#[test]
+fn test_handshake_accept_key() {
+    // Example from RFC 6455
+    assert_eq!(
+        websocket_accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+}
+
+#[test]
+fn test_parse_handshake_and_token() {
+    let handshake = parse_handshake(
+        "GET /?token=secret HTTP/1.1\r\nupgrade: WebSocket\r\nSec-WebSocket-Key: abc==\r\n",
+    )
+    .unwrap();
+    assert_eq!(handshake.key, "abc==");
+    assert_eq!(handshake.token.as_deref(), Some("secret"));
+
+    let handshake = parse_handshake(
+        "GET / HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: abc==\r\nAuthorization: Bearer other\r\n",
+    )
+    .unwrap();
+    assert_eq!(handshake.token.as_deref(), Some("other"));
+
+    assert!(parse_handshake("GET / HTTP/1.1\r\nSec-WebSocket-Key: abc==\r\n").is_err());
+    assert!(parse_handshake("POST / HTTP/1.1\r\nUpgrade: websocket\r\n").is_err());
+
+    assert!(token_matches(Some("secret"), "secret"));
+    assert!(!token_matches(Some("secreT"), "secret"));
+    assert!(!token_matches(Some("secret2"), "secret"));
+    assert!(!token_matches(None, "secret"));
+}
+
+#[test]
+fn test_frames_roundtrip() {
+    let (opcode, payload) = read_frame(&mut client_frame(0x9, b"hello").as_slice()).unwrap();
+    assert_eq!((opcode, payload.as_slice()), (0x9, b"hello".as_slice()));
+
+    let long = vec![b'x'; 300];
+    let frame = encode_frame(0x1, &long);
+    assert_eq!(&frame[..4], &[0x81, 126, 0x01, 0x2C]);
+    let (opcode, payload) = read_frame(&mut frame.as_slice()).unwrap();
+    assert_eq!((opcode, payload), (0x1, long));
+}
+
+#[test]
+fn test_event_socket_requires_token_and_forwards_events() {
+    let socket = EventSocket::start(0, "secret".to_string()).unwrap();
+
+    let (_, response) = connect(socket.port, "/?token=wrong");
+    assert!(response.starts_with("HTTP/1.1 401"), "{}", response);
+
+    let (mut client, response) = connect(socket.port, "/?token=secret");
+    assert!(response.starts_with("HTTP/1.1 101"), "{}", response);
+    assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+    // A pong means the client has been registered for broadcasts
+    client.write_all(&client_frame(0x9, b"ping")).unwrap();
+    assert_eq!(read_frame(&mut client).unwrap(), (0xA, b"ping".to_vec()));
+
+    socket.broadcast(
+        "agent-status-changed",
+        serde_json::json!({ "taskId": "t1", "agentId": "agent-1", "status": "completed" }),
+    );
+    let (opcode, payload) = read_frame(&mut client).unwrap();
+    assert_eq!(opcode, 0x1);
+    let message: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(message["event"], "agent-status-changed");
+    assert_eq!(message["payload"]["status"], "completed");
+
+    client.write_all(&client_frame(0x8, b"")).unwrap();
+    assert_eq!(read_frame(&mut client).unwrap().0, 0x8);
+}
+
+#[test]
+fn test_event_socket_drops_stalled_client() {
+    let socket = EventSocket::start(0, "secret".to_string()).unwrap();
+    let (mut stalled, _) = connect(socket.port, "/?token=secret");
+    stalled.write_all(&client_frame(0x9, b"ping")).unwrap();
+    assert_eq!(read_frame(&mut stalled).unwrap(), (0xA, b"ping".to_vec()));
+
+    // The client reads nothing while its queue and socket buffers fill up;
+    // broadcasting must not wait on it
+    let started = std::time::Instant::now();
+    let filler = "x".repeat(64 * 1024);
+    for _ in 0..1000 {
+        socket.broadcast("stale-worktrees", serde_json::json!({ "filler": filler }));
+    }
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    // Once dropped, the client gets what was already sent, then the connection
+    // closes instead of going quiet (a read timeout would mean it stayed open)
+    let mut received = Vec::new();
+    let closed = stalled.read_to_end(&mut received);
+    assert!(closed.is_ok(), "{:?}", closed);
+    assert!(received.len() < 1000 * filler.len());
+}
+
+#[test]
+fn test_event_socket_limits_connections() {
+    let socket = EventSocket::start(0, "secret".to_string()).unwrap();
+    let clients: Vec<_> = (0..MAX_CLIENTS)
+        .map(|_| {
+            let (client, response) = connect(socket.port, "/?token=secret");
+            assert!(response.starts_with("HTTP/1.1 101"), "{}", response);
+            client
+        })
+        .collect();
+
+    // Over the limit, the connection is closed without a response
+    let (_, response) = connect(socket.port, "/?token=secret");
+    assert!(response.is_empty(), "{}", response);
+
+    // A slot frees up once a client leaves
+    drop(clients);
+    let accepted = (0..50).any(|_| {
+        std::thread::sleep(Duration::from_millis(20));
+        connect(socket.port, "/?token=secret")
+            .1
+            .starts_with("HTTP/1.1 101")
+    });
+    assert!(accepted);
+}
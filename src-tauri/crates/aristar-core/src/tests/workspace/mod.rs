@@ -0,0 +1,7 @@
+//! Workspace tests.
+
+mod event_socket_tests;
+mod recovery_tests;
+mod search_tests;
+mod snapshots_tests;
+mod status_tests;
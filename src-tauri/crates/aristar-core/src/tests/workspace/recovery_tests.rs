@@ -0,0 +1,132 @@
+//! Tests for the intent journal and startup recovery.
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::TaskManagerState;
+use crate::core::journal::{pending_intents_in, record_intent_in, JournalOperation};
+use crate::tests::helpers::TestRepo;
+use crate::workspace::recovery::recover_intents_in;
+use crate::workspace::types::RecoveryOutcome;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::store::AppState;
+use crate::worktrees::types::StoreData;
+
+fn is_registered(repo: &TestRepo, path: &str) -> bool {
+    let output = run_git_command(&["worktree", "list", "--porcelain"], &repo.path_str()).unwrap();
+    let name = Path::new(path)
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|l| l.starts_with("worktree ") && l.ends_with(&name))
+}
+
+#[test]
+fn test_journal_keeps_only_interrupted_intents() {
+    let dir = TempDir::new().unwrap();
+    let operation = JournalOperation::RemoveWorktree {
+        repo_path: "/tmp/repo".to_string(),
+        worktree_path: "/tmp/repo-wt".to_string(),
+    };
+
+    // Operations that return remove their intent
+    drop(record_intent_in(dir.path(), operation.clone()).unwrap());
+    assert!(pending_intents_in(dir.path()).is_empty());
+
+    // A crash leaves it behind
+    std::mem::forget(record_intent_in(dir.path(), operation.clone()).unwrap());
+    fs::write(dir.path().join("broken.json"), "{").unwrap();
+    let pending = pending_intents_in(dir.path());
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].operation, operation);
+}
+
+#[test]
+fn test_recover_rolls_back_unsaved_task_and_finishes_removal() {
+    let repo = TestRepo::new();
+    let data = TempDir::new().unwrap();
+    let journal = data.path().join("journal");
+
+    // A task that died while its second agent worktree was being created
+    let task_folder = data.path().join("a1b2c3d4");
+    let created = task_folder
+        .join("fix-login-gpt-4o")
+        .to_string_lossy()
+        .to_string();
+    let never_created = task_folder
+        .join("fix-login-o3")
+        .to_string_lossy()
+        .to_string();
+    fs::create_dir_all(&task_folder).unwrap();
+    run_git_command(&["worktree", "add", "--detach", &created], &repo.path_str()).unwrap();
+    run_git_command(
+        &["update-ref", "refs/aristar/snapshots/a1b2c3d4", "HEAD"],
+        &repo.path_str(),
+    )
+    .unwrap();
+    std::mem::forget(
+        record_intent_in(
+            &journal,
+            JournalOperation::CreateTask {
+                task_id: "a1b2c3d4".to_string(),
+                source_repo_path: repo.path_str(),
+                task_folder: task_folder.to_string_lossy().to_string(),
+                worktree_paths: vec![created.clone(), never_created],
+            },
+        )
+        .unwrap(),
+    );
+
+    // A removal that died before git removed the (dirty) worktree
+    let removed = data.path().join("removed").to_string_lossy().to_string();
+    run_git_command(&["worktree", "add", "--detach", &removed], &repo.path_str()).unwrap();
+    fs::write(Path::new(&removed).join("scratch.txt"), "wip").unwrap();
+    std::mem::forget(
+        record_intent_in(
+            &journal,
+            JournalOperation::RemoveWorktree {
+                repo_path: repo.path_str(),
+                worktree_path: removed.clone(),
+            },
+        )
+        .unwrap(),
+    );
+
+    let report = recover_intents_in(
+        &journal,
+        &AppState::new(StoreData::default()),
+        &TaskManagerState::default(),
+    );
+    let outcome = |operation: &str| {
+        report
+            .actions
+            .iter()
+            .find(|a| a.operation == operation)
+            .map(|a| a.outcome)
+    };
+    assert_eq!(outcome("create-task"), Some(RecoveryOutcome::RolledBack));
+    assert_eq!(
+        outcome("remove-worktree"),
+        Some(RecoveryOutcome::RolledForward)
+    );
+    assert!(!is_registered(&repo, &created));
+    assert!(!is_registered(&repo, &removed));
+    assert!(!Path::new(&removed).exists());
+    assert!(!task_folder.exists());
+    assert!(run_git_command(
+        &[
+            "rev-parse",
+            "--verify",
+            "-q",
+            "refs/aristar/snapshots/a1b2c3d4"
+        ],
+        &repo.path_str()
+    )
+    .is_err());
+    assert!(pending_intents_in(&journal).is_empty());
+}
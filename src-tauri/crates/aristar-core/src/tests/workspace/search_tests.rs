@@ -0,0 +1,125 @@
+//! Tests for the full-text search index.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::agent_manager::types::{Task, TaskStoreData};
+use crate::tests::helpers::{test_repository, test_task, test_worktree};
+use crate::workspace::search::{
+    collect_documents, load_search_index, save_search_index, tokenize, SearchIndex,
+};
+use crate::workspace::types::SearchEntityKind;
+use crate::worktrees::types::{Repository, WorktreeInfo};
+
+fn worktree(name: &str, branch: &str) -> WorktreeInfo {
+    test_worktree(&format!("/tmp/repo-wt/{}", name), branch)
+}
+
+fn repository(worktrees: Vec<WorktreeInfo>) -> Repository {
+    Repository {
+        id: "repo-1".to_string(),
+        display_name: Some("Storefront".to_string()),
+        ..test_repository("/tmp/repo", worktrees)
+    }
+}
+
+fn task(id: &str, name: &str, summary: Option<&str>) -> Task {
+    Task {
+        id: id.to_string(),
+        name: name.to_string(),
+        result_summary: summary.map(str::to_string),
+        ..test_task(Vec::new())
+    }
+}
+
+#[test]
+fn test_tokenize_splits_on_punctuation_and_lowercases() {
+    assert_eq!(
+        tokenize("feature/PROJ-123 Login_page"),
+        vec!["feature", "proj", "123", "login", "page"]
+    );
+    assert!(tokenize(" -/ ").is_empty());
+}
+
+#[test]
+fn test_search_ranks_across_entity_types() {
+    let notes = TempDir::new().unwrap();
+    fs::write(
+        notes.path().join("t2.md"),
+        "The checkout flow breaks on login",
+    )
+    .unwrap();
+
+    let repos = vec![repository(vec![
+        worktree("login-page", "feature/login"),
+        worktree("cart", "feature/cart"),
+    ])];
+    let tasks = TaskStoreData {
+        tasks: vec![
+            task("t1", "Fix login redirect", None),
+            task("t2", "Checkout polish", None),
+        ],
+        ..Default::default()
+    };
+    let docs = collect_documents(&repos, &tasks, |id| notes.path().join(format!("{}.md", id)));
+    let mut index = SearchIndex::default();
+    assert_eq!(index.sync(&docs), 5);
+
+    let results = index.search("login", 10);
+    let ids: Vec<&str> = results.iter().map(|r| r.id.as_str()).collect();
+    // Name matches outrank branch-only and notes-only matches
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2].id, "t2");
+    assert_eq!(results[2].matched_fields, vec!["notes"]);
+    assert!(ids.contains(&"t1"));
+    assert!(ids.contains(&"/tmp/repo-wt/login-page"));
+    let worktree = results
+        .iter()
+        .find(|r| r.kind == SearchEntityKind::Worktree)
+        .unwrap();
+    assert_eq!(worktree.parent_id.as_deref(), Some("repo-1"));
+
+    // Prefixes match; every query term must match
+    assert_eq!(
+        index.search("stor", 10)[0].kind,
+        SearchEntityKind::Repository
+    );
+    assert_eq!(index.search("login redir", 10).len(), 1);
+    assert!(index.search("login cart", 10).is_empty());
+    assert!(index.search("", 10).is_empty());
+}
+
+#[test]
+fn test_sync_reindexes_only_changes_and_persists() {
+    let dir = TempDir::new().unwrap();
+    let no_notes = |id: &str| dir.path().join(format!("{}.md", id));
+    let repos = vec![repository(vec![])];
+    let mut tasks = TaskStoreData {
+        tasks: vec![task("t1", "Billing export", None)],
+        ..Default::default()
+    };
+    let mut index = SearchIndex::default();
+    index.sync(&collect_documents(&repos, &tasks, no_notes));
+    assert_eq!(index.sync(&collect_documents(&repos, &tasks, no_notes)), 0);
+
+    // Edited task is re-indexed, old terms are gone
+    tasks.tasks[0].name = "Invoice export".to_string();
+    tasks.tasks[0].result_summary = Some("Adds CSV download".to_string());
+    assert_eq!(index.sync(&collect_documents(&repos, &tasks, no_notes)), 1);
+    assert!(index.search("billing", 10).is_empty());
+    assert_eq!(index.search("csv", 10)[0].matched_fields, vec!["summary"]);
+
+    // Deleted task is dropped
+    tasks.tasks.clear();
+    assert_eq!(index.sync(&collect_documents(&repos, &tasks, no_notes)), 1);
+    assert!(index.search("invoice", 10).is_empty());
+
+    let path = dir.path().join("search-index.json");
+    save_search_index(&path, &index).unwrap();
+    assert_eq!(load_search_index(&path), index);
+    assert_eq!(
+        load_search_index(&dir.path().join("missing.json")),
+        SearchIndex::default()
+    );
+}
@@ -0,0 +1,74 @@
+//! Tests for workspace snapshots.
+
+use tempfile::TempDir;
+
+use crate::agent_manager::TaskManagerState;
+use crate::tests::helpers::test_repository;
+use crate::workspace::snapshots::*;
+use crate::worktrees::store::{AppState, RepositoryKey};
+use crate::worktrees::types::StoreData;
+
+fn app_state(ids: &[&str]) -> AppState {
+    AppState::new(StoreData {
+        repositories: ids
+            .iter()
+            .map(|id| test_repository(&format!("/tmp/{}", id), vec![]))
+            .collect(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_validate_snapshot_name() {
+    assert!(validate_snapshot_name("demo data_1").is_ok());
+    assert!(validate_snapshot_name("").is_err());
+    assert!(validate_snapshot_name("  ").is_err());
+    assert!(validate_snapshot_name("../store").is_err());
+    assert!(validate_snapshot_name(&"a".repeat(MAX_SNAPSHOT_NAME_LEN + 1)).is_err());
+}
+
+#[test]
+fn test_snapshot_write_list_read_delete() {
+    let dir = TempDir::new().unwrap();
+    let state = app_state(&["a", "b"]);
+    let tasks = TaskManagerState::default();
+
+    let mut older = capture_snapshot("older", &state, &tasks, None).unwrap();
+    older.created_at -= 1000;
+    write_snapshot(dir.path(), &older).unwrap();
+    let ui_state = serde_json::json!({ "theme": "dark" });
+    let demo = capture_snapshot("demo", &state, &tasks, Some(ui_state.clone())).unwrap();
+    write_snapshot(dir.path(), &demo).unwrap();
+
+    let listed = list_snapshots(dir.path());
+    let names: Vec<&str> = listed.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["demo", "older"]);
+    assert_eq!(listed[0].repository_count, 2);
+
+    let read = read_snapshot(dir.path(), "demo").unwrap();
+    assert_eq!(read.ui_state, Some(ui_state));
+
+    delete_snapshot(dir.path(), "demo").unwrap();
+    assert!(read_snapshot(dir.path(), "demo").is_err());
+    assert_eq!(list_snapshots(dir.path()).len(), 1);
+}
+
+#[test]
+fn test_restore_snapshot_replaces_state_and_index() {
+    let demo_state = app_state(&["demo"]);
+    let tasks = TaskManagerState::default();
+    let snapshot = capture_snapshot("demo", &demo_state, &tasks, None).unwrap();
+
+    let state = app_state(&["real-1", "real-2"]);
+    restore_snapshot(snapshot, &state, &tasks).unwrap();
+
+    assert_eq!(state.store.read().unwrap().repositories.len(), 1);
+    assert!(state
+        .get_repository(RepositoryKey::Id("demo"))
+        .unwrap()
+        .is_some());
+    assert!(state
+        .get_repository(RepositoryKey::Id("real-1"))
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,77 @@
+//! Tests for global status counters.
+
+use crate::tests::helpers::TestRepo;
+use crate::workspace::status::*;
+use crate::workspace::types::GlobalStatus;
+
+// ============================================================================
+// parse_df_capacity tests
+// ============================================================================
+
+#[test]
+fn test_parse_df_capacity_macos_output() {
+    let output = "Filesystem 512-blocks Used Available Capacity Mounted on\n\
+                  /dev/disk3s1 971350180 605216328 346102840 64% /System/Volumes/Data\n";
+    assert_eq!(parse_df_capacity(output), Some(64));
+}
+
+#[test]
+fn test_parse_df_capacity_invalid_output() {
+    assert_eq!(parse_df_capacity(""), None);
+    assert_eq!(parse_df_capacity("header only\n"), None);
+}
+
+#[test]
+fn test_get_directory_size_bytes() {
+    assert_eq!(parse_du_kilobytes("128\t/tmp/agent\n"), Some(128 * 1024));
+    assert_eq!(parse_du_kilobytes(""), None);
+
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("blob.bin"), vec![1u8; 256 * 1024]).unwrap();
+    let size = get_directory_size_bytes(dir.path()).unwrap();
+    assert!(size >= 256 * 1024, "{}", size);
+    assert_eq!(get_directory_size_bytes(&dir.path().join("missing")), None);
+}
+
+// ============================================================================
+// Dirty worktree tests
+// ============================================================================
+
+#[test]
+fn test_is_worktree_dirty_clean_repo() {
+    let repo = TestRepo::new();
+    assert!(!is_worktree_dirty(&repo.path_str()));
+}
+
+#[test]
+fn test_is_worktree_dirty_modified_file() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("test.txt"), "changed").unwrap();
+    assert!(is_worktree_dirty(&repo.path_str()));
+}
+
+#[test]
+fn test_is_worktree_dirty_missing_path() {
+    assert!(!is_worktree_dirty("/nonexistent/worktree"));
+}
+
+#[test]
+fn test_cache_reuses_fresh_result() {
+    let repo = TestRepo::new();
+    let cache = GlobalStatusCache::new();
+
+    assert!(!cache.is_dirty(&repo.path_str(), 1_000));
+    std::fs::write(repo.path().join("test.txt"), "changed").unwrap();
+
+    // Still within the TTL: cached result is returned
+    assert!(!cache.is_dirty(&repo.path_str(), 1_000 + SUB_RESULT_TTL_MS - 1));
+    // TTL expired: recomputed
+    assert!(cache.is_dirty(&repo.path_str(), 1_000 + SUB_RESULT_TTL_MS));
+}
+
+#[test]
+fn test_global_status_is_camel_case() {
+    let json = serde_json::to_value(GlobalStatus::default()).unwrap();
+    assert!(json.get("serversRunning").is_some());
+    assert!(json.get("diskUsagePercent").is_some());
+}
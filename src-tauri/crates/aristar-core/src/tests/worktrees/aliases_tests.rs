@@ -0,0 +1,82 @@
+//! Tests for worktree alias symlinks.
+
+use std::fs;
+
+use crate::worktrees::aliases::{
+    alias_path_in, create_alias_link, prune_dangling_aliases, remove_alias_links_to,
+    sanitize_alias_component,
+};
+
+#[test]
+fn test_alias_path_sanitizes_names() {
+    assert_eq!(sanitize_alias_component("feature/login"), "feature-login");
+    assert_eq!(sanitize_alias_component(".."), "_");
+    assert_eq!(sanitize_alias_component(" webapp "), "webapp");
+
+    let base = std::path::Path::new("/aliases");
+    assert_eq!(
+        alias_path_in(base, "webapp", "fix/bug"),
+        base.join("webapp").join("fix-bug")
+    );
+}
+
+#[test]
+fn test_create_alias_link() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let target = temp.path().join("abc123/login");
+    let other = temp.path().join("def456/login");
+    fs::create_dir_all(&target).unwrap();
+    fs::create_dir_all(&other).unwrap();
+    let link = alias_path_in(&temp.path().join("by-name"), "webapp", "login");
+
+    create_alias_link(&target, &link).unwrap();
+    assert_eq!(fs::read_link(&link).unwrap(), target);
+    // Idempotent
+    create_alias_link(&target, &link).unwrap();
+
+    // Another live worktree keeps its alias
+    let err = create_alias_link(&other, &link).unwrap_err();
+    assert!(err.contains("already points to"));
+
+    // A dangling alias is replaced
+    fs::remove_dir(&target).unwrap();
+    create_alias_link(&other, &link).unwrap();
+    assert_eq!(fs::read_link(&link).unwrap(), other);
+}
+
+#[test]
+fn test_create_alias_link_refuses_real_directories() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let target = temp.path().join("abc123/login");
+    let link = temp.path().join("by-name/webapp/login");
+    fs::create_dir_all(&target).unwrap();
+    fs::create_dir_all(&link).unwrap();
+
+    assert!(create_alias_link(&target, &link).is_err());
+    assert!(link.is_dir());
+}
+
+#[test]
+fn test_remove_and_prune_aliases() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let alias_base = temp.path().join("by-name");
+    let kept = temp.path().join("abc123/kept");
+    let removed = temp.path().join("abc123/removed");
+    let dangling = temp.path().join("def456/gone");
+    for dir in [&kept, &removed, &dangling] {
+        fs::create_dir_all(dir).unwrap();
+    }
+    create_alias_link(&kept, &alias_path_in(&alias_base, "webapp", "kept")).unwrap();
+    create_alias_link(&removed, &alias_path_in(&alias_base, "webapp", "removed")).unwrap();
+    create_alias_link(&dangling, &alias_path_in(&alias_base, "api", "gone")).unwrap();
+
+    remove_alias_links_to(&alias_base, &removed).unwrap();
+    assert!(!alias_base.join("webapp/removed").exists());
+    assert!(alias_base.join("webapp/kept").exists());
+
+    fs::remove_dir(&dangling).unwrap();
+    assert_eq!(prune_dangling_aliases(&alias_base).unwrap(), 1);
+    // Empty repository folders are removed
+    assert!(!alias_base.join("api").exists());
+    assert!(alias_base.join("webapp").exists());
+}
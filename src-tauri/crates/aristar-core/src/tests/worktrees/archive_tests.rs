@@ -0,0 +1,84 @@
+//! Tests for worktree archives.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::archive::{
+    archive_worktree_in, delete_archive_in, list_archives_in, restore_archive_in,
+};
+use crate::worktrees::operations::run_git_command;
+
+/// A worktree on `feature` with a modified, an untracked and an ignored file.
+fn dirty_worktree(repo: &TestRepo, parent: &TempDir) -> String {
+    let path = parent.path().join("wt").to_string_lossy().to_string();
+    run_git_command(&["branch", "feature"], &repo.path_str()).unwrap();
+    run_git_command(&["worktree", "add", &path, "feature"], &repo.path_str()).unwrap();
+    fs::write(repo.path().join(".git/info/exclude"), "node_modules/\n").unwrap();
+    fs::write(format!("{}/test.txt", path), "changed").unwrap();
+    fs::write(format!("{}/notes.txt", path), "untracked").unwrap();
+    fs::create_dir_all(format!("{}/node_modules/pkg", path)).unwrap();
+    fs::write(format!("{}/node_modules/pkg/index.js", path), "ignored").unwrap();
+    path
+}
+
+#[test]
+fn test_tracked_only_archive_restores_changes_on_branch() {
+    let repo = TestRepo::new();
+    let parent = TempDir::new().unwrap();
+    let archives = TempDir::new().unwrap();
+    let path = dirty_worktree(&repo, &parent);
+
+    let archive = archive_worktree_in(archives.path(), &path, true).unwrap();
+    assert_eq!(archive.name, "wt");
+    assert_eq!(archive.branch.as_deref(), Some("feature"));
+    assert!(archive.commit.is_some());
+    assert!(archive.size_bytes > 0);
+
+    run_git_command(&["worktree", "remove", "--force", &path], &repo.path_str()).unwrap();
+    assert!(!parent.path().join("wt").exists());
+
+    let restored = restore_archive_in(archives.path(), &archive.id, None).unwrap();
+    assert_eq!(restored, path);
+    assert_eq!(
+        fs::read_to_string(format!("{}/test.txt", path)).unwrap(),
+        "changed"
+    );
+    assert_eq!(
+        fs::read_to_string(format!("{}/notes.txt", path)).unwrap(),
+        "untracked"
+    );
+    assert!(!parent.path().join("wt/node_modules").exists());
+    let head = run_git_command(&["symbolic-ref", "--short", "HEAD"], &path).unwrap();
+    assert_eq!(String::from_utf8_lossy(&head.stdout).trim(), "feature");
+
+    // Restoring over an existing folder is refused
+    assert!(restore_archive_in(archives.path(), &archive.id, None).is_err());
+}
+
+#[test]
+fn test_full_archive_list_and_delete() {
+    let repo = TestRepo::new();
+    let parent = TempDir::new().unwrap();
+    let archives = TempDir::new().unwrap();
+    let path = dirty_worktree(&repo, &parent);
+
+    let archive = archive_worktree_in(archives.path(), &path, false).unwrap();
+    assert_eq!(list_archives_in(archives.path()), vec![archive.clone()]);
+
+    // Somewhere else, without the worktree's .git link
+    let target = parent.path().join("copy").to_string_lossy().to_string();
+    restore_archive_in(archives.path(), &archive.id, Some(&target)).unwrap();
+    assert!(parent
+        .path()
+        .join("copy/node_modules/pkg/index.js")
+        .exists());
+    let git = fs::read_to_string(parent.path().join("copy/.git")).unwrap();
+    assert!(git.contains("worktrees/copy"));
+
+    assert!(delete_archive_in(archives.path(), "../x").is_err());
+    delete_archive_in(archives.path(), &archive.id).unwrap();
+    assert!(list_archives_in(archives.path()).is_empty());
+    assert!(fs::read_dir(archives.path()).unwrap().next().is_none());
+}
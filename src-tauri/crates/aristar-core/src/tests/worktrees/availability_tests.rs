@@ -0,0 +1,127 @@
+//! Tests for repository availability tracking.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::availability::{
+    apply_probe_result, backoff_delay_ms, is_repository_reachable, probe_repositories,
+    RepositoryAvailability, INITIAL_BACKOFF_MS, MAX_BACKOFF_MS,
+};
+use crate::worktrees::store::{AppState, RepositoryKey};
+use crate::worktrees::types::{Repository, StoreData};
+
+fn create_repository(id: &str, path: &str) -> Repository {
+    Repository {
+        id: id.to_string(),
+        path: path.to_string(),
+        name: id.to_string(),
+        display_name: None,
+        worktrees: vec![],
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: None,
+        remote_default_branch: None,
+        protected_branches: vec![],
+        remote_protected_branches: vec![],
+        remote_protection_checked_at: None,
+        scan_settings: Default::default(),
+        labels: vec![],
+    }
+}
+
+fn create_state(repos: Vec<Repository>) -> AppState {
+    AppState::new(StoreData {
+        repositories: repos,
+        ..Default::default()
+    })
+}
+
+// ============================================================================
+// Backoff tests
+// ============================================================================
+
+#[test]
+fn test_backoff_delay_doubles_and_caps() {
+    assert_eq!(backoff_delay_ms(0), 0);
+    assert_eq!(backoff_delay_ms(1), INITIAL_BACKOFF_MS);
+    assert_eq!(backoff_delay_ms(2), INITIAL_BACKOFF_MS * 2);
+    assert_eq!(backoff_delay_ms(3), INITIAL_BACKOFF_MS * 4);
+    assert_eq!(backoff_delay_ms(50), MAX_BACKOFF_MS);
+}
+
+#[test]
+fn test_tracker_schedules_next_probe() {
+    let tracker = RepositoryAvailability::new();
+    assert!(tracker.should_probe("repo", 0));
+
+    let state = tracker.record_failure("repo", 1_000);
+    assert_eq!(state.failures, 1);
+    assert!(!tracker.should_probe("repo", 1_000));
+    assert!(tracker.should_probe("repo", 1_000 + INITIAL_BACKOFF_MS));
+
+    let state = tracker.record_failure("repo", 2_000);
+    assert_eq!(state.failures, 2);
+    assert_eq!(state.next_probe_at, 2_000 + INITIAL_BACKOFF_MS * 2);
+
+    tracker.reset("repo");
+    assert!(tracker.should_probe("repo", 2_000));
+}
+
+// ============================================================================
+// Probe tests
+// ============================================================================
+
+#[test]
+fn test_is_repository_reachable() {
+    let repo = TestRepo::new();
+    assert!(is_repository_reachable(&repo.path_str()));
+    assert!(!is_repository_reachable("/nonexistent/volume/repo"));
+}
+
+#[test]
+fn test_apply_probe_result_emits_only_on_change() {
+    let state = create_state(vec![create_repository("r1", "/nonexistent/r1")]);
+    let tracker = RepositoryAvailability::new();
+
+    let event = apply_probe_result(&state, &tracker, "r1", false, 100)
+        .unwrap()
+        .expect("becoming unavailable should produce an event");
+    assert!(!event.available);
+    assert_eq!(event.unavailable_since, Some(100));
+
+    let again = apply_probe_result(&state, &tracker, "r1", false, 200).unwrap();
+    assert!(again.is_none());
+
+    let back = apply_probe_result(&state, &tracker, "r1", true, 300)
+        .unwrap()
+        .expect("becoming available should produce an event");
+    assert!(back.available);
+    assert!(tracker.should_probe("r1", 300));
+}
+
+#[test]
+fn test_probe_repositories_keeps_worktrees_of_unavailable_repo() {
+    let state = create_state(vec![create_repository("gone", "/nonexistent/volume/gone")]);
+    let tracker = RepositoryAvailability::new();
+
+    let changes = probe_repositories(&state, &tracker, 1_000).unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].repository_id, "gone");
+
+    // Still inside the backoff window: no probe, no change
+    let changes = probe_repositories(&state, &tracker, 1_001).unwrap();
+    assert!(changes.is_empty());
+
+    let stored = state
+        .get_repository(RepositoryKey::Id("gone"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.unavailable_since, Some(1_000));
+}
+
+#[test]
+fn test_repository_deserializes_without_unavailable_since() {
+    let json = r#"{"id":"1","path":"/p","name":"p","worktrees":[],"last_scanned":0}"#;
+    let repo: Repository = serde_json::from_str(json).unwrap();
+    assert!(repo.unavailable_since.is_none());
+}
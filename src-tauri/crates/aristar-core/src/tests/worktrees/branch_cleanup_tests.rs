@@ -0,0 +1,79 @@
+//! Tests for merged branch cleanup suggestions.
+
+use std::collections::HashSet;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::branch_cleanup::{
+    delete_merged_branches, parse_merged_branches, suggest_branch_cleanup,
+};
+use crate::worktrees::operations::{create_worktree, local_branch_exists, run_git_command};
+use crate::worktrees::types::CreateWorktreeOptions;
+
+#[test]
+fn test_parse_merged_branches() {
+    let output = "refs/heads/main\x00aaa\x00a\x00Ann\x00100\x00Base\n\
+                  refs/heads/old\x00bbb\x00b\x00Bob\x00200\x00Old work\n\
+                  refs/heads/newer\x00ccc\x00c\x00Cid\x00300\x00Newer work\n\
+                  refs/heads/in-use\x00ddd\x00d\x00Dee\x00400\x00Checked out\n\
+                  refs/heads/release\x00eee\x00e\x00Eve\x00500\x00Protected\n";
+    let checked_out = HashSet::from(["in-use".to_string()]);
+
+    let branches = parse_merged_branches(output, "main", &checked_out, |b| b == "release");
+    let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["newer", "old"]);
+    assert_eq!(branches[1].last_commit.hash, "bbb");
+    assert_eq!(branches[1].last_commit.author, "Bob");
+    assert_eq!(branches[1].last_commit.date, 200);
+    assert_eq!(branches[1].last_commit.message, "Old work");
+}
+
+#[test]
+fn test_suggest_and_delete_merged_branches() {
+    let repo = TestRepo::new();
+    let base = repo.current_branch();
+    repo.create_branch("merged");
+    repo.create_branch("unmerged");
+    repo.checkout("unmerged");
+    repo.commit("unmerged work");
+    repo.checkout(&base);
+    repo.create_branch("in-worktree");
+    create_worktree(
+        &repo.path_str(),
+        "in-worktree",
+        &CreateWorktreeOptions {
+            branch: Some("in-worktree".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let suggestion = suggest_branch_cleanup(&repo.path_str(), None).unwrap();
+    assert_eq!(suggestion.base_branch.as_deref(), Some(base.as_str()));
+    let names: Vec<&str> = suggestion
+        .branches
+        .iter()
+        .map(|b| b.name.as_str())
+        .collect();
+    assert_eq!(names, ["merged"]);
+
+    let confirm = vec![
+        "merged".to_string(),
+        "unmerged".to_string(),
+        "missing".to_string(),
+    ];
+    run_git_command(
+        &["config", "branch.merged.description", "Done"],
+        &repo.path_str(),
+    )
+    .unwrap();
+    let report = delete_merged_branches(&repo.path_str(), None, &confirm).unwrap();
+    assert_eq!(report.deleted, ["merged"]);
+    let skipped: Vec<&str> = report.skipped.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(skipped, ["unmerged", "missing"]);
+    assert_eq!(report.skipped[1].reason, "No such branch");
+
+    assert!(!local_branch_exists(&repo.path_str(), "merged"));
+    assert!(run_git_command(&["config", "branch.merged.description"], &repo.path_str()).is_err());
+    assert!(local_branch_exists(&repo.path_str(), "unmerged"));
+    assert!(local_branch_exists(&repo.path_str(), "in-worktree"));
+}
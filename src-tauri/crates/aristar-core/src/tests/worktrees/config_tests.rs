@@ -0,0 +1,146 @@
+//! Tests for per-worktree git config.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::config::{
+    get_repo_config_summary, get_worktree_config, parse_config_list, parse_count_objects,
+    parse_remote_list, set_worktree_config, validate_config_key, worktree_config_enabled,
+    RemoteInfo, WorktreeConfigEntry,
+};
+use crate::worktrees::operations::run_git_command;
+
+#[test]
+fn test_validate_config_key() {
+    assert!(validate_config_key("user.email").is_ok());
+    assert!(validate_config_key("core.hooksPath").is_ok());
+    assert!(validate_config_key("remote.origin.url").is_ok());
+
+    assert!(validate_config_key("email").is_err());
+    assert!(validate_config_key("user.").is_err());
+    assert!(validate_config_key("user email.name").is_err());
+    assert!(validate_config_key("extensions.worktreeConfig").is_err());
+    assert!(validate_config_key("core.bare").is_err());
+}
+
+#[test]
+fn test_parse_config_list() {
+    let entries = parse_config_list(
+        "user.email=me@example.com\ncore.sparsecheckout=true\nalias.x=!git log --format=%h\n",
+    );
+
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries[0],
+        WorktreeConfigEntry {
+            key: "user.email".to_string(),
+            value: "me@example.com".to_string(),
+        }
+    );
+    assert_eq!(entries[2].value, "!git log --format=%h");
+}
+
+#[test]
+fn test_set_worktree_config_is_isolated() {
+    let repo = TestRepo::new();
+    let worktree_dir = tempfile::TempDir::new().unwrap();
+    let worktree_path = worktree_dir.path().join("wt");
+    let worktree = worktree_path.to_str().unwrap();
+    run_git_command(&["worktree", "add", "-b", "wt", worktree], &repo.path_str()).unwrap();
+
+    assert!(!worktree_config_enabled(worktree));
+    assert!(get_worktree_config(worktree).unwrap().is_empty());
+
+    let entries = set_worktree_config(worktree, "user.email", Some("bot@example.com")).unwrap();
+    assert!(worktree_config_enabled(&repo.path_str()));
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].value, "bot@example.com");
+
+    // The main worktree doesn't see the value
+    assert!(get_worktree_config(&repo.path_str()).unwrap().is_empty());
+    let main_email = run_git_command(&["config", "--get", "user.email"], &repo.path_str()).unwrap();
+    assert_ne!(
+        String::from_utf8_lossy(&main_email.stdout).trim(),
+        "bot@example.com"
+    );
+
+    let entries = set_worktree_config(worktree, "user.email", None).unwrap();
+    assert!(entries.is_empty());
+}
+
+#[test]
+fn test_set_worktree_config_rejects_missing_path() {
+    let err = set_worktree_config("/nonexistent/worktree", "user.email", Some("x")).unwrap_err();
+    assert!(err.contains("not found"));
+}
+
+#[test]
+fn test_parse_remote_list_and_count_objects() {
+    let remotes = parse_remote_list(
+        "origin\tgit@example.com:org/app.git (fetch)\n\
+         origin\tgit@example.com:org/app.git (push)\n\
+         fork\thttps://example.com/me/app.git (fetch)\n\
+         fork\thttps://example.com/me/app.git (push)\n",
+    );
+    assert_eq!(
+        remotes,
+        vec![
+            RemoteInfo {
+                name: "origin".to_string(),
+                url: "git@example.com:org/app.git".to_string(),
+            },
+            RemoteInfo {
+                name: "fork".to_string(),
+                url: "https://example.com/me/app.git".to_string(),
+            },
+        ]
+    );
+    assert!(parse_remote_list("").is_empty());
+
+    let size = parse_count_objects(
+        "count: 3\nsize: 12\nin-pack: 40\npacks: 1\nsize-pack: 100\nprune-packable: 0\n\
+         garbage: 0\nsize-garbage: 0\n",
+    );
+    assert_eq!(size, 112 * 1024);
+}
+
+#[test]
+fn test_repo_config_summary() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+
+    let summary = get_repo_config_summary(&path, true).unwrap();
+    assert_eq!(summary.user_email.as_deref(), Some("test@example.com"));
+    assert!(summary.remotes.is_empty());
+    assert!(!summary.uses_lfs);
+    assert!(!summary.has_submodules);
+    assert!(summary.size_bytes > 0);
+    assert!(summary.warnings.iter().any(|w| w.contains("No remotes")));
+
+    // Cached until refreshed
+    run_git_command(
+        &["remote", "add", "origin", "https://example.com/app.git"],
+        &path,
+    )
+    .unwrap();
+    run_git_command(&["config", "core.hooksPath", "missing-hooks"], &path).unwrap();
+    std::fs::write(
+        repo.path().join(".gitattributes"),
+        "*.psd filter=lfs diff=lfs\n",
+    )
+    .unwrap();
+    assert!(get_repo_config_summary(&path, false)
+        .unwrap()
+        .remotes
+        .is_empty());
+
+    let summary = get_repo_config_summary(&path, true).unwrap();
+    assert_eq!(summary.remotes.len(), 1);
+    assert_eq!(summary.hooks_path.as_deref(), Some("missing-hooks"));
+    assert!(summary.uses_lfs);
+    assert!(summary
+        .warnings
+        .iter()
+        .any(|w| w.contains("core.hooksPath")));
+    assert!(!summary.warnings.iter().any(|w| w.contains("No remotes")));
+
+    assert!(get_repo_config_summary("/nonexistent/repo", false).is_err());
+}
@@ -0,0 +1,62 @@
+//! Tests for worktree dev server URLs.
+
+use std::path::Path;
+
+use crate::worktrees::dev_server::{
+    parse_listening_ports, parse_process_cwds, ports_in_worktree, resolve_dev_url, validate_dev_url,
+};
+
+#[test]
+fn test_validate_dev_url() {
+    assert_eq!(
+        validate_dev_url("  http://localhost:3000/app ").unwrap(),
+        "http://localhost:3000/app"
+    );
+    assert!(validate_dev_url("https://feature.dev.test").is_ok());
+
+    assert!(validate_dev_url("localhost:3000").is_err());
+    assert!(validate_dev_url("file:///etc/passwd").is_err());
+    assert!(validate_dev_url("http://").is_err());
+    assert!(validate_dev_url("http://:3000").is_err());
+    assert!(validate_dev_url("http://localhost:3000/a b").is_err());
+}
+
+#[test]
+fn test_ports_found_for_processes_inside_worktree() {
+    let listening = parse_listening_ports(
+        "p100\nn*:5173\nn[::1]:5173\np200\nn127.0.0.1:8080\np300\nn*:3000\nn*:3001\n",
+    );
+    assert_eq!(
+        listening,
+        [(100, 5173), (200, 8080), (300, 3000), (300, 3001)]
+    );
+
+    let cwds = parse_process_cwds(
+        "p100\nfcwd\nn/work/feature/web\np200\nfcwd\nn/work/other\np300\nfcwd\nn/work/feature\n",
+    );
+    assert_eq!(
+        ports_in_worktree(&listening, &cwds, Path::new("/work/feature")),
+        [3000, 3001, 5173]
+    );
+    // A sibling folder sharing the prefix is not inside the worktree
+    assert!(ports_in_worktree(&listening, &cwds, Path::new("/work/feat")).is_empty());
+
+    assert_eq!(
+        resolve_dev_url(None, &[5173, 3000], None).as_deref(),
+        Some("http://localhost:3000")
+    );
+    assert_eq!(
+        resolve_dev_url(None, &[5173, 3000], Some(5173)).as_deref(),
+        Some("http://localhost:5173")
+    );
+    // A reserved port nothing listens on isn't opened
+    assert_eq!(
+        resolve_dev_url(None, &[3000], Some(41000)).as_deref(),
+        Some("http://localhost:3000")
+    );
+    assert_eq!(
+        resolve_dev_url(Some("https://app.test"), &[3000], None).as_deref(),
+        Some("https://app.test")
+    );
+    assert_eq!(resolve_dev_url(None, &[], Some(41000)), None);
+}
@@ -0,0 +1,88 @@
+//! Tests for worktree diffs against a base ref.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::diff::{
+    get_changed_files, get_diff, parse_name_status_z, parse_numstat_z, untracked_file_patch,
+    ChangedFileStatus,
+};
+
+#[test]
+fn test_parse_diff_z_output() {
+    let statuses = parse_name_status_z("M\0src/a b.rs\0A\0new.rs\0D\0old.rs\0");
+    assert_eq!(
+        statuses,
+        [
+            ('M', "src/a b.rs".to_string()),
+            ('A', "new.rs".to_string()),
+            ('D', "old.rs".to_string()),
+        ]
+    );
+
+    let numstat = parse_numstat_z("3\t1\tsrc/a b.rs\0-\t-\timage.png\0");
+    assert_eq!(
+        numstat,
+        [
+            ("src/a b.rs".to_string(), Some(3), Some(1)),
+            ("image.png".to_string(), None, None),
+        ]
+    );
+}
+
+#[test]
+fn test_untracked_file_patch() {
+    assert_eq!(
+        untracked_file_patch("notes.txt", b"one\ntwo"),
+        "diff --git a/notes.txt b/notes.txt\nnew file mode 100644\n\
+         --- /dev/null\n+++ b/notes.txt\n@@ -0,0 +1,2 @@\n+one\n+two\n\
+         \\ No newline at end of file\n"
+    );
+    assert!(untracked_file_patch("blob.bin", b"\x00\x01").ends_with("differ\n"));
+}
+
+#[test]
+fn test_worktree_diff_against_base() {
+    let repo = TestRepo::new();
+    let base = repo.current_branch();
+    repo.create_branch("feature");
+    repo.checkout("feature");
+    repo.commit("feature work");
+    // Work on the base after the branch point is not part of the diff
+    repo.checkout(&base);
+    repo.commit("base work");
+    repo.checkout("feature");
+    std::fs::write(repo.path().join("test.txt"), "changed\n").unwrap();
+    std::fs::write(repo.path().join("scratch.txt"), "draft\n").unwrap();
+
+    let path = repo.path_str();
+    let committed = get_changed_files(&path, &base, false).unwrap();
+    let files: Vec<(&str, ChangedFileStatus)> = committed
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.status))
+        .collect();
+    assert_eq!(files, [("feature_work.txt", ChangedFileStatus::Added)]);
+
+    let all = get_changed_files(&path, &base, true).unwrap();
+    let files: Vec<(&str, ChangedFileStatus)> = all
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.status))
+        .collect();
+    assert_eq!(
+        files,
+        [
+            ("feature_work.txt", ChangedFileStatus::Added),
+            ("scratch.txt", ChangedFileStatus::Untracked),
+            ("test.txt", ChangedFileStatus::Modified),
+        ]
+    );
+
+    let diff = get_diff(&path, &base, &["test.txt".to_string()], true).unwrap();
+    assert!(diff.patch.contains("+changed"));
+    assert!(!diff.patch.contains("feature_work.txt"));
+    assert!(!diff.patch.contains("base_work.txt"));
+    assert!(!diff.truncated);
+
+    let diff = get_diff(&path, &base, &[], true).unwrap();
+    assert!(diff.patch.contains("+++ b/scratch.txt"));
+}
@@ -0,0 +1,72 @@
+//! Tests for the worktree file tree.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::files::{build_file_tree, list_worktree_files, validate_relative_dir};
+
+#[test]
+fn test_build_file_tree() {
+    let files = ["src/main.rs", "src/lib/mod.rs", "README.md", "docs/a.md"];
+
+    let (entries, truncated) = build_file_tree(&files, "", 1, 100);
+    assert!(!truncated);
+    let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["docs", "src", "README.md"]);
+    assert!(entries[1].is_dir);
+    assert_eq!(entries[1].children, None);
+    assert_eq!(entries[2].children, None);
+
+    let (entries, _) = build_file_tree(&files, "", 2, 100);
+    let src = entries[1].children.as_ref().unwrap();
+    assert_eq!(src[0].path, "src/lib");
+    assert!(src[0].is_dir);
+    assert_eq!(src[0].children, None);
+    assert_eq!(src[1].path, "src/main.rs");
+
+    let (entries, _) = build_file_tree(&["mod.rs"], "src/lib", 1, 100);
+    assert_eq!(entries[0].path, "src/lib/mod.rs");
+
+    let (entries, truncated) = build_file_tree(&files, "", 3, 3);
+    assert!(truncated);
+    assert_eq!(entries.len(), 1);
+}
+
+#[test]
+fn test_validate_relative_dir() {
+    assert!(validate_relative_dir("").is_ok());
+    assert!(validate_relative_dir("src/lib").is_ok());
+    assert!(validate_relative_dir("../other").is_err());
+    assert!(validate_relative_dir("src/../../x").is_err());
+    assert!(validate_relative_dir("/etc").is_err());
+}
+
+#[test]
+fn test_list_worktree_files_honors_gitignore() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    std::fs::write(repo.path().join(".gitignore"), "target/\n*.log\n").unwrap();
+    std::fs::create_dir_all(repo.path().join("target/debug")).unwrap();
+    std::fs::write(repo.path().join("target/debug/app"), "bin").unwrap();
+    std::fs::write(repo.path().join("debug.log"), "log").unwrap();
+    std::fs::create_dir_all(repo.path().join("src/nested")).unwrap();
+    std::fs::write(repo.path().join("src/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(repo.path().join("src/nested/deep.rs"), "").unwrap();
+
+    let tree = list_worktree_files(&path, None, None).unwrap();
+    let names: Vec<&str> = tree.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["src", ".gitignore", "test.txt"]);
+    assert!(!tree.truncated);
+
+    let tree = list_worktree_files(&path, Some("src"), Some(2)).unwrap();
+    assert_eq!(tree.dir, "src");
+    assert_eq!(tree.entries[0].path, "src/nested");
+    let nested = tree.entries[0].children.as_ref().unwrap();
+    assert_eq!(nested[0].path, "src/nested/deep.rs");
+
+    // Deleted tracked files are left out
+    std::fs::remove_file(repo.path().join("test.txt")).unwrap();
+    let tree = list_worktree_files(&path, None, None).unwrap();
+    assert!(!tree.entries.iter().any(|e| e.name == "test.txt"));
+
+    assert!(list_worktree_files(&path, Some("../"), None).is_err());
+    assert!(list_worktree_files(&path, Some("missing"), None).is_err());
+}
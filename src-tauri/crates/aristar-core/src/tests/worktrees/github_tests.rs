@@ -0,0 +1,95 @@
+//! Tests for branch protection rules from GitHub.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::github::{parse_branch_names, parse_github_remote, resolve_github_token};
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::sync::push_target_branch;
+use crate::worktrees::types::Repository;
+
+#[test]
+fn test_parse_github_remote() {
+    let expected = Some(("acme".to_string(), "web-app".to_string()));
+    assert_eq!(
+        parse_github_remote("git@github.com:acme/web-app.git\n"),
+        expected
+    );
+    assert_eq!(
+        parse_github_remote("https://github.com/acme/web-app"),
+        expected
+    );
+    assert_eq!(
+        parse_github_remote("https://x-access-token@github.com/acme/web-app.git/"),
+        expected
+    );
+    assert_eq!(
+        parse_github_remote("ssh://git@github.com/acme/web-app.git"),
+        expected
+    );
+
+    assert_eq!(parse_github_remote("git@gitlab.com:acme/web-app.git"), None);
+    assert_eq!(parse_github_remote("https://github.com/acme"), None);
+    assert_eq!(parse_github_remote("https://github.com/acme/a b"), None);
+    assert_eq!(
+        resolve_github_token(Some(" ghp_token \n")).as_deref(),
+        Some("ghp_token")
+    );
+}
+
+#[test]
+fn test_parse_branch_names() {
+    let body =
+        br#"[{"name": "main", "protected": true}, {"name": "release/1.x", "protected": true}]"#;
+    assert_eq!(parse_branch_names(body).unwrap(), ["main", "release/1.x"]);
+
+    let err = parse_branch_names(br#"{"message": "Bad credentials"}"#).unwrap_err();
+    assert!(err.contains("Bad credentials"));
+    assert!(parse_branch_names(b"<html>").is_err());
+}
+
+#[test]
+fn test_remote_protection_and_push_target() {
+    let repo = Repository {
+        id: "repo-1".to_string(),
+        path: "/repo".to_string(),
+        name: "repo".to_string(),
+        display_name: None,
+        worktrees: vec![],
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: None,
+        remote_default_branch: None,
+        protected_branches: vec![],
+        remote_protected_branches: vec!["release/1.x".to_string()],
+        remote_protection_checked_at: Some(1),
+        scan_settings: Default::default(),
+        labels: vec![],
+    };
+    assert!(repo.is_remote_protected_branch("release/1.x"));
+    assert!(repo.is_protected_branch("release/1.x"));
+    assert!(!repo.is_remote_protected_branch("main"));
+    assert!(repo.is_protected_branch("main"));
+
+    let local = TestRepo::new();
+    local.create_branch("feature");
+    local.checkout("feature");
+    assert_eq!(push_target_branch(&local.path_str()).unwrap(), "feature");
+
+    // An upstream with another name is what a push updates
+    local.create_branch("release/1.x");
+    run_git_command(
+        &["config", "branch.feature.remote", "origin"],
+        &local.path_str(),
+    )
+    .unwrap();
+    run_git_command(
+        &["config", "branch.feature.merge", "refs/heads/release/1.x"],
+        &local.path_str(),
+    )
+    .unwrap();
+    assert_eq!(
+        push_target_branch(&local.path_str()).unwrap(),
+        "release/1.x"
+    );
+}
@@ -0,0 +1,79 @@
+//! Tests for copying git hooks into new worktrees.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::hooks::{copy_hooks_into_worktree, relative_hooks_path};
+use crate::worktrees::operations::run_git_command;
+
+#[test]
+fn test_relative_hooks_path() {
+    let repo = TestRepo::new();
+    let set = |value: &str| {
+        run_git_command(&["config", "core.hooksPath", value], &repo.path_str()).unwrap();
+    };
+
+    assert_eq!(relative_hooks_path(&repo.path_str()), None);
+    set(".husky/_");
+    assert_eq!(
+        relative_hooks_path(&repo.path_str()).unwrap(),
+        std::path::PathBuf::from(".husky/_")
+    );
+    set("/usr/share/git-hooks");
+    assert_eq!(relative_hooks_path(&repo.path_str()), None);
+    set("../shared-hooks");
+    assert_eq!(relative_hooks_path(&repo.path_str()), None);
+}
+
+#[test]
+fn test_copy_hooks_into_worktree_is_excluded() {
+    let repo = TestRepo::new();
+    run_git_command(&["config", "core.hooksPath", "githooks"], &repo.path_str()).unwrap();
+    fs::create_dir(repo.path().join("githooks")).unwrap();
+    fs::write(
+        repo.path().join("githooks/pre-commit"),
+        "#!/bin/sh\nexit 0\n",
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(
+            repo.path().join("githooks/pre-commit"),
+            fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+    }
+
+    let parent = TempDir::new().unwrap();
+    let worktree = parent.path().join("agent").to_string_lossy().to_string();
+    run_git_command(
+        &["worktree", "add", "--detach", &worktree],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        copy_hooks_into_worktree(&repo.path_str(), &worktree).unwrap(),
+        1
+    );
+    assert_eq!(
+        copy_hooks_into_worktree(&repo.path_str(), &worktree).unwrap(),
+        0
+    );
+
+    let hook = std::path::Path::new(&worktree).join("githooks/pre-commit");
+    assert!(hook.exists());
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(
+            fs::metadata(&hook).unwrap().permissions().mode() & 0o111,
+            0o111
+        );
+    }
+    let status = run_git_command(&["status", "--porcelain"], &worktree).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
@@ -0,0 +1,793 @@
+//! Integration tests for worktree operations.
+
+use std::path::Path;
+
+use crate::core::ports;
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::activity::worktree_git_dir;
+use crate::worktrees::operations::*;
+use crate::worktrees::types::{CreateWorktreeOptions, RenameWorktreeError, WorktreeLockFile};
+
+fn existing_branch(branch: &str) -> CreateWorktreeOptions {
+    CreateWorktreeOptions {
+        branch: Some(branch.to_string()),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// list_worktrees tests
+// ============================================================================
+
+#[test]
+fn test_list_worktrees_single_main() {
+    let repo = TestRepo::new();
+    let worktrees = list_worktrees(&repo.path_str());
+    assert!(worktrees.is_ok());
+    let worktrees = worktrees.unwrap();
+
+    // Should have exactly one worktree (the main one)
+    assert_eq!(worktrees.len(), 1);
+
+    let main_worktree = &worktrees[0];
+    assert!(main_worktree.is_main);
+    assert_eq!(main_worktree.name, "main");
+}
+
+#[test]
+fn test_list_worktrees_has_branch() {
+    let repo = TestRepo::new();
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+
+    assert!(!worktrees.is_empty());
+    // Main worktree should have a branch
+    assert!(worktrees[0].branch.is_some());
+}
+
+#[test]
+fn test_list_worktrees_reports_git_activity() {
+    let repo = TestRepo::new();
+    create_worktree(
+        &repo.path_str(),
+        "active-worktree",
+        &CreateWorktreeOptions::default(),
+    )
+    .unwrap();
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let linked = worktrees.iter().find(|w| !w.is_main).unwrap();
+    let git_dir = worktree_git_dir(Path::new(&linked.path)).unwrap();
+    assert!(git_dir.ends_with("worktrees/active-worktree"));
+
+    // Both worktrees have git activity, no later than now
+    let now = chrono::Utc::now().timestamp_millis();
+    for worktree in &worktrees {
+        let last_active = worktree.last_active.expect("git activity");
+        assert!(last_active > 0 && last_active <= now + 1_000);
+    }
+}
+
+// ============================================================================
+// create_worktree tests
+// ============================================================================
+
+#[test]
+fn test_create_worktree_basic() {
+    let repo = TestRepo::new();
+    repo.create_branch("feature-test");
+
+    let result = create_worktree(
+        &repo.path_str(),
+        "test-worktree",
+        &existing_branch("feature-test"),
+    );
+
+    assert!(
+        result.is_ok(),
+        "Failed to create worktree: {:?}",
+        result.err()
+    );
+    let worktree = result.unwrap();
+    assert_eq!(worktree.name, "test-worktree");
+    assert!(!worktree.is_main);
+}
+
+#[test]
+fn test_create_worktree_from_base_branch() {
+    let repo = TestRepo::new();
+    let start = repo.current_branch();
+    repo.create_branch("release");
+    repo.checkout("release");
+    repo.commit("release fix");
+    let release_head = run_git_command(&["rev-parse", "HEAD"], &repo.path_str()).unwrap();
+    repo.checkout(&start);
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "from-release",
+        &CreateWorktreeOptions {
+            start_point: Some("release".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(worktree.branch.as_deref(), Some("from-release"));
+    assert_eq!(
+        worktree.commit.as_deref(),
+        Some(String::from_utf8_lossy(&release_head.stdout).trim())
+    );
+    let _ = remove_worktree(&worktree.path, true, true);
+}
+
+#[test]
+fn test_create_worktree_with_new_branch() {
+    let repo = TestRepo::new();
+
+    let result = create_worktree(
+        &repo.path_str(),
+        "new-branch-worktree",
+        &existing_branch("new-feature-branch"),
+    );
+
+    // This might fail if git worktree add doesn't auto-create branches
+    // The behavior depends on the branch existing
+    if result.is_ok() {
+        let worktree = result.unwrap();
+        assert_eq!(worktree.name, "new-branch-worktree");
+    }
+}
+
+#[test]
+fn test_create_worktree_new_branch_from_base_ref() {
+    let repo = TestRepo::new();
+    let main = repo.current_branch();
+    repo.create_branch("release");
+    repo.checkout("release");
+    repo.commit("release fix");
+    repo.checkout(&main);
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "hotfix",
+        &CreateWorktreeOptions {
+            new_branch: Some("hotfix/login".to_string()),
+            start_point: Some("release".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(worktree.branch.as_deref(), Some("hotfix/login"));
+    let release = run_git_command(&["rev-parse", "release"], &repo.path_str()).unwrap();
+    assert_eq!(
+        worktree.commit.as_deref(),
+        Some(String::from_utf8_lossy(&release.stdout).trim())
+    );
+}
+
+#[test]
+fn test_create_worktree_new_branch_errors() {
+    let repo = TestRepo::new();
+    repo.create_branch("taken");
+
+    let existing = create_worktree(
+        &repo.path_str(),
+        "taken-worktree",
+        &CreateWorktreeOptions {
+            new_branch: Some("taken".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(existing.unwrap_err().contains("already exists"));
+
+    let both = create_worktree(
+        &repo.path_str(),
+        "both-worktree",
+        &CreateWorktreeOptions {
+            branch: Some("taken".to_string()),
+            new_branch: Some("fresh".to_string()),
+            ..Default::default()
+        },
+    );
+    assert!(both.is_err());
+
+    let detached_branch = create_worktree(
+        &repo.path_str(),
+        "detached-worktree",
+        &CreateWorktreeOptions {
+            new_branch: Some("fresh".to_string()),
+            detach: true,
+            ..Default::default()
+        },
+    );
+    assert!(detached_branch.is_err());
+}
+
+#[test]
+fn test_create_worktree_detached() {
+    let repo = TestRepo::new();
+    let first = run_git_command(&["rev-parse", "HEAD"], &repo.path_str()).unwrap();
+    let first = String::from_utf8_lossy(&first.stdout).trim().to_string();
+    repo.commit("second");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "at-commit",
+        &CreateWorktreeOptions {
+            start_point: Some(first.clone()),
+            detach: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(worktree.is_detached);
+    assert_eq!(worktree.commit.as_deref(), Some(first.as_str()));
+    assert!(!local_branch_exists(&repo.path_str(), "at-commit"));
+}
+
+#[test]
+fn test_create_worktree_appears_in_list() {
+    let repo = TestRepo::new();
+    repo.create_branch("list-test");
+
+    let _ = create_worktree(
+        &repo.path_str(),
+        "listed-worktree",
+        &existing_branch("list-test"),
+    );
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    assert!(worktrees.len() >= 2);
+    assert!(worktrees.iter().any(|w| w.name == "listed-worktree"));
+}
+
+#[test]
+fn test_create_worktree_setup_script_is_ignored() {
+    let repo = TestRepo::new();
+    repo.create_branch("script-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "script-worktree",
+        &CreateWorktreeOptions {
+            branch: Some("script-test".to_string()),
+            startup_script: Some("echo hi".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(Path::new(&worktree.path).join(SETUP_SCRIPT_FILE).exists());
+    let status = run_git_command(&["status", "--porcelain"], &worktree.path).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
+
+#[test]
+fn test_create_worktree_releases_port_on_failure() {
+    let repo = TestRepo::new();
+    let with_port = |branch: &str| CreateWorktreeOptions {
+        branch: Some(branch.to_string()),
+        startup_script: Some("echo {{port}}".to_string()),
+        ..Default::default()
+    };
+    let reserved = |path: &str| ports::reserved_port(ports::DEV_SERVER_SERVICE, path).unwrap();
+
+    let failed = create_worktree(&repo.path_str(), "no-branch", &with_port("missing"));
+    assert!(failed.is_err());
+    let path = get_worktree_base_for_repo(&repo.path().canonicalize().unwrap().to_string_lossy())
+        .join("no-branch");
+    assert_eq!(reserved(&path.to_string_lossy()), None);
+
+    // A failed duplicate keeps the port of the worktree already there
+    repo.create_branch("ported");
+    let worktree = create_worktree(&repo.path_str(), "ported", &with_port("ported")).unwrap();
+    let port = reserved(&worktree.path).unwrap();
+    assert!(create_worktree(&repo.path_str(), "ported", &with_port("ported")).is_err());
+    assert_eq!(reserved(&worktree.path), Some(port));
+
+    let _ = remove_worktree(&worktree.path, true, false);
+    let _ = ports::release_ports(&worktree.path, None);
+}
+
+#[test]
+fn test_create_worktree_duplicate_name() {
+    let repo = TestRepo::new();
+    repo.create_branch("dup-test-1");
+    repo.create_branch("dup-test-2");
+
+    let _ = create_worktree(
+        &repo.path_str(),
+        "duplicate-worktree",
+        &existing_branch("dup-test-1"),
+    );
+
+    // Creating with same name should fail
+    let result = create_worktree(
+        &repo.path_str(),
+        "duplicate-worktree",
+        &existing_branch("dup-test-2"),
+    );
+
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// remove_worktree tests
+// ============================================================================
+
+#[test]
+fn test_remove_worktree_basic() {
+    let repo = TestRepo::new();
+    repo.create_branch("remove-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "to-remove",
+        &existing_branch("remove-test"),
+    )
+    .unwrap();
+
+    // Remove it
+    let result = remove_worktree(&worktree.path, false, false);
+    assert!(result.is_ok());
+
+    // Verify it's gone from the list
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    assert_eq!(worktrees.len(), 1); // Only main worktree remains
+}
+
+#[test]
+fn test_remove_worktree_force() {
+    let repo = TestRepo::new();
+    repo.create_branch("force-remove");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "force-worktree",
+        &existing_branch("force-remove"),
+    )
+    .unwrap();
+
+    // Add uncommitted changes to make it "dirty"
+    let dirty_file = std::path::Path::new(&worktree.path).join("dirty.txt");
+    std::fs::write(&dirty_file, "uncommitted changes").unwrap();
+
+    // Force remove should work even with uncommitted changes
+    let result = remove_worktree(&worktree.path, true, false);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_remove_worktree_nonexistent() {
+    let repo = TestRepo::new();
+    let result = remove_worktree(&format!("{}/nonexistent", repo.path_str()), false, false);
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// rename_worktree tests
+// ============================================================================
+
+#[test]
+fn test_rename_worktree_basic() {
+    let repo = TestRepo::new();
+    repo.create_branch("rename-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "original-name",
+        &existing_branch("rename-test"),
+    )
+    .unwrap();
+
+    let result = rename_worktree(&worktree.path, "new-name", false);
+    assert!(result.is_ok());
+
+    let renamed = result.unwrap();
+    assert_eq!(renamed.name, "new-name");
+}
+
+#[test]
+fn test_rename_worktree_updates_list() {
+    let repo = TestRepo::new();
+    repo.create_branch("rename-list-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "rename-me",
+        &existing_branch("rename-list-test"),
+    )
+    .unwrap();
+
+    let _ = rename_worktree(&worktree.path, "renamed", false);
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    assert!(worktrees.iter().any(|w| w.name == "renamed"));
+    assert!(!worktrees.iter().any(|w| w.name == "rename-me"));
+}
+
+#[test]
+fn test_rename_worktree_target_exists() {
+    let repo = TestRepo::new();
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "rename-source",
+        &CreateWorktreeOptions::default(),
+    )
+    .unwrap();
+    let occupied = std::path::Path::new(&worktree.path)
+        .parent()
+        .unwrap()
+        .join("rename-occupied");
+    std::fs::create_dir_all(&occupied).unwrap();
+
+    let err = rename_worktree(&worktree.path, "rename-occupied", false).unwrap_err();
+    assert!(
+        matches!(err, RenameWorktreeError::TargetExists { ref path } if path.ends_with("rename-occupied")),
+        "{:?}",
+        err
+    );
+    // Nothing moved
+    assert!(std::path::Path::new(&worktree.path).exists());
+
+    let _ = std::fs::remove_dir_all(&occupied);
+    let _ = remove_worktree(&worktree.path, true, false);
+}
+
+#[test]
+fn test_rename_worktree_invalid_name() {
+    let repo = TestRepo::new();
+    for name in ["", "..", "a/b", "-x"] {
+        let err = rename_worktree(&repo.path_str(), name, false).unwrap_err();
+        assert!(
+            matches!(err, RenameWorktreeError::InvalidName { .. }),
+            "{}: {:?}",
+            name,
+            err
+        );
+    }
+}
+
+// ============================================================================
+// move_worktree tests
+// ============================================================================
+
+#[test]
+fn test_move_worktree_to_other_directory() {
+    let repo = TestRepo::new();
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "move-source",
+        &CreateWorktreeOptions::default(),
+    )
+    .unwrap();
+    let elsewhere = std::path::Path::new(&worktree.path)
+        .parent()
+        .unwrap()
+        .join("moved-elsewhere");
+    let target = elsewhere.join("move-target");
+
+    let moved = move_worktree(&worktree.path, &target.to_string_lossy(), false).unwrap();
+    assert_eq!(moved.name, "move-target");
+    assert!(target.is_dir());
+    assert!(!std::path::Path::new(&worktree.path).exists());
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    assert!(worktrees.iter().any(|w| w.path == moved.path));
+
+    let _ = remove_worktree(&moved.path, true, false);
+    let _ = std::fs::remove_dir_all(&elsewhere);
+}
+
+#[test]
+fn test_move_worktree_refuses_outside_allowed_bases() {
+    let repo = TestRepo::new();
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "move-outside",
+        &CreateWorktreeOptions::default(),
+    )
+    .unwrap();
+    let err = move_worktree(&worktree.path, "/aristar-move-escaped", false).unwrap_err();
+    assert!(err.to_string().contains("not within allowed"), "{:?}", err);
+    assert!(std::path::Path::new(&worktree.path).exists());
+
+    let err = move_worktree(&worktree.path, "relative/path", false).unwrap_err();
+    assert!(err.to_string().contains("absolute"), "{:?}", err);
+
+    let _ = remove_worktree(&worktree.path, true, false);
+}
+
+#[test]
+fn test_is_transient_move_error() {
+    assert!(is_transient_move_error(
+        "fatal: failed to move '/a' to '/b': Device or resource busy"
+    ));
+    assert!(is_transient_move_error(
+        "The process cannot access the file because it is being used by another process."
+    ));
+    assert!(!is_transient_move_error("fatal: '/b' already exists"));
+}
+
+// ============================================================================
+// duplicate_worktree tests
+// ============================================================================
+
+#[test]
+fn test_duplicate_worktree_copies_uncommitted_changes() {
+    let repo = TestRepo::new();
+    repo.commit("tracked file");
+    let source = repo.path();
+    std::fs::write(source.join("tracked_file.txt"), "edited").unwrap();
+    std::fs::create_dir_all(source.join("notes")).unwrap();
+    std::fs::write(source.join("notes/idea.md"), "untracked").unwrap();
+
+    let duplicate = duplicate_worktree(&repo.path_str(), "forked").unwrap();
+    let target = std::path::Path::new(&duplicate.path);
+
+    let head = run_git_command(&["rev-parse", "HEAD"], &repo.path_str()).unwrap();
+    assert_eq!(
+        duplicate.commit.as_deref(),
+        Some(String::from_utf8_lossy(&head.stdout).trim())
+    );
+    assert_eq!(
+        std::fs::read_to_string(target.join("tracked_file.txt")).unwrap(),
+        "edited"
+    );
+    assert_eq!(
+        std::fs::read_to_string(target.join("notes/idea.md")).unwrap(),
+        "untracked"
+    );
+    // The source keeps its changes
+    assert_eq!(
+        std::fs::read_to_string(source.join("tracked_file.txt")).unwrap(),
+        "edited"
+    );
+    let _ = remove_worktree(&duplicate.path, true, false);
+}
+
+#[test]
+fn test_duplicate_worktree_clean_source() {
+    let repo = TestRepo::new();
+    let duplicate = duplicate_worktree(&repo.path_str(), "clean-copy").unwrap();
+
+    let status = get_worktree_status(&duplicate.path).unwrap();
+    assert!(!status.is_dirty, "{:?}", status);
+    let _ = remove_worktree(&duplicate.path, true, false);
+}
+
+// ============================================================================
+// checkout_in_worktree tests
+// ============================================================================
+
+#[test]
+fn test_checkout_in_worktree_switches_branch_and_detaches() {
+    let repo = TestRepo::new();
+    repo.create_branch("other");
+
+    let switched = checkout_in_worktree(&repo.path_str(), "other").unwrap();
+    assert_eq!(switched.branch.as_deref(), Some("other"));
+    assert!(!switched.is_detached);
+    assert_eq!(repo.current_branch(), "other");
+
+    let detached = checkout_in_worktree(&repo.path_str(), "HEAD~0").unwrap();
+    assert!(detached.is_detached);
+    assert_eq!(detached.commit, switched.commit);
+}
+
+#[test]
+fn test_checkout_in_worktree_rejects_dirty_and_unknown_refs() {
+    let repo = TestRepo::new();
+    repo.create_branch("other");
+
+    assert!(checkout_in_worktree(&repo.path_str(), "no-such-ref").is_err());
+    assert!(checkout_in_worktree(&repo.path_str(), "--orphan").is_err());
+
+    std::fs::write(repo.path().join("test.txt"), "edited").unwrap();
+    let err = checkout_in_worktree(&repo.path_str(), "other").unwrap_err();
+    assert!(err.contains("uncommitted changes"), "{}", err);
+    assert_ne!(repo.current_branch(), "other");
+}
+
+// ============================================================================
+// lock_worktree tests
+// ============================================================================
+
+#[test]
+fn test_lock_worktree_basic() {
+    let repo = TestRepo::new();
+    repo.create_branch("lock-test");
+
+    let worktree =
+        create_worktree(&repo.path_str(), "lockable", &existing_branch("lock-test")).unwrap();
+
+    let result = lock_worktree(&worktree.path, None, None);
+    assert!(result.is_ok());
+
+    // Verify it's locked
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let locked = worktrees.iter().find(|w| w.name == "lockable").unwrap();
+    assert!(locked.is_locked);
+}
+
+#[test]
+fn test_lock_worktree_with_reason() {
+    let repo = TestRepo::new();
+    repo.create_branch("lock-reason-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "lockable-reason",
+        &existing_branch("lock-reason-test"),
+    )
+    .unwrap();
+
+    let result = lock_worktree(&worktree.path, Some("important work"), None);
+    assert!(result.is_ok());
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let locked = worktrees
+        .iter()
+        .find(|w| w.name == "lockable-reason")
+        .unwrap();
+    assert!(locked.is_locked);
+    assert!(locked.lock_reason.is_some());
+}
+
+#[test]
+fn test_unlock_worktree() {
+    let repo = TestRepo::new();
+    repo.create_branch("unlock-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "unlockable",
+        &existing_branch("unlock-test"),
+    )
+    .unwrap();
+
+    lock_worktree(&worktree.path, None, None).unwrap();
+    let result = unlock_worktree(&worktree.path);
+    assert!(result.is_ok());
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let unlocked = worktrees.iter().find(|w| w.name == "unlockable").unwrap();
+    assert!(!unlocked.is_locked);
+}
+
+#[test]
+fn test_lock_prevents_removal() {
+    let repo = TestRepo::new();
+    repo.create_branch("lock-prevent-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "locked-worktree",
+        &existing_branch("lock-prevent-test"),
+    )
+    .unwrap();
+
+    lock_worktree(&worktree.path, None, None).unwrap();
+
+    // Try to remove without force - should fail
+    let result = remove_worktree(&worktree.path, false, false);
+    assert!(
+        result.is_err(),
+        "Expected error when removing locked worktree without force"
+    );
+
+    // Force remove should work
+    let result = remove_worktree(&worktree.path, true, false);
+    assert!(
+        result.is_ok(),
+        "Force remove failed with: {:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn test_lock_file_written_and_removed() {
+    let repo = TestRepo::new();
+    repo.create_branch("lock-file-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "lock-file",
+        &existing_branch("lock-file-test"),
+    )
+    .unwrap();
+
+    lock_worktree(&worktree.path, Some("ci running"), None).unwrap();
+    let lock_path = std::path::Path::new(&worktree.path).join(LOCK_FILE);
+    let lock: WorktreeLockFile =
+        serde_json::from_str(&std::fs::read_to_string(&lock_path).unwrap()).unwrap();
+    assert!(lock.locked);
+    assert_eq!(lock.reason.as_deref(), Some("ci running"));
+    assert_eq!(lock.locked_by, "aristar-worktrees");
+
+    // Excluded from git, so the worktree stays clean
+    let status = run_git_command(&["status", "--porcelain"], &worktree.path).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+
+    unlock_worktree(&worktree.path).unwrap();
+    assert!(!lock_path.exists());
+    assert!(!std::path::Path::new(&worktree.path)
+        .join(".aristar")
+        .exists());
+}
+
+#[test]
+fn test_wait_for_unlock() {
+    let repo = TestRepo::new();
+    repo.create_branch("wait-unlock-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "wait-unlock",
+        &existing_branch("wait-unlock-test"),
+    )
+    .unwrap();
+
+    let timeout = std::time::Duration::from_millis(300);
+    assert!(wait_for_unlock(&worktree.path, timeout).unwrap());
+
+    lock_worktree(&worktree.path, None, None).unwrap();
+    assert!(is_worktree_locked(&worktree.path).unwrap());
+    assert!(!wait_for_unlock(&worktree.path, timeout).unwrap());
+
+    // An external unlock (e.g. from a script) is picked up
+    let path = worktree.path.clone();
+    let waiter =
+        std::thread::spawn(move || wait_for_unlock(&path, std::time::Duration::from_secs(10)));
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    run_git_command(&["worktree", "unlock", &worktree.path], &repo.path_str()).unwrap();
+    assert!(waiter.join().unwrap().unwrap());
+}
+
+#[test]
+fn test_lock_worktree_with_expiry() {
+    let repo = TestRepo::new();
+    repo.create_branch("lock-expiry-test");
+
+    let worktree = create_worktree(
+        &repo.path_str(),
+        "lock-expiry",
+        &existing_branch("lock-expiry-test"),
+    )
+    .unwrap();
+
+    let now = chrono::Utc::now().timestamp_millis();
+    assert!(lock_worktree(&worktree.path, None, Some(now - 1)).is_err());
+    assert!(!is_worktree_locked(&worktree.path).unwrap());
+
+    let expires_at = now + 60 * 60 * 1000;
+    lock_worktree(&worktree.path, Some("afternoon"), Some(expires_at)).unwrap();
+    let lock_path = std::path::Path::new(&worktree.path).join(LOCK_FILE);
+    let lock: WorktreeLockFile =
+        serde_json::from_str(&std::fs::read_to_string(&lock_path).unwrap()).unwrap();
+    assert_eq!(lock.expires_at, Some(expires_at));
+}
+
+// ============================================================================
+// Edge cases and error handling
+// ============================================================================
+
+#[test]
+fn test_worktree_has_unique_id() {
+    let repo = TestRepo::new();
+    repo.create_branch("id-test-1");
+    repo.create_branch("id-test-2");
+
+    let _ = create_worktree(&repo.path_str(), "wt1", &existing_branch("id-test-1"));
+    let _ = create_worktree(&repo.path_str(), "wt2", &existing_branch("id-test-2"));
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let ids: Vec<&String> = worktrees.iter().map(|w| &w.id).collect();
+
+    // All IDs should be unique
+    let mut unique_ids = ids.clone();
+    unique_ids.sort();
+    unique_ids.dedup();
+    assert_eq!(ids.len(), unique_ids.len());
+}
@@ -0,0 +1,69 @@
+//! Tests for SSH host key handling of remote operations.
+
+use crate::worktrees::known_hosts::{
+    is_changed_host_key_error, is_unknown_host_error, known_hosts_name, parse_keyscan_output,
+    parse_ssh_remote, ssh_command,
+};
+use crate::worktrees::sync::{describe_sync_error, SyncOperation};
+
+#[test]
+fn test_parse_ssh_remote() {
+    assert_eq!(
+        parse_ssh_remote("git@github.com:acme/app.git"),
+        Some(("github.com".to_string(), None))
+    );
+    assert_eq!(
+        parse_ssh_remote("ssh://git@git.example.com:2222/acme/app.git"),
+        Some(("git.example.com".to_string(), Some(2222)))
+    );
+    assert_eq!(
+        parse_ssh_remote("ssh://example.com:22/app"),
+        Some(("example.com".to_string(), None))
+    );
+    assert_eq!(parse_ssh_remote("https://github.com/acme/app.git"), None);
+    assert_eq!(parse_ssh_remote("/srv/git/app.git"), None);
+    assert_eq!(parse_ssh_remote("./relative:path"), None);
+    assert_eq!(parse_ssh_remote("-oProxyCommand=x:app"), None);
+    assert_eq!(parse_ssh_remote("ssh://host:notaport/app"), None);
+}
+
+#[test]
+fn test_host_key_errors() {
+    let unknown = "No ED25519 host key is known for example.com and you have requested strict checking.\nHost key verification failed.\nfatal: Could not read from remote repository.";
+    let changed = "@@@@@@@\n@    WARNING: REMOTE HOST IDENTIFICATION HAS CHANGED!     @\nHost key verification failed.";
+
+    assert!(is_unknown_host_error(unknown));
+    assert!(!is_changed_host_key_error(unknown));
+    assert!(is_changed_host_key_error(changed));
+    assert!(!is_unknown_host_error(changed));
+    assert!(!is_unknown_host_error("Permission denied (publickey)."));
+
+    let message = describe_sync_error(SyncOperation::Fetch, unknown);
+    assert!(message.contains("host key is not known"), "{}", message);
+    let message = describe_sync_error(SyncOperation::Push, changed);
+    assert!(message.contains("host key changed"), "{}", message);
+}
+
+#[test]
+fn test_parse_keyscan_output_and_names() {
+    let output = "# example.com:22 SSH-2.0-OpenSSH_9.6\nexample.com ssh-ed25519 AAAAC3Nza\n[example.com]:2222 ecdsa-sha2-nistp256 AAAAE2Vj\n";
+    assert_eq!(
+        parse_keyscan_output(output),
+        vec![
+            ("ssh-ed25519".to_string(), "AAAAC3Nza".to_string()),
+            ("ecdsa-sha2-nistp256".to_string(), "AAAAE2Vj".to_string()),
+        ]
+    );
+
+    assert_eq!(known_hosts_name("example.com", None), "example.com");
+    assert_eq!(
+        known_hosts_name("example.com", Some(2222)),
+        "[example.com]:2222"
+    );
+
+    let command = ssh_command("/home/o'neil/.aristar-worktrees/known_hosts");
+    assert!(command.starts_with("ssh -o BatchMode=yes -o 'GlobalKnownHostsFile="));
+    assert!(!command.contains("StrictHostKeyChecking"), "{}", command);
+    assert!(!command.contains("UserKnownHostsFile"), "{}", command);
+    assert!(command.contains("o'\\''neil"), "{}", command);
+}
@@ -0,0 +1,119 @@
+//! Tests for worktree and repository labels.
+
+use crate::tests::helpers::{test_repository, test_worktree};
+use crate::worktrees::labels::{
+    add_labels, collect_labels, delete_label, filter_by_labels, normalize_label, remove_labels,
+    rename_label, MAX_LABELS,
+};
+use crate::worktrees::types::{Repository, WorktreeInfo};
+
+fn worktree(path: &str, labels: &[&str]) -> WorktreeInfo {
+    WorktreeInfo {
+        labels: strings(labels),
+        ..test_worktree(path, "main")
+    }
+}
+
+fn repository(id: &str, labels: &[&str], worktrees: Vec<WorktreeInfo>) -> Repository {
+    Repository {
+        labels: strings(labels),
+        ..test_repository(&format!("/repos/{}", id), worktrees)
+    }
+}
+
+fn strings(labels: &[&str]) -> Vec<String> {
+    labels.iter().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn test_normalize_label() {
+    assert_eq!(normalize_label("  WIP ").unwrap(), "wip");
+    assert_eq!(normalize_label("team/ui:v2").unwrap(), "team/ui:v2");
+    assert!(normalize_label("").is_err());
+    assert!(normalize_label("two words").is_err());
+    assert!(normalize_label("-flag").is_err());
+    assert!(normalize_label(&"x".repeat(33)).is_err());
+}
+
+#[test]
+fn test_add_and_remove_labels() {
+    let mut labels = strings(&["review"]);
+    add_labels(&mut labels, &strings(&["WIP", "review", "agent"])).unwrap();
+    assert_eq!(labels, strings(&["agent", "review", "wip"]));
+
+    // An invalid label changes nothing
+    assert!(add_labels(&mut labels, &strings(&["ok", "not ok"])).is_err());
+    assert_eq!(labels.len(), 3);
+
+    let many: Vec<String> = (0..MAX_LABELS).map(|i| format!("l{}", i)).collect();
+    assert!(add_labels(&mut labels, &many).is_err());
+
+    assert!(remove_labels(&mut labels, &strings(&["Review", "missing"])));
+    assert_eq!(labels, strings(&["agent", "wip"]));
+    assert!(!remove_labels(&mut labels, &strings(&["missing"])));
+}
+
+#[test]
+fn test_collect_rename_and_delete_labels() {
+    let mut repositories = vec![
+        repository(
+            "web",
+            &["frontend"],
+            vec![worktree("/wt/web/login", &["review", "wip"])],
+        ),
+        repository("api", &[], vec![worktree("/wt/api/fix", &["wip"])]),
+    ];
+
+    let usage = collect_labels(&repositories);
+    let names: Vec<&str> = usage.iter().map(|u| u.label.as_str()).collect();
+    assert_eq!(names, vec!["frontend", "review", "wip"]);
+    assert_eq!(usage[0].repository_ids, strings(&["web"]));
+    assert_eq!(
+        usage[2].worktree_paths,
+        strings(&["/wt/web/login", "/wt/api/fix"])
+    );
+
+    // Renaming onto a label the worktree already has merges them
+    assert_eq!(rename_label(&mut repositories, "wip", "review").unwrap(), 2);
+    assert_eq!(repositories[0].worktrees[0].labels, strings(&["review"]));
+    assert_eq!(repositories[1].worktrees[0].labels, strings(&["review"]));
+
+    assert_eq!(delete_label(&mut repositories, "REVIEW"), 2);
+    assert!(collect_labels(&repositories)
+        .iter()
+        .all(|u| u.label == "frontend"));
+}
+
+#[test]
+fn test_filter_by_labels() {
+    let repositories = vec![
+        repository(
+            "web",
+            &["frontend"],
+            vec![worktree("/wt/web/a", &[]), worktree("/wt/web/b", &[])],
+        ),
+        repository(
+            "api",
+            &[],
+            vec![
+                worktree("/wt/api/a", &["wip", "agent"]),
+                worktree("/wt/api/b", &["wip"]),
+            ],
+        ),
+    ];
+
+    // A matching repository comes with all its worktrees
+    let found = filter_by_labels(&repositories, &strings(&["Frontend"]), false).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].worktrees.len(), 2);
+
+    let found = filter_by_labels(&repositories, &strings(&["wip"]), false).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].worktrees.len(), 2);
+
+    let found = filter_by_labels(&repositories, &strings(&["wip", "agent"]), true).unwrap();
+    let paths: Vec<&str> = found[0].worktrees.iter().map(|w| w.path.as_str()).collect();
+    assert_eq!(paths, vec!["/wt/api/a"]);
+
+    assert_eq!(filter_by_labels(&repositories, &[], true).unwrap().len(), 2);
+}
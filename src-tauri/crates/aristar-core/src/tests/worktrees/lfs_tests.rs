@@ -0,0 +1,39 @@
+//! Tests for Git LFS setup in new worktrees.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::lfs::{parse_lfs_progress, uses_lfs};
+
+#[test]
+fn test_parse_lfs_progress() {
+    assert_eq!(
+        parse_lfs_progress("Downloading LFS objects:  50% (1/2), 1.2 MB | 1.0 MB/s"),
+        Some((50, 1, 2))
+    );
+    assert_eq!(
+        parse_lfs_progress("Downloading LFS objects: 100% (12/12), 40 MB | 8.1 MB/s, done."),
+        Some((100, 12, 12))
+    );
+    assert_eq!(
+        parse_lfs_progress("Fetching reference refs/heads/main"),
+        None
+    );
+    assert_eq!(parse_lfs_progress(""), None);
+}
+
+#[test]
+fn test_uses_lfs_detection() {
+    let repo = TestRepo::new();
+    assert!(!uses_lfs(&repo.path_str()));
+
+    std::fs::write(
+        repo.path().join(".gitattributes"),
+        "*.psd filter=lfs diff=lfs merge=lfs -text\n",
+    )
+    .unwrap();
+    assert!(uses_lfs(&repo.path_str()));
+
+    // LFS objects in the git dir count even without attributes in this checkout
+    let other = TestRepo::new();
+    std::fs::create_dir_all(other.path().join(".git/lfs/objects")).unwrap();
+    assert!(uses_lfs(&other.path_str()));
+}
@@ -0,0 +1,151 @@
+//! Tests for the worktree list cache.
+
+use std::process::Command;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::list_cache::{git_dir_stamp, resolve_common_git_dir};
+use crate::worktrees::operations::list_worktrees;
+
+fn git(args: &[&str], dir: &std::path::Path) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[test]
+fn test_resolve_common_git_dir_from_linked_worktree() {
+    let repo = TestRepo::new();
+    let linked = repo.path().join("linked");
+    git(
+        &["worktree", "add", "-q", "--detach", "linked"],
+        repo.path(),
+    );
+
+    let main_git = resolve_common_git_dir(repo.path()).unwrap();
+    let from_linked = resolve_common_git_dir(&linked).unwrap();
+    assert_eq!(
+        main_git.canonicalize().unwrap(),
+        from_linked.canonicalize().unwrap()
+    );
+    assert!(resolve_common_git_dir(&repo.path().join("missing")).is_none());
+}
+
+#[test]
+fn test_git_dir_stamp_changes_with_worktrees() {
+    let repo = TestRepo::new();
+    let before = git_dir_stamp(repo.path()).unwrap();
+    assert_eq!(git_dir_stamp(repo.path()).unwrap(), before);
+
+    // Changes made outside the app are picked up without explicit invalidation
+    git(
+        &["worktree", "add", "-q", "--detach", "linked"],
+        repo.path(),
+    );
+    assert_ne!(git_dir_stamp(repo.path()).unwrap(), before);
+}
+
+#[test]
+fn test_list_worktrees_cache_sees_external_changes() {
+    let repo = TestRepo::new();
+    let repo_path = repo.path_str();
+
+    let first = list_worktrees(&repo_path).unwrap();
+    let second = list_worktrees(&repo_path).unwrap();
+    assert_eq!(first.len(), 1);
+    // Served from the cache: same ids
+    assert_eq!(first[0].id, second[0].id);
+
+    git(
+        &["worktree", "add", "-q", "-b", "feature", "linked"],
+        repo.path(),
+    );
+    let listed = list_worktrees(&repo_path).unwrap();
+    assert_eq!(listed.len(), 2);
+
+    git(&["worktree", "lock", "linked"], repo.path());
+    let listed = list_worktrees(&repo_path).unwrap();
+    assert!(listed.iter().any(|w| w.is_locked));
+
+    repo.commit("after cache");
+    let listed = list_worktrees(&repo_path).unwrap();
+    let head = String::from_utf8(
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo.path())
+            .output()
+            .unwrap()
+            .stdout,
+    )
+    .unwrap();
+    let main = listed.iter().find(|w| w.is_main).unwrap();
+    assert_eq!(main.commit.as_deref(), Some(head.trim()));
+}
+
+#[test]
+fn test_list_worktrees_cache_sees_commits_in_bare_repository() {
+    let repo = TestRepo::new();
+    let temp = tempfile::TempDir::new().unwrap();
+    let bare = temp.path().join("repo.git");
+    git(
+        &[
+            "clone",
+            "-q",
+            "--bare",
+            &repo.path_str(),
+            bare.to_str().unwrap(),
+        ],
+        temp.path(),
+    );
+    // Bare repositories keep no reflog, so only the branch ref moves on commit
+    git(&["config", "core.logAllRefUpdates", "false"], &bare);
+    let linked = temp.path().join("linked");
+    git(
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature",
+            linked.to_str().unwrap(),
+        ],
+        &bare,
+    );
+
+    assert_eq!(resolve_common_git_dir(&bare), Some(bare.clone()));
+    // Listed from the repository and from the worktree, both cached
+    let paths = [bare, linked.clone()].map(|p| p.to_string_lossy().to_string());
+    for path in &paths {
+        let before = list_worktrees(path).unwrap();
+        assert_eq!(list_worktrees(path).unwrap()[0].id, before[0].id);
+    }
+
+    git(
+        &[
+            "-c",
+            "user.name=Test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "in worktree",
+        ],
+        &linked,
+    );
+    let head = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(&linked)
+        .output()
+        .unwrap()
+        .stdout;
+    let head = String::from_utf8_lossy(&head).trim().to_string();
+    for path in &paths {
+        let listed = list_worktrees(path).unwrap();
+        let worktree = listed.iter().find(|w| w.path.ends_with("linked")).unwrap();
+        assert_eq!(worktree.commit.as_deref(), Some(head.as_str()), "{}", path);
+    }
+}
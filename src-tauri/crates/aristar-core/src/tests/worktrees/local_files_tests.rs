@@ -0,0 +1,66 @@
+//! Tests for copying untracked config files into new worktrees.
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::local_files::{copy_untracked_files, pattern_pathspec};
+use crate::worktrees::operations::run_git_command;
+
+#[test]
+fn test_pattern_pathspec() {
+    assert_eq!(pattern_pathspec(".env*").unwrap(), ":(glob)**/.env*");
+    assert_eq!(
+        pattern_pathspec("./config/local.*").unwrap(),
+        ":(glob)config/local.*"
+    );
+    assert_eq!(
+        pattern_pathspec("apps/**/.env").unwrap(),
+        ":(glob)apps/**/.env"
+    );
+
+    assert_eq!(pattern_pathspec(""), None);
+    assert_eq!(pattern_pathspec("/etc/passwd"), None);
+    assert_eq!(pattern_pathspec("../secrets/*"), None);
+    assert_eq!(pattern_pathspec(":(top)*"), None);
+}
+
+#[test]
+fn test_copy_untracked_files_into_worktree() {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join(".gitignore"), ".env\n").unwrap();
+    repo.commit("Ignore .env");
+    fs::write(repo.path().join(".env"), "API_KEY=1\n").unwrap();
+    fs::create_dir_all(repo.path().join("apps/web")).unwrap();
+    fs::write(repo.path().join("apps/web/.env.local"), "PORT=3000\n").unwrap();
+    fs::create_dir(repo.path().join("config")).unwrap();
+    fs::write(repo.path().join("config/local.json"), "{}\n").unwrap();
+    fs::write(repo.path().join("notes.txt"), "not copied\n").unwrap();
+
+    let parent = TempDir::new().unwrap();
+    let worktree = parent.path().join("agent").to_string_lossy().to_string();
+    run_git_command(
+        &["worktree", "add", "--detach", &worktree],
+        &repo.path_str(),
+    )
+    .unwrap();
+    fs::write(Path::new(&worktree).join(".env"), "API_KEY=2\n").unwrap();
+
+    let patterns = vec![".env*".to_string(), "config/local.*".to_string()];
+    let mut copied = copy_untracked_files(&repo.path_str(), &worktree, &patterns).unwrap();
+    copied.sort();
+    assert_eq!(copied, ["apps/web/.env.local", "config/local.json"]);
+
+    let worktree = Path::new(&worktree);
+    // Existing files are kept
+    assert_eq!(
+        fs::read_to_string(worktree.join(".env")).unwrap(),
+        "API_KEY=2\n"
+    );
+    assert!(!worktree.join("notes.txt").exists());
+    // Copies don't show up as untracked
+    let status = run_git_command(&["status", "--porcelain"], &worktree.to_string_lossy()).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
@@ -0,0 +1,65 @@
+//! Tests for expiring worktree locks.
+
+use crate::tests::helpers::{test_repository, test_worktree};
+use crate::worktrees::lock_expiry::find_expired_locks;
+use crate::worktrees::types::{Repository, WorktreeInfo};
+
+const NOW: i64 = 1_760_000_000_000;
+
+fn worktree(path: &str, lock_expires_at: Option<i64>) -> WorktreeInfo {
+    WorktreeInfo {
+        is_locked: true,
+        lock_reason: Some("afternoon".to_string()),
+        lock_expires_at,
+        ..test_worktree(path, "main")
+    }
+}
+
+fn repository(id: &str, worktrees: Vec<WorktreeInfo>) -> Repository {
+    test_repository(&format!("/repos/{}", id), worktrees)
+}
+
+#[test]
+fn test_find_expired_locks() {
+    let mut unlocked = worktree("/wt/unlocked", Some(NOW - 1));
+    unlocked.is_locked = false;
+    let mut offline = repository("offline", vec![worktree("/wt/offline", Some(NOW - 1))]);
+    offline.unavailable_since = Some(NOW);
+    let repositories = vec![
+        repository(
+            "app",
+            vec![
+                worktree("/wt/expired", Some(NOW - 1)),
+                worktree("/wt/due", Some(NOW)),
+                worktree("/wt/later", Some(NOW + 1)),
+                worktree("/wt/forever", None),
+                unlocked,
+            ],
+        ),
+        offline,
+    ];
+
+    let expired = find_expired_locks(&repositories, NOW);
+    let paths: Vec<&str> = expired.iter().map(|e| e.path.as_str()).collect();
+    assert_eq!(paths, ["/wt/expired", "/wt/due"]);
+    assert_eq!(expired[0].repository_id, "app");
+    assert_eq!(expired[0].reason.as_deref(), Some("afternoon"));
+    assert_eq!(expired[0].expires_at, NOW - 1);
+}
+
+#[test]
+fn test_replace_worktrees_keeps_expiry_of_held_locks() {
+    let mut repo = repository(
+        "app",
+        vec![
+            worktree("/wt/held", Some(NOW)),
+            worktree("/wt/lifted", Some(NOW)),
+        ],
+    );
+
+    let mut lifted = worktree("/wt/lifted", None);
+    lifted.is_locked = false;
+    repo.replace_worktrees(vec![worktree("/wt/held", None), lifted]);
+    assert_eq!(repo.worktrees[0].lock_expires_at, Some(NOW));
+    assert_eq!(repo.worktrees[1].lock_expires_at, None);
+}
@@ -0,0 +1,32 @@
+//! Worktree tests.
+
+mod aliases_tests;
+mod archive_tests;
+mod availability_tests;
+mod branch_cleanup_tests;
+mod config_tests;
+mod dev_server_tests;
+mod diff_tests;
+mod files_tests;
+mod github_tests;
+mod hooks_tests;
+mod integration_tests;
+mod known_hosts_tests;
+mod labels_tests;
+mod lfs_tests;
+mod list_cache_tests;
+mod local_files_tests;
+mod lock_expiry_tests;
+mod naming_tests;
+mod operations_tests;
+mod prefetch_tests;
+mod processes_tests;
+mod repo_queue_tests;
+mod security_tests;
+mod shared_deps_tests;
+mod sparse_tests;
+mod staging_tests;
+mod stale_tests;
+mod store_tests;
+mod sync_tests;
+mod templates_tests;
@@ -0,0 +1,150 @@
+//! Tests for worktree name suggestions.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::naming::*;
+
+// ============================================================================
+// slugify_name / strip_commit_prefix tests
+// ============================================================================
+
+#[test]
+fn test_slugify_name_basic() {
+    assert_eq!(slugify_name("Fix: Login Bug!"), "fix-login-bug");
+}
+
+#[test]
+fn test_slugify_name_truncates_without_trailing_hyphen() {
+    let long = "word ".repeat(30);
+    let slug = slugify_name(&long);
+    assert!(slug.len() <= 48);
+    assert!(!slug.ends_with('-'));
+}
+
+#[test]
+fn test_strip_commit_prefix_conventional() {
+    assert_eq!(strip_commit_prefix("feat(ui): add sidebar"), "add sidebar");
+    assert_eq!(
+        strip_commit_prefix("fix!: crash on start"),
+        "crash on start"
+    );
+}
+
+#[test]
+fn test_strip_commit_prefix_plain_subject() {
+    assert_eq!(strip_commit_prefix("Note: keep this"), "Note: keep this");
+}
+
+// ============================================================================
+// render_name_template tests
+// ============================================================================
+
+#[test]
+fn test_render_name_template_placeholders() {
+    let name = render_name_template("{user}/{hint}-{date}", "login", "20260101", "jane-doe");
+    assert_eq!(name, "jane-doe/login-20260101");
+}
+
+#[test]
+fn test_render_name_template_drops_empty_segments() {
+    let name = render_name_template("{user}/{hint}", "login", "20260101", "");
+    assert_eq!(name, "login");
+}
+
+// ============================================================================
+// suggest_worktree_names tests
+// ============================================================================
+
+#[test]
+fn test_suggest_worktree_names_uses_hint_and_commits() {
+    let repo = TestRepo::new();
+    repo.commit("feat: add payments page");
+
+    let names = suggest_worktree_names(&repo.path_str(), Some("Login Fix"), None).unwrap();
+
+    assert_eq!(names[0], "login-fix");
+    assert!(names.contains(&"add-payments-page".to_string()));
+    assert!(names.len() <= MAX_SUGGESTIONS);
+}
+
+#[test]
+fn test_suggest_worktree_names_without_hint() {
+    let repo = TestRepo::new();
+    let names = suggest_worktree_names(&repo.path_str(), None, None).unwrap();
+    assert!(names.contains(&"initial-commit".to_string()));
+}
+
+// ============================================================================
+// Generated name tests
+// ============================================================================
+
+#[test]
+fn test_find_ticket_id_whole_words() {
+    assert_eq!(
+        find_ticket_id("feature/PROJ-123-login", "*-#"),
+        Some("PROJ-123".to_string())
+    );
+    assert_eq!(
+        find_ticket_id("fix-gh-42", "gh-#"),
+        Some("gh-42".to_string())
+    );
+    assert_eq!(find_ticket_id("GH-42", "gh-#"), Some("GH-42".to_string()));
+    // Not inside words, and the digits must be there
+    assert_eq!(find_ticket_id("xgh-42", "gh-#"), None);
+    assert_eq!(find_ticket_id("feature/login", "*-#"), None);
+    assert_eq!(find_ticket_id("PROJ-123", ""), None);
+}
+
+#[test]
+fn test_render_generated_name() {
+    assert_eq!(
+        render_generated_name(
+            "{ticket}-{date}",
+            "feature/PROJ-123-login",
+            "PROJ-123",
+            "20260115",
+            ""
+        ),
+        "proj-123-20260115"
+    );
+    assert_eq!(
+        render_generated_name("{branch}", "feature/login", "", "20260115", ""),
+        "feature-login"
+    );
+    // Missing values leave no stray hyphens or folders
+    assert_eq!(
+        render_generated_name("{user}/{ticket}-{branch}", "main", "", "20260115", ""),
+        "main"
+    );
+    assert_eq!(
+        unique_name("main", |n| n == "main" || n == "main-2"),
+        "main-3"
+    );
+}
+
+#[test]
+fn test_generate_worktree_name_from_branch() {
+    let repo = TestRepo::new();
+    let current = repo.current_branch();
+
+    let name = generate_worktree_name(&repo.path_str(), None, None, None).unwrap();
+    assert!(name.starts_with(&slugify_name(&current)));
+
+    let name = generate_worktree_name(
+        &repo.path_str(),
+        Some("feature/ENG-7-search"),
+        Some("{ticket}"),
+        None,
+    )
+    .unwrap();
+    assert!(name.starts_with("eng-7"));
+}
+
+#[test]
+fn test_resolve_worktree_name() {
+    let repo = TestRepo::new();
+    assert_eq!(
+        resolve_worktree_name(&repo.path_str(), "feature-login").unwrap(),
+        "feature-login"
+    );
+    assert!(resolve_worktree_name("/nonexistent/repo", "feature-login").is_err());
+}
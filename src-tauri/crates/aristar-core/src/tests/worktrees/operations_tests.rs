@@ -0,0 +1,976 @@
+//! Unit tests for worktree operations.
+
+use crate::tests::helpers::{create_non_git_dir, TestRepo};
+use crate::worktrees::operations::*;
+use crate::worktrees::types::{BranchSort, RebaseStatus};
+
+// ============================================================================
+// is_git_repository tests
+// ============================================================================
+
+#[test]
+fn test_is_git_repository_valid_repo() {
+    let repo = TestRepo::new();
+    assert!(is_git_repository(&repo.path_str()));
+}
+
+#[test]
+fn test_is_git_repository_nonexistent_path() {
+    assert!(!is_git_repository("/nonexistent/path/to/repo"));
+}
+
+#[test]
+fn test_is_git_repository_non_git_directory() {
+    let temp = create_non_git_dir();
+    assert!(!is_git_repository(temp.path().to_str().unwrap()));
+}
+
+#[test]
+fn test_is_git_repository_subdirectory() {
+    let repo = TestRepo::new();
+    let subdir = repo.path().join("subdir");
+    std::fs::create_dir(&subdir).unwrap();
+
+    // Subdirectory of a git repo should still be detected
+    // (the function checks for .git at the exact path, so this should fail)
+    assert!(!is_git_repository(subdir.to_str().unwrap()));
+}
+
+// ============================================================================
+// get_repository_name tests
+// ============================================================================
+
+#[test]
+fn test_get_repository_name_simple_path() {
+    assert_eq!(get_repository_name("/path/to/my-repo"), "my-repo");
+}
+
+#[test]
+fn test_get_repository_name_nested_path() {
+    assert_eq!(
+        get_repository_name("/home/user/projects/deep/nested/repo-name"),
+        "repo-name"
+    );
+}
+
+#[test]
+fn test_get_repository_name_with_trailing_slash() {
+    // Path::file_name returns None for paths ending in /
+    // So this should return "Unknown" or handle it gracefully
+    let result = get_repository_name("/path/to/repo/");
+    assert!(!result.is_empty());
+}
+
+#[test]
+fn test_get_repository_name_root_path() {
+    // Edge case: root path
+    let result = get_repository_name("/");
+    assert!(!result.is_empty());
+}
+
+#[test]
+fn test_get_repository_name_with_special_chars() {
+    assert_eq!(
+        get_repository_name("/path/to/repo-with_special.chars"),
+        "repo-with_special.chars"
+    );
+}
+
+// ============================================================================
+// run_git_command tests
+// ============================================================================
+
+#[test]
+fn test_run_git_command_success() {
+    let repo = TestRepo::new();
+    let result = run_git_command(&["status"], &repo.path_str());
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_run_git_command_failure() {
+    let repo = TestRepo::new();
+    // Invalid git command should fail
+    let result = run_git_command(&["invalid-command"], &repo.path_str());
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_run_git_command_invalid_directory() {
+    let result = run_git_command(&["status"], "/nonexistent/path");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_run_git_command_with_output() {
+    let repo = TestRepo::new();
+    let result = run_git_command(&["rev-parse", "--show-toplevel"], &repo.path_str());
+    assert!(result.is_ok());
+    let output = result.unwrap();
+    assert!(!output.stdout.is_empty());
+}
+
+#[test]
+fn test_transient_git_errors_and_retry_delay() {
+    assert!(is_transient_git_error(
+        "fatal: Unable to create '/repo/.git/index.lock': File exists.\n\nAnother git process seems to be running in this repository"
+    ));
+    assert!(is_transient_git_error(
+        "error: cannot lock ref 'refs/heads/main': Unable to create '/repo/.git/refs/heads/main.lock': File exists."
+    ));
+    assert!(!is_transient_git_error("fatal: not a git repository"));
+
+    for attempt in 0..3 {
+        let delay = git_retry_delay(attempt).as_millis();
+        let base = 100 * 2u128.pow(attempt);
+        assert!(delay >= base && delay <= base + base / 2);
+    }
+}
+
+#[test]
+fn test_run_git_command_retries_while_index_is_locked() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("file.txt"), "content").unwrap();
+    let lock = repo.path().join(".git").join("index.lock");
+    std::fs::write(&lock, "").unwrap();
+
+    let releaser = {
+        let lock = lock.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(150));
+            std::fs::remove_file(lock).unwrap();
+        })
+    };
+    let result = run_git_command(&["add", "file.txt"], &repo.path_str());
+    releaser.join().unwrap();
+    assert!(result.is_ok(), "{:?}", result.err());
+}
+
+#[test]
+fn test_remove_stale_locks() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    assert!(remove_stale_locks(&path, STALE_LOCK_MIN_AGE)
+        .unwrap()
+        .is_empty());
+
+    let lock = repo.path().join(".git").join("index.lock");
+    std::fs::write(&lock, "").unwrap();
+    assert_eq!(find_git_lock_files(&path).unwrap().len(), 1);
+
+    // A fresh lock may belong to a running git process
+    let err = remove_stale_locks(&path, STALE_LOCK_MIN_AGE).unwrap_err();
+    assert!(err.contains("only"));
+    assert!(lock.exists());
+
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(120);
+    std::fs::File::options()
+        .write(true)
+        .open(&lock)
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+    let removed = remove_stale_locks(&path, STALE_LOCK_MIN_AGE).unwrap();
+    assert_eq!(removed.len(), 1);
+    assert!(!lock.exists());
+}
+
+// ============================================================================
+// get_current_branch tests
+// ============================================================================
+
+#[test]
+fn test_get_current_branch_default() {
+    let repo = TestRepo::new();
+    let branch = get_current_branch(&repo.path_str());
+    assert!(branch.is_ok());
+    // Default branch is typically "main" or "master"
+    let branch_name = branch.unwrap();
+    assert!(branch_name == "main" || branch_name == "master");
+}
+
+#[test]
+fn test_get_current_branch_after_checkout() {
+    let repo = TestRepo::new();
+    repo.create_branch("feature-branch");
+    repo.checkout("feature-branch");
+
+    let branch = get_current_branch(&repo.path_str());
+    assert!(branch.is_ok());
+    assert_eq!(branch.unwrap(), "feature-branch");
+}
+
+#[test]
+fn test_get_current_branch_invalid_repo() {
+    let result = get_current_branch("/nonexistent/path");
+    assert!(result.is_err());
+}
+
+// ============================================================================
+// get_branches tests
+// ============================================================================
+
+#[test]
+fn test_get_branches_default_repo() {
+    let repo = TestRepo::new();
+    let branches = get_branches(&repo.path_str());
+    assert!(branches.is_ok());
+    let branches = branches.unwrap();
+    assert!(!branches.is_empty());
+}
+
+#[test]
+fn test_get_branches_multiple_branches() {
+    let repo = TestRepo::with_branches(&["feature-1", "feature-2", "bugfix"]);
+    let branches = get_branches(&repo.path_str());
+    assert!(branches.is_ok());
+    let branches = branches.unwrap();
+    // Should have main/master + 3 feature branches
+    assert!(branches.len() >= 4);
+}
+
+#[test]
+fn test_get_branches_current_branch_marked() {
+    let repo = TestRepo::new();
+    repo.create_branch("feature-branch");
+    repo.checkout("feature-branch");
+
+    let branches = get_branches(&repo.path_str());
+    assert!(branches.is_ok());
+    let branches = branches.unwrap();
+
+    let current = branches.iter().find(|b| b.is_current);
+    assert!(current.is_some());
+    assert_eq!(current.unwrap().name, "feature-branch");
+}
+
+#[test]
+fn test_get_branches_local_branches_not_remote() {
+    let repo = TestRepo::new();
+    let branches = get_branches(&repo.path_str());
+    assert!(branches.is_ok());
+    let branches = branches.unwrap();
+
+    // All branches should be local (no remote in test repo)
+    for branch in &branches {
+        assert!(!branch.is_remote);
+    }
+}
+
+#[test]
+fn test_parse_branch_refs_tracking() {
+    let output = "refs/heads/main\0origin/main\0ahead 2, behind 1\n\
+                  refs/heads/old\0origin/old\0gone\n\
+                  refs/heads/local\0\0\n\
+                  refs/remotes/origin/HEAD\0\0\n\
+                  refs/remotes/origin/main\0\0\n";
+    let branches = parse_branch_refs(output, Some("main"));
+
+    assert_eq!(branches.len(), 4);
+    let main = &branches[0];
+    assert!(main.is_current && !main.is_remote);
+    assert_eq!(main.upstream.as_deref(), Some("origin/main"));
+    assert_eq!((main.ahead, main.behind, main.upstream_gone), (2, 1, false));
+
+    assert!(branches[1].upstream_gone);
+    assert_eq!(branches[2].upstream, None);
+    assert_eq!((branches[2].ahead, branches[2].behind), (0, 0));
+
+    let remote = &branches[3];
+    assert_eq!(remote.name, "origin/main");
+    assert!(remote.is_remote && !remote.is_current);
+}
+
+#[test]
+fn test_get_branches_counts_ahead_and_behind_upstream() {
+    let origin = TestRepo::new();
+    let clones = tempfile::TempDir::new().unwrap();
+    let clone_path = clones.path().join("clone").to_string_lossy().to_string();
+    run_git_command(
+        &["clone", "--quiet", &origin.path_str(), &clone_path],
+        &clones.path().to_string_lossy(),
+    )
+    .unwrap();
+    run_git_command(&["config", "user.email", "test@example.com"], &clone_path).unwrap();
+    run_git_command(&["config", "user.name", "Test User"], &clone_path).unwrap();
+
+    origin.commit("Upstream change");
+    run_git_command(&["fetch", "--quiet", "origin"], &clone_path).unwrap();
+    for message in ["Local 1", "Local 2"] {
+        run_git_command(
+            &["commit", "--allow-empty", "--quiet", "-m", message],
+            &clone_path,
+        )
+        .unwrap();
+    }
+
+    let branches = get_branches(&clone_path).unwrap();
+    let current = branches.iter().find(|b| b.is_current).unwrap();
+    assert_eq!(
+        current.upstream.as_deref(),
+        Some(format!("origin/{}", origin.current_branch()).as_str())
+    );
+    assert_eq!((current.ahead, current.behind), (2, 1));
+    assert!(branches.iter().any(|b| b.is_remote));
+}
+
+#[test]
+fn test_get_branch_page_filters_and_paginates() {
+    let repo = TestRepo::with_branches(&["feat/a", "feat/b", "feat/c", "fix/d"]);
+    let path = repo.path_str();
+
+    let page = get_branch_page(&path, Some("FEAT/"), BranchSort::Name, true, 0, 2).unwrap();
+    let names: Vec<&str> = page.branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, vec!["feat/a", "feat/b"]);
+    assert_eq!((page.total, page.has_more), (3, true));
+
+    let page = get_branch_page(&path, Some("feat/"), BranchSort::Name, true, 2, 2).unwrap();
+    assert_eq!(page.branches.len(), 1);
+    assert_eq!(page.branches[0].name, "feat/c");
+    assert!(!page.has_more);
+
+    let page = get_branch_page(&path, None, BranchSort::Name, true, 10, 2).unwrap();
+    assert!(page.branches.is_empty());
+    assert_eq!(page.total, 5);
+
+    // Recent ordering puts the branch with the newest commit first
+    std::process::Command::new("git")
+        .args(["commit", "--allow-empty", "--quiet", "-m", "Later"])
+        .env("GIT_COMMITTER_DATE", "@4000000000 +0000")
+        .current_dir(repo.path())
+        .output()
+        .unwrap();
+    run_git_command(&["branch", "-f", "fix/d", "HEAD"], &path).unwrap();
+    run_git_command(&["reset", "--hard", "--quiet", "HEAD~1"], &path).unwrap();
+    let page = get_branch_page(&path, None, BranchSort::Recent, true, 0, 1).unwrap();
+    assert_eq!(page.branches[0].name, "fix/d");
+}
+
+#[test]
+fn test_branch_matches_prefix() {
+    assert!(branch_matches_prefix("feature/login", false, "Feat"));
+    assert!(!branch_matches_prefix("bugfix/feat", false, "feat"));
+    assert!(branch_matches_prefix("origin/feature/login", true, "feat"));
+    assert!(branch_matches_prefix(
+        "origin/feature/login",
+        true,
+        "origin/f"
+    ));
+    assert!(!branch_matches_prefix(
+        "origin/feature/login",
+        false,
+        "feat"
+    ));
+}
+
+// ============================================================================
+// Merge base tests
+// ============================================================================
+
+#[test]
+fn test_get_merge_base_and_is_ancestor() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    let fork = run_git_command(&["rev-parse", "HEAD"], &path).unwrap();
+    let fork = String::from_utf8_lossy(&fork.stdout).trim().to_string();
+
+    run_git_command(&["checkout", "--quiet", "-b", "feature"], &path).unwrap();
+    run_git_command(
+        &["commit", "--allow-empty", "--quiet", "-m", "Feature"],
+        &path,
+    )
+    .unwrap();
+    run_git_command(&["checkout", "--quiet", "-"], &path).unwrap();
+    run_git_command(&["commit", "--allow-empty", "--quiet", "-m", "Main"], &path).unwrap();
+
+    assert_eq!(
+        get_merge_base(&path, "feature", "HEAD").unwrap(),
+        Some(fork.clone())
+    );
+    assert!(is_ancestor(&path, &fork, "feature").unwrap());
+    assert!(is_ancestor(&path, "HEAD", "HEAD").unwrap());
+    assert!(!is_ancestor(&path, "feature", "HEAD").unwrap());
+
+    // Merged into HEAD, the feature branch becomes an ancestor
+    run_git_command(&["merge", "--quiet", "--no-edit", "feature"], &path).unwrap();
+    assert!(is_ancestor(&path, "feature", "HEAD").unwrap());
+}
+
+#[test]
+fn test_get_merge_base_unrelated_and_unknown_refs() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    let main = get_current_branch(&path).unwrap();
+    run_git_command(&["checkout", "--quiet", "--orphan", "other"], &path).unwrap();
+    run_git_command(&["commit", "--allow-empty", "--quiet", "-m", "Root"], &path).unwrap();
+
+    assert_eq!(get_merge_base(&path, "other", &main).unwrap(), None);
+    assert!(get_merge_base(&path, "other", "missing").is_err());
+    assert!(is_ancestor(&path, "--all", "HEAD").is_err());
+}
+
+// ============================================================================
+// get_repo_context tests
+// ============================================================================
+
+#[test]
+fn test_get_repo_context_main_worktree() {
+    let repo = TestRepo::new();
+    let root = repo.path().canonicalize().unwrap();
+    std::fs::create_dir_all(root.join("src/nested")).unwrap();
+
+    let context = get_repo_context(root.join("src/nested").to_str().unwrap()).unwrap();
+
+    assert!(!context.is_worktree);
+    assert_eq!(context.toplevel, root);
+    assert_eq!(context.common_dir, root.join(".git"));
+    assert_eq!(context.repo_root(), root.as_path());
+}
+
+#[test]
+fn test_get_repo_context_linked_worktree_with_unusual_name() {
+    let repo = TestRepo::new();
+    let root = repo.path().canonicalize().unwrap();
+    let temp = tempfile::TempDir::new().unwrap();
+    // A folder named like git's own layout used to confuse the path splitting
+    let worktree = temp.path().join(".git/worktrees/feature");
+    std::fs::create_dir_all(worktree.parent().unwrap()).unwrap();
+    run_git_command(
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            worktree.to_str().unwrap(),
+        ],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    let context = get_repo_context(worktree.to_str().unwrap()).unwrap();
+
+    assert!(context.is_worktree);
+    assert_eq!(context.toplevel, worktree.canonicalize().unwrap());
+    assert_eq!(context.common_dir, root.join(".git"));
+    assert_eq!(
+        find_git_repo_root(worktree.to_str().unwrap()).unwrap(),
+        root.to_string_lossy()
+    );
+}
+
+#[test]
+fn test_get_repo_context_bare_repository() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let bare = temp.path().join("repo.git");
+    run_git_command(
+        &["init", "--bare", bare.to_str().unwrap()],
+        &temp.path().to_string_lossy(),
+    )
+    .unwrap();
+    let bare = bare.canonicalize().unwrap();
+
+    for path in [bare.clone(), bare.join("refs")] {
+        let context = get_repo_context(path.to_str().unwrap()).unwrap();
+        assert!(!context.is_worktree);
+        assert_eq!(context.common_dir, bare);
+        assert_eq!(context.toplevel, bare);
+        assert_eq!(context.repo_root(), bare.as_path());
+    }
+    assert_eq!(
+        find_git_repo_root(bare.to_str().unwrap()).unwrap(),
+        bare.to_string_lossy()
+    );
+}
+
+#[test]
+fn test_get_repo_context_not_a_repository() {
+    let dir = create_non_git_dir();
+    assert!(get_repo_context(&dir.path().to_string_lossy()).is_err());
+}
+
+// ============================================================================
+// head_state tests
+// ============================================================================
+
+#[test]
+fn test_parse_head_state() {
+    use crate::worktrees::types::HeadState;
+
+    let sha = "0123456789abcdef0123456789abcdef01234567";
+    assert_eq!(
+        parse_head_state(Some(sha), Some("refs/heads/feature/x"), false),
+        HeadState::Branch("feature/x".to_string())
+    );
+    assert_eq!(
+        parse_head_state(Some(sha), None, true),
+        HeadState::Detached(sha.to_string())
+    );
+    assert_eq!(
+        parse_head_state(Some(&"0".repeat(40)), Some("refs/heads/main"), false),
+        HeadState::Unborn
+    );
+}
+
+#[test]
+fn test_list_worktrees_reports_detached_head() {
+    use crate::worktrees::types::HeadState;
+
+    let repo = TestRepo::new();
+    let head = run_git_command(&["rev-parse", "HEAD"], &repo.path_str()).unwrap();
+    let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+    let temp = tempfile::TempDir::new().unwrap();
+    let worktree = temp.path().join("detached");
+    run_git_command(
+        &["worktree", "add", "--detach", worktree.to_str().unwrap()],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    let worktrees = list_worktrees(&repo.path_str()).unwrap();
+    let main = worktrees.iter().find(|w| w.is_main).unwrap();
+    let detached = worktrees.iter().find(|w| !w.is_main).unwrap();
+
+    assert!(matches!(main.head_state, Some(HeadState::Branch(_))));
+    assert!(!main.is_detached);
+    assert_eq!(detached.head_state, Some(HeadState::Detached(head)));
+    assert!(detached.is_detached);
+    assert_eq!(detached.branch, None);
+}
+
+// ============================================================================
+// base branch tests
+// ============================================================================
+
+#[test]
+fn test_detect_base_branch_falls_back_to_main_or_master() {
+    let repo = TestRepo::new();
+    run_git_command(&["branch", "-M", "trunk"], &repo.path_str()).unwrap();
+    assert_eq!(detect_base_branch(&repo.path_str()), None);
+
+    repo.create_branch("master");
+    assert_eq!(
+        detect_base_branch(&repo.path_str()).as_deref(),
+        Some("master")
+    );
+    repo.create_branch("main");
+    assert_eq!(
+        detect_base_branch(&repo.path_str()).as_deref(),
+        Some("main")
+    );
+}
+
+#[test]
+fn test_detect_base_branch_uses_configured_default() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    run_git_command(&["branch", "-M", "trunk"], &path).unwrap();
+    repo.create_branch("master");
+    run_git_command(&["config", "init.defaultBranch", "trunk"], &path).unwrap();
+    assert_eq!(detect_base_branch(&path).as_deref(), Some("trunk"));
+
+    // A configured branch that doesn't exist is ignored
+    run_git_command(&["config", "init.defaultBranch", "stable"], &path).unwrap();
+    assert_eq!(detect_base_branch(&path).as_deref(), Some("master"));
+}
+
+#[test]
+fn test_detect_base_branch_uses_origin_head() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    run_git_command(
+        &["update-ref", "refs/remotes/origin/develop", "HEAD"],
+        &path,
+    )
+    .unwrap();
+    run_git_command(
+        &[
+            "symbolic-ref",
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+        ],
+        &path,
+    )
+    .unwrap();
+
+    assert_eq!(detect_base_branch(&path).as_deref(), Some("origin/develop"));
+    repo.create_branch("develop");
+    assert_eq!(detect_base_branch(&path).as_deref(), Some("develop"));
+}
+
+#[test]
+fn test_repository_base_branch_prefers_override() {
+    use crate::worktrees::types::Repository;
+
+    let mut repo = Repository {
+        id: "1".to_string(),
+        path: "/tmp/repo".to_string(),
+        name: "repo".to_string(),
+        display_name: None,
+        worktrees: vec![],
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: Some("main".to_string()),
+        remote_default_branch: None,
+        protected_branches: vec![],
+        remote_protected_branches: vec![],
+        remote_protection_checked_at: None,
+        scan_settings: Default::default(),
+        labels: vec![],
+    };
+    assert_eq!(repo.base_branch(), Some("main"));
+
+    repo.default_base_branch = Some("develop".to_string());
+    assert_eq!(repo.base_branch(), Some("develop"));
+}
+
+#[test]
+fn test_repository_display_name() {
+    use crate::worktrees::types::Repository;
+
+    let mut repo = Repository {
+        id: "1".to_string(),
+        path: "/tmp/clients/acme/frontend".to_string(),
+        name: "frontend".to_string(),
+        display_name: None,
+        worktrees: vec![],
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: None,
+        remote_default_branch: None,
+        protected_branches: vec![],
+        remote_protected_branches: vec![],
+        remote_protection_checked_at: None,
+        scan_settings: Default::default(),
+        labels: vec![],
+    };
+    assert_eq!(repo.display_name(), "frontend");
+
+    repo.set_display_name(Some("  Acme frontend ")).unwrap();
+    assert_eq!(repo.display_name(), "Acme frontend");
+    assert_eq!(repo.name, "frontend");
+
+    assert!(repo.set_display_name(Some(&"x".repeat(101))).is_err());
+    assert!(repo.set_display_name(Some("a\nb")).is_err());
+    assert_eq!(repo.display_name(), "Acme frontend");
+
+    // Blank names and the folder name clear it
+    repo.set_display_name(Some(" ")).unwrap();
+    assert_eq!(repo.display_name, None);
+    repo.set_display_name(Some("Acme")).unwrap();
+    repo.set_display_name(Some("frontend")).unwrap();
+    assert_eq!(repo.display_name, None);
+}
+
+#[test]
+fn test_get_default_branch_follows_remote_head() {
+    let origin = TestRepo::new();
+    let clones = tempfile::TempDir::new().unwrap();
+    let clone_path = clones.path().join("clone").to_string_lossy().to_string();
+    run_git_command(
+        &["clone", "--quiet", &origin.path_str(), &clone_path],
+        &clones.path().to_string_lossy(),
+    )
+    .unwrap();
+    assert_eq!(
+        get_default_branch(&clone_path),
+        Some(origin.current_branch())
+    );
+
+    // The remote switches its default branch, e.g. master -> main
+    origin.create_branch("trunk");
+    run_git_command(
+        &["symbolic-ref", "HEAD", "refs/heads/trunk"],
+        &origin.path_str(),
+    )
+    .unwrap();
+    run_git_command(&["fetch", "--quiet", "origin"], &clone_path).unwrap();
+    update_remote_head(&clone_path).unwrap();
+
+    assert_eq!(get_default_branch(&clone_path).as_deref(), Some("trunk"));
+    assert_eq!(
+        detect_base_branch(&clone_path).as_deref(),
+        Some("origin/trunk")
+    );
+}
+
+#[test]
+fn test_remote_default_branch_change_is_recorded_and_applied() {
+    use crate::worktrees::types::{DefaultBranchChange, Repository};
+
+    let mut repo = Repository {
+        id: "1".to_string(),
+        path: "/tmp/repo".to_string(),
+        name: "repo".to_string(),
+        display_name: None,
+        worktrees: vec![],
+        last_scanned: 0,
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: Some("origin/master".to_string()),
+        detected_base_branch: None,
+        remote_default_branch: None,
+        protected_branches: vec![],
+        remote_protected_branches: vec![],
+        remote_protection_checked_at: None,
+        scan_settings: Default::default(),
+        labels: vec![],
+    };
+
+    // First sighting and unchanged branches are not changes
+    assert_eq!(
+        repo.record_remote_default_branch(Some("master".into())),
+        None
+    );
+    assert_eq!(
+        repo.record_remote_default_branch(Some("master".into())),
+        None
+    );
+    assert_eq!(repo.record_remote_default_branch(None), None);
+
+    let change = repo.record_remote_default_branch(Some("trunk".into()));
+    assert_eq!(
+        change,
+        Some(DefaultBranchChange {
+            repository_id: "1".to_string(),
+            previous: "master".to_string(),
+            current: "trunk".to_string(),
+        })
+    );
+    assert_eq!(repo.remote_default_branch.as_deref(), Some("trunk"));
+
+    assert!(!repo.is_protected_branch("trunk"));
+    repo.apply_default_branch_change("master", "trunk");
+    assert!(repo.is_protected_branch("trunk"));
+    assert_eq!(repo.default_base_branch.as_deref(), Some("origin/trunk"));
+
+    // Applying twice doesn't duplicate the protected entry
+    repo.apply_default_branch_change("master", "trunk");
+    assert_eq!(repo.protected_branches, vec!["trunk".to_string()]);
+}
+
+// ============================================================================
+// get_commits tests
+// ============================================================================
+
+#[test]
+fn test_parse_commit_log() {
+    let output = "a1\x1fa\x1fp1 p2\x1fHEAD -> main, tag: v1\x1fAda\x1f1700000000\x1fMerge a|b\n\
+                  p1\x1fp\x1f\x1f\x1fAda\x1f1690000000\x1fInitial\n";
+    let commits = parse_commit_log(output);
+
+    assert_eq!(commits.len(), 2);
+    assert_eq!(commits[0].message, "Merge a|b");
+    assert_eq!(commits[0].author, "Ada");
+    assert_eq!(commits[0].date, 1700000000);
+    assert_eq!(commits[0].parents, vec!["p1", "p2"]);
+    assert_eq!(commits[0].refs, vec!["HEAD -> main", "tag: v1"]);
+    assert!(commits[1].parents.is_empty());
+    assert!(commits[1].refs.is_empty());
+}
+
+#[test]
+fn test_get_commit_page_skip_and_before_hash() {
+    let repo = TestRepo::new();
+    for i in 1..=4 {
+        repo.commit(&format!("change {}", i));
+    }
+    let path = repo.path_str();
+
+    let first = get_commit_page(&path, 2, 0, None).unwrap();
+    let messages = |commits: &[crate::worktrees::types::CommitInfo]| {
+        commits
+            .iter()
+            .map(|c| c.message.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(messages(&first), ["change 4", "change 3"]);
+    assert!(first[0].refs.iter().any(|r| r.starts_with("HEAD -> ")));
+    assert_eq!(first[0].parents, vec![first[1].hash.clone()]);
+
+    let by_skip = get_commit_page(&path, 2, 2, None).unwrap();
+    let by_cursor = get_commit_page(&path, 2, 0, Some(&first[1].hash)).unwrap();
+    assert_eq!(messages(&by_skip), ["change 2", "change 1"]);
+    assert_eq!(messages(&by_cursor), messages(&by_skip));
+
+    // The root commit ends the log
+    let root = get_commit_page(&path, 10, 0, None).unwrap().pop().unwrap();
+    assert!(get_commit_page(&path, 10, 0, Some(&root.hash))
+        .unwrap()
+        .is_empty());
+    assert!(get_commit_page(&path, 10, 0, Some("--all")).is_err());
+}
+
+// ============================================================================
+// get_worktree_status tests
+// ============================================================================
+
+#[test]
+fn test_parse_worktree_status() {
+    let output = "\
+# branch.oid 0123456789abcdef0123456789abcdef01234567
+# branch.head feature
+# branch.upstream origin/feature
+# branch.ab +2 -3
+1 M. N... 100644 100644 100644 aaa bbb staged.rs
+1 .M N... 100644 100644 100644 aaa bbb modified.rs
+1 MM N... 100644 100644 100644 aaa bbb both.rs
+2 R. N... 100644 100644 100644 aaa bbb R100 new.rs\told.rs
+u UU N... 100644 100644 100644 100644 aaa bbb ccc conflict.rs
+? untracked.rs
+";
+    let status = parse_worktree_status(output);
+
+    assert_eq!(
+        status.head.as_deref(),
+        Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(status.branch.as_deref(), Some("feature"));
+    assert_eq!(status.upstream.as_deref(), Some("origin/feature"));
+    assert_eq!((status.ahead, status.behind), (2, 3));
+    assert_eq!(status.staged, 3);
+    assert_eq!(status.modified, 2);
+    assert_eq!(status.conflicted, 1);
+    assert_eq!(status.untracked, 1);
+    assert!(status.is_dirty);
+}
+
+#[test]
+fn test_parse_worktree_status_detached_and_initial() {
+    let status = parse_worktree_status("# branch.oid (initial)\n# branch.head (detached)\n");
+    assert_eq!(status.head, None);
+    assert_eq!(status.branch, None);
+    assert_eq!(status.upstream, None);
+    assert!(!status.is_dirty);
+}
+
+#[test]
+fn test_get_worktree_status_counts_changes() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("test.txt"), "changed").unwrap();
+    std::fs::write(repo.path().join("new.txt"), "new").unwrap();
+
+    let status = get_worktree_status(&repo.path_str()).unwrap();
+    assert_eq!(status.modified, 1);
+    assert_eq!(status.untracked, 1);
+    assert_eq!(status.staged, 0);
+    assert!(status.is_dirty);
+    assert_eq!(status.branch, Some(repo.current_branch()));
+
+    assert!(get_worktree_status("/nonexistent/worktree").is_err());
+}
+
+// ============================================================================
+// rebase_worktree tests
+// ============================================================================
+
+/// A repository plus a `feature` worktree, both with a commit editing `shared.txt`.
+fn create_rebase_fixture(feature_content: &str) -> (TestRepo, tempfile::TempDir, String) {
+    let repo = TestRepo::new();
+    let dir = tempfile::TempDir::new().unwrap();
+    let worktree = dir.path().join("feature").to_string_lossy().to_string();
+    run_git_command(
+        &["worktree", "add", "-b", "feature", &worktree],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    for (path, content) in [
+        (repo.path_str(), "base"),
+        (worktree.clone(), feature_content),
+    ] {
+        std::fs::write(std::path::Path::new(&path).join("shared.txt"), content).unwrap();
+        run_git_command(&["add", "."], &path).unwrap();
+        run_git_command(&["commit", "-m", content], &path).unwrap();
+    }
+    (repo, dir, worktree)
+}
+
+#[test]
+fn test_rebase_worktree_onto_base() {
+    let (repo, _dir, worktree) = create_rebase_fixture("base");
+    let base = repo.current_branch();
+    repo.commit("more base");
+
+    let result = rebase_worktree(&worktree, &base).unwrap();
+    assert_eq!(result.status, RebaseStatus::Rebased);
+    assert_eq!(result.onto, base);
+    assert!(std::path::Path::new(&worktree)
+        .join("more_base.txt")
+        .exists());
+    // The feature commit became empty against the base and was dropped
+    assert_eq!(result.commits, 0);
+
+    let again = rebase_worktree(&worktree, &base).unwrap();
+    assert_eq!(again.status, RebaseStatus::UpToDate);
+
+    assert!(rebase_worktree(&worktree, "no-such-branch")
+        .unwrap_err()
+        .contains("not found"));
+    std::fs::write(std::path::Path::new(&worktree).join("shared.txt"), "dirty").unwrap();
+    assert!(rebase_worktree(&worktree, &base)
+        .unwrap_err()
+        .contains("uncommitted changes"));
+}
+
+#[test]
+fn test_rebase_worktree_conflicts_continue_and_abort() {
+    let (repo, _dir, worktree) = create_rebase_fixture("feature");
+    let base = repo.current_branch();
+    let shared = std::path::Path::new(&worktree).join("shared.txt");
+
+    let result = rebase_worktree(&worktree, &base).unwrap();
+    assert_eq!(result.status, RebaseStatus::Conflicts);
+    assert_eq!(result.conflicts, vec!["shared.txt"]);
+    assert!(is_rebase_in_progress(&worktree));
+    assert!(rebase_worktree(&worktree, &base)
+        .unwrap_err()
+        .contains("already in progress"));
+
+    // Markers still there: nothing is staged
+    let still = continue_rebase(&worktree).unwrap();
+    assert_eq!(still.status, RebaseStatus::Conflicts);
+    assert_eq!(still.conflicts, vec!["shared.txt"]);
+
+    std::fs::write(&shared, "resolved").unwrap();
+    let done = continue_rebase(&worktree).unwrap();
+    assert_eq!(done.status, RebaseStatus::Rebased);
+    assert_eq!(done.commits, 1);
+    assert!(!is_rebase_in_progress(&worktree));
+    assert_eq!(std::fs::read_to_string(&shared).unwrap(), "resolved");
+
+    // Abort restores the starting point
+    repo.commit("conflict again");
+    std::fs::write(repo.path().join("shared.txt"), "base 2").unwrap();
+    run_git_command(&["commit", "-am", "base 2"], &repo.path_str()).unwrap();
+    let head = run_git_command(&["rev-parse", "HEAD"], &worktree)
+        .unwrap()
+        .stdout;
+    assert_eq!(
+        rebase_worktree(&worktree, &base).unwrap().status,
+        RebaseStatus::Conflicts
+    );
+    assert_eq!(
+        abort_rebase(&worktree).unwrap().status,
+        RebaseStatus::Aborted
+    );
+    assert!(!is_rebase_in_progress(&worktree));
+    assert_eq!(
+        run_git_command(&["rev-parse", "HEAD"], &worktree)
+            .unwrap()
+            .stdout,
+        head
+    );
+    assert!(abort_rebase(&worktree).unwrap_err().contains("No rebase"));
+}
+
+#[test]
+fn test_has_conflict_markers() {
+    assert!(has_conflict_markers(
+        "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> x\n"
+    ));
+    assert!(!has_conflict_markers("Title\n=======\nbody\n"));
+}
@@ -0,0 +1,64 @@
+//! Tests for repository scan settings and the commit history prefetcher.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::get_commit_page;
+use crate::worktrees::prefetch::*;
+use crate::worktrees::types::RepoScanSettings;
+
+#[test]
+fn test_scan_settings_for_size() {
+    let output =
+        "count: 12\nsize: 48\nin-pack: 3000\npacks: 1\nsize-pack: 2048\nprune-packable: 0\n";
+    assert_eq!(parse_object_size_kib(output), Some(2096));
+    assert_eq!(parse_object_size_kib("garbage"), None);
+
+    assert_eq!(
+        scan_settings_for_size(Some(2096)),
+        RepoScanSettings::default()
+    );
+    assert_eq!(scan_settings_for_size(None), RepoScanSettings::default());
+
+    let large = scan_settings_for_size(Some(LARGE_REPO_THRESHOLD_KIB + 1));
+    assert_eq!(large.history_depth, LARGE_REPO_HISTORY_DEPTH);
+    assert!(!large.eager_status);
+}
+
+#[test]
+fn test_cached_commit_page_follows_new_commits() {
+    let repo = TestRepo::new();
+    repo.commit("second");
+    repo.commit("third");
+    let path = repo.path_str();
+
+    // Fewer commits than the depth: the whole history is cached
+    assert_eq!(prefetch_history(&path, 10).unwrap(), 3);
+    let page = cached_commit_page(&path, 2, 1, None).unwrap();
+    let expected = get_commit_page(&path, 2, 1, None).unwrap();
+    assert_eq!(
+        page.iter().map(|c| &c.hash).collect::<Vec<_>>(),
+        expected.iter().map(|c| &c.hash).collect::<Vec<_>>()
+    );
+    assert!(cached_commit_page(&path, 5, 3, None).unwrap().is_empty());
+
+    repo.commit("fourth");
+    let page = cached_commit_page(&path, 1, 0, None).unwrap();
+    assert_eq!(page[0].message, "fourth");
+
+    assert_eq!(prefetch_history(&path, 0).unwrap(), 0);
+}
+
+#[test]
+fn test_deep_scan_repository_reads_statuses() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("test.txt"), "changed").unwrap();
+    let path = repo.path_str();
+
+    let result =
+        deep_scan_repository(&path, &[path.clone(), "/nonexistent/worktree".into()]).unwrap();
+
+    assert_eq!(result.commits_cached, 1);
+    assert_eq!(result.statuses.len(), 1);
+    assert!(result.statuses[&path].is_dirty);
+    assert_eq!(result.dirty_worktrees, 1);
+    invalidate_history_cache(&path);
+}
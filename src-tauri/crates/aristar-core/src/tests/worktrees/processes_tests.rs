@@ -0,0 +1,60 @@
+//! Tests for inspecting and stopping processes running inside a worktree.
+
+use std::path::{Path, PathBuf};
+
+use crate::worktrees::processes::{parse_process_list, processes_in_worktree};
+
+#[test]
+fn test_parse_process_list() {
+    let output =
+        "p101\ncnode\nn/wt/feature/apps/web\np102\nczsh\nn/wt/feature\np103\ncvim\nn/home/me\n";
+    let processes = parse_process_list(output);
+    assert_eq!(processes.len(), 3);
+    assert_eq!(
+        processes[0],
+        (
+            101,
+            "node".to_string(),
+            PathBuf::from("/wt/feature/apps/web")
+        )
+    );
+
+    let listening = [(101, 5173), (101, 24678), (103, 8080)];
+    let found = processes_in_worktree(&processes, &listening, Path::new("/wt/feature"), 102);
+    // Own process left out, other folders too
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].pid, 101);
+    assert_eq!(found[0].command, "node");
+    assert_eq!(found[0].ports, [5173, 24678]);
+    // Path prefixes that aren't parents don't count
+    assert!(processes_in_worktree(&processes, &[], Path::new("/wt/feat"), 0).is_empty());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_find_and_terminate_worktree_process() {
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    use crate::worktrees::processes::{get_processes_in_worktree, terminate_worktree_processes};
+
+    let worktree = TempDir::new().unwrap();
+    let path = worktree.path().canonicalize().unwrap();
+    let path = path.to_string_lossy().to_string();
+    let mut child = Command::new("sleep")
+        .arg("30")
+        .current_dir(&path)
+        .spawn()
+        .unwrap();
+
+    let found = get_processes_in_worktree(&path).unwrap();
+    assert!(found.iter().any(|p| p.pid == child.id()));
+
+    // Pids outside the worktree are ignored
+    assert!(terminate_worktree_processes(&path, Some(&[1]), false)
+        .unwrap()
+        .is_empty());
+    let signaled = terminate_worktree_processes(&path, Some(&[child.id()]), false).unwrap();
+    assert_eq!(signaled, [child.id()]);
+    assert!(!child.wait().unwrap().success());
+}
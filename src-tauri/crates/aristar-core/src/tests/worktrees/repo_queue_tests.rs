@@ -0,0 +1,105 @@
+//! Tests for the per-repository operation queue.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+use crate::worktrees::repo_queue::{get_queue_status, run_queued};
+
+fn repo_key(dir: &TempDir) -> String {
+    dir.path()
+        .canonicalize()
+        .unwrap()
+        .to_string_lossy()
+        .to_string()
+}
+
+#[test]
+fn test_run_queued_serializes_same_repo() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().to_string_lossy().to_string();
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let (path, running, max_running) = (path.clone(), running.clone(), max_running.clone());
+            thread::spawn(move || {
+                run_queued(&path, &format!("op {}", i), || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_running.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i
+                })
+            })
+        })
+        .collect();
+
+    let mut results: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    results.sort();
+    assert_eq!(results, vec![0, 1, 2, 3]);
+    assert_eq!(max_running.load(Ordering::SeqCst), 1);
+    assert!(get_queue_status()
+        .iter()
+        .all(|s| s.repo_path != repo_key(&dir)));
+}
+
+#[test]
+fn test_run_queued_other_repos_do_not_wait() {
+    let first = TempDir::new().unwrap();
+    let second = TempDir::new().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let first_path = first.path().to_string_lossy().to_string();
+    let waiter = thread::spawn(move || {
+        run_queued(&first_path, "hold", || {
+            rx.recv_timeout(Duration::from_secs(5)).is_ok()
+        })
+    });
+    // Only finishes while the first repository is still busy
+    let second_path = second.path().to_string_lossy().to_string();
+    run_queued(&second_path, "signal", || tx.send(()).unwrap());
+
+    assert!(waiter.join().unwrap());
+}
+
+#[test]
+fn test_queue_status_shows_waiting_operations() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().to_string_lossy().to_string();
+    let key = repo_key(&dir);
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let (started_tx, started_rx) = mpsc::channel();
+
+    let holder_path = path.clone();
+    let holder = thread::spawn(move || {
+        run_queued(&holder_path, "create worktree a", || {
+            started_tx.send(()).unwrap();
+            let _ = release_rx.recv_timeout(Duration::from_secs(5));
+        })
+    });
+    started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    let waiter_path = path.clone();
+    let waiter = thread::spawn(move || run_queued(&waiter_path, "remove worktree b", || ()));
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let status = loop {
+        let status = get_queue_status().into_iter().find(|s| s.repo_path == key);
+        if status.as_ref().is_some_and(|s| !s.waiting.is_empty()) || Instant::now() > deadline {
+            break status.unwrap();
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert_eq!(status.running.as_deref(), Some("create worktree a"));
+    assert_eq!(status.waiting, vec!["remove worktree b".to_string()]);
+
+    release_tx.send(()).unwrap();
+    holder.join().unwrap();
+    waiter.join().unwrap();
+}
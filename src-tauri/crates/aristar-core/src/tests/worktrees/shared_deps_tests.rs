@@ -0,0 +1,116 @@
+//! Tests for sharing dependency folders with the main worktree.
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::TempDir;
+
+use crate::core::SharedDepsMode;
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::shared_deps::{
+    share_dependency_dirs, unshare_dependency_dirs, validate_shared_dir,
+};
+
+/// A repository with an ignored `node_modules` and a linked worktree.
+fn repo_with_worktree() -> (TestRepo, TempDir, String) {
+    let repo = TestRepo::new();
+    fs::write(repo.path().join(".gitignore"), "node_modules/\n").unwrap();
+    repo.commit("Ignore node_modules");
+    fs::create_dir_all(repo.path().join("node_modules/left-pad")).unwrap();
+    fs::write(
+        repo.path().join("node_modules/left-pad/index.js"),
+        "module.exports = 1;\n",
+    )
+    .unwrap();
+
+    let parent = TempDir::new().unwrap();
+    let worktree = parent.path().join("agent").to_string_lossy().to_string();
+    run_git_command(
+        &["worktree", "add", "--detach", &worktree],
+        &repo.path_str(),
+    )
+    .unwrap();
+    (repo, parent, worktree)
+}
+
+fn is_clean(worktree: &str) -> bool {
+    let status = run_git_command(&["status", "--porcelain"], worktree).unwrap();
+    String::from_utf8_lossy(&status.stdout).trim().is_empty()
+}
+
+#[test]
+fn test_validate_shared_dir() {
+    assert!(validate_shared_dir("node_modules").is_some());
+    assert!(validate_shared_dir("packages/web/node_modules/").is_some());
+    assert!(validate_shared_dir("").is_none());
+    assert!(validate_shared_dir("/usr/lib").is_none());
+    assert!(validate_shared_dir("../node_modules").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinked_dependencies_are_excluded_and_unlinked() {
+    let (repo, _parent, worktree) = repo_with_worktree();
+    let dirs = vec!["node_modules".to_string(), "target".to_string()];
+
+    let shared =
+        share_dependency_dirs(&repo.path_str(), &worktree, &dirs, SharedDepsMode::Symlink).unwrap();
+    // `target` doesn't exist in the main worktree
+    assert_eq!(shared.len(), 1);
+    assert_eq!(shared[0].path, "node_modules");
+    assert_eq!(shared[0].mode, SharedDepsMode::Symlink);
+
+    let link = Path::new(&worktree).join("node_modules");
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert!(link.join("left-pad/index.js").exists());
+    // "node_modules/" doesn't match a symlink, so it was excluded
+    assert!(is_clean(&worktree));
+    // Shared once
+    assert!(
+        share_dependency_dirs(&repo.path_str(), &worktree, &dirs, SharedDepsMode::Symlink)
+            .unwrap()
+            .is_empty()
+    );
+
+    assert_eq!(unshare_dependency_dirs(&worktree, &dirs), 1);
+    assert!(fs::symlink_metadata(&link).is_err());
+    assert!(repo.path().join("node_modules/left-pad/index.js").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hardlinked_dependencies() {
+    use std::os::unix::fs::MetadataExt;
+
+    let (repo, _parent, worktree) = repo_with_worktree();
+    std::os::unix::fs::symlink(
+        "../left-pad/index.js",
+        repo.path().join("node_modules/.bin"),
+    )
+    .unwrap();
+    let dirs = vec!["node_modules".to_string()];
+
+    let shared =
+        share_dependency_dirs(&repo.path_str(), &worktree, &dirs, SharedDepsMode::Hardlink)
+            .unwrap();
+    assert_eq!(shared[0].mode, SharedDepsMode::Hardlink);
+
+    let copy = Path::new(&worktree).join("node_modules");
+    assert!(copy.is_dir());
+    let file = fs::metadata(copy.join("left-pad/index.js")).unwrap();
+    let original = fs::metadata(repo.path().join("node_modules/left-pad/index.js")).unwrap();
+    assert_eq!(file.ino(), original.ino());
+    assert_eq!(
+        fs::read_link(copy.join(".bin")).unwrap(),
+        Path::new("../left-pad/index.js")
+    );
+    assert!(is_clean(&worktree));
+
+    // Only symlinks are unlinked
+    assert_eq!(unshare_dependency_dirs(&worktree, &dirs), 0);
+    assert!(copy.is_dir());
+}
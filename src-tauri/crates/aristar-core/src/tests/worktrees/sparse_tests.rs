@@ -0,0 +1,61 @@
+//! Tests for sparse (lightweight) worktrees.
+
+use std::fs;
+
+use tempfile::TempDir;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::sparse::{checkout_sparse, normalize_sparse_paths};
+
+#[test]
+fn test_normalize_sparse_paths() {
+    let paths = vec![
+        " src/app/ ".to_string(),
+        String::new(),
+        "docs".to_string(),
+        "src/app".to_string(),
+    ];
+    assert_eq!(
+        normalize_sparse_paths(&paths).unwrap(),
+        vec!["src/app".to_string(), "docs".to_string()]
+    );
+
+    assert!(normalize_sparse_paths(&["../outside".to_string()]).is_err());
+    assert!(normalize_sparse_paths(&["/etc".to_string()]).is_err());
+    assert!(normalize_sparse_paths(&["src/./app".to_string()]).is_ok());
+}
+
+#[test]
+fn test_checkout_sparse_only_writes_selected_folders() {
+    let repo = TestRepo::new();
+    for dir in ["app", "assets"] {
+        fs::create_dir(repo.path().join(dir)).unwrap();
+        fs::write(repo.path().join(dir).join("file.txt"), dir).unwrap();
+    }
+    run_git_command(&["add", "."], &repo.path_str()).unwrap();
+    run_git_command(&["commit", "-m", "Add folders"], &repo.path_str()).unwrap();
+
+    let parent = TempDir::new().unwrap();
+    let worktree = parent.path().join("sparse");
+    let worktree_str = worktree.to_string_lossy().to_string();
+    run_git_command(
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            "--no-checkout",
+            &worktree_str,
+        ],
+        &repo.path_str(),
+    )
+    .unwrap();
+
+    checkout_sparse(&worktree_str, &["app".to_string()]).unwrap();
+
+    assert!(worktree.join("app/file.txt").exists());
+    assert!(worktree.join("test.txt").exists());
+    assert!(!worktree.join("assets").exists());
+    let status = run_git_command(&["status", "--porcelain"], &worktree_str).unwrap();
+    assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+}
@@ -0,0 +1,83 @@
+//! Tests for staging and committing in a worktree.
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::staging::{commit_changes, stage_files, unstage_files};
+
+#[test]
+fn test_stage_and_unstage_files() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+    std::fs::write(repo.path().join("a.txt"), "a").unwrap();
+    std::fs::write(repo.path().join("-b.txt"), "b").unwrap();
+    std::fs::remove_file(repo.path().join("test.txt")).unwrap();
+
+    let status = stage_files(&path, &["a.txt".to_string()]).unwrap();
+    assert_eq!(status.staged, 1);
+    assert_eq!(status.untracked, 1);
+    assert_eq!(status.modified, 1);
+
+    // Names starting with a dash are paths, not options
+    let status = stage_files(&path, &["-b.txt".to_string()]).unwrap();
+    assert_eq!(status.staged, 2);
+
+    let status = stage_files(&path, &[]).unwrap();
+    assert_eq!(status.staged, 3);
+    assert_eq!(status.modified + status.untracked, 0);
+
+    let status = unstage_files(&path, &["a.txt".to_string()]).unwrap();
+    assert_eq!(status.staged, 2);
+    assert_eq!(status.untracked, 1);
+
+    let status = unstage_files(&path, &[]).unwrap();
+    assert_eq!(status.staged, 0);
+    assert!(repo.path().join("a.txt").exists());
+
+    assert!(stage_files(&path, &["../outside.txt".to_string()]).is_err());
+    assert!(stage_files("/nonexistent/worktree", &[]).is_err());
+}
+
+#[test]
+fn test_unstage_without_commits() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().to_string_lossy().to_string();
+    run_git_command(&["init", "-q"], &path).unwrap();
+    std::fs::write(dir.path().join("new.txt"), "new").unwrap();
+
+    assert_eq!(stage_files(&path, &[]).unwrap().staged, 1);
+    let status = unstage_files(&path, &[]).unwrap();
+    assert_eq!(status.staged, 0);
+    assert_eq!(status.untracked, 1);
+}
+
+#[test]
+fn test_commit_changes() {
+    let repo = TestRepo::new();
+    let path = repo.path_str();
+
+    assert!(commit_changes(&path, "  ", true)
+        .unwrap_err()
+        .contains("empty"));
+    assert!(commit_changes(&path, "Nothing", false)
+        .unwrap_err()
+        .contains("Nothing staged"));
+
+    std::fs::write(repo.path().join("test.txt"), "changed").unwrap();
+    std::fs::write(repo.path().join("other.txt"), "other").unwrap();
+    stage_files(&path, &["test.txt".to_string()]).unwrap();
+
+    let commit = commit_changes(&path, "Update test file\n", false).unwrap();
+    assert_eq!(commit.message, "Update test file");
+    assert_eq!(commit.author, "Test User");
+    let head = run_git_command(&["rev-parse", "HEAD"], &path).unwrap();
+    assert_eq!(String::from_utf8_lossy(&head.stdout).trim(), commit.hash);
+
+    // Unstaged files stay out unless everything is staged
+    let show = run_git_command(&["show", "--name-only", "--format=", "HEAD"], &path).unwrap();
+    assert_eq!(String::from_utf8_lossy(&show.stdout).trim(), "test.txt");
+
+    let commit = commit_changes(&path, "Add other file", true).unwrap();
+    assert_eq!(commit.message, "Add other file");
+    let status = run_git_command(&["status", "--porcelain"], &path).unwrap();
+    assert!(status.stdout.is_empty());
+}
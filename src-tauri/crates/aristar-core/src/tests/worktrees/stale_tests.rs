@@ -0,0 +1,97 @@
+//! Tests for stale worktree cleanup.
+
+use crate::core::StaleCleanupPolicy;
+use crate::tests::helpers::{test_repository, test_worktree};
+use crate::worktrees::stale::{
+    find_stale_worktrees, policy_keep_reason, validate_stale_cleanup_policy,
+};
+use crate::worktrees::types::{Repository, WorktreeInfo};
+
+const DAY: i64 = 24 * 60 * 60 * 1000;
+const NOW: i64 = 1_000 * DAY;
+
+fn worktree(path: &str, last_active: Option<i64>) -> WorktreeInfo {
+    WorktreeInfo {
+        last_active,
+        ..test_worktree(path, path.rsplit('/').next().unwrap_or(path))
+    }
+}
+
+fn repository(id: &str, worktrees: Vec<WorktreeInfo>) -> Repository {
+    test_repository(&format!("/repos/{}", id), worktrees)
+}
+
+#[test]
+fn test_find_stale_worktrees() {
+    let mut main = worktree("/repos/app", Some(NOW - 90 * DAY));
+    main.is_main = true;
+    let mut cache = worktree("/wt/cache", Some(NOW - 90 * DAY));
+    cache.hidden = true;
+    let mut offline = repository("offline", vec![worktree("/wt/offline", Some(0))]);
+    offline.unavailable_since = Some(NOW - DAY);
+
+    let repositories = vec![
+        repository(
+            "app",
+            vec![
+                main,
+                cache,
+                worktree("/wt/recent", Some(NOW - 29 * DAY)),
+                worktree("/wt/old", Some(NOW - 31 * DAY)),
+                worktree("/wt/older", Some(NOW - 60 * DAY - DAY / 2)),
+                worktree("/wt/unknown", None),
+            ],
+        ),
+        offline,
+    ];
+
+    let stale = find_stale_worktrees(&repositories, 30, NOW);
+    let paths: Vec<&str> = stale.iter().map(|w| w.path.as_str()).collect();
+    assert_eq!(paths, vec!["/wt/older", "/wt/old"]);
+    assert_eq!(stale[0].idle_days, 60);
+    assert_eq!(stale[0].repository_id, "app");
+    assert_eq!(stale[1].branch.as_deref(), Some("old"));
+
+    // Exactly at the threshold counts as stale
+    let stale = find_stale_worktrees(&repositories, 31, NOW);
+    assert_eq!(stale.len(), 2);
+}
+
+#[test]
+fn test_policy_keep_reason() {
+    let repositories = vec![repository("app", {
+        let mut locked = worktree("/wt/locked", Some(0));
+        locked.is_locked = true;
+        vec![locked, worktree("/wt/dirty", Some(0))]
+    })];
+    let stale = find_stale_worktrees(&repositories, 1, NOW);
+    let locked = stale.iter().find(|w| w.path == "/wt/locked").unwrap();
+    let dirty = stale.iter().find(|w| w.path == "/wt/dirty").unwrap();
+
+    let strict = StaleCleanupPolicy::default();
+    assert_eq!(
+        policy_keep_reason(locked, &strict, |_| false).as_deref(),
+        Some("Locked")
+    );
+    assert!(policy_keep_reason(dirty, &strict, |_| true).is_some());
+    assert!(policy_keep_reason(dirty, &strict, |_| false).is_none());
+
+    let loose = StaleCleanupPolicy {
+        only_clean: false,
+        only_unlocked: false,
+        ..StaleCleanupPolicy::default()
+    };
+    assert!(policy_keep_reason(locked, &loose, |_| true).is_none());
+    // Changes are not looked at when they would not keep the worktree
+    assert!(policy_keep_reason(dirty, &loose, |_| panic!("checked")).is_none());
+}
+
+#[test]
+fn test_validate_stale_cleanup_policy() {
+    assert!(validate_stale_cleanup_policy(&StaleCleanupPolicy::default()).is_ok());
+    let policy = StaleCleanupPolicy {
+        max_idle_days: 0,
+        ..StaleCleanupPolicy::default()
+    };
+    assert!(validate_stale_cleanup_policy(&policy).is_err());
+}
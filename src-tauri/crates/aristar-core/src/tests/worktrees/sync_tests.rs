@@ -0,0 +1,163 @@
+//! Tests for fetch, pull and push against a local bare remote.
+
+use tempfile::TempDir;
+
+use crate::tests::helpers::TestRepo;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::sync::{
+    describe_sync_error, fetch_worktree, pull_worktree, push_worktree, resolve_remote,
+    SyncOperation,
+};
+
+/// A bare remote seeded from a TestRepo, plus two clones of it.
+struct RemoteFixture {
+    _origin: TestRepo,
+    dir: TempDir,
+}
+
+impl RemoteFixture {
+    fn new() -> Self {
+        let origin = TestRepo::new();
+        let dir = TempDir::new().unwrap();
+        let base = dir.path().to_string_lossy().to_string();
+        run_git_command(
+            &[
+                "clone",
+                "--quiet",
+                "--bare",
+                &origin.path_str(),
+                "remote.git",
+            ],
+            &base,
+        )
+        .unwrap();
+        for clone in ["a", "b"] {
+            run_git_command(&["clone", "--quiet", "remote.git", clone], &base).unwrap();
+            let path = dir.path().join(clone).to_string_lossy().to_string();
+            run_git_command(&["config", "user.email", "test@example.com"], &path).unwrap();
+            run_git_command(&["config", "user.name", "Test User"], &path).unwrap();
+        }
+        RemoteFixture {
+            _origin: origin,
+            dir,
+        }
+    }
+
+    fn clone_path(&self, name: &str) -> String {
+        self.dir.path().join(name).to_string_lossy().to_string()
+    }
+
+    fn commit(&self, clone: &str, message: &str) {
+        let path = self.clone_path(clone);
+        std::fs::write(self.dir.path().join(clone).join(message), message).unwrap();
+        run_git_command(&["add", "."], &path).unwrap();
+        run_git_command(&["commit", "-m", message], &path).unwrap();
+    }
+}
+
+#[test]
+fn test_fetch_pull_and_push_between_clones() {
+    let fixture = RemoteFixture::new();
+    let a = fixture.clone_path("a");
+    let b = fixture.clone_path("b");
+
+    fixture.commit("a", "one");
+    fixture.commit("a", "two");
+    let pushed = push_worktree(&a, false).unwrap();
+    assert_eq!(pushed.operation, SyncOperation::Push);
+    assert_eq!(pushed.remote, "origin");
+    assert_eq!(pushed.commits, 2);
+    assert_eq!(pushed.ahead, 0);
+    assert!(!pushed.upstream_set);
+
+    let fetched = fetch_worktree(&b).unwrap();
+    assert_eq!(fetched.commits, 2);
+    assert_eq!(fetched.behind, 2);
+
+    let pulled = pull_worktree(&b, false).unwrap();
+    assert_eq!(pulled.commits, 2);
+    assert_eq!(pulled.behind, 0);
+
+    // Nothing new the second time
+    assert_eq!(fetch_worktree(&b).unwrap().commits, 0);
+}
+
+#[test]
+fn test_push_sets_upstream_for_new_branch() {
+    let fixture = RemoteFixture::new();
+    let a = fixture.clone_path("a");
+    run_git_command(&["checkout", "-b", "feature"], &a).unwrap();
+    fixture.commit("a", "feature-work");
+
+    assert!(pull_worktree(&a, false)
+        .unwrap_err()
+        .contains("has no upstream"));
+
+    let pushed = push_worktree(&a, false).unwrap();
+    assert!(pushed.upstream_set);
+    assert_eq!(pushed.branch.as_deref(), Some("feature"));
+    assert_eq!(pushed.upstream.as_deref(), Some("origin/feature"));
+    assert_eq!(pushed.commits, 1);
+
+    // The upstream is used from now on
+    fixture.commit("a", "more-work");
+    let pushed = push_worktree(&a, false).unwrap();
+    assert!(!pushed.upstream_set);
+    assert_eq!(pushed.commits, 1);
+}
+
+#[test]
+fn test_diverged_branches() {
+    let fixture = RemoteFixture::new();
+    let a = fixture.clone_path("a");
+    let b = fixture.clone_path("b");
+
+    fixture.commit("a", "from-a");
+    push_worktree(&a, false).unwrap();
+    fixture.commit("b", "from-b");
+
+    let err = push_worktree(&b, false).unwrap_err();
+    assert!(
+        err.starts_with("Push failed: the remote has commits"),
+        "{}",
+        err
+    );
+
+    let err = pull_worktree(&b, false).unwrap_err();
+    assert!(
+        err.starts_with("Pull failed: the branch has diverged"),
+        "{}",
+        err
+    );
+
+    let pulled = pull_worktree(&b, true).unwrap();
+    assert_eq!(pulled.commits, 1);
+    assert_eq!(pulled.ahead, 1);
+    assert_eq!(pulled.behind, 0);
+    assert_eq!(push_worktree(&b, false).unwrap().commits, 1);
+}
+
+#[test]
+fn test_resolve_remote_and_errors() {
+    let repo = TestRepo::new();
+    assert!(resolve_remote(&repo.path_str(), None)
+        .unwrap_err()
+        .contains("No remote"));
+
+    run_git_command(&["checkout", "--detach"], &repo.path_str()).unwrap();
+    assert!(push_worktree(&repo.path_str(), false)
+        .unwrap_err()
+        .contains("detached"));
+
+    let message = describe_sync_error(
+        SyncOperation::Fetch,
+        "fatal: unable to access 'https://example.com/x.git/': Could not resolve host: example.com\n",
+    );
+    assert!(message.starts_with("Fetch failed: the remote could not be reached\n\n"));
+    assert!(message.ends_with("Could not resolve host: example.com"));
+
+    assert_eq!(
+        describe_sync_error(SyncOperation::Pull, "error: something odd"),
+        "Pull failed: error: something odd"
+    );
+}
@@ -0,0 +1,67 @@
+//! Tests for startup script template variables.
+
+use crate::worktrees::templates::{render_script, uses_variable, ScriptTemplateContext};
+
+fn context() -> ScriptTemplateContext {
+    ScriptTemplateContext {
+        branch: Some("feature/login".to_string()),
+        worktree_path: Some("/Users/me/.aristar-worktrees/abc/login".to_string()),
+        repo_name: Some("webapp".to_string()),
+        task_id: None,
+        port: Some("41007".to_string()),
+    }
+}
+
+#[test]
+fn test_render_script_replaces_variables() {
+    let rendered = render_script(
+        "cd \"{{worktree_path}}\"\necho {{ branch }} {{repo_name}} [{{task_id}}]",
+        &context(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        rendered,
+        "cd \"/Users/me/.aristar-worktrees/abc/login\"\necho feature/login webapp []"
+    );
+}
+
+#[test]
+fn test_port_variable() {
+    let script = "npm run dev -- --port {{ port }}";
+    assert!(uses_variable(script, "port"));
+    assert!(!uses_variable("echo {{ports}} {{port", "port"));
+    assert_eq!(
+        render_script(script, &context()).unwrap(),
+        "npm run dev -- --port 41007"
+    );
+}
+
+#[test]
+fn test_render_script_rejects_unknown_variables() {
+    let err = render_script("echo {{branch_name}}", &context()).unwrap_err();
+    assert!(err.contains("branch_name"));
+    assert!(err.contains("worktree_path"));
+}
+
+#[test]
+fn test_render_script_leaves_other_braces() {
+    let script = "echo {{ $HOME }} {{Name}} ${x} {{unclosed";
+    assert_eq!(render_script(script, &context()).unwrap(), script);
+    assert_eq!(
+        render_script("no variables", &context()).unwrap(),
+        "no variables"
+    );
+}
+
+#[test]
+fn test_render_script_rejects_unsafe_values() {
+    let context = ScriptTemplateContext {
+        branch: Some("x$(rm -rf ~)".to_string()),
+        ..context()
+    };
+
+    assert!(render_script("git log {{branch}}", &context).is_err());
+    // Unused unsafe values are fine
+    assert!(render_script("echo {{repo_name}}", &context).is_ok());
+}
@@ -0,0 +1,443 @@
+//! Local WebSocket exposing app events to external tools.
+//!
+//! Opt-in with `settings.event_socket_port` (0 takes a port from the shared
+//! registry, see `core::ports`). The server listens on 127.0.0.1 only and
+//! forwards the events `core::events` marks as forwarded to every connected client as
+//! `{"event": name, "payload": ...}` text frames, so dashboards or scripts can
+//! react to e.g. agent completions without polling. Clients authenticate with the
+//! token written to `~/.aristar-worktrees/event-socket.json` on startup, passed as
+//! `?token=` (browsers can't set headers) or `Authorization: Bearer`. The socket
+//! is one-way: client messages other than ping and close are ignored.
+//!
+//! There is no WebSocket crate among the dependencies, so the handshake and
+//! framing are implemented here. Each client has a bounded queue drained by its
+//! own writer thread; a client whose queue fills up or whose writes time out is
+//! disconnected, so a stalled reader can't hold up broadcasts. At most
+//! `MAX_CLIENTS` connections are served at once.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use schemars::JsonSchema;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::{get_aristar_worktrees_base, ports};
+use crate::worktrees::store::AppState;
+
+/// App events forwarded to socket clients.
+pub use crate::core::events::forwarded_events;
+
+/// File with the port and token clients connect with.
+pub const EVENT_SOCKET_INFO_FILE: &str = "event-socket.json";
+
+/// GUID appended to the client key when computing `Sec-WebSocket-Accept`.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Longest handshake request accepted.
+const MAX_HANDSHAKE_BYTES: usize = 8 * 1024;
+
+/// Largest client frame accepted (clients only send control frames).
+const MAX_CLIENT_FRAME_BYTES: u64 = 64 * 1024;
+
+/// Frames queued for a client before it is dropped as too slow.
+const CLIENT_QUEUE_FRAMES: usize = 256;
+
+/// A write to a client taking longer than this drops the client.
+const CLIENT_WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connections served at once (including unfinished handshakes); further ones
+/// are closed right away.
+pub const MAX_CLIENTS: usize = 32;
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// Contents of `event-socket.json`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EventSocketInfo {
+    pub port: u16,
+    pub token: String,
+}
+
+/// `Sec-WebSocket-Accept` value for a client's `Sec-WebSocket-Key`.
+pub fn websocket_accept_key(key: &str) -> String {
+    STANDARD.encode(Sha1::digest(format!("{}{}", key.trim(), WEBSOCKET_GUID)))
+}
+
+/// The parts of a WebSocket upgrade request the server needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handshake {
+    pub key: String,
+    /// From `?token=` or `Authorization: Bearer`
+    pub token: Option<String>,
+}
+
+/// Parse an HTTP upgrade request (request line and headers).
+pub fn parse_handshake(request: &str) -> Result<Handshake, String> {
+    let mut lines = request.lines();
+    let request_line = lines.next().unwrap_or_default();
+    let target = match request_line
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        ["GET", target, _] => target.to_string(),
+        _ => return Err("Expected a GET request".to_string()),
+    };
+
+    let mut key = None;
+    let mut upgrade = false;
+    let mut token = target
+        .split_once('?')
+        .and_then(|(_, query)| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("token="))
+        })
+        .map(str::to_string);
+
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_lowercase().as_str() {
+            "upgrade" => upgrade = value.eq_ignore_ascii_case("websocket"),
+            "sec-websocket-key" => key = Some(value.to_string()),
+            "authorization" => {
+                if let Some(bearer) = value.strip_prefix("Bearer ") {
+                    token = Some(bearer.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !upgrade {
+        return Err("Not a WebSocket upgrade request".to_string());
+    }
+    Ok(Handshake {
+        key: key.ok_or("Missing Sec-WebSocket-Key")?,
+        token,
+    })
+}
+
+/// Compare tokens without returning early on the first mismatch.
+pub fn token_matches(given: Option<&str>, expected: &str) -> bool {
+    let Some(given) = given else {
+        return false;
+    };
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Encode an unmasked (server to client) frame.
+pub fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Read one client frame, returning its opcode and unmasked payload.
+pub fn read_frame(reader: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header)?;
+    let opcode = header[0] & 0x0F;
+    let masked = header[1] & 0x80 != 0;
+
+    let len = match header[1] & 0x7F {
+        126 => {
+            let mut bytes = [0u8; 2];
+            reader.read_exact(&mut bytes)?;
+            u64::from(u16::from_be_bytes(bytes))
+        }
+        127 => {
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes)?;
+            u64::from_be_bytes(bytes)
+        }
+        len => u64::from(len),
+    };
+    if len > MAX_CLIENT_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Frame too large",
+        ));
+    }
+
+    let mut mask = [0u8; 4];
+    if masked {
+        reader.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok((opcode, payload))
+}
+
+/// A connected client: the queue of frames its writer thread sends, and its
+/// stream to disconnect it when the queue fills up.
+struct Client {
+    id: u64,
+    queue: SyncSender<Vec<u8>>,
+    stream: TcpStream,
+}
+
+type Clients = Arc<Mutex<Vec<Client>>>;
+
+/// Counts a connection as open until dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A running event socket server.
+pub struct EventSocket {
+    pub port: u16,
+    clients: Clients,
+}
+
+impl EventSocket {
+    /// Listen on 127.0.0.1:`port` (0 picks a free port) and accept clients
+    /// presenting `token` in a background thread.
+    pub fn start(port: u16, token: String) -> Result<Self, String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| format!("Failed to bind event socket on port {}: {}", port, e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        let clients: Clients = Arc::new(Mutex::new(Vec::new()));
+
+        let accept_clients = clients.clone();
+        let token = Arc::new(token);
+        std::thread::spawn(move || {
+            let next_id = AtomicU64::new(0);
+            let open = Arc::new(AtomicUsize::new(0));
+            for stream in listener.incoming().flatten() {
+                if open.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+                    open.fetch_sub(1, Ordering::SeqCst);
+                    let _ = stream.shutdown(Shutdown::Both);
+                    continue;
+                }
+                let slot = ConnectionSlot(open.clone());
+                let id = next_id.fetch_add(1, Ordering::Relaxed);
+                let clients = accept_clients.clone();
+                let token = token.clone();
+                std::thread::spawn(move || {
+                    let _slot = slot;
+                    handle_client(stream, id, &token, &clients)
+                });
+            }
+        });
+
+        Ok(EventSocket { port, clients })
+    }
+
+    /// Queue an event for every connected client, disconnecting clients that
+    /// are gone or too far behind. Never waits on a client.
+    pub fn broadcast(&self, event: &str, payload: serde_json::Value) {
+        let message = serde_json::json!({ "event": event, "payload": payload }).to_string();
+        let frame = encode_frame(OPCODE_TEXT, message.as_bytes());
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.retain(|client| {
+                let queued = client.queue.try_send(frame.clone()).is_ok();
+                if !queued {
+                    // Ends the client's read loop, which drops its queue
+                    let _ = client.stream.shutdown(Shutdown::Both);
+                }
+                queued
+            });
+        }
+    }
+}
+
+fn read_handshake(stream: &TcpStream) -> Result<String, String> {
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut request = String::new();
+    loop {
+        let mut line = String::new();
+        let read = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        if read == 0 || line == "\r\n" || line == "\n" {
+            return Ok(request);
+        }
+        request.push_str(&line);
+        if request.len() > MAX_HANDSHAKE_BYTES {
+            return Err("Handshake too large".to_string());
+        }
+    }
+}
+
+fn handle_client(mut stream: TcpStream, id: u64, token: &str, clients: &Clients) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let handshake = read_handshake(&stream).and_then(|r| parse_handshake(&r));
+
+    let handshake = match handshake {
+        Ok(h) if token_matches(h.token.as_deref(), token) => h,
+        Ok(_) => {
+            let _ = stream.write_all(
+                b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            );
+            return;
+        }
+        Err(e) => {
+            let response = format!(
+                "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                e.len(),
+                e
+            );
+            let _ = stream.write_all(response.as_bytes());
+            return;
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        websocket_accept_key(&handshake.key)
+    );
+    if stream.write_all(response.as_bytes()).is_err() {
+        return;
+    }
+    let (Ok(mut writer), Ok(registered)) = (stream.try_clone(), stream.try_clone()) else {
+        return;
+    };
+    let _ = writer.set_write_timeout(Some(CLIENT_WRITE_TIMEOUT));
+    let _ = stream.set_read_timeout(None);
+
+    // All writes go through the queue so they don't interleave with broadcasts.
+    // The writer closes the connection once the queue is dropped (the read loop
+    // ended, also when `broadcast` shuts the stream down) or a write fails.
+    let (queue, frames) = mpsc::sync_channel::<Vec<u8>>(CLIENT_QUEUE_FRAMES);
+    std::thread::spawn(move || {
+        for frame in frames {
+            if writer.write_all(&frame).is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown(Shutdown::Both);
+    });
+    if let Ok(mut clients) = clients.lock() {
+        clients.push(Client {
+            id,
+            queue: queue.clone(),
+            stream: registered,
+        });
+    }
+
+    loop {
+        match read_frame(&mut stream) {
+            Ok((OPCODE_PING, payload)) => {
+                let _ = queue.try_send(encode_frame(OPCODE_PONG, &payload));
+            }
+            Ok((OPCODE_CLOSE, payload)) => {
+                let _ = queue.try_send(encode_frame(OPCODE_CLOSE, &payload));
+                break;
+            }
+            Ok(_) => {}
+            Err(_) => break,
+        }
+    }
+
+    if let Ok(mut clients) = clients.lock() {
+        clients.retain(|client| client.id != id);
+    }
+}
+
+fn write_info_file(info: &EventSocketInfo) -> Result<(), String> {
+    let path = get_aristar_worktrees_base().join(EVENT_SOCKET_INFO_FILE);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+
+    // Created owner-only, so the token is never readable by others
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    // A file left by an older version keeps its mode when opened
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+    file.write_all(json.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Start the event socket if `settings.event_socket_port` is set, with a new
+/// token for this run. The caller forwards app events to it with `broadcast`.
+pub fn start_configured_event_socket(state: &AppState) -> Option<Arc<EventSocket>> {
+    let port = match state.store.read() {
+        Ok(store) => store.settings.event_socket_port,
+        Err(_) => None,
+    }?;
+    // 0 keeps the port reserved for the event socket, or reserves a new one
+    let port = match port {
+        0 => ports::reserve_port(ports::EVENT_SOCKET_SERVICE, ports::APP_OWNER, None),
+        port => {
+            ports::claim_port(ports::EVENT_SOCKET_SERVICE, ports::APP_OWNER, port).map(|_| port)
+        }
+    };
+    let port = match port {
+        Ok(port) => port,
+        Err(e) => {
+            eprintln!("[workspace] Event socket not started: {}", e);
+            return None;
+        }
+    };
+
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let socket = match EventSocket::start(port, token.clone()) {
+        Ok(socket) => Arc::new(socket),
+        Err(e) => {
+            eprintln!("[workspace] {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = write_info_file(&EventSocketInfo {
+        port: socket.port,
+        token,
+    }) {
+        eprintln!("[workspace] Failed to write event socket info: {}", e);
+    }
+    println!(
+        "[workspace] Event socket listening on 127.0.0.1:{}",
+        socket.port
+    );
+    Some(socket)
+}
@@ -0,0 +1,18 @@
+//! Workspace module - Aggregates across repositories, worktrees and tasks.
+//!
+//! This module contains:
+//! - Global status counters for the status bar
+//! - Background refresh that pushes `global-status` events
+//! - Opt-in localhost WebSocket forwarding app events to external tools
+//! - Snapshots of the whole workspace for switching between demo and real data
+//! - Startup recovery of operations interrupted by a crash
+//! - Full-text search across repositories, worktrees, tasks, presets and schedules
+
+pub mod event_socket;
+pub mod recovery;
+pub mod search;
+pub mod snapshots;
+pub mod status;
+pub mod types;
+
+pub use status::GlobalStatusCache;
@@ -0,0 +1,227 @@
+//! Startup recovery of operations interrupted by a crash.
+//!
+//! Replays the intents left in `core::journal`:
+//!
+//! - `create-task`: when the task never made it into `tasks.json`, its agent
+//!   worktrees, snapshot and checkpoint refs and task folder are removed.
+//! - `remove-worktree`: the removal is finished (`git worktree remove --force`,
+//!   `git worktree prune`) and the worktree dropped from the repository store.
+//!
+//! Repaired intents leave the journal; failed ones stay and are retried on the
+//! next start. In read-only mode nothing is touched and intents are reported as
+//! pending. `get_recovery_report` returns what the last run did.
+
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::agent_manager::snapshot::{delete_checkpoint_refs, delete_snapshot_ref};
+use crate::agent_manager::TaskManagerState;
+use crate::core::journal::{
+    discard_intent_in, get_journal_dir, pending_intents_in, JournalIntent, JournalOperation,
+};
+use crate::core::{is_read_only, paths_equal};
+use crate::worktrees::list_cache::invalidate_worktree_list_cache;
+use crate::worktrees::operations::{run_git_command, worktree_display_name};
+use crate::worktrees::repo_queue::run_queued;
+use crate::worktrees::store::AppState;
+
+use super::types::{RecoveryAction, RecoveryOutcome, RecoveryReport};
+
+static LAST_REPORT: RwLock<Option<RecoveryReport>> = RwLock::new(None);
+
+/// Whether `worktree_path` is still registered as a worktree of `repo_path`.
+fn is_registered_worktree(repo_path: &str, worktree_path: &str) -> bool {
+    let Ok(output) = run_git_command(&["worktree", "list", "--porcelain"], repo_path) else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix("worktree "))
+        .any(|path| paths_equal(path, worktree_path))
+}
+
+/// Remove a worktree from git and disk, whatever state it was left in, queued
+/// with the repository's other worktree operations. Returns the steps taken.
+fn force_remove_worktree(repo_path: &str, worktree_path: &str) -> Result<Vec<String>, String> {
+    let operation = format!("remove worktree {}", worktree_display_name(worktree_path));
+    let steps = run_queued(repo_path, &operation, || {
+        let mut steps = Vec::new();
+        if is_registered_worktree(repo_path, worktree_path) {
+            if Path::new(worktree_path).exists() {
+                run_git_command(
+                    &["worktree", "remove", "--force", "--force", worktree_path],
+                    repo_path,
+                )?;
+            }
+            steps.push(format!("Removed worktree {}", worktree_path));
+        }
+        if Path::new(worktree_path).exists() {
+            fs::remove_dir_all(worktree_path)
+                .map_err(|e| format!("Failed to delete {}: {}", worktree_path, e))?;
+            steps.push(format!("Deleted folder {}", worktree_path));
+        }
+        run_git_command(&["worktree", "prune"], repo_path)?;
+        Ok::<_, String>(steps)
+    })?;
+    invalidate_worktree_list_cache(repo_path);
+    Ok(steps)
+}
+
+/// Undo a task creation that never reached the store: its worktrees, refs and
+/// folder. The folder is only deleted when it is named after the task.
+pub fn roll_back_create_task(
+    task_id: &str,
+    source_repo_path: &str,
+    task_folder: &str,
+    worktree_paths: &[String],
+) -> Result<Vec<String>, String> {
+    let mut steps = Vec::new();
+    if Path::new(source_repo_path).exists() {
+        for worktree_path in worktree_paths {
+            steps.extend(force_remove_worktree(source_repo_path, worktree_path)?);
+        }
+        delete_snapshot_ref(source_repo_path, task_id);
+        delete_checkpoint_refs(source_repo_path, task_id);
+        steps.push(format!("Deleted refs of task {}", task_id));
+    }
+
+    let folder = Path::new(task_folder);
+    if folder.exists() && folder.file_name().is_some_and(|name| name == task_id) {
+        fs::remove_dir_all(folder)
+            .map_err(|e| format!("Failed to delete {}: {}", task_folder, e))?;
+        steps.push(format!("Deleted task folder {}", task_folder));
+    }
+    Ok(steps)
+}
+
+/// Finish removing a worktree from git. Returns the steps taken.
+pub fn roll_forward_remove_worktree(
+    repo_path: &str,
+    worktree_path: &str,
+) -> Result<Vec<String>, String> {
+    if !Path::new(repo_path).exists() {
+        return Ok(Vec::new());
+    }
+    force_remove_worktree(repo_path, worktree_path)
+}
+
+fn recover_intent(
+    intent: &JournalIntent,
+    app_state: &AppState,
+    task_state: &TaskManagerState,
+) -> RecoveryAction {
+    let (operation, target) = match &intent.operation {
+        JournalOperation::CreateTask { task_id, .. } => ("create-task", task_id.clone()),
+        JournalOperation::RemoveWorktree { worktree_path, .. } => {
+            ("remove-worktree", worktree_path.clone())
+        }
+    };
+    let mut action = RecoveryAction {
+        intent_id: intent.id.clone(),
+        operation: operation.to_string(),
+        target,
+        started_at: intent.started_at,
+        outcome: RecoveryOutcome::Pending,
+        steps: Vec::new(),
+        error: None,
+    };
+    if is_read_only() {
+        return action;
+    }
+
+    let result = match &intent.operation {
+        JournalOperation::CreateTask {
+            task_id,
+            source_repo_path,
+            task_folder,
+            worktree_paths,
+        } => {
+            let saved = task_state
+                .store
+                .lock()
+                .map(|store| store.tasks.iter().any(|t| &t.id == task_id))
+                .unwrap_or(true);
+            if saved {
+                Ok((RecoveryOutcome::NothingToDo, Vec::new()))
+            } else {
+                roll_back_create_task(task_id, source_repo_path, task_folder, worktree_paths)
+                    .map(|steps| (RecoveryOutcome::RolledBack, steps))
+            }
+        }
+        JournalOperation::RemoveWorktree {
+            repo_path,
+            worktree_path,
+        } => roll_forward_remove_worktree(repo_path, worktree_path).and_then(|mut steps| {
+            if app_state.get_worktree(worktree_path)?.is_some() {
+                app_state.remove_worktree_entry(worktree_path)?;
+                app_state.save()?;
+                steps.push("Removed it from the repository list".to_string());
+            }
+            let outcome = if steps.is_empty() {
+                RecoveryOutcome::NothingToDo
+            } else {
+                RecoveryOutcome::RolledForward
+            };
+            Ok((outcome, steps))
+        }),
+    };
+
+    match result {
+        Ok((outcome, steps)) => {
+            action.outcome = outcome;
+            action.steps = steps;
+        }
+        Err(e) => {
+            action.outcome = RecoveryOutcome::Failed;
+            action.error = Some(e);
+        }
+    }
+    action
+}
+
+/// Recover the intents left in the journal at `dir`.
+pub fn recover_intents_in(
+    dir: &Path,
+    app_state: &AppState,
+    task_state: &TaskManagerState,
+) -> RecoveryReport {
+    let mut report = RecoveryReport {
+        ran_at: Utc::now().timestamp_millis(),
+        actions: Vec::new(),
+    };
+    for intent in pending_intents_in(dir) {
+        let action = recover_intent(&intent, app_state, task_state);
+        let done = !matches!(
+            action.outcome,
+            RecoveryOutcome::Pending | RecoveryOutcome::Failed
+        );
+        if done {
+            if let Err(e) = discard_intent_in(dir, &intent.id) {
+                eprintln!("[recovery] {}", e);
+            }
+        }
+        println!(
+            "[recovery] {} {}: {:?}",
+            action.operation, action.target, action.outcome
+        );
+        report.actions.push(action);
+    }
+    report
+}
+
+/// Recover interrupted operations at startup and keep the report.
+pub fn recover_on_startup(app_state: &AppState, task_state: &TaskManagerState) {
+    let report = recover_intents_in(&get_journal_dir(), app_state, task_state);
+    *LAST_REPORT.write().unwrap_or_else(|e| e.into_inner()) = Some(report);
+}
+
+/// What startup recovery did; empty before it ran.
+pub fn get_recovery_report() -> RecoveryReport {
+    LAST_REPORT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
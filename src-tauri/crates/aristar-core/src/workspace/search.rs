@@ -0,0 +1,432 @@
+//! Full-text search across repositories, worktrees, tasks, presets and
+//! schedules.
+//!
+//! A small inverted index: every entity becomes a document whose fields
+//! (names, branches, prompts, notes) are split into lowercase terms, each term
+//! weighted by its field. The index lives in memory and in
+//! `~/.aristar-worktrees/search-index.json`. It is brought up to date on the
+//! first search after a store save (`core::store_generation`): only documents
+//! whose content hash changed are re-indexed.
+//!
+//! Queries match terms by prefix, all query terms must match, and results are
+//! ranked by field weight, term frequency and rarity (idf), with exact terms
+//! scoring above prefixes.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::agent_manager::scratchpad::get_task_scratchpad_path;
+use crate::agent_manager::types::TaskStoreData;
+use crate::core::{get_aristar_worktrees_base, store_generation};
+use crate::worktrees::types::Repository;
+
+use super::types::{SearchEntityKind, SearchResult};
+
+/// Results returned when no limit is given.
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Field weights.
+const NAME_WEIGHT: f32 = 3.0;
+const BRANCH_WEIGHT: f32 = 2.0;
+const TEXT_WEIGHT: f32 = 1.0;
+const DETAIL_WEIGHT: f32 = 0.5;
+
+/// Score factor of a term matched by prefix rather than exactly.
+const PREFIX_FACTOR: f32 = 0.5;
+
+/// An entity to index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchDocument {
+    pub kind: SearchEntityKind,
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub parent_id: Option<String>,
+    /// `(field, text, weight)`
+    pub fields: Vec<(&'static str, String, f32)>,
+}
+
+impl SearchDocument {
+    fn key(&self) -> String {
+        document_key(self.kind, &self.id)
+    }
+
+    fn content_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for part in [&self.title, self.subtitle.as_deref().unwrap_or("")] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        for (field, text, _) in &self.fields {
+            hasher.update(field.as_bytes());
+            hasher.update([0]);
+            hasher.update(text.as_bytes());
+            hasher.update([0]);
+        }
+        hex::encode(&hasher.finalize()[..16])
+    }
+}
+
+fn document_key(kind: SearchEntityKind, id: &str) -> String {
+    format!("{:?}:{}", kind, id)
+}
+
+/// A term occurrence in a document field.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+struct Posting {
+    doc: String,
+    field: String,
+    weight: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct IndexedDocument {
+    kind: SearchEntityKind,
+    id: String,
+    title: String,
+    subtitle: Option<String>,
+    parent_id: Option<String>,
+    hash: String,
+    terms: Vec<String>,
+}
+
+/// The inverted index.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct SearchIndex {
+    documents: HashMap<String, IndexedDocument>,
+    /// Sorted, so prefixes are a range
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+/// Split text into lowercase alphanumeric terms.
+/// e.g., "feature/PROJ-123 Login" -> ["feature", "proj", "123", "login"]
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl SearchIndex {
+    fn remove(&mut self, key: &str) {
+        let Some(doc) = self.documents.remove(key) else {
+            return;
+        };
+        for term in doc.terms {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                postings.retain(|p| p.doc != key);
+                if postings.is_empty() {
+                    self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    fn insert(&mut self, doc: &SearchDocument, hash: String) {
+        let key = doc.key();
+        // term -> field -> occurrences
+        let mut counts: BTreeMap<String, BTreeMap<&str, (usize, f32)>> = BTreeMap::new();
+        for (field, text, weight) in &doc.fields {
+            for term in tokenize(text) {
+                let entry = counts
+                    .entry(term)
+                    .or_default()
+                    .entry(field)
+                    .or_insert((0, *weight));
+                entry.0 += 1;
+            }
+        }
+        for (term, fields) in &counts {
+            let postings = self.postings.entry(term.clone()).or_default();
+            for (field, (count, weight)) in fields {
+                postings.push(Posting {
+                    doc: key.clone(),
+                    field: field.to_string(),
+                    weight: weight * (1.0 + (*count as f32).ln()),
+                });
+            }
+        }
+        self.documents.insert(
+            key,
+            IndexedDocument {
+                kind: doc.kind,
+                id: doc.id.clone(),
+                title: doc.title.clone(),
+                subtitle: doc.subtitle.clone(),
+                parent_id: doc.parent_id.clone(),
+                hash,
+                terms: counts.into_keys().collect(),
+            },
+        );
+    }
+
+    /// Bring the index in line with `docs`: documents gone are removed, new or
+    /// changed ones (re-)indexed. Returns the number of documents touched.
+    pub fn sync(&mut self, docs: &[SearchDocument]) -> usize {
+        let keys: HashMap<String, &SearchDocument> = docs.iter().map(|d| (d.key(), d)).collect();
+        let stale: Vec<String> = self
+            .documents
+            .keys()
+            .filter(|k| !keys.contains_key(*k))
+            .cloned()
+            .collect();
+        let mut touched = stale.len();
+        for key in stale {
+            self.remove(&key);
+        }
+
+        for (key, doc) in keys {
+            let hash = doc.content_hash();
+            if self.documents.get(&key).is_some_and(|d| d.hash == hash) {
+                continue;
+            }
+            self.remove(&key);
+            self.insert(doc, hash);
+            touched += 1;
+        }
+        touched
+    }
+
+    /// Documents matching every term of `query`, best first.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchResult> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let total = self.documents.len().max(1) as f32;
+
+        // doc -> (score, fields, query terms matched)
+        let mut hits: HashMap<&str, (f32, Vec<String>, usize)> = HashMap::new();
+        for query_term in &terms {
+            let mut matched: HashMap<&str, (f32, Vec<String>)> = HashMap::new();
+            for (term, postings) in self
+                .postings
+                .range(query_term.clone()..)
+                .take_while(|(term, _)| term.starts_with(query_term.as_str()))
+            {
+                let frequency = postings.len() as f32;
+                let idf = (1.0 + total / frequency).ln();
+                let factor = if term == query_term {
+                    1.0
+                } else {
+                    PREFIX_FACTOR
+                };
+                for posting in postings {
+                    let entry = matched.entry(posting.doc.as_str()).or_default();
+                    entry.0 += posting.weight * idf * factor;
+                    if !entry.1.contains(&posting.field) {
+                        entry.1.push(posting.field.clone());
+                    }
+                }
+            }
+            for (doc, (score, fields)) in matched {
+                let hit = hits.entry(doc).or_default();
+                hit.0 += score;
+                hit.2 += 1;
+                for field in fields {
+                    if !hit.1.contains(&field) {
+                        hit.1.push(field);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<SearchResult> = hits
+            .into_iter()
+            .filter(|(_, (_, _, count))| *count == terms.len())
+            .filter_map(|(key, (score, mut fields, _))| {
+                let doc = self.documents.get(key)?;
+                fields.sort();
+                Some(SearchResult {
+                    kind: doc.kind,
+                    id: doc.id.clone(),
+                    title: doc.title.clone(),
+                    subtitle: doc.subtitle.clone(),
+                    parent_id: doc.parent_id.clone(),
+                    score,
+                    matched_fields: fields,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.title.cmp(&b.title))
+        });
+        results.truncate(limit);
+        results
+    }
+}
+
+/// The documents to index for the repositories and the task store. Task
+/// scratchpads are read from `scratchpad_path(task_id)`.
+pub fn collect_documents(
+    repositories: &[Repository],
+    tasks: &TaskStoreData,
+    scratchpad_path: impl Fn(&str) -> PathBuf,
+) -> Vec<SearchDocument> {
+    let mut docs = Vec::new();
+    for repo in repositories {
+        let title = repo
+            .display_name
+            .clone()
+            .unwrap_or_else(|| repo.name.clone());
+        docs.push(SearchDocument {
+            kind: SearchEntityKind::Repository,
+            id: repo.id.clone(),
+            title: title.clone(),
+            subtitle: Some(repo.path.clone()),
+            parent_id: None,
+            fields: vec![
+                ("name", format!("{} {}", title, repo.name), NAME_WEIGHT),
+                ("path", repo.path.clone(), DETAIL_WEIGHT),
+            ],
+        });
+        for worktree in &repo.worktrees {
+            let branch = worktree.branch.clone().unwrap_or_default();
+            docs.push(SearchDocument {
+                kind: SearchEntityKind::Worktree,
+                id: worktree.path.clone(),
+                title: worktree.name.clone(),
+                subtitle: worktree.branch.clone(),
+                parent_id: Some(repo.id.clone()),
+                fields: vec![
+                    ("name", worktree.name.clone(), NAME_WEIGHT),
+                    ("branch", branch, BRANCH_WEIGHT),
+                ],
+            });
+        }
+    }
+
+    for task in &tasks.tasks {
+        let mut notes = fs::read_to_string(scratchpad_path(&task.id)).unwrap_or_default();
+        for agent in &task.agents {
+            for comment in &agent.review_comments {
+                notes.push('\n');
+                notes.push_str(&comment.body);
+            }
+        }
+        let timeline: Vec<&str> = task.timeline.iter().map(|e| e.message.as_str()).collect();
+        docs.push(SearchDocument {
+            kind: SearchEntityKind::Task,
+            id: task.id.clone(),
+            title: task.name.clone(),
+            subtitle: task.source_branch.clone(),
+            parent_id: None,
+            fields: vec![
+                ("name", task.name.clone(), NAME_WEIGHT),
+                (
+                    "branch",
+                    task.source_branch.clone().unwrap_or_default(),
+                    BRANCH_WEIGHT,
+                ),
+                (
+                    "summary",
+                    task.result_summary.clone().unwrap_or_default(),
+                    TEXT_WEIGHT,
+                ),
+                ("notes", notes, TEXT_WEIGHT),
+                ("timeline", timeline.join("\n"), DETAIL_WEIGHT),
+            ],
+        });
+    }
+
+    for saved in &tasks.presets {
+        docs.push(SearchDocument {
+            kind: SearchEntityKind::Preset,
+            id: saved.id.clone(),
+            title: saved.preset.name.clone(),
+            subtitle: saved.preset.source_branch.clone(),
+            parent_id: None,
+            fields: vec![
+                ("name", saved.preset.name.clone(), NAME_WEIGHT),
+                (
+                    "prompt",
+                    saved.preset.prompt.clone().unwrap_or_default(),
+                    TEXT_WEIGHT,
+                ),
+            ],
+        });
+    }
+    for schedule in &tasks.schedules {
+        docs.push(SearchDocument {
+            kind: SearchEntityKind::Schedule,
+            id: schedule.id.clone(),
+            title: schedule.name.clone(),
+            subtitle: Some(schedule.cron.clone()),
+            parent_id: None,
+            fields: vec![
+                ("name", schedule.name.clone(), NAME_WEIGHT),
+                (
+                    "prompt",
+                    schedule.preset.prompt.clone().unwrap_or_default(),
+                    TEXT_WEIGHT,
+                ),
+            ],
+        });
+    }
+    docs
+}
+
+/// The index file: ~/.aristar-worktrees/search-index.json
+pub fn get_search_index_path() -> PathBuf {
+    get_aristar_worktrees_base().join("search-index.json")
+}
+
+/// Load an index written by `save_search_index`; empty when missing or broken.
+pub fn load_search_index(path: &Path) -> SearchIndex {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_search_index(path: &Path, index: &SearchIndex) -> Result<(), String> {
+    let json =
+        serde_json::to_string(index).map_err(|e| format!("Failed to serialize index: {}", e))?;
+    fs::write(path, json).map_err(|e| format!("Failed to write search index: {}", e))
+}
+
+/// The index and the store generation it reflects (`None` before the first sync).
+struct LiveIndex {
+    index: Option<SearchIndex>,
+    generation: Option<u64>,
+}
+
+static LIVE_INDEX: Mutex<LiveIndex> = Mutex::new(LiveIndex {
+    index: None,
+    generation: None,
+});
+
+/// Search everything, syncing the index first when a store changed since the
+/// last search.
+pub fn global_search(
+    repositories: impl FnOnce() -> Vec<Repository>,
+    tasks: impl FnOnce() -> TaskStoreData,
+    query: &str,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut live = LIVE_INDEX.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_search_index_path();
+    let generation = store_generation();
+    if live.generation != Some(generation) {
+        let docs = collect_documents(&repositories(), &tasks(), get_task_scratchpad_path);
+        let index = live.index.get_or_insert_with(|| load_search_index(&path));
+        if index.sync(&docs) > 0 {
+            if let Err(e) = save_search_index(&path, index) {
+                eprintln!("[search] {}", e);
+            }
+        }
+        live.generation = Some(generation);
+    }
+    live.index
+        .as_ref()
+        .map(|index| index.search(query, limit))
+        .unwrap_or_default()
+}
@@ -0,0 +1,189 @@
+//! Workspace snapshots for switching between demo data and real data.
+//!
+//! A snapshot holds the repository store (including settings), the task store
+//! and the frontend's UI state, as `~/.aristar-worktrees/snapshots/{name}.json`.
+//! Worktree contents are not part of it: loading a snapshot only swaps what the
+//! app knows about, never touches the repositories on disk.
+//!
+//! Loading first saves the current workspace as the `autosave` snapshot, so a
+//! load can always be undone. It is refused while OpenCode servers are running,
+//! since they belong to the current tasks.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::agent_manager::types::TaskStoreData;
+use crate::agent_manager::TaskManagerState;
+use crate::core::{get_aristar_worktrees_base, save_json_store};
+use crate::worktrees::store::AppState;
+use crate::worktrees::types::StoreData;
+
+/// Folder holding the snapshots.
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Snapshot written before another one is loaded.
+pub const AUTOSAVE_SNAPSHOT: &str = "autosave";
+
+/// Longest allowed snapshot name.
+pub const MAX_SNAPSHOT_NAME_LEN: usize = 64;
+
+/// Everything needed to restore a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSnapshot {
+    pub name: String,
+    /// Epoch millis
+    pub created_at: i64,
+    pub store: StoreData,
+    pub tasks: TaskStoreData,
+    /// Opaque UI state from the frontend (persisted stores)
+    #[serde(default)]
+    pub ui_state: Option<serde_json::Value>,
+}
+
+/// Summary of a saved snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceSnapshotInfo {
+    pub name: String,
+    pub created_at: i64,
+    pub repository_count: usize,
+    pub task_count: usize,
+}
+
+impl WorkspaceSnapshot {
+    pub fn info(&self) -> WorkspaceSnapshotInfo {
+        WorkspaceSnapshotInfo {
+            name: self.name.clone(),
+            created_at: self.created_at,
+            repository_count: self.store.repositories.len(),
+            task_count: self.tasks.tasks.len(),
+        }
+    }
+}
+
+/// Folder holding the snapshots: ~/.aristar-worktrees/snapshots/
+pub fn get_snapshot_dir() -> PathBuf {
+    get_aristar_worktrees_base().join(SNAPSHOT_DIR)
+}
+
+/// Check that `name` is usable as a snapshot file name.
+pub fn validate_snapshot_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Snapshot name cannot be empty".to_string());
+    }
+    if name.len() > MAX_SNAPSHOT_NAME_LEN {
+        return Err(format!(
+            "Snapshot name is longer than {} characters",
+            MAX_SNAPSHOT_NAME_LEN
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ' '))
+    {
+        return Err(format!(
+            "Snapshot name may only contain letters, digits, spaces, '-' and '_': {}",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn snapshot_path_in(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.json", name))
+}
+
+// ============ Snapshot Files ============
+
+/// Write `snapshot` to `dir`, replacing one with the same name.
+pub fn write_snapshot(dir: &Path, snapshot: &WorkspaceSnapshot) -> Result<(), String> {
+    validate_snapshot_name(&snapshot.name)?;
+    save_json_store(&snapshot_path_in(dir, &snapshot.name), snapshot)
+}
+
+/// Read the snapshot `name` from `dir`.
+pub fn read_snapshot(dir: &Path, name: &str) -> Result<WorkspaceSnapshot, String> {
+    validate_snapshot_name(name)?;
+    let path = snapshot_path_in(dir, name);
+    if !path.exists() {
+        return Err(format!("Snapshot not found: {}", name));
+    }
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read snapshot: {}", e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to parse snapshot: {}", e))
+}
+
+/// Snapshots in `dir`, newest first. Unreadable files are skipped.
+pub fn list_snapshots(dir: &Path) -> Vec<WorkspaceSnapshotInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<WorkspaceSnapshotInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let contents = fs::read_to_string(&path).ok()?;
+            match serde_json::from_str::<WorkspaceSnapshot>(&contents) {
+                Ok(snapshot) => Some(snapshot.info()),
+                Err(e) => {
+                    eprintln!("[snapshots] Skipping {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    snapshots
+}
+
+/// Delete the snapshot `name` from `dir`.
+pub fn delete_snapshot(dir: &Path, name: &str) -> Result<(), String> {
+    validate_snapshot_name(name)?;
+    let path = snapshot_path_in(dir, name);
+    if !path.exists() {
+        return Err(format!("Snapshot not found: {}", name));
+    }
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete snapshot: {}", e))
+}
+
+// ============ Capture / Restore ============
+
+/// Copy the current in-memory workspace into a snapshot.
+pub fn capture_snapshot(
+    name: &str,
+    app_state: &AppState,
+    task_state: &TaskManagerState,
+    ui_state: Option<serde_json::Value>,
+) -> Result<WorkspaceSnapshot, String> {
+    validate_snapshot_name(name)?;
+    let store = app_state.store.read().map_err(|e| e.to_string())?.clone();
+    let tasks = task_state.store.lock().map_err(|e| e.to_string())?.clone();
+
+    Ok(WorkspaceSnapshot {
+        name: name.to_string(),
+        created_at: Utc::now().timestamp_millis(),
+        store,
+        tasks,
+        ui_state,
+    })
+}
+
+/// Replace the in-memory workspace with `snapshot`. The caller saves the
+/// stores to disk.
+pub fn restore_snapshot(
+    snapshot: WorkspaceSnapshot,
+    app_state: &AppState,
+    task_state: &TaskManagerState,
+) -> Result<Option<serde_json::Value>, String> {
+    app_state.replace(snapshot.store)?;
+    *task_state.store.lock().map_err(|e| e.to_string())? = snapshot.tasks;
+    Ok(snapshot.ui_state)
+}
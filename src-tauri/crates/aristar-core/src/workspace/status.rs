@@ -0,0 +1,188 @@
+//! Global status counters.
+//!
+//! Counting dirty worktrees and disk usage shells out to git/df, so those
+//! sub-results are cached and only recomputed after `SUB_RESULT_TTL_MS`.
+//! Directory sizes (`du`) are measured here too, for agent disk quotas.
+
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::agent_manager::types::TaskStatus;
+use crate::agent_manager::{OpenCodeManager, TaskManagerState};
+use crate::core::get_aristar_worktrees_base;
+use crate::worktrees::operations::run_git_command;
+use crate::worktrees::store::AppState;
+
+use super::types::GlobalStatus;
+
+pub use crate::core::events::GLOBAL_STATUS_EVENT;
+
+/// How long cached sub-results (dirty state, disk usage) stay valid.
+pub const SUB_RESULT_TTL_MS: i64 = 30_000;
+
+/// Interval between background refreshes.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Cached sub-results used to compute `GlobalStatus`.
+#[derive(Default)]
+pub struct GlobalStatusCache {
+    /// worktree path -> (is dirty, computed at)
+    dirty: Mutex<HashMap<String, (bool, i64)>>,
+    /// (disk usage percent, computed at)
+    disk: Mutex<Option<(Option<u8>, i64)>>,
+}
+
+impl GlobalStatusCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a worktree has uncommitted changes, using the cache when fresh.
+    pub fn is_dirty(&self, worktree_path: &str, now: i64) -> bool {
+        if let Ok(cache) = self.dirty.lock() {
+            if let Some((dirty, at)) = cache.get(worktree_path) {
+                if now - at < SUB_RESULT_TTL_MS {
+                    return *dirty;
+                }
+            }
+        }
+
+        let dirty = is_worktree_dirty(worktree_path);
+        if let Ok(mut cache) = self.dirty.lock() {
+            cache.insert(worktree_path.to_string(), (dirty, now));
+        }
+        dirty
+    }
+
+    /// Disk usage of the data directory volume, using the cache when fresh.
+    pub fn disk_usage_percent(&self, now: i64) -> Option<u8> {
+        if let Ok(cache) = self.disk.lock() {
+            if let Some((percent, at)) = *cache {
+                if now - at < SUB_RESULT_TTL_MS {
+                    return percent;
+                }
+            }
+        }
+
+        let percent = get_disk_usage_percent(&get_aristar_worktrees_base());
+        if let Ok(mut cache) = self.disk.lock() {
+            *cache = Some((percent, now));
+        }
+        percent
+    }
+
+    /// Drop cached entries for worktrees that are no longer tracked.
+    pub fn retain_paths(&self, paths: &[String]) {
+        if let Ok(mut cache) = self.dirty.lock() {
+            cache.retain(|p, _| paths.contains(p));
+        }
+    }
+}
+
+/// Check whether a worktree has uncommitted changes.
+/// Missing paths and git errors count as clean.
+pub fn is_worktree_dirty(worktree_path: &str) -> bool {
+    if !Path::new(worktree_path).exists() {
+        return false;
+    }
+    run_git_command(&["status", "--porcelain"], worktree_path)
+        .map(|o| !o.stdout.is_empty())
+        .unwrap_or(false)
+}
+
+/// Parse the "Capacity" column of `df -P` output.
+pub fn parse_df_capacity(output: &str) -> Option<u8> {
+    let line = output.lines().nth(1)?;
+    line.split_whitespace()
+        .find(|col| col.ends_with('%'))
+        .and_then(|col| col.trim_end_matches('%').parse().ok())
+}
+
+/// Get the percentage of a volume in use via `df -P`.
+pub fn get_disk_usage_percent(path: &Path) -> Option<u8> {
+    let output = Command::new("df").arg("-P").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_capacity(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the size column of `du -sk` output, in bytes.
+pub fn parse_du_kilobytes(output: &str) -> Option<u64> {
+    let kilobytes: u64 = output.split_whitespace().next()?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Get the size of a directory tree on disk via `du -sk`, in bytes.
+pub fn get_directory_size_bytes(path: &Path) -> Option<u64> {
+    let output = Command::new("du").arg("-sk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_du_kilobytes(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Compute the global status from the managed states.
+///
+/// Each store lock is taken and released on its own, never nested.
+pub fn compute_global_status(
+    app_state: &AppState,
+    task_state: &TaskManagerState,
+    opencode: &OpenCodeManager,
+    cache: &GlobalStatusCache,
+) -> Result<GlobalStatus, String> {
+    let now = Utc::now().timestamp_millis();
+
+    let mut worktree_paths: Vec<String> = {
+        let store = app_state.store.read().map_err(|e| e.to_string())?;
+        store
+            .repositories
+            .iter()
+            .filter(|r| r.unavailable_since.is_none() && r.scan_settings.eager_status)
+            .flat_map(|r| r.worktrees.iter().map(|w| w.path.clone()))
+            .collect()
+    };
+
+    let (tasks_running, pending_cleanups, agent_paths) = {
+        let store = task_state.store.lock().map_err(|e| e.to_string())?;
+        let running = store
+            .tasks
+            .iter()
+            .filter(|t| t.status == TaskStatus::Running)
+            .count();
+        let pending = store
+            .tasks
+            .iter()
+            .filter(|t| t.agents.iter().any(|a| a.accepted) && t.agents.iter().any(|a| !a.accepted))
+            .count();
+        let paths: Vec<String> = store
+            .tasks
+            .iter()
+            .flat_map(|t| t.agents.iter().map(|a| a.worktree_path.clone()))
+            .collect();
+        (running, pending, paths)
+    };
+
+    worktree_paths.extend(agent_paths);
+    worktree_paths.sort();
+    worktree_paths.dedup();
+    cache.retain_paths(&worktree_paths);
+
+    let dirty_worktrees = worktree_paths
+        .iter()
+        .filter(|p| cache.is_dirty(p, now))
+        .count();
+
+    Ok(GlobalStatus {
+        servers_running: opencode.running_count(),
+        tasks_running,
+        dirty_worktrees,
+        pending_cleanups,
+        disk_usage_percent: cache.disk_usage_percent(now),
+        updated_at: now,
+    })
+}
@@ -0,0 +1,92 @@
+//! Workspace-wide types.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Aggregated counters shown in the status bar.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalStatus {
+    /// Number of running OpenCode servers
+    pub servers_running: usize,
+    /// Number of tasks with status `running`
+    pub tasks_running: usize,
+    /// Number of worktrees (repository and agent) with uncommitted changes
+    pub dirty_worktrees: usize,
+    /// Number of tasks with an accepted agent and unaccepted agents left to clean up
+    pub pending_cleanups: usize,
+    /// Percentage of the data directory's volume in use, if it could be determined
+    pub disk_usage_percent: Option<u8>,
+    /// Timestamp when the status was computed (milliseconds since epoch)
+    pub updated_at: i64,
+}
+
+/// What startup recovery did with an interrupted operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecoveryOutcome {
+    /// Undone, e.g. the worktrees of a task that was never saved
+    RolledBack,
+    /// Finished, e.g. a worktree removal
+    RolledForward,
+    /// The operation had completed before the app died
+    NothingToDo,
+    /// Left in the journal (read-only mode)
+    Pending,
+    /// Recovery failed; retried on the next start
+    Failed,
+}
+
+/// One interrupted operation found in the journal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryAction {
+    pub intent_id: String,
+    /// "create-task" or "remove-worktree"
+    pub operation: String,
+    /// Task id or worktree path
+    pub target: String,
+    /// When the operation started (milliseconds since epoch)
+    pub started_at: i64,
+    pub outcome: RecoveryOutcome,
+    /// Repairs made, e.g. "Removed worktree /path"
+    pub steps: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// What startup recovery found and repaired.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryReport {
+    /// Milliseconds since epoch
+    pub ran_at: i64,
+    pub actions: Vec<RecoveryAction>,
+}
+
+/// Kind of entity a search result points at.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SearchEntityKind {
+    Repository,
+    Worktree,
+    Task,
+    Preset,
+    Schedule,
+}
+
+/// A `global_search` hit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub kind: SearchEntityKind,
+    /// Repository, preset or schedule id, task id, or worktree path
+    pub id: String,
+    pub title: String,
+    /// Path, branch or similar context
+    pub subtitle: Option<String>,
+    /// Repository id of a worktree
+    pub parent_id: Option<String>,
+    pub score: f32,
+    /// Fields the query matched, e.g. "name", "branch", "notes"
+    pub matched_fields: Vec<String>,
+}
@@ -0,0 +1,59 @@
+//! Last activity per worktree.
+//!
+//! `WorktreeInfo::last_active` is the latest of three kinds of activity:
+//! opening the worktree in a terminal or editor, starting OpenCode in it, and
+//! git activity. The first two are recorded by the commands doing them with
+//! `record_worktree_activity`. Git activity is read when the worktree list is
+//! refreshed, from the modification times of `HEAD`, `index` and `logs/HEAD`
+//! in the worktree's own git dir, so commits, checkouts and staging count.
+//! A listing served from the list cache keeps the time it was read with.
+//! The value only ever moves forward.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::core::is_read_only;
+
+use super::store::AppState;
+
+/// Files in a worktree's git dir whose modification time counts as activity.
+const ACTIVITY_FILES: [&str; 3] = ["HEAD", "index", "logs/HEAD"];
+
+/// The git dir of the worktree at `worktree_path` itself: `.git` for the main
+/// worktree, `{common}/worktrees/{name}` for a linked one.
+pub fn worktree_git_dir(worktree_path: &Path) -> Option<PathBuf> {
+    let dot_git = worktree_path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let contents = fs::read_to_string(&dot_git).ok()?;
+    let gitdir = contents.lines().find_map(|l| l.strip_prefix("gitdir: "))?;
+    Some(worktree_path.join(gitdir.trim()))
+}
+
+/// Time of the latest git activity in the worktree at `worktree_path`, in
+/// milliseconds since the epoch. `None` when none of the files can be read.
+pub fn git_activity_at(worktree_path: &Path) -> Option<i64> {
+    let git_dir = worktree_git_dir(worktree_path)?;
+    ACTIVITY_FILES
+        .iter()
+        .filter_map(|file| fs::metadata(git_dir.join(file)).ok()?.modified().ok())
+        .filter_map(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_millis() as i64)
+        .max()
+}
+
+/// Record activity at `at` (milliseconds) on the tracked worktree at `path` and
+/// save the store. Nothing is saved in read-only mode or when the worktree is
+/// not tracked. Returns whether the worktree was found.
+pub fn record_worktree_activity(state: &AppState, path: &str, at: i64) -> Result<bool, String> {
+    if is_read_only() {
+        return Ok(false);
+    }
+    if !state.update_worktree(path, |wt| wt.record_activity(at))? {
+        return Ok(false);
+    }
+    state.save()?;
+    Ok(true)
+}
@@ -0,0 +1,193 @@
+//! Human-readable alias symlinks for worktrees.
+//!
+//! Worktrees live under `~/.aristar-worktrees/{hash}/{name}`, which is hard to
+//! recognise in terminals and editors. With `settings.worktree_aliases` enabled,
+//! each of them also gets `~/.aristar-worktrees/by-name/{repo}/{name}` pointing at
+//! the hashed path. Aliases are best effort: failures are logged, never returned
+//! to the caller of the worktree operation.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::get_aristar_worktrees_base;
+
+use super::operations::get_worktree_base_for_repo;
+use super::types::Repository;
+
+/// Folder holding the alias symlinks.
+pub const ALIAS_DIR: &str = "by-name";
+
+/// Base folder for aliases: ~/.aristar-worktrees/by-name/
+pub fn get_alias_base() -> PathBuf {
+    get_aristar_worktrees_base().join(ALIAS_DIR)
+}
+
+/// Make a name safe to use as a single path component.
+pub fn sanitize_alias_component(name: &str) -> String {
+    let sanitized: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+
+    match sanitized.trim_matches('.') {
+        "" => "_".to_string(),
+        _ => sanitized,
+    }
+}
+
+/// Alias path for a worktree of `repo_name` under `alias_base`.
+pub fn alias_path_in(alias_base: &Path, repo_name: &str, worktree_name: &str) -> PathBuf {
+    alias_base
+        .join(sanitize_alias_component(repo_name))
+        .join(sanitize_alias_component(worktree_name))
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+/// Point `link` at `target`, replacing a stale or dangling alias.
+/// Refuses to replace anything that isn't a symlink, or an alias that points at
+/// another existing worktree (e.g. two repositories with the same name).
+pub fn create_alias_link(target: &Path, link: &Path) -> Result<(), String> {
+    if is_symlink(link) {
+        match fs::read_link(link) {
+            Ok(existing) if existing == target => return Ok(()),
+            Ok(existing) if existing.exists() => {
+                return Err(format!(
+                    "Alias {} already points to {}",
+                    link.display(),
+                    existing.display()
+                ));
+            }
+            _ => remove_symlink(link)?,
+        }
+    } else if link.exists() {
+        return Err(format!("Alias path is not a symlink: {}", link.display()));
+    }
+
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    create_symlink(target, link)
+        .map_err(|e| format!("Failed to create alias {}: {}", link.display(), e))
+}
+
+fn remove_symlink(link: &Path) -> Result<(), String> {
+    // Directory symlinks on Windows are removed with remove_dir
+    fs::remove_file(link)
+        .or_else(|_| fs::remove_dir(link))
+        .map_err(|e| format!("Failed to remove alias {}: {}", link.display(), e))
+}
+
+/// Remove every alias under `alias_base` that points at `target`, then any
+/// repository folders left empty.
+pub fn remove_alias_links_to(alias_base: &Path, target: &Path) -> Result<(), String> {
+    let Ok(repo_dirs) = fs::read_dir(alias_base) else {
+        return Ok(());
+    };
+
+    for repo_dir in repo_dirs.flatten().map(|e| e.path()) {
+        if !repo_dir.is_dir() || is_symlink(&repo_dir) {
+            continue;
+        }
+        for link in fs::read_dir(&repo_dir).into_iter().flatten().flatten() {
+            let link = link.path();
+            if is_symlink(&link) && fs::read_link(&link).is_ok_and(|t| t == target) {
+                remove_symlink(&link)?;
+            }
+        }
+        // Only succeeds when the folder is empty
+        let _ = fs::remove_dir(&repo_dir);
+    }
+    Ok(())
+}
+
+/// Remove aliases whose worktree no longer exists.
+pub fn prune_dangling_aliases(alias_base: &Path) -> Result<usize, String> {
+    let Ok(repo_dirs) = fs::read_dir(alias_base) else {
+        return Ok(0);
+    };
+
+    let mut removed = 0;
+    for repo_dir in repo_dirs.flatten().map(|e| e.path()) {
+        if !repo_dir.is_dir() || is_symlink(&repo_dir) {
+            continue;
+        }
+        for link in fs::read_dir(&repo_dir).into_iter().flatten().flatten() {
+            let link = link.path();
+            // exists() follows the link, so it is false for dangling links
+            if is_symlink(&link) && !link.exists() {
+                remove_symlink(&link)?;
+                removed += 1;
+            }
+        }
+        let _ = fs::remove_dir(&repo_dir);
+    }
+    Ok(removed)
+}
+
+/// Create the alias for a worktree of `repo_path`, filed under `repo_name`
+/// (the repository's display name). Only worktrees stored under
+/// `~/.aristar-worktrees/{hash}/` get one.
+pub fn create_worktree_alias(
+    repo_path: &str,
+    repo_name: &str,
+    worktree_path: &str,
+) -> Result<(), String> {
+    let target = Path::new(worktree_path);
+    // Worktree folders are keyed by the hash of the canonical repo path
+    let repo_path = Path::new(repo_path)
+        .canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| repo_path.to_string());
+    let base = get_worktree_base_for_repo(&repo_path);
+    let base = base.canonicalize().unwrap_or(base);
+    if target.parent() != Some(base.as_path()) {
+        return Ok(());
+    }
+
+    let worktree_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid worktree path: {}", worktree_path))?;
+    let link = alias_path_in(&get_alias_base(), repo_name, &worktree_name);
+    create_alias_link(target, &link)
+}
+
+/// Remove the aliases pointing at a removed or moved worktree. Also prunes
+/// dangling aliases, which covers links made from a differently spelled path.
+pub fn remove_worktree_alias(worktree_path: &str) -> Result<(), String> {
+    let alias_base = get_alias_base();
+    remove_alias_links_to(&alias_base, Path::new(worktree_path))?;
+    prune_dangling_aliases(&alias_base).map(|_| ())
+}
+
+/// Create missing aliases for a repository's worktrees and prune dangling ones.
+pub fn sync_repository_aliases(repo: &Repository) -> Result<(), String> {
+    prune_dangling_aliases(&get_alias_base())?;
+    for worktree in repo.worktrees.iter().filter(|w| !w.is_main) {
+        if let Err(e) = create_worktree_alias(&repo.path, repo.display_name(), &worktree.path) {
+            eprintln!("[worktrees] {}", e);
+        }
+    }
+    Ok(())
+}
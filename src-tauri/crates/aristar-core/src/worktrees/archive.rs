@@ -0,0 +1,291 @@
+//! Worktree archives.
+//!
+//! Before removing a worktree (typically a dirty agent worktree removed with
+//! `force`), its files can be packed into `~/.aristar-worktrees/archives/` as
+//! `{id}.tar.gz`, next to `{id}.json` describing where it came from. With
+//! `tracked_only`, only files git knows about or would add (tracked, modified
+//! and untracked but not ignored) are packed, leaving out `node_modules`,
+//! build output and the like. The `.git` link file is never packed.
+//!
+//! Restoring recreates the worktree at its archived commit (on its branch when
+//! the branch still points there) and unpacks the files over it, so changes
+//! that were never committed come back as changes. When the repository is
+//! gone, the files are unpacked into a plain folder.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::{get_aristar_worktrees_base, save_json_store};
+
+use super::operations::{find_git_repo_root, run_git_command};
+use super::repo_queue::run_queued;
+
+/// An archived worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeArchive {
+    /// e.g. "20261015-142501-a1b2c3d4"
+    pub id: String,
+    /// Folder name of the worktree
+    pub name: String,
+    pub worktree_path: String,
+    pub repo_path: String,
+    pub branch: Option<String>,
+    /// HEAD when archived; `None` on a branch without commits
+    pub commit: Option<String>,
+    /// Only tracked, modified and untracked-but-not-ignored files were packed
+    pub tracked_only: bool,
+    /// Size of the `.tar.gz`
+    pub size_bytes: u64,
+    /// Milliseconds since epoch
+    pub created_at: i64,
+}
+
+/// Archive folder: ~/.aristar-worktrees/archives/
+pub fn get_archives_dir() -> PathBuf {
+    get_aristar_worktrees_base().join("archives")
+}
+
+fn archive_file(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.tar.gz", id))
+}
+
+fn metadata_file(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+/// Ids are generated, so anything else (e.g. "../x") is rejected.
+fn validate_archive_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid archive id: {}", id));
+    }
+    Ok(())
+}
+
+fn git_output(args: &[&str], cwd: &str) -> Option<String> {
+    let output = run_git_command(args, cwd).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn run_tar(mut command: Command) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "tar failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Files git tracks or would add, relative to the worktree. Tracked files
+/// deleted in the worktree are left out.
+pub fn tracked_and_modified_files(path: &str) -> Result<Vec<String>, String> {
+    let output = run_git_command(
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ],
+        path,
+    )
+    .map_err(|e| format!("Failed to list files: {}", e.trim()))?;
+    let mut files: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .filter(|f| Path::new(path).join(f).symlink_metadata().is_ok())
+        .map(str::to_string)
+        .collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Pack the worktree at `path` into the archive folder `dir`.
+pub fn archive_worktree_in(
+    dir: &Path,
+    path: &str,
+    tracked_only: bool,
+) -> Result<WorktreeArchive, String> {
+    let worktree = Path::new(path);
+    if !worktree.is_dir() {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    let repo_path = find_git_repo_root(path)?;
+    let name = worktree
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let id = format!(
+        "{}-{}",
+        Utc::now().format("%Y%m%d-%H%M%S"),
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    );
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create archive folder: {}", e))?;
+
+    let archive_path = archive_file(dir, &id);
+    let list_path = dir.join(format!("{}.files", id));
+    let mut tar = Command::new("tar");
+    tar.arg("-czf").arg(&archive_path).arg("-C").arg(worktree);
+    if tracked_only {
+        let files = tracked_and_modified_files(path)?;
+        fs::write(&list_path, files.join("\0"))
+            .map_err(|e| format!("Failed to write file list: {}", e))?;
+        tar.arg("--null").arg("-T").arg(&list_path);
+    } else {
+        tar.arg("--exclude=.git").arg(".");
+    }
+    let packed = run_tar(tar);
+    let _ = fs::remove_file(&list_path);
+    if let Err(e) = packed {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+
+    let archive = WorktreeArchive {
+        id: id.clone(),
+        name,
+        worktree_path: path.to_string(),
+        repo_path,
+        branch: git_output(&["symbolic-ref", "--quiet", "--short", "HEAD"], path),
+        commit: git_output(&["rev-parse", "--verify", "--quiet", "HEAD"], path),
+        tracked_only,
+        size_bytes: fs::metadata(&archive_path).map(|m| m.len()).unwrap_or(0),
+        created_at: Utc::now().timestamp_millis(),
+    };
+    if let Err(e) = save_json_store(&metadata_file(dir, &id), &archive) {
+        let _ = fs::remove_file(&archive_path);
+        return Err(e);
+    }
+    Ok(archive)
+}
+
+/// Archives in `dir`, newest first.
+pub fn list_archives_in(dir: &Path) -> Vec<WorktreeArchive> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut archives: Vec<WorktreeArchive> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let json = fs::read_to_string(&path).ok()?;
+            serde_json::from_str::<WorktreeArchive>(&json).ok()
+        })
+        .filter(|archive| archive_file(dir, &archive.id).is_file())
+        .collect();
+    archives.sort_by_key(|a| std::cmp::Reverse(a.created_at));
+    archives
+}
+
+fn read_archive(dir: &Path, id: &str) -> Result<WorktreeArchive, String> {
+    validate_archive_id(id)?;
+    let json = fs::read_to_string(metadata_file(dir, id))
+        .map_err(|_| format!("Archive not found: {}", id))?;
+    serde_json::from_str(&json).map_err(|e| format!("Failed to read archive {}: {}", id, e))
+}
+
+/// Recreate the worktree for `archive` at `target`, or a plain folder when
+/// its repository or commit is gone.
+fn recreate_worktree(archive: &WorktreeArchive, target: &str) -> Result<(), String> {
+    let repo = &archive.repo_path;
+    let commit = archive
+        .commit
+        .as_deref()
+        .filter(|c| Path::new(repo).is_dir() && git_output(&["cat-file", "-t", c], repo).is_some());
+    let Some(commit) = commit else {
+        return fs::create_dir_all(target)
+            .map_err(|e| format!("Failed to create {}: {}", target, e));
+    };
+
+    run_queued(repo, &format!("restore worktree {}", archive.name), || {
+        // Back on the branch when it hasn't moved and isn't checked out elsewhere
+        if let Some(branch) = &archive.branch {
+            let tip = git_output(
+                &[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("refs/heads/{}", branch),
+                ],
+                repo,
+            );
+            if tip.as_deref() == Some(commit)
+                && run_git_command(&["worktree", "add", target, branch], repo).is_ok()
+            {
+                return Ok(());
+            }
+        }
+        run_git_command(&["worktree", "add", "--detach", target, commit], repo)
+            .map_err(|e| format!("Failed to recreate worktree: {}", e.trim()))?;
+        Ok(())
+    })
+}
+
+/// Restore archive `id` from `dir` to `target` (default: where it was
+/// archived from), which must not exist. Returns the restored path.
+pub fn restore_archive_in(dir: &Path, id: &str, target: Option<&str>) -> Result<String, String> {
+    let archive = read_archive(dir, id)?;
+    let archive_path = archive_file(dir, id);
+    if !archive_path.is_file() {
+        return Err(format!("Archive file missing: {}", archive_path.display()));
+    }
+    let target = target.unwrap_or(&archive.worktree_path).to_string();
+    if Path::new(&target).exists() {
+        return Err(format!("{} already exists", target));
+    }
+
+    recreate_worktree(&archive, &target)?;
+    let mut tar = Command::new("tar");
+    tar.arg("-xzf").arg(&archive_path).arg("-C").arg(&target);
+    run_tar(tar)?;
+    Ok(target)
+}
+
+/// Delete archive `id` from `dir`.
+pub fn delete_archive_in(dir: &Path, id: &str) -> Result<(), String> {
+    validate_archive_id(id)?;
+    let metadata = metadata_file(dir, id);
+    if !metadata.exists() {
+        return Err(format!("Archive not found: {}", id));
+    }
+    for path in [archive_file(dir, id), metadata] {
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to delete {}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+/// Archive a worktree (async version).
+pub async fn archive_worktree_async(
+    path: String,
+    tracked_only: bool,
+) -> Result<WorktreeArchive, String> {
+    tokio::task::spawn_blocking(move || {
+        archive_worktree_in(&get_archives_dir(), &path, tracked_only)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Restore an archive (async version).
+pub async fn restore_archive_async(id: String, target: Option<String>) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        restore_archive_in(&get_archives_dir(), &id, target.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,174 @@
+//! Repository availability tracking.
+//!
+//! Repositories on network volumes can disappear for a while (VPN drop, sleep,
+//! unmounted share). Instead of erroring on every call and dropping their
+//! worktrees on refresh, such repositories are marked unavailable and probed
+//! again with exponential backoff. Destructive automatic changes (replacing the
+//! worktree list, dirty checks) are skipped until the repository comes back.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::operations::is_git_repository;
+use super::store::{AppState, RepositoryKey};
+
+pub use crate::core::events::AVAILABILITY_EVENT;
+
+/// Delay before the first re-probe of an unavailable repository.
+pub const INITIAL_BACKOFF_MS: i64 = 5_000;
+
+/// Upper bound for the delay between probes.
+pub const MAX_BACKOFF_MS: i64 = 300_000;
+
+/// Interval at which the background monitor wakes up.
+pub const MONITOR_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Event payload for availability changes.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryAvailabilityEvent {
+    pub repository_id: String,
+    pub path: String,
+    pub available: bool,
+    pub unavailable_since: Option<i64>,
+}
+
+/// Backoff state of one unavailable repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackoffState {
+    pub failures: u32,
+    pub next_probe_at: i64,
+}
+
+/// Delay before the next probe after `failures` consecutive failed probes.
+pub fn backoff_delay_ms(failures: u32) -> i64 {
+    if failures == 0 {
+        return 0;
+    }
+    let exponent = (failures - 1).min(16);
+    INITIAL_BACKOFF_MS
+        .saturating_mul(1 << exponent)
+        .min(MAX_BACKOFF_MS)
+}
+
+/// Probe schedule for unavailable repositories, keyed by repository ID.
+#[derive(Default)]
+pub struct RepositoryAvailability {
+    backoff: Mutex<HashMap<String, BackoffState>>,
+}
+
+impl RepositoryAvailability {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether an unavailable repository is due for another probe.
+    pub fn should_probe(&self, repo_id: &str, now: i64) -> bool {
+        self.backoff
+            .lock()
+            .map(|b| b.get(repo_id).is_none_or(|s| now >= s.next_probe_at))
+            .unwrap_or(true)
+    }
+
+    /// Record a failed probe and schedule the next one.
+    pub fn record_failure(&self, repo_id: &str, now: i64) -> BackoffState {
+        let mut backoff = match self.backoff.lock() {
+            Ok(b) => b,
+            Err(e) => e.into_inner(),
+        };
+        let failures = backoff.get(repo_id).map(|s| s.failures).unwrap_or(0) + 1;
+        let state = BackoffState {
+            failures,
+            next_probe_at: now + backoff_delay_ms(failures),
+        };
+        backoff.insert(repo_id.to_string(), state);
+        state
+    }
+
+    /// Forget the backoff state of a repository (probe succeeded or retry forced).
+    pub fn reset(&self, repo_id: &str) {
+        if let Ok(mut backoff) = self.backoff.lock() {
+            backoff.remove(repo_id);
+        }
+    }
+}
+
+/// Whether a repository's path is currently reachable as a git repository.
+pub fn is_repository_reachable(repo_path: &str) -> bool {
+    Path::new(repo_path).is_dir() && is_git_repository(repo_path)
+}
+
+/// Record the outcome of a probe on the repository and the backoff schedule.
+///
+/// Returns an event when the availability changed.
+pub fn apply_probe_result(
+    state: &AppState,
+    tracker: &RepositoryAvailability,
+    repo_id: &str,
+    reachable: bool,
+    now: i64,
+) -> Result<Option<RepositoryAvailabilityEvent>, String> {
+    if reachable {
+        tracker.reset(repo_id);
+    } else {
+        let backoff = tracker.record_failure(repo_id, now);
+        println!(
+            "[availability] Repository {} unavailable ({} failed probes), next probe in {}ms",
+            repo_id,
+            backoff.failures,
+            backoff.next_probe_at - now
+        );
+    }
+
+    let change = state.update_repository(RepositoryKey::Id(repo_id), |repo| {
+        let was_available = repo.unavailable_since.is_none();
+        if reachable == was_available {
+            return None;
+        }
+        repo.unavailable_since = if reachable { None } else { Some(now) };
+        Some(RepositoryAvailabilityEvent {
+            repository_id: repo.id.clone(),
+            path: repo.path.clone(),
+            available: reachable,
+            unavailable_since: repo.unavailable_since,
+        })
+    })?;
+
+    Ok(change.flatten())
+}
+
+/// Probe every repository that is due and return the availability changes.
+///
+/// Available repositories get a cheap check on every pass; unavailable ones
+/// are only probed once their backoff delay has elapsed.
+pub fn probe_repositories(
+    state: &AppState,
+    tracker: &RepositoryAvailability,
+    now: i64,
+) -> Result<Vec<RepositoryAvailabilityEvent>, String> {
+    let repos: Vec<(String, String, bool)> = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        store
+            .repositories
+            .iter()
+            .map(|r| (r.id.clone(), r.path.clone(), r.unavailable_since.is_some()))
+            .collect()
+    };
+
+    let mut changes = Vec::new();
+    for (id, path, unavailable) in repos {
+        if unavailable && !tracker.should_probe(&id, now) {
+            continue;
+        }
+        if let Some(event) =
+            apply_probe_result(state, tracker, &id, is_repository_reachable(&path), now)?
+        {
+            changes.push(event);
+        }
+    }
+    Ok(changes)
+}
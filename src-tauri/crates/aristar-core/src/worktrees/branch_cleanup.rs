@@ -0,0 +1,277 @@
+//! Cleanup of branches left behind by merged work.
+//!
+//! A local branch is suggested for deletion when it is fully merged into the
+//! repository's default branch (`detect_base_branch`), no worktree has it
+//! checked out and it is not protected. `suggest_branch_cleanup` lists them
+//! with their last commit; `delete_merged_branches` deletes those of a
+//! confirmed list that are still suggested when it runs, so a branch that
+//! gained commits or a worktree since the suggestion is kept. Each ref is only
+//! deleted if it still points at the commit that was checked. A background
+//! loop checks the available repositories every half hour and emits
+//! `branch-cleanup-suggested` when a repository's suggestions changed.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use super::operations::{
+    detect_base_branch, list_worktrees, local_branch_exists, run_git_command, PROTECTED_BRANCHES,
+};
+use super::repo_queue::run_queued;
+use super::store::AppState;
+use super::types::{CommitInfo, Repository};
+
+pub use crate::core::events::BRANCH_CLEANUP_EVENT;
+
+/// How often the background loop looks for merged branches.
+pub const BRANCH_CLEANUP_CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// `git for-each-ref` format read by `parse_merged_branches`: full ref name
+/// and last commit fields separated by NUL.
+const MERGED_BRANCH_FORMAT: &str = "--format=%(refname)%00%(objectname)%00%(objectname:short)%00%(authorname)%00%(authordate:unix)%00%(contents:subject)";
+
+/// A local branch merged into the default branch.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedBranch {
+    pub name: String,
+    /// Tip of the branch (no parents or refs)
+    pub last_commit: CommitInfo,
+}
+
+/// Branches of a repository that can be deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchCleanupSuggestion {
+    /// The tracked repository, if `repo_path` is one
+    pub repository_id: Option<String>,
+    pub repo_path: String,
+    /// Branch the suggestions are merged into, `None` when none was found
+    pub base_branch: Option<String>,
+    /// Most recent last commit first
+    pub branches: Vec<MergedBranch>,
+    /// When the check ran (milliseconds since epoch)
+    pub checked_at: i64,
+}
+
+/// A confirmed branch `delete_merged_branches` did not delete.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedBranch {
+    pub name: String,
+    pub reason: String,
+}
+
+/// Result of `delete_merged_branches`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDeletionReport {
+    pub deleted: Vec<String>,
+    pub skipped: Vec<SkippedBranch>,
+}
+
+/// Parse `MERGED_BRANCH_FORMAT` output, leaving out `base_branch`, branches in
+/// `checked_out` and those `is_protected` keeps. Most recent commit first.
+pub fn parse_merged_branches(
+    output: &str,
+    base_branch: &str,
+    checked_out: &HashSet<String>,
+    is_protected: impl Fn(&str) -> bool,
+) -> Vec<MergedBranch> {
+    let mut branches: Vec<MergedBranch> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let name = fields.next()?.strip_prefix("refs/heads/")?;
+            if name == base_branch || checked_out.contains(name) || is_protected(name) {
+                return None;
+            }
+            let hash = fields.next()?.to_string();
+            let short_hash = fields.next()?.to_string();
+            let author = fields.next()?.to_string();
+            let date = fields.next()?.parse().unwrap_or(0);
+            let message = fields.next().unwrap_or("").to_string();
+            Some(MergedBranch {
+                name: name.to_string(),
+                last_commit: CommitInfo {
+                    hash,
+                    short_hash,
+                    message,
+                    author,
+                    date,
+                    parents: Vec::new(),
+                    refs: Vec::new(),
+                },
+            })
+        })
+        .collect();
+    branches.sort_by_key(|b| std::cmp::Reverse(b.last_commit.date));
+    branches
+}
+
+/// Whether `repository` (or without one, the built-in list) protects `branch`.
+fn is_protected(repository: Option<&Repository>, branch: &str) -> bool {
+    match repository {
+        Some(repo) => repo.is_protected_branch(branch),
+        None => PROTECTED_BRANCHES.contains(&branch),
+    }
+}
+
+/// Local branches of `repo_path` merged into its default branch, without a
+/// worktree and not protected by `repository`.
+pub fn suggest_branch_cleanup(
+    repo_path: &str,
+    repository: Option<&Repository>,
+) -> Result<BranchCleanupSuggestion, String> {
+    let checked_at = Utc::now().timestamp_millis();
+    let base = detect_base_branch(repo_path);
+    let branches = match &base {
+        Some(base) => {
+            let checked_out: HashSet<String> = list_worktrees(repo_path)?
+                .into_iter()
+                .filter_map(|w| w.branch)
+                .collect();
+            let merged = format!("--merged={}", base);
+            let output = run_git_command(
+                &["for-each-ref", MERGED_BRANCH_FORMAT, &merged, "refs/heads"],
+                repo_path,
+            )?;
+            // A remote base (`origin/main`) leaves its local name out too
+            let base_name = base.strip_prefix("origin/").unwrap_or(base);
+            parse_merged_branches(
+                &String::from_utf8_lossy(&output.stdout),
+                base_name,
+                &checked_out,
+                |b| is_protected(repository, b),
+            )
+        }
+        None => Vec::new(),
+    };
+
+    Ok(BranchCleanupSuggestion {
+        repository_id: repository.map(|r| r.id.clone()),
+        repo_path: repo_path.to_string(),
+        base_branch: base,
+        branches,
+        checked_at,
+    })
+}
+
+/// Delete the branches of `confirm_list` that are still suggested for
+/// cleanup. Others are skipped with the reason; nothing else is deleted.
+pub fn delete_merged_branches(
+    repo_path: &str,
+    repository: Option<&Repository>,
+    confirm_list: &[String],
+) -> Result<BranchDeletionReport, String> {
+    let mut report = BranchDeletionReport::default();
+    if confirm_list.is_empty() {
+        return Ok(report);
+    }
+
+    run_queued(repo_path, "delete merged branches", || {
+        let suggestion = suggest_branch_cleanup(repo_path, repository)?;
+        let base = suggestion
+            .base_branch
+            .as_deref()
+            .unwrap_or("the default branch");
+        for name in confirm_list {
+            let Some(branch) = suggestion.branches.iter().find(|b| &b.name == name) else {
+                let reason = if local_branch_exists(repo_path, name) {
+                    format!("Not merged into {}, checked out or protected", base)
+                } else {
+                    "No such branch".to_string()
+                };
+                report.skipped.push(SkippedBranch {
+                    name: name.clone(),
+                    reason,
+                });
+                continue;
+            };
+            // Fails if the branch moved since it was checked (`branch -D` would
+            // delete it wherever it points)
+            let ref_name = format!("refs/heads/{}", name);
+            let deleted = run_git_command(
+                &["update-ref", "-d", &ref_name, &branch.last_commit.hash],
+                repo_path,
+            );
+            match deleted {
+                Ok(_) => {
+                    // Upstream and description, as `branch -D` drops them; often absent
+                    let section = format!("branch.{}", name);
+                    let _ = run_git_command(&["config", "--remove-section", &section], repo_path);
+                    report.deleted.push(name.clone());
+                }
+                Err(e) => report.skipped.push(SkippedBranch {
+                    name: name.clone(),
+                    reason: e.trim().to_string(),
+                }),
+            }
+        }
+        Ok(report)
+    })
+}
+
+/// Suggest branches to clean up (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn suggest_branch_cleanup_async(
+    repo_path: String,
+    repository: Option<Repository>,
+) -> Result<BranchCleanupSuggestion, String> {
+    tokio::task::spawn_blocking(move || suggest_branch_cleanup(&repo_path, repository.as_ref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Delete confirmed merged branches (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn delete_merged_branches_async(
+    repo_path: String,
+    repository: Option<Repository>,
+    confirm_list: Vec<String>,
+) -> Result<BranchDeletionReport, String> {
+    tokio::task::spawn_blocking(move || {
+        delete_merged_branches(&repo_path, repository.as_ref(), &confirm_list)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Suggestions for the available repositories whose suggested branches
+/// differ from `seen` (branch names by repository id), which is updated.
+/// Repositories without suggestions are only returned when they had some.
+pub fn check_branch_cleanup(
+    state: &AppState,
+    seen: &mut HashMap<String, Vec<String>>,
+) -> Result<Vec<BranchCleanupSuggestion>, String> {
+    let repositories: Vec<Repository> = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        store
+            .repositories
+            .iter()
+            .filter(|r| r.unavailable_since.is_none())
+            .cloned()
+            .collect()
+    };
+
+    let mut changed = Vec::new();
+    for repo in &repositories {
+        let suggestion = match suggest_branch_cleanup(&repo.path, Some(repo)) {
+            Ok(suggestion) => suggestion,
+            Err(e) => {
+                eprintln!("[branch_cleanup] {}: {}", repo.path, e);
+                continue;
+            }
+        };
+        let names: Vec<String> = suggestion.branches.iter().map(|b| b.name.clone()).collect();
+        let previous = seen.get(&repo.id).map(Vec::as_slice).unwrap_or(&[]);
+        if names != previous {
+            seen.insert(repo.id.clone(), names);
+            changed.push(suggestion);
+        }
+    }
+    seen.retain(|id, _| repositories.iter().any(|r| &r.id == id));
+    Ok(changed)
+}
@@ -0,0 +1,322 @@
+//! Per-worktree git config.
+//!
+//! Uses `git config --worktree`, which writes `config.worktree` in the worktree's
+//! git dir. That file is only read when `extensions.worktreeConfig` is enabled in
+//! the repository's shared config, so setting a value enables it first. Without
+//! the extension `--worktree` would silently write to the shared config instead.
+//!
+//! Also summarizes a repository's config (identity, hooks, remotes, LFS, size)
+//! so problems show up before an agent run trips over them.
+
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use super::lfs::{git_lfs_installed, gitattributes_use_lfs};
+use super::operations::{get_default_branch, get_repo_context, run_git_command};
+
+/// Keys that must stay repository-wide.
+const SHARED_ONLY_KEYS: [&str; 3] = ["core.repositoryformatversion", "core.bare", "core.worktree"];
+
+/// A config entry set for a single worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct WorktreeConfigEntry {
+    pub key: String,
+    pub value: String,
+}
+
+/// A remote and its fetch URL.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+}
+
+/// Repository config worth checking before work starts in it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoConfigSummary {
+    /// Repository root the summary is for
+    pub repo_path: String,
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    /// `core.hooksPath` as configured
+    pub hooks_path: Option<String>,
+    pub remotes: Vec<RemoteInfo>,
+    pub default_branch: Option<String>,
+    /// `.gitattributes` uses the LFS filter or LFS objects exist
+    pub uses_lfs: bool,
+    pub has_submodules: bool,
+    /// Size of the object database (`git count-objects`)
+    pub size_bytes: u64,
+    /// Misconfigurations, e.g. no `user.email` to commit with
+    pub warnings: Vec<String>,
+    /// Epoch millis
+    pub computed_at: i64,
+}
+
+/// How long a cached summary is returned before it is recomputed.
+pub const CONFIG_SUMMARY_TTL_MS: i64 = 60_000;
+
+/// Validate a config key (`section.name` or `section.subsection.name`).
+pub fn validate_config_key(key: &str) -> Result<(), String> {
+    let (section, name) = match (key.split_once('.'), key.rsplit_once('.')) {
+        (Some((section, _)), Some((_, name))) => (section, name),
+        _ => return Err(format!("Config key must look like section.name: {}", key)),
+    };
+
+    let valid_part = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if !valid_part(section) || !valid_part(name) || key.contains(char::is_whitespace) {
+        return Err(format!("Invalid config key: {}", key));
+    }
+
+    let lower = key.to_lowercase();
+    if lower.starts_with("extensions.") || SHARED_ONLY_KEYS.contains(&lower.as_str()) {
+        return Err(format!("{} cannot be set per worktree", key));
+    }
+    Ok(())
+}
+
+/// Parse `git config --list` output (`key=value` lines; values may contain `=`).
+pub fn parse_config_list(output: &str) -> Vec<WorktreeConfigEntry> {
+    output
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let (key, value) = line.split_once('=').unwrap_or((line, ""));
+            WorktreeConfigEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn ensure_worktree(path: &str) -> Result<(), String> {
+    if !Path::new(path).is_dir() {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    get_repo_context(path)
+        .map(|_| ())
+        .map_err(|_| format!("Not a git worktree: {}", path))
+}
+
+/// Whether `extensions.worktreeConfig` is enabled for the repository.
+pub fn worktree_config_enabled(path: &str) -> bool {
+    run_git_command(
+        &["config", "--bool", "--get", "extensions.worktreeConfig"],
+        path,
+    )
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "true")
+    .unwrap_or(false)
+}
+
+/// Config entries set for the worktree at `path` only.
+pub fn get_worktree_config(path: &str) -> Result<Vec<WorktreeConfigEntry>, String> {
+    ensure_worktree(path)?;
+    if !worktree_config_enabled(path) {
+        return Ok(Vec::new());
+    }
+
+    // Exit code 1 means the file has no entries
+    match run_git_command(&["config", "--worktree", "--list"], path) {
+        Ok(output) => Ok(parse_config_list(&String::from_utf8_lossy(&output.stdout))),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Set (or with `None`, unset) a config value for the worktree at `path` only.
+/// Returns the worktree's config entries afterwards.
+pub fn set_worktree_config(
+    path: &str,
+    key: &str,
+    value: Option<&str>,
+) -> Result<Vec<WorktreeConfigEntry>, String> {
+    validate_config_key(key)?;
+    ensure_worktree(path)?;
+
+    match value {
+        Some(value) => {
+            if !worktree_config_enabled(path) {
+                run_git_command(&["config", "extensions.worktreeConfig", "true"], path)
+                    .map_err(|e| format!("Failed to enable worktree config: {}", e.trim()))?;
+                println!("[worktrees] Enabled extensions.worktreeConfig for {}", path);
+            }
+            run_git_command(&["config", "--worktree", key, value], path)
+                .map_err(|e| format!("Failed to set {}: {}", key, e.trim()))?;
+        }
+        None => {
+            if worktree_config_enabled(path) {
+                // Exit code 5 means the key was not set
+                let _ = run_git_command(&["config", "--worktree", "--unset-all", key], path);
+            }
+        }
+    }
+
+    get_worktree_config(path)
+}
+
+/// Get a worktree's config (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_worktree_config_async(path: String) -> Result<Vec<WorktreeConfigEntry>, String> {
+    tokio::task::spawn_blocking(move || get_worktree_config(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Set a worktree config value (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn set_worktree_config_async(
+    path: String,
+    key: String,
+    value: Option<String>,
+) -> Result<Vec<WorktreeConfigEntry>, String> {
+    tokio::task::spawn_blocking(move || set_worktree_config(&path, &key, value.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+// ============ Repository Config Summary ============
+
+static SUMMARY_CACHE: OnceLock<Mutex<HashMap<String, RepoConfigSummary>>> = OnceLock::new();
+
+fn summary_cache() -> &'static Mutex<HashMap<String, RepoConfigSummary>> {
+    SUMMARY_CACHE.get_or_init(Default::default)
+}
+
+fn config_value(path: &str, key: &str) -> Option<String> {
+    run_git_command(&["config", "--get", key], path)
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Parse `git remote -v` output into one entry per remote (fetch URLs).
+pub fn parse_remote_list(output: &str) -> Vec<RemoteInfo> {
+    let mut remotes: Vec<RemoteInfo> = Vec::new();
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(name), Some(url)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if parts.next() == Some("(push)") || remotes.iter().any(|r| r.name == name) {
+            continue;
+        }
+        remotes.push(RemoteInfo {
+            name: name.to_string(),
+            url: url.to_string(),
+        });
+    }
+    remotes
+}
+
+/// Parse `git count-objects -v` output into the bytes used by loose objects,
+/// packs and garbage (git reports KiB).
+pub fn parse_count_objects(output: &str) -> u64 {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(key, _)| matches!(key.trim(), "size" | "size-pack" | "size-garbage"))
+        .filter_map(|(_, value)| value.trim().parse::<u64>().ok())
+        .sum::<u64>()
+        * 1024
+}
+
+/// Warnings for a summary that is otherwise complete.
+fn summary_warnings(summary: &RepoConfigSummary) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if summary.user_name.is_none() || summary.user_email.is_none() {
+        warnings.push("user.name or user.email is not set; commits will fail".to_string());
+    }
+    if let Some(hooks_path) = &summary.hooks_path {
+        if !Path::new(&summary.repo_path).join(hooks_path).is_dir() {
+            warnings.push(format!(
+                "core.hooksPath points to a missing folder: {}",
+                hooks_path
+            ));
+        }
+    }
+    if summary.remotes.is_empty() {
+        warnings.push("No remotes; fetch, pull and push are unavailable".to_string());
+    }
+    if summary.default_branch.is_none() {
+        warnings.push("No default branch found (origin/HEAD, main or master)".to_string());
+    }
+    if summary.uses_lfs && !git_lfs_installed() {
+        warnings.push("The repository uses Git LFS but git-lfs is not installed".to_string());
+    }
+    warnings
+}
+
+fn compute_repo_config_summary(repo_root: &str, common_dir: &Path) -> RepoConfigSummary {
+    let root = Path::new(repo_root);
+    let remotes = run_git_command(&["remote", "-v"], repo_root)
+        .map(|o| parse_remote_list(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default();
+    let size_bytes = run_git_command(&["count-objects", "-v"], repo_root)
+        .map(|o| parse_count_objects(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or(0);
+
+    let mut summary = RepoConfigSummary {
+        repo_path: repo_root.to_string(),
+        user_name: config_value(repo_root, "user.name"),
+        user_email: config_value(repo_root, "user.email"),
+        hooks_path: config_value(repo_root, "core.hooksPath"),
+        remotes,
+        default_branch: get_default_branch(repo_root),
+        uses_lfs: gitattributes_use_lfs(root) || common_dir.join("lfs").is_dir(),
+        has_submodules: root.join(".gitmodules").is_file(),
+        size_bytes,
+        warnings: Vec::new(),
+        computed_at: Utc::now().timestamp_millis(),
+    };
+    summary.warnings = summary_warnings(&summary);
+    summary
+}
+
+/// Config summary of the repository containing `repo_path`. Cached for
+/// `CONFIG_SUMMARY_TTL_MS`; `refresh` recomputes it right away.
+pub fn get_repo_config_summary(
+    repo_path: &str,
+    refresh: bool,
+) -> Result<RepoConfigSummary, String> {
+    if !Path::new(repo_path).is_dir() {
+        return Err(format!("Repository not found: {}", repo_path));
+    }
+    let context =
+        get_repo_context(repo_path).map_err(|_| format!("Not a git repository: {}", repo_path))?;
+    let repo_root = context.repo_root().to_string_lossy().to_string();
+
+    let now = Utc::now().timestamp_millis();
+    if !refresh {
+        let cache = summary_cache().lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(summary) = cache.get(&repo_root) {
+            if now - summary.computed_at < CONFIG_SUMMARY_TTL_MS {
+                return Ok(summary.clone());
+            }
+        }
+    }
+
+    let summary = compute_repo_config_summary(&repo_root, &context.common_dir);
+    summary_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(repo_root, summary.clone());
+    Ok(summary)
+}
+
+/// Get a repository's config summary (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_repo_config_summary_async(
+    repo_path: String,
+    refresh: bool,
+) -> Result<RepoConfigSummary, String> {
+    tokio::task::spawn_blocking(move || get_repo_config_summary(&repo_path, refresh))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
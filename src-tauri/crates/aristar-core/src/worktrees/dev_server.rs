@@ -0,0 +1,209 @@
+//! Dev server URLs of worktrees.
+//!
+//! A worktree can have the URL of the app it serves registered
+//! (`register_dev_url`), stored as `dev_url` on the worktree. `open_dev_url`
+//! opens it in the default browser. Without a registered URL, it probes for a
+//! dev server instead: TCP ports listened on by a process whose working
+//! directory is inside the worktree (as for one started by the worktree's
+//! startup script), found with `lsof`, and opens `http://localhost:{port}`,
+//! preferring the port reserved for the worktree's `{{port}}`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::ports;
+
+/// Longest URL accepted by `register_dev_url`.
+pub const MAX_DEV_URL_LEN: usize = 2048;
+
+/// Check a URL before storing it: `http` or `https`, with a host, no
+/// whitespace. Returns the trimmed URL.
+pub fn validate_dev_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    if url.len() > MAX_DEV_URL_LEN {
+        return Err(format!(
+            "Dev URL cannot be longer than {} characters",
+            MAX_DEV_URL_LEN
+        ));
+    }
+    if url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err("Dev URL cannot contain whitespace".to_string());
+    }
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or_else(|| format!("Dev URL must start with http:// or https://: {}", url))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if host.is_empty() || host.starts_with(':') {
+        return Err(format!("Dev URL has no host: {}", url));
+    }
+    Ok(url.to_string())
+}
+
+/// URL of a dev server listening on `port`.
+pub fn local_url(port: u16) -> String {
+    format!("http://localhost:{}", port)
+}
+
+// ============ Port probing ============
+
+/// Parse `lsof -F pn` output into `(pid, name)` pairs: `p` lines start a
+/// process, each following `n` line is one of its files.
+fn parse_lsof_fields(output: &str) -> Vec<(u32, String)> {
+    let mut pid = None;
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        if let Some(value) = line.strip_prefix('p') {
+            pid = value.parse().ok();
+        } else if let (Some(name), Some(pid)) = (line.strip_prefix('n'), pid) {
+            entries.push((pid, name.to_string()));
+        }
+    }
+    entries
+}
+
+/// Ports per process from `lsof -nP -iTCP -sTCP:LISTEN -F pn` output, where
+/// names look like `*:3000`, `127.0.0.1:5173` or `[::1]:8080`.
+pub fn parse_listening_ports(output: &str) -> Vec<(u32, u16)> {
+    let mut ports = Vec::new();
+    for (pid, name) in parse_lsof_fields(output) {
+        let Some(port) = name.rsplit_once(':').and_then(|(_, p)| p.parse().ok()) else {
+            continue;
+        };
+        if !ports.contains(&(pid, port)) {
+            ports.push((pid, port));
+        }
+    }
+    ports
+}
+
+/// Working directory per process from `lsof -a -d cwd -p ... -F pn` output.
+pub fn parse_process_cwds(output: &str) -> HashMap<u32, PathBuf> {
+    parse_lsof_fields(output)
+        .into_iter()
+        .map(|(pid, name)| (pid, PathBuf::from(name)))
+        .collect()
+}
+
+/// Ports of `listening` whose process runs inside `worktree_path`, sorted.
+pub fn ports_in_worktree(
+    listening: &[(u32, u16)],
+    cwds: &HashMap<u32, PathBuf>,
+    worktree_path: &Path,
+) -> Vec<u16> {
+    let mut ports: Vec<u16> = listening
+        .iter()
+        .filter(|(pid, _)| {
+            cwds.get(pid)
+                .is_some_and(|cwd| cwd.starts_with(worktree_path))
+        })
+        .map(|&(_, port)| port)
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Run `lsof`; `None` when it isn't installed.
+pub fn run_lsof(args: &[&str]) -> Option<String> {
+    let output = Command::new("lsof").args(args).output().ok()?;
+    // lsof exits with 1 when nothing matched
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// TCP ports listened on by processes running inside the worktree. Empty
+/// when there are none or `lsof` isn't available.
+pub fn detect_dev_ports(worktree_path: &str) -> Vec<u16> {
+    let worktree = Path::new(worktree_path);
+    let worktree = worktree
+        .canonicalize()
+        .unwrap_or_else(|_| worktree.to_path_buf());
+
+    let Some(output) = run_lsof(&["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pn"]) else {
+        return Vec::new();
+    };
+    let listening = parse_listening_ports(&output);
+    if listening.is_empty() {
+        return Vec::new();
+    }
+
+    let mut pids: Vec<String> = listening.iter().map(|(pid, _)| pid.to_string()).collect();
+    pids.dedup();
+    let Some(output) = run_lsof(&["-a", "-d", "cwd", "-p", &pids.join(","), "-F", "pn"]) else {
+        return Vec::new();
+    };
+    ports_in_worktree(&listening, &parse_process_cwds(&output), &worktree)
+}
+
+/// The URL to open for a worktree: the registered one, else the probed port
+/// reserved for its dev server (`{{port}}`), else the lowest probed port.
+pub fn resolve_dev_url(
+    registered: Option<&str>,
+    ports: &[u16],
+    reserved: Option<u16>,
+) -> Option<String> {
+    registered.map(str::to_string).or_else(|| {
+        reserved
+            .filter(|port| ports.contains(port))
+            .or_else(|| ports.iter().min().copied())
+            .map(local_url)
+    })
+}
+
+/// Open a URL in the default browser.
+pub fn open_url(url: &str) -> Result<(), String> {
+    let url = validate_dev_url(url)?;
+
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command
+        .arg(&url)
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", url, e))?;
+    Ok(())
+}
+
+/// Open the dev server of a worktree, probing for one when no URL is
+/// registered. Returns the opened URL.
+pub fn open_dev_url(worktree_path: &str, registered: Option<&str>) -> Result<String, String> {
+    let (ports, reserved) = match registered {
+        Some(_) => (Vec::new(), None),
+        None => (
+            detect_dev_ports(worktree_path),
+            ports::reserved_port(ports::DEV_SERVER_SERVICE, worktree_path)?,
+        ),
+    };
+    let url = resolve_dev_url(registered, &ports, reserved).ok_or_else(|| {
+        format!(
+            "No dev URL registered and no dev server found for {}",
+            worktree_path
+        )
+    })?;
+    open_url(&url)?;
+    Ok(url)
+}
+
+pub async fn detect_dev_ports_async(worktree_path: String) -> Result<Vec<u16>, String> {
+    tokio::task::spawn_blocking(move || detect_dev_ports(&worktree_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+pub async fn open_dev_url_async(
+    worktree_path: String,
+    registered: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || open_dev_url(&worktree_path, registered.as_deref()))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
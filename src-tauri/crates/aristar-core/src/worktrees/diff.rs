@@ -0,0 +1,303 @@
+//! What a worktree changed compared to its base ref.
+//!
+//! Changes are measured from the merge base of the base ref and the
+//! worktree's HEAD, so commits added to the base branch since the worktree
+//! started don't show up as reverted. By default the working tree is compared
+//! (committed, uncommitted and untracked changes); without
+//! `include_uncommitted` only HEAD is. Renames are reported as a deletion and
+//! an addition, as elsewhere in the app. Agent change summaries, comparisons
+//! and agreement reports use the same helpers.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::operations::{get_merge_base, run_git_command};
+
+/// Patches are cut after this many bytes.
+pub const MAX_PATCH_BYTES: usize = 2 * 1024 * 1024;
+
+/// How a file changed.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChangedFileStatus {
+    Added,
+    Modified,
+    Deleted,
+    /// File type changed (e.g. regular file to symlink)
+    TypeChanged,
+    /// Not tracked by git yet (working tree only)
+    Untracked,
+}
+
+/// A file that differs from the base.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangedFile {
+    pub path: String,
+    pub status: ChangedFileStatus,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub binary: bool,
+}
+
+/// Files a worktree changed against its base ref.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeChanges {
+    pub base_ref: String,
+    /// Commit the changes are measured from
+    pub merge_base: String,
+    pub include_uncommitted: bool,
+    /// Sorted by path
+    pub files: Vec<ChangedFile>,
+}
+
+/// Unified diff of a worktree against its base ref.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeDiff {
+    pub base_ref: String,
+    pub merge_base: String,
+    pub include_uncommitted: bool,
+    /// `git diff` output, with untracked files shown as added
+    pub patch: String,
+    /// The patch was cut at `MAX_PATCH_BYTES`
+    pub truncated: bool,
+}
+
+/// Parse `git diff --name-status -z` output into (status letter, path).
+pub fn parse_name_status_z(output: &str) -> Vec<(char, String)> {
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    let mut entries = Vec::new();
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        if let Some(letter) = status.chars().next() {
+            entries.push((letter, path.to_string()));
+        }
+    }
+    entries
+}
+
+/// Parse `git diff --numstat -z` output into (path, insertions, deletions).
+/// Binary files report `None` counts.
+pub fn parse_numstat_z(output: &str) -> Vec<(String, Option<u32>, Option<u32>)> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let mut cols = entry.splitn(3, '\t');
+            let added = cols.next()?;
+            let removed = cols.next()?;
+            let path = cols.next()?;
+            Some((path.to_string(), added.parse().ok(), removed.parse().ok()))
+        })
+        .collect()
+}
+
+/// Join name-status and numstat entries into changed files.
+pub fn build_changed_files(
+    statuses: &[(char, String)],
+    numstat: Vec<(String, Option<u32>, Option<u32>)>,
+) -> Vec<ChangedFile> {
+    numstat
+        .into_iter()
+        .map(|(path, added, removed)| {
+            let status = match statuses.iter().find(|(_, p)| *p == path).map(|(s, _)| *s) {
+                Some('A') => ChangedFileStatus::Added,
+                Some('D') => ChangedFileStatus::Deleted,
+                Some('T') => ChangedFileStatus::TypeChanged,
+                _ => ChangedFileStatus::Modified,
+            };
+            ChangedFile {
+                status,
+                insertions: added.unwrap_or(0),
+                deletions: removed.unwrap_or(0),
+                binary: added.is_none(),
+                path,
+            }
+        })
+        .collect()
+}
+
+/// Patch showing an untracked file as added.
+pub fn untracked_file_patch(path: &str, content: &[u8]) -> String {
+    let mut patch = format!("diff --git a/{0} b/{0}\nnew file mode 100644\n", path);
+    if content.contains(&0) {
+        patch.push_str(&format!("Binary files /dev/null and b/{} differ\n", path));
+        return patch;
+    }
+    let text = String::from_utf8_lossy(content);
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.is_empty() {
+        return patch;
+    }
+    patch.push_str(&format!(
+        "--- /dev/null\n+++ b/{}\n@@ -0,0 +1,{} @@\n",
+        path,
+        lines.len()
+    ));
+    for line in lines {
+        patch.push('+');
+        patch.push_str(line);
+        patch.push('\n');
+    }
+    if !text.ends_with('\n') {
+        patch.push_str("\\ No newline at end of file\n");
+    }
+    patch
+}
+
+/// Merge base of `base_ref` and the worktree's HEAD, the commit diffs
+/// against the base ref start from.
+pub fn diff_base(worktree_path: &str, base_ref: &str) -> Result<String, String> {
+    get_merge_base(worktree_path, base_ref, "HEAD")
+        .map_err(|e| format!("Failed to find base of {}: {}", base_ref, e))?
+        .ok_or_else(|| format!("Failed to find base of {}: no common history", base_ref))
+}
+
+/// Output of a `git diff` from `merge_base` with `extra` options, to HEAD
+/// unless the working tree is included, limited to `paths` when any are given.
+pub fn diff_output(
+    worktree_path: &str,
+    merge_base: &str,
+    include_uncommitted: bool,
+    extra: &[&str],
+    paths: &[String],
+) -> Result<String, String> {
+    let mut args = vec!["diff", "--no-color", "--no-renames", "--no-ext-diff"];
+    args.extend_from_slice(extra);
+    args.push(merge_base);
+    if !include_uncommitted {
+        args.push("HEAD");
+    }
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+    let output = run_git_command(&args, worktree_path)?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Untracked files of the worktree, limited to `paths` when any are given.
+pub fn untracked_files(worktree_path: &str, paths: &[String]) -> Result<Vec<String>, String> {
+    let mut args = vec!["ls-files", "--others", "--exclude-standard", "-z", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    let output = run_git_command(&args, worktree_path)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Files `worktree_path` changed since `merge_base`, sorted by path. With
+/// `include_uncommitted`, untracked files are listed too.
+pub fn changed_files(
+    worktree_path: &str,
+    merge_base: &str,
+    include_uncommitted: bool,
+) -> Result<Vec<ChangedFile>, String> {
+    let run =
+        |extra: &[&str]| diff_output(worktree_path, merge_base, include_uncommitted, extra, &[]);
+    let statuses = parse_name_status_z(&run(&["--name-status", "-z"])?);
+    let mut files = build_changed_files(&statuses, parse_numstat_z(&run(&["--numstat", "-z"])?));
+    if include_uncommitted {
+        for path in untracked_files(worktree_path, &[])? {
+            let content = fs::read(Path::new(worktree_path).join(&path)).unwrap_or_default();
+            let binary = content.contains(&0);
+            files.push(ChangedFile {
+                insertions: if binary {
+                    0
+                } else {
+                    String::from_utf8_lossy(&content).lines().count() as u32
+                },
+                deletions: 0,
+                status: ChangedFileStatus::Untracked,
+                binary,
+                path,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Files `worktree_path` changed since its merge base with `base_ref`.
+pub fn get_changed_files(
+    worktree_path: &str,
+    base_ref: &str,
+    include_uncommitted: bool,
+) -> Result<WorktreeChanges, String> {
+    let merge_base = diff_base(worktree_path, base_ref)?;
+    let files = changed_files(worktree_path, &merge_base, include_uncommitted)?;
+    Ok(WorktreeChanges {
+        base_ref: base_ref.to_string(),
+        merge_base,
+        include_uncommitted,
+        files,
+    })
+}
+
+/// Unified diff of `worktree_path` since its merge base with `base_ref`,
+/// limited to `paths` when any are given.
+pub fn get_diff(
+    worktree_path: &str,
+    base_ref: &str,
+    paths: &[String],
+    include_uncommitted: bool,
+) -> Result<WorktreeDiff, String> {
+    let merge_base = diff_base(worktree_path, base_ref)?;
+    let mut patch = diff_output(worktree_path, &merge_base, include_uncommitted, &[], paths)?;
+    if include_uncommitted {
+        for path in untracked_files(worktree_path, paths)? {
+            if patch.len() > MAX_PATCH_BYTES {
+                break;
+            }
+            let content = fs::read(Path::new(worktree_path).join(&path)).unwrap_or_default();
+            patch.push_str(&untracked_file_patch(&path, &content));
+        }
+    }
+
+    let truncated = patch.len() > MAX_PATCH_BYTES;
+    if truncated {
+        let mut cut = MAX_PATCH_BYTES;
+        while !patch.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        patch.truncate(cut);
+    }
+    Ok(WorktreeDiff {
+        base_ref: base_ref.to_string(),
+        merge_base,
+        include_uncommitted,
+        patch,
+        truncated,
+    })
+}
+
+/// List changed files (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_changed_files_async(
+    worktree_path: String,
+    base_ref: String,
+    include_uncommitted: bool,
+) -> Result<WorktreeChanges, String> {
+    tokio::task::spawn_blocking(move || {
+        get_changed_files(&worktree_path, &base_ref, include_uncommitted)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get a worktree's diff (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_diff_async(
+    worktree_path: String,
+    base_ref: String,
+    paths: Vec<String>,
+    include_uncommitted: bool,
+) -> Result<WorktreeDiff, String> {
+    tokio::task::spawn_blocking(move || {
+        get_diff(&worktree_path, &base_ref, &paths, include_uncommitted)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -2,6 +2,11 @@
 
 use std::process::Command;
 
+/// Shell metacharacters that could enable injection in a custom command.
+pub const FORBIDDEN_COMMAND_CHARS: [char; 13] = [
+    '|', ';', '&', '$', '`', '(', ')', '{', '}', '\n', '\r', '<', '>',
+];
+
 /// Validate a custom command to prevent command injection.
 /// Only allows absolute paths to known safe locations, no shell metacharacters.
 ///
@@ -34,8 +39,7 @@ pub fn validate_custom_command(cmd: &str) -> Result<(), String> {
     }
 
     // Disallow shell metacharacters that could enable injection
-    let forbidden_chars = ['|', ';', '&', '$', '`', '(', ')', '{', '}', '\n', '\r', '<', '>'];
-    if cmd.chars().any(|c| forbidden_chars.contains(&c)) {
+    if cmd.chars().any(|c| FORBIDDEN_COMMAND_CHARS.contains(&c)) {
         return Err("Custom command contains forbidden characters".to_string());
     }
 
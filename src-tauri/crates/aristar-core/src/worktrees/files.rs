@@ -0,0 +1,204 @@
+//! File tree of a worktree for the file browser.
+//!
+//! Files come from `git ls-files --cached --others --exclude-standard`, so
+//! `.gitignore`d files (build output, `node_modules`) are left out and untracked
+//! files are listed. Folders only appear when they contain a listed file.
+//!
+//! Trees are depth-limited: folders below `depth` are returned without children
+//! and loaded with another call for that folder. At most `MAX_FILE_TREE_ENTRIES`
+//! entries are returned; `truncated` is set when more were left out.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+
+use super::operations::run_git_command;
+
+/// Default number of folder levels returned.
+pub const DEFAULT_FILE_TREE_DEPTH: usize = 1;
+
+/// Deepest tree a single call returns.
+pub const MAX_FILE_TREE_DEPTH: usize = 10;
+
+/// Most entries a single call returns.
+pub const MAX_FILE_TREE_ENTRIES: usize = 5_000;
+
+/// A file or folder in a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTreeEntry {
+    pub name: String,
+    /// Relative to the worktree root, `/`-separated
+    pub path: String,
+    pub is_dir: bool,
+    /// Folder contents; `None` for files and for folders below the depth limit
+    pub children: Option<Vec<FileTreeEntry>>,
+}
+
+/// Entries under `dir` in a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTree {
+    /// Folder listed, relative to the worktree root (empty for the root)
+    pub dir: String,
+    pub entries: Vec<FileTreeEntry>,
+    /// More entries exist than were returned
+    pub truncated: bool,
+}
+
+#[derive(Default)]
+struct Node {
+    is_dir: bool,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn into_entries(self, prefix: &str, level: usize, depth: usize) -> Vec<FileTreeEntry> {
+        let mut entries: Vec<FileTreeEntry> = self
+            .children
+            .into_iter()
+            .map(|(name, node)| {
+                let path = if prefix.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", prefix, name)
+                };
+                let is_dir = node.is_dir;
+                let children =
+                    (is_dir && level < depth).then(|| node.into_entries(&path, level + 1, depth));
+                FileTreeEntry {
+                    name,
+                    path,
+                    is_dir,
+                    children,
+                }
+            })
+            .collect();
+        // Folders first, then by name
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        entries
+    }
+}
+
+/// Build a tree from `/`-separated file paths relative to the listed folder.
+/// Returns the entries and whether some were left out to stay within
+/// `max_entries`.
+pub fn build_file_tree(
+    files: &[&str],
+    prefix: &str,
+    depth: usize,
+    max_entries: usize,
+) -> (Vec<FileTreeEntry>, bool) {
+    let mut root = Node::default();
+    let mut count = 0;
+    let mut truncated = false;
+
+    for file in files {
+        let parts: Vec<&str> = file.split('/').filter(|p| !p.is_empty()).collect();
+        let mut node = &mut root;
+        for (i, part) in parts.iter().enumerate() {
+            let is_last = i + 1 == parts.len();
+            // Deeper parts are left out; their folder is returned without children
+            if i >= depth {
+                break;
+            }
+            if !node.children.contains_key(*part) {
+                if count >= max_entries {
+                    truncated = true;
+                    break;
+                }
+                count += 1;
+            }
+            node = node.children.entry(part.to_string()).or_default();
+            node.is_dir |= !is_last;
+        }
+    }
+
+    (root.into_entries(prefix, 1, depth), truncated)
+}
+
+/// Check that `dir` is a relative path that stays inside the worktree.
+pub fn validate_relative_dir(dir: &str) -> Result<(), String> {
+    let valid = Path::new(dir)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    if !valid {
+        return Err(format!("Folder must be relative to the worktree: {}", dir));
+    }
+    Ok(())
+}
+
+/// List files under `dir` (the root when `None`) in the worktree at `path`,
+/// `depth` folder levels deep (default 1, at most `MAX_FILE_TREE_DEPTH`).
+pub fn list_worktree_files(
+    path: &str,
+    dir: Option<&str>,
+    depth: Option<usize>,
+) -> Result<FileTree, String> {
+    if !Path::new(path).is_dir() {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    let dir = match dir.unwrap_or("").trim_matches('/') {
+        "." => "",
+        dir => dir,
+    };
+    validate_relative_dir(dir)?;
+    if !dir.is_empty() && !Path::new(path).join(dir).is_dir() {
+        return Err(format!("Folder not found: {}", dir));
+    }
+    let depth = depth
+        .unwrap_or(DEFAULT_FILE_TREE_DEPTH)
+        .clamp(1, MAX_FILE_TREE_DEPTH);
+
+    let pathspec = if dir.is_empty() { "." } else { dir };
+    let output = run_git_command(
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+            "--",
+            pathspec,
+        ],
+        path,
+    )
+    .map_err(|e| format!("Failed to list files: {}", e.trim()))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let root = Path::new(path);
+    let mut files: Vec<&str> = stdout
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        // Tracked files deleted from disk are still in the index
+        .filter(|f| root.join(f).exists())
+        .filter_map(|f| {
+            if dir.is_empty() {
+                Some(f)
+            } else {
+                f.strip_prefix(dir).and_then(|rest| rest.strip_prefix('/'))
+            }
+        })
+        .collect();
+    files.dedup();
+
+    let (entries, truncated) = build_file_tree(&files, dir, depth, MAX_FILE_TREE_ENTRIES);
+    Ok(FileTree {
+        dir: dir.to_string(),
+        entries,
+        truncated,
+    })
+}
+
+/// List worktree files (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn list_worktree_files_async(
+    path: String,
+    dir: Option<String>,
+    depth: Option<usize>,
+) -> Result<FileTree, String> {
+    tokio::task::spawn_blocking(move || list_worktree_files(&path, dir.as_deref(), depth))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
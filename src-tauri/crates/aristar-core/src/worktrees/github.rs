@@ -0,0 +1,165 @@
+//! Branch protection rules from GitHub.
+//!
+//! With a GitHub token configured (`settings.github_token`, else the
+//! `GITHUB_TOKEN` / `GH_TOKEN` environment variables), the protected branches
+//! of a repository whose remote is on github.com are fetched from the REST API
+//! and stored as `Repository::remote_protected_branches`. They count as
+//! protected like the local list, so they are never deleted with a worktree,
+//! and force-pushing to them is refused before git tries (the server would
+//! reject it anyway). The token is passed to `curl` on stdin, never as an
+//! argument.
+
+use chrono::Utc;
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::operations::run_git_command;
+use super::sync::resolve_remote;
+
+/// Environment variables checked for a token when the settings have none.
+pub const GITHUB_TOKEN_ENV: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Protected branches listed per request (GitHub's maximum page size).
+const BRANCHES_PER_PAGE: usize = 100;
+
+/// Pages fetched at most (10 000 protected branches).
+const MAX_PAGES: usize = 100;
+
+/// The token from the settings, else from the environment.
+pub fn resolve_github_token(configured: Option<&str>) -> Option<String> {
+    configured
+        .map(str::to_string)
+        .or_else(|| {
+            GITHUB_TOKEN_ENV
+                .iter()
+                .find_map(|var| std::env::var(var).ok())
+        })
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// `(owner, repo)` of a github.com remote URL: `git@github.com:owner/repo.git`,
+/// `https://github.com/owner/repo` or `ssh://git@github.com/owner/repo.git`.
+pub fn parse_github_remote(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let path = url.strip_prefix("git@github.com:").or_else(|| {
+        let rest = url.split_once("://")?.1;
+        // Drop credentials, e.g. https://user@github.com/...
+        let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+        rest.strip_prefix("github.com/")
+            .or_else(|| rest.strip_prefix("www.github.com/"))
+    })?;
+
+    let (owner, repo) = path.trim_end_matches('/').split_once('/')?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    let valid = |s: &str| {
+        !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    (valid(owner) && valid(repo)).then(|| (owner.to_string(), repo.to_string()))
+}
+
+/// Branch names in a `GET /repos/{owner}/{repo}/branches` response.
+pub fn parse_branch_names(body: &[u8]) -> Result<Vec<String>, String> {
+    let json: Value = serde_json::from_slice(body)
+        .map_err(|e| format!("Failed to parse GitHub branches: {}", e))?;
+    let branches = json.as_array().ok_or_else(|| {
+        let message = json.get("message").and_then(Value::as_str);
+        format!(
+            "Unexpected GitHub response: {}",
+            message.unwrap_or("not a list")
+        )
+    })?;
+    Ok(branches
+        .iter()
+        .filter_map(|b| b.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect())
+}
+
+/// GET a GitHub API URL, with the token sent as a header read from stdin.
+fn github_get(url: &str, token: &str) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("curl")
+        .args(["-fsSL", "--retry", "2", "-H", "@-"])
+        .args(["-H", "Accept: application/vnd.github+json", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("Authorization: Bearer {}\n", token).as_bytes())
+            .map_err(|e| format!("Failed to pass the GitHub token to curl: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "GitHub request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Names of the protected branches of `owner/repo`.
+pub fn fetch_protected_branches(
+    owner: &str,
+    repo: &str,
+    token: &str,
+) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    for page in 1..=MAX_PAGES {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/branches?protected=true&per_page={}&page={}",
+            owner, repo, BRANCHES_PER_PAGE, page
+        );
+        let batch = parse_branch_names(&github_get(&url, token)?)?;
+        let done = batch.len() < BRANCHES_PER_PAGE;
+        names.extend(batch);
+        if done {
+            break;
+        }
+    }
+    Ok(names)
+}
+
+/// The github.com `(owner, repo)` of the repository's default remote.
+pub fn github_repo_of(repo_path: &str) -> Option<(String, String)> {
+    let remote = resolve_remote(repo_path, None).ok()?;
+    let output = run_git_command(&["remote", "get-url", &remote], repo_path).ok()?;
+    parse_github_remote(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Fetch the protected branches of a repository from GitHub. Returns `None`
+/// when its remote isn't on github.com. Also returns when they were checked.
+pub fn fetch_repository_protection(
+    repo_path: &str,
+    token: &str,
+) -> Result<Option<(Vec<String>, i64)>, String> {
+    let Some((owner, repo)) = github_repo_of(repo_path) else {
+        return Ok(None);
+    };
+    let branches = fetch_protected_branches(&owner, &repo, token)?;
+    println!(
+        "[github] {}/{} has {} protected branch(es)",
+        owner,
+        repo,
+        branches.len()
+    );
+    Ok(Some((branches, Utc::now().timestamp_millis())))
+}
+
+pub async fn fetch_repository_protection_async(
+    repo_path: String,
+    token: String,
+) -> Result<Option<(Vec<String>, i64)>, String> {
+    tokio::task::spawn_blocking(move || fetch_repository_protection(&repo_path, &token))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
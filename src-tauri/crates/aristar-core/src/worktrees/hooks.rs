@@ -0,0 +1,112 @@
+//! Git hooks for new worktrees.
+//!
+//! Hooks in `.git/hooks` (or an absolute `core.hooksPath`) are shared by every
+//! worktree already. A relative `core.hooksPath` is resolved against the
+//! worktree the hook runs in, so it breaks when the folder is generated rather
+//! than committed: husky points it at `.husky/_`, which `npm install` creates
+//! and `.gitignore` hides. New worktrees get a copy of such a folder from the
+//! source repository (only files missing in the worktree are copied). When the
+//! copy would show up as untracked, it is added to `info/exclude`.
+//!
+//! Enabled unless `settings.copy_hooks` is `false` (read at startup).
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::operations::{ensure_excluded, run_git_command};
+
+static COPY_HOOKS: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable copying hooks into new worktrees.
+pub fn set_copy_hooks(enabled: bool) {
+    COPY_HOOKS.store(enabled, Ordering::SeqCst);
+}
+
+/// The relative `core.hooksPath` of the repository at `repo_path`, if set.
+/// Unset and absolute paths need no copying and return `None`.
+pub fn relative_hooks_path(repo_path: &str) -> Option<PathBuf> {
+    let output = run_git_command(&["config", "--get", "core.hooksPath"], repo_path).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let path = PathBuf::from(&value);
+    if value.is_empty() || value.starts_with('~') || path.is_absolute() {
+        return None;
+    }
+    // Stay inside the worktree
+    if !path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return None;
+    }
+    Some(path)
+}
+
+/// Copy the files of `source` missing in `target`, recursively. Permissions
+/// (the executable bit of hooks) are kept. Returns the number of files copied.
+pub fn copy_missing_files(source: &Path, target: &Path) -> Result<usize, String> {
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+
+    let mut copied = 0;
+    for entry in entries.flatten() {
+        let from = entry.path();
+        let to = target.join(entry.file_name());
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            copied += copy_missing_files(&from, &to)?;
+        } else if !to.exists() {
+            fs::copy(&from, &to)
+                .map_err(|e| format!("Failed to copy hook {}: {}", from.display(), e))?;
+            copied += 1;
+        }
+    }
+    Ok(copied)
+}
+
+/// Copy the hooks folder of a relative `core.hooksPath` from `repo_path` into
+/// `worktree_path`. Returns the number of files copied.
+pub fn copy_hooks_into_worktree(repo_path: &str, worktree_path: &str) -> Result<usize, String> {
+    let Some(hooks_path) = relative_hooks_path(repo_path) else {
+        return Ok(0);
+    };
+    let source = Path::new(repo_path).join(&hooks_path);
+    if !source.is_dir() {
+        return Ok(0);
+    }
+
+    let copied = copy_missing_files(&source, &Path::new(worktree_path).join(&hooks_path))?;
+    if copied == 0 {
+        return Ok(0);
+    }
+
+    let relative = hooks_path.to_string_lossy().replace('\\', "/");
+    let relative = relative.trim_start_matches("./").trim_end_matches('/');
+    let ignored = run_git_command(&["check-ignore", "-q", relative], worktree_path).is_ok();
+    let tracked = run_git_command(&["ls-files", "--", relative], worktree_path)
+        .map(|o| !o.stdout.is_empty())
+        .unwrap_or(false);
+    if !ignored && !tracked {
+        ensure_excluded(Path::new(worktree_path), &format!("/{}/", relative))?;
+    }
+    Ok(copied)
+}
+
+/// Copy hooks into a newly created worktree. Failures are logged, never fatal.
+pub fn setup_worktree_hooks(repo_path: &str, worktree_path: &str) {
+    if !COPY_HOOKS.load(Ordering::SeqCst) {
+        return;
+    }
+    match copy_hooks_into_worktree(repo_path, worktree_path) {
+        Ok(0) => {}
+        Ok(count) => println!(
+            "[hooks] Copied {} hook file(s) into {}",
+            count, worktree_path
+        ),
+        Err(e) => eprintln!("[hooks] {}", e),
+    }
+}
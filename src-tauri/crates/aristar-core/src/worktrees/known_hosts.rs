@@ -0,0 +1,317 @@
+//! SSH host keys for fetch, pull and push.
+//!
+//! Git runs ssh without a terminal, so the first connection to a host fails on
+//! host key verification with nobody to ask. Remote operations therefore run
+//! ssh in batch mode, with the app's own `~/.aristar-worktrees/known_hosts`
+//! added to the global known_hosts files, unless the user set
+//! `GIT_SSH_COMMAND`, `GIT_SSH` or `core.sshCommand`. When a host is unknown its
+//! keys are scanned with `ssh-keyscan` and `ssh-host-key-unknown` carries their
+//! fingerprints; `trust_ssh_host` appends the approved keys to the app's file,
+//! after which the operation can be retried. A changed host key is reported as
+//! an error and never offered for approval.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::core::get_aristar_worktrees_base;
+
+use super::operations::run_git_command;
+use super::sync::SyncOperation;
+
+pub use crate::core::events::SSH_HOST_KEY_UNKNOWN_EVENT;
+
+/// Seconds `ssh-keyscan` waits for a host.
+const KEYSCAN_TIMEOUT_SECS: &str = "5";
+
+/// A public key offered by an SSH host.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SshHostKey {
+    /// e.g. `ssh-ed25519`
+    pub key_type: String,
+    /// `SHA256:...`, as printed by `ssh-keygen -l`
+    pub fingerprint: String,
+}
+
+/// Payload of `ssh-host-key-unknown`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownSshHost {
+    pub host: String,
+    /// `None` for the default port 22
+    pub port: Option<u16>,
+    /// Worktree whose operation failed, to retry it after approval
+    pub worktree_path: String,
+    pub operation: SyncOperation,
+    /// Keys the host offers now; empty when it could not be scanned
+    pub keys: Vec<SshHostKey>,
+}
+
+type UnknownHostListener = Box<dyn Fn(UnknownSshHost) + Send + Sync>;
+
+static LISTENER: OnceLock<UnknownHostListener> = OnceLock::new();
+
+/// Set the function told about unknown hosts. Only the first call has an effect.
+pub fn set_unknown_host_listener(listener: impl Fn(UnknownSshHost) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+/// The app's known_hosts file (`~/.aristar-worktrees/known_hosts`).
+pub fn app_known_hosts_path() -> PathBuf {
+    get_aristar_worktrees_base().join("known_hosts")
+}
+
+/// Quote `value` for the shell git runs `GIT_SSH_COMMAND` with.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// ssh's default global known_hosts files, which `GlobalKnownHostsFile` replaces.
+const SYSTEM_KNOWN_HOSTS: &str = "/etc/ssh/ssh_known_hosts /etc/ssh/ssh_known_hosts2";
+
+/// The ssh command remote operations run with: no prompts (so unknown hosts
+/// are refused), and the app's known_hosts read after the system-wide ones.
+/// The user's own known_hosts and host key checking settings still apply.
+pub fn ssh_command(app_known_hosts: &str) -> String {
+    let files = format!(
+        "GlobalKnownHostsFile={} \"{}\"",
+        SYSTEM_KNOWN_HOSTS, app_known_hosts
+    );
+    format!("ssh -o BatchMode=yes -o {}", shell_quote(&files))
+}
+
+/// Make `command` (a git command in `path`) use `ssh_command`, unless the user
+/// chose their own ssh command.
+pub fn apply_ssh_command(command: &mut Command, path: &str) {
+    let user_set = ["GIT_SSH_COMMAND", "GIT_SSH"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+        || run_git_command(&["config", "--get", "core.sshCommand"], path).is_ok();
+    if !user_set {
+        let known_hosts = app_known_hosts_path();
+        command.env(
+            "GIT_SSH_COMMAND",
+            ssh_command(&known_hosts.to_string_lossy()),
+        );
+    }
+}
+
+/// Whether ssh refused a host whose key is in no known_hosts file.
+pub fn is_unknown_host_error(stderr: &str) -> bool {
+    !is_changed_host_key_error(stderr)
+        && (stderr.contains("Host key verification failed")
+            || (stderr.contains("host key is known for")
+                && stderr.contains("requested strict checking")))
+}
+
+/// Whether ssh refused a host whose key differs from the known one.
+pub fn is_changed_host_key_error(stderr: &str) -> bool {
+    stderr.contains("REMOTE HOST IDENTIFICATION HAS CHANGED")
+}
+
+/// Host and port (`None` for the default) of an SSH remote URL:
+/// `ssh://[user@]host[:port]/path` or scp-like `[user@]host:path`. Other
+/// remotes (https, file paths) give `None`.
+pub fn parse_ssh_remote(url: &str) -> Option<(String, Option<u16>)> {
+    let url = url.trim();
+    let (authority, explicit_port) = if let Some((scheme, rest)) = url.split_once("://") {
+        if !matches!(scheme, "ssh" | "git+ssh" | "ssh+git") {
+            return None;
+        }
+        let authority = rest.split('/').next()?;
+        let authority = authority.rsplit('@').next()?;
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse::<u16>().ok()?)),
+            None => (authority, None),
+        }
+    } else {
+        let (authority, _path) = url.split_once(':')?;
+        // A slash before the colon makes it a local path
+        if authority.contains('/') || authority.contains('\\') {
+            return None;
+        }
+        (authority.rsplit('@').next()?, None)
+    };
+
+    if authority.is_empty() || authority.starts_with('-') || authority.starts_with('[') {
+        return None;
+    }
+    Some((
+        authority.to_string(),
+        explicit_port.filter(|&port| port != 22),
+    ))
+}
+
+/// How a host appears in known_hosts: `host`, or `[host]:port`.
+pub fn known_hosts_name(host: &str, port: Option<u16>) -> String {
+    match port {
+        Some(port) => format!("[{}]:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+/// Key type and key of each `ssh-keyscan` line, comments skipped.
+pub fn parse_keyscan_output(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter(|l| !l.trim_start().starts_with('#'))
+        .filter_map(|l| {
+            let mut fields = l.split_whitespace();
+            let _host = fields.next()?;
+            Some((fields.next()?.to_string(), fields.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// `SHA256:...` fingerprint of a public key, from `ssh-keygen -l`.
+fn fingerprint(key_type: &str, key: &str) -> Result<String, String> {
+    let mut child = Command::new("ssh-keygen")
+        .args(["-l", "-E", "sha256", "-f", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{} {}", key_type, key).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "ssh-keygen failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)
+        .map(str::to_string)
+        .ok_or_else(|| "ssh-keygen printed no fingerprint".to_string())
+}
+
+/// Keys the host offers, with the key itself for writing to known_hosts.
+fn scan_host(host: &str, port: Option<u16>) -> Result<Vec<(SshHostKey, String)>, String> {
+    if host.is_empty() || host.starts_with('-') {
+        return Err(format!("Invalid host: {:?}", host));
+    }
+    let port = port.unwrap_or(22).to_string();
+    let output = Command::new("ssh-keyscan")
+        .args(["-T", KEYSCAN_TIMEOUT_SECS, "-p", &port, host])
+        .output()
+        .map_err(|e| format!("Failed to run ssh-keyscan: {}", e))?;
+
+    let keys = parse_keyscan_output(&String::from_utf8_lossy(&output.stdout));
+    if keys.is_empty() {
+        return Err(format!("{} offered no host keys", host));
+    }
+    keys.into_iter()
+        .map(|(key_type, key)| {
+            let fingerprint = fingerprint(&key_type, &key)?;
+            Ok((
+                SshHostKey {
+                    key_type,
+                    fingerprint,
+                },
+                key,
+            ))
+        })
+        .collect()
+}
+
+/// Keys the host offers now, to show before trusting it.
+pub fn scan_host_keys(host: &str, port: Option<u16>) -> Result<Vec<SshHostKey>, String> {
+    Ok(scan_host(host, port)?.into_iter().map(|(k, _)| k).collect())
+}
+
+/// Tell the listener that `operation` in `path` failed on an unknown host of
+/// `remote`, with the keys the host offers.
+pub fn report_unknown_host(operation: SyncOperation, path: &str, remote: &str) {
+    let Some(listener) = LISTENER.get() else {
+        return;
+    };
+    let url = match run_git_command(&["remote", "get-url", remote], path) {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        Err(_) => return,
+    };
+    let Some((host, port)) = parse_ssh_remote(&url) else {
+        return;
+    };
+
+    let keys = scan_host_keys(&host, port).unwrap_or_else(|e| {
+        eprintln!("[known_hosts] {}", e);
+        Vec::new()
+    });
+    listener(UnknownSshHost {
+        host,
+        port,
+        worktree_path: path.to_string(),
+        operation,
+        keys,
+    });
+}
+
+/// Add the keys of `host` whose fingerprints were approved to the app's
+/// known_hosts. The host is scanned again, so a key that changed since it was
+/// shown is not written. Returns the keys added or already trusted.
+pub fn trust_ssh_host(
+    host: &str,
+    port: Option<u16>,
+    fingerprints: &[String],
+) -> Result<Vec<SshHostKey>, String> {
+    if fingerprints.is_empty() {
+        return Err("No host key fingerprints approved".to_string());
+    }
+    let port = port.filter(|&p| p != 22);
+    let approved: Vec<(SshHostKey, String)> = scan_host(host, port)?
+        .into_iter()
+        .filter(|(k, _)| fingerprints.contains(&k.fingerprint))
+        .collect();
+    if approved.is_empty() {
+        return Err(format!(
+            "{} no longer offers the approved keys; check its fingerprints again",
+            host
+        ));
+    }
+
+    let path = app_known_hosts_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let name = known_hosts_name(host, port);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    for (key, blob) in &approved {
+        let line = format!("{} {} {}", name, key.key_type, blob);
+        if !existing.lines().any(|l| l.trim() == line) {
+            writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+        }
+    }
+
+    println!(
+        "[known_hosts] Trusted {} key(s) of {}",
+        approved.len(),
+        name
+    );
+    Ok(approved.into_iter().map(|(k, _)| k).collect())
+}
+
+/// Trust a host's approved keys (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn trust_ssh_host_async(
+    host: String,
+    port: Option<u16>,
+    fingerprints: Vec<String>,
+) -> Result<Vec<SshHostKey>, String> {
+    tokio::task::spawn_blocking(move || trust_ssh_host(&host, port, &fingerprints))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,200 @@
+//! Labels on worktrees and repositories.
+//!
+//! Labels are short tags ("wip", "review", "agent") kept in the app store,
+//! never in git. They are lowercased and checked by `normalize_label`, and
+//! each worktree's and repository's list is kept sorted without duplicates.
+//! `collect_labels` groups repositories and worktrees by label;
+//! `filter_by_labels` narrows a listing to those carrying some (or all) of the
+//! given labels.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::types::Repository;
+
+/// Longest label, in characters.
+pub const MAX_LABEL_LEN: usize = 32;
+
+/// Most labels on one worktree or repository.
+pub const MAX_LABELS: usize = 20;
+
+/// A label in use, with what carries it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelUsage {
+    pub label: String,
+    /// Ids of the repositories labeled with it
+    pub repository_ids: Vec<String>,
+    /// Paths of the worktrees labeled with it
+    pub worktree_paths: Vec<String>,
+}
+
+/// A label as stored: trimmed and lowercased. Letters, digits and `-_./:` are
+/// allowed, starting with a letter or digit.
+pub fn normalize_label(label: &str) -> Result<String, String> {
+    let label = label.trim().to_lowercase();
+    if label.is_empty() {
+        return Err("Label can't be empty".to_string());
+    }
+    if label.chars().count() > MAX_LABEL_LEN {
+        return Err(format!(
+            "Label is too long (at most {} characters): {}",
+            MAX_LABEL_LEN, label
+        ));
+    }
+    if !label.starts_with(|c: char| c.is_alphanumeric())
+        || !label
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./:".contains(c))
+    {
+        return Err(format!("Invalid label: {}", label));
+    }
+    Ok(label)
+}
+
+/// Normalize `labels`, sorted without duplicates.
+pub fn normalize_labels(labels: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized = labels
+        .iter()
+        .map(|l| normalize_label(l))
+        .collect::<Result<Vec<_>, _>>()?;
+    normalized.sort();
+    normalized.dedup();
+    Ok(normalized)
+}
+
+/// Add `labels` to `current`. Fails, changing nothing, when a label is invalid
+/// or the result would have more than `MAX_LABELS`.
+pub fn add_labels(current: &mut Vec<String>, labels: &[String]) -> Result<(), String> {
+    let mut merged = normalize_labels(labels)?;
+    merged.extend(current.iter().cloned());
+    merged.sort();
+    merged.dedup();
+    if merged.len() > MAX_LABELS {
+        return Err(format!("At most {} labels are allowed", MAX_LABELS));
+    }
+    *current = merged;
+    Ok(())
+}
+
+/// Remove `labels` from `current`, compared case-insensitively. Labels not
+/// present are ignored. Returns whether anything was removed.
+pub fn remove_labels(current: &mut Vec<String>, labels: &[String]) -> bool {
+    let labels: Vec<String> = labels.iter().map(|l| l.trim().to_lowercase()).collect();
+    let before = current.len();
+    current.retain(|l| !labels.contains(l));
+    current.len() != before
+}
+
+fn usage_entry<'a>(usage: &'a mut BTreeMap<String, LabelUsage>, label: &str) -> &'a mut LabelUsage {
+    usage
+        .entry(label.to_string())
+        .or_insert_with(|| LabelUsage {
+            label: label.to_string(),
+            repository_ids: Vec::new(),
+            worktree_paths: Vec::new(),
+        })
+}
+
+/// Every label in use, sorted, with the repositories and worktrees carrying it.
+pub fn collect_labels(repositories: &[Repository]) -> Vec<LabelUsage> {
+    let mut usage = BTreeMap::new();
+    for repo in repositories {
+        for label in &repo.labels {
+            usage_entry(&mut usage, label)
+                .repository_ids
+                .push(repo.id.clone());
+        }
+        for worktree in &repo.worktrees {
+            for label in &worktree.labels {
+                usage_entry(&mut usage, label)
+                    .worktree_paths
+                    .push(worktree.path.clone());
+            }
+        }
+    }
+    usage.into_values().collect()
+}
+
+fn replace_label(labels: &mut Vec<String>, from: &str, to: &str) -> bool {
+    if !labels.iter().any(|l| l == from) {
+        return false;
+    }
+    labels.retain(|l| l != from);
+    labels.push(to.to_string());
+    labels.sort();
+    labels.dedup();
+    true
+}
+
+/// Rename a label everywhere; where both labels are present they are merged.
+/// Returns how many repositories and worktrees were changed.
+pub fn rename_label(
+    repositories: &mut [Repository],
+    from: &str,
+    to: &str,
+) -> Result<usize, String> {
+    let from = normalize_label(from)?;
+    let to = normalize_label(to)?;
+    if from == to {
+        return Ok(0);
+    }
+    let mut changed = 0;
+    for repo in repositories {
+        changed += usize::from(replace_label(&mut repo.labels, &from, &to));
+        for worktree in &mut repo.worktrees {
+            changed += usize::from(replace_label(&mut worktree.labels, &from, &to));
+        }
+    }
+    Ok(changed)
+}
+
+/// Remove a label everywhere. Returns how many repositories and worktrees
+/// carried it.
+pub fn delete_label(repositories: &mut [Repository], label: &str) -> usize {
+    let label = [label.to_string()];
+    let mut changed = 0;
+    for repo in repositories {
+        changed += usize::from(remove_labels(&mut repo.labels, &label));
+        for worktree in &mut repo.worktrees {
+            changed += usize::from(remove_labels(&mut worktree.labels, &label));
+        }
+    }
+    changed
+}
+
+/// Whether `item_labels` carries all of `labels` (`match_all`) or any of them.
+pub fn matches_labels(item_labels: &[String], labels: &[String], match_all: bool) -> bool {
+    if match_all {
+        labels.iter().all(|l| item_labels.contains(l))
+    } else {
+        labels.iter().any(|l| item_labels.contains(l))
+    }
+}
+
+/// The repositories matching `labels`, with all their worktrees, and of the
+/// others those with matching worktrees, with only those. An empty `labels`
+/// matches everything.
+pub fn filter_by_labels(
+    repositories: &[Repository],
+    labels: &[String],
+    match_all: bool,
+) -> Result<Vec<Repository>, String> {
+    let labels = normalize_labels(labels)?;
+    if labels.is_empty() {
+        return Ok(repositories.to_vec());
+    }
+    Ok(repositories
+        .iter()
+        .filter_map(|repo| {
+            if matches_labels(&repo.labels, &labels, match_all) {
+                return Some(repo.clone());
+            }
+            let mut repo = repo.clone();
+            repo.worktrees
+                .retain(|w| matches_labels(&w.labels, &labels, match_all));
+            (!repo.worktrees.is_empty()).then_some(repo)
+        })
+        .collect())
+}
@@ -0,0 +1,214 @@
+//! Git LFS setup for new worktrees.
+//!
+//! Without LFS filters configured, `git worktree add` checks out pointer files
+//! instead of the real content, which leaves agents working on stubs. When a
+//! repository uses LFS (a `filter=lfs` attribute or an `lfs/` folder in the git
+//! dir), every new worktree gets `git lfs install --local` and `git lfs pull`.
+//! Progress is reported through `lfs-pull-progress` events. Failures are
+//! reported and logged but never fail the worktree creation.
+//!
+//! Enabled unless `settings.lfs_auto_pull` is `false` (read at startup).
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use super::operations::{get_repo_context, run_git_command};
+
+pub use crate::core::events::LFS_PULL_PROGRESS_EVENT;
+
+/// Stage of an LFS pull.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum LfsPullStage {
+    Started,
+    Progress,
+    Completed,
+    Failed,
+}
+
+/// Payload of `lfs-pull-progress`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LfsPullProgress {
+    pub worktree_path: String,
+    pub stage: LfsPullStage,
+    pub percent: Option<u8>,
+    pub completed_files: Option<u64>,
+    pub total_files: Option<u64>,
+    /// Error for `failed`
+    pub message: Option<String>,
+}
+
+impl LfsPullProgress {
+    fn new(worktree_path: &str, stage: LfsPullStage) -> Self {
+        Self {
+            worktree_path: worktree_path.to_string(),
+            stage,
+            percent: None,
+            completed_files: None,
+            total_files: None,
+            message: None,
+        }
+    }
+
+    fn failed(worktree_path: &str, message: String) -> Self {
+        Self {
+            message: Some(message),
+            ..Self::new(worktree_path, LfsPullStage::Failed)
+        }
+    }
+}
+
+type ProgressListener = Box<dyn Fn(LfsPullProgress) + Send + Sync>;
+
+static AUTO_PULL: AtomicBool = AtomicBool::new(true);
+static LISTENER: OnceLock<ProgressListener> = OnceLock::new();
+
+/// Enable or disable LFS setup for new worktrees.
+pub fn set_auto_pull(enabled: bool) {
+    AUTO_PULL.store(enabled, Ordering::SeqCst);
+}
+
+/// Set the function told about pull progress. Only the first call has an effect.
+pub fn set_progress_listener(listener: impl Fn(LfsPullProgress) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+fn report(progress: LfsPullProgress) {
+    if let Some(listener) = LISTENER.get() {
+        listener(progress);
+    }
+}
+
+/// Whether `.gitattributes` in `root` routes files through the LFS filter.
+pub fn gitattributes_use_lfs(root: &Path) -> bool {
+    std::fs::read_to_string(root.join(".gitattributes"))
+        .map(|content| content.contains("filter=lfs"))
+        .unwrap_or(false)
+}
+
+/// Whether the `git lfs` command is available.
+pub fn git_lfs_installed() -> bool {
+    Command::new("git")
+        .args(["lfs", "version"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Whether the repository or worktree at `path` uses Git LFS.
+pub fn uses_lfs(path: &str) -> bool {
+    gitattributes_use_lfs(Path::new(path))
+        || get_repo_context(path)
+            .map(|context| context.common_dir.join("lfs").is_dir())
+            .unwrap_or(false)
+}
+
+/// Parse a `git lfs pull` progress line:
+/// `Downloading LFS objects:  50% (1/2), 1.2 MB | 1.0 MB/s`.
+/// Returns the percentage and the completed and total file counts.
+pub fn parse_lfs_progress(line: &str) -> Option<(u8, u64, u64)> {
+    let rest = line.trim().strip_prefix("Downloading LFS objects:")?;
+    let (percent, rest) = rest.trim_start().split_once('%')?;
+    let percent = percent.trim().parse().ok()?;
+    let counts = rest.trim_start().strip_prefix('(')?.split(')').next()?;
+    let (done, total) = counts.split_once('/')?;
+    Some((
+        percent,
+        done.trim().parse().ok()?,
+        total.trim().parse().ok()?,
+    ))
+}
+
+/// Run `git lfs install --local` and `git lfs pull` in `worktree_path`,
+/// reporting progress.
+pub fn pull_lfs_objects(worktree_path: &str) -> Result<(), String> {
+    run_git_command(&["lfs", "install", "--local"], worktree_path)
+        .map_err(|e| format!("git lfs install failed: {}", e.trim()))?;
+
+    let mut child = Command::new("git")
+        .args(["lfs", "pull"])
+        .current_dir(worktree_path)
+        // Progress is only written to terminals otherwise
+        .env("GIT_LFS_FORCE_PROGRESS", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git lfs pull: {}", e))?;
+
+    let mut stderr = child.stderr.take().ok_or("Failed to read git lfs output")?;
+    let mut output = Vec::new();
+    let mut line = Vec::new();
+    let mut last_percent = None;
+    let mut buf = [0u8; 1024];
+    while let Ok(n) = stderr.read(&mut buf) {
+        if n == 0 {
+            break;
+        }
+        for &byte in &buf[..n] {
+            output.push(byte);
+            // Progress lines are redrawn with `\r`
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            if let Some((percent, done, total)) =
+                parse_lfs_progress(&String::from_utf8_lossy(&line))
+            {
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    report(LfsPullProgress {
+                        percent: Some(percent),
+                        completed_files: Some(done),
+                        total_files: Some(total),
+                        ..LfsPullProgress::new(worktree_path, LfsPullStage::Progress)
+                    });
+                }
+            }
+            line.clear();
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        let output = String::from_utf8_lossy(&output);
+        let last = output.lines().rev().find(|l| !l.trim().is_empty());
+        return Err(format!(
+            "git lfs pull failed: {}",
+            last.unwrap_or("unknown error").trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Download LFS objects into a newly created worktree of a repository that
+/// uses LFS. Does nothing when disabled or when the repository doesn't use LFS.
+pub fn setup_worktree_lfs(worktree_path: &str) {
+    if !AUTO_PULL.load(Ordering::SeqCst) || !uses_lfs(worktree_path) {
+        return;
+    }
+    if !git_lfs_installed() {
+        let message = "The repository uses Git LFS but git-lfs is not installed; the worktree contains pointer files".to_string();
+        eprintln!("[lfs] {}", message);
+        report(LfsPullProgress::failed(worktree_path, message));
+        return;
+    }
+
+    println!("[lfs] Pulling LFS objects into {}", worktree_path);
+    report(LfsPullProgress::new(worktree_path, LfsPullStage::Started));
+    match pull_lfs_objects(worktree_path) {
+        Ok(()) => report(LfsPullProgress {
+            percent: Some(100),
+            ..LfsPullProgress::new(worktree_path, LfsPullStage::Completed)
+        }),
+        Err(e) => {
+            eprintln!("[lfs] {}", e);
+            report(LfsPullProgress::failed(worktree_path, e));
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Cache for `list_worktrees`.
+//!
+//! Listing runs `git worktree list` and canonicalizes every path, and the UI
+//! lists often. Results are cached per repository and keyed by a stamp of the
+//! git dir: the modification times of `HEAD` and `logs/HEAD` in the common git
+//! dir, of `worktrees/`, of `HEAD`, `logs/HEAD`, `locked` and `gitdir` of
+//! every linked worktree, and of the branch each HEAD points to (its loose ref
+//! and `packed-refs`). Adding or removing a worktree changes `worktrees/`,
+//! moving one rewrites `gitdir`, locking creates `locked`, checkouts rewrite a
+//! HEAD and commits rewrite its branch ref (the reflog isn't enough: bare
+//! repositories don't keep one by default), so any change that
+//! `git worktree list` would show changes the stamp.
+//!
+//! File sizes are part of the stamp too, so a reflog append is seen even within
+//! the timestamp resolution; worktree operations in this app also invalidate
+//! the entry explicitly. A cached entry is dropped when one of
+//! its worktree folders no longer exists.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use crate::core::path_key;
+
+use super::types::WorktreeInfo;
+
+/// Modification times and sizes identifying one state of a repository's git dir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitDirStamp(Vec<(PathBuf, Option<(SystemTime, u64)>)>);
+
+struct CachedList {
+    stamp: GitDirStamp,
+    worktrees: Vec<WorktreeInfo>,
+}
+
+static LIST_CACHE: OnceLock<Mutex<HashMap<String, CachedList>>> = OnceLock::new();
+
+fn list_cache() -> &'static Mutex<HashMap<String, CachedList>> {
+    LIST_CACHE.get_or_init(Default::default)
+}
+
+/// The common git dir of the repository or worktree at `repo_path`, read from
+/// the file system without running git. A bare repository is its own git dir.
+pub fn resolve_common_git_dir(repo_path: &Path) -> Option<PathBuf> {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    if !dot_git.exists() && repo_path.join("HEAD").is_file() && repo_path.join("objects").is_dir() {
+        return Some(repo_path.to_path_buf());
+    }
+
+    // Linked worktree: `.git` is a file pointing at `{common}/worktrees/{name}`
+    let contents = fs::read_to_string(&dot_git).ok()?;
+    let gitdir = contents.lines().find_map(|l| l.strip_prefix("gitdir: "))?;
+    let gitdir = repo_path.join(gitdir.trim());
+    match fs::read_to_string(gitdir.join("commondir")) {
+        Ok(common) => Some(gitdir.join(common.trim())),
+        Err(_) => Some(gitdir),
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Loose ref file of the branch `{git_dir}/HEAD` points to, if it is on one.
+/// Branch refs are shared, so they live in the common dir.
+fn head_ref_path(common: &Path, git_dir: &Path) -> Option<PathBuf> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let target = head.trim().strip_prefix("ref: ")?;
+    Some(common.join(target))
+}
+
+/// Stamp of the git dir of `repo_path`, or `None` when it can't be found (the
+/// listing is then not cached).
+pub fn git_dir_stamp(repo_path: &Path) -> Option<GitDirStamp> {
+    let common = resolve_common_git_dir(repo_path)?;
+    let worktrees_dir = common.join("worktrees");
+
+    let mut paths = vec![
+        common.join("HEAD"),
+        common.join("logs").join("HEAD"),
+        common.join("packed-refs"),
+        worktrees_dir.clone(),
+    ];
+    paths.extend(head_ref_path(&common, &common));
+    if let Ok(entries) = fs::read_dir(&worktrees_dir) {
+        let mut dirs: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        dirs.sort();
+        for dir in dirs {
+            for file in ["HEAD", "logs/HEAD", "locked", "gitdir"] {
+                paths.push(dir.join(file));
+            }
+            paths.extend(head_ref_path(&common, &dir));
+        }
+    }
+
+    Some(GitDirStamp(
+        paths
+            .into_iter()
+            .map(|path| {
+                let time = file_stamp(&path);
+                (path, time)
+            })
+            .collect(),
+    ))
+}
+
+/// Cached worktrees of `repo_path` if they were listed at `stamp`.
+pub fn get_cached_worktrees(repo_path: &str, stamp: &GitDirStamp) -> Option<Vec<WorktreeInfo>> {
+    let mut cache = list_cache().lock().unwrap_or_else(|e| e.into_inner());
+    let key = path_key(repo_path);
+    let cached = cache.get(&key)?;
+
+    let fresh =
+        cached.stamp == *stamp && cached.worktrees.iter().all(|w| Path::new(&w.path).exists());
+    if !fresh {
+        cache.remove(&key);
+        return None;
+    }
+    Some(cached.worktrees.clone())
+}
+
+/// Remember the worktrees of `repo_path` listed at `stamp`.
+pub fn cache_worktrees(repo_path: &str, stamp: GitDirStamp, worktrees: &[WorktreeInfo]) {
+    list_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            path_key(repo_path),
+            CachedList {
+                stamp,
+                worktrees: worktrees.to_vec(),
+            },
+        );
+}
+
+/// Drop the cached worktrees of `repo_path`.
+pub fn invalidate_worktree_list_cache(repo_path: &str) {
+    list_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&path_key(repo_path));
+}
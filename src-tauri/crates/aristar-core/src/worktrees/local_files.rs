@@ -0,0 +1,132 @@
+//! Untracked config files for new worktrees.
+//!
+//! Many projects don't run until files kept out of git exist, e.g. `.env` or
+//! `config/local.json`. New worktrees and agent worktrees get a copy of the
+//! untracked files of the source repository matching
+//! `settings.copy_untracked_files` (read at startup, empty by default).
+//! Patterns use git's glob syntax: one without `/` matches a file name at any
+//! depth (`.env*`), one with `/` matches the path from the root
+//! (`config/local.*`, `apps/**/.env`). Files already in the worktree are kept.
+//! Copies that would show up as untracked are added to `info/exclude`.
+
+use std::fs;
+use std::path::{Component, Path};
+use std::sync::RwLock;
+
+use super::operations::{ensure_excluded, run_git_command};
+
+static PATTERNS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Set the patterns of untracked files copied into new worktrees.
+pub fn set_copy_patterns(patterns: Vec<String>) {
+    *PATTERNS.write().unwrap_or_else(|e| e.into_inner()) = patterns;
+}
+
+/// The patterns of untracked files copied into new worktrees.
+pub fn copy_patterns() -> Vec<String> {
+    PATTERNS.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// The git pathspec for a pattern, or `None` for patterns that could reach
+/// outside the worktree or use pathspec magic.
+pub fn pattern_pathspec(pattern: &str) -> Option<String> {
+    let pattern = pattern.trim().trim_start_matches("./");
+    if pattern.is_empty() || pattern.starts_with(':') || pattern.contains('\\') {
+        return None;
+    }
+    if !Path::new(pattern)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    let pattern = pattern.trim_end_matches('/');
+    if pattern.contains('/') {
+        Some(format!(":(glob){}", pattern))
+    } else {
+        Some(format!(":(glob)**/{}", pattern))
+    }
+}
+
+/// Untracked files (ignored ones included) of `source_path` matching
+/// `patterns`, relative to its root.
+pub fn matching_untracked_files(
+    source_path: &str,
+    patterns: &[String],
+) -> Result<Vec<String>, String> {
+    let pathspecs: Vec<String> = patterns
+        .iter()
+        .filter_map(|p| {
+            let pathspec = pattern_pathspec(p);
+            if pathspec.is_none() {
+                eprintln!("[local-files] Ignoring pattern {:?}", p);
+            }
+            pathspec
+        })
+        .collect();
+    if pathspecs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = vec!["ls-files", "--others", "-z", "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    let output = run_git_command(&args, source_path)?;
+    Ok(output
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).to_string())
+        .collect())
+}
+
+/// Copy the untracked files of `source_path` matching `patterns` into
+/// `worktree_path`, keeping files that exist there. Symlinks are skipped.
+/// Returns the paths copied.
+pub fn copy_untracked_files(
+    source_path: &str,
+    worktree_path: &str,
+    patterns: &[String],
+) -> Result<Vec<String>, String> {
+    let mut copied = Vec::new();
+    for relative in matching_untracked_files(source_path, patterns)? {
+        let from = Path::new(source_path).join(&relative);
+        let to = Path::new(worktree_path).join(&relative);
+        let is_file = fs::symlink_metadata(&from)
+            .map(|m| m.file_type().is_file())
+            .unwrap_or(false);
+        if !is_file || to.symlink_metadata().is_ok() {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::copy(&from, &to).map_err(|e| format!("Failed to copy {}: {}", relative, e))?;
+
+        let ignored =
+            run_git_command(&["check-ignore", "-q", "--", &relative], worktree_path).is_ok();
+        if !ignored {
+            ensure_excluded(Path::new(worktree_path), &format!("/{}", relative))?;
+        }
+        copied.push(relative);
+    }
+    Ok(copied)
+}
+
+/// Copy the configured untracked files into a newly created worktree.
+/// Failures are logged, never fatal.
+pub fn setup_worktree_local_files(source_path: &str, worktree_path: &str) {
+    let patterns = copy_patterns();
+    if patterns.is_empty() {
+        return;
+    }
+    match copy_untracked_files(source_path, worktree_path, &patterns) {
+        Ok(copied) if copied.is_empty() => {}
+        Ok(copied) => println!(
+            "[local-files] Copied {} untracked file(s) into {}",
+            copied.len(),
+            worktree_path
+        ),
+        Err(e) => eprintln!("[local-files] {}", e),
+    }
+}
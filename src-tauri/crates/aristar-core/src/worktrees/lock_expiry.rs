@@ -0,0 +1,94 @@
+//! Worktree locks that lift themselves.
+//!
+//! `lock_worktree` takes an optional `expires_at`, kept on the worktree in the
+//! app store and written to `.aristar/lock.json`. A background loop checks
+//! once a minute, unlocks worktrees whose lock has expired and emits
+//! `worktree-lock-expired` for each. Locks without an expiry (including those
+//! placed by git or scripts) are never lifted, and read-only mode leaves all
+//! locks alone.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::core::is_read_only;
+
+use super::operations;
+use super::store::AppState;
+use super::types::Repository;
+
+pub use crate::core::events::WORKTREE_LOCK_EXPIRED_EVENT;
+
+/// How often the background loop looks for expired locks.
+pub const LOCK_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A worktree whose lock expired.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeLockExpiredEvent {
+    pub repository_id: String,
+    pub path: String,
+    pub reason: Option<String>,
+    /// When the lock expired (milliseconds since epoch)
+    pub expires_at: i64,
+}
+
+/// The locked worktrees of available `repositories` whose lock expired at or
+/// before `now`.
+pub fn find_expired_locks(repositories: &[Repository], now: i64) -> Vec<WorktreeLockExpiredEvent> {
+    repositories
+        .iter()
+        .filter(|repo| repo.unavailable_since.is_none())
+        .flat_map(|repo| {
+            repo.worktrees
+                .iter()
+                .filter(|w| w.is_locked)
+                .filter_map(move |w| {
+                    let expires_at = w.lock_expires_at.filter(|&at| at <= now)?;
+                    Some(WorktreeLockExpiredEvent {
+                        repository_id: repo.id.clone(),
+                        path: w.path.clone(),
+                        reason: w.lock_reason.clone(),
+                        expires_at,
+                    })
+                })
+        })
+        .collect()
+}
+
+/// Unlock the worktrees whose lock expired at `now` and return them. A lock
+/// already lifted outside the app only has its entry cleared; worktrees that
+/// fail to unlock are retried on the next check.
+pub fn unlock_expired_worktrees(
+    state: &AppState,
+    now: i64,
+) -> Result<Vec<WorktreeLockExpiredEvent>, String> {
+    if is_read_only() {
+        return Ok(Vec::new());
+    }
+    let expired = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        find_expired_locks(&store.repositories, now)
+    };
+
+    let mut unlocked = Vec::with_capacity(expired.len());
+    for lock in expired {
+        if let Err(e) = operations::unlock_worktree(&lock.path) {
+            if operations::is_worktree_locked(&lock.path).unwrap_or(true) {
+                eprintln!("[lock_expiry] Failed to unlock {}: {}", lock.path, e);
+                continue;
+            }
+        }
+        state.update_worktree(&lock.path, |wt| {
+            wt.is_locked = false;
+            wt.lock_reason = None;
+            wt.lock_expires_at = None;
+        })?;
+        unlocked.push(lock);
+    }
+
+    if !unlocked.is_empty() {
+        state.save()?;
+    }
+    Ok(unlocked)
+}
@@ -0,0 +1,72 @@
+//! Worktrees module - Git worktree management.
+//!
+//! This module handles all git worktree operations including:
+//! - Listing, creating, removing, renaming worktrees
+//! - Branch and commit information
+//! - Changed files and diffs against a worktree's base ref
+//! - Worktree name suggestions
+//! - External app integration (terminals, editors)
+//! - Repository state management
+//! - Availability tracking for repositories on removable/network volumes
+//! - Template variables in startup scripts
+//! - Per-worktree git config
+//! - Readable alias symlinks for worktree folders
+//! - Fetch, pull and push per worktree
+//! - Per-repository queue for worktree-mutating git operations
+//! - Staging and committing in a worktree
+//! - File tree listing for the file browser
+//! - Cached worktree listing keyed by git dir timestamps
+//! - Git LFS setup for new worktrees
+//! - Git hooks for new worktrees (relative `core.hooksPath`)
+//! - Sparse (lightweight) worktrees for agents
+//! - Dev server URLs (registered or found by port probing)
+//! - Branch protection rules from GitHub
+//! - Untracked config files (`.env` etc.) copied into new worktrees
+//! - Dependency folders shared with the main worktree
+//! - Processes running inside a worktree
+//! - Archives of worktrees taken before removal, and their restore
+//! - Per-repository scan settings and read-ahead of commit history
+//! - Labels on worktrees and repositories
+//! - Last activity per worktree
+//! - Cleanup of stale worktrees by an idle-days policy
+//! - Suggested cleanup of merged branches without a worktree
+//! - Worktree locks that expire
+//! - App-managed SSH known_hosts for fetch, pull and push
+
+pub mod activity;
+pub mod aliases;
+pub mod archive;
+pub mod availability;
+pub mod branch_cleanup;
+pub mod config;
+pub mod dev_server;
+pub mod diff;
+pub mod external_apps;
+pub mod files;
+pub mod github;
+pub mod hooks;
+pub mod known_hosts;
+pub mod labels;
+pub mod lfs;
+pub mod list_cache;
+pub mod local_files;
+pub mod lock_expiry;
+pub mod naming;
+pub mod operations;
+pub mod prefetch;
+pub mod processes;
+pub mod repo_queue;
+pub mod shared_deps;
+pub mod sparse;
+pub mod staging;
+pub mod stale;
+pub mod store;
+pub mod sync;
+pub mod templates;
+pub mod types;
+
+// Re-export store init function (AppState is used via store:: prefix)
+pub use store::init_store;
+
+// Re-export persistence utilities
+pub use crate::core::get_aristar_worktrees_base;
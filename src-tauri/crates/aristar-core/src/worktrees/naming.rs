@@ -0,0 +1,328 @@
+//! Worktree/branch name suggestions.
+//!
+//! Suggestions are computed backend-side so every client gets the same list.
+//! Sources: the user's hint, the configured branch naming template, the current
+//! date, and the subjects of recent commits.
+//!
+//! `create_worktree` without a name generates one from
+//! `settings.worktree_name_template` (default `{branch}`), filled from the
+//! branch the worktree is for, a ticket ID found in it and the date.
+
+use chrono::Local;
+use std::path::Path;
+
+use super::operations::{get_commits, get_worktree_base_for_repo, run_git_command};
+
+/// Template used when no branch naming template is configured.
+pub const DEFAULT_NAME_TEMPLATE: &str = "{hint}";
+
+/// Template for generated worktree names when none is configured.
+pub const DEFAULT_GENERATED_NAME_TEMPLATE: &str = "{branch}";
+
+/// Ticket ID pattern when none is configured, e.g. "PROJ-123".
+pub const DEFAULT_TICKET_PATTERN: &str = "*-#";
+
+/// Maximum number of suggestions returned.
+pub const MAX_SUGGESTIONS: usize = 8;
+
+/// Number of recent commits used as suggestion sources.
+const RECENT_COMMITS: usize = 5;
+
+/// Maximum length of a single suggested name.
+const MAX_NAME_LEN: usize = 48;
+
+/// Conventional-commit prefixes stripped from commit subjects.
+const COMMIT_PREFIXES: [&str; 8] = [
+    "feat", "fix", "chore", "docs", "refactor", "test", "perf", "style",
+];
+
+/// Slugify text into a name that is safe for folders and git refs.
+/// e.g., "Fix: Login Bug!" -> "fix-login-bug"
+pub fn slugify_name(s: &str) -> String {
+    let slug = s
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    truncate_slug(&slug, MAX_NAME_LEN)
+}
+
+/// Truncate a slug to `max_len` characters without leaving a trailing hyphen.
+pub fn truncate_slug(slug: &str, max_len: usize) -> String {
+    if slug.len() <= max_len {
+        return slug.to_string();
+    }
+    slug[..max_len].trim_end_matches('-').to_string()
+}
+
+/// Strip a conventional-commit prefix (e.g. "feat(ui): ") from a commit subject.
+pub fn strip_commit_prefix(subject: &str) -> &str {
+    if let Some((prefix, rest)) = subject.split_once(':') {
+        let kind = prefix
+            .split('(')
+            .next()
+            .unwrap_or(prefix)
+            .trim_end_matches('!');
+        if COMMIT_PREFIXES.contains(&kind.trim().to_lowercase().as_str()) {
+            return rest.trim();
+        }
+    }
+    subject
+}
+
+/// Render a branch naming template.
+///
+/// Supported placeholders:
+/// - `{hint}` - slugified hint
+/// - `{date}` - current date as `YYYYMMDD`
+/// - `{user}` - slugified git `user.name`
+pub fn render_name_template(template: &str, hint: &str, date: &str, user: &str) -> String {
+    template
+        .replace("{hint}", hint)
+        .replace("{date}", date)
+        .replace("{user}", user)
+        .split('/')
+        .map(slugify_name)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_ascii_alphanumeric())
+}
+
+/// Match `pattern` at the start of `chars`, returning the length matched.
+fn match_ticket_at(chars: &[char], pattern: &[char]) -> Option<usize> {
+    let mut pos = 0;
+    for &p in pattern {
+        let class: Option<fn(&char) -> bool> = match p {
+            '*' => Some(|c| c.is_ascii_alphabetic()),
+            '#' => Some(|c| c.is_ascii_digit()),
+            _ => None,
+        };
+        match class {
+            Some(class) => {
+                let run = chars[pos..].iter().take_while(|c| class(c)).count();
+                if run == 0 {
+                    return None;
+                }
+                pos += run;
+            }
+            None if chars.get(pos).is_some_and(|c| c.eq_ignore_ascii_case(&p)) => pos += 1,
+            None => return None,
+        }
+    }
+    Some(pos)
+}
+
+/// Find a ticket ID in `text`. In `pattern`, `*` matches letters, `#` digits
+/// and anything else itself (case-insensitive); matches must be whole words.
+/// e.g., "feature/PROJ-123-login" with "*-#" -> "PROJ-123"
+pub fn find_ticket_id(text: &str, pattern: &str) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.trim().chars().collect();
+    if pattern.is_empty() {
+        return None;
+    }
+    (0..chars.len())
+        .filter(|&start| start == 0 || !is_word_char(chars.get(start - 1).copied()))
+        .find_map(|start| {
+            let len = match_ticket_at(&chars[start..], &pattern)?;
+            (len > 0 && !is_word_char(chars.get(start + len).copied()))
+                .then(|| chars[start..start + len].iter().collect())
+        })
+}
+
+/// Render a generated worktree name: `template` with `{branch}`, `{ticket}`,
+/// `{date}` and `{user}` filled in, flattened into one folder name.
+/// e.g., "{ticket}-{date}" for "feature/PROJ-123-login" -> "proj-123-20260115"
+pub fn render_generated_name(
+    template: &str,
+    branch: &str,
+    ticket: &str,
+    date: &str,
+    user: &str,
+) -> String {
+    let filled = template
+        .replace("{branch}", &slugify_name(branch))
+        .replace("{ticket}", &slugify_name(ticket));
+    slugify_name(&render_name_template(&filled, "", date, user))
+}
+
+/// Append `-2`, `-3`, ... to `name` until `is_taken` is false.
+pub fn unique_name(name: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !is_taken(candidate))
+        .unwrap_or_default()
+}
+
+/// Generate a worktree name for a repository from the branch it is for
+/// (`None` uses the repository's current branch), the naming template and
+/// the ticket pattern. Names whose folder already exists get a number.
+pub fn generate_worktree_name(
+    repo_path: &str,
+    branch: Option<&str>,
+    template: Option<&str>,
+    ticket_pattern: Option<&str>,
+) -> Result<String, String> {
+    let template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_GENERATED_NAME_TEMPLATE);
+    let ticket_pattern = ticket_pattern
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(DEFAULT_TICKET_PATTERN);
+
+    let branch = match branch.map(str::trim).filter(|b| !b.is_empty()) {
+        Some(branch) => branch.to_string(),
+        None => {
+            let output = run_git_command(&["rev-parse", "--abbrev-ref", "HEAD"], repo_path)?;
+            let head = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if head == "HEAD" {
+                let output = run_git_command(&["rev-parse", "--short", "HEAD"], repo_path)?;
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            } else {
+                head
+            }
+        }
+    };
+    let ticket = find_ticket_id(&branch, ticket_pattern).unwrap_or_default();
+    let date = Local::now().format("%Y%m%d").to_string();
+    let user = if template.contains("{user}") {
+        get_git_user_slug(repo_path)
+    } else {
+        String::new()
+    };
+
+    let mut name = render_generated_name(template, &branch, &ticket, &date, &user);
+    if name.is_empty() {
+        name = format!("worktree-{}", date);
+    }
+    resolve_worktree_name(repo_path, &name)
+}
+
+/// `name`, or `name-2`, `name-3`, ... when a worktree folder of that name
+/// already exists for the repository.
+pub fn resolve_worktree_name(repo_path: &str, name: &str) -> Result<String, String> {
+    let repo_path_canonical = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    let base = get_worktree_base_for_repo(&repo_path_canonical);
+    Ok(unique_name(name, |n| base.join(n).exists()))
+}
+
+/// Get the slugified git user name for a repository, if configured.
+fn get_git_user_slug(repo_path: &str) -> String {
+    run_git_command(&["config", "user.name"], repo_path)
+        .map(|o| slugify_name(String::from_utf8_lossy(&o.stdout).trim()))
+        .unwrap_or_default()
+}
+
+/// Suggest worktree names for a repository.
+///
+/// Names already used by folders in the repository's worktree base are skipped.
+pub fn suggest_worktree_names(
+    repo_path: &str,
+    hint: Option<&str>,
+    template: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let hint_slug = hint.map(slugify_name).unwrap_or_default();
+    let date = Local::now().format("%Y%m%d").to_string();
+    let template = template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(DEFAULT_NAME_TEMPLATE);
+
+    let mut candidates: Vec<String> = Vec::new();
+
+    if !hint_slug.is_empty() {
+        let user = if template.contains("{user}") {
+            get_git_user_slug(repo_path)
+        } else {
+            String::new()
+        };
+        candidates.push(render_name_template(template, &hint_slug, &date, &user));
+        candidates.push(hint_slug.clone());
+        candidates.push(format!("{}-{}", hint_slug, date));
+    }
+
+    for commit in get_commits(repo_path, RECENT_COMMITS)? {
+        candidates.push(slugify_name(strip_commit_prefix(&commit.message)));
+    }
+
+    if candidates.is_empty() {
+        candidates.push(format!("worktree-{}", date));
+    }
+
+    let repo_path_canonical = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    let base = get_worktree_base_for_repo(&repo_path_canonical);
+    let mut suggestions: Vec<String> = Vec::new();
+    for name in candidates {
+        if name.is_empty() || suggestions.contains(&name) || base.join(&name).exists() {
+            continue;
+        }
+        suggestions.push(name);
+        if suggestions.len() >= MAX_SUGGESTIONS {
+            break;
+        }
+    }
+
+    Ok(suggestions)
+}
+
+/// Suggest worktree names (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn suggest_worktree_names_async(
+    repo_path: String,
+    hint: Option<String>,
+    template: Option<String>,
+) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || {
+        suggest_worktree_names(&repo_path, hint.as_deref(), template.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Resolve a worktree name collision (async version).
+pub async fn resolve_worktree_name_async(
+    repo_path: String,
+    name: String,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || resolve_worktree_name(&repo_path, &name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Generate a worktree name (async version).
+pub async fn generate_worktree_name_async(
+    repo_path: String,
+    branch: Option<String>,
+    template: Option<String>,
+    ticket_pattern: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        generate_worktree_name(
+            &repo_path,
+            branch.as_deref(),
+            template.as_deref(),
+            ticket_pattern.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
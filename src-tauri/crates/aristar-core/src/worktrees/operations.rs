@@ -0,0 +1,2152 @@
+//! Git worktree operations.
+//!
+//! Core functions for working with git worktrees - listing, creating, removing, etc.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::core::{get_aristar_worktrees_base, ports};
+
+use super::activity;
+use super::hooks;
+use super::lfs;
+use super::list_cache::{self, invalidate_worktree_list_cache};
+use super::local_files;
+use super::processes::{get_processes_in_worktree, terminate_and_wait, TERMINATE_TIMEOUT};
+use super::repo_queue::run_queued;
+use super::shared_deps;
+use super::sparse;
+use super::templates::{render_script, uses_variable, ScriptTemplateContext};
+use super::types::{
+    BranchInfo, BranchPage, BranchSort, CommitInfo, CreateWorktreeOptions, HeadState, RebaseResult,
+    RebaseStatus, RenameWorktreeError, RepoContext, WorktreeInfo, WorktreeLockFile, WorktreeStatus,
+};
+
+// ============ Path Security ============
+
+/// Validate that a path is within an allowed base directory.
+/// This prevents path traversal attacks where user input could escape
+/// to arbitrary filesystem locations.
+///
+/// # Arguments
+/// * `path` - The path to validate (can be relative or absolute)
+/// * `allowed_bases` - List of allowed base directories
+///
+/// # Returns
+/// * `Ok(PathBuf)` - The canonicalized path if valid
+/// * `Err(String)` - Error message if path traversal detected
+pub fn validate_path_within_bases(path: &Path, allowed_bases: &[PathBuf]) -> Result<PathBuf, String> {
+    // For paths that don't exist yet, we need to check the parent
+    let check_path = if path.exists() {
+        path.canonicalize().map_err(|e| format!("Failed to resolve path: {}", e))?
+    } else {
+        // Path doesn't exist yet - check parent and combine with filename
+        let parent = path.parent().ok_or("Path has no parent directory")?;
+        let filename = path.file_name().ok_or("Path has no filename")?;
+        
+        // Ensure parent exists or create it, then canonicalize
+        if !parent.exists() {
+            // Walk up to find existing ancestor
+            let mut ancestor = parent.to_path_buf();
+            while !ancestor.exists() {
+                ancestor = ancestor.parent()
+                    .ok_or("Cannot find existing ancestor directory")?
+                    .to_path_buf();
+            }
+            let canonical_ancestor = ancestor.canonicalize()
+                .map_err(|e| format!("Failed to resolve ancestor: {}", e))?;
+            
+            // Check ancestor is in allowed bases
+            if !allowed_bases.iter().any(|base| {
+                base.canonicalize().ok()
+                    .map(|cb| canonical_ancestor.starts_with(&cb))
+                    .unwrap_or(false)
+            }) {
+                return Err(format!(
+                    "Path traversal detected: {} is not within allowed directories",
+                    path.display()
+                ));
+            }
+            
+            // Build expected canonical path
+            let relative_from_ancestor = parent.strip_prefix(&ancestor).unwrap_or(parent);
+            canonical_ancestor.join(relative_from_ancestor).join(filename)
+        } else {
+            let canonical_parent = parent.canonicalize()
+                .map_err(|e| format!("Failed to resolve parent: {}", e))?;
+            canonical_parent.join(filename)
+        }
+    };
+
+    // Verify the path is within one of the allowed bases
+    let is_allowed = allowed_bases.iter().any(|base| {
+        base.canonicalize().ok()
+            .map(|canonical_base| check_path.starts_with(&canonical_base))
+            .unwrap_or(false)
+    });
+
+    if !is_allowed {
+        return Err(format!(
+            "Path traversal detected: {} is not within allowed directories",
+            path.display()
+        ));
+    }
+
+    Ok(check_path)
+}
+
+/// Get the list of allowed base directories for worktree operations.
+/// This includes:
+/// - ~/.aristar-worktrees (our managed directory)
+/// - User's home directory (for repos in Documents, Projects, etc.)
+pub fn get_allowed_worktree_bases() -> Vec<PathBuf> {
+    let mut bases = vec![get_aristar_worktrees_base()];
+    
+    // Also allow home directory for user repos
+    if let Some(home) = dirs::home_dir() {
+        bases.push(home);
+    }
+    
+    bases
+}
+
+/// Get the repository name from its path.
+pub fn get_repository_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Generate a hash for the repository path (first 8 hex chars of SHA256).
+pub fn get_repo_hash(repo_path: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(repo_path.as_bytes());
+    let result = hasher.finalize();
+    hex::encode(&result[..4]) // First 4 bytes = 8 hex chars
+}
+
+/// Get the worktree base directory for a specific repository.
+pub fn get_worktree_base_for_repo(repo_path: &str) -> PathBuf {
+    get_aristar_worktrees_base().join(get_repo_hash(repo_path))
+}
+
+/// Ensure the repo info file exists in the worktree base directory.
+pub fn ensure_repo_info(repo_path: &str) -> Result<(), String> {
+    let base = get_worktree_base_for_repo(repo_path);
+    std::fs::create_dir_all(&base).map_err(|e| e.to_string())?;
+
+    let info_file = base.join(".aristar-repo-info.json");
+    if !info_file.exists() {
+        let info = serde_json::json!({"originalPath": repo_path});
+        std::fs::write(&info_file, info.to_string()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Check if a path is a git repository.
+pub fn is_git_repository(path: &str) -> bool {
+    let git_path = format!("{}/.git", path);
+    Path::new(&git_path).exists() || Path::new(path).join(".git").is_dir()
+}
+
+/// Attempts for a git command that fails with a transient error.
+const GIT_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry, doubled for each further one.
+const GIT_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Whether git failed only because another git process holds a lock
+/// (`index.lock`, `HEAD.lock`, a ref lock, ...). Such failures go away once
+/// that process finishes.
+pub fn is_transient_git_error(stderr: &str) -> bool {
+    stderr.contains(".lock': File exists")
+        || stderr.contains("Another git process seems to be running")
+}
+
+/// Delay before retry number `attempt` (0-based): exponential backoff with up
+/// to 50% random jitter, so competing processes don't retry in lockstep.
+pub fn git_retry_delay(attempt: u32) -> Duration {
+    let base = GIT_RETRY_BASE_DELAY * 2u32.pow(attempt);
+    let jitter_ms = Uuid::new_v4().as_u128() % (base.as_millis() / 2 + 1);
+    base + Duration::from_millis(jitter_ms as u64)
+}
+
+/// Run a git command in the specified directory (synchronous version).
+/// Failures caused by another git process holding a lock are retried a few
+/// times with backoff before the error is returned.
+/// NOTE: For Tauri commands, prefer `run_git_command_async` to avoid blocking the main thread.
+pub fn run_git_command(args: &[&str], cwd: &str) -> Result<std::process::Output, String> {
+    let mut attempt = 0;
+    loop {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        if output.status.success() {
+            return Ok(output);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        if attempt + 1 >= GIT_ATTEMPTS || !is_transient_git_error(&stderr) {
+            return Err(stderr);
+        }
+        let delay = git_retry_delay(attempt);
+        println!(
+            "[git] `git {}` hit a lock, retrying in {}ms",
+            args.first().unwrap_or(&""),
+            delay.as_millis()
+        );
+        std::thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+/// Run a git command asynchronously without blocking the Tauri main thread.
+/// This wraps the blocking git command in tokio::task::spawn_blocking.
+#[allow(dead_code)]
+pub async fn run_git_command_async(
+    args: Vec<String>,
+    cwd: String,
+) -> Result<std::process::Output, String> {
+    tokio::task::spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_git_command(&args, &cwd)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get the current branch name for a repository.
+pub fn get_current_branch(repo_path: &str) -> Result<String, String> {
+    let output = run_git_command(&["symbolic-ref", "--short", "HEAD"], repo_path)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse `%(upstream:track,nobracket)`: "ahead 2, behind 1", "gone" or empty.
+/// Returns (ahead, behind, gone).
+pub fn parse_upstream_track(track: &str) -> (u32, u32, bool) {
+    let mut ahead = 0;
+    let mut behind = 0;
+    for part in track.split(',').map(str::trim) {
+        if part == "gone" {
+            return (0, 0, true);
+        }
+        if let Some(count) = part.strip_prefix("ahead ") {
+            ahead = count.parse().unwrap_or(0);
+        } else if let Some(count) = part.strip_prefix("behind ") {
+            behind = count.parse().unwrap_or(0);
+        }
+    }
+    (ahead, behind, false)
+}
+
+/// Parse the `git for-each-ref` output of `get_branches` (full ref name,
+/// upstream and tracking info separated by NUL).
+pub fn parse_branch_refs(output: &str, current_branch: Option<&str>) -> Vec<BranchInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\0');
+            let refname = fields.next()?;
+            let upstream = fields.next().unwrap_or("");
+            let track = fields.next().unwrap_or("");
+
+            let (name, is_remote) = if let Some(name) = refname.strip_prefix("refs/heads/") {
+                (name, false)
+            } else {
+                (refname.strip_prefix("refs/remotes/")?, true)
+            };
+            // Symbolic `origin/HEAD`
+            if is_remote && name.ends_with("/HEAD") {
+                return None;
+            }
+
+            let (ahead, behind, upstream_gone) = parse_upstream_track(track);
+            Some(BranchInfo {
+                name: name.to_string(),
+                is_current: !is_remote && Some(name) == current_branch,
+                is_remote,
+                upstream: (!upstream.is_empty()).then(|| upstream.to_string()),
+                ahead,
+                behind,
+                upstream_gone,
+            })
+        })
+        .collect()
+}
+
+/// Get all branches for a repository, with each local branch's upstream and
+/// how far it is ahead of and behind it.
+pub fn get_branches(repo_path: &str) -> Result<Vec<BranchInfo>, String> {
+    let output = run_git_command(
+        &[
+            "for-each-ref",
+            "--format=%(refname)%00%(upstream:short)%00%(upstream:track,nobracket)",
+            "refs/heads",
+            "refs/remotes",
+        ],
+        repo_path,
+    )?;
+
+    let current_branch = get_current_branch(repo_path).ok();
+    Ok(parse_branch_refs(
+        &String::from_utf8_lossy(&output.stdout),
+        current_branch.as_deref(),
+    ))
+}
+
+/// Most branches returned by one page of `get_branch_page`.
+pub const MAX_BRANCH_PAGE: usize = 500;
+
+/// Whether branch `name` starts with `prefix`, ignoring case. A remote branch
+/// also matches by its name without the remote (`origin/feat` for `feat`).
+pub fn branch_matches_prefix(name: &str, is_remote: bool, prefix: &str) -> bool {
+    let prefix = prefix.to_lowercase();
+    let name = name.to_lowercase();
+    name.starts_with(&prefix)
+        || (is_remote
+            && name
+                .split_once('/')
+                .is_some_and(|(_, branch)| branch.starts_with(&prefix)))
+}
+
+/// Full ref names of the branches matching the filter, in `sort` order,
+/// without remote `HEAD`s.
+fn list_branch_refnames(
+    repo_path: &str,
+    prefix: Option<&str>,
+    sort: BranchSort,
+    include_remote: bool,
+) -> Result<Vec<String>, String> {
+    let mut args = vec!["for-each-ref", "--format=%(refname)"];
+    if sort == BranchSort::Recent {
+        args.push("--sort=-committerdate");
+    }
+    args.push("refs/heads");
+    if include_remote {
+        args.push("refs/remotes");
+    }
+    let output = run_git_command(&args, repo_path)?;
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|refname| {
+            let (name, is_remote) = match refname.strip_prefix("refs/heads/") {
+                Some(name) => (name, false),
+                None => match refname.strip_prefix("refs/remotes/") {
+                    Some(name) => (name, true),
+                    None => return false,
+                },
+            };
+            !(is_remote && name.ends_with("/HEAD"))
+                && prefix.is_none_or(|p| branch_matches_prefix(name, is_remote, p))
+        })
+        .map(str::to_string)
+        .collect())
+}
+
+/// Get one page of a repository's branches, for repositories with too many
+/// to list at once. Only the ref names are read for every branch; upstream
+/// and ahead/behind are computed for the page alone. `prefix` filters with
+/// `branch_matches_prefix`; `limit` is capped at `MAX_BRANCH_PAGE`.
+pub fn get_branch_page(
+    repo_path: &str,
+    prefix: Option<&str>,
+    sort: BranchSort,
+    include_remote: bool,
+    offset: usize,
+    limit: usize,
+) -> Result<BranchPage, String> {
+    let prefix = prefix.map(str::trim).filter(|p| !p.is_empty());
+    let refnames = list_branch_refnames(repo_path, prefix, sort, include_remote)?;
+    let total = refnames.len();
+    let page: Vec<&str> = refnames
+        .iter()
+        .skip(offset)
+        .take(limit.min(MAX_BRANCH_PAGE))
+        .map(String::as_str)
+        .collect();
+
+    let mut branches = Vec::with_capacity(page.len());
+    if !page.is_empty() {
+        let mut args = vec![
+            "for-each-ref",
+            "--format=%(refname)%00%(upstream:short)%00%(upstream:track,nobracket)",
+        ];
+        args.extend(&page);
+        let output = run_git_command(&args, repo_path)?;
+        let current_branch = get_current_branch(repo_path).ok();
+        let mut found = parse_branch_refs(
+            &String::from_utf8_lossy(&output.stdout),
+            current_branch.as_deref(),
+        );
+
+        // A pattern also matches refs below it (`feat` matches `feat/x`), so
+        // pick the page's refs out in page order
+        for refname in &page {
+            let (name, is_remote) = match refname.strip_prefix("refs/heads/") {
+                Some(name) => (name, false),
+                None => (refname.trim_start_matches("refs/remotes/"), true),
+            };
+            if let Some(i) = found
+                .iter()
+                .position(|b| b.name == name && b.is_remote == is_remote)
+            {
+                branches.push(found.swap_remove(i));
+            }
+        }
+    }
+
+    Ok(BranchPage {
+        has_more: offset + page.len() < total,
+        branches,
+        total,
+        offset,
+    })
+}
+
+/// Most commits returned by one page of the commit log.
+pub const MAX_COMMIT_PAGE: usize = 1000;
+
+/// `git log` format read by `parse_commit_log`: fields separated by 0x1f, the
+/// subject last so it may contain anything.
+const COMMIT_LOG_FORMAT: &str = "--format=%H%x1f%h%x1f%P%x1f%D%x1f%an%x1f%at%x1f%s";
+
+/// Parse `git log` output in `COMMIT_LOG_FORMAT`.
+pub fn parse_commit_log(output: &str) -> Vec<CommitInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(7, '\x1f').collect();
+            if parts.len() < 7 {
+                return None;
+            }
+            Some(CommitInfo {
+                hash: parts[0].to_string(),
+                short_hash: parts[1].to_string(),
+                message: parts[6].to_string(),
+                author: parts[4].to_string(),
+                date: parts[5].parse().unwrap_or(0),
+                parents: parts[2].split_whitespace().map(str::to_string).collect(),
+                refs: parts[3]
+                    .split(", ")
+                    .filter(|r| !r.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Get recent commits for a repository.
+pub fn get_commits(repo_path: &str, limit: usize) -> Result<Vec<CommitInfo>, String> {
+    get_commit_page(repo_path, limit, 0, None)
+}
+
+/// Get a page of the commit log from HEAD, skipping the first `skip` commits.
+/// With `before_hash` (the last commit of the previous page), the log starts
+/// below that commit instead: its ancestors, without the commit itself.
+/// `limit` is capped at `MAX_COMMIT_PAGE`.
+pub fn get_commit_page(
+    repo_path: &str,
+    limit: usize,
+    skip: usize,
+    before_hash: Option<&str>,
+) -> Result<Vec<CommitInfo>, String> {
+    let start = match before_hash {
+        Some(hash) => {
+            if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid commit hash: {}", hash));
+            }
+            format!("{}^@", hash)
+        }
+        None => "HEAD".to_string(),
+    };
+    let limit_str = limit.min(MAX_COMMIT_PAGE).to_string();
+    let skip_str = format!("--skip={}", skip);
+    let output = run_git_command(
+        &[
+            "log",
+            COMMIT_LOG_FORMAT,
+            "--decorate=short",
+            "-n",
+            &limit_str,
+            &skip_str,
+            &start,
+            "--",
+        ],
+        repo_path,
+    )?;
+
+    Ok(parse_commit_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Resolve `git_ref` to a commit, failing with a readable error. Refs that
+/// look like options are refused before reaching git.
+fn resolve_commit(repo_path: &str, git_ref: &str) -> Result<String, String> {
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err(format!("Invalid ref: {:?}", git_ref));
+    }
+    let spec = format!("{}^{{commit}}", git_ref);
+    run_git_command(&["rev-parse", "--verify", "--quiet", &spec], repo_path)
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .map_err(|_| format!("Unknown ref: {}", git_ref))
+}
+
+/// The best common ancestor of `ref_a` and `ref_b` (`git merge-base`), or
+/// `None` when their histories share no commit.
+pub fn get_merge_base(repo_path: &str, ref_a: &str, ref_b: &str) -> Result<Option<String>, String> {
+    let a = resolve_commit(repo_path, ref_a)?;
+    let b = resolve_commit(repo_path, ref_b)?;
+    match run_git_command(&["merge-base", &a, &b], repo_path) {
+        Ok(output) => Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        )),
+        // Exit status 1 without a message: no common ancestor
+        Err(e) if e.trim().is_empty() => Ok(None),
+        Err(e) => Err(format!("Failed to find the merge base: {}", e.trim())),
+    }
+}
+
+/// Whether `ancestor` is reachable from `descendant`, e.g. whether a branch is
+/// already merged into main. A commit counts as its own ancestor.
+pub fn is_ancestor(repo_path: &str, ancestor: &str, descendant: &str) -> Result<bool, String> {
+    let a = resolve_commit(repo_path, ancestor)?;
+    let d = resolve_commit(repo_path, descendant)?;
+    match run_git_command(&["merge-base", "--is-ancestor", &a, &d], repo_path) {
+        Ok(_) => Ok(true),
+        Err(e) if e.trim().is_empty() => Ok(false),
+        Err(e) => Err(format!(
+            "Failed to compare {} and {}: {}",
+            ancestor,
+            descendant,
+            e.trim()
+        )),
+    }
+}
+
+/// Parse `git status --porcelain=v2 --branch` output.
+pub fn parse_worktree_status(output: &str) -> WorktreeStatus {
+    let mut status = WorktreeStatus::default();
+
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" if value != "(initial)" => status.head = Some(value.to_string()),
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let mut fields = line.splitn(3, ' ');
+        match (fields.next(), fields.next()) {
+            // Ordinary ("1") and renamed/copied ("2") entries carry XY codes
+            (Some("1" | "2"), Some(xy)) => {
+                let mut codes = xy.chars();
+                if codes.next().is_some_and(|c| c != '.') {
+                    status.staged += 1;
+                }
+                if codes.next().is_some_and(|c| c != '.') {
+                    status.modified += 1;
+                }
+            }
+            (Some("u"), _) => status.conflicted += 1,
+            (Some("?"), _) => status.untracked += 1,
+            _ => {}
+        }
+    }
+
+    status.is_dirty = status.staged + status.modified + status.untracked + status.conflicted > 0;
+    status
+}
+
+/// Get the working tree status of a worktree: changed file counts, HEAD and
+/// ahead/behind relative to its upstream.
+pub fn get_worktree_status(path: &str) -> Result<WorktreeStatus, String> {
+    if !Path::new(path).is_dir() {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    let output = run_git_command(&["status", "--porcelain=v2", "--branch"], path)?;
+    Ok(parse_worktree_status(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Determine a worktree's HEAD state from its `git worktree list --porcelain` entry.
+pub fn parse_head_state(commit: Option<&str>, branch: Option<&str>, detached: bool) -> HeadState {
+    let commit = commit.filter(|c| !c.is_empty() && c.chars().any(|ch| ch != '0'));
+    match (commit, branch) {
+        (None, _) => HeadState::Unborn,
+        (Some(commit), _) if detached => HeadState::Detached(commit.to_string()),
+        (_, Some(branch)) => HeadState::Branch(
+            branch
+                .strip_prefix("refs/heads/")
+                .unwrap_or(branch)
+                .to_string(),
+        ),
+        (Some(commit), None) => HeadState::Detached(commit.to_string()),
+    }
+}
+
+/// List all worktrees for a repository.
+pub fn list_worktrees(repo_path: &str) -> Result<Vec<WorktreeInfo>, String> {
+    let stamp = list_cache::git_dir_stamp(Path::new(repo_path));
+    if let Some(worktrees) = stamp
+        .as_ref()
+        .and_then(|stamp| list_cache::get_cached_worktrees(repo_path, stamp))
+    {
+        return Ok(worktrees);
+    }
+
+    let worktrees = read_worktree_list(repo_path)?;
+    if let Some(stamp) = stamp {
+        list_cache::cache_worktrees(repo_path, stamp, &worktrees);
+    }
+    Ok(worktrees)
+}
+
+/// Run `git worktree list` and parse it, bypassing the cache.
+fn read_worktree_list(repo_path: &str) -> Result<Vec<WorktreeInfo>, String> {
+    let output = run_git_command(&["worktree", "list", "--porcelain"], repo_path)?;
+
+    let mut worktrees: Vec<WorktreeInfo> = Vec::new();
+    let output_str = String::from_utf8_lossy(&output.stdout);
+
+    let main_path = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    let mut current_path: Option<String> = None;
+    let mut current_commit: Option<String> = None;
+    let mut worktree_branch: Option<String> = None;
+    let mut is_locked = false;
+    let mut lock_reason: Option<String> = None;
+    let mut is_bare = false;
+    let mut is_detached = false;
+
+    for line in output_str.lines() {
+        if line.is_empty() {
+            if let Some(worktree_path) = current_path.take() {
+                let worktree_path_obj = Path::new(&worktree_path);
+
+                // Skip worktrees that no longer exist on disk (stale/prunable)
+                if !worktree_path_obj.exists() {
+                    // Clear state for this worktree entry
+                    current_commit.take();
+                    worktree_branch.take();
+                    lock_reason.take();
+                    is_locked = false;
+                    is_bare = false;
+                    is_detached = false;
+                    continue;
+                }
+
+                let path = worktree_path_obj
+                    .canonicalize()
+                    .map_err(|e| e.to_string())?
+                    .to_string_lossy()
+                    .to_string();
+
+                let is_main = path == main_path;
+
+                let name = if is_main {
+                    "main".to_string()
+                } else {
+                    Path::new(&worktree_path)
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .map(|s| s.to_string())
+                        .unwrap_or_else(|| "worktree".to_string())
+                };
+
+                let head_state = parse_head_state(
+                    current_commit.as_deref(),
+                    worktree_branch.as_deref(),
+                    is_detached,
+                );
+                let branch = worktree_branch
+                    .take()
+                    .map(|b| b.strip_prefix("refs/heads/").unwrap_or(&b).to_string());
+
+                if !is_bare {
+                    worktrees.push(WorktreeInfo {
+                        id: Uuid::new_v4().to_string(),
+                        name,
+                        path,
+                        branch,
+                        commit: current_commit.take(),
+                        head_state: Some(head_state),
+                        is_detached,
+                        is_main,
+                        is_locked,
+                        lock_reason: lock_reason.take(),
+                        lock_expires_at: None,
+                        startup_script: None,
+                        script_executed: false,
+                        created_at: 0,
+                        hidden: false,
+                        dev_url: None,
+                        description: None,
+                        labels: vec![],
+                        last_active: activity::git_activity_at(worktree_path_obj),
+                    });
+                }
+            }
+
+            is_locked = false;
+            is_bare = false;
+            is_detached = false;
+            continue;
+        }
+
+        if let Some(path) = line.strip_prefix("worktree ") {
+            current_path = Some(path.to_string());
+        } else if let Some(commit) = line.strip_prefix("HEAD ") {
+            current_commit = Some(commit.to_string());
+        } else if let Some(branch) = line.strip_prefix("branch ") {
+            worktree_branch = Some(branch.to_string());
+        } else if line == "locked" {
+            is_locked = true;
+        } else if let Some(reason) = line.strip_prefix("locked ") {
+            is_locked = true;
+            lock_reason = Some(reason.to_string());
+        } else if line == "detached" {
+            is_detached = true;
+        } else if line == "bare" {
+            is_bare = true;
+        }
+    }
+
+    // Handle the last worktree if output doesn't end with blank line
+    if let Some(worktree_path) = current_path.take() {
+        let worktree_path_obj = Path::new(&worktree_path);
+
+        // Skip worktrees that no longer exist on disk (stale/prunable)
+        if worktree_path_obj.exists() {
+            let path = worktree_path_obj
+                .canonicalize()
+                .map_err(|e| e.to_string())?
+                .to_string_lossy()
+                .to_string();
+
+            let is_main = path == main_path;
+
+            let name = if is_main {
+                "main".to_string()
+            } else {
+                Path::new(&worktree_path)
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "worktree".to_string())
+            };
+
+            let head_state = parse_head_state(
+                current_commit.as_deref(),
+                worktree_branch.as_deref(),
+                is_detached,
+            );
+            let branch = worktree_branch
+                .take()
+                .map(|b| b.strip_prefix("refs/heads/").unwrap_or(&b).to_string());
+
+            if !is_bare {
+                worktrees.push(WorktreeInfo {
+                    id: Uuid::new_v4().to_string(),
+                    name,
+                    path,
+                    branch,
+                    commit: current_commit.take(),
+                    head_state: Some(head_state),
+                    is_detached,
+                    is_main,
+                    is_locked,
+                    lock_reason: lock_reason.take(),
+                    lock_expires_at: None,
+                    startup_script: None,
+                    script_executed: false,
+                    created_at: 0,
+                    hidden: false,
+                    dev_url: None,
+                    description: None,
+                    labels: vec![],
+                    last_active: activity::git_activity_at(worktree_path_obj),
+                });
+            }
+        }
+    }
+
+    Ok(worktrees)
+}
+
+/// Whether a local branch exists.
+pub fn local_branch_exists(repo_path: &str, branch: &str) -> bool {
+    let reference = format!("refs/heads/{}", branch);
+    run_git_command(&["rev-parse", "--verify", "--quiet", &reference], repo_path).is_ok()
+}
+
+/// Branches never deleted along with a worktree, in addition to each
+/// repository's `protected_branches`.
+pub const PROTECTED_BRANCHES: [&str; 4] = ["main", "master", "develop", "development"];
+
+fn remote_branch_exists(repo_path: &str, branch: &str) -> bool {
+    let reference = format!("refs/remotes/origin/{}", branch);
+    run_git_command(&["rev-parse", "--verify", "--quiet", &reference], repo_path).is_ok()
+}
+
+/// Name of the remote's default branch (e.g. `main`), from `origin/HEAD`.
+/// Without it, falls back to the branch named by `init.defaultBranch` when it
+/// exists locally or on `origin`, then an `origin/main` or `origin/master`
+/// remote branch, then a local `main` or `master`.
+pub fn get_default_branch(repo_path: &str) -> Option<String> {
+    let remote_head = run_git_command(
+        &[
+            "symbolic-ref",
+            "--quiet",
+            "--short",
+            "refs/remotes/origin/HEAD",
+        ],
+        repo_path,
+    )
+    .ok()
+    .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    .filter(|s| !s.is_empty());
+
+    if let Some(remote_head) = remote_head {
+        return Some(
+            remote_head
+                .strip_prefix("origin/")
+                .unwrap_or(&remote_head)
+                .to_string(),
+        );
+    }
+
+    let configured = run_git_command(&["config", "--get", "init.defaultBranch"], repo_path)
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|b| {
+            !b.is_empty()
+                && (local_branch_exists(repo_path, b) || remote_branch_exists(repo_path, b))
+        });
+    if configured.is_some() {
+        return configured;
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|b| remote_branch_exists(repo_path, b))
+        .or_else(|| {
+            ["main", "master"]
+                .into_iter()
+                .find(|b| local_branch_exists(repo_path, b))
+        })
+        .map(str::to_string)
+}
+
+/// Ask the remote for its default branch and update `origin/HEAD` to match.
+/// Needs network access; fails without an `origin` remote.
+pub fn update_remote_head(repo_path: &str) -> Result<(), String> {
+    run_git_command(&["remote", "set-head", "origin", "--auto"], repo_path).map(|_| ())
+}
+
+/// Detect the branch new work should start from: the remote default branch
+/// (local name if it exists, else `origin/<name>`).
+pub fn detect_base_branch(repo_path: &str) -> Option<String> {
+    let branch = get_default_branch(repo_path)?;
+    if local_branch_exists(repo_path, &branch) {
+        Some(branch)
+    } else if remote_branch_exists(repo_path, &branch) {
+        Some(format!("origin/{}", branch))
+    } else {
+        None
+    }
+}
+
+/// Startup script written into new worktrees, kept out of git through the
+/// repository's `info/exclude`.
+pub const SETUP_SCRIPT_FILE: &str = ".worktree-setup.sh";
+
+/// Create a new worktree. With `options.branch` that branch is checked out;
+/// with `options.detach`, the start point is checked out without a branch.
+/// Otherwise a branch is created from the start point (default HEAD), named
+/// `new_branch` or after the worktree. An explicit `new_branch` fails if it
+/// already exists; an existing branch named after the worktree is checked out
+/// instead, as git does.
+pub fn create_worktree(
+    repo_path: &str,
+    name: &str,
+    options: &CreateWorktreeOptions,
+) -> Result<WorktreeInfo, String> {
+    let repo_path_canonical = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?;
+    let repo_path_str = repo_path_canonical.to_string_lossy().to_string();
+
+    let branch = options.branch.as_deref();
+    let start_point = options.start_point.as_deref();
+    if let Some(new_branch) = options.new_branch.as_deref() {
+        if branch.is_some() || options.detach {
+            return Err(
+                "Pass either an existing branch, a new branch or a detached checkout".to_string(),
+            );
+        }
+        if local_branch_exists(&repo_path_str, new_branch) {
+            return Err(format!("Branch already exists: {}", new_branch));
+        }
+    }
+    if branch.is_some() && options.detach {
+        return Err("Pass either an existing branch or a detached checkout, not both".to_string());
+    }
+
+    // Use ~/.aristar-worktrees/{hash}/{name} for worktree location
+    ensure_repo_info(&repo_path_str)?;
+    let worktree_base = get_worktree_base_for_repo(&repo_path_str);
+    let worktree_path = worktree_base.join(name);
+    let worktree_path_str = worktree_path.to_string_lossy().to_string();
+
+    // Render template variables before anything is created. A port reserved
+    // here for `{{port}}` is released again if the worktree isn't created; one
+    // already held for the path (e.g. by a worktree of that name) is kept.
+    let (port, new_reservation) = match options.startup_script.as_deref() {
+        Some(script) if uses_variable(script, "port") => {
+            let held = ports::reserved_port(ports::DEV_SERVER_SERVICE, &worktree_path_str)?;
+            let port = ports::reserve_port(ports::DEV_SERVER_SERVICE, &worktree_path_str, None)?;
+            (Some(port), held.is_none())
+        }
+        _ => (None, false),
+    };
+    let release_port = || {
+        if new_reservation {
+            let _ = ports::release_ports(&worktree_path_str, Some(ports::DEV_SERVER_SERVICE));
+        }
+    };
+    let startup_script = match options.startup_script.as_deref() {
+        Some(script) => {
+            let context = ScriptTemplateContext {
+                branch: branch.or(options.new_branch.as_deref()).map(str::to_string),
+                worktree_path: Some(worktree_path_str.clone()),
+                repo_name: repo_path_canonical
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string()),
+                task_id: None,
+                port: port.map(|port| port.to_string()),
+            };
+            Some(render_script(script, &context).inspect_err(|_| release_port())?)
+        }
+        None => None,
+    };
+
+    let mut args = vec!["worktree", "add"];
+    let new_branch = match (branch, options.detach) {
+        (None, false) => options
+            .new_branch
+            .as_deref()
+            .or_else(|| (!local_branch_exists(&repo_path_str, name)).then_some(name)),
+        _ => None,
+    };
+    if let Some(new_branch) = new_branch {
+        args.extend(["-b", new_branch]);
+    } else if options.detach {
+        args.push("--detach");
+    }
+    args.push(worktree_path_str.as_str());
+
+    // Without a new branch, git checks out the existing branch named after the worktree
+    let start_point = match branch {
+        Some(branch) => Some(branch),
+        None if new_branch.is_some() || options.detach => start_point,
+        None => None,
+    };
+    if let Some(start_point) = start_point {
+        args.push(start_point);
+    }
+
+    run_queued(&repo_path_str, &format!("create worktree {}", name), || {
+        run_git_command(&args, &repo_path_str)
+    })
+    .inspect_err(|_| release_port())?;
+    invalidate_worktree_list_cache(&repo_path_str);
+
+    let worktrees = list_worktrees(&repo_path_str)?;
+    let new_worktree = worktrees
+        .iter()
+        .find(|w| w.path == worktree_path_str)
+        .cloned()
+        .ok_or("Failed to find created worktree")?;
+    lfs::setup_worktree_lfs(&worktree_path_str);
+    hooks::setup_worktree_hooks(&repo_path_str, &worktree_path_str);
+    local_files::setup_worktree_local_files(&repo_path_str, &worktree_path_str);
+    shared_deps::setup_worktree_shared_deps(&repo_path_str, &worktree_path_str);
+
+    if let Some(script) = startup_script {
+        ensure_excluded(&worktree_path, &format!("/{}", SETUP_SCRIPT_FILE))?;
+        let script_path = worktree_path.join(SETUP_SCRIPT_FILE);
+        std::fs::write(&script_path, script).map_err(|e| e.to_string())?;
+
+        if options.execute_script {
+            let output = Command::new("bash")
+                .arg(&script_path)
+                .current_dir(&worktree_path)
+                .output()
+                .map_err(|e| e.to_string())?;
+
+            if !output.status.success() {
+                return Err(String::from_utf8_lossy(&output.stderr).to_string());
+            }
+        }
+    }
+
+    Ok(new_worktree)
+}
+
+/// Folder name of a worktree path, for messages.
+pub fn worktree_display_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Remove a worktree.
+pub fn remove_worktree(path: &str, force: bool, delete_branch: bool) -> Result<(), String> {
+    let repo_path = find_git_repo_root(path)?;
+    let path_canonical = Path::new(path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    // Get branch name before deletion (if we need to delete it)
+    let branch_to_delete = if delete_branch {
+        let worktrees = list_worktrees(&repo_path)?;
+        worktrees
+            .iter()
+            .find(|w| w.path == path_canonical)
+            .and_then(|w| w.branch.clone())
+    } else {
+        None
+    };
+
+    let operation = format!("remove worktree {}", worktree_display_name(&path_canonical));
+    run_queued(&repo_path, &operation, || {
+        // Unlink shared dependency folders so nothing reaches the main worktree
+        shared_deps::unshare_dependency_dirs(&path_canonical, &shared_deps::shared_dirs());
+
+        // Remove worktree first
+        let mut args = vec!["worktree", "remove", &path_canonical];
+        if force {
+            // Git requires --force twice to remove a locked worktree
+            args.push("--force");
+            args.push("--force");
+        }
+
+        run_git_command(&args, &repo_path)?;
+        invalidate_worktree_list_cache(&repo_path);
+
+        // Then delete branch if requested (skip protected branches)
+        if let Some(branch) = branch_to_delete {
+            if !PROTECTED_BRANCHES.contains(&branch.as_str()) {
+                let delete_args = if force {
+                    vec!["branch", "-D", &branch]
+                } else {
+                    vec!["branch", "-d", &branch]
+                };
+                // Ignore errors - branch might already be deleted or have unmerged changes
+                let _ = run_git_command(&delete_args, &repo_path);
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Tries at `git worktree move` while the directory is busy.
+pub const RENAME_ATTEMPTS: u32 = 4;
+
+/// Wait before the first retry of a busy rename; doubled after each try.
+pub const RENAME_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Whether a failed `git worktree move` may succeed when retried: the
+/// directory or a file in it is held open (EBUSY, or a sharing violation on
+/// Windows, which git reports as permission denied).
+pub fn is_transient_move_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("resource busy")
+        || stderr.contains("being used by another process")
+        || stderr.contains("sharing violation")
+        || (cfg!(windows) && stderr.contains("permission denied"))
+}
+
+/// Rename a worktree, moving its directory to `new_name` next to it.
+///
+/// Checked before anything moves: the name, a free target path, and the
+/// processes running inside the worktree. Those are stopped with
+/// `stop_processes`; otherwise their presence fails the rename. A move that
+/// fails because the directory is busy is retried `RENAME_ATTEMPTS` times.
+pub fn rename_worktree(
+    old_path: &str,
+    new_name: &str,
+    stop_processes: bool,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    let name = new_name.trim();
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.starts_with('-')
+        || name.contains(['/', '\\'])
+    {
+        return Err(RenameWorktreeError::InvalidName {
+            name: new_name.to_string(),
+        });
+    }
+
+    let old_path_canonical = Path::new(old_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?;
+    let parent = old_path_canonical
+        .parent()
+        .ok_or("No parent directory".to_string())?;
+    let new_path = parent.join(name);
+
+    relocate_worktree(
+        &old_path_canonical.to_string_lossy(),
+        &new_path,
+        &format!("rename worktree to {}", name),
+        stop_processes,
+    )
+}
+
+/// Move a worktree's directory to `new_path`, which may be in another
+/// directory. The target must lie within the allowed worktree bases (see
+/// `get_allowed_worktree_bases`); missing parent directories are created.
+/// Otherwise checked and retried like `rename_worktree`.
+pub fn move_worktree(
+    old_path: &str,
+    new_path: &str,
+    stop_processes: bool,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    let target = new_path.trim();
+    if target.is_empty() || target.starts_with('-') {
+        return Err(RenameWorktreeError::InvalidName {
+            name: new_path.to_string(),
+        });
+    }
+    let target = Path::new(target);
+    if !target.is_absolute() {
+        return Err(format!("Target must be an absolute path: {}", target.display()).into());
+    }
+    let target = validate_path_within_bases(target, &get_allowed_worktree_bases())?;
+
+    let old_path_canonical = Path::new(old_path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    if target.starts_with(&old_path_canonical) && target != Path::new(&old_path_canonical) {
+        return Err(format!("Cannot move a worktree into itself: {}", target.display()).into());
+    }
+
+    if let Some(parent) = target.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+        }
+    }
+
+    relocate_worktree(
+        &old_path_canonical,
+        &target,
+        &format!("move worktree to {}", target.display()),
+        stop_processes,
+    )
+}
+
+/// `git worktree move` from `old_path_canonical` to `new_path`, shared by
+/// `rename_worktree` and `move_worktree`.
+fn relocate_worktree(
+    old_path_canonical: &str,
+    new_path: &Path,
+    op_label: &str,
+    stop_processes: bool,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    let repo_path = find_git_repo_root(old_path_canonical)?;
+    let new_path_string = new_path.to_string_lossy().to_string();
+
+    if new_path_string != old_path_canonical {
+        if new_path.symlink_metadata().is_ok() {
+            return Err(RenameWorktreeError::TargetExists {
+                path: new_path_string,
+            });
+        }
+
+        // Can't tell without lsof or /proc; let the move find out
+        if let Ok(running) = get_processes_in_worktree(old_path_canonical) {
+            if !running.is_empty() {
+                let remaining = if stop_processes {
+                    terminate_and_wait(old_path_canonical, TERMINATE_TIMEOUT)?
+                } else {
+                    running
+                };
+                if !remaining.is_empty() {
+                    return Err(RenameWorktreeError::ProcessesRunning {
+                        processes: remaining,
+                    });
+                }
+            }
+        }
+
+        let args = ["worktree", "move", old_path_canonical, &new_path_string];
+        let mut delay = RENAME_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            let result = run_queued(&repo_path, op_label, || run_git_command(&args, &repo_path));
+            match result {
+                Ok(_) => break,
+                Err(e) if is_transient_move_error(&e) && attempt < RENAME_ATTEMPTS => {
+                    println!(
+                        "[worktrees] {} is busy, retrying move in {:?}",
+                        old_path_canonical, delay
+                    );
+                    std::thread::sleep(delay);
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) if is_transient_move_error(&e) => {
+                    return Err(RenameWorktreeError::Busy {
+                        path: old_path_canonical.to_string(),
+                        attempts: attempt,
+                    });
+                }
+                Err(e) if e.contains("already exists") => {
+                    return Err(RenameWorktreeError::TargetExists {
+                        path: new_path_string,
+                    });
+                }
+                Err(e) => {
+                    return Err(RenameWorktreeError::Failed {
+                        message: e.trim().to_string(),
+                    });
+                }
+            }
+        }
+        invalidate_worktree_list_cache(&repo_path);
+    }
+
+    let worktrees = list_worktrees(&repo_path)?;
+    worktrees
+        .iter()
+        .find(|w| w.path == new_path_string)
+        .cloned()
+        .ok_or_else(|| "Failed to find moved worktree".to_string().into())
+}
+
+/// Create a worktree named `new_name` at the same commit as the worktree at
+/// `path` (detached) and carry its uncommitted changes across: modified and
+/// deleted tracked files via `git diff --binary HEAD`, untracked files that
+/// are not ignored by copying them. The new worktree is removed again if the
+/// changes can't be applied.
+pub fn duplicate_worktree(path: &str, new_name: &str) -> Result<WorktreeInfo, String> {
+    let repo_path = find_git_repo_root(path)?;
+    let head = run_git_command(&["rev-parse", "--verify", "HEAD"], path)
+        .map_err(|_| "Cannot duplicate a worktree without commits".to_string())?;
+    let head = String::from_utf8_lossy(&head.stdout).trim().to_string();
+
+    let diff = run_git_command(&["diff", "--binary", "HEAD"], path)?.stdout;
+    let untracked = run_git_command(&["ls-files", "-z", "--others", "--exclude-standard"], path)?;
+    let untracked: Vec<String> = String::from_utf8_lossy(&untracked.stdout)
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let new_worktree = create_worktree(
+        &repo_path,
+        new_name,
+        &CreateWorktreeOptions {
+            start_point: Some(head),
+            detach: true,
+            ..Default::default()
+        },
+    )?;
+
+    let result = (|| {
+        if !diff.is_empty() {
+            let patch_path =
+                std::env::temp_dir().join(format!("aristar-duplicate-{}.patch", Uuid::new_v4()));
+            fs::write(&patch_path, &diff).map_err(|e| format!("Failed to write patch: {}", e))?;
+            let applied = run_git_command(
+                &["apply", "--binary", &patch_path.to_string_lossy()],
+                &new_worktree.path,
+            );
+            let _ = fs::remove_file(&patch_path);
+            applied.map_err(|e| format!("Failed to apply uncommitted changes: {}", e.trim()))?;
+        }
+
+        for relative in &untracked {
+            let from = Path::new(path).join(relative);
+            if !from.is_file() {
+                continue;
+            }
+            let to = Path::new(&new_worktree.path).join(relative);
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            fs::copy(&from, &to).map_err(|e| format!("Failed to copy {}: {}", relative, e))?;
+        }
+        Ok::<(), String>(())
+    })();
+
+    if let Err(e) = result {
+        let _ = remove_worktree(&new_worktree.path, true, false);
+        return Err(e);
+    }
+    Ok(new_worktree)
+}
+
+/// Check out `git_ref` in the worktree at `path`, in place. A local branch is
+/// checked out as that branch; anything else (tag, commit, remote branch)
+/// detaches HEAD. Fails when the worktree has uncommitted changes to tracked
+/// files, a rebase is in progress, or the branch is checked out in another
+/// worktree. Untracked files are left alone unless the checkout would
+/// overwrite them, which git refuses.
+pub fn checkout_in_worktree(path: &str, git_ref: &str) -> Result<WorktreeInfo, String> {
+    let git_ref = git_ref.trim();
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err(format!("Invalid ref: {}", git_ref));
+    }
+    if is_rebase_in_progress(path) {
+        return Err("A rebase is in progress; continue or abort it first".to_string());
+    }
+    let status = get_worktree_status(path)?;
+    if status.staged + status.modified + status.conflicted > 0 {
+        return Err(
+            "Worktree has uncommitted changes; commit or stash them before switching".to_string(),
+        );
+    }
+    let commit = format!("{}^{{commit}}", git_ref);
+    run_git_command(&["rev-parse", "--verify", "--quiet", &commit], path)
+        .map_err(|_| format!("Cannot check out {}: not found", git_ref))?;
+
+    let repo_path = find_git_repo_root(path)?;
+    let args = if local_branch_exists(&repo_path, git_ref) {
+        vec!["checkout", git_ref, "--"]
+    } else {
+        vec!["checkout", "--detach", git_ref, "--"]
+    };
+    run_git_command(&args, path)
+        .map_err(|e| format!("Failed to check out {}: {}", git_ref, e.trim()))?;
+    invalidate_worktree_list_cache(&repo_path);
+    println!("[worktrees] Checked out {} in {}", git_ref, path);
+
+    let path_canonical = Path::new(path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+    list_worktrees(&repo_path)?
+        .into_iter()
+        .find(|w| w.path == path_canonical)
+        .ok_or_else(|| "Failed to find worktree after checkout".to_string())
+}
+
+/// Lock a worktree. With `expires_at` (milliseconds) the lock is lifted by
+/// the app once that time has passed (see `lock_expiry`).
+pub fn lock_worktree(
+    path: &str,
+    reason: Option<&str>,
+    expires_at: Option<i64>,
+) -> Result<(), String> {
+    if expires_at.is_some_and(|at| at <= chrono::Utc::now().timestamp_millis()) {
+        return Err("The lock expiry must be in the future".to_string());
+    }
+    let repo_path = find_git_repo_root(path)?;
+    let path_canonical = Path::new(path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    let mut args = vec!["worktree", "lock", &path_canonical];
+    if let Some(r) = reason {
+        args.push("--reason");
+        args.push(r);
+    }
+
+    run_git_command(&args, &repo_path)?;
+    invalidate_worktree_list_cache(&repo_path);
+    write_lock_file(Path::new(path), reason, expires_at)?;
+
+    Ok(())
+}
+
+/// Unlock a worktree.
+pub fn unlock_worktree(path: &str) -> Result<(), String> {
+    let repo_path = find_git_repo_root(path)?;
+    let path_canonical = Path::new(path)
+        .canonicalize()
+        .map_err(|e| e.to_string())?
+        .to_string_lossy()
+        .to_string();
+
+    run_git_command(&["worktree", "unlock", &path_canonical], &repo_path)?;
+    invalidate_worktree_list_cache(&repo_path);
+    remove_lock_file(Path::new(path));
+
+    Ok(())
+}
+
+// ============ Lock File ============
+
+/// Lock file inside a locked worktree, for scripts and CI.
+pub const LOCK_FILE: &str = ".aristar/lock.json";
+
+/// How often `wait_for_unlock` checks the lock.
+const UNLOCK_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exclude pattern keeping `.aristar/` out of git.
+const ARISTAR_EXCLUDE_PATTERN: &str = "/.aristar/";
+
+/// Add `.aristar/` to the repository's `info/exclude` (shared by all worktrees).
+pub fn ensure_aristar_excluded(worktree_path: &Path) -> Result<(), String> {
+    ensure_excluded(worktree_path, ARISTAR_EXCLUDE_PATTERN)
+}
+
+/// Add `pattern` to the repository's `info/exclude` unless it is there already.
+pub fn ensure_excluded(worktree_path: &Path, pattern: &str) -> Result<(), String> {
+    let cwd = worktree_path.to_string_lossy();
+    let output = run_git_command(&["rev-parse", "--git-path", "info/exclude"], &cwd)?;
+    let exclude_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let exclude_path = if exclude_path.is_absolute() {
+        exclude_path
+    } else {
+        worktree_path.join(exclude_path)
+    };
+
+    let existing = fs::read_to_string(&exclude_path).unwrap_or_default();
+    if existing.lines().any(|l| l.trim() == pattern) {
+        return Ok(());
+    }
+
+    if let Some(parent) = exclude_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create git info folder: {}", e))?;
+    }
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(pattern);
+    contents.push('\n');
+    fs::write(&exclude_path, contents).map_err(|e| format!("Failed to update git exclude: {}", e))
+}
+
+fn write_lock_file(
+    worktree_path: &Path,
+    reason: Option<&str>,
+    expires_at: Option<i64>,
+) -> Result<(), String> {
+    ensure_aristar_excluded(worktree_path)?;
+
+    let lock = WorktreeLockFile {
+        locked: true,
+        reason: reason.map(str::to_string),
+        locked_at: chrono::Utc::now().timestamp_millis(),
+        locked_by: "aristar-worktrees".to_string(),
+        pid: std::process::id(),
+        expires_at,
+    };
+    let lock_path = worktree_path.join(LOCK_FILE);
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string_pretty(&lock).map_err(|e| e.to_string())?;
+    fs::write(&lock_path, json).map_err(|e| format!("Failed to write lock file: {}", e))
+}
+
+fn remove_lock_file(worktree_path: &Path) {
+    let lock_path = worktree_path.join(LOCK_FILE);
+    let _ = fs::remove_file(&lock_path);
+    // Only succeeds when nothing else (e.g. context files) lives there
+    if let Some(parent) = lock_path.parent() {
+        let _ = fs::remove_dir(parent);
+    }
+}
+
+/// Whether git considers a worktree locked.
+pub fn is_worktree_locked(path: &str) -> Result<bool, String> {
+    let output = run_git_command(&["rev-parse", "--git-dir"], path)?;
+    let git_dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let git_dir = if git_dir.is_absolute() {
+        git_dir
+    } else {
+        Path::new(path).join(git_dir)
+    };
+    Ok(git_dir.join("locked").exists())
+}
+
+/// Block until a worktree is unlocked or `timeout` passes.
+/// Returns whether the worktree is unlocked.
+pub fn wait_for_unlock(path: &str, timeout: Duration) -> Result<bool, String> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !is_worktree_locked(path)? {
+            return Ok(true);
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(false);
+        }
+        std::thread::sleep(UNLOCK_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+// ============ Stale Locks ============
+
+/// Locks younger than this may still belong to a running git process.
+pub const STALE_LOCK_MIN_AGE: Duration = Duration::from_secs(60);
+
+/// Lock files git leaves behind when a process crashes: in the shared git dir
+/// and in each worktree's admin folder.
+pub fn find_git_lock_files(repo_path: &str) -> Result<Vec<PathBuf>, String> {
+    let common_dir = get_repo_context(repo_path)?.common_dir;
+
+    let mut git_dirs = vec![common_dir.clone()];
+    if let Ok(entries) = fs::read_dir(common_dir.join("worktrees")) {
+        let mut admin_dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        admin_dirs.sort();
+        git_dirs.extend(admin_dirs);
+    }
+
+    Ok(git_dirs
+        .iter()
+        .flat_map(|dir| {
+            ["index.lock", "HEAD.lock", "config.lock", "packed-refs.lock"]
+                .iter()
+                .map(move |name| dir.join(name))
+        })
+        .filter(|p| p.is_file())
+        .collect())
+}
+
+/// PIDs of processes with `path` open, via `lsof`. `None` when `lsof` isn't
+/// available.
+fn lock_file_holders(path: &Path) -> Option<Vec<u32>> {
+    let output = Command::new("lsof").arg("-t").arg(path).output().ok()?;
+    // lsof exits with 1 when nothing has the file open
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|l| l.trim().parse().ok())
+            .collect(),
+    )
+}
+
+/// PIDs of running git processes, via `pgrep`.
+fn running_git_processes() -> Vec<u32> {
+    Command::new("pgrep")
+        .args(["-x", "git"])
+        .output()
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter_map(|l| l.trim().parse().ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Remove git lock files a crashed process left behind in `repo_path`.
+/// Nothing is removed unless every lock is older than `min_age` and no process
+/// holds it open (or, without `lsof`, no git process is running at all).
+/// Returns the removed files.
+pub fn remove_stale_locks(repo_path: &str, min_age: Duration) -> Result<Vec<String>, String> {
+    let locks = find_git_lock_files(repo_path)?;
+
+    for lock in &locks {
+        let age = fs::metadata(lock)
+            .and_then(|m| m.modified())
+            .map_err(|e| format!("Failed to read {}: {}", lock.display(), e))?
+            .elapsed()
+            .unwrap_or_default();
+        if age < min_age {
+            return Err(format!(
+                "{} is only {}s old; another git process may still be using it",
+                lock.display(),
+                age.as_secs()
+            ));
+        }
+
+        let holders = lock_file_holders(lock).unwrap_or_else(running_git_processes);
+        if let Some(pid) = holders.first() {
+            return Err(format!(
+                "{} is still in use by process {}",
+                lock.display(),
+                pid
+            ));
+        }
+    }
+
+    let mut removed = Vec::new();
+    for lock in locks {
+        fs::remove_file(&lock)
+            .map_err(|e| format!("Failed to remove {}: {}", lock.display(), e))?;
+        println!("[git] Removed stale lock {}", lock.display());
+        removed.push(lock.to_string_lossy().to_string());
+    }
+    Ok(removed)
+}
+
+/// Remove stale git lock files in a repository, see `remove_stale_locks`.
+pub fn force_remove_stale_lock(repo_path: &str) -> Result<Vec<String>, String> {
+    remove_stale_locks(repo_path, STALE_LOCK_MIN_AGE)
+}
+
+// ============ Rebase ============
+
+/// Whether a rebase is in progress in a worktree.
+pub fn is_rebase_in_progress(path: &str) -> bool {
+    ["rebase-merge", "rebase-apply"].iter().any(|dir| {
+        run_git_command(&["rev-parse", "--git-path", dir], path)
+            .map(|o| {
+                Path::new(path)
+                    .join(String::from_utf8_lossy(&o.stdout).trim())
+                    .is_dir()
+            })
+            .unwrap_or(false)
+    })
+}
+
+/// Files with unresolved conflicts.
+fn conflicted_files(path: &str) -> Result<Vec<String>, String> {
+    let output = run_git_command(&["diff", "--name-only", "--diff-filter=U"], path)?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Whether a file still contains conflict markers.
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|l| l.starts_with("<<<<<<< ") || l.starts_with(">>>>>>> "))
+}
+
+fn count_range(path: &str, range: &str) -> u32 {
+    run_git_command(&["rev-list", "--count", range], path)
+        .ok()
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Run a rebase step (`git rebase ...`) and report where it ended up: done, or
+/// stopped on conflicts. Other failures are errors.
+fn run_rebase_step(path: &str, args: &[&str], onto: &str) -> Result<RebaseResult, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(path)
+        // Keep the commit messages instead of opening an editor on continue
+        .env("GIT_EDITOR", "true")
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let text = text.trim().to_string();
+
+    if output.status.success() && !is_rebase_in_progress(path) {
+        return Ok(RebaseResult {
+            status: RebaseStatus::Rebased,
+            onto: onto.to_string(),
+            commits: count_range(path, &format!("{}..HEAD", onto)),
+            conflicts: Vec::new(),
+            output: text,
+        });
+    }
+
+    let conflicts = conflicted_files(path)?;
+    if is_rebase_in_progress(path) && !conflicts.is_empty() {
+        return Ok(RebaseResult {
+            status: RebaseStatus::Conflicts,
+            onto: onto.to_string(),
+            commits: count_range(path, &format!("{}..HEAD", onto)),
+            conflicts,
+            output: text,
+        });
+    }
+    Err(format!("Rebase failed: {}", text))
+}
+
+/// Rebase a worktree's branch (or detached HEAD) onto `onto`, e.g. its base
+/// branch. Stops on conflicts, leaving the rebase in progress so it can be
+/// continued with `continue_rebase` or undone with `abort_rebase`.
+pub fn rebase_worktree(path: &str, onto: &str) -> Result<RebaseResult, String> {
+    if is_rebase_in_progress(path) {
+        return Err("A rebase is already in progress; continue or abort it".to_string());
+    }
+    let status = get_worktree_status(path)?;
+    if status.staged + status.modified + status.conflicted > 0 {
+        return Err(
+            "Worktree has uncommitted changes; commit or stash them before rebasing".to_string(),
+        );
+    }
+    run_git_command(&["rev-parse", "--verify", "--quiet", onto], path)
+        .map_err(|_| format!("Cannot rebase onto {}: not found", onto))?;
+
+    if is_ancestor(path, onto, "HEAD")? {
+        return Ok(RebaseResult {
+            status: RebaseStatus::UpToDate,
+            onto: onto.to_string(),
+            commits: 0,
+            conflicts: Vec::new(),
+            output: String::new(),
+        });
+    }
+
+    let result = run_rebase_step(path, &["rebase", onto], onto)?;
+    println!(
+        "[worktrees] Rebased {} onto {}: {:?}",
+        path, onto, result.status
+    );
+    Ok(result)
+}
+
+/// Continue a rebase stopped on conflicts. Conflicted files without conflict
+/// markers left are staged first; the others are reported as still conflicted.
+pub fn continue_rebase(path: &str) -> Result<RebaseResult, String> {
+    if !is_rebase_in_progress(path) {
+        return Err("No rebase in progress".to_string());
+    }
+
+    let onto = run_git_command(&["rev-parse", "--git-path", "rebase-merge/onto"], path)
+        .ok()
+        .and_then(|o| {
+            let file = Path::new(path).join(String::from_utf8_lossy(&o.stdout).trim());
+            std::fs::read_to_string(file).ok()
+        })
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+
+    let mut unresolved = Vec::new();
+    for file in conflicted_files(path)? {
+        let content = std::fs::read_to_string(Path::new(path).join(&file)).unwrap_or_default();
+        if has_conflict_markers(&content) {
+            unresolved.push(file);
+        } else {
+            run_git_command(&["add", "--", &file], path)?;
+        }
+    }
+    if !unresolved.is_empty() {
+        return Ok(RebaseResult {
+            status: RebaseStatus::Conflicts,
+            onto: onto.clone(),
+            commits: count_range(path, &format!("{}..HEAD", onto)),
+            conflicts: unresolved,
+            output: "Resolve the conflict markers before continuing".to_string(),
+        });
+    }
+
+    run_rebase_step(path, &["rebase", "--continue"], &onto)
+}
+
+/// Abort a rebase in progress, restoring the worktree to where it started.
+pub fn abort_rebase(path: &str) -> Result<RebaseResult, String> {
+    if !is_rebase_in_progress(path) {
+        return Err("No rebase in progress".to_string());
+    }
+    let output = run_git_command(&["rebase", "--abort"], path)?;
+    Ok(RebaseResult {
+        status: RebaseStatus::Aborted,
+        onto: String::new(),
+        commits: 0,
+        conflicts: Vec::new(),
+        output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// Resolve where `path` sits within its git repository.
+///
+/// Relative paths printed by git are resolved against `path`, and all paths are
+/// canonicalized so they compare equal to `git worktree list` output. A bare
+/// repository has no working tree, so its git directory stands in for the
+/// toplevel.
+pub fn get_repo_context(path: &str) -> Result<RepoContext, String> {
+    let rev_parse = |args: &[&str]| -> Result<Vec<String>, String> {
+        let output = Command::new("git")
+            .arg("rev-parse")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err("No git repository found".to_string());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .collect())
+    };
+
+    // `--show-toplevel` fails in a bare repository, so only ask outside one
+    let fields = rev_parse(&["--is-bare-repository", "--git-common-dir", "--git-dir"])?;
+    let [is_bare, common_dir, git_dir] = fields.as_slice() else {
+        return Err(format!(
+            "Unexpected git rev-parse output: {}",
+            fields.join("\n")
+        ));
+    };
+
+    let resolve = |p: &str| {
+        let p = Path::new(path).join(p);
+        p.canonicalize()
+            .map_err(|e| format!("Failed to resolve {}: {}", p.display(), e))
+    };
+    let common_dir = resolve(common_dir)?;
+    let git_dir = resolve(git_dir)?;
+    let toplevel = if is_bare == "true" {
+        git_dir.clone()
+    } else {
+        let toplevel = rev_parse(&["--show-toplevel"])?;
+        match toplevel.first() {
+            Some(toplevel) => resolve(toplevel)?,
+            None => return Err("Unexpected git rev-parse output: no toplevel".to_string()),
+        }
+    };
+
+    Ok(RepoContext {
+        is_worktree: git_dir != common_dir,
+        common_dir,
+        toplevel,
+    })
+}
+
+/// Find the root git repository for a path (works for worktrees too).
+pub fn find_git_repo_root(path: &str) -> Result<String, String> {
+    let context = get_repo_context(path)?;
+    Ok(context.repo_root().to_string_lossy().to_string())
+}
+
+/// Create a worktree at a specific custom path.
+/// Used by the Agent Manager to create worktrees inside task folders.
+///
+/// # Security
+/// This function validates that the destination path is within allowed directories
+/// to prevent path traversal attacks.
+pub fn create_worktree_at_path(
+    repo_path: &str,
+    destination_path: &str,
+    branch_or_commit: Option<&str>,
+) -> Result<String, String> {
+    create_sparse_worktree_at_path(repo_path, destination_path, branch_or_commit, &[])
+}
+
+/// Create a worktree at a specific custom path, checking out only
+/// `sparse_paths` (see `sparse`). An empty list checks out everything.
+pub fn create_sparse_worktree_at_path(
+    repo_path: &str,
+    destination_path: &str,
+    branch_or_commit: Option<&str>,
+    sparse_paths: &[String],
+) -> Result<String, String> {
+    let sparse_paths = sparse::normalize_sparse_paths(sparse_paths)?;
+    let repo_path_canonical = Path::new(repo_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve repo path: {}", e))?;
+    let repo_path_str = repo_path_canonical.to_string_lossy().to_string();
+
+    // Security: Validate destination path is within allowed directories
+    let dest_path = Path::new(destination_path);
+    let allowed_bases = get_allowed_worktree_bases();
+    validate_path_within_bases(dest_path, &allowed_bases)?;
+
+    // Ensure the parent directory exists
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
+    }
+
+    // Build the git worktree add command
+    let mut args = vec!["worktree", "add", destination_path];
+
+    // Always use --detach to create worktrees in detached HEAD mode.
+    // This prevents "branch already used by worktree" errors when creating
+    // multiple worktrees from the same branch (e.g., for agent tasks).
+    args.push("--detach");
+
+    if !sparse_paths.is_empty() {
+        args.push("--no-checkout");
+    }
+
+    if let Some(ref_name) = branch_or_commit {
+        args.push(ref_name);
+    }
+
+    let operation = format!(
+        "create worktree {}",
+        worktree_display_name(destination_path)
+    );
+    run_queued(&repo_path_str, &operation, || {
+        run_git_command(&args, &repo_path_str)
+    })?;
+    invalidate_worktree_list_cache(&repo_path_str);
+
+    // Canonicalize the destination path after creation
+    let created_path = Path::new(destination_path)
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve created worktree path: {}", e))?
+        .to_string_lossy()
+        .to_string();
+    if !sparse_paths.is_empty() {
+        if let Err(e) = sparse::checkout_sparse(&created_path, &sparse_paths) {
+            let operation = format!("remove worktree {}", worktree_display_name(&created_path));
+            let _ = run_queued(&repo_path_str, &operation, || {
+                run_git_command(
+                    &["worktree", "remove", "--force", &created_path],
+                    &repo_path_str,
+                )
+            });
+            invalidate_worktree_list_cache(&repo_path_str);
+            return Err(e);
+        }
+    }
+    lfs::setup_worktree_lfs(&created_path);
+    hooks::setup_worktree_hooks(&repo_path_str, &created_path);
+    local_files::setup_worktree_local_files(&repo_path_str, &created_path);
+    shared_deps::setup_worktree_shared_deps(&repo_path_str, &created_path);
+
+    Ok(created_path)
+}
+
+// ============ Async Versions ============
+// These versions use spawn_blocking to avoid blocking the Tauri main thread.
+
+/// List all worktrees for a repository (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn list_worktrees_async(repo_path: String) -> Result<Vec<WorktreeInfo>, String> {
+    tokio::task::spawn_blocking(move || list_worktrees(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Create a new worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn create_worktree_async(
+    repo_path: String,
+    name: String,
+    options: CreateWorktreeOptions,
+) -> Result<WorktreeInfo, String> {
+    tokio::task::spawn_blocking(move || create_worktree(&repo_path, &name, &options))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Remove a worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn remove_worktree_async(
+    path: String,
+    force: bool,
+    delete_branch: bool,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || remove_worktree(&path, force, delete_branch))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Rename a worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn rename_worktree_async(
+    old_path: String,
+    new_name: String,
+    stop_processes: bool,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    tokio::task::spawn_blocking(move || rename_worktree(&old_path, &new_name, stop_processes))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Move a worktree to another path (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn move_worktree_async(
+    old_path: String,
+    new_path: String,
+    stop_processes: bool,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    tokio::task::spawn_blocking(move || move_worktree(&old_path, &new_path, stop_processes))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Duplicate a worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn duplicate_worktree_async(
+    path: String,
+    new_name: String,
+) -> Result<WorktreeInfo, String> {
+    tokio::task::spawn_blocking(move || duplicate_worktree(&path, &new_name))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Check out a ref in a worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn checkout_in_worktree_async(
+    path: String,
+    git_ref: String,
+) -> Result<WorktreeInfo, String> {
+    tokio::task::spawn_blocking(move || checkout_in_worktree(&path, &git_ref))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get a worktree's status (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_worktree_status_async(path: String) -> Result<WorktreeStatus, String> {
+    tokio::task::spawn_blocking(move || get_worktree_status(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get branches (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_branches_async(repo_path: String) -> Result<Vec<BranchInfo>, String> {
+    tokio::task::spawn_blocking(move || get_branches(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get a page of branches (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_branch_page_async(
+    repo_path: String,
+    prefix: Option<String>,
+    sort: BranchSort,
+    include_remote: bool,
+    offset: usize,
+    limit: usize,
+) -> Result<BranchPage, String> {
+    tokio::task::spawn_blocking(move || {
+        get_branch_page(
+            &repo_path,
+            prefix.as_deref(),
+            sort,
+            include_remote,
+            offset,
+            limit,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get commits (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_commits_async(
+    repo_path: String,
+    limit: usize,
+    skip: usize,
+    before_hash: Option<String>,
+) -> Result<Vec<CommitInfo>, String> {
+    tokio::task::spawn_blocking(move || {
+        super::prefetch::cached_commit_page(&repo_path, limit, skip, before_hash.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Get the merge base of two refs (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn get_merge_base_async(
+    repo_path: String,
+    ref_a: String,
+    ref_b: String,
+) -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(move || get_merge_base(&repo_path, &ref_a, &ref_b))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Check whether one ref is an ancestor of another (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn is_ancestor_async(
+    repo_path: String,
+    ancestor: String,
+    descendant: String,
+) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || is_ancestor(&repo_path, &ancestor, &descendant))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Create worktree at a specific path (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+#[allow(dead_code)]
+pub async fn create_worktree_at_path_async(
+    repo_path: String,
+    destination_path: String,
+    branch_or_commit: Option<String>,
+) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        create_worktree_at_path(&repo_path, &destination_path, branch_or_commit.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Rebase a worktree onto `onto` (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn rebase_worktree_async(path: String, onto: String) -> Result<RebaseResult, String> {
+    tokio::task::spawn_blocking(move || rebase_worktree(&path, &onto))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Remove stale git lock files (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn force_remove_stale_lock_async(repo_path: String) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || force_remove_stale_lock(&repo_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Wait for a worktree to be unlocked (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn wait_for_unlock_async(path: String, timeout: Duration) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || wait_for_unlock(&path, timeout))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Continue a rebase (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn continue_rebase_async(path: String) -> Result<RebaseResult, String> {
+    tokio::task::spawn_blocking(move || continue_rebase(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,232 @@
+//! Read-ahead of repository data, governed by each repository's
+//! `RepoScanSettings`.
+//!
+//! The prefetcher reads the first `history_depth` commits of HEAD into a
+//! per-repository history cache, from which `get_commits` serves its pages.
+//! Entries are keyed by the git dir stamp of `list_cache`, so a commit or
+//! checkout in any worktree drops them. A branch created without moving a HEAD
+//! shows up in the cached decorations after the next HEAD move or deep scan.
+//!
+//! `add_repository` picks the settings (lighter ones for large repositories,
+//! judged by `git count-objects`) and prefetches in the background; at startup
+//! every available repository is prefetched once. `deep_scan_repository` reads
+//! `DEEP_SCAN_HISTORY_DEPTH` commits and every worktree's status, whatever the
+//! settings say.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::core::path_key;
+
+use super::list_cache::{git_dir_stamp, GitDirStamp};
+use super::operations::{get_commit_page, get_worktree_status, run_git_command, MAX_COMMIT_PAGE};
+use super::store::AppState;
+use super::types::{CommitInfo, DeepScanResult, RepoScanSettings};
+
+/// Repositories with more git objects than this (loose and packed, in KiB)
+/// get `large_repo_scan_settings` when added.
+pub const LARGE_REPO_THRESHOLD_KIB: u64 = 1024 * 1024;
+
+/// History depth for large repositories.
+pub const LARGE_REPO_HISTORY_DEPTH: usize = 50;
+
+/// Commits read by a deep scan.
+pub const DEEP_SCAN_HISTORY_DEPTH: usize = 5000;
+
+struct CachedHistory {
+    stamp: GitDirStamp,
+    commits: Vec<CommitInfo>,
+    /// HEAD has no commits beyond `commits`
+    complete: bool,
+}
+
+static HISTORY_CACHE: OnceLock<Mutex<HashMap<String, CachedHistory>>> = OnceLock::new();
+
+fn history_cache() -> &'static Mutex<HashMap<String, CachedHistory>> {
+    HISTORY_CACHE.get_or_init(Default::default)
+}
+
+/// Settings for a repository of `size_kib` (`None` when unknown).
+pub fn scan_settings_for_size(size_kib: Option<u64>) -> RepoScanSettings {
+    match size_kib {
+        Some(size) if size > LARGE_REPO_THRESHOLD_KIB => RepoScanSettings {
+            history_depth: LARGE_REPO_HISTORY_DEPTH,
+            eager_status: false,
+        },
+        _ => RepoScanSettings::default(),
+    }
+}
+
+/// Total of `size` and `size-pack` in `git count-objects -v` output.
+pub fn parse_object_size_kib(output: &str) -> Option<u64> {
+    let sizes: Vec<u64> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            matches!(key.trim(), "size" | "size-pack")
+                .then(|| value.trim().parse().ok())
+                .flatten()
+        })
+        .collect();
+    (!sizes.is_empty()).then(|| sizes.iter().sum())
+}
+
+/// Size of the repository's git objects in KiB.
+pub fn repository_size_kib(repo_path: &str) -> Option<u64> {
+    let output = run_git_command(&["count-objects", "-v"], repo_path).ok()?;
+    parse_object_size_kib(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Settings for a newly added repository, judged by its size.
+pub fn default_scan_settings(repo_path: &str) -> RepoScanSettings {
+    scan_settings_for_size(repository_size_kib(repo_path))
+}
+
+/// Read the first `depth` commits of HEAD into the history cache and return
+/// how many were cached. A depth of 0 drops the cache entry.
+pub fn prefetch_history(repo_path: &str, depth: usize) -> Result<usize, String> {
+    if depth == 0 {
+        invalidate_history_cache(repo_path);
+        return Ok(0);
+    }
+    let Some(stamp) = git_dir_stamp(Path::new(repo_path)) else {
+        return Ok(0);
+    };
+
+    let mut commits: Vec<CommitInfo> = Vec::new();
+    let mut complete = false;
+    while commits.len() < depth {
+        let limit = (depth - commits.len()).min(MAX_COMMIT_PAGE);
+        let page = get_commit_page(repo_path, limit, commits.len(), None)?;
+        let short = page.len() < limit;
+        commits.extend(page);
+        if short {
+            complete = true;
+            break;
+        }
+    }
+
+    let count = commits.len();
+    history_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            path_key(repo_path),
+            CachedHistory {
+                stamp,
+                commits,
+                complete,
+            },
+        );
+    Ok(count)
+}
+
+/// The cached commits `skip..skip + limit` of HEAD, if the cache holds them
+/// and the repository has not changed since.
+fn cached_page(repo_path: &str, limit: usize, skip: usize) -> Option<Vec<CommitInfo>> {
+    let stamp = git_dir_stamp(Path::new(repo_path))?;
+    let mut cache = history_cache().lock().unwrap_or_else(|e| e.into_inner());
+    let key = path_key(repo_path);
+    let cached = cache.get(&key)?;
+
+    if cached.stamp != stamp {
+        cache.remove(&key);
+        return None;
+    }
+    let end = skip + limit;
+    if end > cached.commits.len() && !cached.complete {
+        return None;
+    }
+    let end = end.min(cached.commits.len());
+    Some(cached.commits.get(skip..end).unwrap_or_default().to_vec())
+}
+
+/// `get_commit_page`, served from the history cache when it holds the page.
+/// Pages continuing below `before_hash` always read the log.
+pub fn cached_commit_page(
+    repo_path: &str,
+    limit: usize,
+    skip: usize,
+    before_hash: Option<&str>,
+) -> Result<Vec<CommitInfo>, String> {
+    if before_hash.is_none() {
+        if let Some(page) = cached_page(repo_path, limit.min(MAX_COMMIT_PAGE), skip) {
+            return Ok(page);
+        }
+    }
+    get_commit_page(repo_path, limit, skip, before_hash)
+}
+
+/// Drop the cached history of `repo_path`.
+pub fn invalidate_history_cache(repo_path: &str) {
+    history_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&path_key(repo_path));
+}
+
+/// Prefetch the given repositories one after another on a background thread.
+pub fn spawn_prefetcher(repositories: Vec<(String, RepoScanSettings)>) {
+    if repositories.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for (path, settings) in repositories {
+            if let Err(e) = prefetch_history(&path, settings.history_depth) {
+                eprintln!("[prefetch] Failed to prefetch {}: {}", path, e);
+            }
+        }
+    });
+}
+
+/// Prefetch every available repository of the store in the background.
+pub fn spawn_startup_prefetch(app_state: &AppState) {
+    let repositories = match app_state.store.read() {
+        Ok(store) => store
+            .repositories
+            .iter()
+            .filter(|r| r.unavailable_since.is_none())
+            .map(|r| (r.path.clone(), r.scan_settings.clone()))
+            .collect(),
+        Err(_) => return,
+    };
+    spawn_prefetcher(repositories);
+}
+
+/// Read `DEEP_SCAN_HISTORY_DEPTH` commits into the history cache and the
+/// status of every worktree in `worktree_paths`, regardless of the settings.
+pub fn deep_scan_repository(
+    repo_path: &str,
+    worktree_paths: &[String],
+) -> Result<DeepScanResult, String> {
+    let commits_cached = prefetch_history(repo_path, DEEP_SCAN_HISTORY_DEPTH)?;
+
+    let mut statuses = HashMap::new();
+    for path in worktree_paths {
+        match get_worktree_status(path) {
+            Ok(status) => {
+                statuses.insert(path.clone(), status);
+            }
+            Err(e) => eprintln!("[prefetch] Failed to get status of {}: {}", path, e),
+        }
+    }
+    let dirty_worktrees = statuses.values().filter(|s| s.is_dirty).count();
+
+    Ok(DeepScanResult {
+        commits_cached,
+        statuses,
+        dirty_worktrees,
+    })
+}
+
+/// Deep-scan a repository (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn deep_scan_repository_async(
+    repo_path: String,
+    worktree_paths: Vec<String>,
+) -> Result<DeepScanResult, String> {
+    tokio::task::spawn_blocking(move || deep_scan_repository(&repo_path, &worktree_paths))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,232 @@
+//! Processes running inside a worktree.
+//!
+//! Removing a worktree breaks whatever runs in it: a dev server, a shell, a
+//! file watcher. `get_processes_in_worktree` lists the processes whose working
+//! directory is inside the worktree, with the TCP ports they listen on, so
+//! they can be shown before removal. `terminate_worktree_processes` stops them
+//! (SIGTERM, or SIGKILL with `force`), and `remove_worktree` does so first with
+//! `terminate_processes: true`. Processes are found with `lsof`, else from
+//! `/proc` on Linux. The app's own process is never listed.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::dev_server::{parse_listening_ports, run_lsof};
+
+/// How long `terminate_and_wait` waits for processes to exit.
+pub const TERMINATE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A process whose working directory is inside a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeProcess {
+    pub pid: u32,
+    /// Command name, e.g. "node" or "zsh"
+    pub command: String,
+    pub cwd: String,
+    /// TCP ports it listens on
+    pub ports: Vec<u16>,
+}
+
+/// `(pid, command, cwd)` per process from `lsof -d cwd -F pcn` output: `p`
+/// lines start a process, `c` is its command and `n` its working directory.
+pub fn parse_process_list(output: &str) -> Vec<(u32, String, PathBuf)> {
+    let mut processes = Vec::new();
+    let mut current: Option<(u32, String)> = None;
+    for line in output.lines() {
+        if let Some(pid) = line.strip_prefix('p') {
+            current = pid.parse().ok().map(|pid| (pid, String::new()));
+        } else if let (Some(command), Some((_, current))) =
+            (line.strip_prefix('c'), current.as_mut())
+        {
+            *current = command.to_string();
+        } else if let (Some(cwd), Some((pid, command))) = (line.strip_prefix('n'), &current) {
+            processes.push((*pid, command.clone(), PathBuf::from(cwd)));
+        }
+    }
+    processes
+}
+
+/// The processes running inside `worktree_path`, other than `own_pid`, with
+/// their ports from `listening`. Sorted by pid.
+pub fn processes_in_worktree(
+    processes: &[(u32, String, PathBuf)],
+    listening: &[(u32, u16)],
+    worktree_path: &Path,
+    own_pid: u32,
+) -> Vec<WorktreeProcess> {
+    let mut found: Vec<WorktreeProcess> = processes
+        .iter()
+        .filter(|(pid, _, cwd)| *pid != own_pid && cwd.starts_with(worktree_path))
+        .map(|(pid, command, cwd)| {
+            let mut ports: Vec<u16> = listening
+                .iter()
+                .filter(|(p, _)| p == pid)
+                .map(|&(_, port)| port)
+                .collect();
+            ports.sort_unstable();
+            ports.dedup();
+            WorktreeProcess {
+                pid: *pid,
+                command: command.clone(),
+                cwd: cwd.to_string_lossy().to_string(),
+                ports,
+            }
+        })
+        .collect();
+    found.sort_by_key(|p| p.pid);
+    found.dedup_by_key(|p| p.pid);
+    found
+}
+
+#[cfg(target_os = "linux")]
+fn list_proc_cwds() -> Option<Vec<(u32, String, PathBuf)>> {
+    let entries = std::fs::read_dir("/proc").ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter_map(|entry| {
+                let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+                let cwd = std::fs::read_link(entry.path().join("cwd")).ok()?;
+                let command = std::fs::read_to_string(entry.path().join("comm"))
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_default();
+                Some((pid, command, cwd))
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn list_proc_cwds() -> Option<Vec<(u32, String, PathBuf)>> {
+    None
+}
+
+/// `(pid, command, cwd)` of every process that can be inspected.
+fn list_process_cwds() -> Result<Vec<(u32, String, PathBuf)>, String> {
+    if let Some(output) = run_lsof(&["-nP", "-d", "cwd", "-F", "pcn"]) {
+        return Ok(parse_process_list(&output));
+    }
+    list_proc_cwds().ok_or_else(|| "Can't list processes: lsof is not installed".to_string())
+}
+
+/// The processes running inside a worktree.
+pub fn get_processes_in_worktree(worktree_path: &str) -> Result<Vec<WorktreeProcess>, String> {
+    let worktree = Path::new(worktree_path);
+    let worktree = worktree
+        .canonicalize()
+        .unwrap_or_else(|_| worktree.to_path_buf());
+
+    let processes = list_process_cwds()?;
+    let listening = run_lsof(&["-nP", "-iTCP", "-sTCP:LISTEN", "-F", "pn"])
+        .map(|output| parse_listening_ports(&output))
+        .unwrap_or_default();
+    Ok(processes_in_worktree(
+        &processes,
+        &listening,
+        &worktree,
+        std::process::id(),
+    ))
+}
+
+fn signal_process(pid: u32, force: bool) -> bool {
+    let pid = pid.to_string();
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("kill");
+        command.args([if force { "-KILL" } else { "-TERM" }, &pid]);
+        command
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("taskkill");
+        command.args(["/PID", &pid]);
+        if force {
+            command.arg("/F");
+        }
+        command
+    };
+    command
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Signal the processes running inside a worktree, only those in `pids` when
+/// given (others are ignored, so a stale list can't hit unrelated processes).
+/// Returns the pids signaled.
+pub fn terminate_worktree_processes(
+    worktree_path: &str,
+    pids: Option<&[u32]>,
+    force: bool,
+) -> Result<Vec<u32>, String> {
+    let signaled: Vec<u32> = get_processes_in_worktree(worktree_path)?
+        .into_iter()
+        .map(|p| p.pid)
+        .filter(|pid| pids.is_none_or(|pids| pids.contains(pid)))
+        .filter(|&pid| signal_process(pid, force))
+        .collect();
+    if !signaled.is_empty() {
+        println!(
+            "[processes] Sent {} to {} process(es) in {}",
+            if force { "SIGKILL" } else { "SIGTERM" },
+            signaled.len(),
+            worktree_path
+        );
+    }
+    Ok(signaled)
+}
+
+/// Terminate every process inside a worktree and wait up to `timeout` for
+/// them to exit. Returns the processes still running.
+pub fn terminate_and_wait(
+    worktree_path: &str,
+    timeout: Duration,
+) -> Result<Vec<WorktreeProcess>, String> {
+    if terminate_worktree_processes(worktree_path, None, false)?.is_empty() {
+        return get_processes_in_worktree(worktree_path);
+    }
+    let started = Instant::now();
+    loop {
+        let remaining = get_processes_in_worktree(worktree_path)?;
+        if remaining.is_empty() || started.elapsed() >= timeout {
+            return Ok(remaining);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+// ============ Async Versions ============
+
+pub async fn get_processes_in_worktree_async(
+    worktree_path: String,
+) -> Result<Vec<WorktreeProcess>, String> {
+    tokio::task::spawn_blocking(move || get_processes_in_worktree(&worktree_path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+pub async fn terminate_worktree_processes_async(
+    worktree_path: String,
+    pids: Option<Vec<u32>>,
+    force: bool,
+) -> Result<Vec<u32>, String> {
+    tokio::task::spawn_blocking(move || {
+        terminate_worktree_processes(&worktree_path, pids.as_deref(), force)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+pub async fn terminate_and_wait_async(
+    worktree_path: String,
+    timeout: Duration,
+) -> Result<Vec<WorktreeProcess>, String> {
+    tokio::task::spawn_blocking(move || terminate_and_wait(&worktree_path, timeout))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
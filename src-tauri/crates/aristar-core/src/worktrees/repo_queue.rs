@@ -0,0 +1,165 @@
+//! Per-repository queue for worktree-mutating git operations.
+//!
+//! Worktrees of a repository share one `.git` folder, so two `git worktree add`
+//! (or `remove`/`move`) runs at the same time can fail on `index.lock` or the
+//! `worktrees/` admin folder, e.g. when two tasks create agent worktrees at once.
+//! Such operations go through `run_queued`, which runs them one at a time per
+//! repository, first come first served. Operations on different repositories
+//! don't wait for each other.
+//!
+//! An operation that has to wait is reported to the queue listener (set at
+//! startup to emit `repo-operation-queued`), and `get_repo_queue_status` shows what
+//! is running and waiting.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Condvar, Mutex, OnceLock};
+
+pub use crate::core::events::REPO_OPERATION_QUEUED_EVENT;
+
+/// Payload of `repo-operation-queued`.
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoOperationQueued {
+    pub repo_path: String,
+    pub operation: String,
+    /// 1 when next in line
+    pub position: usize,
+    /// Operation currently running on the repository
+    pub running: Option<String>,
+}
+
+/// What is running and waiting on one repository.
+#[derive(Debug, Clone, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoQueueStatus {
+    pub repo_path: String,
+    pub running: Option<String>,
+    pub waiting: Vec<String>,
+}
+
+#[derive(Default)]
+struct RepoQueue {
+    running: Option<String>,
+    waiting: VecDeque<(u64, String)>,
+}
+
+#[derive(Default)]
+struct Queues {
+    repos: Mutex<HashMap<String, RepoQueue>>,
+    changed: Condvar,
+}
+
+type QueueListener = Box<dyn Fn(RepoOperationQueued) + Send + Sync>;
+
+static QUEUES: OnceLock<Queues> = OnceLock::new();
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(0);
+static LISTENER: OnceLock<QueueListener> = OnceLock::new();
+
+fn queues() -> &'static Queues {
+    QUEUES.get_or_init(Queues::default)
+}
+
+/// Queue key: the canonical repository path, so differently spelled paths
+/// share a queue.
+fn queue_key(repo_path: &str) -> String {
+    Path::new(repo_path)
+        .canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| repo_path.to_string())
+}
+
+/// Set the function told about operations that have to wait. Only the first
+/// call has an effect.
+pub fn set_queue_listener(listener: impl Fn(RepoOperationQueued) + Send + Sync + 'static) {
+    let _ = LISTENER.set(Box::new(listener));
+}
+
+/// Releases the repository when the operation ends, also on panic.
+struct RunningGuard {
+    key: String,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        let queues = queues();
+        let mut repos = queues.repos.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(queue) = repos.get_mut(&self.key) {
+            queue.running = None;
+            if queue.waiting.is_empty() {
+                repos.remove(&self.key);
+            }
+        }
+        queues.changed.notify_all();
+    }
+}
+
+/// Run `f` once no other queued operation is running on `repo_path`.
+/// `operation` describes it in queue feedback, e.g. "create worktree feature-x".
+pub fn run_queued<T>(repo_path: &str, operation: &str, f: impl FnOnce() -> T) -> T {
+    let queues = queues();
+    let key = queue_key(repo_path);
+    let ticket = NEXT_TICKET.fetch_add(1, Ordering::SeqCst);
+
+    let queued = {
+        let mut repos = queues.repos.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = repos.entry(key.clone()).or_default();
+        queue.waiting.push_back((ticket, operation.to_string()));
+        (queue.running.is_some() || queue.waiting.len() > 1).then(|| RepoOperationQueued {
+            repo_path: key.clone(),
+            operation: operation.to_string(),
+            position: queue.waiting.len(),
+            running: queue.running.clone(),
+        })
+    };
+
+    // Told outside the lock, so the listener may look at the queues
+    if let Some(event) = queued {
+        println!(
+            "[repo_queue] '{}' waits for '{}' in {}",
+            operation,
+            event.running.as_deref().unwrap_or("queued operations"),
+            key
+        );
+        if let Some(listener) = LISTENER.get() {
+            listener(event);
+        }
+    }
+
+    let mut repos = queues.repos.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        let queue = repos.entry(key.clone()).or_default();
+        let is_next = queue.waiting.front().is_some_and(|(t, _)| *t == ticket);
+        if queue.running.is_none() && is_next {
+            let (_, operation) = queue.waiting.pop_front().unwrap_or_default();
+            queue.running = Some(operation);
+            break;
+        }
+        repos = queues
+            .changed
+            .wait(repos)
+            .unwrap_or_else(|e| e.into_inner());
+    }
+    drop(repos);
+
+    let _guard = RunningGuard { key };
+    f()
+}
+
+/// Repositories with a running or waiting operation.
+pub fn get_queue_status() -> Vec<RepoQueueStatus> {
+    let repos = queues().repos.lock().unwrap_or_else(|e| e.into_inner());
+    let mut status: Vec<RepoQueueStatus> = repos
+        .iter()
+        .map(|(repo_path, queue)| RepoQueueStatus {
+            repo_path: repo_path.clone(),
+            running: queue.running.clone(),
+            waiting: queue.waiting.iter().map(|(_, op)| op.clone()).collect(),
+        })
+        .collect();
+    status.sort_by(|a, b| a.repo_path.cmp(&b.repo_path));
+    status
+}
@@ -0,0 +1,255 @@
+//! Dependency folders shared with the main worktree.
+//!
+//! Each new worktree would otherwise need its own `npm install`, `cargo build`
+//! or virtualenv. With `settings.share_dependencies` set (read at startup),
+//! new worktrees and agent worktrees get the heavy folders of the main
+//! worktree (`settings.shared_dependency_dirs`, default `node_modules`,
+//! `target` and `.venv`) as a symlink, or as a tree of hard links:
+//!
+//! - `symlink`: one link, builds write into the main worktree's folder.
+//!   Falls back to hard links where symlinks can't be created (e.g. Windows
+//!   without developer mode).
+//! - `hardlink`: the worktree gets its own folders whose files are hard links,
+//!   so adding or deleting files doesn't touch the main worktree (files
+//!   rewritten in place do). Skipped across filesystems, where the folder is
+//!   left for the usual install.
+//!
+//! Folders already in the worktree are kept. A shared folder not ignored by
+//! git (`.gitignore`'s `node_modules/` doesn't match a symlink) is added to
+//! `info/exclude`. Symlinks are removed before the worktree is, so removal
+//! never reaches into the main worktree.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+
+use crate::core::SharedDepsMode;
+
+use super::operations::{ensure_excluded, run_git_command};
+
+/// Folders shared when the settings don't list any.
+pub const DEFAULT_SHARED_DIRS: [&str; 3] = ["node_modules", "target", ".venv"];
+
+/// A folder shared into a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedDir {
+    /// Relative to the worktree, e.g. "node_modules"
+    pub path: String,
+    /// How it was shared (after any fallback)
+    pub mode: SharedDepsMode,
+}
+
+struct SharedDepsSettings {
+    mode: Option<SharedDepsMode>,
+    dirs: Vec<String>,
+}
+
+static SETTINGS: RwLock<SharedDepsSettings> = RwLock::new(SharedDepsSettings {
+    mode: None,
+    dirs: Vec::new(),
+});
+
+/// Set how new worktrees share dependency folders (`None` disables it) and
+/// which folders (empty uses `DEFAULT_SHARED_DIRS`).
+pub fn set_shared_dependencies(mode: Option<SharedDepsMode>, dirs: Vec<String>) {
+    *SETTINGS.write().unwrap_or_else(|e| e.into_inner()) = SharedDepsSettings { mode, dirs };
+}
+
+/// How new worktrees share dependency folders, if at all.
+pub fn shared_dependencies_mode() -> Option<SharedDepsMode> {
+    SETTINGS.read().unwrap_or_else(|e| e.into_inner()).mode
+}
+
+/// The folders shared into worktrees.
+pub fn shared_dirs() -> Vec<String> {
+    let settings = SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    if settings.dirs.is_empty() {
+        DEFAULT_SHARED_DIRS.iter().map(|d| d.to_string()).collect()
+    } else {
+        settings.dirs.clone()
+    }
+}
+
+/// A shared folder as a relative path, or `None` when it could reach outside
+/// the worktree.
+pub fn validate_shared_dir(dir: &str) -> Option<PathBuf> {
+    let dir = dir.trim().trim_end_matches('/');
+    let path = PathBuf::from(dir);
+    let valid = !dir.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+    valid.then_some(path)
+}
+
+#[cfg(unix)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+#[cfg(windows)]
+fn symlink_dir(source: &Path, target: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(source, target)
+}
+
+/// Recreate `source` at `target` with every file hard-linked. Symlinks inside
+/// (e.g. `node_modules/.bin`) are recreated as symlinks. Returns the number of
+/// files linked.
+pub fn hardlink_tree(source: &Path, target: &Path) -> Result<usize, String> {
+    fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+    let entries =
+        fs::read_dir(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    let mut linked = 0;
+    for entry in entries.flatten() {
+        let from = entry.path();
+        let to = target.join(entry.file_name());
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            #[cfg(unix)]
+            {
+                let link = fs::read_link(&from)
+                    .map_err(|e| format!("Failed to read link {}: {}", from.display(), e))?;
+                std::os::unix::fs::symlink(&link, &to)
+                    .map_err(|e| format!("Failed to link {}: {}", to.display(), e))?;
+            }
+        } else if file_type.is_dir() {
+            linked += hardlink_tree(&from, &to)?;
+        } else {
+            fs::hard_link(&from, &to)
+                .map_err(|e| format!("Failed to hard-link {}: {}", from.display(), e))?;
+            linked += 1;
+        }
+    }
+    Ok(linked)
+}
+
+/// Hard-link `from` to `to`, removing a partial tree on failure.
+fn share_by_hardlinks(from: &Path, to: &Path) -> Result<(), String> {
+    hardlink_tree(from, to).map(|_| ()).inspect_err(|_| {
+        let _ = fs::remove_dir_all(to);
+    })
+}
+
+/// Share the folders `dirs` of `source_path` into `worktree_path`. Folders
+/// missing in the source or present in the worktree are skipped, as are those
+/// that can't be shared (logged). Returns the folders shared.
+pub fn share_dependency_dirs(
+    source_path: &str,
+    worktree_path: &str,
+    dirs: &[String],
+    mode: SharedDepsMode,
+) -> Result<Vec<SharedDir>, String> {
+    let mut shared = Vec::new();
+    for dir in dirs {
+        let Some(relative) = validate_shared_dir(dir) else {
+            eprintln!("[shared-deps] Ignoring folder {:?}", dir);
+            continue;
+        };
+        let from = Path::new(source_path).join(&relative);
+        let to = Path::new(worktree_path).join(&relative);
+        let is_dir = fs::symlink_metadata(&from)
+            .map(|m| m.is_dir())
+            .unwrap_or(false);
+        if !is_dir || to.symlink_metadata().is_ok() {
+            continue;
+        }
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let result = match mode {
+            SharedDepsMode::Symlink => match symlink_dir(&from, &to) {
+                Ok(()) => Ok(SharedDepsMode::Symlink),
+                Err(e) => {
+                    eprintln!(
+                        "[shared-deps] Can't symlink {} ({}), using hard links",
+                        relative.display(),
+                        e
+                    );
+                    share_by_hardlinks(&from, &to).map(|_| SharedDepsMode::Hardlink)
+                }
+            },
+            SharedDepsMode::Hardlink => {
+                share_by_hardlinks(&from, &to).map(|_| SharedDepsMode::Hardlink)
+            }
+        };
+        let mode = match result {
+            Ok(mode) => mode,
+            Err(e) => {
+                eprintln!("[shared-deps] Not sharing {}: {}", relative.display(), e);
+                continue;
+            }
+        };
+
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let relative = relative.trim_start_matches("./").to_string();
+        let ignored =
+            run_git_command(&["check-ignore", "-q", "--", &relative], worktree_path).is_ok();
+        if !ignored {
+            ensure_excluded(Path::new(worktree_path), &format!("/{}", relative))?;
+        }
+        shared.push(SharedDir {
+            path: relative,
+            mode,
+        });
+    }
+    Ok(shared)
+}
+
+/// Remove the symlinks among the folders `dirs` of `worktree_path`, leaving
+/// their targets alone. Returns the number removed.
+pub fn unshare_dependency_dirs(worktree_path: &str, dirs: &[String]) -> usize {
+    dirs.iter()
+        .filter_map(|dir| validate_shared_dir(dir))
+        .map(|relative| Path::new(worktree_path).join(relative))
+        .filter(|path| {
+            fs::symlink_metadata(path)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false)
+        })
+        // Directory symlinks are directories to remove_dir on Windows
+        .filter(|path| {
+            fs::remove_file(path)
+                .or_else(|_| fs::remove_dir(path))
+                .is_ok()
+        })
+        .count()
+}
+
+/// Share dependency folders into a newly created worktree when enabled.
+/// Failures are logged, never fatal.
+pub fn setup_worktree_shared_deps(source_path: &str, worktree_path: &str) {
+    let Some(mode) = shared_dependencies_mode() else {
+        return;
+    };
+    match share_dependency_dirs(source_path, worktree_path, &shared_dirs(), mode) {
+        Ok(shared) if shared.is_empty() => {}
+        Ok(shared) => println!(
+            "[shared-deps] Shared {} folder(s) into {}",
+            shared.len(),
+            worktree_path
+        ),
+        Err(e) => eprintln!("[shared-deps] {}", e),
+    }
+}
+
+pub async fn share_dependency_dirs_async(
+    source_path: String,
+    worktree_path: String,
+    mode: SharedDepsMode,
+) -> Result<Vec<SharedDir>, String> {
+    tokio::task::spawn_blocking(move || {
+        share_dependency_dirs(&source_path, &worktree_path, &shared_dirs(), mode)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
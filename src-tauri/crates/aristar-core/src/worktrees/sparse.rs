@@ -0,0 +1,62 @@
+//! Sparse (lightweight) worktrees.
+//!
+//! Worktrees share the repository's object store, so partial clones and shallow
+//! history buy nothing here; what costs time and disk on huge repositories is
+//! checking out every file into every worktree. A sparse worktree is added with
+//! `--no-checkout`, restricted to a list of directories with cone-mode
+//! `git sparse-checkout` and only then checked out, so files outside those
+//! directories are never written. Files at the repository root are always
+//! included (a property of cone mode).
+
+use std::path::{Component, Path};
+
+use super::operations::run_git_command;
+
+/// Most directories a sparse worktree may be restricted to.
+pub const MAX_SPARSE_PATHS: usize = 100;
+
+/// Normalize and check the directories of a sparse checkout: trims entries,
+/// drops empty ones and trailing slashes, and rejects absolute paths and `..`.
+pub fn normalize_sparse_paths(paths: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for path in paths {
+        let path = path.trim().trim_end_matches('/');
+        if path.is_empty() {
+            continue;
+        }
+        let valid = Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+        if !valid {
+            return Err(format!(
+                "Sparse path must be a folder inside the repository: {}",
+                path
+            ));
+        }
+        if !normalized.iter().any(|p| p == path) {
+            normalized.push(path.to_string());
+        }
+    }
+
+    if normalized.len() > MAX_SPARSE_PATHS {
+        return Err(format!(
+            "At most {} sparse paths are supported",
+            MAX_SPARSE_PATHS
+        ));
+    }
+    Ok(normalized)
+}
+
+/// Restrict a worktree added with `--no-checkout` to `paths` and check it out.
+pub fn checkout_sparse(worktree_path: &str, paths: &[String]) -> Result<(), String> {
+    let mut args = vec!["sparse-checkout", "set", "--cone", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(&args, worktree_path)
+        .map_err(|e| format!("Failed to set sparse checkout: {}", e.trim()))?;
+
+    // The index of a --no-checkout worktree is empty; populate it and the
+    // files inside the cone
+    run_git_command(&["reset", "--hard", "-q"], worktree_path)
+        .map_err(|e| format!("Failed to check out sparse worktree: {}", e.trim()))?;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! Staging and committing in a worktree.
+//!
+//! Lets the user review an agent's changes and commit them without leaving the
+//! app. Files are passed to git after `--`, so names starting with `-` are never
+//! read as options; paths outside the worktree are rejected by git itself.
+//! An empty file list means every change.
+
+use std::path::Path;
+
+use super::operations::{get_commits, get_worktree_status, run_git_command};
+use super::types::{CommitInfo, WorktreeStatus};
+
+fn ensure_worktree(path: &str) -> Result<(), String> {
+    if !Path::new(path).is_dir() {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    Ok(())
+}
+
+fn git_error(action: &str, e: String) -> String {
+    format!("Failed to {}: {}", action, e.trim())
+}
+
+/// Whether HEAD points to a commit (false on a branch with no commits yet).
+fn has_head_commit(path: &str) -> bool {
+    run_git_command(&["rev-parse", "--verify", "--quiet", "HEAD"], path).is_ok()
+}
+
+/// Stage `files` (new, modified and deleted), or every change when empty.
+/// Returns the worktree status afterwards.
+pub fn stage_files(path: &str, files: &[String]) -> Result<WorktreeStatus, String> {
+    ensure_worktree(path)?;
+
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(files.iter().map(String::as_str));
+    run_git_command(&args, path).map_err(|e| git_error("stage files", e))?;
+
+    get_worktree_status(path)
+}
+
+/// Unstage `files`, or everything when empty, keeping the changes in the
+/// working tree. Returns the worktree status afterwards.
+pub fn unstage_files(path: &str, files: &[String]) -> Result<WorktreeStatus, String> {
+    ensure_worktree(path)?;
+
+    let all = [".".to_string()];
+    let files = if files.is_empty() { &all[..] } else { files };
+
+    // Without a commit there is nothing to reset to, so drop the entries instead
+    let mut args = if has_head_commit(path) {
+        vec!["reset", "-q", "HEAD", "--"]
+    } else {
+        vec!["rm", "-r", "-q", "--cached", "--ignore-unmatch", "--"]
+    };
+    args.extend(files.iter().map(String::as_str));
+    run_git_command(&args, path).map_err(|e| git_error("unstage files", e))?;
+
+    get_worktree_status(path)
+}
+
+/// Commit the staged changes with `message`; with `stage_all`, every change is
+/// staged first. Commit hooks run as usual. Returns the new commit.
+pub fn commit_changes(path: &str, message: &str, stage_all: bool) -> Result<CommitInfo, String> {
+    ensure_worktree(path)?;
+
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("Commit message cannot be empty".to_string());
+    }
+
+    let status = if stage_all {
+        stage_files(path, &[])?
+    } else {
+        get_worktree_status(path)?
+    };
+    if status.conflicted > 0 {
+        return Err(format!(
+            "Resolve {} conflicted file(s) before committing",
+            status.conflicted
+        ));
+    }
+    if status.staged == 0 {
+        return Err("Nothing staged to commit".to_string());
+    }
+
+    run_git_command(&["commit", "-q", "-m", message], path).map_err(|e| git_error("commit", e))?;
+    println!("[worktrees] Committed in {}", path);
+
+    get_commits(path, 1)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Commit not found after committing".to_string())
+}
+
+/// Stage files (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn stage_files_async(path: String, files: Vec<String>) -> Result<WorktreeStatus, String> {
+    tokio::task::spawn_blocking(move || stage_files(&path, &files))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Unstage files (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn unstage_files_async(
+    path: String,
+    files: Vec<String>,
+) -> Result<WorktreeStatus, String> {
+    tokio::task::spawn_blocking(move || unstage_files(&path, &files))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Commit staged changes (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn commit_changes_async(
+    path: String,
+    message: String,
+    stage_all: bool,
+) -> Result<CommitInfo, String> {
+    tokio::task::spawn_blocking(move || commit_changes(&path, &message, stage_all))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,263 @@
+//! Cleanup of stale worktrees.
+//!
+//! A worktree is stale when its `last_active` (see `activity`) is older than
+//! the policy's `max_idle_days`. Main worktrees, hidden worktrees (long-lived
+//! caches), worktrees of unavailable repositories and worktrees without any
+//! recorded activity are never stale. `cleanup_stale_worktrees` reports every
+//! stale worktree and, when asked to remove, removes those the policy allows:
+//! with `only_unlocked` locked ones are kept, with `only_clean` those with
+//! uncommitted changes are kept (otherwise they are removed with `--force`),
+//! and worktrees with processes running inside are always kept. Branches are
+//! never deleted. `StaleCleanupPolicy` lives in the app settings; with
+//! `scheduled` a background loop runs the cleanup once a day and emits
+//! `stale-worktrees`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Duration;
+
+use crate::core::journal::{self, JournalOperation};
+use crate::core::{is_read_only, ports, StaleCleanupPolicy};
+use crate::workspace::status::is_worktree_dirty;
+
+use super::aliases;
+use super::operations;
+use super::processes::get_processes_in_worktree;
+use super::store::AppState;
+use super::types::Repository;
+
+pub use crate::core::events::STALE_WORKTREES_EVENT;
+
+/// How often the background loop checks whether a cleanup is due.
+pub const STALE_CLEANUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Time between background cleanups (one day), in milliseconds.
+pub const STALE_CLEANUP_PERIOD_MS: i64 = 24 * 60 * 60 * 1000;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Last background cleanup (milliseconds since epoch), 0 before the first.
+static LAST_BACKGROUND_CLEANUP: AtomicI64 = AtomicI64::new(0);
+
+/// A worktree idle for longer than the policy allows.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleWorktree {
+    pub repository_id: String,
+    pub path: String,
+    pub branch: Option<String>,
+    pub is_locked: bool,
+    /// Last activity (milliseconds since epoch)
+    pub last_active: i64,
+    /// Whole days since the last activity
+    pub idle_days: u32,
+}
+
+/// What `cleanup_stale_worktrees` did with a stale worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum StaleOutcome {
+    /// Would be removed; nothing was removed (report only)
+    Removable,
+    Removed,
+    /// The policy or running processes keep it
+    Kept {
+        reason: String,
+    },
+    /// Removal was attempted and failed
+    Failed {
+        message: String,
+    },
+}
+
+/// A stale worktree and what happened to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleCleanupEntry {
+    pub worktree: StaleWorktree,
+    pub outcome: StaleOutcome,
+}
+
+/// Result of `cleanup_stale_worktrees`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleCleanupReport {
+    pub policy: StaleCleanupPolicy,
+    /// Whether removal was asked for
+    pub remove: bool,
+    /// When the check ran (milliseconds since epoch)
+    pub checked_at: i64,
+    pub entries: Vec<StaleCleanupEntry>,
+}
+
+impl StaleCleanupReport {
+    /// Number of worktrees removed.
+    pub fn removed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.outcome == StaleOutcome::Removed)
+            .count()
+    }
+}
+
+/// Check a policy before it is saved.
+pub fn validate_stale_cleanup_policy(policy: &StaleCleanupPolicy) -> Result<(), String> {
+    if policy.max_idle_days == 0 {
+        return Err("The idle threshold must be at least one day".to_string());
+    }
+    Ok(())
+}
+
+/// The worktrees of `repositories` idle for at least `max_idle_days` at
+/// `now`, oldest first.
+pub fn find_stale_worktrees(
+    repositories: &[Repository],
+    max_idle_days: u32,
+    now: i64,
+) -> Vec<StaleWorktree> {
+    let threshold = max_idle_days as i64 * DAY_MS;
+    let mut stale: Vec<StaleWorktree> = repositories
+        .iter()
+        .filter(|repo| repo.unavailable_since.is_none())
+        .flat_map(|repo| {
+            repo.worktrees
+                .iter()
+                .filter(|w| !w.is_main && !w.hidden)
+                .filter_map(move |w| {
+                    let last_active = w.last_active?;
+                    let idle = now - last_active;
+                    (idle >= threshold).then(|| StaleWorktree {
+                        repository_id: repo.id.clone(),
+                        path: w.path.clone(),
+                        branch: w.branch.clone(),
+                        is_locked: w.is_locked,
+                        last_active,
+                        idle_days: (idle / DAY_MS) as u32,
+                    })
+                })
+        })
+        .collect();
+    stale.sort_by_key(|w| w.last_active);
+    stale
+}
+
+/// Why the policy keeps `worktree`, if it does. `dirty` is only asked when
+/// the policy keeps worktrees with changes.
+pub fn policy_keep_reason(
+    worktree: &StaleWorktree,
+    policy: &StaleCleanupPolicy,
+    dirty: impl FnOnce(&str) -> bool,
+) -> Option<String> {
+    if policy.only_unlocked && worktree.is_locked {
+        return Some("Locked".to_string());
+    }
+    if policy.only_clean && dirty(&worktree.path) {
+        return Some("Has uncommitted changes".to_string());
+    }
+    None
+}
+
+fn keep_reason(worktree: &StaleWorktree, policy: &StaleCleanupPolicy) -> Option<String> {
+    if let Some(reason) = policy_keep_reason(worktree, policy, is_worktree_dirty) {
+        return Some(reason);
+    }
+    match get_processes_in_worktree(&worktree.path) {
+        Ok(processes) if !processes.is_empty() => {
+            Some(format!("{} process(es) running inside", processes.len()))
+        }
+        _ => None,
+    }
+}
+
+fn remove_stale_worktree(
+    state: &AppState,
+    worktree: &StaleWorktree,
+    force: bool,
+) -> Result<(), String> {
+    let _intent = journal::record_intent(JournalOperation::RemoveWorktree {
+        repo_path: operations::find_git_repo_root(&worktree.path)?,
+        worktree_path: worktree.path.clone(),
+    })?;
+    operations::remove_worktree(&worktree.path, force, false)?;
+    state.remove_worktree_entry(&worktree.path)?;
+    state.save()?;
+
+    if let Err(e) = aliases::remove_worktree_alias(&worktree.path) {
+        eprintln!("[stale] {}", e);
+    }
+    if let Err(e) = ports::release_ports(&worktree.path, None) {
+        eprintln!("[stale] Failed to release ports: {}", e);
+    }
+    Ok(())
+}
+
+/// Find the stale worktrees under `policy` and, with `remove`, remove those
+/// it allows. Without `remove` nothing is changed.
+pub fn cleanup_stale_worktrees(
+    state: &AppState,
+    policy: &StaleCleanupPolicy,
+    remove: bool,
+    now: i64,
+) -> Result<StaleCleanupReport, String> {
+    validate_stale_cleanup_policy(policy)?;
+    let stale = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        find_stale_worktrees(&store.repositories, policy.max_idle_days, now)
+    };
+
+    let mut entries = Vec::with_capacity(stale.len());
+    for worktree in stale {
+        let outcome = if let Some(reason) = keep_reason(&worktree, policy) {
+            StaleOutcome::Kept { reason }
+        } else if !remove {
+            StaleOutcome::Removable
+        } else {
+            match remove_stale_worktree(state, &worktree, !policy.only_clean) {
+                Ok(()) => {
+                    println!(
+                        "[stale] Removed {} (idle {} days)",
+                        worktree.path, worktree.idle_days
+                    );
+                    StaleOutcome::Removed
+                }
+                Err(message) => StaleOutcome::Failed { message },
+            }
+        };
+        entries.push(StaleCleanupEntry { worktree, outcome });
+    }
+
+    Ok(StaleCleanupReport {
+        policy: policy.clone(),
+        remove,
+        checked_at: now,
+        entries,
+    })
+}
+
+/// Run the scheduled cleanup if the stored policy asks for it and a day has
+/// passed since the last one. Returns the report of a run that found stale
+/// worktrees. The first check after startup runs it.
+pub fn run_scheduled_stale_cleanup(
+    state: &AppState,
+    now: i64,
+) -> Result<Option<StaleCleanupReport>, String> {
+    let policy = state
+        .store
+        .read()
+        .map_err(|e| e.to_string())?
+        .settings
+        .stale_cleanup
+        .clone();
+    if !policy.scheduled
+        || now - LAST_BACKGROUND_CLEANUP.load(Ordering::SeqCst) < STALE_CLEANUP_PERIOD_MS
+    {
+        return Ok(None);
+    }
+    LAST_BACKGROUND_CLEANUP.store(now, Ordering::SeqCst);
+
+    // Report only in read-only mode
+    let remove = policy.remove_in_background && !is_read_only();
+    let report = cleanup_stale_worktrees(state, &policy, remove, now)?;
+    Ok((!report.entries.is_empty()).then_some(report))
+}
@@ -0,0 +1,368 @@
+//! Worktree store state management.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::core::{
+    get_store_path, is_read_only, load_json_store, mark_store_changed, normalize_path, path_key,
+    save_json_store, stored_path_key,
+};
+
+use super::operations::detect_base_branch;
+use super::types::{Repository, StoreData, WorktreeInfo};
+
+/// Lookup maps from repository id/path to its position in `StoreData::repositories`.
+/// Paths are keyed by `path_key`, so differently-cased or non-canonical spellings
+/// of the same directory resolve to the same repository. Repository paths are
+/// already normalized, so building the index doesn't touch the filesystem.
+///
+/// Positions are verified on every lookup and the index is rebuilt when it is
+/// stale, so code that mutates `store.repositories` directly cannot cause a
+/// wrong match, only a slower one.
+#[derive(Debug, Default)]
+pub struct RepositoryIndex {
+    by_id: HashMap<String, usize>,
+    by_path: HashMap<String, usize>,
+}
+
+impl RepositoryIndex {
+    /// Build an index over a list of repositories.
+    pub fn build(repositories: &[Repository]) -> Self {
+        let mut index = Self::default();
+        for (idx, repo) in repositories.iter().enumerate() {
+            index.by_id.insert(repo.id.clone(), idx);
+            index
+                .by_path
+                .entry(stored_path_key(&repo.path))
+                .or_insert(idx);
+        }
+        index
+    }
+
+    /// Position of a repository by ID, if indexed.
+    pub fn position_by_id(&self, id: &str) -> Option<usize> {
+        self.by_id.get(id).copied()
+    }
+
+    /// Position of a repository by path, if indexed.
+    pub fn position_by_path(&self, path: &str) -> Option<usize> {
+        self.position_by_key(&path_key(path))
+    }
+
+    /// Position of a repository by `path_key`, if indexed.
+    fn position_by_key(&self, key: &str) -> Option<usize> {
+        self.by_path.get(key).copied()
+    }
+
+    /// Number of indexed repositories.
+    fn len(&self) -> usize {
+        self.by_id.len()
+    }
+}
+
+/// How to look up a repository.
+#[derive(Debug, Clone, Copy)]
+pub enum RepositoryKey<'a> {
+    Id(&'a str),
+    Path(&'a str),
+}
+
+/// A `RepositoryKey` with its path key computed once, to compare with stored
+/// repositories.
+enum KeyMatcher<'a> {
+    Id(&'a str),
+    Path(String),
+}
+
+impl<'a> RepositoryKey<'a> {
+    fn matcher(&self) -> KeyMatcher<'a> {
+        match *self {
+            RepositoryKey::Id(id) => KeyMatcher::Id(id),
+            RepositoryKey::Path(path) => KeyMatcher::Path(path_key(path)),
+        }
+    }
+}
+
+impl KeyMatcher<'_> {
+    fn matches(&self, repo: &Repository) -> bool {
+        match self {
+            KeyMatcher::Id(id) => repo.id == *id,
+            KeyMatcher::Path(key) => stored_path_key(&repo.path) == *key,
+        }
+    }
+}
+
+/// Normalize every repository and worktree path (see `normalize_path`). Done
+/// once when paths enter the store, so lookups can compare them as stored.
+pub fn normalize_store_paths(data: &mut StoreData) {
+    for repo in &mut data.repositories {
+        normalize_repository_paths(repo);
+    }
+}
+
+fn normalize_repository_paths(repo: &mut Repository) {
+    repo.path = normalize_path(&repo.path);
+    for worktree in &mut repo.worktrees {
+        worktree.path = normalize_path(&worktree.path);
+    }
+}
+
+/// Application state containing the worktree store.
+/// Uses RwLock instead of Mutex for better read concurrency.
+/// Multiple readers can access the store simultaneously,
+/// while writers get exclusive access.
+///
+/// Lock order: `store` before `index`.
+pub struct AppState {
+    pub store: RwLock<StoreData>,
+    pub index: RwLock<RepositoryIndex>,
+}
+
+impl AppState {
+    /// Create state from loaded store data and build the repository index.
+    /// Paths in `data` are expected to be normalized (`normalize_store_paths`).
+    pub fn new(data: StoreData) -> Self {
+        let index = RepositoryIndex::build(&data.repositories);
+        Self {
+            store: RwLock::new(data),
+            index: RwLock::new(index),
+        }
+    }
+
+    /// Save the current store to disk.
+    /// Requires a read lock since we're only reading the data to serialize it.
+    pub fn save(&self) -> Result<(), String> {
+        let store = self.store.read().map_err(|e| e.to_string())?;
+        let path = get_store_path();
+        save_json_store(&path, &*store)?;
+        mark_store_changed();
+        println!(
+            "[persistence] Saved {} repositories to store",
+            store.repositories.len()
+        );
+        Ok(())
+    }
+
+    /// Replace the whole store (e.g. when loading a workspace snapshot) and
+    /// rebuild the index.
+    pub fn replace(&self, mut data: StoreData) -> Result<(), String> {
+        normalize_store_paths(&mut data);
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        *store = data;
+        self.reindex(&store.repositories);
+        Ok(())
+    }
+
+    /// Locate a repository's position, verifying the index and rebuilding it if stale.
+    /// The caller must hold the store lock and pass its repositories.
+    fn locate(&self, repositories: &[Repository], key: RepositoryKey) -> Option<usize> {
+        let matcher = key.matcher();
+        let indexed = self.index.read().ok().and_then(|index| match &matcher {
+            KeyMatcher::Id(id) => index.position_by_id(id),
+            KeyMatcher::Path(key) => index.position_by_key(key),
+        });
+
+        if let Some(idx) = indexed {
+            if repositories.get(idx).map(|r| matcher.matches(r)) == Some(true) {
+                return Some(idx);
+            }
+        }
+
+        let found = repositories.iter().position(|r| matcher.matches(r));
+        let index_len = self.index.read().map(|i| i.len()).unwrap_or(0);
+        if indexed.is_some() || found.is_some() || index_len != repositories.len() {
+            self.reindex(repositories);
+        }
+        found
+    }
+
+    /// Rebuild the index from the given repositories.
+    fn reindex(&self, repositories: &[Repository]) {
+        if let Ok(mut index) = self.index.write() {
+            *index = RepositoryIndex::build(repositories);
+        }
+    }
+
+    /// Get a copy of a repository.
+    pub fn get_repository(&self, key: RepositoryKey) -> Result<Option<Repository>, String> {
+        let store = self.store.read().map_err(|e| e.to_string())?;
+        Ok(self
+            .locate(&store.repositories, key)
+            .map(|idx| store.repositories[idx].clone()))
+    }
+
+    /// Base branch for new work in the repository at `path`: the tracked
+    /// repository's setting or detected branch, else detected now.
+    pub fn resolve_base_branch(&self, path: &str) -> Result<Option<String>, String> {
+        let repo = self.get_repository(RepositoryKey::Path(path))?;
+        Ok(match repo {
+            Some(repo) => repo.base_branch().map(str::to_string),
+            None => detect_base_branch(path),
+        })
+    }
+
+    /// Add a repository. Fails if a repository with the same path exists.
+    pub fn insert_repository(&self, mut repo: Repository) -> Result<(), String> {
+        normalize_repository_paths(&mut repo);
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        if self
+            .locate(&store.repositories, RepositoryKey::Path(&repo.path))
+            .is_some()
+        {
+            return Err("Repository already added".to_string());
+        }
+        store.repositories.push(repo);
+        self.reindex(&store.repositories);
+        Ok(())
+    }
+
+    /// Remove a repository. Returns whether it existed.
+    pub fn remove_repository(&self, key: RepositoryKey) -> Result<bool, String> {
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        let removed = match self.locate(&store.repositories, key) {
+            Some(idx) => {
+                store.repositories.remove(idx);
+                true
+            }
+            None => false,
+        };
+        self.reindex(&store.repositories);
+        Ok(removed)
+    }
+
+    /// Apply a change to a repository. Returns `None` if it was not found.
+    /// The closure must not change the repository's id or path.
+    pub fn update_repository<F, R>(&self, key: RepositoryKey, f: F) -> Result<Option<R>, String>
+    where
+        F: FnOnce(&mut Repository) -> R,
+    {
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        Ok(self
+            .locate(&store.repositories, key)
+            .map(|idx| f(&mut store.repositories[idx])))
+    }
+
+    /// The tracked worktree at `path`, in whichever repository holds it.
+    pub fn get_worktree(&self, path: &str) -> Result<Option<WorktreeInfo>, String> {
+        let key = path_key(path);
+        let store = self.store.read().map_err(|e| e.to_string())?;
+        Ok(store
+            .repositories
+            .iter()
+            .flat_map(|r| r.worktrees.iter())
+            .find(|w| stored_path_key(&w.path) == key)
+            .cloned())
+    }
+
+    /// Apply a change to the worktree at `path`, in whichever repository holds it.
+    /// Returns whether a worktree was found.
+    pub fn update_worktree<F>(&self, path: &str, f: F) -> Result<bool, String>
+    where
+        F: FnOnce(&mut WorktreeInfo),
+    {
+        let key = path_key(path);
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        let worktree = store
+            .repositories
+            .iter_mut()
+            .flat_map(|r| r.worktrees.iter_mut())
+            .find(|w| stored_path_key(&w.path) == key);
+
+        match worktree {
+            Some(wt) => {
+                f(wt);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Whether the branch of the tracked worktree at `path` is protected in its
+    /// repository. Untracked worktrees are checked against the built-in list.
+    pub fn is_protected_worktree_branch(&self, path: &str) -> Result<bool, String> {
+        let key = path_key(path);
+        let store = self.store.read().map_err(|e| e.to_string())?;
+        Ok(store.repositories.iter().any(|repo| {
+            repo.worktrees
+                .iter()
+                .filter(|w| stored_path_key(&w.path) == key)
+                .filter_map(|w| w.branch.as_deref())
+                .any(|b| repo.is_protected_branch(b))
+        }))
+    }
+
+    /// Remove the worktree at `path` from every repository.
+    pub fn remove_worktree_entry(&self, path: &str) -> Result<(), String> {
+        let key = path_key(path);
+        let mut store = self.store.write().map_err(|e| e.to_string())?;
+        for repo in &mut store.repositories {
+            repo.worktrees.retain(|w| stored_path_key(&w.path) != key);
+        }
+        Ok(())
+    }
+}
+
+/// Remove repositories and worktrees that point at the same location under
+/// different path spellings. The first entry wins; worktrees of a dropped
+/// duplicate repository are merged into the kept one. Paths are normalized
+/// first (`normalize_store_paths`), as this runs when the store is loaded.
+/// Returns the number of entries removed.
+pub fn dedupe_store_paths(data: &mut StoreData) -> usize {
+    normalize_store_paths(data);
+    let mut removed = 0;
+    let mut kept: Vec<Repository> = Vec::new();
+
+    for repo in data.repositories.drain(..) {
+        let key = stored_path_key(&repo.path);
+        if let Some(existing) = kept.iter_mut().find(|r| stored_path_key(&r.path) == key) {
+            existing.worktrees.extend(repo.worktrees);
+            removed += 1;
+        } else {
+            kept.push(repo);
+        }
+    }
+
+    for repo in &mut kept {
+        let mut seen: Vec<String> = Vec::new();
+        let before = repo.worktrees.len();
+        repo.worktrees.retain(|w| {
+            let key = stored_path_key(&w.path);
+            if seen.contains(&key) {
+                false
+            } else {
+                seen.push(key);
+                true
+            }
+        });
+        removed += before - repo.worktrees.len();
+    }
+
+    data.repositories = kept;
+    removed
+}
+
+/// Initialize the worktree store from disk.
+pub fn init_store() -> AppState {
+    println!("[persistence] Initializing store...");
+    let path = get_store_path();
+    let mut data: StoreData = load_json_store(&path);
+    println!(
+        "[persistence] Loaded {} repositories from store",
+        data.repositories.len()
+    );
+
+    let removed = dedupe_store_paths(&mut data);
+    let state = AppState::new(data);
+    if removed > 0 {
+        println!(
+            "[persistence] Removed {} duplicate path entries from store",
+            removed
+        );
+        if !is_read_only() {
+            if let Err(e) = state.save() {
+                eprintln!("[persistence] Failed to save deduplicated store: {}", e);
+            }
+        }
+    }
+    state
+}
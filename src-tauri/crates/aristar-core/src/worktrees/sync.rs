@@ -0,0 +1,355 @@
+//! Fetch, pull and push for a single worktree.
+//!
+//! Each operation works on the worktree's current branch and its upstream
+//! (`branch.<name>.remote` / `branch.<name>.merge`). Git runs with
+//! `GIT_TERMINAL_PROMPT=0`, so a remote that needs credentials git can't find
+//! fails instead of waiting on a prompt nobody can answer, and ssh runs in
+//! batch mode (see `known_hosts`). Common failures are turned into readable
+//! messages, followed by git's own output.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use super::known_hosts::{self, is_changed_host_key_error, is_unknown_host_error};
+use super::operations::{get_worktree_status, run_git_command};
+use super::types::WorktreeStatus;
+
+/// A remote operation on a worktree.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncOperation {
+    Fetch,
+    Pull,
+    Push,
+}
+
+impl SyncOperation {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Fetch => "Fetch",
+            Self::Pull => "Pull",
+            Self::Push => "Push",
+        }
+    }
+}
+
+/// Outcome of a fetch, pull or push.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncResult {
+    pub operation: SyncOperation,
+    /// Remote that was contacted
+    pub remote: String,
+    /// Current branch (`None` when detached, which only fetch allows)
+    pub branch: Option<String>,
+    /// Upstream after the operation, e.g. `origin/main`
+    pub upstream: Option<String>,
+    /// Whether a push set the upstream of a branch that had none
+    pub upstream_set: bool,
+    /// New upstream commits (fetch), commits brought into the branch (pull)
+    /// or commits sent to the remote (push)
+    pub commits: u32,
+    /// Commits not on the upstream, after the operation
+    pub ahead: u32,
+    /// Upstream commits not in HEAD, after the operation
+    pub behind: u32,
+    /// Git's progress output, for display
+    pub output: String,
+}
+
+/// Turn git's stderr from a failed operation into an error message, leading
+/// with a readable explanation when the failure is a known one.
+pub fn describe_sync_error(operation: SyncOperation, stderr: &str) -> String {
+    let stderr = stderr.trim();
+    let has = |patterns: &[&str]| patterns.iter().any(|p| stderr.contains(p));
+
+    let reason = if is_changed_host_key_error(stderr) {
+        Some("the remote's SSH host key changed; check the new key before updating known_hosts")
+    } else if is_unknown_host_error(stderr) {
+        Some("the remote's SSH host key is not known yet; check and trust its fingerprint to connect")
+    } else if has(&[
+        "Authentication failed",
+        "could not read Username",
+        "terminal prompts disabled",
+        "Permission denied (publickey)",
+    ]) {
+        Some("authentication failed; set up credentials or an SSH key for this remote")
+    } else if has(&[
+        "Could not resolve host",
+        "Connection refused",
+        "Connection timed out",
+        "Could not read from remote repository",
+    ]) {
+        Some("the remote could not be reached")
+    } else if has(&["[rejected]", "[remote rejected]"]) && has(&["fetch first", "non-fast-forward"])
+    {
+        Some("the remote has commits that are not in this branch; pull first")
+    } else if has(&["Not possible to fast-forward", "divergent branches"]) {
+        Some("the branch has diverged from its upstream; pull with rebase instead")
+    } else if has(&[
+        "would be overwritten",
+        "uncommitted changes",
+        "unstaged changes",
+    ]) {
+        Some("local changes would be overwritten; commit or stash them first")
+    } else if has(&["CONFLICT", "could not apply"]) {
+        Some("the rebase hit conflicts and was aborted")
+    } else {
+        None
+    };
+
+    match reason {
+        Some(reason) if stderr.is_empty() => format!("{} failed: {}", operation.label(), reason),
+        Some(reason) => format!("{} failed: {}\n\n{}", operation.label(), reason, stderr),
+        None => format!("{} failed: {}", operation.label(), stderr),
+    }
+}
+
+/// Run a git command that talks to `remote`. Returns git's stderr, where it
+/// writes progress, or the described error. An unknown SSH host is reported
+/// to the known_hosts listener.
+fn run_remote_git(
+    operation: SyncOperation,
+    args: &[&str],
+    path: &str,
+    remote: &str,
+) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .current_dir(path)
+        .env("GIT_TERMINAL_PROMPT", "0");
+    known_hosts::apply_ssh_command(&mut command, path);
+    let output = command.output().map_err(|e| e.to_string())?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    if !output.status.success() {
+        if is_unknown_host_error(&stderr) {
+            known_hosts::report_unknown_host(operation, path, remote);
+        }
+        return Err(describe_sync_error(operation, &stderr));
+    }
+    Ok(stderr.trim().to_string())
+}
+
+fn git_config_value(path: &str, key: &str) -> Option<String> {
+    run_git_command(&["config", "--get", key], path)
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn count_commits(path: &str, args: &[&str]) -> u32 {
+    let mut count_args = vec!["rev-list", "--count"];
+    count_args.extend_from_slice(args);
+    run_git_command(&count_args, path)
+        .ok()
+        .and_then(|o| String::from_utf8_lossy(&o.stdout).trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Remote for the worktree: the branch's configured remote, else `origin`,
+/// else the only remote there is.
+pub fn resolve_remote(path: &str, branch: Option<&str>) -> Result<String, String> {
+    if let Some(remote) =
+        branch.and_then(|b| git_config_value(path, &format!("branch.{}.remote", b)))
+    {
+        // "." means the upstream is a local branch, which has nothing to sync
+        if remote != "." {
+            return Ok(remote);
+        }
+    }
+
+    let output = run_git_command(&["remote"], path)?;
+    let remotes: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    match remotes.as_slice() {
+        [] => Err("No remote configured for this repository".to_string()),
+        [only] => Ok(only.clone()),
+        _ if remotes.iter().any(|r| r == "origin") => Ok("origin".to_string()),
+        _ => Err(format!(
+            "Several remotes ({}) and no upstream; set one with git branch --set-upstream-to",
+            remotes.join(", ")
+        )),
+    }
+}
+
+fn result(
+    operation: SyncOperation,
+    remote: String,
+    status: WorktreeStatus,
+    upstream_set: bool,
+    commits: u32,
+    output: String,
+) -> SyncResult {
+    SyncResult {
+        operation,
+        remote,
+        branch: status.branch,
+        upstream: status.upstream,
+        upstream_set,
+        commits,
+        ahead: status.ahead,
+        behind: status.behind,
+        output,
+    }
+}
+
+/// Fetch the worktree's remote, pruning deleted remote branches.
+pub fn fetch_worktree(path: &str) -> Result<SyncResult, String> {
+    let before = get_worktree_status(path)?;
+    let remote = resolve_remote(path, before.branch.as_deref())?;
+
+    let output = run_remote_git(
+        SyncOperation::Fetch,
+        &["fetch", "--prune", "--progress", &remote],
+        path,
+        &remote,
+    )?;
+
+    let after = get_worktree_status(path)?;
+    let commits = after.behind.saturating_sub(before.behind);
+    Ok(result(
+        SyncOperation::Fetch,
+        remote,
+        after,
+        false,
+        commits,
+        output,
+    ))
+}
+
+/// Pull the upstream into the worktree's branch: fast-forward only, or rebase
+/// local commits onto it with `rebase`. A rebase that hits conflicts is aborted.
+pub fn pull_worktree(path: &str, rebase: bool) -> Result<SyncResult, String> {
+    let before = get_worktree_status(path)?;
+    let branch = before
+        .branch
+        .clone()
+        .ok_or("Cannot pull: HEAD is detached")?;
+    if before.upstream.is_none() {
+        return Err(format!(
+            "Cannot pull: branch {} has no upstream; push it first",
+            branch
+        ));
+    }
+    let remote = resolve_remote(path, Some(&branch))?;
+
+    let mode = if rebase { "--rebase" } else { "--ff-only" };
+    let output = run_remote_git(
+        SyncOperation::Pull,
+        &["pull", mode, "--progress"],
+        path,
+        &remote,
+    )
+    .inspect_err(|_| {
+        if rebase {
+            // Leave the worktree as it was rather than mid-rebase
+            let _ = run_git_command(&["rebase", "--abort"], path);
+        }
+    })?;
+
+    let after = get_worktree_status(path)?;
+    // Upstream commits the branch didn't have; rebased local commits don't count
+    let commits = match &before.head {
+        Some(old) => count_commits(path, &[&format!("{}..@{{upstream}}", old)]),
+        None => 0,
+    };
+    Ok(result(
+        SyncOperation::Pull,
+        remote,
+        after,
+        false,
+        commits,
+        output,
+    ))
+}
+
+/// Branch a push from the worktree updates on the remote: the upstream's
+/// branch, else the same name as the local one.
+pub fn push_target_branch(path: &str) -> Result<String, String> {
+    let branch = get_worktree_status(path)?
+        .branch
+        .ok_or("Cannot push: HEAD is detached")?;
+    Ok(git_config_value(path, &format!("branch.{}.merge", branch))
+        .and_then(|merge_ref| merge_ref.strip_prefix("refs/heads/").map(str::to_string))
+        .unwrap_or(branch))
+}
+
+/// Push the worktree's branch to its upstream, or to the same name on the
+/// remote with the upstream set if it has none. `force` uses
+/// `--force-with-lease`, which refuses to drop commits not seen by a fetch.
+pub fn push_worktree(path: &str, force: bool) -> Result<SyncResult, String> {
+    let before = get_worktree_status(path)?;
+    let branch = before
+        .branch
+        .clone()
+        .ok_or("Cannot push: HEAD is detached")?;
+    if before.head.is_none() {
+        return Err(format!("Cannot push: branch {} has no commits", branch));
+    }
+    let remote = resolve_remote(path, Some(&branch))?;
+
+    let remotes_arg = format!("--remotes={}", remote);
+    let commits = count_commits(path, &["HEAD", "--not", &remotes_arg]);
+
+    let upstream_ref = before
+        .upstream
+        .as_ref()
+        .and_then(|_| git_config_value(path, &format!("branch.{}.merge", branch)));
+    let refspec = match &upstream_ref {
+        Some(merge_ref) => format!("HEAD:{}", merge_ref),
+        None => branch.clone(),
+    };
+
+    let mut args = vec!["push", "--progress"];
+    if force {
+        args.push("--force-with-lease");
+    }
+    if upstream_ref.is_none() {
+        args.push("--set-upstream");
+    }
+    args.push(&remote);
+    args.push(&refspec);
+
+    let output = run_remote_git(SyncOperation::Push, &args, path, &remote)?;
+
+    let after = get_worktree_status(path)?;
+    Ok(result(
+        SyncOperation::Push,
+        remote,
+        after,
+        upstream_ref.is_none(),
+        commits,
+        output,
+    ))
+}
+
+/// Fetch a worktree's remote (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn fetch_worktree_async(path: String) -> Result<SyncResult, String> {
+    tokio::task::spawn_blocking(move || fetch_worktree(&path))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Pull into a worktree (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn pull_worktree_async(path: String, rebase: bool) -> Result<SyncResult, String> {
+    tokio::task::spawn_blocking(move || pull_worktree(&path, rebase))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Push a worktree's branch (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn push_worktree_async(path: String, force: bool) -> Result<SyncResult, String> {
+    tokio::task::spawn_blocking(move || push_worktree(&path, force))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
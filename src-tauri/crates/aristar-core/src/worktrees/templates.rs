@@ -0,0 +1,110 @@
+//! Template variables for startup scripts.
+//!
+//! Scripts may contain `{{branch}}`, `{{worktree_path}}`, `{{repo_name}}`,
+//! `{{task_id}}` and `{{port}}` (whitespace inside the braces is allowed).
+//! `{{port}}` is the worktree's dev server port from the port registry (see
+//! `core::ports`), reserved only when a script uses it. Scripts are rendered
+//! before the script is written and executed. Values are inserted verbatim, so
+//! paths should be quoted in the script (`cd "{{worktree_path}}"`); values
+//! containing shell metacharacters are rejected rather than inserted.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Variables available in startup scripts.
+pub const TEMPLATE_VARIABLES: [&str; 5] =
+    ["branch", "worktree_path", "repo_name", "task_id", "port"];
+
+/// Characters that could change the meaning of a script if inserted verbatim.
+const UNSAFE_CHARS: [char; 12] = [
+    '`', '$', ';', '&', '|', '<', '>', '"', '\'', '\\', '\n', '\r',
+];
+
+/// Values for the template variables. Missing values render as empty strings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptTemplateContext {
+    pub branch: Option<String>,
+    pub worktree_path: Option<String>,
+    pub repo_name: Option<String>,
+    pub task_id: Option<String>,
+    pub port: Option<String>,
+}
+
+impl ScriptTemplateContext {
+    fn value(&self, variable: &str) -> Option<&str> {
+        let value = match variable {
+            "branch" => &self.branch,
+            "worktree_path" => &self.worktree_path,
+            "repo_name" => &self.repo_name,
+            "task_id" => &self.task_id,
+            "port" => &self.port,
+            _ => return None,
+        };
+        Some(value.as_deref().unwrap_or(""))
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+}
+
+/// Whether a script contains the `{{variable}}` placeholder.
+pub fn uses_variable(template: &str, variable: &str) -> bool {
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        match rest.find("}}") {
+            Some(end) if rest[..end].trim() == variable => return true,
+            Some(_) => {}
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Render `{{variable}}` placeholders in a script.
+///
+/// Unknown variable names are an error. Braces that don't enclose a variable
+/// name (e.g. `{{ $x }}`) are left as-is.
+pub fn render_script(template: &str, context: &ScriptTemplateContext) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return Ok(rendered);
+        };
+
+        let name = after[..end].trim();
+        if !is_variable_name(name) {
+            rendered.push_str("{{");
+            rest = after;
+            continue;
+        }
+
+        let value = context.value(name).ok_or_else(|| {
+            format!(
+                "Unknown template variable {{{{{}}}}}. Available: {}",
+                name,
+                TEMPLATE_VARIABLES.join(", ")
+            )
+        })?;
+        if value.contains(UNSAFE_CHARS) {
+            return Err(format!(
+                "Value of {{{{{}}}}} contains characters that are unsafe in a script: {}",
+                name, value
+            ));
+        }
+
+        rendered.push_str(value);
+        rest = &after[end + 2..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
@@ -0,0 +1,552 @@
+//! Worktree-related types.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::{normalize_path, stored_path_key, AppSettings};
+
+use super::operations::PROTECTED_BRANCHES;
+use super::processes::WorktreeProcess;
+
+pub use crate::core::events::DEFAULT_BRANCH_EVENT;
+
+/// Information about a single worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeInfo {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    /// What HEAD points at. `None` only for entries saved before this field
+    /// existed; the next refresh fills it in.
+    #[serde(default, alias = "head_state")]
+    pub head_state: Option<HeadState>,
+    /// HEAD is detached (`detached` in `git worktree list --porcelain`), as for
+    /// agent worktrees
+    #[serde(default, alias = "is_detached")]
+    pub is_detached: bool,
+    #[serde(alias = "is_main")]
+    pub is_main: bool,
+    #[serde(alias = "is_locked")]
+    pub is_locked: bool,
+    #[serde(alias = "lock_reason")]
+    pub lock_reason: Option<String>,
+    /// When a lock placed by the app lifts itself, in milliseconds (see
+    /// `lock_expiry`). Kept in the app store only.
+    #[serde(default, alias = "lock_expires_at")]
+    pub lock_expires_at: Option<i64>,
+    #[serde(alias = "startup_script")]
+    pub startup_script: Option<String>,
+    #[serde(alias = "script_executed")]
+    pub script_executed: bool,
+    #[serde(alias = "created_at")]
+    pub created_at: i64,
+    /// Left out of listings (e.g. long-lived build caches); still shown in
+    /// cleanup and diagnostics views
+    #[serde(default)]
+    pub hidden: bool,
+    /// URL of the app the worktree serves, opened by `open_dev_url`
+    #[serde(default, alias = "dev_url")]
+    pub dev_url: Option<String>,
+    /// Free-form note on why the worktree exists. Kept in the app store only,
+    /// never written to git.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Tags such as "wip" or "review", normalized and sorted (see `labels`)
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Last time the worktree was opened in a terminal or editor, had OpenCode
+    /// started in it, or had git activity, in milliseconds (see `activity`)
+    #[serde(default, alias = "last_active")]
+    pub last_active: Option<i64>,
+}
+
+impl WorktreeInfo {
+    /// Note activity at `at` (milliseconds); an earlier time changes nothing.
+    pub fn record_activity(&mut self, at: i64) {
+        self.last_active = self.last_active.max(Some(at));
+    }
+}
+
+/// Longest description `set_worktree_description` accepts, in characters.
+pub const MAX_WORKTREE_DESCRIPTION_CHARS: usize = 2000;
+
+/// A description as stored: trimmed, `None` when blank. Errors when longer than
+/// `MAX_WORKTREE_DESCRIPTION_CHARS`.
+pub fn normalize_worktree_description(description: Option<&str>) -> Result<Option<String>, String> {
+    let Some(description) = description.map(str::trim).filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    if description.chars().count() > MAX_WORKTREE_DESCRIPTION_CHARS {
+        return Err(format!(
+            "Description is too long (at most {} characters)",
+            MAX_WORKTREE_DESCRIPTION_CHARS
+        ));
+    }
+    Ok(Some(description.to_string()))
+}
+
+/// What a worktree's HEAD points at.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "kind", content = "value", rename_all = "kebab-case")]
+pub enum HeadState {
+    /// On a branch (short name, e.g. `feature/login`)
+    Branch(String),
+    /// Detached at a commit
+    Detached(String),
+    /// On a branch with no commits yet
+    Unborn,
+}
+
+/// Why `rename_worktree` or `move_worktree` failed, tagged by `kind` so the UI can offer the
+/// right way out (pick another name, stop the processes, try again later).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum RenameWorktreeError {
+    /// Not a plain directory name
+    InvalidName { name: String },
+    /// A file or directory already exists at the new path
+    TargetExists { path: String },
+    /// Processes are running inside the worktree; rename with
+    /// `stop_processes`, or these did not exit in time
+    ProcessesRunning { processes: Vec<WorktreeProcess> },
+    /// The directory stayed busy (held open by another program) after
+    /// `attempts` tries
+    Busy { path: String, attempts: u32 },
+    /// Any other failure
+    Failed { message: String },
+}
+
+impl std::fmt::Display for RenameWorktreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidName { name } => write!(f, "Invalid worktree name: {:?}", name),
+            Self::TargetExists { path } => write!(f, "{} already exists", path),
+            Self::ProcessesRunning { processes } => {
+                let names: Vec<String> = processes
+                    .iter()
+                    .map(|p| format!("{} ({})", p.command, p.pid))
+                    .collect();
+                write!(
+                    f,
+                    "Processes still running in the worktree: {}",
+                    names.join(", ")
+                )
+            }
+            Self::Busy { path, attempts } => write!(
+                f,
+                "{} is in use by another program (tried {} times)",
+                path, attempts
+            ),
+            Self::Failed { message } => f.write_str(message),
+        }
+    }
+}
+
+impl From<String> for RenameWorktreeError {
+    fn from(message: String) -> Self {
+        Self::Failed { message }
+    }
+}
+
+/// How a worktree's commits are published onto a target branch.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    /// Squash all commits into one commit with a generated message
+    Squash,
+    /// Rebase the commits onto the target and fast-forward it
+    Rebase,
+    /// Create a merge commit on the target
+    #[default]
+    MergeCommit,
+    /// Move the target to the worktree's HEAD; fails if the target has diverged
+    FastForward,
+}
+
+/// Repository with its worktrees.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Repository {
+    pub id: String,
+    pub path: String,
+    /// Folder name of `path`
+    pub name: String,
+    /// Name set by the user, shown instead of `name`
+    #[serde(default, alias = "display_name")]
+    pub display_name: Option<String>,
+    pub worktrees: Vec<WorktreeInfo>,
+    #[serde(alias = "last_scanned")]
+    pub last_scanned: i64,
+    /// When the repository path stopped being reachable (e.g. unmounted network
+    /// volume). `None` while available.
+    #[serde(default, alias = "unavailable_since")]
+    pub unavailable_since: Option<i64>,
+    /// Default strategy when publishing worktrees of this repository
+    #[serde(default, alias = "default_merge_strategy")]
+    pub default_merge_strategy: Option<MergeStrategy>,
+    /// Branch new work starts from when no ref is given, set by the user.
+    /// Overrides `detected_base_branch`.
+    #[serde(default, alias = "default_base_branch")]
+    pub default_base_branch: Option<String>,
+    /// Base branch detected from `origin/HEAD` (or `init.defaultBranch`, or a
+    /// local main/master) on scan
+    #[serde(default, alias = "detected_base_branch")]
+    pub detected_base_branch: Option<String>,
+    /// Remote default branch as last seen, to notice when it changes
+    #[serde(default, alias = "remote_default_branch")]
+    pub remote_default_branch: Option<String>,
+    /// Branches never deleted with a worktree, besides `PROTECTED_BRANCHES`
+    #[serde(default, alias = "protected_branches")]
+    pub protected_branches: Vec<String>,
+    /// Branches protected on the remote host (GitHub), as last fetched
+    #[serde(default, alias = "remote_protected_branches")]
+    pub remote_protected_branches: Vec<String>,
+    /// When `remote_protected_branches` was fetched
+    #[serde(default, alias = "remote_protection_checked_at")]
+    pub remote_protection_checked_at: Option<i64>,
+    /// How much of the repository is read ahead of time
+    #[serde(default, alias = "scan_settings")]
+    pub scan_settings: RepoScanSettings,
+    /// Tags grouping repositories, normalized and sorted (see `labels`)
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// Commits read ahead into the history cache by default.
+pub const DEFAULT_HISTORY_DEPTH: usize = 200;
+
+/// How much of a repository is scanned ahead of time. Large repositories get
+/// lighter defaults when added (see `prefetch::default_scan_settings`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RepoScanSettings {
+    /// Commits of HEAD read into the history cache by the prefetcher; 0 turns
+    /// the cache off
+    pub history_depth: usize,
+    /// Check the repository's worktrees for uncommitted changes in the
+    /// background (global status); off, they are checked on a deep scan only
+    pub eager_status: bool,
+}
+
+impl Default for RepoScanSettings {
+    fn default() -> Self {
+        Self {
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            eager_status: true,
+        }
+    }
+}
+
+/// Result of `deep_scan_repository`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepScanResult {
+    /// Commits now in the history cache
+    pub commits_cached: usize,
+    /// Status of every worktree, by path; worktrees whose status failed are left out
+    pub statuses: HashMap<String, WorktreeStatus>,
+    /// Worktrees with uncommitted changes
+    pub dirty_worktrees: usize,
+}
+
+/// Longest allowed repository display name.
+pub const MAX_DISPLAY_NAME_LEN: usize = 100;
+
+impl Repository {
+    /// Name shown in listings and used for worktree aliases.
+    pub fn display_name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Set the display name; `None`, a blank name or the folder name clears it.
+    pub fn set_display_name(&mut self, display_name: Option<&str>) -> Result<(), String> {
+        let display_name = display_name.map(str::trim).filter(|n| !n.is_empty());
+        if let Some(name) = display_name {
+            if name.chars().count() > MAX_DISPLAY_NAME_LEN {
+                return Err(format!(
+                    "Display name cannot be longer than {} characters",
+                    MAX_DISPLAY_NAME_LEN
+                ));
+            }
+            if name.chars().any(char::is_control) {
+                return Err("Display name cannot contain control characters".to_string());
+            }
+        }
+        self.display_name = display_name.filter(|n| *n != self.name).map(str::to_string);
+        Ok(())
+    }
+
+    /// Branch new worktrees and tasks start from when no ref is given.
+    pub fn base_branch(&self) -> Option<&str> {
+        self.default_base_branch
+            .as_deref()
+            .or(self.detected_base_branch.as_deref())
+    }
+
+    /// Replace the worktrees with a fresh scan, keeping the `hidden` flags, dev
+    /// URLs, descriptions and labels, the expiry of locks still held, and the
+    /// later of the two `last_active`. The new paths are normalized, as they
+    /// enter the store here.
+    pub fn replace_worktrees(&mut self, mut worktrees: Vec<WorktreeInfo>) {
+        for worktree in &mut worktrees {
+            worktree.path = normalize_path(&worktree.path);
+            let key = stored_path_key(&worktree.path);
+            let old = self
+                .worktrees
+                .iter()
+                .find(|old| stored_path_key(&old.path) == key);
+            worktree.hidden = old.is_some_and(|old| old.hidden);
+            worktree.dev_url = old.and_then(|old| old.dev_url.clone());
+            worktree.description = old.and_then(|old| old.description.clone());
+            worktree.labels = old.map(|old| old.labels.clone()).unwrap_or_default();
+            worktree.lock_expires_at = old
+                .and_then(|old| old.lock_expires_at)
+                .filter(|_| worktree.is_locked);
+            worktree.last_active = worktree
+                .last_active
+                .max(old.and_then(|old| old.last_active));
+        }
+        self.worktrees = worktrees;
+    }
+
+    /// The repository as listed: hidden worktrees are left out.
+    pub fn without_hidden_worktrees(mut self) -> Self {
+        self.worktrees.retain(|w| !w.hidden);
+        self
+    }
+
+    /// Store the remote default branch seen now. Returns the change when a
+    /// previously seen default branch was replaced by another one.
+    pub fn record_remote_default_branch(
+        &mut self,
+        current: Option<String>,
+    ) -> Option<DefaultBranchChange> {
+        let current = current?;
+        let previous = self.remote_default_branch.replace(current.clone())?;
+        (previous != current).then(|| DefaultBranchChange {
+            repository_id: self.id.clone(),
+            previous,
+            current,
+        })
+    }
+
+    /// Whether `branch` must not be deleted along with a worktree.
+    pub fn is_protected_branch(&self, branch: &str) -> bool {
+        PROTECTED_BRANCHES.contains(&branch)
+            || self.protected_branches.iter().any(|b| b == branch)
+            || self.is_remote_protected_branch(branch)
+    }
+
+    /// Whether the remote host protects `branch`, so it rejects force-pushes.
+    pub fn is_remote_protected_branch(&self, branch: &str) -> bool {
+        self.remote_protected_branches.iter().any(|b| b == branch)
+    }
+
+    /// Follow a remote default branch rename: protect `current` and move a
+    /// default base branch set to `previous` (or `origin/<previous>`) over to it.
+    pub fn apply_default_branch_change(&mut self, previous: &str, current: &str) {
+        if !self.is_protected_branch(current) {
+            self.protected_branches.push(current.to_string());
+        }
+
+        let remote_previous = format!("origin/{}", previous);
+        self.default_base_branch = match self.default_base_branch.take() {
+            Some(b) if b == previous => Some(current.to_string()),
+            Some(b) if b == remote_previous => Some(format!("origin/{}", current)),
+            other => other,
+        };
+    }
+}
+
+/// Emitted when a repository's remote default branch changed (e.g. master to
+/// main), so the user can be asked whether to follow it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultBranchChange {
+    pub repository_id: String,
+    pub previous: String,
+    pub current: String,
+}
+
+/// Contents of `.aristar/lock.json`, written into locked worktrees so
+/// scripts and CI can see the app's lock without parsing git.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct WorktreeLockFile {
+    pub locked: bool,
+    pub reason: Option<String>,
+    /// Unix timestamp in milliseconds
+    pub locked_at: i64,
+    pub locked_by: String,
+    pub pid: u32,
+    /// When the app lifts the lock, Unix timestamp in milliseconds
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+/// Working tree status of a single worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeStatus {
+    /// Current commit (`None` before the first commit)
+    pub head: Option<String>,
+    /// Current branch (`None` when detached)
+    pub branch: Option<String>,
+    /// Upstream branch, e.g. `origin/main`
+    pub upstream: Option<String>,
+    /// Commits not on the upstream (0 without upstream)
+    pub ahead: u32,
+    /// Upstream commits not in HEAD (0 without upstream)
+    pub behind: u32,
+    /// Files with staged changes
+    pub staged: usize,
+    /// Files with unstaged changes
+    pub modified: usize,
+    pub untracked: usize,
+    /// Files with merge conflicts
+    pub conflicted: usize,
+    /// Any uncommitted work, including untracked files
+    pub is_dirty: bool,
+}
+
+/// Outcome of a rebase, continue or abort.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RebaseStatus {
+    /// Already based on the target; nothing was done
+    UpToDate,
+    /// The rebase finished
+    Rebased,
+    /// The rebase stopped on conflicts; resolve them, then continue or abort
+    Conflicts,
+    /// The rebase was aborted and the worktree is back where it started
+    Aborted,
+}
+
+/// Result of rebasing a worktree.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct RebaseResult {
+    pub status: RebaseStatus,
+    /// What the worktree is rebased onto (empty after an abort)
+    pub onto: String,
+    /// Commits of the worktree that were replayed so far
+    pub commits: u32,
+    /// Files with unresolved conflicts
+    pub conflicts: Vec<String>,
+    /// Git's output, for display
+    pub output: String,
+}
+
+/// Where a path sits within a git repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoContext {
+    /// Git directory shared by all worktrees (e.g. `/repo/.git`)
+    pub common_dir: PathBuf,
+    /// Root of the worktree containing the path (the repository itself when bare)
+    pub toplevel: PathBuf,
+    /// The path is inside a linked worktree rather than the main one
+    pub is_worktree: bool,
+}
+
+impl RepoContext {
+    /// Directory to run repository-wide git commands in: the main worktree, or
+    /// the git directory itself for worktrees of a bare repository.
+    pub fn repo_root(&self) -> &Path {
+        if !self.is_worktree {
+            return &self.toplevel;
+        }
+        match (self.common_dir.file_name(), self.common_dir.parent()) {
+            (Some(name), Some(parent)) if name == ".git" => parent,
+            _ => &self.common_dir,
+        }
+    }
+}
+
+/// What `create_worktree` checks out in a new worktree.
+#[derive(Debug, Clone, Default)]
+pub struct CreateWorktreeOptions {
+    /// Existing branch to check out
+    pub branch: Option<String>,
+    /// Branch to create (`git worktree add -b`); defaults to the worktree name
+    pub new_branch: Option<String>,
+    /// Commit or ref the new branch (or detached HEAD) starts at; default HEAD
+    pub start_point: Option<String>,
+    /// Check out `start_point` detached instead of creating a branch
+    pub detach: bool,
+    /// Script written to the worktree as `.worktree-setup.sh`
+    pub startup_script: Option<String>,
+    /// Run the startup script after creating the worktree
+    pub execute_script: bool,
+}
+
+/// Branch information.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchInfo {
+    pub name: String,
+    pub is_current: bool,
+    pub is_remote: bool,
+    /// Upstream of a local branch (e.g. "origin/main")
+    #[serde(default)]
+    pub upstream: Option<String>,
+    /// Commits on the branch but not on its upstream
+    #[serde(default)]
+    pub ahead: u32,
+    /// Commits on the upstream but not on the branch
+    #[serde(default)]
+    pub behind: u32,
+    /// The upstream is configured but no longer exists (e.g. deleted after merge)
+    #[serde(default)]
+    pub upstream_gone: bool,
+}
+
+/// Order of a branch page.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum BranchSort {
+    /// By full ref name: local branches, then remote ones
+    #[default]
+    Name,
+    /// Most recently committed first
+    Recent,
+}
+
+/// One page of a repository's branches (`get_branch_page`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchPage {
+    pub branches: Vec<BranchInfo>,
+    /// Branches matching the filter, on all pages
+    pub total: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+/// Commit information.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitInfo {
+    pub hash: String,
+    pub short_hash: String,
+    pub message: String,
+    pub author: String,
+    pub date: i64,
+    /// Parent hashes (two or more for merges, none for root commits)
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// Ref decorations as `git log` shows them (e.g. "HEAD -> main", "tag: v1.0")
+    #[serde(default)]
+    pub refs: Vec<String>,
+}
+
+/// Persistent store data for worktrees/repositories.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreData {
+    pub repositories: Vec<Repository>,
+    pub settings: AppSettings,
+}
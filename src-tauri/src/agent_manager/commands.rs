@@ -1,13 +1,45 @@
 //! Tauri commands for agent manager operations.
 
+use chrono::Utc;
 use std::path::PathBuf;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::core::api_version::Versioned;
+use crate::core::notifications::{self, DesktopNotification, NotificationUrgency};
+use crate::core::{ensure_writable, ports, resolve_locale};
+use crate::worktrees::activity::record_worktree_activity;
+use crate::worktrees::store::AppState;
 
 use super::agent_operations;
+use super::agreement;
+use super::artifacts;
+use super::auto_accept;
+use super::change_summary;
+use super::comparison;
+use super::context_files;
+use super::coordinator;
+use super::events;
+use super::leaderboard;
 use super::opencode::OpenCodeManager;
+use super::opencode_install;
+use super::pipeline;
+use super::presets;
+use super::quota;
+use super::review;
+use super::scheduler;
+use super::scratchpad;
 use super::store::TaskManagerState;
 use super::task_operations;
-use super::types::{AgentStatus, ModelSelection, Task, TaskStatus};
+use super::transcript;
+use super::types::{
+    AgentAgreementReport, AgentComparisonExport, AgentPortChangedEvent, AgentStatus,
+    AgentStatusChangedEvent, AgentSummary, AgentTranscript, AutoAcceptPolicy, BatchTaskSummary,
+    ChangeSummary, ContextFile, ModelLeaderboardEntry, ModelSelection, Pipeline, PipelineRun,
+    RepointTasksReport, ReviewComment, SandboxConfig, SavedTaskPreset, Schedule, Task,
+    TaskArtifact, TaskArtifactCleanup, TaskMode, TaskPreset, TaskStatus, TasksLoadedEvent,
+    TimelineEntry, TranscriptRange, WriteAuditReport, AGENT_PORT_EVENT, AGENT_STATUS_EVENT,
+};
+use super::write_audit;
 
 // ============ Task Commands ============
 
@@ -15,6 +47,7 @@ use super::types::{AgentStatus, ModelSelection, Task, TaskStatus};
 #[allow(clippy::too_many_arguments)]
 pub fn create_task(
     state: State<TaskManagerState>,
+    app_state: State<AppState>,
     name: String,
     source_type: String,
     source_branch: Option<String>,
@@ -22,7 +55,19 @@ pub fn create_task(
     source_repo_path: String,
     agent_type: String,
     models: Vec<ModelSelection>,
+    opencode_version: Option<String>,
+    include_uncommitted: Option<bool>,
+    sparse_paths: Option<Vec<String>>,
+    mode: Option<TaskMode>,
 ) -> Result<Task, String> {
+    ensure_writable("create a task")?;
+
+    // Without an explicit ref, start from the repository's default base branch
+    let source_branch = match (source_type.as_str(), source_branch) {
+        ("commit", branch) | (_, branch @ Some(_)) => branch,
+        (_, None) => app_state.resolve_base_branch(&source_repo_path)?,
+    };
+
     task_operations::create_task_impl(
         &state,
         name,
@@ -32,12 +77,33 @@ pub fn create_task(
         source_repo_path,
         agent_type,
         models,
+        opencode_version,
+        include_uncommitted.unwrap_or(false),
+        sparse_paths.unwrap_or_default(),
+        mode.unwrap_or_default(),
     )
 }
 
+/// All tasks, without their timelines (see `get_task_timeline`). Waits for
+/// the task store to load.
 #[tauri::command]
 pub fn get_tasks(state: State<TaskManagerState>) -> Result<Vec<Task>, String> {
-    task_operations::get_tasks_impl(&state)
+    task_operations::get_task_list_impl(&state)
+}
+
+/// A task's timeline, left out of `get_tasks`.
+#[tauri::command]
+pub fn get_task_timeline(
+    state: State<TaskManagerState>,
+    task_id: String,
+) -> Result<Vec<TimelineEntry>, String> {
+    task_operations::get_task_timeline_impl(&state, &task_id)
+}
+
+/// The task store load, or `None` while `tasks-loaded` hasn't been emitted.
+#[tauri::command]
+pub fn get_tasks_loaded(state: State<TaskManagerState>) -> Option<TasksLoadedEvent> {
+    state.store.loaded().cloned()
 }
 
 #[tauri::command]
@@ -52,16 +118,174 @@ pub fn update_task(
     name: Option<String>,
     status: Option<TaskStatus>,
 ) -> Result<Task, String> {
+    ensure_writable("update a task")?;
+
     task_operations::update_task_impl(&state, task_id, name, status)
 }
 
+#[tauri::command]
+pub fn set_task_opencode_version(
+    state: State<TaskManagerState>,
+    task_id: String,
+    version: Option<String>,
+) -> Result<Task, String> {
+    ensure_writable("pin an OpenCode version")?;
+
+    task_operations::set_task_opencode_version_impl(&state, task_id, version)
+}
+
+#[tauri::command]
+pub fn set_task_sandbox(
+    state: State<TaskManagerState>,
+    task_id: String,
+    sandbox: SandboxConfig,
+) -> Result<Task, String> {
+    ensure_writable("change sandbox options")?;
+
+    task_operations::set_task_sandbox_impl(&state, task_id, sandbox)
+}
+
 #[tauri::command]
 pub fn delete_task(
     state: State<TaskManagerState>,
     task_id: String,
     delete_worktrees: bool,
 ) -> Result<(), String> {
-    task_operations::delete_task_impl(&state, task_id, delete_worktrees)
+    ensure_writable("delete a task")?;
+
+    let task = task_operations::get_task_impl(&state, &task_id)?;
+    task_operations::delete_task_impl(&state, task_id, delete_worktrees)?;
+    if delete_worktrees {
+        for agent in &task.agents {
+            release_agent_ports(&agent.worktree_path);
+        }
+    }
+    Ok(())
+}
+
+/// Point the tasks of a renamed or moved repository at `new_repo_path` and
+/// run `git worktree repair` on their agent worktrees.
+#[tauri::command]
+pub async fn repoint_tasks(
+    state: State<'_, TaskManagerState>,
+    old_repo_path: String,
+    new_repo_path: String,
+) -> Result<RepointTasksReport, String> {
+    ensure_writable("repoint tasks")?;
+
+    task_operations::repoint_tasks_async(&state, old_repo_path, new_repo_path).await
+}
+
+/// Refs the app created for a task (snapshot, fork checkpoints).
+#[tauri::command]
+pub fn list_task_artifacts(
+    state: State<TaskManagerState>,
+    task_id: String,
+) -> Result<Vec<TaskArtifact>, String> {
+    artifacts::list_task_artifacts_impl(&state, &task_id)
+}
+
+/// Delete the refs the app created for a finished task. With `keep_accepted`,
+/// those of accepted work not yet on the source branch are kept.
+#[tauri::command]
+pub fn cleanup_task_artifacts(
+    state: State<TaskManagerState>,
+    task_id: String,
+    keep_accepted: bool,
+) -> Result<TaskArtifactCleanup, String> {
+    ensure_writable("clean up task refs")?;
+    artifacts::cleanup_task_artifacts_impl(&state, &task_id, keep_accepted)
+}
+
+/// Release the ports reserved for a removed agent's worktree.
+fn release_agent_ports(worktree_path: &str) {
+    if let Err(e) = ports::release_ports(worktree_path, None) {
+        eprintln!("[agent_manager] Failed to release ports: {}", e);
+    }
+}
+
+// ============ Context File Commands ============
+
+/// Attach files to a task; they are copied into every agent worktree.
+#[tauri::command]
+pub fn add_task_context_files(
+    state: State<TaskManagerState>,
+    task_id: String,
+    paths: Vec<String>,
+) -> Result<Task, String> {
+    ensure_writable("attach context files")?;
+
+    context_files::add_task_context_files_impl(&state, &task_id, paths)
+}
+
+#[tauri::command]
+pub fn remove_task_context_file(
+    state: State<TaskManagerState>,
+    task_id: String,
+    name: String,
+) -> Result<Task, String> {
+    ensure_writable("remove a context file")?;
+
+    context_files::remove_task_context_file_impl(&state, &task_id, &name)
+}
+
+#[tauri::command]
+pub fn get_task_context_files(
+    state: State<TaskManagerState>,
+    task_id: String,
+) -> Result<Vec<ContextFile>, String> {
+    context_files::get_task_context_files_impl(&state, &task_id)
+}
+
+// ============ Scratchpad Commands ============
+
+/// Read the scratchpad shared by a task's agents.
+#[tauri::command]
+pub fn read_task_scratchpad(
+    state: State<TaskManagerState>,
+    task_id: String,
+) -> Result<String, String> {
+    scratchpad::read_task_scratchpad_impl(&state, &task_id)
+}
+
+/// Append a note to a task's scratchpad. Returns the new contents.
+#[tauri::command]
+pub fn append_task_scratchpad(
+    state: State<TaskManagerState>,
+    task_id: String,
+    text: String,
+    author: Option<String>,
+) -> Result<String, String> {
+    ensure_writable("write to the scratchpad")?;
+
+    scratchpad::append_task_scratchpad_impl(&state, &task_id, &text, author.as_deref())
+}
+
+// ============ Transcript Commands ============
+
+/// Record OpenCode events of an agent in its transcript. Returns the number of
+/// entries written.
+#[tauri::command]
+pub fn append_agent_transcript(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    events: Vec<serde_json::Value>,
+) -> Result<usize, String> {
+    ensure_writable("record a transcript")?;
+
+    transcript::append_agent_transcript_impl(&state, &task_id, &agent_id, &events)
+}
+
+/// Read an agent's transcript, or the entries of `range`.
+#[tauri::command]
+pub fn get_agent_transcript(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    range: Option<TranscriptRange>,
+) -> Result<AgentTranscript, String> {
+    transcript::get_agent_transcript_impl(&state, &task_id, &agent_id, range)
 }
 
 // ============ Agent Commands ============
@@ -74,9 +298,34 @@ pub fn add_agent_to_task(
     provider_id: String,
     agent_type: Option<String>,
 ) -> Result<Task, String> {
+    ensure_writable("add an agent")?;
+
     agent_operations::add_agent_to_task_impl(&state, task_id, model_id, provider_id, agent_type)
 }
 
+/// Add an agent continuing `source_agent_id`'s work (committed and uncommitted)
+/// with another model.
+#[tauri::command]
+pub fn fork_agent(
+    state: State<TaskManagerState>,
+    task_id: String,
+    source_agent_id: String,
+    model_id: String,
+    provider_id: String,
+    agent_type: Option<String>,
+) -> Result<Task, String> {
+    ensure_writable("fork an agent")?;
+
+    agent_operations::fork_agent_impl(
+        &state,
+        task_id,
+        source_agent_id,
+        model_id,
+        provider_id,
+        agent_type,
+    )
+}
+
 #[tauri::command]
 pub fn remove_agent_from_task(
     state: State<TaskManagerState>,
@@ -84,7 +333,18 @@ pub fn remove_agent_from_task(
     agent_id: String,
     delete_worktree: bool,
 ) -> Result<(), String> {
-    agent_operations::remove_agent_from_task_impl(&state, task_id, agent_id, delete_worktree)
+    ensure_writable("remove an agent")?;
+
+    let worktree_path = task_operations::get_task_impl(&state, &task_id)?
+        .agents
+        .into_iter()
+        .find(|a| a.id == agent_id)
+        .map(|a| a.worktree_path);
+    agent_operations::remove_agent_from_task_impl(&state, task_id, agent_id, delete_worktree)?;
+    if let Some(path) = worktree_path.filter(|_| delete_worktree) {
+        release_agent_ports(&path);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -94,17 +354,73 @@ pub fn update_agent_session(
     agent_id: String,
     session_id: Option<String>,
 ) -> Result<(), String> {
+    ensure_writable("update an agent session")?;
+
     agent_operations::update_agent_session_impl(&state, task_id, agent_id, session_id)
 }
 
+/// Notify about an agent that completed (held back during Focus) or failed.
+fn notify_agent_finished(
+    state: &TaskManagerState,
+    task_id: &str,
+    agent_id: &str,
+    status: &AgentStatus,
+) {
+    let (title, urgency) = match status {
+        AgentStatus::Completed => ("Agent completed", NotificationUrgency::Normal),
+        AgentStatus::Failed => ("Agent failed", NotificationUrgency::Urgent),
+        _ => return,
+    };
+    let task_name = state
+        .store
+        .lock()
+        .ok()
+        .and_then(|store| {
+            store
+                .tasks
+                .iter()
+                .find(|t| t.id == task_id)
+                .map(|t| t.name.clone())
+        })
+        .unwrap_or_else(|| task_id.to_string());
+
+    let notification =
+        DesktopNotification::new(title, &format!("{} ({})", task_name, agent_id), urgency);
+    std::thread::spawn(move || notifications::notify(notification));
+}
+
 #[tauri::command]
 pub fn update_agent_status(
+    app: AppHandle,
     state: State<TaskManagerState>,
     task_id: String,
     agent_id: String,
     status: AgentStatus,
 ) -> Result<(), String> {
-    agent_operations::update_agent_status_impl(&state, task_id, agent_id, status)
+    ensure_writable("update an agent status")?;
+
+    agent_operations::update_agent_status_impl(
+        &state,
+        task_id.clone(),
+        agent_id.clone(),
+        status.clone(),
+    )?;
+    notify_agent_finished(&state, &task_id, &agent_id, &status);
+
+    let event = AgentStatusChangedEvent {
+        task_id: task_id.clone(),
+        agent_id,
+        status,
+    };
+    if let Err(e) = app.emit(AGENT_STATUS_EVENT, Versioned::new(event)) {
+        eprintln!(
+            "[task_manager] Failed to emit {}: {}",
+            AGENT_STATUS_EVENT, e
+        );
+    }
+
+    events::start_pipeline_if_ready(&app, &task_id);
+    Ok(())
 }
 
 #[tauri::command]
@@ -113,6 +429,8 @@ pub fn accept_agent(
     task_id: String,
     agent_id: String,
 ) -> Result<(), String> {
+    ensure_writable("accept an agent")?;
+
     agent_operations::accept_agent_impl(&state, task_id, agent_id)
 }
 
@@ -121,6 +439,8 @@ pub fn cleanup_unaccepted_agents(
     state: State<TaskManagerState>,
     task_id: String,
 ) -> Result<(), String> {
+    ensure_writable("clean up agents")?;
+
     agent_operations::cleanup_unaccepted_agents_impl(&state, task_id)
 }
 
@@ -140,38 +460,347 @@ pub fn recreate_agent_worktree(
     task_id: String,
     agent_id: String,
 ) -> Result<String, String> {
+    ensure_writable("recreate an agent worktree")?;
+
     agent_operations::recreate_agent_worktree_impl(&state, task_id, agent_id)
 }
 
+// ============ Review Commands ============
+
+#[tauri::command]
+pub fn add_review_comment(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    file: String,
+    line: u32,
+    body: String,
+) -> Result<ReviewComment, String> {
+    ensure_writable("add a review comment")?;
+
+    review::add_review_comment_impl(&state, task_id, agent_id, file, line, body)
+}
+
+#[tauri::command]
+pub fn list_review_comments(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    include_resolved: Option<bool>,
+) -> Result<Vec<ReviewComment>, String> {
+    review::list_review_comments_impl(&state, task_id, agent_id, include_resolved.unwrap_or(true))
+}
+
+#[tauri::command]
+pub fn resolve_comment(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    comment_id: String,
+) -> Result<ReviewComment, String> {
+    ensure_writable("resolve a review comment")?;
+
+    review::resolve_comment_impl(&state, task_id, agent_id, comment_id)
+}
+
+#[tauri::command]
+pub fn get_agent_summaries(
+    state: State<TaskManagerState>,
+    app_state: State<AppState>,
+    task_id: String,
+) -> Result<Vec<AgentSummary>, String> {
+    let locale = {
+        let store = app_state.store.read().map_err(|e| e.to_string())?;
+        resolve_locale(store.settings.locale.as_deref())
+    };
+    review::get_agent_summaries_impl(&state, &task_id, locale)
+}
+
+// ============ Change Summary Commands ============
+
+/// Summarize an agent's diff against the task source (or the repository's
+/// default base branch if the task has none).
+/// With `save`, the Markdown is stored as the task's result summary.
+#[tauri::command]
+pub async fn generate_change_summary(
+    state: State<'_, TaskManagerState>,
+    app_state: State<'_, AppState>,
+    task_id: String,
+    agent_id: String,
+    save: Option<bool>,
+) -> Result<ChangeSummary, String> {
+    let save = save.unwrap_or(false);
+    if save {
+        ensure_writable("save a task summary")?;
+    }
+
+    // Tasks started without a ref are compared against the default base branch
+    let task = task_operations::get_task_impl(&state, &task_id)?;
+    let default_base = match change_summary::get_task_base_ref(&task) {
+        Ok(_) => None,
+        Err(_) => app_state.resolve_base_branch(&task.source_repo_path)?,
+    };
+
+    change_summary::generate_change_summary_async(&state, task_id, agent_id, save, default_base)
+        .await
+}
+
+/// Compare the agents' diffs file by file: which changes every agent agrees on.
+#[tauri::command]
+pub async fn compute_agent_agreement(
+    state: State<'_, TaskManagerState>,
+    app_state: State<'_, AppState>,
+    task_id: String,
+) -> Result<AgentAgreementReport, String> {
+    // Tasks started without a ref are compared against the default base branch
+    let task = task_operations::get_task_impl(&state, &task_id)?;
+    let default_base = match change_summary::get_task_base_ref(&task) {
+        Ok(_) => None,
+        Err(_) => app_state.resolve_base_branch(&task.source_repo_path)?,
+    };
+
+    agreement::compute_agent_agreement_async(&state, task_id, default_base).await
+}
+
+/// Write a standalone HTML file with the selected agents' diffs side by side
+/// (all agents with a worktree when `agents` is empty). `dest` is a file, or a
+/// folder to write `<task>-comparison.html` into.
+#[tauri::command]
+pub async fn export_agent_comparison(
+    state: State<'_, TaskManagerState>,
+    app_state: State<'_, AppState>,
+    task_id: String,
+    agents: Vec<String>,
+    dest: String,
+) -> Result<AgentComparisonExport, String> {
+    let task = task_operations::get_task_impl(&state, &task_id)?;
+    let default_base = match change_summary::get_task_base_ref(&task) {
+        Ok(_) => None,
+        Err(_) => app_state.resolve_base_branch(&task.source_repo_path)?,
+    };
+
+    comparison::export_agent_comparison_async(&state, task_id, agents, dest, default_base).await
+}
+
+/// Acceptance rate, average score and diff size per model across the tasks
+/// with an accepted agent, only those of `repo_path` when given.
+#[tauri::command]
+pub fn get_model_leaderboard(
+    state: State<TaskManagerState>,
+    repo_path: Option<String>,
+) -> Result<Vec<ModelLeaderboardEntry>, String> {
+    leaderboard::get_model_leaderboard_impl(&state, repo_path.as_deref())
+}
+
+#[tauri::command]
+pub fn set_task_result_summary(
+    state: State<TaskManagerState>,
+    task_id: String,
+    summary: Option<String>,
+) -> Result<(), String> {
+    ensure_writable("update a task summary")?;
+
+    change_summary::set_task_result_summary_impl(&state, &task_id, summary)
+}
+
+// ============ Write Audit Commands ============
+
+/// Check for files written outside an agent's worktree since its server was
+/// last started. The result is recorded in the task timeline.
+#[tauri::command]
+pub async fn audit_agent_writes(
+    state: State<'_, TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+) -> Result<WriteAuditReport, String> {
+    write_audit::audit_agent_writes_async(&state, task_id, agent_id).await
+}
+
+// ============ Schedule Commands ============
+
+/// Create a recurring schedule that creates a task from `preset` on each run.
+#[tauri::command]
+pub fn create_schedule(
+    state: State<TaskManagerState>,
+    name: String,
+    cron: String,
+    preset: TaskPreset,
+) -> Result<Schedule, String> {
+    ensure_writable("create a schedule")?;
+
+    scheduler::create_schedule_impl(&state, name, cron, preset)
+}
+
+#[tauri::command]
+pub fn list_schedules(state: State<TaskManagerState>) -> Result<Vec<Schedule>, String> {
+    scheduler::list_schedules_impl(&state)
+}
+
+#[tauri::command]
+pub fn pause_schedule(
+    state: State<TaskManagerState>,
+    schedule_id: String,
+) -> Result<Schedule, String> {
+    ensure_writable("pause a schedule")?;
+
+    scheduler::set_schedule_paused_impl(&state, &schedule_id, true)
+}
+
+#[tauri::command]
+pub fn resume_schedule(
+    state: State<TaskManagerState>,
+    schedule_id: String,
+) -> Result<Schedule, String> {
+    ensure_writable("resume a schedule")?;
+
+    scheduler::set_schedule_paused_impl(&state, &schedule_id, false)
+}
+
+#[tauri::command]
+pub fn delete_schedule(state: State<TaskManagerState>, schedule_id: String) -> Result<(), String> {
+    ensure_writable("delete a schedule")?;
+
+    scheduler::delete_schedule_impl(&state, &schedule_id)
+}
+
+// ============ Preset Commands ============
+
+/// Save a task preset for reuse, e.g. with `create_task_batch`.
+#[tauri::command]
+pub fn save_task_preset(
+    state: State<TaskManagerState>,
+    preset: TaskPreset,
+) -> Result<SavedTaskPreset, String> {
+    ensure_writable("save a task preset")?;
+
+    presets::save_task_preset_impl(&state, preset)
+}
+
+#[tauri::command]
+pub fn list_task_presets(state: State<TaskManagerState>) -> Result<Vec<SavedTaskPreset>, String> {
+    presets::list_task_presets_impl(&state)
+}
+
+#[tauri::command]
+pub fn delete_task_preset(state: State<TaskManagerState>, preset_id: String) -> Result<(), String> {
+    ensure_writable("delete a task preset")?;
+
+    presets::delete_task_preset_impl(&state, &preset_id)
+}
+
+/// Create one task from a saved preset in each of `repos`, reporting success
+/// or failure per repository.
+#[tauri::command]
+pub fn create_task_batch(
+    state: State<TaskManagerState>,
+    app_state: State<AppState>,
+    preset_id: String,
+    repos: Vec<String>,
+) -> Result<BatchTaskSummary, String> {
+    ensure_writable("create tasks")?;
+
+    presets::create_task_batch_impl(&state, &app_state, &preset_id, repos)
+}
+
+// ============ Pipeline Commands ============
+
+/// Attach a pipeline to a task (or remove it with `null`). It runs on its own
+/// once all of the task's agents have finished.
+#[tauri::command]
+pub fn set_task_pipeline(
+    state: State<TaskManagerState>,
+    task_id: String,
+    pipeline: Option<Pipeline>,
+) -> Result<Task, String> {
+    ensure_writable("change a task pipeline")?;
+
+    pipeline::set_task_pipeline_impl(&state, &task_id, pipeline)
+}
+
+/// Run a task's pipeline now, replacing the previous run.
+#[tauri::command]
+pub async fn run_task_pipeline(app: AppHandle, task_id: String) -> Result<PipelineRun, String> {
+    ensure_writable("run a task pipeline")?;
+
+    events::run_task_pipeline_async(app, task_id).await
+}
+
+/// Set (or clear) a task's auto-accept policy, checked after each pipeline run.
+#[tauri::command]
+pub fn set_task_auto_accept(
+    state: State<TaskManagerState>,
+    task_id: String,
+    policy: Option<AutoAcceptPolicy>,
+) -> Result<Task, String> {
+    ensure_writable("change a task's auto-accept policy")?;
+
+    auto_accept::set_task_auto_accept_impl(&state, &task_id, policy)
+}
+
+/// Cancel a task's pending automatic accept. Returns whether one was pending.
+#[tauri::command]
+pub fn cancel_auto_accept(state: State<TaskManagerState>, task_id: String) -> Result<bool, String> {
+    ensure_writable("cancel an auto-accept")?;
+
+    auto_accept::cancel_auto_accept_impl(&state, &task_id)
+}
+
+/// Set (or clear) the disk quota for each agent worktree of a task, in MB.
+#[tauri::command]
+pub fn set_task_disk_quota(
+    state: State<TaskManagerState>,
+    task_id: String,
+    quota_mb: Option<u64>,
+) -> Result<Task, String> {
+    ensure_writable("change a task's disk quota")?;
+
+    quota::set_task_disk_quota_impl(&state, &task_id, quota_mb)
+}
+
+/// Clear an agent's disk quota flag after reviewing its worktree. Returns
+/// whether it was flagged.
+#[tauri::command]
+pub fn clear_agent_quota_flag(
+    state: State<TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+) -> Result<bool, String> {
+    ensure_writable("clear an agent's quota flag")?;
+
+    quota::clear_agent_quota_flag_impl(&state, &task_id, &agent_id)
+}
+
 // ============ Agent OpenCode Commands ============
 
 /// Start OpenCode server for a specific agent.
+/// Reuses the agent's preferred port when free; emits `agent-port-changed` otherwise.
 #[tauri::command]
 pub fn start_agent_opencode(
+    app: AppHandle,
     task_state: State<TaskManagerState>,
     opencode_state: State<OpenCodeManager>,
     task_id: String,
     agent_id: String,
 ) -> Result<u16, String> {
-    let worktree_path = {
-        let store = task_state.store.lock().map_err(|e| e.to_string())?;
-        let task = store
-            .tasks
-            .iter()
-            .find(|t| t.id == task_id)
-            .ok_or_else(|| format!("Task not found: {}", task_id))?;
-
-        let agent = task
-            .agents
-            .iter()
-            .find(|a| a.id == agent_id)
-            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
-
-        agent.worktree_path.clone()
-    };
+    ensure_writable("start OpenCode")?;
+
+    let started =
+        coordinator::start_agent_server(&task_state, &opencode_state, &task_id, &agent_id)?;
+
+    // Emitted with no lock held (see `coordinator`)
+    if let Some(previous_port) = started.previous_port {
+        let event = AgentPortChangedEvent {
+            task_id,
+            agent_id,
+            previous_port,
+            port: started.port,
+        };
+        if let Err(e) = app.emit(AGENT_PORT_EVENT, Versioned::new(event)) {
+            eprintln!("[opencode] Failed to emit {}: {}", AGENT_PORT_EVENT, e);
+        }
+    }
 
-    let path = PathBuf::from(worktree_path);
-    opencode_state.start(path)
+    Ok(started.port)
 }
 
 /// Stop OpenCode server for a specific agent.
@@ -182,25 +811,7 @@ pub fn stop_agent_opencode(
     task_id: String,
     agent_id: String,
 ) -> Result<(), String> {
-    let worktree_path = {
-        let store = task_state.store.lock().map_err(|e| e.to_string())?;
-        let task = store
-            .tasks
-            .iter()
-            .find(|t| t.id == task_id)
-            .ok_or_else(|| format!("Task not found: {}", task_id))?;
-
-        let agent = task
-            .agents
-            .iter()
-            .find(|a| a.id == agent_id)
-            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
-
-        agent.worktree_path.clone()
-    };
-
-    let path = PathBuf::from(worktree_path);
-    opencode_state.stop(&path)
+    coordinator::stop_agent_server(&task_state, &opencode_state, &task_id, &agent_id)
 }
 
 /// Get OpenCode port for a specific agent.
@@ -211,25 +822,7 @@ pub fn get_agent_opencode_port(
     task_id: String,
     agent_id: String,
 ) -> Result<Option<u16>, String> {
-    let worktree_path = {
-        let store = task_state.store.lock().map_err(|e| e.to_string())?;
-        let task = store
-            .tasks
-            .iter()
-            .find(|t| t.id == task_id)
-            .ok_or_else(|| format!("Task not found: {}", task_id))?;
-
-        let agent = task
-            .agents
-            .iter()
-            .find(|a| a.id == agent_id)
-            .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
-
-        agent.worktree_path.clone()
-    };
-
-    let path = PathBuf::from(worktree_path);
-    opencode_state.get_port(&path)
+    coordinator::agent_server_port(&task_state, &opencode_state, &task_id, &agent_id)
 }
 
 /// Stop all OpenCode servers for all agents in a task.
@@ -239,26 +832,7 @@ pub fn stop_task_all_opencode(
     opencode_state: State<OpenCodeManager>,
     task_id: String,
 ) -> Result<(), String> {
-    let worktree_paths: Vec<String> = {
-        let store = task_state.store.lock().map_err(|e| e.to_string())?;
-        let task = store
-            .tasks
-            .iter()
-            .find(|t| t.id == task_id)
-            .ok_or_else(|| format!("Task not found: {}", task_id))?;
-
-        task.agents
-            .iter()
-            .map(|a| a.worktree_path.clone())
-            .collect()
-    };
-
-    for worktree_path in worktree_paths {
-        let path = PathBuf::from(worktree_path);
-        let _ = opencode_state.stop(&path);
-    }
-
-    Ok(())
+    coordinator::stop_task_servers(&task_state, &opencode_state, &task_id)
 }
 
 // ============ Worktree-level OpenCode Commands ============
@@ -266,9 +840,23 @@ pub fn stop_task_all_opencode(
 
 /// Start OpenCode for a worktree (not agent).
 #[tauri::command]
-pub fn start_opencode(state: State<OpenCodeManager>, worktree_path: String) -> Result<u16, String> {
-    let path = PathBuf::from(worktree_path);
-    state.start(path)
+pub fn start_opencode(
+    state: State<OpenCodeManager>,
+    app_state: State<AppState>,
+    worktree_path: String,
+) -> Result<u16, String> {
+    ensure_writable("start OpenCode")?;
+
+    let port = state.start(PathBuf::from(&worktree_path))?;
+    if let Err(e) =
+        record_worktree_activity(&app_state, &worktree_path, Utc::now().timestamp_millis())
+    {
+        eprintln!(
+            "[opencode] Failed to record activity for {}: {}",
+            worktree_path, e
+        );
+    }
+    Ok(port)
 }
 
 /// Stop OpenCode for a worktree (not agent).
@@ -299,5 +887,29 @@ pub fn is_opencode_running(state: State<OpenCodeManager>, worktree_path: String)
 /// Returns the number of processes that were cleaned up.
 #[tauri::command]
 pub fn cleanup_orphaned_opencode_processes() -> u32 {
+    if crate::core::is_read_only() {
+        return 0;
+    }
     OpenCodeManager::cleanup_orphaned_processes()
 }
+
+// ============ OpenCode Binary Commands ============
+
+/// Download and install an OpenCode release into ~/.aristar-worktrees/bin/.
+/// Returns the path of the installed binary.
+#[tauri::command]
+pub async fn install_opencode(
+    version: Option<String>,
+    sha256: Option<String>,
+) -> Result<String, String> {
+    ensure_writable("install OpenCode")?;
+
+    let path = opencode_install::install_opencode_async(version, sha256).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// List installed managed OpenCode versions, newest first.
+#[tauri::command]
+pub fn list_installed_opencode_versions() -> Vec<String> {
+    opencode_install::list_installed_versions()
+}
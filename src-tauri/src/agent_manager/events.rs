@@ -0,0 +1,225 @@
+//! Tauri side of scheduling, pipelines, auto-accept and disk quotas: the
+//! background loops and the events they emit.
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::core::api_version::Versioned;
+use crate::core::is_read_only;
+use crate::worktrees::store::AppState;
+
+use super::auto_accept::{
+    cancel_auto_accept_impl, fire_due_auto_accepts, schedule_auto_accept_impl,
+    CancelAutoAcceptPayload, AUTO_ACCEPTED_EVENT, AUTO_ACCEPT_CANCEL_EVENT, AUTO_ACCEPT_INTERVAL,
+    AUTO_ACCEPT_SCHEDULED_EVENT,
+};
+use super::opencode::OpenCodeManager;
+use super::pipeline::{pipeline_ready, run_task_pipeline_impl};
+use super::power::POWER_THROTTLED_EVENT;
+use super::quota::{enforce_disk_quotas, AGENT_QUOTA_EVENT, QUOTA_CHECK_INTERVAL};
+use super::scheduler::{
+    run_due_schedules, update_power_throttle, SCHEDULER_INTERVAL, SCHEDULE_EVENT,
+};
+use super::store::{TaskManagerState, TASKS_LOADED_EVENT};
+use super::task_operations::get_task_impl;
+use super::types::{
+    AgentStatus, AgentStatusChangedEvent, AutoAcceptEvent, PipelineRun, AGENT_STATUS_EVENT,
+};
+
+/// Read the task store in the background and emit `tasks-loaded` when done,
+/// so the window doesn't wait for it to open.
+pub fn spawn_task_store_load(app: AppHandle) {
+    std::thread::spawn(move || {
+        let loaded = app
+            .state::<TaskManagerState>()
+            .store
+            .ensure_loaded()
+            .clone();
+        println!(
+            "[task_manager] Loaded {} tasks in {} ms",
+            loaded.task_count, loaded.duration_ms
+        );
+        if let Err(e) = app.emit(TASKS_LOADED_EVENT, Versioned::new(loaded)) {
+            eprintln!(
+                "[task_manager] Failed to emit {}: {}",
+                TASKS_LOADED_EVENT, e
+            );
+        }
+    });
+}
+
+/// Schedule an accept after a pipeline run and announce it.
+pub fn schedule_auto_accept(app: &AppHandle, task_id: &str) {
+    let now = Utc::now().timestamp_millis();
+    match schedule_auto_accept_impl(&app.state::<TaskManagerState>(), task_id, now) {
+        Ok(Some(pending)) => {
+            let event = AutoAcceptEvent {
+                task_id: task_id.to_string(),
+                agent_id: pending.agent_id,
+                reason: pending.reason,
+                due_at: pending.due_at,
+            };
+            if let Err(e) = app.emit(AUTO_ACCEPT_SCHEDULED_EVENT, Versioned::new(event)) {
+                eprintln!(
+                    "[auto_accept] Failed to emit {}: {}",
+                    AUTO_ACCEPT_SCHEDULED_EVENT, e
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(e) => eprintln!("[auto_accept] Task {}: {}", task_id, e),
+    }
+}
+
+/// Start the background loop firing due accepts, and listen for cancels.
+pub fn spawn_auto_accept_loop(app: AppHandle) {
+    let listener = app.clone();
+    app.listen_any(
+        AUTO_ACCEPT_CANCEL_EVENT,
+        move |event| match serde_json::from_str::<CancelAutoAcceptPayload>(event.payload()) {
+            Ok(payload) => {
+                let state = listener.state::<TaskManagerState>();
+                if let Err(e) = cancel_auto_accept_impl(&state, &payload.task_id) {
+                    eprintln!("[auto_accept] Failed to cancel: {}", e);
+                }
+            }
+            Err(e) => eprintln!(
+                "[auto_accept] Invalid {} payload: {}",
+                AUTO_ACCEPT_CANCEL_EVENT, e
+            ),
+        },
+    );
+
+    std::thread::spawn(move || loop {
+        if !is_read_only() {
+            let now = Utc::now().timestamp_millis();
+            match fire_due_auto_accepts(&app.state::<TaskManagerState>(), now) {
+                Ok(events) => {
+                    for event in events {
+                        if let Err(e) = app.emit(AUTO_ACCEPTED_EVENT, Versioned::new(event)) {
+                            eprintln!(
+                                "[auto_accept] Failed to emit {}: {}",
+                                AUTO_ACCEPTED_EVENT, e
+                            );
+                        }
+                    }
+                }
+                Err(e) => eprintln!("[auto_accept] Failed to fire auto-accepts: {}", e),
+            }
+        }
+
+        std::thread::sleep(AUTO_ACCEPT_INTERVAL);
+    });
+}
+
+/// Run a task's pipeline (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn run_task_pipeline_async(
+    app: AppHandle,
+    task_id: String,
+) -> Result<PipelineRun, String> {
+    tokio::task::spawn_blocking(move || {
+        let run = run_task_pipeline_impl(
+            &app.state::<TaskManagerState>(),
+            &app.state::<AppState>(),
+            &task_id,
+            true,
+        )?;
+        schedule_auto_accept(&app, &task_id);
+        Ok(run)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Start a task's pipeline in the background if it is ready to run.
+pub fn start_pipeline_if_ready(app: &AppHandle, task_id: &str) {
+    let ready = get_task_impl(&app.state::<TaskManagerState>(), task_id)
+        .map(|task| pipeline_ready(&task))
+        .unwrap_or(false);
+    if !ready {
+        return;
+    }
+
+    let app = app.clone();
+    let task_id = task_id.to_string();
+    std::thread::spawn(move || {
+        match run_task_pipeline_impl(
+            &app.state::<TaskManagerState>(),
+            &app.state::<AppState>(),
+            &task_id,
+            false,
+        ) {
+            Ok(_) => schedule_auto_accept(&app, &task_id),
+            Err(e) => eprintln!("[pipeline] Task {}: {}", task_id, e),
+        }
+    });
+}
+
+/// Start the background loop running due schedules and updating battery
+/// throttling.
+pub fn spawn_scheduler(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let (throttled, change) = update_power_throttle(&app.state::<AppState>());
+        if let Some(event) = change {
+            if let Err(e) = app.emit(POWER_THROTTLED_EVENT, Versioned::new(event)) {
+                eprintln!(
+                    "[scheduler] Failed to emit {}: {}",
+                    POWER_THROTTLED_EVENT, e
+                );
+            }
+        }
+        if !is_read_only() && !throttled {
+            let now = Utc::now().timestamp_millis();
+            match run_due_schedules(&app.state::<TaskManagerState>(), now) {
+                Ok(events) => {
+                    for event in events {
+                        if let Err(e) = app.emit(SCHEDULE_EVENT, Versioned::new(event)) {
+                            eprintln!("[scheduler] Failed to emit {}: {}", SCHEDULE_EVENT, e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("[scheduler] Failed to run schedules: {}", e),
+            }
+        }
+
+        std::thread::sleep(SCHEDULER_INTERVAL);
+    });
+}
+
+/// Start the background loop pausing agents whose worktrees outgrow their
+/// task's disk quota.
+pub fn spawn_quota_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(QUOTA_CHECK_INTERVAL);
+        if is_read_only() {
+            continue;
+        }
+
+        let now = Utc::now().timestamp_millis();
+        let events = match enforce_disk_quotas(
+            &app.state::<TaskManagerState>(),
+            &app.state::<OpenCodeManager>(),
+            now,
+        ) {
+            Ok(events) => events,
+            Err(e) => {
+                eprintln!("[quota] Failed to check disk quotas: {}", e);
+                continue;
+            }
+        };
+        for event in events {
+            let status = AgentStatusChangedEvent {
+                task_id: event.task_id.clone(),
+                agent_id: event.agent_id.clone(),
+                status: AgentStatus::Paused,
+            };
+            if let Err(e) = app.emit(AGENT_STATUS_EVENT, Versioned::new(status)) {
+                eprintln!("[quota] Failed to emit {}: {}", AGENT_STATUS_EVENT, e);
+            }
+            if let Err(e) = app.emit(AGENT_QUOTA_EVENT, Versioned::new(event)) {
+                eprintln!("[quota] Failed to emit {}: {}", AGENT_QUOTA_EVENT, e);
+            }
+        }
+    });
+}
@@ -1,18 +1,10 @@
-//! Agent Manager module - AI agent orchestration.
+//! Agent Manager module - Tauri commands and the background loops emitting
+//! app events.
 //!
-//! This module handles task and agent management including:
-//! - Task CRUD operations
-//! - Agent management (add, remove, update status)
-//! - OpenCode process management
-//! - Worktree creation for agents
+//! The logic lives in `aristar_core::agent_manager`, re-exported here so
+//! commands use it through `super::`.
 
-pub mod agent_operations;
-pub mod commands;
-pub mod opencode;
-pub mod store;
-pub mod task_operations;
-pub mod types;
+pub use aristar_core::agent_manager::*;
 
-// Re-export commonly used types
-pub use opencode::OpenCodeManager;
-pub use store::TaskManagerState;
+pub mod commands;
+pub mod events;
@@ -1,6 +1,20 @@
-//! Logger commands for file-based logging.
+//! Core commands: file-based logging, app mode, API version, notifications,
+//! port reservations and plugins.
 
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::agent_manager::task_operations;
+use crate::agent_manager::TaskManagerState;
+use crate::core::api_version::{self, ApiVersionInfo};
 use crate::core::get_log_file_path as rust_get_log_file_path;
+use crate::core::plugins::{self, PluginActionResult, PluginContext, PluginList};
+use crate::core::ports::{self, PortReport};
+use crate::core::read_only::ensure_writable;
+use crate::core::redaction::{self, RedactionPreview};
+use crate::worktrees::operations;
+use crate::worktrees::store::{AppState, RepositoryKey};
+use crate::worktrees::templates::ScriptTemplateContext;
 
 #[tauri::command]
 pub fn get_log_file_path() -> String {
@@ -16,3 +30,127 @@ pub fn append_to_log_file(path: String, content: String) -> Result<(), String> {
 pub fn rotate_logs_if_needed(max_size: u64, max_files: usize) -> Result<(), String> {
     crate::core::rotate_logs_if_needed(max_size, max_files)
 }
+
+/// Run the active redaction rules on a sample, to check what they replace.
+#[tauri::command]
+pub fn test_redaction(sample: String) -> RedactionPreview {
+    redaction::test_redaction(&sample)
+}
+
+#[tauri::command]
+pub fn is_read_only_mode() -> bool {
+    crate::core::is_read_only()
+}
+
+/// IPC schema version of the backend.
+#[tauri::command]
+pub fn get_api_version() -> ApiVersionInfo {
+    ApiVersionInfo::current()
+}
+
+/// Check that the frontend's IPC schema version is supported, naming the side
+/// to rebuild when it isn't.
+#[tauri::command]
+pub fn check_api_compatibility(frontend_version: u32) -> Result<ApiVersionInfo, String> {
+    api_version::register_frontend(frontend_version)
+}
+
+/// Notifications held back while a Focus mode is on.
+#[tauri::command]
+pub fn get_pending_notifications() -> Vec<crate::core::notifications::DesktopNotification> {
+    crate::core::notifications::get_pending_notifications()
+}
+
+/// Port reservations of every feature, the configured range and conflicts.
+#[tauri::command]
+pub fn get_port_reservations() -> Result<PortReport, String> {
+    ports::get_port_report()
+}
+
+/// Release the ports reserved for `owner` (a worktree path, or "app"), only
+/// `service`'s when given. Returns the number released.
+#[tauri::command]
+pub fn release_port_reservations(owner: String, service: Option<String>) -> Result<usize, String> {
+    ensure_writable("release port reservations")?;
+    ports::release_ports(&owner, service.as_deref())
+}
+
+/// Plugins in `~/.aristar-worktrees/plugins/`, and the manifests that failed
+/// to load.
+#[tauri::command]
+pub fn list_plugins() -> PluginList {
+    plugins::list_plugins()
+}
+
+/// Display name of the repository at `repo_path`, or its folder name if untracked.
+fn repository_name(state: &AppState, repo_path: &str) -> Result<String, String> {
+    Ok(state
+        .get_repository(RepositoryKey::Path(repo_path))?
+        .map(|r| r.display_name().to_string())
+        .unwrap_or_else(|| operations::get_repository_name(repo_path)))
+}
+
+/// Run a plugin action on `target`: a repository id, a worktree path or a task
+/// id, as the action's context says. The action runs in the repository's main
+/// worktree, the worktree or the task folder.
+#[tauri::command]
+pub async fn run_plugin_action(
+    app_state: State<'_, AppState>,
+    task_state: State<'_, TaskManagerState>,
+    plugin_id: String,
+    action_id: String,
+    target: String,
+) -> Result<PluginActionResult, String> {
+    ensure_writable("run a plugin action")?;
+
+    let action =
+        plugins::find_plugin_action_in(&plugins::get_plugins_dir(), &plugin_id, &action_id)?;
+
+    let (context, cwd) = match action.context {
+        PluginContext::Repo => {
+            let repo = app_state
+                .get_repository(RepositoryKey::Id(&target))?
+                .ok_or_else(|| format!("Repository not found: {}", target))?;
+            let context = ScriptTemplateContext {
+                branch: repo
+                    .worktrees
+                    .iter()
+                    .find(|w| w.is_main)
+                    .and_then(|w| w.branch.clone()),
+                worktree_path: Some(repo.path.clone()),
+                repo_name: Some(repo.display_name().to_string()),
+                ..Default::default()
+            };
+            (context, PathBuf::from(&repo.path))
+        }
+        PluginContext::Worktree => {
+            let worktree = app_state
+                .get_worktree(&target)?
+                .ok_or_else(|| format!("Worktree not found: {}", target))?;
+            let repo_path = operations::find_git_repo_root(&worktree.path)?;
+            let context = ScriptTemplateContext {
+                branch: worktree.branch.clone(),
+                worktree_path: Some(worktree.path.clone()),
+                repo_name: Some(repository_name(&app_state, &repo_path)?),
+                task_id: None,
+                port: ports::reserved_port(ports::DEV_SERVER_SERVICE, &worktree.path)?
+                    .map(|port| port.to_string()),
+            };
+            (context, PathBuf::from(&worktree.path))
+        }
+        PluginContext::Task => {
+            let task = task_operations::get_task_impl(&task_state, &target)?;
+            let folder = task_operations::get_task_folder_path(&task.id);
+            let context = ScriptTemplateContext {
+                branch: task.source_branch.clone(),
+                worktree_path: Some(folder.to_string_lossy().to_string()),
+                repo_name: Some(repository_name(&app_state, &task.source_repo_path)?),
+                task_id: Some(task.id.clone()),
+                port: None,
+            };
+            (context, folder)
+        }
+    };
+
+    plugins::run_plugin_action_async(action, context, cwd).await
+}
@@ -1,15 +1,8 @@
-//! Core module - Shared infrastructure for the application.
+//! Core module - Tauri commands.
 //!
-//! This module contains:
-//! - Persistence utilities (store load/save)
-//! - Shared types (AppSettings)
-//! - System operations (clipboard, finder)
+//! The logic lives in `aristar_core::core`, re-exported here so commands use
+//! it through `super::`.
 
-pub mod commands;
-pub mod persistence;
-pub mod system;
-pub mod types;
+pub use aristar_core::core::*;
 
-pub use persistence::*;
-pub use system::*;
-pub use types::*;
+pub mod commands;
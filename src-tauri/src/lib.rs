@@ -5,12 +5,12 @@
 
 pub mod agent_manager;
 pub mod core;
+pub mod merge;
+pub mod workspace;
 pub mod worktrees;
 
-#[cfg(test)]
-mod tests;
-
 // Re-export commonly used types and functions
 pub use agent_manager::{OpenCodeManager, TaskManagerState};
+pub use workspace::GlobalStatusCache;
 pub use worktrees::init_store;
 pub use worktrees::store::AppState;
@@ -5,19 +5,35 @@
 
 mod agent_manager;
 mod core;
+mod merge;
+mod workspace;
 mod worktrees;
 
-#[cfg(test)]
-mod tests;
-
 use std::fs;
 use tauri::{Manager, RunEvent};
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = agent_manager::prompt_status::run_status_command(&args) {
+        std::process::exit(code);
+    }
+
     println!("[main] Starting Aristar Worktrees...");
 
+    if let Some(data_dir) = core::data_dir_from_args(&args) {
+        core::set_data_dir_override(data_dir);
+    }
+    if core::read_only_requested(&args) {
+        core::set_read_only(true);
+    }
+
     let worktrees_base = worktrees::get_aristar_worktrees_base();
-    if !worktrees_base.exists() {
+    if core::is_read_only() && !worktrees_base.exists() {
+        eprintln!(
+            "[main] WARNING: Read-only mode against missing data directory: {:?}",
+            worktrees_base
+        );
+    } else if !worktrees_base.exists() {
         println!("[main] Creating worktrees directory: {:?}", worktrees_base);
         if let Err(e) = fs::create_dir_all(&worktrees_base) {
             eprintln!("[main] ERROR: Failed to create worktrees directory: {}", e);
@@ -25,26 +41,127 @@ fn main() {
         }
     }
 
+    let app_state = worktrees::init_store();
+    if app_state
+        .store
+        .read()
+        .map(|s| s.settings.read_only)
+        .unwrap_or(false)
+    {
+        core::set_read_only(true);
+    }
+    if let Ok(store) = app_state.store.read() {
+        worktrees::lfs::set_auto_pull(store.settings.lfs_auto_pull.unwrap_or(true));
+        worktrees::hooks::set_copy_hooks(store.settings.copy_hooks.unwrap_or(true));
+        worktrees::local_files::set_copy_patterns(store.settings.copy_untracked_files.clone());
+        worktrees::shared_deps::set_shared_dependencies(
+            store.settings.share_dependencies,
+            store.settings.shared_dependency_dirs.clone(),
+        );
+        core::redaction::set_redaction_rules(core::redaction::RedactionRules {
+            enabled: store.settings.redact_logs.unwrap_or(true),
+            patterns: store.settings.redaction_patterns.clone(),
+        });
+        agent_manager::task_operations::set_task_folder_settings(
+            store.settings.task_slug_max_length,
+            store.settings.max_worktree_path_length,
+            store.settings.task_folder_collision,
+        );
+        core::ports::set_port_range(core::ports::PortRange::from_settings(
+            store.settings.port_range_start,
+            store.settings.port_range_end,
+        ));
+    }
+
+    let task_state = agent_manager::TaskManagerState::new();
+    workspace::recovery::recover_on_startup(&app_state, &task_state);
+    worktrees::prefetch::spawn_startup_prefetch(&app_state);
+
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
-        .manage(worktrees::init_store())
+        .manage(app_state)
         .manage(agent_manager::OpenCodeManager::new())
-        .manage(agent_manager::TaskManagerState::new())
+        .manage(task_state)
+        .manage(workspace::GlobalStatusCache::new())
+        .manage(worktrees::availability::RepositoryAvailability::new())
         .invoke_handler(tauri::generate_handler![
             // Repository commands
             worktrees::commands::get_repositories,
             worktrees::commands::add_repository,
             worktrees::commands::remove_repository,
+            worktrees::commands::set_repository_scan_settings,
+            worktrees::commands::deep_scan_repository,
+            worktrees::commands::rename_repository,
             worktrees::commands::refresh_repository,
+            worktrees::commands::set_repository_base_branch,
+            worktrees::commands::refresh_default_branch,
+            worktrees::commands::apply_default_branch_change,
+            worktrees::commands::probe_repository_availability,
             // Worktree commands
             worktrees::commands::list_worktrees,
+            worktrees::commands::get_worktree_status,
+            worktrees::commands::get_worktree_changed_files,
+            worktrees::commands::get_worktree_diff,
             worktrees::commands::create_worktree,
             worktrees::commands::remove_worktree,
+            worktrees::commands::archive_worktree,
+            worktrees::commands::list_worktree_archives,
+            worktrees::commands::restore_worktree_archive,
+            worktrees::commands::delete_worktree_archive,
             worktrees::commands::rename_worktree,
+            worktrees::commands::move_worktree,
+            worktrees::commands::duplicate_worktree,
+            worktrees::commands::checkout_in_worktree,
             worktrees::commands::lock_worktree,
             worktrees::commands::unlock_worktree,
+            worktrees::commands::wait_for_unlock,
+            worktrees::commands::hide_worktree,
+            worktrees::commands::unhide_worktree,
+            worktrees::commands::register_dev_url,
+            worktrees::commands::set_worktree_description,
+            worktrees::commands::add_worktree_labels,
+            worktrees::commands::remove_worktree_labels,
+            worktrees::commands::add_repository_labels,
+            worktrees::commands::remove_repository_labels,
+            worktrees::commands::list_labels,
+            worktrees::commands::rename_label,
+            worktrees::commands::delete_label,
+            worktrees::commands::filter_by_labels,
+            worktrees::commands::open_dev_url,
+            worktrees::commands::detect_dev_ports,
+            worktrees::commands::share_dependencies,
+            worktrees::commands::get_processes_in_worktree,
+            worktrees::commands::terminate_worktree_processes,
+            worktrees::commands::get_repo_queue_status,
+            worktrees::commands::force_remove_stale_lock,
+            worktrees::commands::get_worktree_config,
+            worktrees::commands::set_worktree_config,
+            worktrees::commands::get_repo_config_summary,
+            worktrees::commands::list_worktree_files,
+            worktrees::commands::stage_files,
+            worktrees::commands::unstage_files,
+            worktrees::commands::commit_changes,
+            worktrees::commands::fetch_worktree,
+            worktrees::commands::pull_worktree,
+            worktrees::commands::push_worktree,
+            worktrees::commands::trust_ssh_host,
+            worktrees::commands::refresh_remote_protection,
+            worktrees::commands::rebase_worktree,
+            worktrees::commands::continue_rebase,
+            worktrees::commands::abort_rebase,
             worktrees::commands::get_branches,
+            worktrees::commands::get_branch_page,
+            worktrees::commands::cleanup_stale_worktrees,
+            worktrees::commands::get_stale_cleanup_policy,
+            worktrees::commands::set_stale_cleanup_policy,
+            worktrees::commands::suggest_branch_cleanup,
+            worktrees::commands::delete_merged_branches,
             worktrees::commands::get_commits,
+            worktrees::commands::get_merge_base,
+            worktrees::commands::is_ancestor,
+            worktrees::commands::suggest_worktree_name,
+            worktrees::commands::generate_worktree_name,
+            worktrees::commands::preview_rendered_script,
             // System commands
             worktrees::commands::open_in_terminal,
             worktrees::commands::open_in_editor,
@@ -61,12 +178,60 @@ fn main() {
             agent_manager::commands::get_task,
             agent_manager::commands::update_task,
             agent_manager::commands::delete_task,
+            agent_manager::commands::get_task_timeline,
+            agent_manager::commands::get_tasks_loaded,
+            agent_manager::commands::repoint_tasks,
+            agent_manager::commands::list_task_artifacts,
+            agent_manager::commands::cleanup_task_artifacts,
+            agent_manager::commands::set_task_opencode_version,
+            agent_manager::commands::set_task_sandbox,
+            agent_manager::commands::add_task_context_files,
+            agent_manager::commands::remove_task_context_file,
+            agent_manager::commands::get_task_context_files,
+            agent_manager::commands::read_task_scratchpad,
+            agent_manager::commands::append_task_scratchpad,
+            agent_manager::commands::append_agent_transcript,
+            agent_manager::commands::get_agent_transcript,
             agent_manager::commands::add_agent_to_task,
+            agent_manager::commands::fork_agent,
             agent_manager::commands::remove_agent_from_task,
             agent_manager::commands::update_agent_session,
             agent_manager::commands::update_agent_status,
             agent_manager::commands::accept_agent,
             agent_manager::commands::cleanup_unaccepted_agents,
+            // Review commands
+            agent_manager::commands::add_review_comment,
+            agent_manager::commands::list_review_comments,
+            agent_manager::commands::resolve_comment,
+            agent_manager::commands::get_agent_summaries,
+            // Change summary commands
+            agent_manager::commands::generate_change_summary,
+            agent_manager::commands::set_task_result_summary,
+            agent_manager::commands::compute_agent_agreement,
+            agent_manager::commands::export_agent_comparison,
+            agent_manager::commands::get_model_leaderboard,
+            // Write audit commands
+            agent_manager::commands::audit_agent_writes,
+            // Schedule commands
+            agent_manager::commands::create_schedule,
+            agent_manager::commands::list_schedules,
+            agent_manager::commands::pause_schedule,
+            agent_manager::commands::resume_schedule,
+            agent_manager::commands::delete_schedule,
+            agent_manager::commands::save_task_preset,
+            agent_manager::commands::list_task_presets,
+            agent_manager::commands::delete_task_preset,
+            agent_manager::commands::create_task_batch,
+            agent_manager::commands::set_task_pipeline,
+            agent_manager::commands::run_task_pipeline,
+            agent_manager::commands::set_task_auto_accept,
+            agent_manager::commands::cancel_auto_accept,
+            agent_manager::commands::set_task_disk_quota,
+            agent_manager::commands::clear_agent_quota_flag,
+            // Merge commands
+            merge::commands::publish_agent,
+            merge::commands::set_repository_merge_strategy,
+            merge::commands::merge_worktree_into_base,
             // Agent OpenCode commands
             agent_manager::commands::start_agent_opencode,
             agent_manager::commands::stop_agent_opencode,
@@ -77,12 +242,49 @@ fn main() {
             agent_manager::commands::recreate_agent_worktree,
             // Process cleanup commands
             agent_manager::commands::cleanup_orphaned_opencode_processes,
+            // OpenCode binary commands
+            agent_manager::commands::install_opencode,
+            agent_manager::commands::list_installed_opencode_versions,
             // Logger commands
             core::commands::get_log_file_path,
             core::commands::append_to_log_file,
             core::commands::rotate_logs_if_needed,
+            core::commands::test_redaction,
+            core::commands::get_port_reservations,
+            core::commands::release_port_reservations,
+            // API version commands
+            core::commands::get_api_version,
+            core::commands::check_api_compatibility,
+            // App mode commands
+            core::commands::is_read_only_mode,
+            core::commands::list_plugins,
+            core::commands::run_plugin_action,
+            // Notification commands
+            core::commands::get_pending_notifications,
+            // Workspace commands
+            workspace::commands::get_global_status,
+            workspace::commands::save_workspace_snapshot,
+            workspace::commands::load_workspace_snapshot,
+            workspace::commands::list_workspace_snapshots,
+            workspace::commands::delete_workspace_snapshot,
+            workspace::commands::get_recovery_report,
+            workspace::commands::global_search,
         ])
-        .setup(|_app| {
+        .setup(|app| {
+            agent_manager::events::spawn_task_store_load(app.handle().clone());
+            worktrees::events::emit_queue_events(app.handle().clone());
+            worktrees::events::emit_lfs_progress_events(app.handle().clone());
+            worktrees::events::emit_unknown_host_events(app.handle().clone());
+            workspace::events::spawn_status_refresher(app.handle().clone());
+            worktrees::events::spawn_availability_monitor(app.handle().clone());
+            worktrees::events::spawn_stale_cleanup(app.handle().clone());
+            worktrees::events::spawn_lock_expiry_monitor(app.handle().clone());
+            worktrees::events::spawn_branch_cleanup_monitor(app.handle().clone());
+            agent_manager::events::spawn_scheduler(app.handle().clone());
+            agent_manager::events::spawn_auto_accept_loop(app.handle().clone());
+            agent_manager::events::spawn_quota_monitor(app.handle().clone());
+            workspace::events::spawn_event_socket(app.handle().clone());
+            core::notifications::spawn_pending_notification_loop();
             println!("[main] App setup completed");
             Ok(())
         })
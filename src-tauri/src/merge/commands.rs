@@ -0,0 +1,127 @@
+//! Tauri commands for publishing worktree commits and merging worktrees.
+
+use tauri::State;
+
+use crate::agent_manager::agent_operations::accept_agent_impl;
+use crate::agent_manager::task_operations::get_task_impl;
+use crate::agent_manager::TaskManagerState;
+use crate::core::ensure_writable;
+use crate::worktrees::operations::find_git_repo_root;
+use crate::worktrees::store::{AppState, RepositoryKey};
+use crate::worktrees::types::{MergeStrategy, Repository};
+
+use super::operations::{self, publish_worktree, resolve_merge_strategy};
+use super::types::{BaseMergeResult, PublishResult};
+
+/// Publish an agent's commits onto the task's source branch and accept the agent.
+///
+/// `strategy` defaults to the repository's default strategy, then `merge-commit`.
+/// `target_branch` defaults to the task's source branch and is required for
+/// tasks created from a commit.
+#[tauri::command]
+pub async fn publish_agent(
+    app_state: State<'_, AppState>,
+    task_state: State<'_, TaskManagerState>,
+    task_id: String,
+    agent_id: String,
+    strategy: Option<MergeStrategy>,
+    target_branch: Option<String>,
+    message: Option<String>,
+) -> Result<PublishResult, String> {
+    ensure_writable("publish an agent")?;
+
+    let task = get_task_impl(&task_state, &task_id)?;
+    let agent = task
+        .agents
+        .iter()
+        .find(|a| a.id == agent_id)
+        .cloned()
+        .ok_or_else(|| format!("Agent not found: {}", agent_id))?;
+
+    let target_branch = target_branch
+        .or_else(|| task.source_branch.clone())
+        .ok_or("Task was created from a commit; choose a target branch to publish to")?;
+
+    let repo_default = app_state
+        .get_repository(RepositoryKey::Path(&task.source_repo_path))?
+        .and_then(|r| r.default_merge_strategy);
+    let strategy = resolve_merge_strategy(strategy, repo_default);
+
+    let repo_path = task.source_repo_path.clone();
+    let title = task.name.clone();
+    let source_label = format!("{} ({})", agent.id, agent.model_id);
+    let result = tokio::task::spawn_blocking(move || {
+        publish_worktree(
+            &agent.worktree_path,
+            &repo_path,
+            &target_branch,
+            strategy,
+            &title,
+            Some(&source_label),
+            message.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))??;
+
+    accept_agent_impl(&task_state, task_id, agent_id)?;
+    Ok(result)
+}
+
+/// Merge a branch worktree into its repository's base branch.
+///
+/// `strategy` defaults to the repository's default strategy, then `merge-commit`.
+/// `target_branch` defaults to the repository's base branch. Conflicts are
+/// returned in the result (with `merged: false`) rather than as an error.
+#[tauri::command]
+pub async fn merge_worktree_into_base(
+    app_state: State<'_, AppState>,
+    worktree_path: String,
+    strategy: Option<MergeStrategy>,
+    target_branch: Option<String>,
+    message: Option<String>,
+) -> Result<BaseMergeResult, String> {
+    ensure_writable("merge a worktree")?;
+
+    let repo_path = find_git_repo_root(&worktree_path)?;
+    let target_branch = match target_branch {
+        Some(branch) => branch,
+        None => app_state
+            .resolve_base_branch(&repo_path)?
+            .ok_or("Could not determine the base branch; choose a target branch")?,
+    };
+
+    let repo_default = app_state
+        .get_repository(RepositoryKey::Path(&repo_path))?
+        .and_then(|r| r.default_merge_strategy);
+    let strategy = resolve_merge_strategy(strategy, repo_default);
+
+    operations::merge_worktree_into_base_async(
+        worktree_path,
+        repo_path,
+        target_branch,
+        strategy,
+        message,
+    )
+    .await
+}
+
+/// Set (or clear) a repository's default publish strategy.
+#[tauri::command]
+pub fn set_repository_merge_strategy(
+    state: State<AppState>,
+    id: String,
+    strategy: Option<MergeStrategy>,
+) -> Result<Repository, String> {
+    ensure_writable("change the merge strategy")?;
+
+    let repo = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.default_merge_strategy = strategy;
+            repo.clone()
+        })?
+        .ok_or("Repository not found")?;
+
+    state.save()?;
+    Ok(repo)
+}
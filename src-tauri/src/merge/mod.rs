@@ -0,0 +1,8 @@
+//! Merge module - Tauri commands.
+//!
+//! The logic lives in `aristar_core::merge`, re-exported here so commands use
+//! it through `super::`.
+
+pub use aristar_core::merge::*;
+
+pub mod commands;
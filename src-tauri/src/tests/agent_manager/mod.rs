@@ -1,4 +0,0 @@
-//! Agent manager tests.
-
-mod opencode_tests;
-mod task_tests;
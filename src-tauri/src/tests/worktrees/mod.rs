@@ -1,6 +0,0 @@
-//! Worktree tests.
-
-mod integration_tests;
-mod operations_tests;
-mod security_tests;
-mod store_tests;
@@ -0,0 +1,119 @@
+//! Tauri commands for workspace-wide data.
+
+use tauri::State;
+
+use crate::agent_manager::{OpenCodeManager, TaskManagerState};
+use crate::core::ensure_writable;
+use crate::worktrees::store::AppState;
+
+use super::recovery;
+use super::search::{self, DEFAULT_SEARCH_LIMIT};
+use super::snapshots::{
+    capture_snapshot, delete_snapshot, get_snapshot_dir, list_snapshots, read_snapshot,
+    restore_snapshot, write_snapshot, WorkspaceSnapshotInfo, AUTOSAVE_SNAPSHOT,
+};
+use super::status::{compute_global_status, GlobalStatusCache};
+use super::types::{GlobalStatus, RecoveryReport, SearchResult};
+
+/// Get aggregated counters for the status bar.
+#[tauri::command]
+pub fn get_global_status(
+    app_state: State<AppState>,
+    task_state: State<TaskManagerState>,
+    opencode_state: State<OpenCodeManager>,
+    cache: State<GlobalStatusCache>,
+) -> Result<GlobalStatus, String> {
+    compute_global_status(&app_state, &task_state, &opencode_state, &cache)
+}
+
+/// Save the current workspace (store, settings, tasks and the given UI state)
+/// as snapshot `name`, replacing one with the same name.
+#[tauri::command]
+pub fn save_workspace_snapshot(
+    name: String,
+    ui_state: Option<serde_json::Value>,
+    app_state: State<AppState>,
+    task_state: State<TaskManagerState>,
+) -> Result<WorkspaceSnapshotInfo, String> {
+    ensure_writable("save a workspace snapshot")?;
+    let snapshot = capture_snapshot(&name, &app_state, &task_state, ui_state)?;
+    write_snapshot(&get_snapshot_dir(), &snapshot)?;
+    println!("[snapshots] Saved workspace snapshot '{}'", name);
+    Ok(snapshot.info())
+}
+
+/// Load snapshot `name`, saving the current workspace as `autosave` first.
+/// Returns the snapshot's UI state for the frontend to restore.
+#[tauri::command]
+pub fn load_workspace_snapshot(
+    name: String,
+    current_ui_state: Option<serde_json::Value>,
+    app_state: State<AppState>,
+    task_state: State<TaskManagerState>,
+    opencode_state: State<OpenCodeManager>,
+) -> Result<Option<serde_json::Value>, String> {
+    ensure_writable("load a workspace snapshot")?;
+    let running = opencode_state.running_count();
+    if running > 0 {
+        return Err(format!(
+            "Stop the {} running OpenCode server(s) before loading a snapshot",
+            running
+        ));
+    }
+
+    let dir = get_snapshot_dir();
+    let snapshot = read_snapshot(&dir, &name)?;
+    if name != AUTOSAVE_SNAPSHOT {
+        let current =
+            capture_snapshot(AUTOSAVE_SNAPSHOT, &app_state, &task_state, current_ui_state)?;
+        write_snapshot(&dir, &current)?;
+    }
+
+    let ui_state = restore_snapshot(snapshot, &app_state, &task_state)?;
+    app_state.save()?;
+    task_state.save()?;
+    println!("[snapshots] Loaded workspace snapshot '{}'", name);
+    Ok(ui_state)
+}
+
+/// List saved workspace snapshots, newest first.
+#[tauri::command]
+pub fn list_workspace_snapshots() -> Vec<WorkspaceSnapshotInfo> {
+    list_snapshots(&get_snapshot_dir())
+}
+
+/// Delete workspace snapshot `name`.
+#[tauri::command]
+pub fn delete_workspace_snapshot(name: String) -> Result<(), String> {
+    ensure_writable("delete a workspace snapshot")?;
+    delete_snapshot(&get_snapshot_dir(), &name)
+}
+
+/// What startup recovery repaired after the app was killed mid-operation.
+#[tauri::command]
+pub fn get_recovery_report() -> RecoveryReport {
+    recovery::get_recovery_report()
+}
+
+/// Search repositories, worktrees, tasks, presets and schedules, best first.
+#[tauri::command]
+pub async fn global_search(
+    query: String,
+    limit: Option<usize>,
+    app_state: State<'_, AppState>,
+    task_state: State<'_, TaskManagerState>,
+) -> Result<Vec<SearchResult>, String> {
+    let repositories = app_state
+        .store
+        .read()
+        .map_err(|e| e.to_string())?
+        .repositories
+        .clone();
+    let tasks = task_state.store.lock().map_err(|e| e.to_string())?.clone();
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    tokio::task::spawn_blocking(move || {
+        search::global_search(|| repositories, || tasks, &query, limit)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
@@ -0,0 +1,51 @@
+//! Tauri side of the workspace: the status refresher and the event socket's
+//! subscription to app events.
+
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::agent_manager::{OpenCodeManager, TaskManagerState};
+use crate::core::api_version::Versioned;
+use crate::worktrees::store::AppState;
+
+use super::event_socket::{forwarded_events, start_configured_event_socket};
+use super::status::{
+    compute_global_status, GlobalStatusCache, GLOBAL_STATUS_EVENT, REFRESH_INTERVAL,
+};
+
+/// Spawn a background thread that recomputes the global status on a timer
+/// and pushes it to the frontend as a `global-status` event.
+pub fn spawn_status_refresher(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(REFRESH_INTERVAL);
+
+        let status = compute_global_status(
+            &app.state::<AppState>(),
+            &app.state::<TaskManagerState>(),
+            &app.state::<OpenCodeManager>(),
+            &app.state::<GlobalStatusCache>(),
+        );
+
+        match status {
+            Ok(status) => {
+                if let Err(e) = app.emit(GLOBAL_STATUS_EVENT, Versioned::new(status)) {
+                    eprintln!("[workspace] Failed to emit global status: {}", e);
+                }
+            }
+            Err(e) => eprintln!("[workspace] Failed to compute global status: {}", e),
+        }
+    });
+}
+
+/// Start the event socket if configured and forward app events to it.
+pub fn spawn_event_socket(app: AppHandle) {
+    let Some(socket) = start_configured_event_socket(&app.state::<AppState>()) else {
+        return;
+    };
+    for event in forwarded_events() {
+        let socket = socket.clone();
+        app.listen_any(event, move |e| {
+            let payload = serde_json::from_str(e.payload()).unwrap_or(serde_json::Value::Null);
+            socket.broadcast(event, payload);
+        });
+    }
+}
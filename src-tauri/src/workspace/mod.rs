@@ -0,0 +1,10 @@
+//! Workspace module - Tauri commands and the background loops emitting app
+//! events.
+//!
+//! The logic lives in `aristar_core::workspace`, re-exported here so commands
+//! use it through `super::`.
+
+pub use aristar_core::workspace::*;
+
+pub mod commands;
+pub mod events;
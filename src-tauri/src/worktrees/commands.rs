@@ -1,28 +1,107 @@
 //! Tauri commands for worktree operations.
 
 use chrono::Utc;
-use std::path::Path;
-use tauri::State;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
 
+use crate::agent_manager::opencode::OpenCodeManager;
+use crate::core::api_version::Versioned;
+use crate::core::journal::{self, JournalOperation};
 use crate::core::{
-    copy_to_clipboard as core_copy_to_clipboard, reveal_in_finder as core_reveal_in_finder,
+    copy_to_clipboard as core_copy_to_clipboard, ensure_writable, paths_equal, ports,
+    reveal_in_finder as core_reveal_in_finder, SharedDepsMode, StaleCleanupPolicy,
 };
 
+use super::activity;
+use super::aliases;
+use super::archive::{self, WorktreeArchive};
+use super::availability::{apply_probe_result, is_repository_reachable, RepositoryAvailability};
+use super::branch_cleanup::{self, BranchCleanupSuggestion, BranchDeletionReport};
+use super::config::{self, RepoConfigSummary, WorktreeConfigEntry};
+use super::dev_server;
+use super::diff::{self, WorktreeChanges, WorktreeDiff};
+use super::events::{cleanup_stale_worktrees_async, emit_availability_change};
 use super::external_apps::{
     open_in_editor as ext_open_in_editor, open_in_terminal as ext_open_in_terminal,
 };
+use super::files::{self, FileTree};
+use super::github;
+use super::known_hosts::{self, SshHostKey};
+use super::labels::{self, LabelUsage};
+use super::naming;
 use super::operations;
-use super::store::AppState;
-use super::types::{BranchInfo, CommitInfo, Repository, WorktreeInfo};
+use super::prefetch;
+use super::processes::{self, WorktreeProcess};
+use super::repo_queue::{self, RepoQueueStatus};
+use super::shared_deps::{self, SharedDir};
+use super::staging;
+use super::stale::{self, StaleCleanupReport};
+use super::store::{AppState, RepositoryKey};
+use super::sync::{self, SyncResult};
+use super::templates::{self, ScriptTemplateContext};
+use super::types::{
+    normalize_worktree_description, BranchInfo, BranchPage, BranchSort, CommitInfo,
+    CreateWorktreeOptions, DeepScanResult, DefaultBranchChange, RebaseResult, RenameWorktreeError,
+    RepoScanSettings, Repository, WorktreeInfo, WorktreeStatus, DEFAULT_BRANCH_EVENT,
+};
+
+fn emit_default_branch_change(app: &AppHandle, change: DefaultBranchChange) {
+    println!(
+        "[worktrees] Default branch of repository {} changed from {} to {}",
+        change.repository_id, change.previous, change.current
+    );
+    if let Err(e) = app.emit(DEFAULT_BRANCH_EVENT, Versioned::new(change)) {
+        eprintln!("[worktrees] Failed to emit default branch change: {}", e);
+    }
+}
+
+fn aliases_enabled(state: &AppState) -> Result<bool, String> {
+    let store = state.store.read().map_err(|e| e.to_string())?;
+    Ok(store.settings.worktree_aliases)
+}
+
+/// Display name of the repository at `repo_path`, else its folder name.
+fn repository_display_name(state: &AppState, repo_path: &str) -> Result<String, String> {
+    Ok(state
+        .get_repository(RepositoryKey::Path(repo_path))?
+        .map(|r| r.display_name().to_string())
+        .unwrap_or_else(|| operations::get_repository_name(repo_path)))
+}
+
+/// A repository as returned to listings, with hidden worktrees left out
+/// unless `include_hidden` is set.
+fn listed(repo: Repository, include_hidden: Option<bool>) -> Repository {
+    if include_hidden.unwrap_or(false) {
+        repo
+    } else {
+        repo.without_hidden_worktrees()
+    }
+}
 
 #[tauri::command]
-pub fn get_repositories(state: State<AppState>) -> Result<Vec<Repository>, String> {
+pub fn get_repositories(
+    state: State<AppState>,
+    include_hidden: Option<bool>,
+) -> Result<Vec<Repository>, String> {
     let store = state.store.read().map_err(|e| e.to_string())?;
-    Ok(store.repositories.clone())
+    Ok(store
+        .repositories
+        .iter()
+        .map(|repo| listed(repo.clone(), include_hidden))
+        .collect())
 }
 
+/// Add a repository. Without `scan_settings`, large repositories get lighter
+/// ones; the commit history is then prefetched in the background.
 #[tauri::command]
-pub fn add_repository(state: State<AppState>, path: String) -> Result<Repository, String> {
+pub fn add_repository(
+    state: State<AppState>,
+    path: String,
+    scan_settings: Option<RepoScanSettings>,
+) -> Result<Repository, String> {
+    ensure_writable("add a repository")?;
+
     println!("[add_repository] Called with path: {}", path);
 
     let path_obj = Path::new(&path);
@@ -49,149 +128,800 @@ pub fn add_repository(state: State<AppState>, path: String) -> Result<Repository
         id: uuid::Uuid::new_v4().to_string(),
         path: abs_path.clone(),
         name: operations::get_repository_name(&abs_path),
+        display_name: None,
         worktrees,
         last_scanned: Utc::now().timestamp_millis(),
+        unavailable_since: None,
+        default_merge_strategy: None,
+        default_base_branch: None,
+        detected_base_branch: operations::detect_base_branch(&abs_path),
+        remote_default_branch: operations::get_default_branch(&abs_path),
+        protected_branches: vec![],
+        remote_protected_branches: vec![],
+        remote_protection_checked_at: None,
+        scan_settings: scan_settings.unwrap_or_else(|| prefetch::default_scan_settings(&abs_path)),
+        labels: vec![],
     };
 
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        if store.repositories.iter().any(|r| r.path == repo.path) {
-            return Err("Repository already added".to_string());
-        }
-        store.repositories.push(repo.clone());
-    }
+    state.insert_repository(repo.clone())?;
 
     state.save()?;
+
+    prefetch::spawn_prefetcher(vec![(repo.path.clone(), repo.scan_settings.clone())]);
     Ok(repo)
 }
 
 #[tauri::command]
 pub fn remove_repository(state: State<AppState>, id: String) -> Result<(), String> {
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        store.repositories.retain(|r| r.id != id);
-    }
+    ensure_writable("remove a repository")?;
+
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path);
+    state.remove_repository(RepositoryKey::Id(&id))?;
 
     state.save()?;
+
+    if let Some(repo_path) = repo_path {
+        prefetch::invalidate_history_cache(&repo_path);
+    }
     Ok(())
 }
 
+/// Re-scan a repository's worktrees.
+/// An unreachable repository is marked unavailable and returned unchanged
+/// instead of having its worktrees dropped. Hidden worktrees are kept (and
+/// left out of the result unless `include_hidden` is set).
 #[tauri::command]
-pub fn refresh_repository(state: State<AppState>, id: String) -> Result<Repository, String> {
-    let repo = {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        if let Some(repo) = store.repositories.iter_mut().find(|r| r.id == id) {
-            let worktrees = operations::list_worktrees(&repo.path)?;
-            repo.worktrees = worktrees;
+pub fn refresh_repository(
+    app: AppHandle,
+    state: State<AppState>,
+    availability: State<RepositoryAvailability>,
+    id: String,
+    include_hidden: Option<bool>,
+) -> Result<Repository, String> {
+    ensure_writable("refresh a repository")?;
+
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path)
+        .ok_or("Repository not found")?;
+
+    let now = Utc::now().timestamp_millis();
+    let reachable = is_repository_reachable(&repo_path);
+    if let Some(event) = apply_probe_result(&state, &availability, &id, reachable, now)? {
+        emit_availability_change(&app, event);
+    }
+    if !reachable {
+        return state
+            .get_repository(RepositoryKey::Id(&id))?
+            .map(|repo| listed(repo, include_hidden))
+            .ok_or_else(|| "Repository not found".to_string());
+    }
+
+    let worktrees = operations::list_worktrees(&repo_path)?;
+    let detected_base_branch = operations::detect_base_branch(&repo_path);
+    let remote_default_branch = operations::get_default_branch(&repo_path);
+
+    let (repo, change) = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.replace_worktrees(worktrees);
+            repo.detected_base_branch = detected_base_branch;
             repo.last_scanned = Utc::now().timestamp_millis();
+            let change = repo.record_remote_default_branch(remote_default_branch);
+            (repo.clone(), change)
+        })?
+        .ok_or("Repository not found")?;
+
+    state.save()?;
+
+    if let Some(change) = change {
+        emit_default_branch_change(&app, change);
+    }
+
+    if aliases_enabled(&state)? {
+        if let Err(e) = aliases::sync_repository_aliases(&repo) {
+            eprintln!("[worktrees] Failed to sync aliases: {}", e);
+        }
+    }
+    Ok(listed(repo, include_hidden))
+}
+
+/// Set (or clear with `None`) the name a repository is shown with. Worktree
+/// aliases are moved to the new name.
+#[tauri::command]
+pub fn rename_repository(
+    state: State<AppState>,
+    id: String,
+    display_name: Option<String>,
+) -> Result<Repository, String> {
+    ensure_writable("rename a repository")?;
+
+    let repo = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.set_display_name(display_name.as_deref())
+                .map(|_| repo.clone())
+        })?
+        .ok_or("Repository not found")??;
+
+    state.save()?;
+
+    for worktree in repo.worktrees.iter().filter(|w| !w.is_main) {
+        if let Err(e) = aliases::remove_worktree_alias(&worktree.path) {
+            eprintln!("[worktrees] {}", e);
+        }
+    }
+    if aliases_enabled(&state)? {
+        if let Err(e) = aliases::sync_repository_aliases(&repo) {
+            eprintln!("[worktrees] Failed to sync aliases: {}", e);
+        }
+    }
+    Ok(repo)
+}
+
+/// Set (or clear, falling back to the detected branch) a repository's default
+/// base branch for new worktrees and tasks.
+#[tauri::command]
+pub fn set_repository_base_branch(
+    state: State<AppState>,
+    id: String,
+    branch: Option<String>,
+) -> Result<Repository, String> {
+    ensure_writable("change the base branch")?;
+
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path)
+        .ok_or("Repository not found")?;
+
+    let branch = branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+    if let Some(ref branch) = branch {
+        if branch.starts_with('-') {
+            return Err(format!("Invalid branch name: {}", branch));
+        }
+        operations::run_git_command(&["rev-parse", "--verify", "--quiet", branch], &repo_path)
+            .map_err(|_| format!("Branch not found: {}", branch))?;
+    }
+
+    let repo = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.default_base_branch = branch;
             repo.clone()
-        } else {
-            return Err("Repository not found".to_string());
+        })?
+        .ok_or("Repository not found")?;
+
+    state.save()?;
+    Ok(repo)
+}
+
+/// Change how much of a repository is scanned ahead of time, and prefetch
+/// with the new settings.
+#[tauri::command]
+pub fn set_repository_scan_settings(
+    state: State<AppState>,
+    id: String,
+    scan_settings: RepoScanSettings,
+) -> Result<Repository, String> {
+    ensure_writable("change the scan settings")?;
+
+    let repo = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.scan_settings = scan_settings;
+            repo.clone()
+        })?
+        .ok_or("Repository not found")?;
+
+    state.save()?;
+
+    prefetch::spawn_prefetcher(vec![(repo.path.clone(), repo.scan_settings.clone())]);
+    Ok(repo)
+}
+
+/// Read the commit history deeply and every worktree's status now,
+/// regardless of the repository's scan settings.
+#[tauri::command]
+pub async fn deep_scan_repository(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<DeepScanResult, String> {
+    let repo = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .ok_or("Repository not found")?;
+    let worktree_paths = repo.worktrees.iter().map(|w| w.path.clone()).collect();
+
+    prefetch::deep_scan_repository_async(repo.path, worktree_paths).await
+}
+
+/// Ask the remote for its default branch, updating `origin/HEAD`, and report
+/// whether it changed since last seen. A change is also emitted as
+/// `default-branch-changed`. Falls back to the local heuristics when the
+/// remote can't be reached.
+#[tauri::command]
+pub async fn refresh_default_branch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<DefaultBranchChange>, String> {
+    ensure_writable("refresh the default branch")?;
+
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path)
+        .ok_or("Repository not found")?;
+
+    let (default_branch, base_branch) = tokio::task::spawn_blocking(move || {
+        if let Err(e) = operations::update_remote_head(&repo_path) {
+            eprintln!("[worktrees] Failed to query remote default branch: {}", e);
         }
-    };
+        (
+            operations::get_default_branch(&repo_path),
+            operations::detect_base_branch(&repo_path),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    let change = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.detected_base_branch = base_branch;
+            repo.record_remote_default_branch(default_branch)
+        })?
+        .ok_or("Repository not found")?;
+
+    state.save()?;
+
+    if let Some(ref change) = change {
+        emit_default_branch_change(&app, change.clone());
+    }
+    Ok(change)
+}
+
+/// Follow a remote default branch change: protect the new branch from deletion
+/// and move a default base branch that pointed at the old one.
+#[tauri::command]
+pub fn apply_default_branch_change(
+    state: State<AppState>,
+    id: String,
+    previous: String,
+    current: String,
+) -> Result<Repository, String> {
+    ensure_writable("update the default branch")?;
+
+    let repo = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            repo.apply_default_branch_change(&previous, &current);
+            repo.clone()
+        })?
+        .ok_or("Repository not found")?;
 
     state.save()?;
     Ok(repo)
 }
 
+/// Probe a repository's availability now, skipping any pending backoff delay.
+#[tauri::command]
+pub fn probe_repository_availability(
+    app: AppHandle,
+    state: State<AppState>,
+    availability: State<RepositoryAvailability>,
+    id: String,
+) -> Result<Repository, String> {
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path)
+        .ok_or("Repository not found")?;
+
+    availability.reset(&id);
+    let now = Utc::now().timestamp_millis();
+    let reachable = is_repository_reachable(&repo_path);
+    if let Some(event) = apply_probe_result(&state, &availability, &id, reachable, now)? {
+        emit_availability_change(&app, event);
+    }
+
+    state
+        .get_repository(RepositoryKey::Id(&id))?
+        .ok_or_else(|| "Repository not found".to_string())
+}
+
 #[tauri::command]
 pub async fn list_worktrees(repo_path: String) -> Result<Vec<WorktreeInfo>, String> {
     operations::list_worktrees_async(repo_path).await
 }
 
+/// Changed file counts and ahead/behind of a worktree, e.g. to warn before removal.
+#[tauri::command]
+pub async fn get_worktree_status(path: String) -> Result<WorktreeStatus, String> {
+    operations::get_worktree_status_async(path).await
+}
+
+/// `base_ref`, or the base branch of the worktree's repository.
+fn resolve_diff_base(
+    state: &AppState,
+    path: &str,
+    base_ref: Option<String>,
+) -> Result<String, String> {
+    if let Some(base_ref) = base_ref.filter(|r| !r.trim().is_empty()) {
+        return Ok(base_ref);
+    }
+    let repo_path = operations::find_git_repo_root(path)?;
+    state
+        .resolve_base_branch(&repo_path)?
+        .ok_or_else(|| "No base branch found; pass a base ref".to_string())
+}
+
+/// Files a worktree changed since it left `base_ref` (default: the
+/// repository's base branch), including uncommitted and untracked ones unless
+/// `include_uncommitted` is false.
+#[tauri::command]
+pub async fn get_worktree_changed_files(
+    state: State<'_, AppState>,
+    path: String,
+    base_ref: Option<String>,
+    include_uncommitted: Option<bool>,
+) -> Result<WorktreeChanges, String> {
+    let base_ref = resolve_diff_base(&state, &path, base_ref)?;
+    diff::get_changed_files_async(path, base_ref, include_uncommitted.unwrap_or(true)).await
+}
+
+/// Unified diff of a worktree since it left `base_ref`, limited to `paths`
+/// when given. Same defaults as `get_worktree_changed_files`.
 #[tauri::command]
+pub async fn get_worktree_diff(
+    state: State<'_, AppState>,
+    path: String,
+    base_ref: Option<String>,
+    paths: Option<Vec<String>>,
+    include_uncommitted: Option<bool>,
+) -> Result<WorktreeDiff, String> {
+    let base_ref = resolve_diff_base(&state, &path, base_ref)?;
+    diff::get_diff_async(
+        path,
+        base_ref,
+        paths.unwrap_or_default(),
+        include_uncommitted.unwrap_or(true),
+    )
+    .await
+}
+
+/// Create a worktree. `new_branch` creates that branch from `base_ref`
+/// (default: the repository's base branch). With `auto_suffix`, a name whose
+/// folder exists gets `-2`, `-3`, ...; the returned `name` is the one used.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_worktree(
     state: State<'_, AppState>,
     repo_path: String,
-    name: String,
+    name: Option<String>,
     branch: Option<String>,
     commit: Option<String>,
+    new_branch: Option<String>,
+    base_ref: Option<String>,
     startup_script: Option<String>,
     execute_script: bool,
+    auto_suffix: Option<bool>,
 ) -> Result<WorktreeInfo, String> {
+    ensure_writable("create a worktree")?;
+
+    let name = match name.filter(|n| !n.trim().is_empty()) {
+        Some(name) if auto_suffix.unwrap_or(false) => {
+            naming::resolve_worktree_name_async(repo_path.clone(), name).await?
+        }
+        Some(name) => name,
+        None => {
+            let source = new_branch
+                .clone()
+                .or_else(|| branch.clone())
+                .or_else(|| commit.as_ref().map(|c| c.chars().take(7).collect()));
+            generated_worktree_name(&state, repo_path.clone(), source).await?
+        }
+    };
+
+    // A commit without a branch is checked out detached; a new branch starts at
+    // `base_ref`, else the commit, else the repository's base branch
+    let detach = branch.is_none() && new_branch.is_none() && commit.is_some();
+    let start_point = match (&branch, detach) {
+        (Some(_), _) => None,
+        (None, true) => commit,
+        (None, false) => match base_ref.or(commit) {
+            Some(start_point) => Some(start_point),
+            None => state.resolve_base_branch(&repo_path)?,
+        },
+    };
     let new_worktree = operations::create_worktree_async(
         repo_path.clone(),
         name,
-        branch,
-        commit,
-        startup_script,
-        execute_script,
+        CreateWorktreeOptions {
+            branch,
+            new_branch,
+            start_point,
+            detach,
+            startup_script,
+            execute_script,
+        },
     )
     .await?;
 
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        if let Some(repo) = store.repositories.iter_mut().find(|r| r.path == repo_path) {
-            if !repo.worktrees.iter().any(|w| w.path == new_worktree.path) {
-                repo.worktrees.push(new_worktree.clone());
-            }
+    state.update_repository(RepositoryKey::Path(&repo_path), |repo| {
+        if !repo
+            .worktrees
+            .iter()
+            .any(|w| paths_equal(&w.path, &new_worktree.path))
+        {
+            repo.worktrees.push(new_worktree.clone());
         }
-    }
+    })?;
 
     state.save()?;
+
+    if aliases_enabled(&state)? {
+        let repo_name = repository_display_name(&state, &repo_path)?;
+        if let Err(e) = aliases::create_worktree_alias(&repo_path, &repo_name, &new_worktree.path) {
+            eprintln!("[worktrees] {}", e);
+        }
+    }
     Ok(new_worktree)
 }
 
+/// Remove a worktree. With `terminate_processes`, the processes running inside
+/// it are stopped first; any still running after the timeout are killed with
+/// `force`, else the removal fails. With `archive`, its tracked and modified
+/// files are archived first (see `archive_worktree`).
 #[tauri::command]
 pub async fn remove_worktree(
     state: State<'_, AppState>,
     path: String,
     force: bool,
     delete_branch: bool,
+    terminate_processes: Option<bool>,
+    archive: Option<bool>,
 ) -> Result<(), String> {
-    operations::remove_worktree_async(path.clone(), force, delete_branch).await?;
+    ensure_writable("remove a worktree")?;
 
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        for repo in &mut store.repositories {
-            repo.worktrees.retain(|w| w.path != path);
+    if terminate_processes.unwrap_or(false) {
+        let remaining =
+            processes::terminate_and_wait_async(path.clone(), processes::TERMINATE_TIMEOUT).await?;
+        if !remaining.is_empty() {
+            if !force {
+                let names: Vec<String> = remaining
+                    .iter()
+                    .map(|p| format!("{} ({})", p.command, p.pid))
+                    .collect();
+                return Err(format!(
+                    "Processes still running in the worktree: {}",
+                    names.join(", ")
+                ));
+            }
+            let pids: Vec<u32> = remaining.iter().map(|p| p.pid).collect();
+            processes::terminate_worktree_processes_async(path.clone(), Some(pids), true).await?;
         }
     }
 
+    if archive.unwrap_or(false) {
+        archive::archive_worktree_async(path.clone(), true).await?;
+    }
+
+    let delete_branch = delete_branch && !state.is_protected_worktree_branch(&path)?;
+    // Finished at startup if the app dies before the store is updated
+    let _intent = journal::record_intent(JournalOperation::RemoveWorktree {
+        repo_path: operations::find_git_repo_root(&path)?,
+        worktree_path: path.clone(),
+    })?;
+    operations::remove_worktree_async(path.clone(), force, delete_branch).await?;
+
+    state.remove_worktree_entry(&path)?;
+
     state.save()?;
+
+    // Clean up even when aliases are disabled, in case they were enabled earlier
+    if let Err(e) = aliases::remove_worktree_alias(&path) {
+        eprintln!("[worktrees] {}", e);
+    }
+    if let Err(e) = ports::release_ports(&path, None) {
+        eprintln!("[worktrees] Failed to release ports: {}", e);
+    }
     Ok(())
 }
 
+/// Find worktrees unused for longer than `policy` allows (default: the stored
+/// policy) and, with `remove`, remove those it allows. Without `remove` the
+/// stale worktrees are only reported.
+#[tauri::command]
+pub async fn cleanup_stale_worktrees(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    policy: Option<StaleCleanupPolicy>,
+    remove: Option<bool>,
+) -> Result<StaleCleanupReport, String> {
+    let remove = remove.unwrap_or(false);
+    if remove {
+        ensure_writable("remove stale worktrees")?;
+    }
+    let policy = match policy {
+        Some(policy) => policy,
+        None => get_stale_cleanup_policy(state)?,
+    };
+    cleanup_stale_worktrees_async(app, policy, remove).await
+}
+
+/// The stored stale worktree cleanup policy.
+#[tauri::command]
+pub fn get_stale_cleanup_policy(state: State<AppState>) -> Result<StaleCleanupPolicy, String> {
+    Ok(state
+        .store
+        .read()
+        .map_err(|e| e.to_string())?
+        .settings
+        .stale_cleanup
+        .clone())
+}
+
+/// Store the stale worktree cleanup policy, used by the scheduled cleanup and
+/// by `cleanup_stale_worktrees` without a policy.
+#[tauri::command]
+pub fn set_stale_cleanup_policy(
+    state: State<AppState>,
+    policy: StaleCleanupPolicy,
+) -> Result<StaleCleanupPolicy, String> {
+    ensure_writable("change the stale worktree policy")?;
+    stale::validate_stale_cleanup_policy(&policy)?;
+
+    state
+        .store
+        .write()
+        .map_err(|e| e.to_string())?
+        .settings
+        .stale_cleanup = policy.clone();
+    state.save()?;
+    Ok(policy)
+}
+
+/// Local branches of a repository merged into its default branch that no
+/// worktree has checked out, with their last commit.
+#[tauri::command]
+pub async fn suggest_branch_cleanup(
+    state: State<'_, AppState>,
+    repo_path: String,
+) -> Result<BranchCleanupSuggestion, String> {
+    let repository = state.get_repository(RepositoryKey::Path(&repo_path))?;
+    branch_cleanup::suggest_branch_cleanup_async(repo_path, repository).await
+}
+
+/// Delete the branches of `confirm_list` that are still suggested for cleanup;
+/// the others are reported as skipped.
+#[tauri::command]
+pub async fn delete_merged_branches(
+    state: State<'_, AppState>,
+    repo_path: String,
+    confirm_list: Vec<String>,
+) -> Result<BranchDeletionReport, String> {
+    ensure_writable("delete merged branches")?;
+    let repository = state.get_repository(RepositoryKey::Path(&repo_path))?;
+    let report =
+        branch_cleanup::delete_merged_branches_async(repo_path, repository, confirm_list).await?;
+    if !report.deleted.is_empty() {
+        println!(
+            "[branch_cleanup] Deleted {} merged branch(es)",
+            report.deleted.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Pack a worktree into `~/.aristar-worktrees/archives/`, e.g. before removing
+/// it. With `tracked_only`, ignored files (dependencies, build output) are left out.
+#[tauri::command]
+pub async fn archive_worktree(
+    path: String,
+    tracked_only: Option<bool>,
+) -> Result<WorktreeArchive, String> {
+    ensure_writable("archive a worktree")?;
+    archive::archive_worktree_async(path, tracked_only.unwrap_or(false)).await
+}
+
+/// Archived worktrees, newest first.
+#[tauri::command]
+pub fn list_worktree_archives() -> Vec<WorktreeArchive> {
+    archive::list_archives_in(&archive::get_archives_dir())
+}
+
+/// Restore an archived worktree to `target_path` (default: its original path)
+/// and add it back to its repository. Returns the restored path.
+#[tauri::command]
+pub async fn restore_worktree_archive(
+    state: State<'_, AppState>,
+    id: String,
+    target_path: Option<String>,
+) -> Result<String, String> {
+    ensure_writable("restore a worktree")?;
+
+    let path = archive::restore_archive_async(id, target_path).await?;
+
+    // A plain folder when the repository was gone
+    let Ok(repo_path) = operations::find_git_repo_root(&path) else {
+        return Ok(path);
+    };
+    let restored = operations::list_worktrees_async(repo_path.clone())
+        .await?
+        .into_iter()
+        .find(|w| paths_equal(&w.path, &path));
+    if let Some(restored) = restored {
+        state.update_repository(RepositoryKey::Path(&repo_path), |repo| {
+            if !repo.worktrees.iter().any(|w| paths_equal(&w.path, &path)) {
+                repo.worktrees.push(restored);
+            }
+        })?;
+        state.save()?;
+    }
+    Ok(path)
+}
+
+/// Delete an archive.
+#[tauri::command]
+pub fn delete_worktree_archive(id: String) -> Result<(), String> {
+    ensure_writable("delete an archive")?;
+    archive::delete_archive_in(&archive::get_archives_dir(), &id)
+}
+
+/// Rename a worktree. Fails with a `RenameWorktreeError` telling apart a taken
+/// name, running processes and a busy directory. With `stop_processes`, the
+/// worktree's OpenCode server and other processes are stopped first.
 #[tauri::command]
 pub async fn rename_worktree(
     state: State<'_, AppState>,
+    opencode_state: State<'_, OpenCodeManager>,
     old_path: String,
     new_name: String,
+    stop_processes: Option<bool>,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    ensure_writable("rename a worktree")?;
+
+    let stop_processes = stop_processes.unwrap_or(false);
+    let opencode_path = PathBuf::from(&old_path);
+    if stop_processes && opencode_state.is_running(&opencode_path) {
+        opencode_state.stop(&opencode_path)?;
+    }
+
+    let renamed_worktree =
+        operations::rename_worktree_async(old_path.clone(), new_name, stop_processes).await?;
+    Ok(record_worktree_move(&state, &old_path, renamed_worktree)?)
+}
+
+/// Move a worktree to `new_path`, possibly in another directory within the
+/// allowed worktree bases.
+#[tauri::command]
+pub async fn move_worktree(
+    state: State<'_, AppState>,
+    opencode_state: State<'_, OpenCodeManager>,
+    old_path: String,
+    new_path: String,
+    stop_processes: Option<bool>,
+) -> Result<WorktreeInfo, RenameWorktreeError> {
+    ensure_writable("move a worktree")?;
+
+    let stop_processes = stop_processes.unwrap_or(false);
+    let opencode_path = PathBuf::from(&old_path);
+    if stop_processes && opencode_state.is_running(&opencode_path) {
+        opencode_state.stop(&opencode_path)?;
+    }
+
+    let moved_worktree =
+        operations::move_worktree_async(old_path.clone(), new_path, stop_processes).await?;
+    Ok(record_worktree_move(&state, &old_path, moved_worktree)?)
+}
+
+/// Store a worktree that moved away from `old_path`, keeping what only the
+/// store knows about it, and move its alias along.
+fn record_worktree_move(
+    state: &AppState,
+    old_path: &str,
+    moved: WorktreeInfo,
 ) -> Result<WorktreeInfo, String> {
-    let renamed_worktree = operations::rename_worktree_async(old_path.clone(), new_name).await?;
+    let mut stored = None;
+    state.update_worktree(old_path, |wt| {
+        *wt = WorktreeInfo {
+            hidden: wt.hidden,
+            dev_url: wt.dev_url.take(),
+            description: wt.description.take(),
+            labels: std::mem::take(&mut wt.labels),
+            last_active: wt.last_active.max(moved.last_active),
+            ..moved.clone()
+        };
+        stored = Some(wt.clone());
+    })?;
+    let moved = stored.unwrap_or(moved);
 
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        for repo in &mut store.repositories {
-            if let Some(idx) = repo.worktrees.iter().position(|w| w.path == old_path) {
-                repo.worktrees[idx] = renamed_worktree.clone();
-                break;
-            }
+    state.save()?;
+
+    if let Err(e) = aliases::remove_worktree_alias(old_path) {
+        eprintln!("[worktrees] {}", e);
+    }
+    if aliases_enabled(state)? {
+        let repo_path = operations::find_git_repo_root(&moved.path)?;
+        let repo_name = repository_display_name(state, &repo_path)?;
+        if let Err(e) = aliases::create_worktree_alias(&repo_path, &repo_name, &moved.path) {
+            eprintln!("[worktrees] {}", e);
         }
     }
+    Ok(moved)
+}
+
+/// Switch a worktree to another branch or ref in place. Refuses while the
+/// worktree has uncommitted changes or a rebase in progress.
+#[tauri::command]
+pub async fn checkout_in_worktree(
+    state: State<'_, AppState>,
+    path: String,
+    git_ref: String,
+) -> Result<WorktreeInfo, String> {
+    ensure_writable("switch a worktree's branch")?;
+
+    let updated = operations::checkout_in_worktree_async(path.clone(), git_ref).await?;
+
+    state.update_worktree(&path, |wt| {
+        wt.branch = updated.branch.clone();
+        wt.commit = updated.commit.clone();
+        wt.head_state = updated.head_state.clone();
+        wt.is_detached = updated.is_detached;
+    })?;
 
     state.save()?;
-    Ok(renamed_worktree)
+    Ok(state.get_worktree(&path)?.unwrap_or(updated))
 }
 
+/// Create a worktree at the same commit as `path`, with its uncommitted
+/// changes copied across.
+#[tauri::command]
+pub async fn duplicate_worktree(
+    state: State<'_, AppState>,
+    path: String,
+    new_name: String,
+) -> Result<WorktreeInfo, String> {
+    ensure_writable("duplicate a worktree")?;
+
+    let new_worktree = operations::duplicate_worktree_async(path, new_name).await?;
+    let repo_path = operations::find_git_repo_root(&new_worktree.path)?;
+
+    state.update_repository(RepositoryKey::Path(&repo_path), |repo| {
+        if !repo
+            .worktrees
+            .iter()
+            .any(|w| paths_equal(&w.path, &new_worktree.path))
+        {
+            repo.worktrees.push(new_worktree.clone());
+        }
+    })?;
+
+    state.save()?;
+
+    if aliases_enabled(&state)? {
+        let repo_name = repository_display_name(&state, &repo_path)?;
+        if let Err(e) = aliases::create_worktree_alias(&repo_path, &repo_name, &new_worktree.path) {
+            eprintln!("[worktrees] {}", e);
+        }
+    }
+    Ok(new_worktree)
+}
+
+/// Lock a worktree. `expires_at` (milliseconds) has the app lift the lock
+/// once that time has passed.
 #[tauri::command]
 pub fn lock_worktree(
     state: State<AppState>,
     path: String,
     reason: Option<String>,
+    expires_at: Option<i64>,
 ) -> Result<(), String> {
-    operations::lock_worktree(&path, reason.as_deref())?;
+    ensure_writable("lock a worktree")?;
 
-    {
-        let mut store = state.store.write().map_err(|e| e.to_string())?;
-        for repo in &mut store.repositories {
-            if let Some(wt) = repo.worktrees.iter_mut().find(|w| w.path == path) {
-                wt.is_locked = true;
-                wt.lock_reason = reason.clone();
-                break;
-            }
-        }
-    }
+    operations::lock_worktree(&path, reason.as_deref(), expires_at)?;
+
+    state.update_worktree(&path, |wt| {
+        wt.is_locked = true;
+        wt.lock_reason = reason.clone();
+        wt.lock_expires_at = expires_at;
+    })?;
 
     state.save()?;
     Ok(())
@@ -199,21 +929,499 @@ pub fn lock_worktree(
 
 #[tauri::command]
 pub fn unlock_worktree(state: State<AppState>, path: String) -> Result<(), String> {
+    ensure_writable("unlock a worktree")?;
+
     operations::unlock_worktree(&path)?;
 
-    {
+    state.update_worktree(&path, |wt| {
+        wt.is_locked = false;
+        wt.lock_reason = None;
+        wt.lock_expires_at = None;
+    })?;
+
+    state.save()?;
+    Ok(())
+}
+
+fn set_worktree_hidden(state: &AppState, path: &str, hidden: bool) -> Result<(), String> {
+    let mut is_main = false;
+    let found = state.update_worktree(path, |wt| {
+        is_main = wt.is_main;
+        if !wt.is_main {
+            wt.hidden = hidden;
+        }
+    })?;
+    if !found {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    if is_main && hidden {
+        return Err("The main worktree can't be hidden".to_string());
+    }
+
+    state.save()
+}
+
+/// Leave a worktree out of listings. `list_worktrees` and the global status
+/// still include it, so it shows up when cleaning up.
+#[tauri::command]
+pub fn hide_worktree(state: State<AppState>, path: String) -> Result<(), String> {
+    ensure_writable("hide a worktree")?;
+    set_worktree_hidden(&state, &path, true)
+}
+
+#[tauri::command]
+pub fn unhide_worktree(state: State<AppState>, path: String) -> Result<(), String> {
+    ensure_writable("unhide a worktree")?;
+    set_worktree_hidden(&state, &path, false)
+}
+
+/// Register the URL of the app a worktree serves; `None` or a blank URL clears it.
+#[tauri::command]
+pub fn register_dev_url(
+    state: State<AppState>,
+    path: String,
+    url: Option<String>,
+) -> Result<(), String> {
+    ensure_writable("register a dev URL")?;
+
+    let url = url
+        .filter(|u| !u.trim().is_empty())
+        .map(|u| dev_server::validate_dev_url(&u))
+        .transpose()?;
+    if !state.update_worktree(&path, |wt| wt.dev_url = url.clone())? {
+        return Err(format!("Worktree not found: {}", path));
+    }
+
+    state.save()
+}
+
+/// Record why a worktree exists. The description is kept in the app store, not
+/// in git; `None` or a blank one clears it.
+#[tauri::command]
+pub fn set_worktree_description(
+    state: State<AppState>,
+    path: String,
+    description: Option<String>,
+) -> Result<(), String> {
+    ensure_writable("set a worktree description")?;
+
+    let description = normalize_worktree_description(description.as_deref())?;
+    if !state.update_worktree(&path, |wt| wt.description = description.clone())? {
+        return Err(format!("Worktree not found: {}", path));
+    }
+
+    state.save()
+}
+
+/// Add labels to a worktree. Returns its labels.
+#[tauri::command]
+pub fn add_worktree_labels(
+    state: State<AppState>,
+    path: String,
+    labels: Vec<String>,
+) -> Result<Vec<String>, String> {
+    ensure_writable("label a worktree")?;
+
+    let mut result = Ok(Vec::new());
+    if !state.update_worktree(&path, |wt| {
+        result = labels::add_labels(&mut wt.labels, &labels).map(|_| wt.labels.clone());
+    })? {
+        return Err(format!("Worktree not found: {}", path));
+    }
+    let labels = result?;
+
+    state.save()?;
+    Ok(labels)
+}
+
+/// Remove labels from a worktree. Returns its labels.
+#[tauri::command]
+pub fn remove_worktree_labels(
+    state: State<AppState>,
+    path: String,
+    labels: Vec<String>,
+) -> Result<Vec<String>, String> {
+    ensure_writable("unlabel a worktree")?;
+
+    let mut remaining = Vec::new();
+    if !state.update_worktree(&path, |wt| {
+        labels::remove_labels(&mut wt.labels, &labels);
+        remaining = wt.labels.clone();
+    })? {
+        return Err(format!("Worktree not found: {}", path));
+    }
+
+    state.save()?;
+    Ok(remaining)
+}
+
+/// Add labels to a repository. Returns its labels.
+#[tauri::command]
+pub fn add_repository_labels(
+    state: State<AppState>,
+    id: String,
+    labels: Vec<String>,
+) -> Result<Vec<String>, String> {
+    ensure_writable("label a repository")?;
+
+    let labels = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            labels::add_labels(&mut repo.labels, &labels).map(|_| repo.labels.clone())
+        })?
+        .ok_or_else(|| format!("Repository not found: {}", id))??;
+
+    state.save()?;
+    Ok(labels)
+}
+
+/// Remove labels from a repository. Returns its labels.
+#[tauri::command]
+pub fn remove_repository_labels(
+    state: State<AppState>,
+    id: String,
+    labels: Vec<String>,
+) -> Result<Vec<String>, String> {
+    ensure_writable("unlabel a repository")?;
+
+    let remaining = state
+        .update_repository(RepositoryKey::Id(&id), |repo| {
+            labels::remove_labels(&mut repo.labels, &labels);
+            repo.labels.clone()
+        })?
+        .ok_or_else(|| format!("Repository not found: {}", id))?;
+
+    state.save()?;
+    Ok(remaining)
+}
+
+/// Every label in use, with the repositories and worktrees carrying it.
+#[tauri::command]
+pub fn list_labels(state: State<AppState>) -> Result<Vec<LabelUsage>, String> {
+    let store = state.store.read().map_err(|e| e.to_string())?;
+    Ok(labels::collect_labels(&store.repositories))
+}
+
+/// Rename a label on every repository and worktree. Returns how many were changed.
+#[tauri::command]
+pub fn rename_label(state: State<AppState>, from: String, to: String) -> Result<usize, String> {
+    ensure_writable("rename a label")?;
+
+    let changed = {
+        let mut store = state.store.write().map_err(|e| e.to_string())?;
+        labels::rename_label(&mut store.repositories, &from, &to)?
+    };
+    if changed > 0 {
+        state.save()?;
+    }
+    Ok(changed)
+}
+
+/// Remove a label from every repository and worktree. Returns how many carried it.
+#[tauri::command]
+pub fn delete_label(state: State<AppState>, label: String) -> Result<usize, String> {
+    ensure_writable("delete a label")?;
+
+    let changed = {
         let mut store = state.store.write().map_err(|e| e.to_string())?;
-        for repo in &mut store.repositories {
-            if let Some(wt) = repo.worktrees.iter_mut().find(|w| w.path == path) {
-                wt.is_locked = false;
-                wt.lock_reason = None;
-                break;
+        labels::delete_label(&mut store.repositories, &label)
+    };
+    if changed > 0 {
+        state.save()?;
+    }
+    Ok(changed)
+}
+
+/// Repositories and worktrees carrying any of `labels` (all of them with
+/// `match_all`); see `labels::filter_by_labels`.
+#[tauri::command]
+pub fn filter_by_labels(
+    state: State<AppState>,
+    labels: Vec<String>,
+    match_all: Option<bool>,
+    include_hidden: Option<bool>,
+) -> Result<Vec<Repository>, String> {
+    let repositories: Vec<Repository> = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        store
+            .repositories
+            .iter()
+            .map(|repo| listed(repo.clone(), include_hidden))
+            .collect()
+    };
+    labels::filter_by_labels(&repositories, &labels, match_all.unwrap_or(false))
+}
+
+/// Open a worktree's dev server in the browser: the registered URL, or else
+/// the first port a process inside the worktree listens on. Returns the URL.
+#[tauri::command]
+pub async fn open_dev_url(state: State<'_, AppState>, path: String) -> Result<String, String> {
+    let registered = state.get_worktree(&path)?.and_then(|wt| wt.dev_url);
+    dev_server::open_dev_url_async(path, registered).await
+}
+
+/// TCP ports listened on by processes running inside a worktree.
+#[tauri::command]
+pub async fn detect_dev_ports(path: String) -> Result<Vec<u16>, String> {
+    dev_server::detect_dev_ports_async(path).await
+}
+
+/// Share the main worktree's dependency folders (`node_modules`, `target`,
+/// `.venv` unless configured) into an existing worktree. `mode` defaults to
+/// the settings, else symlinks. Returns the folders shared.
+#[tauri::command]
+pub async fn share_dependencies(
+    path: String,
+    mode: Option<SharedDepsMode>,
+) -> Result<Vec<SharedDir>, String> {
+    ensure_writable("share dependency folders")?;
+
+    let source_path = operations::find_git_repo_root(&path)?;
+    if paths_equal(&source_path, &path) {
+        return Err("The main worktree can't share folders with itself".to_string());
+    }
+    let mode = mode
+        .or_else(shared_deps::shared_dependencies_mode)
+        .unwrap_or(SharedDepsMode::Symlink);
+    shared_deps::share_dependency_dirs_async(source_path, path, mode).await
+}
+
+/// Processes whose working directory is inside a worktree, e.g. to show what
+/// breaks before removing it.
+#[tauri::command]
+pub async fn get_processes_in_worktree(path: String) -> Result<Vec<WorktreeProcess>, String> {
+    processes::get_processes_in_worktree_async(path).await
+}
+
+/// Stop processes running inside a worktree (all of them unless `pids` is
+/// given) with SIGTERM, or SIGKILL with `force`. Returns the pids signaled.
+#[tauri::command]
+pub async fn terminate_worktree_processes(
+    path: String,
+    pids: Option<Vec<u32>>,
+    force: Option<bool>,
+) -> Result<Vec<u32>, String> {
+    ensure_writable("terminate worktree processes")?;
+    processes::terminate_worktree_processes_async(path, pids, force.unwrap_or(false)).await
+}
+
+/// Remove git lock files (e.g. `index.lock`) a crashed git process left in a
+/// repository. Refuses while a lock is recent or still held by a process.
+/// Returns the removed files.
+#[tauri::command]
+pub async fn force_remove_stale_lock(repo_path: String) -> Result<Vec<String>, String> {
+    ensure_writable("remove stale git locks")?;
+    operations::force_remove_stale_lock_async(repo_path).await
+}
+
+/// Worktree operations running and waiting, per repository.
+#[tauri::command]
+pub fn get_repo_queue_status() -> Vec<RepoQueueStatus> {
+    repo_queue::get_queue_status()
+}
+
+/// Longest a caller may wait in `wait_for_unlock` (10 minutes).
+const MAX_UNLOCK_WAIT_MS: u64 = 10 * 60 * 1000;
+
+/// Wait until a worktree is unlocked, e.g. by a script or CI job that locked
+/// it. `timeout` is in milliseconds. Returns whether the worktree is unlocked.
+#[tauri::command]
+pub async fn wait_for_unlock(path: String, timeout: u64) -> Result<bool, String> {
+    let timeout = Duration::from_millis(timeout.min(MAX_UNLOCK_WAIT_MS));
+    operations::wait_for_unlock_async(path, timeout).await
+}
+
+#[tauri::command]
+pub async fn get_worktree_config(path: String) -> Result<Vec<WorktreeConfigEntry>, String> {
+    config::get_worktree_config_async(path).await
+}
+
+/// Set a git config value for a single worktree; `value: None` unsets it.
+#[tauri::command]
+pub async fn set_worktree_config(
+    path: String,
+    key: String,
+    value: Option<String>,
+) -> Result<Vec<WorktreeConfigEntry>, String> {
+    ensure_writable("change worktree config")?;
+
+    config::set_worktree_config_async(path, key, value).await
+}
+
+/// Identity, hooks, remotes, LFS/submodules and size of a repository, with
+/// warnings for misconfigurations. Cached briefly unless `refresh`.
+#[tauri::command]
+pub async fn get_repo_config_summary(
+    repo_path: String,
+    refresh: Option<bool>,
+) -> Result<RepoConfigSummary, String> {
+    config::get_repo_config_summary_async(repo_path, refresh.unwrap_or(false)).await
+}
+
+/// Files under `dir` in a worktree, honoring `.gitignore`, `depth` folder levels deep.
+#[tauri::command]
+pub async fn list_worktree_files(
+    path: String,
+    dir: Option<String>,
+    depth: Option<usize>,
+) -> Result<FileTree, String> {
+    files::list_worktree_files_async(path, dir, depth).await
+}
+
+/// Stage files in a worktree; an empty list stages every change.
+#[tauri::command]
+pub async fn stage_files(path: String, files: Vec<String>) -> Result<WorktreeStatus, String> {
+    ensure_writable("stage files")?;
+
+    staging::stage_files_async(path, files).await
+}
+
+/// Unstage files in a worktree; an empty list unstages everything.
+#[tauri::command]
+pub async fn unstage_files(path: String, files: Vec<String>) -> Result<WorktreeStatus, String> {
+    ensure_writable("unstage files")?;
+
+    staging::unstage_files_async(path, files).await
+}
+
+/// Commit the staged changes of a worktree, staging everything first if `stage_all`.
+#[tauri::command]
+pub async fn commit_changes(
+    path: String,
+    message: String,
+    stage_all: Option<bool>,
+) -> Result<CommitInfo, String> {
+    ensure_writable("commit changes")?;
+
+    staging::commit_changes_async(path, message, stage_all.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn fetch_worktree(path: String) -> Result<SyncResult, String> {
+    ensure_writable("fetch a worktree")?;
+
+    sync::fetch_worktree_async(path).await
+}
+
+/// Pull the upstream into a worktree's branch; fast-forward only unless `rebase`.
+#[tauri::command]
+pub async fn pull_worktree(path: String, rebase: Option<bool>) -> Result<SyncResult, String> {
+    ensure_writable("pull a worktree")?;
+
+    sync::pull_worktree_async(path, rebase.unwrap_or(false)).await
+}
+
+/// Push a worktree's branch, setting its upstream if it has none. Force-pushing
+/// to a branch protected on GitHub is refused.
+#[tauri::command]
+pub async fn push_worktree(
+    state: State<'_, AppState>,
+    path: String,
+    force: Option<bool>,
+) -> Result<SyncResult, String> {
+    ensure_writable("push a worktree")?;
+
+    let force = force.unwrap_or(false);
+    if force {
+        let repo = operations::find_git_repo_root(&path)
+            .ok()
+            .and_then(|repo_path| state.get_repository(RepositoryKey::Path(&repo_path)).ok())
+            .flatten();
+        if let Some(repo) = repo.filter(|r| !r.remote_protected_branches.is_empty()) {
+            let target = sync::push_target_branch(&path)?;
+            if repo.is_remote_protected_branch(&target) {
+                return Err(format!(
+                    "{} is protected on GitHub; the server would reject a force-push",
+                    target
+                ));
             }
         }
     }
+    sync::push_worktree_async(path, force).await
+}
+
+/// Add the approved keys of an SSH host to the app's known_hosts, after a
+/// fetch, pull or push failed on it (`ssh-host-key-unknown`). Only keys whose
+/// fingerprints are in `fingerprints` and that the host still offers are added.
+#[tauri::command]
+pub async fn trust_ssh_host(
+    host: String,
+    port: Option<u16>,
+    fingerprints: Vec<String>,
+) -> Result<Vec<SshHostKey>, String> {
+    ensure_writable("trust an SSH host")?;
+
+    known_hosts::trust_ssh_host_async(host, port, fingerprints).await
+}
 
+/// Fetch the protected branches of a repository from GitHub and store them
+/// with the repository. Needs a GitHub token and a github.com remote.
+#[tauri::command]
+pub async fn refresh_remote_protection(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Vec<String>, String> {
+    ensure_writable("refresh branch protection")?;
+
+    let repo_path = state
+        .get_repository(RepositoryKey::Id(&id))?
+        .map(|r| r.path)
+        .ok_or("Repository not found")?;
+    let configured = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        store.settings.github_token.clone()
+    };
+    let token = github::resolve_github_token(configured.as_deref())
+        .ok_or("No GitHub token configured (settings.github_token or GITHUB_TOKEN)")?;
+
+    let (branches, checked_at) =
+        github::fetch_repository_protection_async(repo_path.clone(), token)
+            .await?
+            .ok_or_else(|| format!("The remote of {} is not on github.com", repo_path))?;
+
+    state.update_repository(RepositoryKey::Id(&id), |repo| {
+        repo.remote_protected_branches = branches.clone();
+        repo.remote_protection_checked_at = Some(checked_at);
+    })?;
     state.save()?;
-    Ok(())
+    Ok(branches)
+}
+
+/// Rebase a worktree onto `onto`, by default its repository's base branch.
+/// Conflicts leave the rebase in progress for `continue_rebase` / `abort_rebase`.
+#[tauri::command]
+pub async fn rebase_worktree(
+    state: State<'_, AppState>,
+    path: String,
+    onto: Option<String>,
+) -> Result<RebaseResult, String> {
+    ensure_writable("rebase a worktree")?;
+
+    let onto = match onto {
+        Some(onto) => onto,
+        None => {
+            let repo_path = operations::find_git_repo_root(&path)?;
+            state
+                .resolve_base_branch(&repo_path)?
+                .ok_or("Could not determine the base branch; choose what to rebase onto")?
+        }
+    };
+    operations::rebase_worktree_async(path, onto).await
+}
+
+/// Continue a rebase after resolving its conflicts.
+#[tauri::command]
+pub async fn continue_rebase(path: String) -> Result<RebaseResult, String> {
+    ensure_writable("continue a rebase")?;
+
+    operations::continue_rebase_async(path).await
+}
+
+/// Abort a rebase in progress.
+#[tauri::command]
+pub fn abort_rebase(path: String) -> Result<RebaseResult, String> {
+    ensure_writable("abort a rebase")?;
+
+    operations::abort_rebase(&path)
 }
 
 #[tauri::command]
@@ -221,27 +1429,144 @@ pub async fn get_branches(repo_path: String) -> Result<Vec<BranchInfo>, String>
     operations::get_branches_async(repo_path).await
 }
 
+/// A page of branches, for pickers in repositories with thousands of them:
+/// filtered by `prefix`, sorted by name or most recently committed first.
+#[tauri::command]
+pub async fn get_branch_page(
+    repo_path: String,
+    prefix: Option<String>,
+    sort: Option<BranchSort>,
+    include_remote: Option<bool>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<BranchPage, String> {
+    operations::get_branch_page_async(
+        repo_path,
+        prefix,
+        sort.unwrap_or_default(),
+        include_remote.unwrap_or(true),
+        offset.unwrap_or(0),
+        limit.unwrap_or(100),
+    )
+    .await
+}
+
+/// A page of the commit log: `skip` commits from HEAD are skipped, or with
+/// `before_hash` the log continues below that commit.
+#[tauri::command]
+pub async fn get_commits(
+    repo_path: String,
+    limit: Option<usize>,
+    skip: Option<usize>,
+    before_hash: Option<String>,
+) -> Result<Vec<CommitInfo>, String> {
+    operations::get_commits_async(
+        repo_path,
+        limit.unwrap_or(50),
+        skip.unwrap_or(0),
+        before_hash.filter(|h| !h.is_empty()),
+    )
+    .await
+}
+
+/// The best common ancestor of two refs, or `None` when they share no history.
+#[tauri::command]
+pub async fn get_merge_base(
+    repo_path: String,
+    ref_a: String,
+    ref_b: String,
+) -> Result<Option<String>, String> {
+    operations::get_merge_base_async(repo_path, ref_a, ref_b).await
+}
+
+/// Whether `a` is an ancestor of `b`, e.g. whether an agent's base is already
+/// merged into main.
 #[tauri::command]
-pub async fn get_commits(repo_path: String, limit: Option<usize>) -> Result<Vec<CommitInfo>, String> {
-    operations::get_commits_async(repo_path, limit.unwrap_or(50)).await
+pub async fn is_ancestor(repo_path: String, a: String, b: String) -> Result<bool, String> {
+    operations::is_ancestor_async(repo_path, a, b).await
+}
+
+/// Generate a name from the configured templates for a worktree of `branch`.
+async fn generated_worktree_name(
+    state: &AppState,
+    repo_path: String,
+    branch: Option<String>,
+) -> Result<String, String> {
+    let (template, ticket_pattern) = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        (
+            store.settings.worktree_name_template.clone(),
+            store.settings.ticket_pattern.clone(),
+        )
+    };
+    naming::generate_worktree_name_async(repo_path, branch, template, ticket_pattern).await
+}
+
+/// The name `create_worktree` would generate for a worktree of `branch`
+/// (default: the repository's current branch).
+#[tauri::command]
+pub async fn generate_worktree_name(
+    state: State<'_, AppState>,
+    repo_path: String,
+    branch: Option<String>,
+) -> Result<String, String> {
+    generated_worktree_name(&state, repo_path, branch).await
+}
+
+#[tauri::command]
+pub async fn suggest_worktree_name(
+    state: State<'_, AppState>,
+    repo_path: String,
+    hint: Option<String>,
+) -> Result<Vec<String>, String> {
+    let template = {
+        let store = state.store.read().map_err(|e| e.to_string())?;
+        store.settings.branch_name_template.clone()
+    };
+
+    naming::suggest_worktree_names_async(repo_path, hint, template).await
+}
+
+/// Render a startup script's template variables for the script editor.
+#[tauri::command]
+pub fn preview_rendered_script(
+    template: String,
+    context: ScriptTemplateContext,
+) -> Result<String, String> {
+    templates::render_script(&template, &context)
 }
 
 #[tauri::command]
 pub fn open_in_terminal(
+    state: State<AppState>,
     path: String,
     app: String,
     custom_command: Option<String>,
 ) -> Result<(), String> {
-    ext_open_in_terminal(&path, &app, custom_command.as_deref())
+    ext_open_in_terminal(&path, &app, custom_command.as_deref())?;
+    note_worktree_opened(&state, &path);
+    Ok(())
 }
 
 #[tauri::command]
 pub fn open_in_editor(
+    state: State<AppState>,
     path: String,
     app: String,
     custom_command: Option<String>,
 ) -> Result<(), String> {
-    ext_open_in_editor(&path, &app, custom_command.as_deref())
+    ext_open_in_editor(&path, &app, custom_command.as_deref())?;
+    note_worktree_opened(&state, &path);
+    Ok(())
+}
+
+/// Record that the worktree at `path` was opened. Failing to save this must
+/// not fail the open, so errors are only logged.
+fn note_worktree_opened(state: &AppState, path: &str) {
+    let now = Utc::now().timestamp_millis();
+    if let Err(e) = activity::record_worktree_activity(state, path, now) {
+        eprintln!("[worktrees] Failed to record activity for {}: {}", path, e);
+    }
 }
 
 #[tauri::command]
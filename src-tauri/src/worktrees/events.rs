@@ -0,0 +1,197 @@
+//! Tauri side of repository availability, LFS pulls, the per-repository
+//! queue, unknown SSH hosts, stale worktree cleanup, expiring locks and
+//! merged branch suggestions: the background loops and the events emitted to
+//! the frontend.
+
+use chrono::Utc;
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::api_version::Versioned;
+use crate::core::StaleCleanupPolicy;
+
+use super::availability::{
+    probe_repositories, RepositoryAvailability, RepositoryAvailabilityEvent, AVAILABILITY_EVENT,
+    MONITOR_INTERVAL,
+};
+use super::branch_cleanup::{
+    check_branch_cleanup, BRANCH_CLEANUP_CHECK_INTERVAL, BRANCH_CLEANUP_EVENT,
+};
+use super::known_hosts::{set_unknown_host_listener, SSH_HOST_KEY_UNKNOWN_EVENT};
+use super::lfs::{set_progress_listener, LFS_PULL_PROGRESS_EVENT};
+use super::lock_expiry::{
+    unlock_expired_worktrees, LOCK_EXPIRY_CHECK_INTERVAL, WORKTREE_LOCK_EXPIRED_EVENT,
+};
+use super::repo_queue::{set_queue_listener, REPO_OPERATION_QUEUED_EVENT};
+use super::stale::{
+    cleanup_stale_worktrees, run_scheduled_stale_cleanup, StaleCleanupReport,
+    STALE_CLEANUP_CHECK_INTERVAL, STALE_WORKTREES_EVENT,
+};
+use super::store::AppState;
+
+/// Emit an availability change event to the frontend.
+pub fn emit_availability_change(app: &AppHandle, event: RepositoryAvailabilityEvent) {
+    println!(
+        "[availability] Repository {} is now {}",
+        event.path,
+        if event.available {
+            "available"
+        } else {
+            "unavailable"
+        }
+    );
+    if let Err(e) = app.emit(AVAILABILITY_EVENT, Versioned::new(event)) {
+        eprintln!("[availability] Failed to emit availability change: {}", e);
+    }
+}
+
+/// Spawn a background thread that probes repositories and emits
+/// `repository-availability-changed` events.
+pub fn spawn_availability_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let now = Utc::now().timestamp_millis();
+        let changes = probe_repositories(
+            &app.state::<AppState>(),
+            &app.state::<RepositoryAvailability>(),
+            now,
+        );
+
+        match changes {
+            Ok(changes) => {
+                for event in changes {
+                    emit_availability_change(&app, event);
+                }
+            }
+            Err(e) => eprintln!("[availability] Failed to probe repositories: {}", e),
+        }
+
+        std::thread::sleep(MONITOR_INTERVAL);
+    });
+}
+
+/// Emit `lfs-pull-progress` for every LFS pull.
+pub fn emit_lfs_progress_events(app: AppHandle) {
+    set_progress_listener(move |progress| {
+        if let Err(e) = app.emit(LFS_PULL_PROGRESS_EVENT, Versioned::new(progress)) {
+            eprintln!("[lfs] Failed to emit {}: {}", LFS_PULL_PROGRESS_EVENT, e);
+        }
+    });
+}
+
+/// Emit `repo-operation-queued` for every operation that has to wait.
+pub fn emit_queue_events(app: AppHandle) {
+    set_queue_listener(move |event| {
+        if let Err(e) = app.emit(REPO_OPERATION_QUEUED_EVENT, Versioned::new(event)) {
+            eprintln!(
+                "[repo_queue] Failed to emit {}: {}",
+                REPO_OPERATION_QUEUED_EVENT, e
+            );
+        }
+    });
+}
+
+/// Emit `ssh-host-key-unknown` when a fetch, pull or push hit an unknown host.
+pub fn emit_unknown_host_events(app: AppHandle) {
+    set_unknown_host_listener(move |host| {
+        if let Err(e) = app.emit(SSH_HOST_KEY_UNKNOWN_EVENT, Versioned::new(host)) {
+            eprintln!(
+                "[known_hosts] Failed to emit {}: {}",
+                SSH_HOST_KEY_UNKNOWN_EVENT, e
+            );
+        }
+    });
+}
+
+/// Cleanup stale worktrees (async version).
+/// Use this from Tauri commands to avoid freezing the UI.
+pub async fn cleanup_stale_worktrees_async(
+    app: AppHandle,
+    policy: StaleCleanupPolicy,
+    remove: bool,
+) -> Result<StaleCleanupReport, String> {
+    tokio::task::spawn_blocking(move || {
+        let now = Utc::now().timestamp_millis();
+        cleanup_stale_worktrees(&app.state::<AppState>(), &policy, remove, now)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Spawn a background thread that runs the scheduled stale worktree cleanup
+/// and emits `stale-worktrees` when it found any.
+pub fn spawn_stale_cleanup(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let now = Utc::now().timestamp_millis();
+        match run_scheduled_stale_cleanup(&app.state::<AppState>(), now) {
+            Ok(Some(report)) => {
+                println!(
+                    "[stale] {} stale worktree(s), {} removed",
+                    report.entries.len(),
+                    report.removed_count()
+                );
+                if let Err(e) = app.emit(STALE_WORKTREES_EVENT, Versioned::new(report)) {
+                    eprintln!("[stale] Failed to emit {}: {}", STALE_WORKTREES_EVENT, e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[stale] Scheduled cleanup failed: {}", e),
+        }
+
+        std::thread::sleep(STALE_CLEANUP_CHECK_INTERVAL);
+    });
+}
+
+/// Spawn a background thread that lifts expired worktree locks and emits
+/// `worktree-lock-expired` for each.
+pub fn spawn_lock_expiry_monitor(app: AppHandle) {
+    std::thread::spawn(move || loop {
+        let now = Utc::now().timestamp_millis();
+        match unlock_expired_worktrees(&app.state::<AppState>(), now) {
+            Ok(unlocked) => {
+                for event in unlocked {
+                    println!("[lock_expiry] Lock on {} expired", event.path);
+                    if let Err(e) = app.emit(WORKTREE_LOCK_EXPIRED_EVENT, Versioned::new(event)) {
+                        eprintln!(
+                            "[lock_expiry] Failed to emit {}: {}",
+                            WORKTREE_LOCK_EXPIRED_EVENT, e
+                        );
+                    }
+                }
+            }
+            Err(e) => eprintln!("[lock_expiry] Failed to check locks: {}", e),
+        }
+
+        std::thread::sleep(LOCK_EXPIRY_CHECK_INTERVAL);
+    });
+}
+
+/// Spawn a background thread that looks for merged branches without a
+/// worktree and emits `branch-cleanup-suggested` for each repository whose
+/// suggestions changed.
+pub fn spawn_branch_cleanup_monitor(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut seen = HashMap::new();
+        loop {
+            match check_branch_cleanup(&app.state::<AppState>(), &mut seen) {
+                Ok(changed) => {
+                    for suggestion in changed {
+                        println!(
+                            "[branch_cleanup] {} merged branch(es) in {}",
+                            suggestion.branches.len(),
+                            suggestion.repo_path
+                        );
+                        if let Err(e) = app.emit(BRANCH_CLEANUP_EVENT, Versioned::new(suggestion)) {
+                            eprintln!(
+                                "[branch_cleanup] Failed to emit {}: {}",
+                                BRANCH_CLEANUP_EVENT, e
+                            );
+                        }
+                    }
+                }
+                Err(e) => eprintln!("[branch_cleanup] Failed to check branches: {}", e),
+            }
+
+            std::thread::sleep(BRANCH_CLEANUP_CHECK_INTERVAL);
+        }
+    });
+}
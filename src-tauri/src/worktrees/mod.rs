@@ -1,19 +1,10 @@
-//! Worktrees module - Git worktree management.
+//! Worktrees module - Tauri commands and the background loops emitting app
+//! events.
 //!
-//! This module handles all git worktree operations including:
-//! - Listing, creating, removing, renaming worktrees
-//! - Branch and commit information
-//! - External app integration (terminals, editors)
-//! - Repository state management
+//! The logic lives in `aristar_core::worktrees`, re-exported here so commands
+//! use it through `super::`.
 
-pub mod commands;
-pub mod external_apps;
-pub mod operations;
-pub mod store;
-pub mod types;
-
-// Re-export store init function (AppState is used via store:: prefix)
-pub use store::init_store;
+pub use aristar_core::worktrees::*;
 
-// Re-export persistence utilities
-pub use crate::core::get_aristar_worktrees_base;
+pub mod commands;
+pub mod events;
@@ -7,10 +7,11 @@ import { useAppStore } from '@/store/use-app-store';
 import { useAgentManagerStore } from '@agent-manager/store';
 import { Header } from '@core/components';
 import { useTheme } from '@core/hooks';
-import { RepositorySidebar, WorktreeCard, CreateWorktreeDialog, RenameDialog } from '@worktrees/components';
+import { commands, getRepositoryLabel } from '@core/lib';
+import { RepositorySidebar, WorktreeCard, CreateWorktreeDialog, RenameDialog, CommitDialog } from '@worktrees/components';
 import { isProtectedBranch } from '@worktrees/lib';
 import { AgentManagerView } from '@agent-manager/components';
-import type { WorktreeMetadata } from '@/store/types';
+import type { WorktreeMetadata, WorktreeProcess } from '@/store/types';
 
 function App() {
   // Initialize theme system
@@ -32,9 +33,13 @@ function App() {
   const [createDialogOpen, setCreateDialogOpen] = useState(false);
   const [renameDialogOpen, setRenameDialogOpen] = useState(false);
   const [worktreeToRename, setWorktreeToRename] = useState<WorktreeMetadata | null>(null);
+  const [worktreeToCommit, setWorktreeToCommit] = useState<WorktreeMetadata | null>(null);
   const [worktreeToDelete, setWorktreeToDelete] = useState<WorktreeMetadata | null>(null);
   const [deleteBranch, setDeleteBranch] = useState(true);
+  const [runningProcesses, setRunningProcesses] = useState<WorktreeProcess[]>([]);
+  const [terminateProcesses, setTerminateProcesses] = useState(true);
   const [repositoryToDelete, setRepositoryToDelete] = useState<string | null>(null);
+  const [apiError, setApiError] = useState<string | null>(null);
 
   const selectedRepo = repositories.find(r => r.id === selectedRepositoryId);
 
@@ -42,6 +47,21 @@ function App() {
     useAppStore.getState().loadRepositories();
   }, []);
 
+  // Show what would break before a worktree is deleted
+  useEffect(() => {
+    setRunningProcesses([]);
+    if (!worktreeToDelete) return;
+    commands
+      .getProcessesInWorktree(worktreeToDelete.path)
+      .then(setRunningProcesses)
+      .catch((err) => console.warn('[App] Failed to list worktree processes:', err));
+  }, [worktreeToDelete]);
+
+  // Fail loudly when the frontend and the Rust core were built from different versions
+  useEffect(() => {
+    commands.checkApiCompatibility().catch((err) => setApiError(String(err)));
+  }, []);
+
   // Load OpenCode providers and agents on startup when we have a repository
   useEffect(() => {
     const loadOpenCodeData = async () => {
@@ -108,9 +128,15 @@ function App() {
       const shouldDeleteBranch = deleteBranch && 
         !worktreeToDelete.isMain && 
         !!worktreeToDelete.branch;
-      await removeWorktree(worktreeToDelete.path, force, shouldDeleteBranch);
+      await removeWorktree(
+        worktreeToDelete.path,
+        force,
+        shouldDeleteBranch,
+        runningProcesses.length > 0 && terminateProcesses
+      );
       setWorktreeToDelete(null);
       setDeleteBranch(true); // Reset for next time
+      setTerminateProcesses(true);
     }
   };
 
@@ -143,7 +169,7 @@ function App() {
                     <div className="flex items-center gap-3">
                       <FolderGit2 className="h-5 w-5 text-muted-foreground" />
                       <div>
-                        <h2 className="font-medium">{selectedRepo.name}</h2>
+                        <h2 className="font-medium">{getRepositoryLabel(selectedRepo)}</h2>
                         <p className="text-sm text-muted-foreground">
                           {selectedRepo.worktrees.length} worktree{selectedRepo.worktrees.length !== 1 ? 's' : ''}
                         </p>
@@ -178,6 +204,7 @@ function App() {
                           key={worktree.id}
                           worktree={worktree}
                           onRename={handleRename}
+                          onCommit={setWorktreeToCommit}
                           onDelete={handleDelete}
                           onLock={handleLock}
                           onUnlock={handleUnlock}
@@ -210,6 +237,13 @@ function App() {
         <AgentManagerView />
       )}
 
+      {apiError && (
+        <div className="flex items-center gap-2 border-t bg-destructive/10 px-6 py-2 text-sm text-destructive">
+          <AlertTriangle className="h-4 w-4 shrink-0" />
+          {apiError}
+        </div>
+      )}
+
       {error && (
         <div className="border-t bg-destructive/10 px-6 py-2 text-sm text-destructive">
           {error}
@@ -227,6 +261,12 @@ function App() {
         worktree={worktreeToRename}
       />
 
+      <CommitDialog
+        open={worktreeToCommit !== null}
+        onOpenChange={(open) => !open && setWorktreeToCommit(null)}
+        worktree={worktreeToCommit}
+      />
+
       {worktreeToDelete && (
         <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
           <div className="w-full max-w-md rounded-lg bg-card p-6 shadow-lg">
@@ -264,11 +304,42 @@ function App() {
                 )}
               </div>
             )}
+
+            {/* Processes running inside the worktree */}
+            {runningProcesses.length > 0 && (
+              <div className="mb-4 space-y-3">
+                <div className="flex items-start gap-2 rounded-md bg-amber-500/10 p-3 text-sm text-amber-600 dark:text-amber-400">
+                  <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0" />
+                  <div>
+                    <p className="font-medium">Running in this worktree</p>
+                    <ul className="text-xs opacity-90">
+                      {runningProcesses.map((process) => (
+                        <li key={process.pid}>
+                          {process.command} (PID {process.pid})
+                          {process.ports.length > 0 && ` on port ${process.ports.join(', ')}`}
+                        </li>
+                      ))}
+                    </ul>
+                  </div>
+                </div>
+                <div className="flex items-center gap-3">
+                  <Switch
+                    id="terminate-processes"
+                    checked={terminateProcesses}
+                    onCheckedChange={setTerminateProcesses}
+                  />
+                  <Label htmlFor="terminate-processes" className="text-sm cursor-pointer">
+                    Stop them before deleting
+                  </Label>
+                </div>
+              </div>
+            )}
             
             <div className="flex justify-end gap-2">
               <Button variant="outline" onClick={() => {
                 setWorktreeToDelete(null);
                 setDeleteBranch(true);
+                setTerminateProcesses(true);
               }}>
                 Cancel
               </Button>
@@ -17,6 +17,7 @@ function getStatusText(status: AgentStatus): string {
     case 'completed': return 'Task completed';
     case 'failed': return 'Failed';
     case 'paused': return 'Paused';
+    case 'waiting': return 'Waiting for hand-off';
     default: return 'Ready';
   }
 }
@@ -24,7 +24,7 @@ import { ModelSelector } from './model-selector';
 import { useAppStore } from '@/store/use-app-store';
 import { useAgentManagerStore } from '../store/agent-manager-store';
 import { opencodeClient } from '../api/opencode';
-import { commands } from '@core/lib';
+import { commands, getRepositoryLabel } from '@core/lib';
 import type { CommitInfo, SourceType } from '@/store/types';
 import type { ModelSelection } from '../store/types';
 
@@ -214,7 +214,7 @@ export function CreateTaskDialog({ open, onOpenChange }: CreateTaskDialogProps)
                 <SelectContent>
                   {repositories.map((repo) => (
                     <SelectItem key={repo.id} value={repo.id}>
-                      {repo.name}
+                      {getRepositoryLabel(repo)}
                     </SelectItem>
                   ))}
                 </SelectContent>
@@ -14,6 +14,9 @@ import { commands } from '@core/lib';
 import { cn } from '@core/lib/utils';
 import type { BranchInfo, CommitInfo, SourceType } from '@/store/types';
 
+/** Commits loaded per page of the commit list */
+const COMMIT_PAGE_SIZE = 50;
+
 interface SourceSelectorProps {
   repoPath: string;
   sourceType: SourceType;
@@ -41,6 +44,8 @@ export function SourceSelector({
   const [commitSearch, setCommitSearch] = useState('');
   const [loadingBranches, setLoadingBranches] = useState(false);
   const [loadingCommits, setLoadingCommits] = useState(false);
+  const [hasMoreCommits, setHasMoreCommits] = useState(false);
+  const [loadingMoreCommits, setLoadingMoreCommits] = useState(false);
 
   const loadBranches = useCallback(async () => {
     if (!repoPath) return;
@@ -63,8 +68,9 @@ export function SourceSelector({
     if (!repoPath) return;
     setLoadingCommits(true);
     try {
-      const commitList = await commands.getCommits(repoPath, 50);
+      const commitList = await commands.getCommits(repoPath, COMMIT_PAGE_SIZE);
       setCommits(commitList);
+      setHasMoreCommits(commitList.length === COMMIT_PAGE_SIZE);
     } catch (err) {
       console.error('Failed to load commits:', err);
     } finally {
@@ -72,6 +78,20 @@ export function SourceSelector({
     }
   }, [repoPath]);
 
+  const loadMoreCommits = useCallback(async () => {
+    if (!repoPath || loadingMoreCommits) return;
+    setLoadingMoreCommits(true);
+    try {
+      const page = await commands.getCommits(repoPath, COMMIT_PAGE_SIZE, { skip: commits.length });
+      setCommits((prev) => [...prev, ...page]);
+      setHasMoreCommits(page.length === COMMIT_PAGE_SIZE);
+    } catch (err) {
+      console.error('Failed to load more commits:', err);
+    } finally {
+      setLoadingMoreCommits(false);
+    }
+  }, [repoPath, commits.length, loadingMoreCommits]);
+
   useEffect(() => {
     if (repoPath) {
       loadBranches();
@@ -112,7 +132,7 @@ export function SourceSelector({
             <SelectValue />
           </SelectTrigger>
           <SelectContent>
-            <SelectItem value="current-branch">Current Branch</SelectItem>
+            <SelectItem value="current-branch">Default Branch</SelectItem>
             <SelectItem value="existing-branch">Existing Branch</SelectItem>
             <SelectItem value="commit">Specific Commit</SelectItem>
           </SelectContent>
@@ -210,6 +230,17 @@ export function SourceSelector({
                     </div>
                   </button>
                 ))}
+                {hasMoreCommits && !commitSearch && (
+                  <button
+                    type="button"
+                    onClick={loadMoreCommits}
+                    disabled={loadingMoreCommits}
+                    className="flex w-full items-center justify-center gap-1.5 rounded px-2 py-1.5 text-xs text-muted-foreground hover:bg-muted/50"
+                  >
+                    {loadingMoreCommits && <Loader2 className="h-3 w-3 animate-spin" />}
+                    Load older commits
+                  </button>
+                )}
               </div>
             )}
           </ScrollArea>
@@ -1,4 +1,4 @@
-import { Loader2, Pause, Check, X, Circle } from 'lucide-react';
+import { Loader2, Pause, Check, X, Circle, Hourglass } from 'lucide-react';
 import { cn } from '@core/lib/utils';
 import type { TaskStatus, AgentStatus } from '../store/types';
 
@@ -42,6 +42,11 @@ const statusConfig: Record<Status, {
     icon: X,
     className: 'bg-red-500/10 text-red-600 dark:text-red-400',
   },
+  waiting: {
+    label: 'Waiting',
+    icon: Hourglass,
+    className: 'bg-muted text-muted-foreground',
+  },
 };
 
 export function StatusBadge({ status, size = 'md', showLabel = true }: StatusBadgeProps) {
@@ -84,6 +89,7 @@ const dotColors: Record<Status, string> = {
   paused: 'bg-yellow-500',
   completed: 'bg-green-500',
   failed: 'bg-red-500',
+  waiting: 'bg-muted-foreground/50',
 };
 
 export function StatusDot({ status, size = 'md' }: StatusDotProps) {
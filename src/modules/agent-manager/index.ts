@@ -42,6 +42,7 @@ export type {
   TaskAgent,
   TaskStatus,
   AgentStatus,
+  TaskMode,
   OpenCodeModel,
   OpenCodeProvider,
   OpenCodeAgentConfig,
@@ -14,6 +14,7 @@ import type { OpenCodeMessage, OpenCodeMessageExtended } from '../api/opencode';
 import { opencodeClient, opencodeClientManager } from '../api/opencode';
 import { commands } from '@core/lib';
 import { sseManager } from './sse-manager';
+import { recordTranscriptEvent, flushTranscripts } from './transcript-recorder';
 import { useMessageStore } from './message-store';
 import { useDiffStore } from './diff-store';
 import type { Message, APIPart } from '../api/opencode-types';
@@ -65,6 +66,9 @@ export function cleanupAgentManagerResources(): void {
     }
   }
   sseUnsubscribers.clear();
+
+  // Write transcript events still waiting for their batch
+  void flushTranscripts();
   
   // Clear recovery tracking
   agentsBeingRecovered.clear();
@@ -158,6 +162,7 @@ interface AgentManagerActions {
 
   // Agent management
   addAgentToTask: (taskId: string, model: ModelSelection) => Promise<void>;
+  forkAgent: (taskId: string, sourceAgentId: string, model: ModelSelection) => Promise<void>;
   removeAgentFromTask: (taskId: string, agentId: string, deleteWorktree: boolean) => Promise<void>;
   acceptAgent: (taskId: string, agentId: string) => Promise<void>;
   cleanupUnacceptedAgents: (taskId: string) => Promise<void>;
@@ -348,7 +353,10 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
             params.sourceCommit,
             params.sourceRepoPath,
             params.agentType,
-            params.models
+            params.models,
+            params.includeUncommitted,
+            params.sparsePaths,
+            params.mode
           );
           set((state) => ({
             tasks: [...state.tasks, task],
@@ -435,6 +443,25 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
         }
       },
 
+      forkAgent: async (taskId, sourceAgentId, model) => {
+        set({ isLoading: true, error: null });
+        try {
+          const updatedTask = await commands.forkAgent(
+            taskId,
+            sourceAgentId,
+            model.modelId,
+            model.providerId,
+            undefined
+          );
+          set((state) => ({
+            tasks: state.tasks.map((t) => (t.id === taskId ? updatedTask : t)),
+            isLoading: false,
+          }));
+        } catch (err) {
+          set({ error: String(err), isLoading: false });
+        }
+      },
+
       removeAgentFromTask: async (taskId, agentId, deleteWorktree) => {
         set({ isLoading: true, error: null });
         try {
@@ -491,18 +518,10 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
         set({ isLoading: true, error: null });
         try {
           await commands.acceptAgent(taskId, agentId);
+          // Refetch: in a relay, accepting hands the work to the next agent
+          const updatedTask = await commands.getTask(taskId);
           set((state) => ({
-            tasks: state.tasks.map((t) =>
-              t.id === taskId
-                ? {
-                    ...t,
-                    agents: t.agents.map((a) => ({
-                      ...a,
-                      accepted: a.id === agentId,
-                    })),
-                  }
-                : t
-            ),
+            tasks: state.tasks.map((t) => (t.id === taskId ? updatedTask : t)),
             isLoading: false,
           }));
           toast.success('Agent accepted');
@@ -637,6 +656,9 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
             // Dispatch events to the message store
             messageStore.handleSSEEvent(agentKey, event);
 
+            // Mirror events into the agent's transcript (if enabled)
+            recordTranscriptEvent(taskId, agentId, event);
+
             // Dispatch diff events to the diff store
             useDiffStore.getState().handleSSEEvent(event);
 
@@ -859,10 +881,10 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
             ),
           }));
 
-          // Start all agents in parallel
-          const startPromises = task.agents.map((agent) =>
-            get().startAgent(taskId, agent.id, initialPrompt)
-          );
+          // Start all agents in parallel (relay agents waiting for a hand-off start later)
+          const startPromises = task.agents
+            .filter((agent) => agent.status !== 'waiting')
+            .map((agent) => get().startAgent(taskId, agent.id, initialPrompt));
 
           await Promise.allSettled(startPromises);
 
@@ -947,6 +969,7 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
               await sseManager.connect(port);
               const unsubscribe = sseManager.subscribe(port, agent.sessionId, (event) => {
                 messageStore.handleSSEEvent(agentKey, event);
+                recordTranscriptEvent(taskId, agent.id, event);
                 useDiffStore.getState().handleSSEEvent(event);
 
                 if (event.type === 'session.status' || event.type === 'session.idle') {
@@ -1141,6 +1164,7 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
                  if (!sseUnsubscribers.has(agentKey) && agent.sessionId) {
                    const unsubscribe = sseManager.subscribe(port, agent.sessionId, (event) => {
                      messageStore.handleSSEEvent(agentKey, event);
+                     recordTranscriptEvent(taskId, agent.id, event);
                      useDiffStore.getState().handleSSEEvent(event);
                      if (event.type === 'session.status' || event.type === 'session.idle') {
                        const status = (event.properties as { status?: { type: string } | string })?.status;
@@ -1194,6 +1218,7 @@ export const useAgentManagerStore = create<AgentManagerStore>()(
              if (!sseUnsubscribers.has(agentKey) && agent.sessionId) {
                const unsubscribe = sseManager.subscribe(port, agent.sessionId, (event) => {
                  messageStore.handleSSEEvent(agentKey, event);
+                 recordTranscriptEvent(taskId, agent.id, event);
                  useDiffStore.getState().handleSSEEvent(event);
                  if (event.type === 'session.status' || event.type === 'session.idle') {
                    const status = (event.properties as { status?: { type: string } | string })?.status;
@@ -0,0 +1,67 @@
+/**
+ * Transcript Recorder
+ *
+ * Mirrors agents' SSE events into their transcripts in the task folder when
+ * `recordTranscripts` is on in the app settings. Events are batched per agent
+ * and flushed once a second; the backend keeps only finished messages, parts
+ * and tool calls.
+ */
+
+import type { SSEEvent } from '../api/opencode-types';
+import { commands } from '@core/lib';
+import { useAppStore } from '@/store/use-app-store';
+
+const FLUSH_DELAY_MS = 1000;
+
+/** Event types the backend records, everything else is never sent */
+const RECORDED_EVENT_TYPES = new Set(['message.updated', 'message.part.updated', 'session.error']);
+
+interface PendingBatch {
+  taskId: string;
+  agentId: string;
+  events: SSEEvent[];
+  timer: ReturnType<typeof setTimeout>;
+}
+
+const pending = new Map<string, PendingBatch>();
+
+async function flush(agentKey: string): Promise<void> {
+  const batch = pending.get(agentKey);
+  if (!batch) return;
+  pending.delete(agentKey);
+  clearTimeout(batch.timer);
+
+  try {
+    await commands.appendAgentTranscript(batch.taskId, batch.agentId, batch.events);
+  } catch (err) {
+    console.warn(`[TranscriptRecorder] Failed to record transcript for ${agentKey}:`, err);
+  }
+}
+
+/**
+ * Queue an agent's SSE event for its transcript.
+ */
+export function recordTranscriptEvent(taskId: string, agentId: string, event: SSEEvent): void {
+  if (!useAppStore.getState().settings.recordTranscripts) return;
+  if (!RECORDED_EVENT_TYPES.has(event.type)) return;
+
+  const agentKey = `${taskId}:${agentId}`;
+  const batch = pending.get(agentKey);
+  if (batch) {
+    batch.events.push(event);
+    return;
+  }
+  pending.set(agentKey, {
+    taskId,
+    agentId,
+    events: [event],
+    timer: setTimeout(() => void flush(agentKey), FLUSH_DELAY_MS),
+  });
+}
+
+/**
+ * Write all queued events now (e.g. before the app closes).
+ */
+export async function flushTranscripts(): Promise<void> {
+  await Promise.all([...pending.keys()].map(flush));
+}
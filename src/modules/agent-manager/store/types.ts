@@ -1,68 +1,60 @@
 // Agent Manager Types
 // Types for the Agent Manager feature that allows running multiple AI agents in parallel
 
-// ============ Status Types ============
-
-export type TaskStatus = 'idle' | 'running' | 'paused' | 'completed' | 'failed';
-export type AgentStatus = 'idle' | 'running' | 'paused' | 'completed' | 'failed';
-
-// ============ Task Agent ============
-
-/**
- * Represents one AI model/agent working on a task.
- * Each agent has its own worktree and OpenCode session.
- */
-export interface TaskAgent {
-  /** Unique ID within task (e.g., "agent-1") */
-  id: string;
-  /** Model ID (e.g., "claude-sonnet-4") */
-  modelId: string;
-  /** Provider ID (e.g., "anthropic") */
-  providerId: string;
-  /** Override task's default agent type */
-  agentType?: string;
-  /** Full path to agent's worktree */
-  worktreePath: string;
-  /** OpenCode session ID */
-  sessionId?: string;
-  /** Current status */
-  status: AgentStatus;
-  /** Whether this agent's output was accepted as the winner */
-  accepted: boolean;
-  /** Timestamp when agent was created */
-  createdAt: number;
-}
-
-// ============ Task ============
-
-/**
- * A task represents a goal/prompt with multiple agents working on it.
- * Each task has its own folder with agent worktrees inside.
- */
-export interface Task {
-  /** Unique 8-char hash (e.g., "a1b2c3d4") */
-  id: string;
-  /** User-friendly name */
-  name: string;
-  /** Source type: branch or commit */
-  sourceType: 'branch' | 'commit';
-  /** Source branch name (when sourceType is 'branch') */
-  sourceBranch?: string;
-  /** Source commit hash (when sourceType is 'commit') */
-  sourceCommit?: string;
-  /** Original repository path */
-  sourceRepoPath: string;
-  /** Default agent type for all agents (e.g., "build") */
-  agentType: string;
-  /** Current task status */
-  status: TaskStatus;
-  /** Timestamp when task was created */
-  createdAt: number;
-  /** Timestamp when task was last updated */
-  updatedAt: number;
-  /** List of agents working on this task */
-  agents: TaskAgent[];
-}
+import type { ModelSelection, TaskMode } from '@/store/generated/types';
+
+// Tasks, agents and the other types the backend sends are generated from the Rust types
+export type {
+  TaskStatus,
+  AgentStatus,
+  TaskMode,
+  TaskAgent,
+  DiskQuotaExceeded,
+  ReviewComment,
+  AgentSummary,
+  Task,
+  ContextFile,
+  TimelineEntryKind,
+  TimelineEntry,
+  WriteAuditReport,
+  SandboxConfig,
+  ChangeSummary,
+  FileAgreement,
+  TaskArtifact,
+  TaskArtifactCleanup,
+  AgentAgreementReport,
+  AgentComparisonExport,
+  WorktreeRepairFailure,
+  RepointTasksReport,
+  AgentDiffStats,
+  AgentOutcome,
+  ModelLeaderboardEntry,
+  TranscriptEntry,
+  TranscriptRange,
+  AgentTranscript,
+  ModelSelection,
+  TaskPreset,
+  Schedule,
+  ScheduleTriggeredEvent,
+  PowerThrottledEvent,
+  AgentStatusChangedEvent,
+  SavedTaskPreset,
+  BatchTaskResult,
+  BatchTaskSummary,
+  PipelineStepKind,
+  PipelineStatus,
+  PipelineStep,
+  Pipeline,
+  PipelineStepRun,
+  AgentVerification,
+  AgentScore,
+  PipelineRun,
+  AutoAcceptPolicy,
+  PendingAutoAccept,
+  AutoAcceptEvent,
+  TasksLoadedEvent,
+  AgentQuotaExceededEvent,
+} from '@/store/generated/types';
 
 // ============ OpenCode Types ============
 
@@ -99,14 +91,6 @@ export interface OpenCodeAgentConfig {
 
 // ============ Request/Params Types ============
 
-/**
- * Model selection for creating agents
- */
-export interface ModelSelection {
-  providerId: string;
-  modelId: string;
-}
-
 /**
  * Parameters for creating a new task
  */
@@ -118,6 +102,10 @@ export interface CreateTaskParams {
   sourceRepoPath: string;
   agentType: string;
   models: ModelSelection[];
+  includeUncommitted?: boolean;
+  /** Folders to check out in agent worktrees (sparse); all when empty */
+  sparsePaths?: string[];
+  mode?: TaskMode;
 }
 
 // ============ Message Part Types ============
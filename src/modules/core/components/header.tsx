@@ -11,7 +11,7 @@ import { ThemeToggle } from './theme-toggle';
 import { SettingsDialog } from './settings-dialog';
 import { useAppStore } from '@/store/use-app-store';
 import { useAgentManagerStore } from '@agent-manager/store';
-import { getRepositoryName, cn } from '@core/lib/utils';
+import { getRepositoryLabel, cn } from '@core/lib/utils';
 import type { ActiveView } from '@/store/types';
 
 interface HeaderProps {
@@ -85,7 +85,7 @@ export function Header({ onAddRepository }: HeaderProps) {
               <DropdownMenuTrigger asChild>
                 <Button variant="outline" size="sm" className="gap-2">
                   <FolderGit2 className="h-4 w-4" />
-                  {selectedRepo ? getRepositoryName(selectedRepo.path) : 'Select Repository'}
+                  {selectedRepo ? getRepositoryLabel(selectedRepo) : 'Select Repository'}
                   <ChevronDown className="h-3 w-3 opacity-50" />
                 </Button>
               </DropdownMenuTrigger>
@@ -98,7 +98,7 @@ export function Header({ onAddRepository }: HeaderProps) {
                       repo.id === selectedRepositoryId && 'bg-accent'
                     )}
                   >
-                    {getRepositoryName(repo.path)}
+                    {getRepositoryLabel(repo)}
                   </DropdownMenuItem>
                 ))}
               </DropdownMenuContent>
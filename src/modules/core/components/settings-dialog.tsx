@@ -70,6 +70,7 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
   const [expandToolsByDefault, setExpandToolsByDefault] = useState(settings.toolDisplay?.expandToolsByDefault ?? false);
   const [showToolCommands, setShowToolCommands] = useState(settings.toolDisplay?.showToolCommands ?? false);
   const [outputVisibility, setOutputVisibility] = useState<ToolOutputVisibility>(settings.toolDisplay?.outputVisibility ?? 'hidden');
+  const [recordTranscripts, setRecordTranscripts] = useState(settings.recordTranscripts ?? false);
 
   // Optimization settings
   const [optimizationModel, setOptimizationModel] = useState<OptimizationModelSelection | undefined>(settings.optimizationModel);
@@ -89,6 +90,7 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
       setExpandToolsByDefault(settings.toolDisplay?.expandToolsByDefault ?? false);
       setShowToolCommands(settings.toolDisplay?.showToolCommands ?? false);
       setOutputVisibility(settings.toolDisplay?.outputVisibility ?? 'hidden');
+      setRecordTranscripts(settings.recordTranscripts ?? false);
       setOptimizationModel(settings.optimizationModel);
     }
   }, [open, settings]);
@@ -151,6 +153,7 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
         truncatedOutputLines: settings.toolDisplay?.truncatedOutputLines ?? 10,
       },
       optimizationModel,
+      recordTranscripts,
     });
     onOpenChange(false);
   };
@@ -428,6 +431,20 @@ export function SettingsDialog({ open, onOpenChange }: SettingsDialogProps) {
                       {outputVisibilityOptions.find((o) => o.value === outputVisibility)?.description}
                     </p>
                   </div>
+
+                  <div className="flex items-center justify-between">
+                    <div className="space-y-0.5">
+                      <Label htmlFor="recordTranscripts">Record session transcripts</Label>
+                      <p className="text-xs text-muted-foreground">
+                        Save each agent's messages and tool calls in its task folder
+                      </p>
+                    </div>
+                    <Switch
+                      id="recordTranscripts"
+                      checked={recordTranscripts}
+                      onCheckedChange={setRecordTranscripts}
+                    />
+                  </div>
                 </div>
               </div>
             )}
@@ -2,36 +2,130 @@ import { invoke } from '@tauri-apps/api/core';
 import type {
   WorktreeMetadata,
   Repository,
+  RepoScanSettings,
+  DeepScanResult,
   BranchInfo,
+  BranchPage,
+  BranchSort,
   CommitInfo,
+  WorktreeConfigEntry,
+  RepoConfigSummary,
+  FileTree,
+  DesktopNotification,
+  RedactionPreview,
+  PortReport,
+  SharedDepsMode,
+  SharedDir,
+  WorktreeArchive,
+  StaleCleanupPolicy,
+  StaleCleanupReport,
+  BranchCleanupSuggestion,
+  BranchDeletionReport,
+  WorktreeProcess,
+  RenameWorktreeError,
+  LabelUsage,
+  PluginList,
+  PluginActionResult,
+  ApiVersionInfo,
+  WorkspaceSnapshotInfo,
+  RecoveryReport,
+  SearchResult,
+  DefaultBranchChange,
+  WorktreeStatus,
+  WorktreeChanges,
+  WorktreeDiff,
+  SyncResult,
+  SshHostKey,
+  RebaseResult,
+  RepoQueueStatus,
+  MergeStrategy,
+  BaseMergeResult,
   Task,
   TaskStatus,
   AgentStatus,
+  TaskMode,
+  AgentAgreementReport,
+  AgentComparisonExport,
+  ModelLeaderboardEntry,
+  RepointTasksReport,
+  TasksLoadedEvent,
+  TimelineEntry,
+  TaskArtifact,
+  TaskArtifactCleanup,
+  AgentTranscript,
+  TranscriptRange,
   ModelSelection,
 } from '@/store/types';
 
-export async function getRepositories(): Promise<Repository[]> {
-  return await invoke('get_repositories');
+export async function getRepositories(includeHidden?: boolean): Promise<Repository[]> {
+  return await invoke('get_repositories', { includeHidden });
 }
 
-export async function addRepository(path: string): Promise<Repository> {
-  return await invoke('add_repository', { path });
+export async function addRepository(path: string, scanSettings?: RepoScanSettings): Promise<Repository> {
+  return await invoke('add_repository', { path, scanSettings });
 }
 
 export async function removeRepository(id: string): Promise<void> {
   return await invoke('remove_repository', { id });
 }
 
-export async function refreshRepository(id: string): Promise<Repository> {
-  return await invoke('refresh_repository', { id });
+export async function refreshRepository(id: string, includeHidden?: boolean): Promise<Repository> {
+  return await invoke('refresh_repository', { id, includeHidden });
 }
 
 export async function getBranches(repoPath: string): Promise<BranchInfo[]> {
   return await invoke('get_branches', { repoPath });
 }
 
-export async function getCommits(repoPath: string, limit?: number): Promise<CommitInfo[]> {
-  return await invoke('get_commits', { repoPath, limit });
+/**
+ * Get a page of branches, for repositories with too many to list at once.
+ * `prefix` matches branch names (remote ones also without the remote)
+ */
+export async function getBranchPage(
+  repoPath: string,
+  options?: {
+    prefix?: string;
+    sort?: BranchSort;
+    includeRemote?: boolean;
+    offset?: number;
+    limit?: number;
+  }
+): Promise<BranchPage> {
+  return await invoke('get_branch_page', {
+    repoPath,
+    prefix: options?.prefix,
+    sort: options?.sort,
+    includeRemote: options?.includeRemote,
+    offset: options?.offset,
+    limit: options?.limit,
+  });
+}
+
+/** Best common ancestor of two refs, null when their histories are unrelated */
+export async function getMergeBase(repoPath: string, refA: string, refB: string): Promise<string | null> {
+  return await invoke('get_merge_base', { repoPath, refA, refB });
+}
+
+/** Whether a is an ancestor of b, e.g. whether a branch is already merged into main */
+export async function isAncestor(repoPath: string, a: string, b: string): Promise<boolean> {
+  return await invoke('is_ancestor', { repoPath, a, b });
+}
+
+/**
+ * Get a page of the commit log. Pass `skip` (commits already loaded) to page
+ * from HEAD, or `beforeHash` to continue below a commit
+ */
+export async function getCommits(
+  repoPath: string,
+  limit?: number,
+  options?: { skip?: number; beforeHash?: string }
+): Promise<CommitInfo[]> {
+  return await invoke('get_commits', {
+    repoPath,
+    limit,
+    skip: options?.skip,
+    beforeHash: options?.beforeHash,
+  });
 }
 
 export async function listWorktrees(repoPath: string): Promise<WorktreeMetadata[]> {
@@ -40,38 +134,396 @@ export async function listWorktrees(repoPath: string): Promise<WorktreeMetadata[
 
 export async function createWorktree(
   repoPath: string,
-  name: string,
+  /** Omit to generate a name from `settings.worktree_name_template` */
+  name: string | undefined,
   branch: string | undefined,
   commit: string | undefined,
   startupScript: string | undefined,
-  executeScript: boolean
+  executeScript: boolean,
+  /**
+   * Create `newBranch` from `baseRef` (default: the repository's base branch).
+   * `autoSuffix` appends `-2`, `-3`, ... to a taken name; the result has the name used.
+   */
+  options?: { newBranch?: string; baseRef?: string; autoSuffix?: boolean }
 ): Promise<WorktreeMetadata> {
   return await invoke('create_worktree', {
     repoPath,
     name,
     branch,
     commit,
+    newBranch: options?.newBranch,
+    baseRef: options?.baseRef,
     startupScript,
     executeScript,
+    autoSuffix: options?.autoSuffix,
   });
 }
 
-export async function removeWorktree(path: string, force: boolean, deleteBranch: boolean): Promise<void> {
-  return await invoke('remove_worktree', { path, force, deleteBranch });
+/** The name `createWorktree` would generate for a worktree of `branch` */
+export async function generateWorktreeName(repoPath: string, branch?: string): Promise<string> {
+  return await invoke<string>('generate_worktree_name', { repoPath, branch });
 }
 
-export async function renameWorktree(oldPath: string, newName: string): Promise<WorktreeMetadata> {
-  return await invoke('rename_worktree', { oldPath, newName });
+export async function getWorktreeStatus(path: string): Promise<WorktreeStatus> {
+  return await invoke('get_worktree_status', { path });
 }
 
-export async function lockWorktree(path: string, reason: string | undefined): Promise<void> {
-  return await invoke('lock_worktree', { path, reason });
+/** Files changed since baseRef (default: the repository's base branch), uncommitted ones included by default */
+export async function getWorktreeChangedFiles(
+  path: string,
+  baseRef?: string,
+  includeUncommitted?: boolean
+): Promise<WorktreeChanges> {
+  return await invoke('get_worktree_changed_files', { path, baseRef, includeUncommitted });
+}
+
+/** Unified diff since baseRef, limited to paths when given */
+export async function getWorktreeDiff(
+  path: string,
+  baseRef?: string,
+  paths?: string[],
+  includeUncommitted?: boolean
+): Promise<WorktreeDiff> {
+  return await invoke('get_worktree_diff', { path, baseRef, paths, includeUncommitted });
+}
+
+export async function removeWorktree(
+  path: string,
+  force: boolean,
+  deleteBranch: boolean,
+  terminateProcesses?: boolean,
+  archive?: boolean
+): Promise<void> {
+  return await invoke('remove_worktree', {
+    path,
+    force,
+    deleteBranch,
+    terminateProcesses,
+    archive,
+  });
+}
+
+/** Pack a worktree into ~/.aristar-worktrees/archives/ (ignored files left out with trackedOnly) */
+export async function archiveWorktree(path: string, trackedOnly?: boolean): Promise<WorktreeArchive> {
+  return await invoke('archive_worktree', { path, trackedOnly });
+}
+
+export async function listWorktreeArchives(): Promise<WorktreeArchive[]> {
+  return await invoke('list_worktree_archives');
+}
+
+/** Restore an archive to targetPath (default: its original path); returns the restored path */
+export async function restoreWorktreeArchive(id: string, targetPath?: string): Promise<string> {
+  return await invoke('restore_worktree_archive', { id, targetPath });
+}
+
+export async function deleteWorktreeArchive(id: string): Promise<void> {
+  return await invoke('delete_worktree_archive', { id });
+}
+
+/** Report worktrees idle past the policy (default: the stored one), removing those it allows with remove */
+export async function cleanupStaleWorktrees(
+  policy?: StaleCleanupPolicy,
+  remove?: boolean
+): Promise<StaleCleanupReport> {
+  return await invoke('cleanup_stale_worktrees', { policy, remove });
+}
+
+export async function getStaleCleanupPolicy(): Promise<StaleCleanupPolicy> {
+  return await invoke('get_stale_cleanup_policy');
+}
+
+export async function setStaleCleanupPolicy(policy: StaleCleanupPolicy): Promise<StaleCleanupPolicy> {
+  return await invoke('set_stale_cleanup_policy', { policy });
+}
+
+/** Local branches merged into the default branch that no worktree has checked out */
+export async function suggestBranchCleanup(repoPath: string): Promise<BranchCleanupSuggestion> {
+  return await invoke('suggest_branch_cleanup', { repoPath });
+}
+
+/** Delete the confirmed branches that are still suggested; the others are reported as skipped */
+export async function deleteMergedBranches(
+  repoPath: string,
+  confirmList: string[]
+): Promise<BranchDeletionReport> {
+  return await invoke('delete_merged_branches', { repoPath, confirmList });
+}
+
+/** Rejects with a `RenameWorktreeError` */
+export async function renameWorktree(
+  oldPath: string,
+  newName: string,
+  stopProcesses?: boolean
+): Promise<WorktreeMetadata> {
+  return await invoke('rename_worktree', { oldPath, newName, stopProcesses });
+}
+
+/** Move a worktree to another path; rejects with a `RenameWorktreeError` */
+export async function moveWorktree(
+  oldPath: string,
+  newPath: string,
+  stopProcesses?: boolean
+): Promise<WorktreeMetadata> {
+  return await invoke('move_worktree', { oldPath, newPath, stopProcesses });
+}
+
+export function renameWorktreeErrorMessage(err: unknown): string {
+  if (typeof err !== 'object' || err === null || !('kind' in err)) {
+    return String(err);
+  }
+  const error = err as RenameWorktreeError;
+  switch (error.kind) {
+    case 'invalid-name':
+      return `Invalid worktree name: "${error.name}"`;
+    case 'target-exists':
+      return `${error.path} already exists`;
+    case 'processes-running':
+      return `Processes still running in the worktree: ${error.processes
+        .map((p) => `${p.command} (${p.pid})`)
+        .join(', ')}`;
+    case 'busy':
+      return `${error.path} is in use by another program (tried ${error.attempts} times)`;
+    case 'failed':
+      return error.message;
+  }
+}
+
+export async function duplicateWorktree(path: string, newName: string): Promise<WorktreeMetadata> {
+  return await invoke('duplicate_worktree', { path, newName });
+}
+
+export async function checkoutInWorktree(path: string, gitRef: string): Promise<WorktreeMetadata> {
+  return await invoke('checkout_in_worktree', { path, gitRef });
+}
+
+/** Lock a worktree; with `expiresAt` (ms) the app lifts the lock at that time. */
+export async function lockWorktree(
+  path: string,
+  reason: string | undefined,
+  expiresAt?: number
+): Promise<void> {
+  return await invoke('lock_worktree', { path, reason, expiresAt });
 }
 
 export async function unlockWorktree(path: string): Promise<void> {
   return await invoke('unlock_worktree', { path });
 }
 
+export async function getRepoQueueStatus(): Promise<RepoQueueStatus[]> {
+  return await invoke('get_repo_queue_status');
+}
+
+/** Remove git lock files left by a crashed git process; returns the removed files. */
+export async function forceRemoveStaleLock(repoPath: string): Promise<string[]> {
+  return await invoke('force_remove_stale_lock', { repoPath });
+}
+
+export async function hideWorktree(path: string): Promise<void> {
+  return await invoke('hide_worktree', { path });
+}
+
+export async function unhideWorktree(path: string): Promise<void> {
+  return await invoke('unhide_worktree', { path });
+}
+
+/** Register the URL of the app a worktree serves; `null` clears it. */
+export async function registerDevUrl(path: string, url: string | null): Promise<void> {
+  return await invoke('register_dev_url', { path, url });
+}
+
+export async function setWorktreeDescription(path: string, description: string | null): Promise<void> {
+  return await invoke('set_worktree_description', { path, description });
+}
+
+export async function addWorktreeLabels(path: string, labels: string[]): Promise<string[]> {
+  return await invoke('add_worktree_labels', { path, labels });
+}
+
+export async function removeWorktreeLabels(path: string, labels: string[]): Promise<string[]> {
+  return await invoke('remove_worktree_labels', { path, labels });
+}
+
+export async function addRepositoryLabels(id: string, labels: string[]): Promise<string[]> {
+  return await invoke('add_repository_labels', { id, labels });
+}
+
+export async function removeRepositoryLabels(id: string, labels: string[]): Promise<string[]> {
+  return await invoke('remove_repository_labels', { id, labels });
+}
+
+export async function listLabels(): Promise<LabelUsage[]> {
+  return await invoke('list_labels');
+}
+
+export async function renameLabel(from: string, to: string): Promise<number> {
+  return await invoke('rename_label', { from, to });
+}
+
+export async function deleteLabel(label: string): Promise<number> {
+  return await invoke('delete_label', { label });
+}
+
+/** Repositories carrying the labels, or those of their worktrees that do */
+export async function filterByLabels(
+  labels: string[],
+  matchAll?: boolean,
+  includeHidden?: boolean
+): Promise<Repository[]> {
+  return await invoke('filter_by_labels', { labels, matchAll, includeHidden });
+}
+
+/** Open a worktree's dev server in the browser. Returns the opened URL. */
+export async function openDevUrl(path: string): Promise<string> {
+  return await invoke<string>('open_dev_url', { path });
+}
+
+/** TCP ports listened on by processes running inside a worktree. */
+export async function detectDevPorts(path: string): Promise<number[]> {
+  return await invoke<number[]>('detect_dev_ports', { path });
+}
+
+export async function shareDependencies(path: string, mode?: SharedDepsMode): Promise<SharedDir[]> {
+  return await invoke<SharedDir[]>('share_dependencies', { path, mode });
+}
+
+export async function getProcessesInWorktree(path: string): Promise<WorktreeProcess[]> {
+  return await invoke<WorktreeProcess[]>('get_processes_in_worktree', { path });
+}
+
+export async function terminateWorktreeProcesses(
+  path: string,
+  pids?: number[],
+  force?: boolean
+): Promise<number[]> {
+  return await invoke<number[]>('terminate_worktree_processes', { path, pids, force });
+}
+
+/** Wait up to `timeout` ms for a worktree to be unlocked. */
+export async function waitForUnlock(path: string, timeout: number): Promise<boolean> {
+  return await invoke('wait_for_unlock', { path, timeout });
+}
+
+export async function renameRepository(id: string, displayName: string | null): Promise<Repository> {
+  return await invoke('rename_repository', { id, displayName });
+}
+
+export async function setRepositoryScanSettings(
+  id: string,
+  scanSettings: RepoScanSettings
+): Promise<Repository> {
+  return await invoke('set_repository_scan_settings', { id, scanSettings });
+}
+
+export async function deepScanRepository(id: string): Promise<DeepScanResult> {
+  return await invoke('deep_scan_repository', { id });
+}
+
+export async function setRepositoryBaseBranch(id: string, branch: string | null): Promise<Repository> {
+  return await invoke('set_repository_base_branch', { id, branch });
+}
+
+export async function refreshDefaultBranch(id: string): Promise<DefaultBranchChange | null> {
+  return await invoke('refresh_default_branch', { id });
+}
+
+export async function applyDefaultBranchChange(
+  id: string,
+  previous: string,
+  current: string
+): Promise<Repository> {
+  return await invoke('apply_default_branch_change', { id, previous, current });
+}
+
+export async function getWorktreeConfig(path: string): Promise<WorktreeConfigEntry[]> {
+  return await invoke('get_worktree_config', { path });
+}
+
+export async function setWorktreeConfig(
+  path: string,
+  key: string,
+  value: string | null
+): Promise<WorktreeConfigEntry[]> {
+  return await invoke('set_worktree_config', { path, key, value });
+}
+
+export async function getRepoConfigSummary(
+  repoPath: string,
+  refresh?: boolean
+): Promise<RepoConfigSummary> {
+  return await invoke('get_repo_config_summary', { repoPath, refresh });
+}
+
+export async function listWorktreeFiles(
+  path: string,
+  dir?: string,
+  depth?: number
+): Promise<FileTree> {
+  return await invoke('list_worktree_files', { path, dir, depth });
+}
+
+export async function stageFiles(path: string, files: string[] = []): Promise<WorktreeStatus> {
+  return await invoke('stage_files', { path, files });
+}
+
+export async function unstageFiles(path: string, files: string[] = []): Promise<WorktreeStatus> {
+  return await invoke('unstage_files', { path, files });
+}
+
+export async function commitChanges(
+  path: string,
+  message: string,
+  stageAll?: boolean
+): Promise<CommitInfo> {
+  return await invoke('commit_changes', { path, message, stageAll });
+}
+
+/** Trust the approved keys of an SSH host reported by ssh-host-key-unknown, then retry the operation */
+export async function trustSshHost(
+  host: string,
+  port: number | null | undefined,
+  fingerprints: string[]
+): Promise<SshHostKey[]> {
+  return await invoke('trust_ssh_host', { host, port, fingerprints });
+}
+
+export async function fetchWorktree(path: string): Promise<SyncResult> {
+  return await invoke('fetch_worktree', { path });
+}
+
+export async function pullWorktree(path: string, rebase?: boolean): Promise<SyncResult> {
+  return await invoke('pull_worktree', { path, rebase });
+}
+
+export async function pushWorktree(path: string, force?: boolean): Promise<SyncResult> {
+  return await invoke('push_worktree', { path, force });
+}
+
+export async function refreshRemoteProtection(id: string): Promise<string[]> {
+  return await invoke('refresh_remote_protection', { id });
+}
+
+export async function rebaseWorktree(path: string, onto?: string): Promise<RebaseResult> {
+  return await invoke('rebase_worktree', { path, onto });
+}
+
+export async function continueRebase(path: string): Promise<RebaseResult> {
+  return await invoke('continue_rebase', { path });
+}
+
+export async function abortRebase(path: string): Promise<RebaseResult> {
+  return await invoke('abort_rebase', { path });
+}
+
+export async function mergeWorktreeIntoBase(
+  worktreePath: string,
+  strategy?: MergeStrategy,
+  targetBranch?: string,
+  message?: string
+): Promise<BaseMergeResult> {
+  return await invoke('merge_worktree_into_base', { worktreePath, strategy, targetBranch, message });
+}
+
 export async function openInTerminal(
   path: string,
   app: string,
@@ -121,7 +573,10 @@ export async function createTask(
   sourceCommit: string | undefined,
   sourceRepoPath: string,
   agentType: string,
-  models: ModelSelection[]
+  models: ModelSelection[],
+  includeUncommitted?: boolean,
+  sparsePaths?: string[],
+  mode?: TaskMode
 ): Promise<Task> {
   return await invoke('create_task', {
     name,
@@ -131,6 +586,9 @@ export async function createTask(
     sourceRepoPath,
     agentType,
     models,
+    includeUncommitted,
+    sparsePaths,
+    mode,
   });
 }
 
@@ -138,6 +596,16 @@ export async function getTasks(): Promise<Task[]> {
   return await invoke('get_tasks');
 }
 
+/** A task's timeline, which getTasks leaves out */
+export async function getTaskTimeline(taskId: string): Promise<TimelineEntry[]> {
+  return await invoke('get_task_timeline', { taskId });
+}
+
+/** The task store load, or null until tasks-loaded was emitted */
+export async function getTasksLoaded(): Promise<TasksLoadedEvent | null> {
+  return await invoke('get_tasks_loaded');
+}
+
 export async function getTask(taskId: string): Promise<Task> {
   return await invoke('get_task', { taskId });
 }
@@ -154,6 +622,28 @@ export async function deleteTask(taskId: string, deleteWorktrees: boolean): Prom
   return await invoke('delete_task', { taskId, deleteWorktrees });
 }
 
+/**
+ * Point the tasks of a renamed or moved repository at its new path and repair
+ * their agent worktrees
+ */
+export async function repointTasks(
+  oldRepoPath: string,
+  newRepoPath: string
+): Promise<RepointTasksReport> {
+  return await invoke('repoint_tasks', { oldRepoPath, newRepoPath });
+}
+
+export async function listTaskArtifacts(taskId: string): Promise<TaskArtifact[]> {
+  return await invoke<TaskArtifact[]>('list_task_artifacts', { taskId });
+}
+
+export async function cleanupTaskArtifacts(
+  taskId: string,
+  keepAccepted: boolean
+): Promise<TaskArtifactCleanup> {
+  return await invoke<TaskArtifactCleanup>('cleanup_task_artifacts', { taskId, keepAccepted });
+}
+
 // ============ Agent Management Commands ============
 
 export async function addAgentToTask(
@@ -165,6 +655,59 @@ export async function addAgentToTask(
   return await invoke('add_agent_to_task', { taskId, modelId, providerId, agentType });
 }
 
+/**
+ * Add an agent that continues another agent's commits and uncommitted changes
+ */
+export async function forkAgent(
+  taskId: string,
+  sourceAgentId: string,
+  modelId: string,
+  providerId: string,
+  agentType: string | undefined
+): Promise<Task> {
+  return await invoke('fork_agent', { taskId, sourceAgentId, modelId, providerId, agentType });
+}
+
+/**
+ * Read the scratchpad shared by a task's agents
+ */
+export async function readTaskScratchpad(taskId: string): Promise<string> {
+  return await invoke('read_task_scratchpad', { taskId });
+}
+
+/**
+ * Append a note to a task's scratchpad, returning the new contents
+ */
+export async function appendTaskScratchpad(
+  taskId: string,
+  text: string,
+  author?: string
+): Promise<string> {
+  return await invoke('append_task_scratchpad', { taskId, text, author });
+}
+
+/**
+ * Record OpenCode events of an agent in its transcript
+ */
+export async function appendAgentTranscript(
+  taskId: string,
+  agentId: string,
+  events: unknown[]
+): Promise<number> {
+  return await invoke('append_agent_transcript', { taskId, agentId, events });
+}
+
+/**
+ * Read an agent's transcript, or the entries of `range`
+ */
+export async function getAgentTranscript(
+  taskId: string,
+  agentId: string,
+  range?: TranscriptRange
+): Promise<AgentTranscript> {
+  return await invoke('get_agent_transcript', { taskId, agentId, range });
+}
+
 export async function removeAgentFromTask(
   taskId: string,
   agentId: string,
@@ -193,6 +736,33 @@ export async function acceptAgent(taskId: string, agentId: string): Promise<void
   return await invoke('accept_agent', { taskId, agentId });
 }
 
+/**
+ * Compare the agents' diffs file by file (identical, similar, divergent)
+ */
+export async function computeAgentAgreement(taskId: string): Promise<AgentAgreementReport> {
+  return await invoke('compute_agent_agreement', { taskId });
+}
+
+/**
+ * Write the agents' diffs side by side into a standalone HTML file (all agents when
+ * none are given). dest is a file, or a folder to write <task>-comparison.html into
+ */
+export async function exportAgentComparison(
+  taskId: string,
+  agents: string[],
+  dest: string
+): Promise<AgentComparisonExport> {
+  return await invoke('export_agent_comparison', { taskId, agents, dest });
+}
+
+/**
+ * Acceptance rate, average score and diff size per model across tasks with an
+ * accepted agent, only those of repoPath when given
+ */
+export async function getModelLeaderboard(repoPath?: string): Promise<ModelLeaderboardEntry[]> {
+  return await invoke('get_model_leaderboard', { repoPath });
+}
+
 export async function cleanupUnacceptedAgents(taskId: string): Promise<void> {
   return await invoke('cleanup_unaccepted_agents', { taskId });
 }
@@ -233,3 +803,138 @@ export async function validateTaskWorktrees(taskId: string): Promise<string[]> {
 export async function recreateAgentWorktree(taskId: string, agentId: string): Promise<string> {
   return await invoke('recreate_agent_worktree', { taskId, agentId });
 }
+
+// ============ API Version Commands ============
+
+/**
+ * IPC schema version this frontend was built against.
+ * Bump together with `API_VERSION` in src-tauri/crates/aristar-core/src/core/api_version.rs.
+ */
+export const API_VERSION = 2;
+
+export async function getApiVersion(): Promise<ApiVersionInfo> {
+  return await invoke('get_api_version');
+}
+
+/**
+ * Check that the backend supports this frontend; rejects with a message naming
+ * the side to rebuild when it doesn't
+ */
+export async function checkApiCompatibility(): Promise<ApiVersionInfo> {
+  return await invoke('check_api_compatibility', { frontendVersion: API_VERSION });
+}
+
+// ============ Notification Commands ============
+
+/**
+ * Notifications held back while a Focus / Do Not Disturb mode is on
+ */
+export async function getPendingNotifications(): Promise<DesktopNotification[]> {
+  return await invoke('get_pending_notifications');
+}
+
+// ============ Redaction Commands ============
+
+/**
+ * Run the active redaction rules (applied to logs and transcripts) on a sample
+ */
+export async function testRedaction(sample: string): Promise<RedactionPreview> {
+  return await invoke('test_redaction', { sample });
+}
+
+/**
+ * Port reservations of OpenCode servers, dev servers and the event socket
+ */
+export async function getPortReservations(): Promise<PortReport> {
+  return await invoke('get_port_reservations');
+}
+
+/**
+ * Release the ports reserved for a worktree path (or "app"), only a service's when given
+ */
+export async function releasePortReservations(owner: string, service?: string): Promise<number> {
+  return await invoke('release_port_reservations', { owner, service });
+}
+
+// ============ Plugin Commands ============
+
+/**
+ * Plugins in ~/.aristar-worktrees/plugins/, and the manifests that failed to load
+ */
+export async function listPlugins(): Promise<PluginList> {
+  return await invoke('list_plugins');
+}
+
+/**
+ * Run a plugin action on a repository id, worktree path or task id, per the action's context
+ */
+export async function runPluginAction(
+  pluginId: string,
+  actionId: string,
+  target: string
+): Promise<PluginActionResult> {
+  return await invoke('run_plugin_action', { pluginId, actionId, target });
+}
+
+// ============ Workspace Snapshot Commands ============
+
+/** localStorage keys of the persisted stores captured as UI state */
+const SNAPSHOT_UI_STATE_KEYS = [
+  'aristar-worktrees-store',
+  'aristar-agent-manager-store',
+  'aristar-diff-store',
+];
+
+function collectUiState(): Record<string, string> {
+  const uiState: Record<string, string> = {};
+  for (const key of SNAPSHOT_UI_STATE_KEYS) {
+    const value = localStorage.getItem(key);
+    if (value !== null) uiState[key] = value;
+  }
+  return uiState;
+}
+
+/**
+ * Save the workspace (store, settings, tasks and UI state) as snapshot `name`
+ */
+export async function saveWorkspaceSnapshot(name: string): Promise<WorkspaceSnapshotInfo> {
+  return await invoke('save_workspace_snapshot', { name, uiState: collectUiState() });
+}
+
+/**
+ * Load snapshot `name` and reload the app with its UI state.
+ * The current workspace is saved as the `autosave` snapshot first.
+ */
+export async function loadWorkspaceSnapshot(name: string): Promise<void> {
+  const uiState = await invoke<Record<string, string> | null>('load_workspace_snapshot', {
+    name,
+    currentUiState: collectUiState(),
+  });
+  for (const key of SNAPSHOT_UI_STATE_KEYS) {
+    const value = uiState?.[key];
+    if (value !== undefined) {
+      localStorage.setItem(key, value);
+    } else {
+      localStorage.removeItem(key);
+    }
+  }
+  window.location.reload();
+}
+
+export async function listWorkspaceSnapshots(): Promise<WorkspaceSnapshotInfo[]> {
+  return await invoke('list_workspace_snapshots');
+}
+
+export async function deleteWorkspaceSnapshot(name: string): Promise<void> {
+  return await invoke('delete_workspace_snapshot', { name });
+}
+
+/** What startup recovery repaired after the app was killed mid-operation */
+export async function getRecoveryReport(): Promise<RecoveryReport> {
+  return await invoke('get_recovery_report');
+}
+
+/** Search repositories, worktrees, tasks, presets and schedules, best first */
+export async function globalSearch(query: string, limit?: number): Promise<SearchResult[]> {
+  return await invoke('global_search', { query, limit });
+}
@@ -1,6 +1,6 @@
 // Core library utilities
 
-export { cn, generateId, getRepositoryName, truncatePath, formatDate, getIconPath } from './utils';
+export { cn, generateId, getRepositoryName, getRepositoryLabel, truncatePath, formatDate, getIconPath } from './utils';
 export type { IconCategory } from './utils';
 export * as commands from './commands';
 export { logger, Logger, type LogLevel } from './logger';
@@ -14,6 +14,11 @@ export function getRepositoryName(path: string): string {
   return parts[parts.length - 1] || 'Unknown';
 }
 
+/** Name a repository is listed with: its display name, else the folder name. */
+export function getRepositoryLabel(repo: { path: string; displayName?: string | null }): string {
+  return repo.displayName || getRepositoryName(repo.path);
+}
+
 export function truncatePath(path: string, maxLength: number = 40): string {
   if (path.length <= maxLength) return path;
   const parts = path.split('/');
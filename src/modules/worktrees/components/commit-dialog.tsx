@@ -0,0 +1,138 @@
+'use client';
+
+import { useEffect, useState } from 'react';
+import { Loader2 } from 'lucide-react';
+import { Button } from '@core/ui/button';
+import { Textarea } from '@core/ui/textarea';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@core/ui/dialog';
+import { Label } from '@core/ui/label';
+import { Switch } from '@core/ui/switch';
+import { commands } from '@core/lib';
+import type { WorktreeMetadata, WorktreeStatus } from '@/store/types';
+
+interface CommitDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  worktree: WorktreeMetadata | null;
+}
+
+export function CommitDialog({ open, onOpenChange, worktree }: CommitDialogProps) {
+  const [message, setMessage] = useState('');
+  const [stageAll, setStageAll] = useState(true);
+  const [status, setStatus] = useState<WorktreeStatus | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (open && worktree) {
+      commands.getWorktreeStatus(worktree.path).then(setStatus).catch(() => setStatus(null));
+    }
+  }, [open, worktree]);
+
+  const handleOpenChange = (isOpen: boolean) => {
+    if (!isOpen) {
+      setMessage('');
+      setStatus(null);
+      setError(null);
+    }
+    onOpenChange(isOpen);
+  };
+
+  const handleUnstageAll = async () => {
+    if (!worktree) return;
+    try {
+      setStatus(await commands.unstageFiles(worktree.path));
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  const handleSubmit = async () => {
+    if (!worktree || !message.trim()) {
+      setError('Please enter a commit message');
+      return;
+    }
+
+    setLoading(true);
+    setError(null);
+
+    try {
+      await commands.commitChanges(worktree.path, message, stageAll);
+      handleOpenChange(false);
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  return (
+    <Dialog open={open} onOpenChange={handleOpenChange}>
+      <DialogContent className="sm:max-w-[480px]">
+        <DialogHeader>
+          <DialogTitle>Commit Changes</DialogTitle>
+          <DialogDescription>
+            Commit the changes in "{worktree?.name}".
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-4">
+          {status && (
+            <div className="flex items-center justify-between text-xs text-muted-foreground">
+              <span>
+                {status.staged} staged, {status.modified} modified, {status.untracked} untracked
+              </span>
+              {status.staged > 0 && (
+                <Button variant="ghost" size="sm" className="h-6 text-xs" onClick={handleUnstageAll}>
+                  Unstage all
+                </Button>
+              )}
+            </div>
+          )}
+
+          <div className="space-y-2">
+            <Label htmlFor="commit-message">Message</Label>
+            <Textarea
+              id="commit-message"
+              placeholder="Describe the changes"
+              value={message}
+              onChange={(e) => setMessage(e.target.value)}
+              rows={4}
+              className="resize-none"
+            />
+          </div>
+
+          <div className="flex items-center justify-between">
+            <Label htmlFor="stage-all" className="text-xs cursor-pointer">
+              Stage all changes first
+            </Label>
+            <Switch id="stage-all" checked={stageAll} onCheckedChange={setStageAll} />
+          </div>
+
+          {error && (
+            <div className="rounded-md bg-destructive/10 p-3 text-sm text-destructive">
+              {error}
+            </div>
+          )}
+        </div>
+
+        <DialogFooter>
+          <Button variant="outline" onClick={() => handleOpenChange(false)}>
+            Cancel
+          </Button>
+          <Button onClick={handleSubmit} disabled={loading || !message.trim()}>
+            {loading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Commit
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
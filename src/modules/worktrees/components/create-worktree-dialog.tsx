@@ -53,8 +53,7 @@ export function CreateWorktreeDialog({ open, onOpenChange }: CreateWorktreeDialo
   }, [open, selectedRepo]);
 
   const handleSubmit = async () => {
-    if (!selectedRepo || !name.trim()) {
-      setError('Please enter a worktree name');
+    if (!selectedRepo) {
       return;
     }
 
@@ -64,7 +63,7 @@ export function CreateWorktreeDialog({ open, onOpenChange }: CreateWorktreeDialo
     try {
       await createWorktree(
         selectedRepo.path,
-        name.trim(),
+        name.trim() || undefined,
         sourceType === 'existing-branch' ? selectedBranch : undefined,
         sourceType === 'commit' && selectedCommit ? selectedCommit.hash : undefined,
         startupScript.trim() || undefined,
@@ -98,7 +97,7 @@ export function CreateWorktreeDialog({ open, onOpenChange }: CreateWorktreeDialo
             <Label htmlFor="name" className="text-xs text-muted-foreground">Name</Label>
             <Input
               id="name"
-              placeholder="feature/my-feature"
+              placeholder="Leave empty to generate from the branch"
               value={name}
               onChange={(e) => handleNameChange(e.target.value)}
               className="h-9"
@@ -166,7 +165,7 @@ export function CreateWorktreeDialog({ open, onOpenChange }: CreateWorktreeDialo
           <Button variant="ghost" onClick={() => onOpenChange(false)}>
             Cancel
           </Button>
-          <Button onClick={handleSubmit} disabled={loading || !name.trim()}>
+          <Button onClick={handleSubmit} disabled={loading}>
             {loading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
             Create
           </Button>
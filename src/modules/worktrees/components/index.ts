@@ -3,4 +3,6 @@
 export { WorktreeCard } from './worktree-card';
 export { CreateWorktreeDialog } from './create-worktree-dialog';
 export { RenameDialog } from './rename-dialog';
+export { CommitDialog } from './commit-dialog';
 export { RepositorySidebar } from './repository-sidebar';
+export { RepositorySettingsDrawer } from './repository-settings-drawer';
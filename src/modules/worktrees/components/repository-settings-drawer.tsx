@@ -0,0 +1,148 @@
+'use client';
+
+import { useEffect, useState } from 'react';
+import { AlertTriangle, Loader2, RefreshCw } from 'lucide-react';
+import { Button } from '@core/ui/button';
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from '@core/ui/dialog';
+import { commands, getRepositoryLabel } from '@core/lib';
+import type { RepoConfigSummary, Repository } from '@/store/types';
+
+interface RepositorySettingsDrawerProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+  repository: Repository | null;
+}
+
+function formatSize(bytes: number): string {
+  const units = ['B', 'KB', 'MB', 'GB'];
+  let size = bytes;
+  let unit = 0;
+  while (size >= 1024 && unit < units.length - 1) {
+    size /= 1024;
+    unit++;
+  }
+  return `${size.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+}
+
+function Row({ label, value }: { label: string; value?: string }) {
+  return (
+    <div className="flex justify-between gap-4 text-sm">
+      <span className="text-muted-foreground">{label}</span>
+      <span className="truncate text-right" title={value}>
+        {value ?? <span className="text-muted-foreground">Not set</span>}
+      </span>
+    </div>
+  );
+}
+
+export function RepositorySettingsDrawer({
+  open,
+  onOpenChange,
+  repository,
+}: RepositorySettingsDrawerProps) {
+  const [summary, setSummary] = useState<RepoConfigSummary | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadSummary = async (refresh: boolean) => {
+    if (!repository) return;
+    setLoading(true);
+    setError(null);
+    try {
+      setSummary(await commands.getRepoConfigSummary(repository.path, refresh));
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    if (open) {
+      setSummary(null);
+      loadSummary(false);
+    }
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [open, repository?.path]);
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="top-0 right-0 left-auto h-full max-w-sm translate-x-0 translate-y-0 content-start overflow-y-auto rounded-none border-l">
+        <DialogHeader>
+          <DialogTitle>Repository Settings</DialogTitle>
+          <DialogDescription>
+            {repository ? getRepositoryLabel(repository) : ''}
+          </DialogDescription>
+        </DialogHeader>
+
+        {error && (
+          <div className="rounded-md bg-destructive/10 p-3 text-sm text-destructive">
+            {error}
+          </div>
+        )}
+
+        {!summary && loading && (
+          <div className="flex justify-center py-8">
+            <Loader2 className="h-5 w-5 animate-spin text-muted-foreground" />
+          </div>
+        )}
+
+        {summary && (
+          <div className="space-y-4">
+            {summary.warnings.length > 0 && (
+              <div className="space-y-1 rounded-md bg-destructive/10 p-3">
+                {summary.warnings.map((warning) => (
+                  <p key={warning} className="flex items-start gap-2 text-sm text-destructive">
+                    <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0" />
+                    {warning}
+                  </p>
+                ))}
+              </div>
+            )}
+
+            <div className="space-y-2">
+              <Row label="user.name" value={summary.userName} />
+              <Row label="user.email" value={summary.userEmail} />
+              <Row label="core.hooksPath" value={summary.hooksPath} />
+              <Row label="Default branch" value={summary.defaultBranch} />
+              <Row label="Git LFS" value={summary.usesLfs ? 'Used' : 'Not used'} />
+              <Row label="Submodules" value={summary.hasSubmodules ? 'Yes' : 'No'} />
+              <Row label="Size" value={formatSize(summary.sizeBytes)} />
+            </div>
+
+            <div className="space-y-1">
+              <p className="text-sm font-medium">Remotes</p>
+              {summary.remotes.length === 0 ? (
+                <p className="text-sm text-muted-foreground">No remotes</p>
+              ) : (
+                summary.remotes.map((remote) => (
+                  <Row key={remote.name} label={remote.name} value={remote.url} />
+                ))
+              )}
+            </div>
+
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => loadSummary(true)}
+              disabled={loading}
+            >
+              {loading ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <RefreshCw className="mr-2 h-4 w-4" />
+              )}
+              Refresh
+            </Button>
+          </div>
+        )}
+      </DialogContent>
+    </Dialog>
+  );
+}
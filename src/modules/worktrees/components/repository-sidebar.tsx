@@ -1,14 +1,15 @@
 'use client';
 
 import { useEffect, useState } from 'react';
-import { FolderGit2, Trash2, ChevronDown, ChevronRight, PanelLeftClose, PanelLeft } from 'lucide-react';
+import { FolderGit2, Trash2, ChevronDown, ChevronRight, PanelLeftClose, PanelLeft, Settings } from 'lucide-react';
 import { ScrollArea } from '@core/ui/scroll-area';
 import { Separator } from '@core/ui/separator';
 import { Tooltip, TooltipContent, TooltipTrigger } from '@core/ui/tooltip';
 import { Button } from '@core/ui/button';
 import { useAppStore } from '@/store/use-app-store';
-import { getRepositoryName, truncatePath } from '@core/lib/utils';
+import { getRepositoryLabel, truncatePath } from '@core/lib/utils';
 import { cn } from '@core/lib/utils';
+import { RepositorySettingsDrawer } from './repository-settings-drawer';
 
 interface RepositorySidebarProps {
   onSelectRepository: (id: string) => void;
@@ -18,6 +19,8 @@ interface RepositorySidebarProps {
 export function RepositorySidebar({ onSelectRepository, onRemoveRepository }: RepositorySidebarProps) {
   const { repositories, selectedRepositoryId, settings, setSettings } = useAppStore();
   const [expandedIds, setExpandedIds] = useState<Set<string>>(new Set());
+  const [settingsRepoId, setSettingsRepoId] = useState<string | null>(null);
+  const settingsRepo = repositories.find((r) => r.id === settingsRepoId) ?? null;
   
   const isCollapsed = settings.sidebarCollapsed;
   const toggleSidebar = () => setSettings({ sidebarCollapsed: !isCollapsed });
@@ -80,7 +83,7 @@ export function RepositorySidebar({ onSelectRepository, onRemoveRepository }: Re
                     <FolderGit2 className="h-4 w-4" />
                   </button>
                 </TooltipTrigger>
-                <TooltipContent side="right">{getRepositoryName(repo.path)}</TooltipContent>
+                <TooltipContent side="right">{getRepositoryLabel(repo)}</TooltipContent>
               </Tooltip>
             ))}
           </div>
@@ -139,8 +142,22 @@ export function RepositorySidebar({ onSelectRepository, onRemoveRepository }: Re
                   </button>
                   <FolderGit2 className="h-4 w-4 shrink-0" />
                   <span className="truncate flex-1">
-                    {getRepositoryName(repo.path)}
+                    {getRepositoryLabel(repo)}
                   </span>
+                  <Tooltip>
+                    <TooltipTrigger asChild>
+                      <button
+                        onClick={(e) => {
+                          e.stopPropagation();
+                          setSettingsRepoId(repo.id);
+                        }}
+                        className="opacity-0 group-hover:opacity-100 transition-opacity p-1 hover:bg-sidebar-accent rounded"
+                      >
+                        <Settings className="h-3 w-3" />
+                      </button>
+                    </TooltipTrigger>
+                    <TooltipContent>Repository settings</TooltipContent>
+                  </Tooltip>
                   <Tooltip>
                     <TooltipTrigger asChild>
                       <button
@@ -172,6 +189,11 @@ export function RepositorySidebar({ onSelectRepository, onRemoveRepository }: Re
           )}
         </div>
       </ScrollArea>
+      <RepositorySettingsDrawer
+        open={settingsRepo !== null}
+        onOpenChange={(open) => !open && setSettingsRepoId(null)}
+        repository={settingsRepo}
+      />
     </aside>
   );
 }
@@ -11,6 +11,8 @@ import {
   Lock,
   Unlock,
   Edit2,
+  GitCommit,
+  Globe,
   Trash2,
 } from 'lucide-react';
 import { Button } from '@core/ui/button';
@@ -27,9 +29,23 @@ import type { WorktreeMetadata } from '@/store/types';
 import { getBranchColorIndex, getBranchColorStyle } from '../lib/branch-colors';
 import { cn } from '@core/lib/utils';
 
+function describeHead(worktree: WorktreeMetadata): string {
+  switch (worktree.headState?.kind) {
+    case 'branch':
+      return worktree.headState.value;
+    case 'detached':
+      return `detached at ${worktree.headState.value.slice(0, 7)}`;
+    case 'unborn':
+      return worktree.branch ? `${worktree.branch} (no commits)` : 'no commits';
+    default:
+      return worktree.branch || `${worktree.commit?.slice(0, 7)}`;
+  }
+}
+
 interface WorktreeCardProps {
   worktree: WorktreeMetadata;
   onRename: (worktree: WorktreeMetadata) => void;
+  onCommit: (worktree: WorktreeMetadata) => void;
   onDelete: (worktree: WorktreeMetadata) => void;
   onLock: (worktree: WorktreeMetadata) => void;
   onUnlock: (worktree: WorktreeMetadata) => void;
@@ -38,11 +54,13 @@ interface WorktreeCardProps {
 export function WorktreeCard({
   worktree,
   onRename,
+  onCommit,
   onDelete,
   onLock,
   onUnlock,
 }: WorktreeCardProps) {
-  const { openInTerminal, openInEditor, revealInFinder, copyToClipboard } = useAppStore();
+  const { openInTerminal, openInEditor, revealInFinder, openDevUrl, copyToClipboard } =
+    useAppStore();
   const [copied, setCopied] = useState(false);
   
   const colorIndex = getBranchColorIndex(worktree.branch);
@@ -87,7 +105,7 @@ export function WorktreeCard({
           )}
         </div>
         <p className="truncate text-xs text-muted-foreground">
-          {worktree.branch || `${worktree.commit?.slice(0, 7)}`}
+          {describeHead(worktree)}
         </p>
       </div>
 
@@ -132,10 +150,18 @@ export function WorktreeCard({
               <FolderOpen className="mr-2 h-4 w-4" />
               Reveal
             </DropdownMenuItem>
+            <DropdownMenuItem onClick={() => openDevUrl(worktree.path)}>
+              <Globe className="mr-2 h-4 w-4" />
+              Open App
+            </DropdownMenuItem>
             <DropdownMenuItem onClick={handleCopy}>
               <Copy className="mr-2 h-4 w-4" />
               {copied ? 'Copied!' : 'Copy Path'}
             </DropdownMenuItem>
+            <DropdownMenuItem onClick={() => onCommit(worktree)}>
+              <GitCommit className="mr-2 h-4 w-4" />
+              Commit...
+            </DropdownMenuItem>
             
             <DropdownMenuSeparator />
             
@@ -0,0 +1,1393 @@
+// Generated from the Rust types by `cargo run -p aristar-core --bin generate-schemas`. Do not edit.
+
+/** Per-file agreement of a task's agents, from `compute_agent_agreement`. */
+export interface AgentAgreementReport {
+  /** Agents compared (those with a worktree) */
+  agentIds: string[];
+  divergentCount: number;
+  /** Files changed by any agent, divergent first */
+  files: FileAgreementEntry[];
+  identicalCount: number;
+  similarCount: number;
+}
+
+/** HTML comparison written by `export_agent_comparison`. */
+export interface AgentComparisonExport {
+  /** Agents in the comparison, one column each */
+  agentIds: string[];
+  /** Files changed by any of them */
+  fileCount: number;
+  /** File written */
+  path: string;
+  sizeBytes: number;
+}
+
+/** Size of an agent's diff against the task source. */
+export interface AgentDiffStats {
+  deletions: number;
+  filesChanged: number;
+  insertions: number;
+}
+
+/** One agent of a task whose winner was accepted. */
+export interface AgentOutcome {
+  accepted: boolean;
+  agentId: string;
+  /** `None` when the worktree was gone or the diff could not be computed */
+  diff?: AgentDiffStats | null;
+  modelId: string;
+  providerId: string;
+  /** Timestamp of the accept (milliseconds since epoch) */
+  recordedAt: number;
+  /** Score from the task's pipeline run, when it scored the agent */
+  score?: number | null;
+}
+
+/** Payload of the `agent-port-changed` event, emitted when an agent's OpenCode server could not get its preferred port back. */
+export interface AgentPortChangedEvent {
+  agentId: string;
+  port: number;
+  previousPort: number;
+  taskId: string;
+}
+
+/** Payload of the `agent-quota-exceeded` event. */
+export interface AgentQuotaExceededEvent {
+  agentId: string;
+  exceeded: DiskQuotaExceeded;
+  taskId: string;
+  worktreePath: string;
+}
+
+/** Score of a completed agent. */
+export interface AgentScore {
+  agentId: string;
+  checksPassed: number;
+  checksTotal: number;
+  /** Unresolved review comments, each costing 10 points */
+  openComments: number;
+  /** 0-100 */
+  score: number;
+}
+
+/** Status of an agent. */
+export type AgentStatus = "idle" | "running" | "paused" | "completed" | "failed" | "waiting";
+
+/** Payload of the `agent-status-changed` event. */
+export interface AgentStatusChangedEvent {
+  agentId: string;
+  status: AgentStatus;
+  taskId: string;
+}
+
+/** Per-agent overview of a task, used for comparing agents at a glance. */
+export interface AgentSummary {
+  accepted: boolean;
+  agentId: string;
+  /** When the agent was created, e.g. "2 days ago" */
+  createdRelative: string;
+  /** When the agent's server was last started, e.g. "5 minutes ago" */
+  lastStartedRelative?: string | null;
+  modelId: string;
+  /** Number of unresolved review comments */
+  openComments: number;
+  providerId: string;
+  /** Time since the last start while the agent is running, e.g. "1 h 5 min" */
+  runningFor?: string | null;
+  status: AgentStatus;
+  /** Total number of review comments */
+  totalComments: number;
+}
+
+/** Part of an agent's transcript, from `get_agent_transcript`. */
+export interface AgentTranscript {
+  agentId: string;
+  entries: TranscriptEntry[];
+  /** Number of entries in the whole transcript */
+  total: number;
+}
+
+/** Result of running a `verify` command in one agent's worktree. */
+export interface AgentVerification {
+  agentId: string;
+  command: string;
+  /** Exit code (`None` when killed by a signal or not started) */
+  exitCode?: number | null;
+  /** Last lines of the combined output */
+  output: string;
+  passed: boolean;
+}
+
+/** Result of `get_api_version`. */
+export interface ApiVersionInfo {
+  apiVersion: number;
+  /** App version from Cargo.toml */
+  appVersion: string;
+  minFrontendApiVersion: number;
+}
+
+/** Application settings stored in the persistent store. */
+export interface AppSettings {
+  autoRefresh: boolean;
+  /** OpenCode servers allowed while throttled; `None` uses 1 */
+  batteryMaxOpencodeInstances?: number | null;
+  /** Battery percentage below which agents are throttled; `None` uses 20, 0 disables throttling */
+  batteryThrottlePercent?: number | null;
+  /** Template for suggested worktree names (e.g. "{user}/{hint}") */
+  branchNameTemplate?: string | null;
+  colorScheme: string;
+  /** Copy a relative `core.hooksPath` folder into new worktrees; `None` means on */
+  copyHooks?: boolean | null;
+  /** Untracked files copied into new worktrees, as git globs (e.g. ".env*", "config/local.*") */
+  copyUntrackedFiles?: string[];
+  /** Port of the local event WebSocket; `None` keeps it disabled */
+  eventSocketPort?: number | null;
+  /** Token for the GitHub API (branch protection); `None` uses `GITHUB_TOKEN` or `GH_TOKEN` */
+  githubToken?: string | null;
+  /** Run `git lfs pull` in new worktrees of LFS repositories; `None` means on */
+  lfsAutoPull?: boolean | null;
+  /** Language of humanized report fields (e.g. "de"); `None` uses `LANG` */
+  locale?: string | null;
+  /** Longest agent worktree path accepted, in bytes; `None` uses 200 */
+  maxWorktreePathLength?: number | null;
+  /** Last port handed out by the port registry; `None` uses 41999 */
+  portRangeEnd?: number | null;
+  /** First port handed out by the port registry; `None` uses 41000 */
+  portRangeStart?: number | null;
+  /** Start in read-only observer mode (all mutating commands rejected) */
+  readOnly?: boolean;
+  /** Redact secrets and emails in logs and transcripts; `None` means on */
+  redactLogs?: boolean | null;
+  /** Extra redaction patterns, `*` matching any characters (e.g. "*.corp.example.com") */
+  redactionPatterns?: string[];
+  /** Share dependency folders of the main worktree into new worktrees; `None` means off */
+  shareDependencies?: SharedDepsMode | null;
+  /** Folders shared; empty uses `node_modules`, `target` and `.venv` */
+  sharedDependencyDirs?: string[];
+  /** Which worktrees count as stale, and whether a background run cleans them up (see `worktrees::stale`) */
+  staleCleanup?: StaleCleanupPolicy;
+  /** What to do when an agent folder name is taken; `None` appends the agent number */
+  taskFolderCollision?: TaskFolderCollision | null;
+  /** Longest task name slug in agent folder names; `None` uses 40. Longer slugs are cut and end in a hash of the full slug */
+  taskSlugMaxLength?: number | null;
+  themeName: string;
+  /** Ticket IDs found in branch names for `{ticket}`, `*` matching letters and `#` digits; `None` uses "*-#" (e.g. "PROJ-123") */
+  ticketPattern?: string | null;
+  /** Maintain readable symlinks under ~/.aristar-worktrees/by-name/ */
+  worktreeAliases?: boolean;
+  /** Template for names generated when `create_worktree` gets none (e.g. "{branch}-{date}"); `None` uses "{branch}" */
+  worktreeNameTemplate?: string | null;
+}
+
+/** Changed files grouped by area (top-level folder). */
+export interface AreaChanges {
+  area: string;
+  deletions: number;
+  files: FileChange[];
+  insertions: number;
+}
+
+/** Payload of the `auto-accept-scheduled` and `auto-accepted` events. */
+export interface AutoAcceptEvent {
+  agentId: string;
+  dueAt: number;
+  reason: string;
+  taskId: string;
+}
+
+/** When to accept an agent automatically after a pipeline run. At least one rule must be set; the sole-passer rule is checked first. */
+export interface AutoAcceptPolicy {
+  /** Remove the other agents and their worktrees after accepting */
+  cleanup?: boolean;
+  /** Seconds to wait before accepting, during which it can be cancelled */
+  delaySecs?: number;
+  /** Accept the top scorer if its score is at least this (0-100) and no other agent has the same score */
+  minScore?: number | null;
+  /** Accept the only completed agent that passed every verification */
+  solePasser?: boolean;
+}
+
+/** Result of merging a worktree into its base branch. When the merge has conflicts nothing is changed, `merged` is false and `conflicts` lists them. */
+export interface BaseMergeResult {
+  /** Target branch tip after merging (`None` when not merged) */
+  commit?: string | null;
+  /** Number of worktree commits not yet on the target */
+  commitsMerged: number;
+  /** Paths with conflicts */
+  conflicts: string[];
+  /** Whether the target branch was updated */
+  merged: boolean;
+  /** Git's messages about the merge (e.g. "CONFLICT (content): ...") */
+  messages: string[];
+  /** Target branch tip before merging */
+  previousCommit: string;
+  /** Worktree branch (or short commit when detached) that was merged */
+  source: string;
+  /** Strategy that was applied */
+  strategy: MergeStrategy;
+  /** Branch that received the commits */
+  targetBranch: string;
+}
+
+/** Outcome of creating a batch task in one repository. */
+export interface BatchTaskResult {
+  error?: string | null;
+  repoPath: string;
+  /** Created task, if creation succeeded */
+  taskId?: string | null;
+}
+
+/** Summary of `create_task_batch`, one result per repository. */
+export interface BatchTaskSummary {
+  failed: number;
+  presetId: string;
+  /** Prompt the frontend sends to the agents of each created task */
+  prompt?: string | null;
+  results: BatchTaskResult[];
+  succeeded: number;
+}
+
+/** Branches of a repository that can be deleted. */
+export interface BranchCleanupSuggestion {
+  /** Branch the suggestions are merged into, `None` when none was found */
+  baseBranch?: string | null;
+  /** Most recent last commit first */
+  branches: MergedBranch[];
+  /** When the check ran (milliseconds since epoch) */
+  checkedAt: number;
+  repoPath: string;
+  /** The tracked repository, if `repo_path` is one */
+  repositoryId?: string | null;
+}
+
+/** Result of `delete_merged_branches`. */
+export interface BranchDeletionReport {
+  deleted: string[];
+  skipped: SkippedBranch[];
+}
+
+/** Branch information. */
+export interface BranchInfo {
+  /** Commits on the branch but not on its upstream */
+  ahead?: number;
+  /** Commits on the upstream but not on the branch */
+  behind?: number;
+  isCurrent: boolean;
+  isRemote: boolean;
+  name: string;
+  /** Upstream of a local branch (e.g. "origin/main") */
+  upstream?: string | null;
+  /** The upstream is configured but no longer exists (e.g. deleted after merge) */
+  upstreamGone?: boolean;
+}
+
+/** One page of a repository's branches (`get_branch_page`). */
+export interface BranchPage {
+  branches: BranchInfo[];
+  hasMore: boolean;
+  offset: number;
+  /** Branches matching the filter, on all pages */
+  total: number;
+}
+
+/** Order of a branch page. */
+export type BranchSort = "name" | "recent";
+
+/** Heuristic summary of an agent's diff against its base. */
+export interface ChangeSummary {
+  /** Public functions/types added (e.g. "src/lib.rs: fn parse") */
+  addedApis: string[];
+  areas: AreaChanges[];
+  /** Commit the diff was computed against */
+  baseCommit: string;
+  deletions: number;
+  filesChanged: number;
+  insertions: number;
+  /** Markdown rendering, suitable for PR descriptions */
+  markdown: string;
+  /** Deleted files and large removals */
+  notableDeletions: string[];
+  /** Public functions/types removed */
+  removedApis: string[];
+}
+
+/** A file that differs from the base. */
+export interface ChangedFile {
+  binary: boolean;
+  deletions: number;
+  insertions: number;
+  path: string;
+  status: ChangedFileStatus;
+}
+
+/** How a file changed. */
+export type ChangedFileStatus = "added" | "modified" | "deleted" | "type-changed" | "untracked";
+
+/** Commit information. */
+export interface CommitInfo {
+  author: string;
+  date: number;
+  hash: string;
+  message: string;
+  /** Parent hashes (two or more for merges, none for root commits) */
+  parents?: string[];
+  /** Ref decorations as `git log` shows them (e.g. "HEAD -> main", "tag: v1.0") */
+  refs?: string[];
+  shortHash: string;
+}
+
+/** A reference file (design doc, spec) attached to a task. */
+export interface ContextFile {
+  /** Timestamp when the file was attached (milliseconds since epoch) */
+  addedAt: number;
+  /** File name inside `.aristar/context/` */
+  name: string;
+  /** Size in bytes */
+  size: number;
+  /** Path the file was attached from */
+  sourcePath: string;
+}
+
+/** Result of `deep_scan_repository`. */
+export interface DeepScanResult {
+  /** Commits now in the history cache */
+  commitsCached: number;
+  /** Worktrees with uncommitted changes */
+  dirtyWorktrees: number;
+  /** Status of every worktree, by path; worktrees whose status failed are left out */
+  statuses: Record<string, WorktreeStatus>;
+}
+
+/** Emitted when a repository's remote default branch changed (e.g. master to main), so the user can be asked whether to follow it. */
+export interface DefaultBranchChange {
+  current: string;
+  previous: string;
+  repositoryId: string;
+}
+
+/** A desktop notification. */
+export interface DesktopNotification {
+  body: string;
+  /** Epoch millis */
+  createdAt: number;
+  title: string;
+  urgency: NotificationUrgency;
+}
+
+/** An agent worktree found over its task's disk quota. */
+export interface DiskQuotaExceeded {
+  /** Timestamp of the measurement (milliseconds since epoch) */
+  detectedAt: number;
+  /** The quota at the time, in bytes */
+  limitBytes: number;
+  /** Size of the worktree when measured, in bytes */
+  sizeBytes: number;
+}
+
+/** Contents of `event-socket.json`. */
+export interface EventSocketInfo {
+  port: number;
+  token: string;
+}
+
+/** How closely the agents' changes to a file agree. */
+export type FileAgreement = "identical" | "similar" | "divergent";
+
+/** Agreement between the agents on one changed file. */
+export interface FileAgreementEntry {
+  agreement: FileAgreement;
+  /** Agents whose diff touches the file */
+  changedBy: string[];
+  path: string;
+  /** Lowest patch similarity between two agents, in percent */
+  similarity: number;
+}
+
+/** One changed file in an agent's diff. */
+export interface FileChange {
+  binary: boolean;
+  deletions: number;
+  insertions: number;
+  path: string;
+  /** "added", "modified" or "deleted" */
+  status: string;
+}
+
+/** Entries under `dir` in a worktree. */
+export interface FileTree {
+  /** Folder listed, relative to the worktree root (empty for the root) */
+  dir: string;
+  entries: FileTreeEntry[];
+  /** More entries exist than were returned */
+  truncated: boolean;
+}
+
+/** A file or folder in a worktree. */
+export interface FileTreeEntry {
+  /** Folder contents; `None` for files and for folders below the depth limit */
+  children?: FileTreeEntry[] | null;
+  isDir: boolean;
+  name: string;
+  /** Relative to the worktree root, `/`-separated */
+  path: string;
+}
+
+/** Aggregated counters shown in the status bar. */
+export interface GlobalStatus {
+  /** Number of worktrees (repository and agent) with uncommitted changes */
+  dirtyWorktrees: number;
+  /** Percentage of the data directory's volume in use, if it could be determined */
+  diskUsagePercent?: number | null;
+  /** Number of tasks with an accepted agent and unaccepted agents left to clean up */
+  pendingCleanups: number;
+  /** Number of running OpenCode servers */
+  serversRunning: number;
+  /** Number of tasks with status `running` */
+  tasksRunning: number;
+  /** Timestamp when the status was computed (milliseconds since epoch) */
+  updatedAt: number;
+}
+
+/** What a worktree's HEAD points at. */
+export type HeadState = { kind: "branch"; value: string } | { kind: "detached"; value: string } | { kind: "unborn" };
+
+/** A label in use, with what carries it. */
+export interface LabelUsage {
+  label: string;
+  /** Ids of the repositories labeled with it */
+  repositoryIds: string[];
+  /** Paths of the worktrees labeled with it */
+  worktreePaths: string[];
+}
+
+/** Payload of `lfs-pull-progress`. */
+export interface LfsPullProgress {
+  completedFiles?: number | null;
+  /** Error for `failed` */
+  message?: string | null;
+  percent?: number | null;
+  stage: LfsPullStage;
+  totalFiles?: number | null;
+  worktreePath: string;
+}
+
+/** Stage of an LFS pull. */
+export type LfsPullStage = "started" | "progress" | "completed" | "failed";
+
+/** How a worktree's commits are published onto a target branch. */
+export type MergeStrategy = "squash" | "rebase" | "merge-commit" | "fast-forward";
+
+/** A local branch merged into the default branch. */
+export interface MergedBranch {
+  /** Tip of the branch (no parents or refs) */
+  lastCommit: CommitInfo;
+  name: string;
+}
+
+/** How often a model's agents won, from `get_model_leaderboard`. */
+export interface ModelLeaderboardEntry {
+  /** `accepted / runs`, from 0 to 1 */
+  acceptanceRate: number;
+  accepted: number;
+  /** Average changed lines (insertions plus deletions) of the runs with a recorded diff */
+  averageDiffLines?: number | null;
+  /** Average pipeline score of the runs that were scored */
+  averageScore?: number | null;
+  modelId: string;
+  providerId: string;
+  /** Agents of the model in tasks with an accepted winner */
+  runs: number;
+}
+
+/** Model selection for creating agents. */
+export interface ModelSelection {
+  modelId: string;
+  providerId: string;
+}
+
+/** Whether a notification may be shown during Focus. */
+export type NotificationUrgency = "normal" | "urgent";
+
+/** An automatic accept waiting for its delay to pass. */
+export interface PendingAutoAccept {
+  agentId: string;
+  /** When the accept fires (milliseconds since epoch) */
+  dueAt: number;
+  /** Why the agent was picked, e.g. "only agent to pass verification" */
+  reason: string;
+}
+
+/** Named, ordered steps run once a task's agents have finished. */
+export interface Pipeline {
+  name: string;
+  steps: PipelineStep[];
+}
+
+/** State of a task's latest pipeline run. */
+export interface PipelineRun {
+  /** Timestamp when the run finished (milliseconds since epoch) */
+  finishedAt?: number | null;
+  /** Name of the pipeline that ran */
+  pipeline: string;
+  scores: AgentScore[];
+  /** Timestamp when the run started (milliseconds since epoch) */
+  startedAt: number;
+  /** `running`, `passed` or `failed` */
+  status: PipelineStatus;
+  /** One entry per pipeline step, in order */
+  steps: PipelineStepRun[];
+  verifications: AgentVerification[];
+}
+
+/** Status of a pipeline step or of a whole pipeline run. */
+export type PipelineStatus = "pending" | "running" | "passed" | "failed" | "skipped";
+
+/** A pipeline step. Only the fields of the step's kind are used. */
+export interface PipelineStep {
+  /** Command for `verify`, run with `bash -c` (e.g. "cargo test") */
+  command?: string | null;
+  kind: PipelineStepKind;
+  /** Strategy for `publish`; defaults like `publish_agent` */
+  strategy?: MergeStrategy | null;
+  /** Minimum score (0-100) for `auto-accept`; defaults to 100 */
+  threshold?: number | null;
+}
+
+/** Kind of a pipeline step. */
+export type PipelineStepKind = "verify" | "score" | "auto-accept" | "publish";
+
+/** Progress of one pipeline step. */
+export interface PipelineStepRun {
+  /** Timestamp when the step finished (milliseconds since epoch) */
+  finishedAt?: number | null;
+  kind: PipelineStepKind;
+  /** Outcome, e.g. "2 of 3 agents passed" */
+  message?: string | null;
+  /** Timestamp when the step started (milliseconds since epoch) */
+  startedAt?: number | null;
+  status: PipelineStatus;
+}
+
+/** A plugin loaded from a manifest. */
+export interface Plugin {
+  actions: PluginAction[];
+  description?: string | null;
+  /** File name of the manifest without `.toml` */
+  id: string;
+  name: string;
+  /** Path of the manifest */
+  path: string;
+  version?: string | null;
+}
+
+/** A custom action declared by a plugin. */
+export interface PluginAction {
+  /** Program and arguments, with template variables in the arguments */
+  command: string[];
+  context: PluginContext;
+  description?: string | null;
+  /** Unique within the plugin (lowercase letters, digits and `-`) */
+  id: string;
+  name: string;
+}
+
+/** Outcome of a plugin action that ran. */
+export interface PluginActionResult {
+  /** `None` when the process was killed by a signal */
+  exitCode?: number | null;
+  stderr: string;
+  stdout: string;
+  success: boolean;
+}
+
+/** What a plugin action runs on. */
+export type PluginContext = "worktree" | "repo" | "task";
+
+/** Result of `list_plugins`: the valid plugins and why the others were skipped. */
+export interface PluginList {
+  errors: PluginLoadError[];
+  plugins: Plugin[];
+}
+
+/** A manifest that could not be loaded. */
+export interface PluginLoadError {
+  message: string;
+  path: string;
+}
+
+export interface PortConflict {
+  kind: PortConflictKind;
+  port: number;
+  reservations: PortReservation[];
+}
+
+/** Why reservations need attention. */
+export type PortConflictKind = "duplicate" | "out-of-range";
+
+/** Inclusive range of ports handed out. */
+export interface PortRange {
+  end: number;
+  start: number;
+}
+
+/** Result of `get_port_reservations`. */
+export interface PortReport {
+  conflicts: PortConflict[];
+  range: PortRange;
+  reservations: PortReservation[];
+}
+
+/** A port held by a service for an owner. */
+export interface PortReservation {
+  owner: string;
+  port: number;
+  reservedAt: number;
+  service: string;
+}
+
+/** Payload of `power-throttled`. */
+export interface PowerThrottledEvent {
+  batteryPercent?: number | null;
+  /** OpenCode servers allowed while throttled */
+  maxInstances?: number | null;
+  onBattery: boolean;
+  /** Whether throttling started (true) or ended (false) */
+  throttled: boolean;
+}
+
+/** Result of publishing a worktree onto a target branch. */
+export interface PublishResult {
+  /** Target branch tip after publishing */
+  commit: string;
+  /** Number of worktree commits that were published */
+  commitsPublished: number;
+  /** Target branch tip before publishing */
+  previousCommit: string;
+  /** Strategy that was applied */
+  strategy: MergeStrategy;
+  /** Branch that received the commits */
+  targetBranch: string;
+}
+
+/** Result of rebasing a worktree. */
+export interface RebaseResult {
+  /** Commits of the worktree that were replayed so far */
+  commits: number;
+  /** Files with unresolved conflicts */
+  conflicts: string[];
+  /** What the worktree is rebased onto (empty after an abort) */
+  onto: string;
+  /** Git's output, for display */
+  output: string;
+  status: RebaseStatus;
+}
+
+/** Outcome of a rebase, continue or abort. */
+export type RebaseStatus = "up-to-date" | "rebased" | "conflicts" | "aborted";
+
+/** One interrupted operation found in the journal. */
+export interface RecoveryAction {
+  error?: string | null;
+  intentId: string;
+  /** "create-task" or "remove-worktree" */
+  operation: string;
+  outcome: RecoveryOutcome;
+  /** When the operation started (milliseconds since epoch) */
+  startedAt: number;
+  /** Repairs made, e.g. "Removed worktree /path" */
+  steps: string[];
+  /** Task id or worktree path */
+  target: string;
+}
+
+/** What startup recovery did with an interrupted operation. */
+export type RecoveryOutcome = "rolled-back" | "rolled-forward" | "nothing-to-do" | "pending" | "failed";
+
+/** What startup recovery found and repaired. */
+export interface RecoveryReport {
+  actions: RecoveryAction[];
+  /** Milliseconds since epoch */
+  ranAt: number;
+}
+
+/** One replaced piece of text. */
+export interface RedactionMatch {
+  /** "api-key", "secret", "email" or the matching pattern */
+  rule: string;
+  text: string;
+}
+
+/** Result of `test_redaction`. */
+export interface RedactionPreview {
+  enabled: boolean;
+  matches: RedactionMatch[];
+  redacted: string;
+}
+
+/** A remote and its fetch URL. */
+export interface RemoteInfo {
+  name: string;
+  url: string;
+}
+
+/** Why `rename_worktree` or `move_worktree` failed, tagged by `kind` so the UI can offer the right way out (pick another name, stop the processes, try again later). */
+export type RenameWorktreeError = { kind: "invalid-name"; name: string } | { kind: "target-exists"; path: string } | { kind: "processes-running"; processes: WorktreeProcess[] } | { attempts: number; kind: "busy"; path: string } | { kind: "failed"; message: string };
+
+/** Repository config worth checking before work starts in it. */
+export interface RepoConfigSummary {
+  /** Epoch millis */
+  computedAt: number;
+  defaultBranch?: string | null;
+  hasSubmodules: boolean;
+  /** `core.hooksPath` as configured */
+  hooksPath?: string | null;
+  remotes: RemoteInfo[];
+  /** Repository root the summary is for */
+  repoPath: string;
+  /** Size of the object database (`git count-objects`) */
+  sizeBytes: number;
+  userEmail?: string | null;
+  userName?: string | null;
+  /** `.gitattributes` uses the LFS filter or LFS objects exist */
+  usesLfs: boolean;
+  /** Misconfigurations, e.g. no `user.email` to commit with */
+  warnings: string[];
+}
+
+/** Payload of `repo-operation-queued`. */
+export interface RepoOperationQueued {
+  operation: string;
+  /** 1 when next in line */
+  position: number;
+  repoPath: string;
+  /** Operation currently running on the repository */
+  running?: string | null;
+}
+
+/** What is running and waiting on one repository. */
+export interface RepoQueueStatus {
+  repoPath: string;
+  running?: string | null;
+  waiting: string[];
+}
+
+/** How much of a repository is scanned ahead of time. Large repositories get lighter defaults when added (see `prefetch::default_scan_settings`). */
+export interface RepoScanSettings {
+  /** Check the repository's worktrees for uncommitted changes in the background (global status); off, they are checked on a deep scan only */
+  eagerStatus?: boolean;
+  /** Commits of HEAD read into the history cache by the prefetcher; 0 turns the cache off */
+  historyDepth?: number;
+}
+
+/** Result of `repoint_tasks`. */
+export interface RepointTasksReport {
+  failed: WorktreeRepairFailure[];
+  /** Resolved new repository path stored in the tasks */
+  newRepoPath: string;
+  /** Agent worktrees repaired */
+  repaired: string[];
+  /** Tasks now pointing at the new path */
+  taskIds: string[];
+}
+
+/** Repository with its worktrees. */
+export interface Repository {
+  /** Branch new work starts from when no ref is given, set by the user. Overrides `detected_base_branch`. */
+  defaultBaseBranch?: string | null;
+  /** Default strategy when publishing worktrees of this repository */
+  defaultMergeStrategy?: MergeStrategy | null;
+  /** Base branch detected from `origin/HEAD` (or `init.defaultBranch`, or a local main/master) on scan */
+  detectedBaseBranch?: string | null;
+  /** Name set by the user, shown instead of `name` */
+  displayName?: string | null;
+  id: string;
+  /** Tags grouping repositories, normalized and sorted (see `labels`) */
+  labels?: string[];
+  lastScanned: number;
+  /** Folder name of `path` */
+  name: string;
+  path: string;
+  /** Branches never deleted with a worktree, besides `PROTECTED_BRANCHES` */
+  protectedBranches?: string[];
+  /** Remote default branch as last seen, to notice when it changes */
+  remoteDefaultBranch?: string | null;
+  /** Branches protected on the remote host (GitHub), as last fetched */
+  remoteProtectedBranches?: string[];
+  /** When `remote_protected_branches` was fetched */
+  remoteProtectionCheckedAt?: number | null;
+  /** How much of the repository is read ahead of time */
+  scanSettings?: RepoScanSettings;
+  /** When the repository path stopped being reachable (e.g. unmounted network volume). `None` while available. */
+  unavailableSince?: number | null;
+  worktrees: WorktreeInfo[];
+}
+
+/** Event payload for availability changes. */
+export interface RepositoryAvailabilityEvent {
+  available: boolean;
+  path: string;
+  repositoryId: string;
+  unavailableSince?: number | null;
+}
+
+/** A file/line comment left while reviewing an agent's diff. */
+export interface ReviewComment {
+  /** Comment text */
+  body: string;
+  /** Timestamp when comment was created (milliseconds since epoch) */
+  createdAt: number;
+  /** File path relative to the agent's worktree */
+  file: string;
+  /** Unique comment ID (UUID) */
+  id: string;
+  /** 1-based line number in the new version of the file */
+  line: number;
+  /** Whether the comment has been resolved */
+  resolved: boolean;
+  /** Timestamp when comment was resolved (milliseconds since epoch) */
+  resolvedAt?: number | null;
+}
+
+/** Sandbox options for a task's agent servers. */
+export interface SandboxConfig {
+  /** Where the OS can't enforce the sandbox (no `sandbox-exec`), start agent servers with a restricted environment only instead of refusing */
+  allowUnenforced?: boolean;
+  /** Hosts reachable through the sandbox proxy (e.g. "api.anthropic.com", "*.github.com") */
+  allowedHosts?: string[];
+  /** Launch agent servers sandboxed */
+  enabled: boolean;
+  /** Extra absolute paths agents may write to, besides their worktree */
+  writablePaths?: string[];
+}
+
+/** A task preset saved for reuse, e.g. across repositories with `create_task_batch`. */
+export interface SavedTaskPreset {
+  /** Timestamp when preset was saved (milliseconds since epoch) */
+  createdAt: number;
+  /** Unique preset ID (UUID) */
+  id: string;
+  preset: TaskPreset;
+}
+
+/** A recurring task run. */
+export interface Schedule {
+  /** Timestamp when schedule was created (milliseconds since epoch) */
+  createdAt: number;
+  /** Cron expression in local time (see `cron.rs`) */
+  cron: string;
+  /** Unique schedule ID (UUID) */
+  id: string;
+  /** Error of the last run, if it failed */
+  lastError?: string | null;
+  /** Last run (milliseconds since epoch) */
+  lastRunAt?: number | null;
+  /** Task created by the last run */
+  lastTaskId?: string | null;
+  name: string;
+  /** Next due run (milliseconds since epoch) */
+  nextRunAt?: number | null;
+  paused: boolean;
+  preset: TaskPreset;
+}
+
+/** Payload of the `schedule-triggered` event. */
+export interface ScheduleTriggeredEvent {
+  error?: string | null;
+  prompt?: string | null;
+  scheduleId: string;
+  /** Created task, if the run succeeded */
+  taskId?: string | null;
+}
+
+/** Kind of entity a search result points at. */
+export type SearchEntityKind = "repository" | "worktree" | "task" | "preset" | "schedule";
+
+/** A `global_search` hit. */
+export interface SearchResult {
+  /** Repository, preset or schedule id, task id, or worktree path */
+  id: string;
+  kind: SearchEntityKind;
+  /** Fields the query matched, e.g. "name", "branch", "notes" */
+  matchedFields: string[];
+  /** Repository id of a worktree */
+  parentId?: string | null;
+  score: number;
+  /** Path, branch or similar context */
+  subtitle?: string | null;
+  title: string;
+}
+
+/** How dependency folders of the main worktree are shared into others. */
+export type SharedDepsMode = "symlink" | "hardlink";
+
+/** A folder shared into a worktree. */
+export interface SharedDir {
+  /** How it was shared (after any fallback) */
+  mode: SharedDepsMode;
+  /** Relative to the worktree, e.g. "node_modules" */
+  path: string;
+}
+
+/** A confirmed branch `delete_merged_branches` did not delete. */
+export interface SkippedBranch {
+  name: string;
+  reason: string;
+}
+
+/** A public key offered by an SSH host. */
+export interface SshHostKey {
+  /** `SHA256:...`, as printed by `ssh-keygen -l` */
+  fingerprint: string;
+  /** e.g. `ssh-ed25519` */
+  keyType: string;
+}
+
+/** A stale worktree and what happened to it. */
+export interface StaleCleanupEntry {
+  outcome: StaleOutcome;
+  worktree: StaleWorktree;
+}
+
+/** When a worktree is stale and what `cleanup_stale_worktrees` may do with it. */
+export interface StaleCleanupPolicy {
+  /** Days without activity (`last_active`) after which a worktree is stale */
+  maxIdleDays?: number;
+  /** Keep worktrees with uncommitted changes */
+  onlyClean?: boolean;
+  /** Keep locked worktrees */
+  onlyUnlocked?: boolean;
+  /** Let the background run remove stale worktrees; otherwise it only reports them */
+  removeInBackground?: boolean;
+  /** Run the cleanup in the background once a day */
+  scheduled?: boolean;
+}
+
+/** Result of `cleanup_stale_worktrees`. */
+export interface StaleCleanupReport {
+  /** When the check ran (milliseconds since epoch) */
+  checkedAt: number;
+  entries: StaleCleanupEntry[];
+  policy: StaleCleanupPolicy;
+  /** Whether removal was asked for */
+  remove: boolean;
+}
+
+/** What `cleanup_stale_worktrees` did with a stale worktree. */
+export type StaleOutcome = { kind: "removable" } | { kind: "removed" } | { kind: "kept"; reason: string } | { kind: "failed"; message: string };
+
+/** A worktree idle for longer than the policy allows. */
+export interface StaleWorktree {
+  branch?: string | null;
+  /** Whole days since the last activity */
+  idleDays: number;
+  isLocked: boolean;
+  /** Last activity (milliseconds since epoch) */
+  lastActive: number;
+  path: string;
+  repositoryId: string;
+}
+
+/** Persistent store data for worktrees/repositories. */
+export interface StoreData {
+  repositories: Repository[];
+  settings: AppSettings;
+}
+
+/** A remote operation on a worktree. */
+export type SyncOperation = "fetch" | "pull" | "push";
+
+/** Outcome of a fetch, pull or push. */
+export interface SyncResult {
+  /** Commits not on the upstream, after the operation */
+  ahead: number;
+  /** Upstream commits not in HEAD, after the operation */
+  behind: number;
+  /** Current branch (`None` when detached, which only fetch allows) */
+  branch?: string | null;
+  /** New upstream commits (fetch), commits brought into the branch (pull) or commits sent to the remote (push) */
+  commits: number;
+  operation: SyncOperation;
+  /** Git's progress output, for display */
+  output: string;
+  /** Remote that was contacted */
+  remote: string;
+  /** Upstream after the operation, e.g. `origin/main` */
+  upstream?: string | null;
+  /** Whether a push set the upstream of a branch that had none */
+  upstreamSet: boolean;
+}
+
+/** A task represents a goal/prompt with multiple agents working on it. Each task has its own folder with agent worktrees inside. */
+export interface Task {
+  /** How each agent fared, recorded when a winner was accepted so it outlives the cleanup of the other agents */
+  agentOutcomes?: AgentOutcome[];
+  /** Default agent type for all agents (e.g., "build") */
+  agentType: string;
+  /** List of agents working on this task */
+  agents: TaskAgent[];
+  /** Automatic accept after pipeline runs */
+  autoAccept?: AutoAcceptPolicy | null;
+  /** Reference files copied into every agent worktree's `.aristar/context/` */
+  contextFiles?: ContextFile[];
+  /** Timestamp when task was created (milliseconds since epoch) */
+  createdAt: number;
+  /** Largest size each agent worktree may grow to, in megabytes; `None` leaves agents unwatched */
+  diskQuotaMb?: number | null;
+  /** Unique 8-char hash (e.g., "a1b2c3d4") */
+  id: string;
+  /** Whether agents work in parallel or as a relay */
+  mode?: TaskMode;
+  /** User-friendly name */
+  name: string;
+  /** Pinned OpenCode version (managed binary); None uses the newest available */
+  opencodeVersion?: string | null;
+  /** Automatic accept waiting for its delay to pass */
+  pendingAutoAccept?: PendingAutoAccept | null;
+  /** Steps run automatically once all agents have finished */
+  pipeline?: Pipeline | null;
+  /** Latest pipeline run */
+  pipelineRun?: PipelineRun | null;
+  /** Human-readable summary of the result (e.g. from `generate_change_summary`) */
+  resultSummary?: string | null;
+  /** Sandbox options applied when starting agent servers */
+  sandbox?: SandboxConfig;
+  /** Snapshot commit of the source repo's uncommitted changes, applied to every agent worktree (kept reachable by `refs/aristar/snapshots/{task-id}`) */
+  snapshotCommit?: string | null;
+  /** Source branch name (when source_type is "branch") */
+  sourceBranch?: string | null;
+  /** Source commit hash (when source_type is "commit") */
+  sourceCommit?: string | null;
+  /** Original repository path */
+  sourceRepoPath: string;
+  /** Source type: "branch" or "commit" */
+  sourceType: string;
+  /** Folders agent worktrees are restricted to (sparse checkout); empty checks out the whole repository */
+  sparsePaths?: string[];
+  /** Current task status */
+  status: TaskStatus;
+  /** Timeline of notable task events (e.g. write audits), oldest first */
+  timeline?: TimelineEntry[];
+  /** Timestamp when task was last updated (milliseconds since epoch) */
+  updatedAt: number;
+}
+
+/** Represents one AI model/agent working on a task. Each agent has its own worktree and OpenCode session. */
+export interface TaskAgent {
+  /** Whether this agent's output was accepted as the winner */
+  accepted: boolean;
+  /** Override task's default agent type */
+  agentType?: string | null;
+  /** Timestamp when agent was created (milliseconds since epoch) */
+  createdAt: number;
+  /** Agent this one was forked from, continuing its work */
+  forkedFrom?: string | null;
+  /** Unique ID within task (e.g., "agent-1") */
+  id: string;
+  /** Timestamp when the agent's OpenCode server was last started (milliseconds since epoch) */
+  lastStartedAt?: number | null;
+  /** Model ID (e.g., "claude-sonnet-4") */
+  modelId: string;
+  /** Port first assigned to this agent's OpenCode server, reused on restart when free */
+  preferredPort?: number | null;
+  /** Provider ID (e.g., "anthropic") */
+  providerId: string;
+  /** Set when the agent was paused for outgrowing the task's disk quota; its worktree needs a review before it runs again */
+  quotaExceeded?: DiskQuotaExceeded | null;
+  /** Review comments left on this agent's diff */
+  reviewComments?: ReviewComment[];
+  /** OpenCode session ID */
+  sessionId?: string | null;
+  /** Current status */
+  status: AgentStatus;
+  /** Full path to agent's worktree */
+  worktreePath: string;
+}
+
+/** A ref the app created for a task. */
+export interface TaskArtifact {
+  /** Accepted work started from it (kept by `keep_accepted`) */
+  accepted: boolean;
+  /** Agent a checkpoint was forked into */
+  agentId?: string | null;
+  commit: string;
+  kind: TaskArtifactKind;
+  /** Its commit is already on the task's source branch */
+  published: boolean;
+  refName: string;
+}
+
+/** Result of `cleanup_task_artifacts`. */
+export interface TaskArtifactCleanup {
+  deleted: TaskArtifact[];
+  kept: TaskArtifact[];
+}
+
+/** Kind of a ref the app created for a task. */
+export type TaskArtifactKind = "snapshot" | "checkpoint";
+
+/** What happens when an agent worktree folder name is already taken. */
+export type TaskFolderCollision = "number" | "error";
+
+/** How the agents of a task work. */
+export type TaskMode = "parallel" | "relay";
+
+/** Task settings a schedule creates each run from (mirrors `create_task`). */
+export interface TaskPreset {
+  agentType: string;
+  models: ModelSelection[];
+  /** Task name; each run appends its date */
+  name: string;
+  opencodeVersion?: string | null;
+  /** Prompt the frontend sends to the agents of each created task */
+  prompt?: string | null;
+  sourceBranch?: string | null;
+  sourceCommit?: string | null;
+  sourceRepoPath: string;
+  sourceType: string;
+  /** Folders the agent worktrees are restricted to; empty for a full checkout */
+  sparsePaths?: string[];
+}
+
+/** Status of a task. */
+export type TaskStatus = "idle" | "running" | "paused" | "completed" | "failed";
+
+/** Persistent storage for tasks. */
+export interface TaskStoreData {
+  /** Saved task presets */
+  presets?: SavedTaskPreset[];
+  /** Recurring task schedules */
+  schedules?: Schedule[];
+  tasks: Task[];
+}
+
+/** Payload of the `tasks-loaded` event. */
+export interface TasksLoadedEvent {
+  /** Time spent reading and parsing `tasks.json` */
+  durationMs: number;
+  taskCount: number;
+}
+
+/** An entry in a task's timeline. */
+export interface TimelineEntry {
+  /** Agent the entry refers to, if any */
+  agentId?: string | null;
+  /** Additional lines (e.g. offending paths) */
+  details?: string[];
+  /** Unique entry ID (UUID) */
+  id: string;
+  kind: TimelineEntryKind;
+  /** Short description */
+  message: string;
+  /** Timestamp of the entry (milliseconds since epoch) */
+  timestamp: number;
+}
+
+/** Kind of a task timeline entry. */
+export type TimelineEntryKind = "write-audit" | "write-violation" | "pipeline-step" | "pipeline-step-failed" | "auto-accept" | "relay-hand-off" | "disk-quota-exceeded" | "sandbox-unenforced";
+
+/** One finished piece of an agent's OpenCode session, a line of its transcript. */
+export interface TranscriptEntry {
+  /** Message info, part or error as sent by OpenCode */
+  data: unknown;
+  /** Message or part ID from OpenCode */
+  id: string;
+  /** "message", "text", "reasoning", "tool" or "error" */
+  kind: string;
+  messageId?: string | null;
+  sessionId?: string | null;
+  /** Timestamp when recorded (milliseconds since epoch) */
+  timestamp: number;
+}
+
+/** Entries `start..end` of a transcript; `end: None` reads to the end. */
+export interface TranscriptRange {
+  end?: number | null;
+  start: number;
+}
+
+/** Payload of `ssh-host-key-unknown`. */
+export interface UnknownSshHost {
+  host: string;
+  /** Keys the host offers now; empty when it could not be scanned */
+  keys: SshHostKey[];
+  operation: SyncOperation;
+  /** `None` for the default port 22 */
+  port?: number | null;
+  /** Worktree whose operation failed, to retry it after approval */
+  worktreePath: string;
+}
+
+/** Everything needed to restore a workspace. */
+export interface WorkspaceSnapshot {
+  /** Epoch millis */
+  createdAt: number;
+  name: string;
+  store: StoreData;
+  tasks: TaskStoreData;
+  /** Opaque UI state from the frontend (persisted stores) */
+  uiState?: unknown;
+}
+
+/** Summary of a saved snapshot. */
+export interface WorkspaceSnapshotInfo {
+  createdAt: number;
+  name: string;
+  repositoryCount: number;
+  taskCount: number;
+}
+
+/** An archived worktree. */
+export interface WorktreeArchive {
+  branch?: string | null;
+  /** HEAD when archived; `None` on a branch without commits */
+  commit?: string | null;
+  /** Milliseconds since epoch */
+  createdAt: number;
+  /** e.g. "20261015-142501-a1b2c3d4" */
+  id: string;
+  /** Folder name of the worktree */
+  name: string;
+  repoPath: string;
+  /** Size of the `.tar.gz` */
+  sizeBytes: number;
+  /** Only tracked, modified and untracked-but-not-ignored files were packed */
+  trackedOnly: boolean;
+  worktreePath: string;
+}
+
+/** Files a worktree changed against its base ref. */
+export interface WorktreeChanges {
+  baseRef: string;
+  /** Sorted by path */
+  files: ChangedFile[];
+  includeUncommitted: boolean;
+  /** Commit the changes are measured from */
+  mergeBase: string;
+}
+
+/** A config entry set for a single worktree. */
+export interface WorktreeConfigEntry {
+  key: string;
+  value: string;
+}
+
+/** Unified diff of a worktree against its base ref. */
+export interface WorktreeDiff {
+  baseRef: string;
+  includeUncommitted: boolean;
+  mergeBase: string;
+  /** `git diff` output, with untracked files shown as added */
+  patch: string;
+  /** The patch was cut at `MAX_PATCH_BYTES` */
+  truncated: boolean;
+}
+
+/** Information about a single worktree. */
+export interface WorktreeInfo {
+  branch?: string | null;
+  commit?: string | null;
+  createdAt: number;
+  /** Free-form note on why the worktree exists. Kept in the app store only, never written to git. */
+  description?: string | null;
+  /** URL of the app the worktree serves, opened by `open_dev_url` */
+  devUrl?: string | null;
+  /** What HEAD points at. `None` only for entries saved before this field existed; the next refresh fills it in. */
+  headState?: HeadState | null;
+  /** Left out of listings (e.g. long-lived build caches); still shown in cleanup and diagnostics views */
+  hidden?: boolean;
+  id: string;
+  /** HEAD is detached (`detached` in `git worktree list --porcelain`), as for agent worktrees */
+  isDetached?: boolean;
+  isLocked: boolean;
+  isMain: boolean;
+  /** Tags such as "wip" or "review", normalized and sorted (see `labels`) */
+  labels?: string[];
+  /** Last time the worktree was opened in a terminal or editor, had OpenCode started in it, or had git activity, in milliseconds (see `activity`) */
+  lastActive?: number | null;
+  /** When a lock placed by the app lifts itself, in milliseconds (see `lock_expiry`). Kept in the app store only. */
+  lockExpiresAt?: number | null;
+  lockReason?: string | null;
+  name: string;
+  path: string;
+  scriptExecuted: boolean;
+  startupScript?: string | null;
+}
+
+/** A worktree whose lock expired. */
+export interface WorktreeLockExpiredEvent {
+  /** When the lock expired (milliseconds since epoch) */
+  expiresAt: number;
+  path: string;
+  reason?: string | null;
+  repositoryId: string;
+}
+
+/** Contents of `.aristar/lock.json`, written into locked worktrees so scripts and CI can see the app's lock without parsing git. */
+export interface WorktreeLockFile {
+  /** When the app lifts the lock, Unix timestamp in milliseconds */
+  expires_at?: number | null;
+  locked: boolean;
+  /** Unix timestamp in milliseconds */
+  locked_at: number;
+  locked_by: string;
+  pid: number;
+  reason?: string | null;
+}
+
+/** A process whose working directory is inside a worktree. */
+export interface WorktreeProcess {
+  /** Command name, e.g. "node" or "zsh" */
+  command: string;
+  cwd: string;
+  pid: number;
+  /** TCP ports it listens on */
+  ports: number[];
+}
+
+/** An agent worktree `repoint_tasks` could not repair. */
+export interface WorktreeRepairFailure {
+  error: string;
+  path: string;
+}
+
+/** Working tree status of a single worktree. */
+export interface WorktreeStatus {
+  /** Commits not on the upstream (0 without upstream) */
+  ahead: number;
+  /** Upstream commits not in HEAD (0 without upstream) */
+  behind: number;
+  /** Current branch (`None` when detached) */
+  branch?: string | null;
+  /** Files with merge conflicts */
+  conflicted: number;
+  /** Current commit (`None` before the first commit) */
+  head?: string | null;
+  /** Any uncommitted work, including untracked files */
+  isDirty: boolean;
+  /** Files with unstaged changes */
+  modified: number;
+  /** Files with staged changes */
+  staged: number;
+  untracked: number;
+  /** Upstream branch, e.g. `origin/main` */
+  upstream?: string | null;
+}
+
+/** Result of `audit_agent_writes`. */
+export interface WriteAuditReport {
+  agentId: string;
+  /** Directories that were scanned */
+  scannedRoots: string[];
+  /** Start of the audited window (milliseconds since epoch) */
+  since: number;
+  /** Whether the violation list was cut off */
+  truncated: boolean;
+  violations: WriteViolation[];
+}
+
+/** A file modified outside an agent's worktree during its run. */
+export interface WriteViolation {
+  /** Modification time (milliseconds since epoch) */
+  modifiedAt: number;
+  path: string;
+}
@@ -1,3 +1,73 @@
+import type { HeadState, Repository } from './generated/types';
+
+// Repositories, worktrees and the other types the backend sends are generated from the Rust types
+export type {
+  HeadState,
+  Repository,
+  LabelUsage,
+  RepoScanSettings,
+  DeepScanResult,
+  DefaultBranchChange,
+  WorktreeStatus,
+  ChangedFileStatus,
+  ChangedFile,
+  WorktreeChanges,
+  WorktreeDiff,
+  WorktreeConfigEntry,
+  RemoteInfo,
+  RepoConfigSummary,
+  FileTreeEntry,
+  FileTree,
+  SyncOperation,
+  SyncResult,
+  SshHostKey,
+  UnknownSshHost,
+  RebaseStatus,
+  RebaseResult,
+  RepoOperationQueued,
+  LfsPullStage,
+  LfsPullProgress,
+  RepoQueueStatus,
+  WorktreeLockFile,
+  NotificationUrgency,
+  DesktopNotification,
+  RedactionPreview,
+  PluginContext,
+  PluginAction,
+  Plugin,
+  PluginList,
+  PluginActionResult,
+  PortReservation,
+  PortReport,
+  WorktreeProcess,
+  RenameWorktreeError,
+  SharedDepsMode,
+  SharedDir,
+  WorktreeArchive,
+  StaleCleanupPolicy,
+  StaleWorktree,
+  StaleOutcome,
+  StaleCleanupReport,
+  WorktreeLockExpiredEvent,
+  MergedBranch,
+  BranchCleanupSuggestion,
+  BranchDeletionReport,
+  ApiVersionInfo,
+  WorkspaceSnapshotInfo,
+  RecoveryOutcome,
+  RecoveryAction,
+  RecoveryReport,
+  SearchEntityKind,
+  SearchResult,
+  MergeStrategy,
+  PublishResult,
+  BaseMergeResult,
+  BranchInfo,
+  BranchSort,
+  BranchPage,
+  CommitInfo,
+} from './generated/types';
+
 export interface WorktreeMetadata {
   id: string;
   repositoryId: string;
@@ -5,12 +75,27 @@ export interface WorktreeMetadata {
   path: string;
   branch?: string;
   commit?: string;
+  headState?: HeadState;
+  /** HEAD is detached, e.g. agent worktrees */
+  isDetached: boolean;
   isMain: boolean;
   isLocked: boolean;
   lockReason?: string;
+  /** When the app lifts the lock (ms) */
+  lockExpiresAt?: number;
   startupScript?: string;
   scriptExecuted: boolean;
   createdAt: number;
+  /** Left out of listings unless asked for */
+  hidden?: boolean;
+  /** URL of the app the worktree serves */
+  devUrl?: string;
+  /** Why the worktree exists; kept in the app store, not in git */
+  description?: string;
+  /** Tags such as "wip" or "review", lowercase and sorted */
+  labels?: string[];
+  /** Last terminal/editor open, OpenCode start or git activity (ms) */
+  lastActive?: number;
 }
 
 // Theme types
@@ -26,13 +111,8 @@ export interface ThemeDefinition {
 
 export type ColorScheme = 'light' | 'dark' | 'system';
 
-export interface Repository {
-  id: string;
-  path: string;
-  name: string;
-  worktrees: WorktreeMetadata[];
-  lastScanned: number;
-}
+/** Event payloads carry the schema version they were written for */
+export type Versioned<T> = T & { schemaVersion: number };
 
 export interface CreateWorktreeRequest {
   repositoryId: string;
@@ -82,6 +162,8 @@ export interface AppSettings {
   sidebarCollapsed: boolean;
   /** AI model to use for prompt optimization (provider/model-id format) */
   optimizationModel?: OptimizationModelSelection;
+  /** Mirror agent session transcripts into their task folders */
+  recordTranscripts?: boolean;
 }
 
 export interface StoreData {
@@ -93,25 +175,26 @@ export type SourceType = 'current-branch' | 'existing-branch' | 'commit';
 
 export type ActiveView = 'worktrees' | 'agent-manager';
 
-export interface BranchInfo {
-  name: string;
-  isCurrent: boolean;
-  isRemote: boolean;
-}
-
-export interface CommitInfo {
-  hash: string;
-  shortHash: string;
-  message: string;
-  author: string;
-  date: number;
-}
-
 // Agent Manager types are now in @agent-manager/store/types
 // Re-export for backwards compatibility during migration
 export type {
   TaskStatus,
   AgentStatus,
+  TaskMode,
+  AgentAgreementReport,
+  AgentComparisonExport,
+  AgentDiffStats,
+  AgentOutcome,
+  ModelLeaderboardEntry,
+  RepointTasksReport,
+  TasksLoadedEvent,
+  TimelineEntry,
+  WorktreeRepairFailure,
+  TaskArtifact,
+  TaskArtifactCleanup,
+  TranscriptEntry,
+  TranscriptRange,
+  AgentTranscript,
   TaskAgent,
   Task,
   OpenCodeModel,
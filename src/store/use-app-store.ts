@@ -22,21 +22,28 @@ interface AppState {
   
   createWorktree: (
     repoPath: string,
-    name: string,
+    /** Omit to generate a name from the configured template */
+    name: string | undefined,
     branch: string | undefined,
     commit: string | undefined,
     startupScript: string | undefined,
     executeScript: boolean
   ) => Promise<void>;
   
-  removeWorktree: (path: string, force: boolean, deleteBranch: boolean) => Promise<void>;
+  removeWorktree: (
+    path: string,
+    force: boolean,
+    deleteBranch: boolean,
+    terminateProcesses?: boolean
+  ) => Promise<void>;
   renameWorktree: (oldPath: string, newName: string) => Promise<void>;
-  lockWorktree: (path: string, reason: string | undefined) => Promise<void>;
+  lockWorktree: (path: string, reason: string | undefined, expiresAt?: number) => Promise<void>;
   unlockWorktree: (path: string) => Promise<void>;
   
   openInTerminal: (path: string) => Promise<void>;
   openInEditor: (path: string) => Promise<void>;
   revealInFinder: (path: string) => Promise<void>;
+  openDevUrl: (path: string) => Promise<void>;
   copyToClipboard: (text: string) => Promise<void>;
   
   clearError: () => void;
@@ -162,10 +169,10 @@ export const useAppStore = create<AppState>()(
         }
       },
 
-      removeWorktree: async (path, force, deleteBranch) => {
+      removeWorktree: async (path, force, deleteBranch, terminateProcesses) => {
         set({ isLoading: true, error: null });
         try {
-          await commands.removeWorktree(path, force, deleteBranch);
+          await commands.removeWorktree(path, force, deleteBranch, terminateProcesses);
           set((state) => ({
             repositories: state.repositories.map((repo) => ({
               ...repo,
@@ -192,19 +199,21 @@ export const useAppStore = create<AppState>()(
             isLoading: false,
           }));
         } catch (err) {
-          set({ error: String(err), isLoading: false });
+          set({ error: commands.renameWorktreeErrorMessage(err), isLoading: false });
         }
       },
 
-      lockWorktree: async (path, reason) => {
+      lockWorktree: async (path, reason, expiresAt) => {
         set({ isLoading: true, error: null });
         try {
-          await commands.lockWorktree(path, reason);
+          await commands.lockWorktree(path, reason, expiresAt);
           set((state) => ({
             repositories: state.repositories.map((repo) => ({
               ...repo,
               worktrees: repo.worktrees.map((wt) =>
-                wt.path === path ? { ...wt, isLocked: true, lockReason: reason } : wt
+                wt.path === path
+                  ? { ...wt, isLocked: true, lockReason: reason, lockExpiresAt: expiresAt }
+                  : wt
               ),
             })),
             isLoading: false,
@@ -222,7 +231,9 @@ export const useAppStore = create<AppState>()(
             repositories: state.repositories.map((repo) => ({
               ...repo,
               worktrees: repo.worktrees.map((wt) =>
-                wt.path === path ? { ...wt, isLocked: false, lockReason: undefined } : wt
+                wt.path === path
+                  ? { ...wt, isLocked: false, lockReason: undefined, lockExpiresAt: undefined }
+                  : wt
               ),
             })),
             isLoading: false,
@@ -266,6 +277,14 @@ export const useAppStore = create<AppState>()(
         }
       },
 
+      openDevUrl: async (path) => {
+        try {
+          await commands.openDevUrl(path);
+        } catch (err) {
+          set({ error: String(err) });
+        }
+      },
+
       copyToClipboard: async (text) => {
         try {
           await commands.copyToClipboard(text);